@@ -2,6 +2,8 @@
 
 use derive_more::{From, Into};
 use k256::elliptic_curve::group::prime::PrimeCurveAffine;
+use k256::elliptic_curve::ops::LinearCombination;
+use k256::elliptic_curve::ops::LinearCombinationExt;
 use k256::elliptic_curve::sec1::ToEncodedPoint;
 use k256::{ProjectivePoint, PublicKey, Scalar};
 use sigma_ser::vlq_encode::{ReadSigmaVlqExt, WriteSigmaVlqExt};
@@ -116,6 +118,71 @@ pub fn exponentiate(base: &EcPoint, exponent: &Scalar) -> EcPoint {
     }
 }
 
+/// Calculates `x^k * y^l` with a single multi-scalar multiplication, which is faster than two
+/// separate exponentiations
+pub fn multi_exponentiate(x: &EcPoint, k: &Scalar, y: &EcPoint, l: &Scalar) -> EcPoint {
+    EcPoint(ProjectivePoint::lincomb(&x.0, k, &y.0, l))
+}
+
+/// Number of points from which Pippenger's bucket method is faster than the interleaved
+/// exponentiation of [`LinearCombinationExt`]
+const PIPPENGER_MIN_POINTS: usize = 32;
+
+/// Calculates `x1^k1 * x2^k2 * ... * xn^kn` with a single multi-scalar multiplication
+pub fn multi_exponentiate_all(points_and_scalars: &[(EcPoint, Scalar)]) -> EcPoint {
+    let points_and_scalars: Vec<(ProjectivePoint, Scalar)> =
+        points_and_scalars.iter().map(|(x, k)| (x.0, *k)).collect();
+    if points_and_scalars.len() < PIPPENGER_MIN_POINTS {
+        EcPoint(ProjectivePoint::lincomb_ext(points_and_scalars.as_slice()))
+    } else {
+        EcPoint(pippenger(&points_and_scalars))
+    }
+}
+
+/// Pippenger's bucket method: for every `c`-bit window of the scalars (from the most
+/// significant), the points are added to the buckets of their window digits and the buckets are
+/// summed weighted by the digits
+fn pippenger(points_and_scalars: &[(ProjectivePoint, Scalar)]) -> ProjectivePoint {
+    // window size close to the optimum of log2(n) - 2 bits
+    let log2_n = (usize::BITS - points_and_scalars.len().leading_zeros()) as usize - 1;
+    let c = log2_n.saturating_sub(2).max(3);
+    let scalars: Vec<[u8; 32]> = points_and_scalars
+        .iter()
+        .map(|(_, k)| k.to_bytes().into())
+        .collect();
+    // digit of the big-endian scalar bytes at the bit offset
+    let digit = |bytes: &[u8; 32], offset: usize| {
+        (offset..(offset + c).min(256)).fold(0, |digit, bit| {
+            let b = (bytes[31 - bit / 8] >> (bit % 8)) & 1;
+            digit | (usize::from(b) << (bit - offset))
+        })
+    };
+    let mut buckets = vec![ProjectivePoint::IDENTITY; (1 << c) - 1];
+    let mut result = ProjectivePoint::IDENTITY;
+    let windows = 256 / c + usize::from(256 % c != 0);
+    for window in (0..windows).rev() {
+        for _ in 0..c {
+            result = result.double();
+        }
+        buckets.fill(ProjectivePoint::IDENTITY);
+        for ((x, _), k) in points_and_scalars.iter().zip(&scalars) {
+            let d = digit(k, window * c);
+            if d != 0 {
+                buckets[d - 1] += x;
+            }
+        }
+        // sum of d * bucket[d] as the sum of the running sums from the highest digit
+        let mut running = ProjectivePoint::IDENTITY;
+        let mut sum = ProjectivePoint::IDENTITY;
+        for bucket in buckets.iter().rev() {
+            running += bucket;
+            sum += running;
+        }
+        result += sum;
+    }
+    result
+}
+
 impl ScorexSerializable for EcPoint {
     fn scorex_serialize<W: WriteSigmaVlqExt>(&self, w: &mut W) -> ScorexSerializeResult {
         let caff = self.0.to_affine();
@@ -179,5 +246,28 @@ mod tests {
             prop_assert_eq![scorex_serialize_roundtrip(&v), v];
         }
 
+        #[test]
+        fn multi_exponentiate_same_as_exponentiate(x in any::<EcPoint>(), y in any::<EcPoint>(), k in any::<u64>(), l in any::<u64>()) {
+            let k = Scalar::from(k);
+            let l = Scalar::from(l);
+            prop_assert_eq![
+                multi_exponentiate(&x, &k, &y, &l),
+                exponentiate(&x, &k) * &exponentiate(&y, &l)
+            ];
+        }
+
+        #[test]
+        fn multi_exponentiate_all_same_as_exponentiate(xs in prop::collection::vec((any::<EcPoint>(), any::<u64>(), any::<bool>()), 0..80)) {
+            // negated scalars have the high bits set
+            let points_and_scalars: Vec<(EcPoint, Scalar)> = xs
+                .into_iter()
+                .map(|(x, k, neg)| (x, if neg { -Scalar::from(k) } else { Scalar::from(k) }))
+                .collect();
+            let expected = points_and_scalars
+                .iter()
+                .fold(identity(), |acc, (x, k)| acc * &exponentiate(x, k));
+            prop_assert_eq![multi_exponentiate_all(&points_and_scalars), expected];
+        }
+
     }
 }
@@ -0,0 +1,66 @@
+//! Re-verifies a signed transaction offline.
+//!
+//! Usage: `ergo-tx-replay [FILE]`, where FILE (or stdin) is a JSON object with `transaction`
+//! (signed transaction), `inputBoxes`, `dataInputBoxes` (optional), `blockHeader` (header of the
+//! block the transaction is included in) and `headers` (10 headers preceding the block) fields.
+//! Prints the verification result and cost of every input, exits with non-zero code if any
+//! input is not verified.
+
+use std::io::Read;
+use std::process::ExitCode;
+
+use ergo_lib::chain::transaction::replay::TxReplayInput;
+
+fn read_input() -> Result<String, String> {
+    let mut json = String::new();
+    match std::env::args().nth(1) {
+        Some(path) => {
+            json = std::fs::read_to_string(&path)
+                .map_err(|e| format!("cannot read {}: {}", path, e))?
+        }
+        None => {
+            std::io::stdin()
+                .read_to_string(&mut json)
+                .map_err(|e| format!("cannot read stdin: {}", e))?;
+        }
+    };
+    Ok(json)
+}
+
+fn run() -> Result<bool, String> {
+    let json = read_input()?;
+    let input: TxReplayInput =
+        serde_json::from_str(&json).map_err(|e| format!("invalid input JSON: {}", e))?;
+    let report = input.replay().map_err(|e| e.to_string())?;
+    println!("transaction {}", report.tx_id);
+    for input in &report.inputs {
+        match &input.result {
+            Ok(res) => {
+                println!(
+                    "input {} ({}): {}, cost {}",
+                    input.input_idx,
+                    input.box_id,
+                    if res.verified { "ok" } else { "NOT VERIFIED" },
+                    res.cost
+                );
+                if let Some(expr) = &res.pretty_printed_expr {
+                    println!("reduced to false:\n{}", expr);
+                }
+            }
+            Err(e) => println!("input {} ({}): error: {}", input.input_idx, input.box_id, e),
+        }
+    }
+    println!("total cost {}", report.total_cost());
+    Ok(report.is_valid())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(2)
+        }
+    }
+}
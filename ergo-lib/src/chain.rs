@@ -7,4 +7,5 @@ pub mod block;
 pub mod contract;
 pub mod ergo_box;
 pub mod ergo_state_context;
+pub mod parameters;
 pub mod transaction;
@@ -1,5 +1,8 @@
 //! Blockchain state
 use ergo_chain_types::{Header, PreHeader};
+use ergotree_ir::validation::ValidationSettings;
+
+use super::parameters::Parameters;
 
 /// Fixed number of last block headers in descending order (first header is the newest one)
 pub type Headers = [Header; 10];
@@ -12,14 +15,36 @@ pub struct ErgoStateContext {
     pub pre_header: PreHeader,
     /// Fixed number of last block headers in descending order (first header is the newest one)
     pub headers: Headers,
+    /// Blockchain parameters (used in transaction validation)
+    pub parameters: Parameters,
+    /// Validation rules statuses voted by miners (see [`ValidationSettings::from_extension`])
+    pub validation_settings: ValidationSettings,
 }
 
 impl ErgoStateContext {
-    /// Create an ErgoStateContext instance
+    /// Create an ErgoStateContext instance with default (current mainnet) parameters
     pub fn new(pre_header: PreHeader, headers: Headers) -> ErgoStateContext {
         ErgoStateContext {
             pre_header,
             headers,
+            parameters: Parameters::default(),
+            validation_settings: ValidationSettings::default(),
+        }
+    }
+
+    /// Return a new ErgoStateContext with the given blockchain parameters
+    pub fn with_parameters(self, parameters: Parameters) -> ErgoStateContext {
+        ErgoStateContext { parameters, ..self }
+    }
+
+    /// Return a new ErgoStateContext with the given validation settings
+    pub fn with_validation_settings(
+        self,
+        validation_settings: ValidationSettings,
+    ) -> ErgoStateContext {
+        ErgoStateContext {
+            validation_settings,
+            ..self
         }
     }
 }
@@ -0,0 +1,51 @@
+//! Blockchain parameters (adjustable via miners voting)
+
+use ergotree_interpreter::eval::costs::Costs;
+use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+
+/// Blockchain parameters used in the transaction validation
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Parameters {
+    /// Block version (protocol version)
+    pub block_version: u8,
+    /// Storage fee factor (per byte per storage period)
+    pub storage_fee_factor: u32,
+    /// Minimum value (in nanoERGs) per byte of the serialized box
+    pub min_value_per_byte: u32,
+    /// Maximum block size (in bytes)
+    pub max_block_size: u32,
+    /// Maximum cumulative computational cost of the block (in block cost units)
+    pub max_block_cost: u64,
+    /// Cost of accessing a token in the transaction inputs or outputs
+    pub token_access_cost: u64,
+    /// Cost of each transaction input
+    pub input_cost: u64,
+    /// Cost of each transaction data input
+    pub data_input_cost: u64,
+    /// Cost of each transaction output
+    pub output_cost: u64,
+}
+
+impl Parameters {
+    /// Maximum size of the serialized box (in bytes)
+    pub const MAX_BOX_SIZE: usize = 4 * 1024;
+    /// Maximum size of the serialized box script (in bytes)
+    pub const MAX_PROPOSITION_BYTES: usize = 4 * 1024;
+}
+
+impl Default for Parameters {
+    /// Current mainnet parameters
+    fn default() -> Self {
+        Parameters {
+            block_version: 3,
+            storage_fee_factor: 1_250_000,
+            min_value_per_byte: BoxValue::MIN_VALUE_PER_BOX_BYTE,
+            max_block_size: 1_271_009,
+            max_block_cost: 8_001_091,
+            token_access_cost: Costs::DEFAULT.tx_token_access_cost,
+            input_cost: Costs::DEFAULT.tx_input_cost,
+            data_input_cost: Costs::DEFAULT.tx_data_input_cost,
+            output_cost: Costs::DEFAULT.tx_output_cost,
+        }
+    }
+}
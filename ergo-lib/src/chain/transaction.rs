@@ -2,8 +2,11 @@
 
 mod data_input;
 pub mod input;
+pub mod parallel;
 pub mod reduced;
+pub mod replay;
 pub mod unsigned;
+pub mod validation;
 
 use bounded_vec::BoundedVec;
 use ergo_chain_types::blake2b256_hash;
@@ -13,6 +16,7 @@ use ergotree_interpreter::eval::extract_sigma_boolean;
 use ergotree_interpreter::eval::EvalError;
 use ergotree_interpreter::sigma_protocol::verifier::verify_signature;
 use ergotree_interpreter::sigma_protocol::verifier::TestVerifier;
+use ergotree_interpreter::sigma_protocol::verifier::VerificationResult;
 use ergotree_interpreter::sigma_protocol::verifier::Verifier;
 use ergotree_interpreter::sigma_protocol::verifier::VerifierError;
 use ergotree_ir::chain::ergo_box::BoxId;
@@ -44,7 +48,7 @@ use indexmap::IndexSet;
 use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::iter::FromIterator;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use super::ergo_state_context::ErgoStateContext;
 
@@ -355,6 +359,16 @@ pub fn verify_tx_input_proof(
     state_context: &ErgoStateContext,
     input_idx: usize,
 ) -> Result<bool, TxVerifyError> {
+    verify_tx_input(tx_context, state_context, input_idx).map(|res| res.result)
+}
+
+/// Verify transaction input's proof, returning the script cost and reduction diagnostics
+/// along with the verification result
+pub fn verify_tx_input(
+    tx_context: &TransactionContext<Transaction>,
+    state_context: &ErgoStateContext,
+    input_idx: usize,
+) -> Result<VerificationResult, TxVerifyError> {
     let input = tx_context
         .spending_tx
         .inputs
@@ -363,18 +377,16 @@ pub fn verify_tx_input_proof(
     let input_box = tx_context
         .get_input_box(&input.box_id)
         .ok_or(TransactionContextError::InputBoxNotFound(input_idx))?;
-    let ctx = Rc::new(make_context(state_context, tx_context, input_idx)?);
+    let ctx = Arc::new(make_context(state_context, tx_context, input_idx)?);
     let verifier = TestVerifier;
     let message_to_sign = tx_context.spending_tx.bytes_to_sign()?;
-    Ok(verifier
-        .verify(
-            &input_box.ergo_tree,
-            &Env::empty(),
-            ctx,
-            input.spending_proof.proof.clone(),
-            message_to_sign.as_slice(),
-        )?
-        .result)
+    Ok(verifier.verify(
+        &input_box.ergo_tree,
+        &Env::empty(),
+        ctx,
+        input.spending_proof.proof.clone(),
+        message_to_sign.as_slice(),
+    )?)
 }
 
 /// Arbitrary impl
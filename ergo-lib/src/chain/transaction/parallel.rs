@@ -0,0 +1,368 @@
+//! Verification of transaction inputs (and transactions of a block) on multiple threads.
+//! Inputs are verified on the [rayon](https://docs.rs/rayon) thread pool with the `parallel`
+//! feature, and one by one without it or on wasm32.
+
+use std::collections::HashMap;
+
+use ergotree_interpreter::sigma_protocol::verifier::VerificationResult;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use thiserror::Error;
+
+use crate::chain::block::FullBlock;
+use crate::chain::ergo_state_context::ErgoStateContext;
+use crate::wallet::signing::TransactionContext;
+use crate::wallet::tx_context::TransactionContextError;
+
+use super::verify_tx_input;
+use super::Transaction;
+use super::TxId;
+use super::TxVerifyError;
+
+/// Result of the input proof verification (see [`verify_tx_input`])
+pub type InputVerificationResult = Result<VerificationResult, TxVerifyError>;
+
+/// Errors on building the transaction contexts for the block verification
+#[derive(Error, Debug)]
+#[error("Transaction {tx_id:?}: {error}")]
+pub struct BlockVerifyError {
+    /// Id of the transaction with the missing input or data input box
+    pub tx_id: TxId,
+    /// The missing box error
+    pub error: TransactionContextError,
+}
+
+/// Verify the proofs of all transaction inputs one by one.
+/// Results are in the order of transaction inputs.
+pub fn verify_tx_inputs(
+    tx_context: &TransactionContext<Transaction>,
+    state_context: &ErgoStateContext,
+) -> Vec<InputVerificationResult> {
+    (0..tx_context.spending_tx.inputs.len())
+        .map(|input_idx| verify_tx_input(tx_context, state_context, input_idx))
+        .collect()
+}
+
+/// Verify the proofs of all transaction inputs on the current rayon thread pool (the global one,
+/// or the one the call is made in with `ThreadPool::install`).
+/// Results are in the order of transaction inputs and are the same as of [`verify_tx_inputs`].
+pub fn verify_tx_inputs_parallel(
+    tx_context: &TransactionContext<Transaction>,
+    state_context: &ErgoStateContext,
+) -> Vec<InputVerificationResult> {
+    run_parallel(tx_context.spending_tx.inputs.len(), |input_idx| {
+        verify_tx_input(tx_context, state_context, input_idx)
+    })
+}
+
+/// Verify the proofs of all inputs of the block transactions one by one.
+/// Input and data input boxes are looked up in `utxos` and in the outputs of the preceding
+/// transactions of the block. `state_context` is the context of the block (its pre-header and
+/// the last headers before it).
+/// Results are in the order of block transactions and their inputs.
+pub fn verify_block(
+    block: &FullBlock,
+    utxos: &HashMap<BoxId, ErgoBox>,
+    state_context: &ErgoStateContext,
+) -> Result<Vec<Vec<InputVerificationResult>>, BlockVerifyError> {
+    Ok(block_tx_contexts(block, utxos)?
+        .iter()
+        .map(|tx_context| verify_tx_inputs(tx_context, state_context))
+        .collect())
+}
+
+/// Verify the proofs of all inputs of the block transactions on the current rayon thread pool
+/// (see [`verify_tx_inputs_parallel`]).
+/// Inputs of all transactions are distributed among the threads, so a block with a few large
+/// transactions is verified as fast as a block with many small ones.
+/// Results are the same as of [`verify_block`].
+pub fn verify_block_parallel(
+    block: &FullBlock,
+    utxos: &HashMap<BoxId, ErgoBox>,
+    state_context: &ErgoStateContext,
+) -> Result<Vec<Vec<InputVerificationResult>>, BlockVerifyError> {
+    let tx_contexts = block_tx_contexts(block, utxos)?;
+    let inputs: Vec<(usize, usize)> = tx_contexts
+        .iter()
+        .enumerate()
+        .flat_map(|(tx_idx, tx_context)| {
+            (0..tx_context.spending_tx.inputs.len()).map(move |input_idx| (tx_idx, input_idx))
+        })
+        .collect();
+    let mut results = run_parallel(inputs.len(), |job_idx| {
+        let (tx_idx, input_idx) = inputs[job_idx];
+        verify_tx_input(&tx_contexts[tx_idx], state_context, input_idx)
+    })
+    .into_iter();
+    Ok(tx_contexts
+        .iter()
+        .map(|tx_context| {
+            results
+                .by_ref()
+                .take(tx_context.spending_tx.inputs.len())
+                .collect()
+        })
+        .collect())
+}
+
+fn block_tx_contexts(
+    block: &FullBlock,
+    utxos: &HashMap<BoxId, ErgoBox>,
+) -> Result<Vec<TransactionContext<Transaction>>, BlockVerifyError> {
+    let mut block_outputs: HashMap<BoxId, ErgoBox> = HashMap::new();
+    let mut tx_contexts = Vec::with_capacity(block.block_transactions.transactions.len());
+    for tx in block.block_transactions.transactions.iter() {
+        let lookup = |box_id: &BoxId| utxos.get(box_id).or_else(|| block_outputs.get(box_id));
+        let to_block_error = |error| BlockVerifyError {
+            tx_id: tx.id(),
+            error,
+        };
+        let input_boxes = tx
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(idx, input)| {
+                lookup(&input.box_id)
+                    .cloned()
+                    .ok_or(TransactionContextError::InputBoxNotFound(idx))
+            })
+            .collect::<Result<Vec<ErgoBox>, _>>()
+            .map_err(to_block_error)?;
+        let data_boxes = tx
+            .data_inputs
+            .iter()
+            .flat_map(|data_inputs| data_inputs.iter())
+            .enumerate()
+            .map(|(idx, data_input)| {
+                lookup(&data_input.box_id)
+                    .cloned()
+                    .ok_or(TransactionContextError::DataInputBoxNotFound(idx))
+            })
+            .collect::<Result<Vec<ErgoBox>, _>>()
+            .map_err(to_block_error)?;
+        block_outputs.extend(tx.outputs.iter().map(|b| (b.box_id(), b.clone())));
+        tx_contexts.push(
+            TransactionContext::new(tx.clone(), input_boxes, data_boxes).map_err(to_block_error)?,
+        );
+    }
+    Ok(tx_contexts)
+}
+
+/// Runs `f` for every job index in `0..jobs_count` on the current rayon thread pool, results are
+/// returned in the order of job indices
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+fn run_parallel<R, F>(jobs_count: usize, f: F) -> Vec<R>
+where
+    R: Send,
+    F: Fn(usize) -> R + Sync + Send,
+{
+    use rayon::prelude::*;
+    (0..jobs_count).into_par_iter().map(f).collect()
+}
+
+/// Runs `f` for every job index in `0..jobs_count` one by one
+#[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+fn run_parallel<R, F>(jobs_count: usize, f: F) -> Vec<R>
+where
+    F: Fn(usize) -> R,
+{
+    (0..jobs_count).map(f).collect()
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ergo_chain_types::Header;
+    use ergotree_interpreter::eval::context::Context;
+    use ergotree_interpreter::eval::env::Env;
+    use ergotree_interpreter::sigma_protocol::private_input::DlogProverInput;
+    use ergotree_interpreter::sigma_protocol::prover::ProofBytes;
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+    use ergotree_ir::ergo_tree::ErgoTree;
+    use ergotree_ir::mir::expr::Expr;
+    use sigma_test_util::force_any_val;
+
+    use super::*;
+    use crate::chain::block::BlockTransactions;
+    use crate::chain::ergo_box::box_builder::ErgoBoxCandidateBuilder;
+    use crate::chain::transaction::unsigned::UnsignedTransaction;
+    use crate::chain::transaction::UnsignedInput;
+    use crate::wallet::secret_key::SecretKey;
+    use crate::wallet::Wallet;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn eval_state_is_send_sync() {
+        assert_send_sync::<Context>();
+        assert_send_sync::<Env>();
+        assert_send_sync::<TransactionContext<Transaction>>();
+        assert_send_sync::<ErgoStateContext>();
+    }
+
+    /// Runs `f` on a new thread pool of `num_threads` threads
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    fn in_thread_pool<R: Send>(num_threads: usize, f: impl FnOnce() -> R + Send) -> R {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .unwrap()
+            .install(f)
+    }
+
+    /// Runs `f` (inputs are verified one by one without the `parallel` feature)
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    fn in_thread_pool<R>(_num_threads: usize, f: impl FnOnce() -> R) -> R {
+        f()
+    }
+
+    fn p2pk_box(secret: &DlogProverInput, tx_id: TxId, index: u16) -> ErgoBox {
+        let tree = ErgoTree::try_from(Expr::Const(secret.public_image().into())).unwrap();
+        ErgoBox::new(
+            BoxValue::SAFE_USER_MIN,
+            tree,
+            None,
+            NonMandatoryRegisters::empty(),
+            0,
+            tx_id,
+            index,
+        )
+        .unwrap()
+    }
+
+    /// Signed transaction spending the given boxes guarded by `secret`
+    fn signed_tx(secret: &DlogProverInput, input_boxes: Vec<ErgoBox>) -> Transaction {
+        let tree = input_boxes[0].ergo_tree.clone();
+        let candidate = ErgoBoxCandidateBuilder::new(BoxValue::SAFE_USER_MIN, tree, 0)
+            .build()
+            .unwrap();
+        let unsigned_tx = UnsignedTransaction::new_from_vec(
+            input_boxes
+                .iter()
+                .cloned()
+                .map(UnsignedInput::from)
+                .collect(),
+            vec![],
+            vec![candidate],
+        )
+        .unwrap();
+        let tx_context = TransactionContext::new(unsigned_tx, input_boxes, vec![]).unwrap();
+        let wallet = Wallet::from_secrets(vec![SecretKey::DlogSecretKey(secret.clone())]);
+        wallet
+            .sign_transaction(tx_context, &force_any_val::<ErgoStateContext>(), None)
+            .unwrap()
+    }
+
+    /// Comparable form of the verification results
+    fn comparable(
+        results: Vec<InputVerificationResult>,
+    ) -> Vec<Result<VerificationResult, String>> {
+        results
+            .into_iter()
+            .map(|r| r.map_err(|e| e.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parallel_tx_inputs_same_as_sequential() {
+        let secret = force_any_val::<DlogProverInput>();
+        let input_boxes: Vec<ErgoBox> =
+            (0..8).map(|i| p2pk_box(&secret, TxId::zero(), i)).collect();
+        let tx = signed_tx(&secret, input_boxes.clone());
+        // the proof of the last input is tampered, so it's not verified
+        let last = input_boxes.len() - 1;
+        let mut inputs = tx.inputs.as_vec().clone();
+        if let ProofBytes::Some(bytes) = &mut inputs[last].spending_proof.proof {
+            bytes[0] ^= 1;
+        }
+        let tx = Transaction::new_from_vec(inputs, vec![], tx.output_candidates.as_vec().clone())
+            .unwrap();
+        let tx_context = TransactionContext::new(tx, input_boxes, vec![]).unwrap();
+        let state_context = force_any_val::<ErgoStateContext>();
+
+        let sequential = comparable(verify_tx_inputs(&tx_context, &state_context));
+        assert_eq!(sequential.len(), 8);
+        assert!(sequential[..last]
+            .iter()
+            .all(|r| r.as_ref().unwrap().result));
+        assert!(!sequential[last].as_ref().unwrap().result);
+        for num_threads in [1, 3, 8, 16] {
+            let parallel = in_thread_pool(num_threads, || {
+                comparable(verify_tx_inputs_parallel(&tx_context, &state_context))
+            });
+            assert_eq!(parallel, sequential);
+        }
+    }
+
+    #[test]
+    fn parallel_block_same_as_sequential() {
+        let secret = force_any_val::<DlogProverInput>();
+        let utxo_boxes: Vec<ErgoBox> = (0..6).map(|i| p2pk_box(&secret, TxId::zero(), i)).collect();
+        let tx1 = signed_tx(&secret, utxo_boxes[..4].to_vec());
+        // spends the output of the first transaction of the block
+        let tx2 = signed_tx(
+            &secret,
+            vec![utxo_boxes[4].clone(), tx1.outputs.first().clone()],
+        );
+        let tx3 = signed_tx(&secret, vec![utxo_boxes[5].clone()]);
+        let block = FullBlock {
+            header: force_any_val::<Header>(),
+            block_transactions: BlockTransactions {
+                transactions: vec![tx1, tx2, tx3].try_into().unwrap(),
+            },
+        };
+        let utxos: HashMap<BoxId, ErgoBox> =
+            utxo_boxes.into_iter().map(|b| (b.box_id(), b)).collect();
+        let state_context = force_any_val::<ErgoStateContext>();
+
+        let sequential: Vec<_> = verify_block(&block, &utxos, &state_context)
+            .unwrap()
+            .into_iter()
+            .map(comparable)
+            .collect();
+        assert_eq!(
+            sequential.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![4, 2, 1]
+        );
+        assert!(sequential
+            .iter()
+            .flatten()
+            .all(|r| r.as_ref().unwrap().result));
+        let parallel: Vec<_> = in_thread_pool(4, || {
+            verify_block_parallel(&block, &utxos, &state_context).unwrap()
+        })
+        .into_iter()
+        .map(comparable)
+        .collect();
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn block_missing_input_box() {
+        let secret = force_any_val::<DlogProverInput>();
+        let input_box = p2pk_box(&secret, TxId::zero(), 0);
+        let tx = signed_tx(&secret, vec![input_box]);
+        let tx_id = tx.id();
+        let block = FullBlock {
+            header: force_any_val::<Header>(),
+            block_transactions: BlockTransactions {
+                transactions: vec![tx].try_into().unwrap(),
+            },
+        };
+        let err = verify_block_parallel(
+            &block,
+            &HashMap::new(),
+            &force_any_val::<ErgoStateContext>(),
+        )
+        .unwrap_err();
+        assert_eq!(err.tx_id, tx_id);
+        assert!(matches!(
+            err.error,
+            TransactionContextError::InputBoxNotFound(0)
+        ));
+    }
+}
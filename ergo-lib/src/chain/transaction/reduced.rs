@@ -1,7 +1,7 @@
 //! Represent `reduced` transaction, i.e. unsigned transaction where each unsigned input
 //! is augmented with ReducedInput which contains a script reduction result.
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 use ergotree_interpreter::eval::env::Env;
 use ergotree_interpreter::eval::reduce_to_crypto;
@@ -23,6 +23,7 @@ use crate::wallet::signing::TxSigningError;
 use crate::wallet::tx_context::TransactionContextError;
 
 use super::unsigned::UnsignedTransaction;
+use super::validation::initial_tx_cost;
 use super::TxIoVec;
 
 /// Input box script reduced to SigmaBoolean
@@ -62,6 +63,11 @@ impl ReducedTransaction {
     pub fn reduced_inputs(&self) -> TxIoVec<ReducedInput> {
         self.reduced_inputs.clone()
     }
+
+    /// Transaction cost according to the prover (block cost units)
+    pub fn tx_cost(&self) -> u32 {
+        self.tx_cost
+    }
 }
 
 /// Reduce each input of unsigned transaction to sigma proposition
@@ -70,6 +76,7 @@ pub fn reduce_tx(
     state_context: &ErgoStateContext,
 ) -> Result<ReducedTransaction, TxSigningError> {
     let tx = &tx_context.spending_tx;
+    let tx_cost = initial_tx_cost(tx, &tx_context.input_boxes(), &state_context.parameters);
     let reduced_inputs = tx
         .inputs
         .clone()
@@ -78,7 +85,7 @@ pub fn reduce_tx(
             let input_box = tx_context
                 .get_input_box(&input.box_id)
                 .ok_or(TransactionContextError::InputBoxNotFound(idx))?;
-            let ctx = Rc::new(make_context(state_context, &tx_context, idx)?);
+            let ctx = Arc::new(make_context(state_context, &tx_context, idx)?);
             let expr = input_box
                 .ergo_tree
                 .proposition()
@@ -96,7 +103,7 @@ pub fn reduce_tx(
     Ok(ReducedTransaction {
         unsigned_tx: tx.clone(),
         reduced_inputs,
-        tx_cost: 0,
+        tx_cost: tx_cost.min(u32::MAX as u64) as u32,
     })
 }
 
@@ -0,0 +1,468 @@
+//! Offline replay (re-verification) of signed transactions
+
+use ergo_chain_types::BlockId;
+use ergo_chain_types::Header;
+use ergo_chain_types::PreHeader;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use thiserror::Error;
+
+use crate::chain::ergo_state_context::ErgoStateContext;
+use crate::chain::ergo_state_context::Headers;
+use crate::wallet::signing::TransactionContext;
+use crate::wallet::tx_context::TransactionContextError;
+
+use super::verify_tx_input;
+use super::Transaction;
+use super::TxId;
+use super::TxVerifyError;
+
+/// Result of the input script verification
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct InputVerification {
+    /// true if the proof satisfies the script
+    pub verified: bool,
+    /// Script cost (in block cost units)
+    pub cost: u64,
+    /// Pretty printed script if it was reduced to `false`
+    pub pretty_printed_expr: Option<String>,
+}
+
+/// Replay result for a single transaction input
+#[derive(Debug)]
+pub struct InputReplay {
+    /// Index of the input in the transaction
+    pub input_idx: usize,
+    /// Id of the spent box
+    pub box_id: BoxId,
+    /// Verification result or the error (i.e. script evaluation failure)
+    pub result: Result<InputVerification, TxVerifyError>,
+}
+
+impl InputReplay {
+    /// Returns true if the input proof is verified
+    pub fn is_verified(&self) -> bool {
+        matches!(&self.result, Ok(InputVerification { verified: true, .. }))
+    }
+}
+
+/// Transaction replay report
+#[derive(Debug)]
+pub struct TxReplayReport {
+    /// Transaction id
+    pub tx_id: TxId,
+    /// Per-input results (in the order of transaction inputs)
+    pub inputs: Vec<InputReplay>,
+}
+
+impl TxReplayReport {
+    /// Returns true if the proofs of all inputs are verified
+    pub fn is_valid(&self) -> bool {
+        self.inputs.iter().all(InputReplay::is_verified)
+    }
+
+    /// Total cost of the successfully evaluated input scripts (in block cost units)
+    pub fn total_cost(&self) -> u64 {
+        self.inputs
+            .iter()
+            .filter_map(|i| i.result.as_ref().ok())
+            .map(|v| v.cost)
+            .sum()
+    }
+}
+
+/// Errors on transaction replay
+#[derive(Error, Debug)]
+pub enum TxReplayError {
+    /// Input or data input boxes do not match the transaction
+    #[error("TransactionContextError: {0}")]
+    TransactionContextError(#[from] TransactionContextError),
+    /// Unexpected number of headers
+    #[error("Expected 10 last block headers, got {0}")]
+    InvalidHeadersCount(usize),
+    /// The newest of the given headers is not the parent of the block
+    #[error("Block parent is {expected}, but the newest header is {got}")]
+    ParentMismatch {
+        /// Parent id of the block
+        expected: BlockId,
+        /// Id of the newest header
+        got: BlockId,
+    },
+}
+
+/// Re-verifies the proofs of all transaction inputs. The context for every input is built the
+/// same way as for signing (see [`crate::wallet::signing::make_context`]).
+/// Errors only if the given boxes do not match the transaction inputs and data inputs, script
+/// failures are reported per input.
+pub fn replay_transaction(
+    tx: Transaction,
+    input_boxes: Vec<ErgoBox>,
+    data_input_boxes: Vec<ErgoBox>,
+    state_context: &ErgoStateContext,
+) -> Result<TxReplayReport, TxReplayError> {
+    let tx_id = tx.id();
+    let tx_context = TransactionContext::new(tx, input_boxes, data_input_boxes)?;
+    let inputs = tx_context
+        .spending_tx
+        .inputs
+        .iter()
+        .enumerate()
+        .map(|(input_idx, input)| InputReplay {
+            input_idx,
+            box_id: input.box_id,
+            result: verify_tx_input(&tx_context, state_context, input_idx).map(|res| {
+                InputVerification {
+                    verified: res.result,
+                    cost: res.cost,
+                    pretty_printed_expr: res.diag.pretty_printed_expr,
+                }
+            }),
+        })
+        .collect();
+    Ok(TxReplayReport { tx_id, inputs })
+}
+
+/// State context of the block the transaction is included in, i.e. the block's pre-header
+/// (use [`PreHeader::from`] for the block header) and the 10 headers preceding the block
+/// (in any order). Fails if the newest header is not the parent of the block.
+pub fn state_context_for_block(
+    pre_header: PreHeader,
+    mut headers: Vec<Header>,
+) -> Result<ErgoStateContext, TxReplayError> {
+    let headers_count = headers.len();
+    headers.sort_by_key(|h| std::cmp::Reverse(h.height));
+    let headers: Headers = headers
+        .try_into()
+        .map_err(|_| TxReplayError::InvalidHeadersCount(headers_count))?;
+    if headers[0].id != pre_header.parent_id {
+        return Err(TxReplayError::ParentMismatch {
+            expected: pre_header.parent_id,
+            got: headers[0].id,
+        });
+    }
+    Ok(ErgoStateContext::new(pre_header, headers))
+}
+
+/// Transaction replay input in the JSON format of the node/explorer API
+#[cfg(feature = "json")]
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct TxReplayInput {
+    /// Signed transaction
+    #[serde(rename = "transaction")]
+    pub transaction: Transaction,
+    /// Boxes spent by the transaction
+    #[serde(rename = "inputBoxes")]
+    pub input_boxes: Vec<ErgoBox>,
+    /// Boxes of the transaction data inputs
+    #[serde(rename = "dataInputBoxes", default)]
+    pub data_input_boxes: Vec<ErgoBox>,
+    /// Header of the block the transaction is included in
+    #[serde(rename = "blockHeader")]
+    pub block_header: Header,
+    /// 10 headers preceding the block
+    #[serde(rename = "headers")]
+    pub headers: Vec<Header>,
+}
+
+#[cfg(feature = "json")]
+impl TxReplayInput {
+    /// Re-verify the transaction in the context of the block it is included in
+    /// (see [`state_context_for_block`] and [`replay_transaction`])
+    pub fn replay(self) -> Result<TxReplayReport, TxReplayError> {
+        let state_context = state_context_for_block(self.block_header.into(), self.headers)?;
+        replay_transaction(
+            self.transaction,
+            self.input_boxes,
+            self.data_input_boxes,
+            &state_context,
+        )
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ergotree_interpreter::sigma_protocol::private_input::DlogProverInput;
+    use ergotree_interpreter::sigma_protocol::prover::ProofBytes;
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+    use ergotree_ir::ergo_tree::ErgoTree;
+    use ergotree_ir::mir::expr::Expr;
+    use sigma_test_util::force_any_val;
+
+    use super::*;
+    use crate::chain::ergo_box::box_builder::ErgoBoxCandidateBuilder;
+    use crate::chain::transaction::unsigned::UnsignedTransaction;
+    use crate::chain::transaction::Input;
+    use crate::chain::transaction::UnsignedInput;
+    use crate::wallet::secret_key::SecretKey;
+    use crate::wallet::Wallet;
+
+    fn signed_p2pk_tx() -> (Transaction, Vec<ErgoBox>) {
+        let secret = force_any_val::<DlogProverInput>();
+        let tree = ErgoTree::try_from(Expr::Const(secret.public_image().into())).unwrap();
+        let input_box = ErgoBox::new(
+            BoxValue::SAFE_USER_MIN,
+            tree.clone(),
+            None,
+            NonMandatoryRegisters::empty(),
+            0,
+            TxId::zero(),
+            0,
+        )
+        .unwrap();
+        let candidate = ErgoBoxCandidateBuilder::new(BoxValue::SAFE_USER_MIN, tree, 0)
+            .build()
+            .unwrap();
+        let unsigned_tx = UnsignedTransaction::new_from_vec(
+            vec![UnsignedInput::from(input_box.clone())],
+            vec![],
+            vec![candidate],
+        )
+        .unwrap();
+        let tx_context =
+            TransactionContext::new(unsigned_tx, vec![input_box.clone()], vec![]).unwrap();
+        let wallet = Wallet::from_secrets(vec![SecretKey::DlogSecretKey(secret)]);
+        let tx = wallet
+            .sign_transaction(tx_context, &force_any_val::<ErgoStateContext>(), None)
+            .unwrap();
+        (tx, vec![input_box])
+    }
+
+    #[test]
+    fn replay_signed_tx() {
+        let (tx, input_boxes) = signed_p2pk_tx();
+        let report = replay_transaction(
+            tx,
+            input_boxes,
+            vec![],
+            &force_any_val::<ErgoStateContext>(),
+        )
+        .unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.inputs.len(), 1);
+        assert!(report.total_cost() > 0);
+    }
+
+    #[test]
+    fn replay_invalid_proof() {
+        let (tx, input_boxes) = signed_p2pk_tx();
+        let inputs: Vec<Input> = tx
+            .inputs
+            .iter()
+            .map(|i| {
+                let mut input = i.clone();
+                input.spending_proof.proof = match &input.spending_proof.proof {
+                    ProofBytes::Some(bytes) => {
+                        let mut bytes = bytes.clone();
+                        bytes[0] ^= 1;
+                        ProofBytes::Some(bytes)
+                    }
+                    ProofBytes::Empty => ProofBytes::Empty,
+                };
+                input
+            })
+            .collect();
+        let tampered =
+            Transaction::new_from_vec(inputs, vec![], tx.output_candidates.as_vec().clone())
+                .unwrap();
+        let report = replay_transaction(
+            tampered,
+            input_boxes,
+            vec![],
+            &force_any_val::<ErgoStateContext>(),
+        )
+        .unwrap();
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn replay_missing_input_box() {
+        let (tx, _) = signed_p2pk_tx();
+        let other_box = force_any_val::<ErgoBox>();
+        assert!(matches!(
+            replay_transaction(
+                tx,
+                vec![other_box],
+                vec![],
+                &force_any_val::<ErgoStateContext>()
+            ),
+            Err(TxReplayError::TransactionContextError(
+                TransactionContextError::InputBoxNotFound(0)
+            ))
+        ));
+    }
+
+    /// The 10 headers preceding the given block (not read by the scripts under test)
+    fn headers_before(block_header: &Header) -> Vec<Header> {
+        let mut headers = force_any_val::<Headers>().to_vec();
+        for (i, h) in headers.iter_mut().enumerate() {
+            h.height = block_header.height - 1 - i as u32;
+            // node API always provides the PoW distance (`d`)
+            h.autolykos_solution.pow_distance = Some(num_bigint::BigInt::from(0));
+        }
+        headers[0].id = block_header.parent_id;
+        headers
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn replay_from_json() {
+        let (transaction, input_boxes) = signed_p2pk_tx();
+        let mut block_header = force_any_val::<Header>();
+        block_header.height = 1000;
+        block_header.autolykos_solution.pow_distance = Some(num_bigint::BigInt::from(0));
+        let mut headers = headers_before(&block_header);
+        let replay_input = TxReplayInput {
+            transaction,
+            input_boxes,
+            data_input_boxes: vec![],
+            block_header: block_header.clone(),
+            headers: headers.clone(),
+        };
+        let json = serde_json::to_string(&replay_input).unwrap();
+        let parsed: TxReplayInput = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, replay_input);
+        assert!(parsed.replay().unwrap().is_valid());
+
+        headers.remove(0);
+        assert!(matches!(
+            state_context_for_block(block_header.clone().into(), headers.clone()),
+            Err(TxReplayError::InvalidHeadersCount(9))
+        ));
+        headers.push(force_any_val::<Header>());
+        assert!(matches!(
+            state_context_for_block(block_header.into(), headers),
+            Err(TxReplayError::ParentMismatch { .. })
+        ));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn replay_mainnet_emission_tx() {
+        // header and emission transaction of the mainnet block 540000 (see `chain::block` tests)
+        let block_header: Header = serde_json::from_str(
+            r#"{
+              "extensionId": "a1c5a5f409fce4d16a501371b11aaaf0e0a44609d8436958c383e12f9c14528c",
+              "difficulty": "1371769604669440",
+              "votes": "000000",
+              "timestamp": 1627249021284,
+              "size": 221,
+              "stateRoot": "1d3d031ba060245d8184948c6f726a8bb98a1bc621affc4a1dcf0e20226eb27716",
+              "height": 540000,
+              "nBits": 117759902,
+              "version": 2,
+              "id": "96911575efdceb082b974aa3042263be07632de48031aa2204d77d8d5a8240b8",
+              "adProofsRoot": "aa0d212ec398d9558b2b2f24239963bdd8d2d22f70b6e8b5cfff3474609bcdde",
+              "transactionsRoot": "235a6e8f28f54fef5fbcd17d2638eb03ef9cfb331f4b5a50fbb74df4a524dcb4",
+              "extensionHash": "badffc4d646e1c2babcf1ce8422b4f2430b6262c947c964671e97486d8bdb601",
+              "powSolutions": {
+                "pk": "02b3a06d6eaa8671431ba1db4dd427a77f75a5c2acbd71bfb725d38adc2b55f669",
+                "w": "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+                "n": "0537288a2c246648",
+                "d": 0
+              },
+              "adProofsId": "13856ec4123971268ff0d7493bfa520021c6328ceba648bf39484b45761f4edf",
+              "transactionsId": "5871d44565a08892d03f3e4f53a3d98a7f21e549738fff0864bce205916a5bfb",
+              "parentId": "c55f05c91fea37f95eff73dfa62e8745f54db6dff5e9f257e39b9c0cfbfd8133"
+            }"#,
+        )
+        .unwrap();
+        let emission_tx: Transaction = serde_json::from_str(
+            r#"{
+              "id": "d301f351d5d74aa314edd19914e4e593bd0316166c25a09aa222f9b519ee5fdf",
+              "inputs": [
+                {
+                  "boxId": "805a5a5293a38c4ef872f5a1b392404a2808f7ca1f149f0874dbddd31a30677f",
+                  "spendingProof": {
+                    "proofBytes": "",
+                    "extension": {}
+                  }
+                }
+              ],
+              "dataInputs": [],
+              "outputs": [
+                {
+                  "boxId": "b00eee09bb8ad9b3b4d93042fd28c966aa9b225c228732c69cb74656788ae8f0",
+                  "value": 56959132500000000,
+                  "ergoTree": "101004020e36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ea02d192a39a8cc7a7017300730110010204020404040004c0fd4f05808c82f5f6030580b8c9e5ae040580f882ad16040204c0944004c0f407040004000580f882ad16d19683030191a38cc7a7019683020193c2b2a57300007473017302830108cdeeac93a38cc7b2a573030001978302019683040193b1a5730493c2a7c2b2a573050093958fa3730673079973089c73097e9a730a9d99a3730b730c0599c1a7c1b2a5730d00938cc7b2a5730e0001a390c1a7730f",
+                  "assets": [],
+                  "creationHeight": 540000,
+                  "additionalRegisters": {},
+                  "transactionId": "d301f351d5d74aa314edd19914e4e593bd0316166c25a09aa222f9b519ee5fdf",
+                  "index": 0
+                },
+                {
+                  "boxId": "9c700fdcfa7cb5fa83df806b30f69bc1a4690e33b1af77076ced4f7b28c76e37",
+                  "value": 67500000000,
+                  "ergoTree": "100204a00b08cd02b3a06d6eaa8671431ba1db4dd427a77f75a5c2acbd71bfb725d38adc2b55f669ea02d192a39a8cc7a70173007301",
+                  "assets": [],
+                  "creationHeight": 540000,
+                  "additionalRegisters": {},
+                  "transactionId": "d301f351d5d74aa314edd19914e4e593bd0316166c25a09aa222f9b519ee5fdf",
+                  "index": 1
+                }
+              ],
+              "size": 344
+            }"#,
+        )
+        .unwrap();
+        let new_emission_box = emission_tx.outputs.first();
+        let miner_reward = emission_tx.outputs.get(1).unwrap();
+        // The spent emission box is the new emission box of the previous block, the emission
+        // transaction pays no fee. Its id depends on the id of the previous emission transaction,
+        // so the input is pointed at the reconstructed box (the emission script requires no proof).
+        let emission_box = ErgoBox::new(
+            new_emission_box
+                .value
+                .checked_add(&miner_reward.value)
+                .unwrap(),
+            new_emission_box.ergo_tree.clone(),
+            None,
+            NonMandatoryRegisters::empty(),
+            block_header.height - 1,
+            force_any_val::<TxId>(),
+            0,
+        )
+        .unwrap();
+        let tx = Transaction::new_from_vec(
+            vec![Input::new(
+                emission_box.box_id(),
+                emission_tx.inputs.first().spending_proof.clone(),
+            )],
+            vec![],
+            emission_tx.output_candidates.as_vec().clone(),
+        )
+        .unwrap();
+        let headers = headers_before(&block_header);
+
+        let state_context =
+            state_context_for_block(block_header.clone().into(), headers.clone()).unwrap();
+        let report = replay_transaction(
+            tx.clone(),
+            vec![emission_box.clone()],
+            vec![],
+            &state_context,
+        )
+        .unwrap();
+        assert!(report.is_valid());
+
+        // the miner reward must be paid to the block's miner at the block's height, neither is
+        // known from the preceding headers
+        let mut parent_pre_header = PreHeader::from(headers[0].clone());
+        parent_pre_header.height += 1;
+        parent_pre_header.parent_id = headers[0].id;
+        let state_context = state_context_for_block(parent_pre_header, headers).unwrap();
+        let report = replay_transaction(tx, vec![emission_box], vec![], &state_context).unwrap();
+        assert!(matches!(
+            report.inputs[0].result,
+            Ok(InputVerification {
+                verified: false,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,1033 @@
+//! Transaction validation (stateless and stateful rules as checked by the node)
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::sync::Arc;
+
+use ergotree_interpreter::eval::costs::Cost;
+use ergotree_interpreter::eval::costs::Costs;
+use ergotree_interpreter::eval::env::Env;
+use ergotree_interpreter::eval::reduce_to_crypto_with_cost;
+use ergotree_interpreter::sigma_protocol::verifier::verify_signature;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::token::TokenAmount;
+use ergotree_ir::chain::token::TokenId;
+use ergotree_ir::serialization::SigmaSerializable;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
+use thiserror::Error;
+
+use crate::chain::ergo_state_context::ErgoStateContext;
+use crate::chain::parameters::Parameters;
+use crate::wallet::signing::make_context;
+use crate::wallet::signing::ErgoTransaction;
+use crate::wallet::signing::TransactionContext;
+use crate::wallet::tx_context::TransactionContextError;
+
+use super::Transaction;
+
+/// Violation of a transaction validation rule.
+/// See [`TxValidationError::rule_id`] for the id of the corresponding node's validation rule.
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum TxValidationError {
+    /// Sum of the output values overflows
+    #[error("Sum of the output values overflows")]
+    OutputSumOverflow,
+    /// The same box is spent more than once
+    #[error("Input box {0:?} is spent more than once")]
+    DuplicateInput(BoxId),
+    /// Too many tokens in the output box
+    #[error("Output #{index} has {count} tokens, max allowed: {max}")]
+    TooManyTokensInBox {
+        /// Output index
+        index: usize,
+        /// Number of tokens
+        count: usize,
+        /// Maximum allowed number of tokens
+        max: usize,
+    },
+    /// Output box value is below the minimum for its size
+    #[error("Output #{index} value {value} is less than minimal {min_value} for its size")]
+    Dust {
+        /// Output index
+        index: usize,
+        /// Box value
+        value: u64,
+        /// Minimum value for the box size
+        min_value: u64,
+    },
+    /// Output box creation height is greater than the current height
+    #[error("Output #{index} creation height {creation_height} is in the future (current height: {height})")]
+    FutureCreationHeight {
+        /// Output index
+        index: usize,
+        /// Output creation height
+        creation_height: u32,
+        /// Current height
+        height: u32,
+    },
+    /// Input box is missing
+    #[error("Input box {0:?} not found")]
+    InputBoxNotFound(BoxId),
+    /// Data input box is missing
+    #[error("Data input box {0:?} not found")]
+    DataInputBoxNotFound(BoxId),
+    /// Sum of the input values overflows
+    #[error("Sum of the input values overflows")]
+    InputSumOverflow,
+    /// Sum of the token amounts in the outputs exceeds the maximal token amount
+    #[error("Sum of the token {0:?} amounts in the outputs overflows")]
+    OutputTokenSumOverflow(TokenId),
+    /// Sum of the token amounts in the inputs exceeds the maximal token amount
+    #[error("Sum of the token {0:?} amounts in the inputs overflows")]
+    InputTokenSumOverflow(TokenId),
+    /// Sum of the input values is not equal to the sum of the output values
+    #[error("ERG is not preserved: inputs {inputs}, outputs {outputs}")]
+    ErgPreservation {
+        /// Sum of the input values
+        inputs: u64,
+        /// Sum of the output values
+        outputs: u64,
+    },
+    /// Output token amount exceeds the amount in the inputs
+    #[error("Token {token_id:?} is not preserved: inputs {inputs}, outputs {outputs}")]
+    TokenPreservation {
+        /// Token id
+        token_id: TokenId,
+        /// Token amount in inputs
+        inputs: u64,
+        /// Token amount in outputs
+        outputs: u64,
+    },
+    /// New token is issued with an id other than the id of the first input box
+    #[error(
+        "New token {token_id:?} can only be issued with the id of the first input box {expected:?}"
+    )]
+    InvalidNewTokenId {
+        /// Issued token id
+        token_id: TokenId,
+        /// Id of the first input box (the only allowed id of the new token)
+        expected: TokenId,
+    },
+    /// Input script is not satisfied (reduced to false, invalid proof or evaluation error)
+    #[error("Input #{index} script validation failed: {reason}")]
+    ScriptValidation {
+        /// Input index
+        index: usize,
+        /// Failure description
+        reason: String,
+    },
+    /// Serialized box is too big
+    #[error("Output #{index} size {size} exceeds max box size {max}")]
+    BoxSize {
+        /// Output index
+        index: usize,
+        /// Serialized box size
+        size: usize,
+        /// Maximum box size
+        max: usize,
+    },
+    /// Output box cannot be serialized
+    #[error("Output #{index} serialization failed: {reason}")]
+    OutputSerialization {
+        /// Output index
+        index: usize,
+        /// Failure description
+        reason: String,
+    },
+    /// Serialized box script is too big
+    #[error("Output #{index} script size {size} exceeds max {max}")]
+    BoxPropositionSize {
+        /// Output index
+        index: usize,
+        /// Serialized script size
+        size: usize,
+        /// Maximum script size
+        max: usize,
+    },
+    /// Output creation height is less than the creation height of the inputs
+    #[error("Output #{index} creation height {creation_height} is less than max input creation height {max_input_height}")]
+    MonotonicHeight {
+        /// Output index
+        index: usize,
+        /// Output creation height
+        creation_height: u32,
+        /// Maximum creation height of the inputs
+        max_input_height: u32,
+    },
+    /// Accumulated transaction cost exceeds the limit
+    #[error("Transaction cost {cost} exceeds the limit {limit}")]
+    CostLimitExceeded {
+        /// Accumulated cost
+        cost: u64,
+        /// Cost limit
+        limit: u64,
+    },
+    /// Error while building the context for the script evaluation
+    #[error("Transaction context error: {0}")]
+    TransactionContext(String),
+}
+
+impl TxValidationError {
+    /// Id of the corresponding validation rule of the node
+    pub fn rule_id(&self) -> u16 {
+        match self {
+            TxValidationError::OutputSumOverflow => 106,
+            TxValidationError::DuplicateInput(_) => 107,
+            TxValidationError::TooManyTokensInBox { .. } => 108,
+            TxValidationError::OutputTokenSumOverflow(_) => 108,
+            TxValidationError::Dust { .. } => 111,
+            TxValidationError::FutureCreationHeight { .. } => 112,
+            TxValidationError::InputBoxNotFound(_) => 113,
+            TxValidationError::DataInputBoxNotFound(_) => 114,
+            TxValidationError::InputSumOverflow => 115,
+            TxValidationError::ErgPreservation { .. } => 116,
+            TxValidationError::TokenPreservation { .. } => 117,
+            TxValidationError::InputTokenSumOverflow(_) => 117,
+            TxValidationError::InvalidNewTokenId { .. } => 117,
+            TxValidationError::TransactionContext(_) => 118,
+            TxValidationError::ScriptValidation { .. } => 119,
+            TxValidationError::CostLimitExceeded { .. } => 119,
+            TxValidationError::BoxSize { .. } => 120,
+            TxValidationError::OutputSerialization { .. } => 120,
+            TxValidationError::BoxPropositionSize { .. } => 121,
+            TxValidationError::MonotonicHeight { .. } => 124,
+        }
+    }
+}
+
+/// All the violated validation rules of a transaction
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TxValidationErrors(pub Vec<TxValidationError>);
+
+impl Display for TxValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Transaction validation failed:")?;
+        for e in &self.0 {
+            writeln!(f, "  rule {}: {}", e.rule_id(), e)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for TxValidationErrors {}
+
+impl Transaction {
+    /// Check the rules that do not require the blockchain state.
+    /// Returns all the violated rules.
+    pub fn validate_stateless(&self) -> Vec<TxValidationError> {
+        let mut errors = vec![];
+        let mut seen_inputs = HashSet::new();
+        for input in self.inputs.iter() {
+            if !seen_inputs.insert(input.box_id) {
+                errors.push(TxValidationError::DuplicateInput(input.box_id));
+            }
+        }
+        let outputs_sum = self
+            .outputs
+            .iter()
+            .try_fold(0u64, |acc, b| acc.checked_add(*b.value.as_u64()));
+        if outputs_sum.is_none() {
+            errors.push(TxValidationError::OutputSumOverflow);
+        }
+        if let Err(token_id) = sum_tokens(self.outputs.iter()) {
+            errors.push(TxValidationError::OutputTokenSumOverflow(token_id));
+        }
+        for (index, output) in self.outputs.iter().enumerate() {
+            let tokens_count = output.tokens.as_ref().map_or(0, |t| t.len());
+            if tokens_count > ErgoBox::MAX_TOKENS_COUNT {
+                errors.push(TxValidationError::TooManyTokensInBox {
+                    index,
+                    count: tokens_count,
+                    max: ErgoBox::MAX_TOKENS_COUNT,
+                });
+            }
+        }
+        errors
+    }
+
+    /// Validate the transaction against the node's stateless and stateful rules:
+    /// ERG and token preservation, new token issuance, output box size and min value,
+    /// creation height, duplicate inputs, tokens per box and script validity of every input.
+    /// `boxes_to_spend` and `data_boxes` are the boxes referenced by the transaction inputs
+    /// and data inputs.
+    /// Returns the total transaction cost (in block cost units) if all the rules are satisfied,
+    /// otherwise returns all the violated rules.
+    pub fn validate(
+        &self,
+        boxes_to_spend: Vec<ErgoBox>,
+        data_boxes: Vec<ErgoBox>,
+        state_context: &ErgoStateContext,
+    ) -> Result<u64, TxValidationErrors> {
+        let params = &state_context.parameters;
+        let height = state_context.pre_header.height;
+        let mut errors = self.validate_stateless();
+
+        let inputs: Vec<&ErgoBox> = self
+            .inputs
+            .iter()
+            .filter_map(|input| {
+                let found = boxes_to_spend.iter().find(|b| b.box_id() == input.box_id);
+                if found.is_none() {
+                    errors.push(TxValidationError::InputBoxNotFound(input.box_id));
+                }
+                found
+            })
+            .collect();
+        if let Some(data_inputs) = self.data_inputs.as_ref() {
+            for data_input in data_inputs.iter() {
+                if !data_boxes.iter().any(|b| b.box_id() == data_input.box_id) {
+                    errors.push(TxValidationError::DataInputBoxNotFound(data_input.box_id));
+                }
+            }
+        }
+
+        self.validate_outputs(params, height, &inputs, &mut errors);
+        validate_erg_preservation(&inputs, self.outputs.as_vec(), &mut errors);
+        let first_input_id = self.inputs.first().box_id;
+        validate_token_preservation(&inputs, self.outputs.as_vec(), first_input_id, &mut errors);
+
+        if !errors.is_empty() {
+            return Err(TxValidationErrors(errors));
+        }
+
+        let initial_cost = initial_tx_cost(self, &inputs, params);
+        let tx_context = TransactionContext::new(self.clone(), boxes_to_spend, data_boxes)
+            .map_err(|e| TxValidationErrors(vec![context_error(e)]))?;
+        if initial_cost > params.max_block_cost {
+            return Err(TxValidationErrors(vec![
+                TxValidationError::CostLimitExceeded {
+                    cost: initial_cost,
+                    limit: params.max_block_cost,
+                },
+            ]));
+        }
+        self.validate_scripts(&tx_context, state_context, initial_cost)
+    }
+
+    fn validate_outputs(
+        &self,
+        params: &Parameters,
+        height: u32,
+        inputs: &[&ErgoBox],
+        errors: &mut Vec<TxValidationError>,
+    ) {
+        let max_input_height = inputs.iter().map(|b| b.creation_height).max();
+        for (index, output) in self.outputs.iter().enumerate() {
+            match output.sigma_serialize_bytes() {
+                Ok(bytes) => {
+                    let size = bytes.len();
+                    if size > Parameters::MAX_BOX_SIZE {
+                        errors.push(TxValidationError::BoxSize {
+                            index,
+                            size,
+                            max: Parameters::MAX_BOX_SIZE,
+                        });
+                    }
+                    let min_value = size as u64 * params.min_value_per_byte as u64;
+                    if *output.value.as_u64() < min_value {
+                        errors.push(TxValidationError::Dust {
+                            index,
+                            value: *output.value.as_u64(),
+                            min_value,
+                        });
+                    }
+                }
+                Err(e) => errors.push(TxValidationError::OutputSerialization {
+                    index,
+                    reason: e.to_string(),
+                }),
+            }
+            if let Ok(tree_bytes) = output.ergo_tree.sigma_serialize_bytes() {
+                if tree_bytes.len() > Parameters::MAX_PROPOSITION_BYTES {
+                    errors.push(TxValidationError::BoxPropositionSize {
+                        index,
+                        size: tree_bytes.len(),
+                        max: Parameters::MAX_PROPOSITION_BYTES,
+                    });
+                }
+            }
+            if output.creation_height > height {
+                errors.push(TxValidationError::FutureCreationHeight {
+                    index,
+                    creation_height: output.creation_height,
+                    height,
+                });
+            }
+            if let Some(max_input_height) = max_input_height {
+                if params.block_version >= 3 && output.creation_height < max_input_height {
+                    errors.push(TxValidationError::MonotonicHeight {
+                        index,
+                        creation_height: output.creation_height,
+                        max_input_height,
+                    });
+                }
+            }
+        }
+    }
+
+    fn validate_scripts(
+        &self,
+        tx_context: &TransactionContext<Transaction>,
+        state_context: &ErgoStateContext,
+        initial_cost: u64,
+    ) -> Result<u64, TxValidationErrors> {
+        let max_cost = state_context.parameters.max_block_cost;
+        let message = self.bytes_to_sign().map_err(|e| {
+            TxValidationErrors(vec![TxValidationError::TransactionContext(e.to_string())])
+        })?;
+        let mut errors = vec![];
+        let mut accumulated_cost = initial_cost;
+        for (index, input) in self.inputs.iter().enumerate() {
+            let script_error =
+                |reason: String| TxValidationError::ScriptValidation { index, reason };
+            let ctx = match make_context(state_context, tx_context, index) {
+                Ok(ctx) => Arc::new(ctx),
+                Err(e) => {
+                    errors.push(TxValidationError::TransactionContext(e.to_string()));
+                    continue;
+                }
+            };
+            #[allow(clippy::unwrap_used)]
+            // input box presence is checked in TransactionContext::new
+            let input_box = tx_context.get_input_box(&input.box_id).unwrap();
+            let expr = match input_box.ergo_tree.proposition() {
+                Ok(expr) => expr,
+                Err(e) => {
+                    errors.push(script_error(e.to_string()));
+                    continue;
+                }
+            };
+            let reduction = match reduce_to_crypto_with_cost(
+                &expr,
+                &Env::empty(),
+                ctx,
+                accumulated_cost,
+                Some(max_cost),
+            ) {
+                Ok(reduction) => reduction,
+                Err(e) => {
+                    errors.push(script_error(e.to_string()));
+                    continue;
+                }
+            };
+            accumulated_cost =
+                reduction
+                    .cost
+                    .saturating_add(Cost::to_block_cost(Costs::sigma_verify_cost(
+                        &reduction.sigma_prop,
+                    )));
+            if accumulated_cost > max_cost {
+                errors.push(TxValidationError::CostLimitExceeded {
+                    cost: accumulated_cost,
+                    limit: max_cost,
+                });
+                break;
+            }
+            if reduction.sigma_prop == SigmaBoolean::TrivialProp(false) {
+                errors.push(script_error(format!(
+                    "script reduced to false\n{}",
+                    reduction.diag
+                )));
+                continue;
+            }
+            match verify_signature(
+                reduction.sigma_prop,
+                message.as_slice(),
+                input.spending_proof.proof.as_ref(),
+            ) {
+                Ok(true) => (),
+                Ok(false) => errors.push(script_error("invalid proof".to_string())),
+                Err(e) => errors.push(script_error(e.to_string())),
+            }
+        }
+        if errors.is_empty() {
+            Ok(accumulated_cost)
+        } else {
+            Err(TxValidationErrors(errors))
+        }
+    }
+}
+
+/// Cost of the transaction validation before the scripts are evaluated
+/// (interpreter initialization, inputs, data inputs, outputs and token access).
+/// Token access is charged per token of every input and output box and per distinct token
+/// of the inputs and of the outputs, as the reference node does.
+pub(crate) fn initial_tx_cost<T: ErgoTransaction>(
+    tx: &T,
+    inputs: &[&ErgoBox],
+    params: &Parameters,
+) -> u64 {
+    let inputs_count = tx.inputs_ids().len() as u64;
+    let data_inputs_count = tx.data_inputs().as_ref().map_or(0, |d| d.len()) as u64;
+    let outputs = tx.outputs();
+    let outputs_count = outputs.len() as u64;
+    let token_accesses = inputs
+        .iter()
+        .copied()
+        .chain(outputs.iter())
+        .map(|b| b.tokens.as_ref().map_or(0, |t| t.len()) as u64)
+        .sum::<u64>()
+        + distinct_tokens_count(inputs.iter().copied())
+        + distinct_tokens_count(outputs.iter());
+    Costs::DEFAULT
+        .tx_base_cost
+        .saturating_add(inputs_count.saturating_mul(params.input_cost))
+        .saturating_add(data_inputs_count.saturating_mul(params.data_input_cost))
+        .saturating_add(outputs_count.saturating_mul(params.output_cost))
+        .saturating_add(token_accesses.saturating_mul(params.token_access_cost))
+}
+
+fn context_error(e: TransactionContextError) -> TxValidationError {
+    TxValidationError::TransactionContext(e.to_string())
+}
+
+fn validate_erg_preservation(
+    inputs: &[&ErgoBox],
+    outputs: &[ErgoBox],
+    errors: &mut Vec<TxValidationError>,
+) {
+    let inputs_sum = inputs
+        .iter()
+        .try_fold(0u64, |acc, b| acc.checked_add(*b.value.as_u64()));
+    let outputs_sum = outputs
+        .iter()
+        .try_fold(0u64, |acc, b| acc.checked_add(*b.value.as_u64()));
+    match (inputs_sum, outputs_sum) {
+        (None, _) => errors.push(TxValidationError::InputSumOverflow),
+        (Some(inputs), Some(outputs)) if inputs != outputs => {
+            errors.push(TxValidationError::ErgPreservation { inputs, outputs })
+        }
+        // output sum overflow is reported by the stateless check
+        _ => (),
+    }
+}
+
+fn distinct_tokens_count<'a, I: Iterator<Item = &'a ErgoBox>>(boxes: I) -> u64 {
+    boxes
+        .flat_map(|b| b.tokens.iter().flatten().map(|t| t.token_id))
+        .collect::<HashSet<TokenId>>()
+        .len() as u64
+}
+
+/// Total amount of every token in the boxes.
+/// Returns the id of the token whose total amount exceeds the maximal token amount.
+fn sum_tokens<'a, I: Iterator<Item = &'a ErgoBox>>(
+    boxes: I,
+) -> Result<HashMap<TokenId, TokenAmount>, TokenId> {
+    let mut res: HashMap<TokenId, TokenAmount> = HashMap::new();
+    for b in boxes {
+        for token in b.tokens.iter().flatten() {
+            let amount = match res.get(&token.token_id) {
+                Some(amount) => amount
+                    .checked_add(&token.amount)
+                    .map_err(|_| token.token_id)?,
+                None => token.amount,
+            };
+            res.insert(token.token_id, amount);
+        }
+    }
+    Ok(res)
+}
+
+fn validate_token_preservation(
+    inputs: &[&ErgoBox],
+    outputs: &[ErgoBox],
+    first_input_id: BoxId,
+    errors: &mut Vec<TxValidationError>,
+) {
+    let new_token_id: TokenId = first_input_id.into();
+    let in_tokens = match sum_tokens(inputs.iter().copied()) {
+        Ok(tokens) => tokens,
+        Err(token_id) => {
+            errors.push(TxValidationError::InputTokenSumOverflow(token_id));
+            return;
+        }
+    };
+    let out_tokens = match sum_tokens(outputs.iter()) {
+        Ok(tokens) => tokens,
+        // reported by the stateless check
+        Err(_) => return,
+    };
+    let mut out_token_ids: Vec<&TokenId> = out_tokens.keys().collect();
+    // report violations in a deterministic order
+    out_token_ids.sort_by_key(|id| String::from(**id));
+    for token_id in out_token_ids {
+        let out_amount = out_tokens.get(token_id).map_or(0, |a| *a.as_u64());
+        match in_tokens.get(token_id).map(|a| *a.as_u64()) {
+            Some(in_amount) if in_amount >= out_amount => (),
+            Some(in_amount) => errors.push(TxValidationError::TokenPreservation {
+                token_id: *token_id,
+                inputs: in_amount,
+                outputs: out_amount,
+            }),
+            None if *token_id == new_token_id => (),
+            None => errors.push(TxValidationError::InvalidNewTokenId {
+                token_id: *token_id,
+                expected: new_token_id,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::collections::HashMap;
+    use std::convert::TryInto;
+
+    use ergo_chain_types::ec_point::generator;
+    use ergo_chain_types::ADDigest;
+    use ergo_chain_types::AutolykosSolution;
+    use ergo_chain_types::BlockId;
+    use ergo_chain_types::Digest;
+    use ergo_chain_types::Digest32;
+    use ergo_chain_types::Header;
+    use ergo_chain_types::PreHeader;
+    use ergo_chain_types::Votes;
+    use ergotree_interpreter::sigma_protocol::private_input::DlogProverInput;
+    use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
+    use ergotree_interpreter::sigma_protocol::prover::ProofBytes;
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::ergo_box::BoxTokens;
+    use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisterId;
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+    use ergotree_ir::chain::token::Token;
+    use ergotree_ir::chain::tx_id::TxId;
+    use ergotree_ir::ergo_tree::ErgoTree;
+    use ergotree_ir::mir::bin_op::BinOp;
+    use ergotree_ir::mir::bin_op::RelationOp;
+    use ergotree_ir::mir::bool_to_sigma::BoolToSigmaProp;
+    use ergotree_ir::mir::expr::Expr;
+    use ergotree_ir::mir::global_vars::GlobalVars;
+
+    use crate::chain::transaction::input::Input;
+    use crate::chain::transaction::input::UnsignedInput;
+
+    use super::*;
+
+    const HEIGHT: u32 = 1000;
+
+    fn true_tree() -> ErgoTree {
+        ErgoTree::try_from(Expr::Const(SigmaBoolean::TrivialProp(true).into())).unwrap()
+    }
+
+    /// `sigmaProp(HEIGHT > height)`
+    fn height_above_tree(height: i32) -> ErgoTree {
+        let expr: Expr = BoolToSigmaProp {
+            input: Box::new(
+                BinOp {
+                    kind: RelationOp::Gt.into(),
+                    left: Box::new(GlobalVars::Height.into()),
+                    right: Box::new(height.into()),
+                }
+                .into(),
+            ),
+        }
+        .into();
+        ErgoTree::try_from(expr).unwrap()
+    }
+
+    fn token(id_byte: u8, amount: u64) -> Token {
+        Token {
+            token_id: Digest32::from(Digest([id_byte; 32])).into(),
+            amount: amount.try_into().unwrap(),
+        }
+    }
+
+    fn make_box(
+        ergo_tree: ErgoTree,
+        value: u64,
+        tokens: Vec<Token>,
+        creation_height: u32,
+        index: u16,
+    ) -> ErgoBox {
+        ErgoBox::new(
+            value.try_into().unwrap(),
+            ergo_tree,
+            tokens.try_into().ok(),
+            NonMandatoryRegisters::empty(),
+            creation_height,
+            TxId::zero(),
+            index,
+        )
+        .unwrap()
+    }
+
+    fn input_box(value: u64, tokens: Vec<Token>) -> ErgoBox {
+        make_box(true_tree(), value, tokens, 0, 0)
+    }
+
+    fn output(value: u64, tokens: Vec<Token>) -> ErgoBoxCandidate {
+        ErgoBoxCandidate {
+            value: value.try_into().unwrap(),
+            ergo_tree: true_tree(),
+            tokens: tokens.try_into().ok(),
+            additional_registers: NonMandatoryRegisters::empty(),
+            creation_height: 0,
+        }
+    }
+
+    fn spend_with_proofs(
+        inputs: &[ErgoBox],
+        proof: ProofBytes,
+        outputs: Vec<ErgoBoxCandidate>,
+    ) -> Transaction {
+        let inputs = inputs
+            .iter()
+            .map(|b| {
+                Input::from_unsigned_input(
+                    UnsignedInput::new(b.box_id(), ContextExtension::empty()),
+                    proof.clone(),
+                )
+            })
+            .collect();
+        Transaction::new_from_vec(inputs, vec![], outputs).unwrap()
+    }
+
+    fn spend(inputs: &[ErgoBox], outputs: Vec<ErgoBoxCandidate>) -> Transaction {
+        spend_with_proofs(inputs, ProofBytes::Empty, outputs)
+    }
+
+    /// State context of the block at the given height with the mainnet parameters
+    fn state_context(height: u32) -> ErgoStateContext {
+        let headers: Vec<Header> = (1..=10)
+            .map(|i| Header {
+                version: 2,
+                id: BlockId(Digest([i as u8; 32])),
+                parent_id: BlockId(Digest([i as u8 + 1; 32])),
+                ad_proofs_root: Digest32::zero(),
+                state_root: ADDigest::zero(),
+                transaction_root: Digest32::zero(),
+                timestamp: 0,
+                n_bits: 0,
+                height: height - i,
+                extension_root: Digest32::zero(),
+                autolykos_solution: AutolykosSolution {
+                    miner_pk: generator().into(),
+                    pow_onetime_pk: None,
+                    nonce: vec![0; 8],
+                    pow_distance: None,
+                },
+                votes: Votes([0; 3]),
+            })
+            .collect();
+        let pre_header = PreHeader {
+            version: 2,
+            parent_id: headers[0].id,
+            timestamp: 0,
+            n_bits: 0,
+            height,
+            miner_pk: generator().into(),
+            votes: Votes([0; 3]),
+        };
+        ErgoStateContext::new(pre_header, headers.try_into().unwrap())
+    }
+
+    /// Errors of the validation of the transaction spending the given boxes at [`HEIGHT`]
+    fn validation_errors(tx: &Transaction, inputs: Vec<ErgoBox>) -> Vec<TxValidationError> {
+        tx.validate(inputs, vec![], &state_context(HEIGHT))
+            .unwrap_err()
+            .0
+    }
+
+    #[test]
+    fn valid_tx() {
+        let value = *BoxValue::SAFE_USER_MIN.as_u64() * 2;
+        let input = input_box(value, vec![]);
+        let tx = spend(std::slice::from_ref(&input), vec![output(value, vec![])]);
+        let params = Parameters::default();
+        let cost = tx
+            .validate(vec![input.clone()], vec![], &state_context(HEIGHT))
+            .unwrap();
+        assert!(cost >= initial_tx_cost(&tx, &[&input], &params));
+    }
+
+    #[test]
+    fn duplicate_inputs() {
+        let value = *BoxValue::SAFE_USER_MIN.as_u64();
+        let input = input_box(value, vec![]);
+        let tx = spend(
+            &[input.clone(), input.clone()],
+            vec![output(value * 2, vec![])],
+        );
+        assert_eq!(
+            tx.validate_stateless(),
+            vec![TxValidationError::DuplicateInput(input.box_id())]
+        );
+    }
+
+    #[test]
+    fn dust_output() {
+        let value = BoxValue::MIN_RAW;
+        let input = input_box(value, vec![]);
+        let tx = spend(std::slice::from_ref(&input), vec![output(value, vec![])]);
+        let size = tx.outputs.first().sigma_serialize_bytes().unwrap().len() as u64;
+        let errors = validation_errors(&tx, vec![input]);
+        assert_eq!(
+            errors,
+            vec![TxValidationError::Dust {
+                index: 0,
+                value,
+                min_value: size * BoxValue::MIN_VALUE_PER_BOX_BYTE as u64,
+            }]
+        );
+        assert_eq!(errors[0].rule_id(), 111);
+    }
+
+    #[test]
+    fn too_many_tokens_in_box() {
+        // a box with more tokens than allowed by the node cannot be built or parsed,
+        // the maximum is accepted
+        let tokens: Vec<Token> = (0..=ErgoBox::MAX_TOKENS_COUNT)
+            .map(|i| token(i as u8, 1))
+            .collect();
+        assert!(BoxTokens::from_vec(tokens.clone()).is_err());
+        let tokens = tokens[..ErgoBox::MAX_TOKENS_COUNT].to_vec();
+        let value = *BoxValue::SAFE_USER_MIN.as_u64() * 10;
+        let input = input_box(value, tokens.clone());
+        let tx = spend(std::slice::from_ref(&input), vec![output(value, tokens)]);
+        assert_eq!(tx.validate_stateless(), vec![]);
+        assert!(tx
+            .validate(vec![input], vec![], &state_context(HEIGHT))
+            .is_ok());
+    }
+
+    #[test]
+    fn future_creation_height() {
+        let value = *BoxValue::SAFE_USER_MIN.as_u64();
+        let input = input_box(value, vec![]);
+        let mut out = output(value, vec![]);
+        out.creation_height = HEIGHT + 1;
+        let tx = spend(std::slice::from_ref(&input), vec![out]);
+        let errors = validation_errors(&tx, vec![input]);
+        assert_eq!(
+            errors,
+            vec![TxValidationError::FutureCreationHeight {
+                index: 0,
+                creation_height: HEIGHT + 1,
+                height: HEIGHT,
+            }]
+        );
+        assert_eq!(errors[0].rule_id(), 112);
+    }
+
+    #[test]
+    fn box_size() {
+        let value = *BoxValue::SAFE_USER_MIN.as_u64() * 10;
+        let input = input_box(value, vec![]);
+        let mut out = output(value, vec![]);
+        let mut regs = HashMap::new();
+        regs.insert(NonMandatoryRegisterId::R4, vec![0u8; 4096].into());
+        out.additional_registers = NonMandatoryRegisters::new(regs).unwrap();
+        let tx = spend(std::slice::from_ref(&input), vec![out]);
+        let size = tx.outputs.first().sigma_serialize_bytes().unwrap().len();
+        let errors = validation_errors(&tx, vec![input]);
+        assert_eq!(
+            errors,
+            vec![TxValidationError::BoxSize {
+                index: 0,
+                size,
+                max: Parameters::MAX_BOX_SIZE,
+            }]
+        );
+        assert_eq!(errors[0].rule_id(), 120);
+    }
+
+    #[test]
+    fn monotonic_height() {
+        let value = *BoxValue::SAFE_USER_MIN.as_u64();
+        let input = make_box(true_tree(), value, vec![], HEIGHT - 10, 0);
+        let mut out = output(value, vec![]);
+        out.creation_height = HEIGHT - 20;
+        let tx = spend(std::slice::from_ref(&input), vec![out]);
+        let errors = validation_errors(&tx, vec![input]);
+        assert_eq!(
+            errors,
+            vec![TxValidationError::MonotonicHeight {
+                index: 0,
+                creation_height: HEIGHT - 20,
+                max_input_height: HEIGHT - 10,
+            }]
+        );
+        assert_eq!(errors[0].rule_id(), 124);
+    }
+
+    #[test]
+    fn erg_not_preserved() {
+        let value = *BoxValue::SAFE_USER_MIN.as_u64();
+        let input = input_box(value * 2, vec![]);
+        let tx = spend(std::slice::from_ref(&input), vec![output(value, vec![])]);
+        let errors = validation_errors(&tx, vec![input]);
+        assert_eq!(
+            errors,
+            vec![TxValidationError::ErgPreservation {
+                inputs: value * 2,
+                outputs: value,
+            }]
+        );
+        assert_eq!(errors[0].rule_id(), 116);
+    }
+
+    #[test]
+    fn token_not_preserved() {
+        let value = *BoxValue::SAFE_USER_MIN.as_u64();
+        let input = input_box(value, vec![token(1, 100)]);
+        let tx = spend(
+            std::slice::from_ref(&input),
+            vec![output(value, vec![token(1, 101)])],
+        );
+        let errors = validation_errors(&tx, vec![input]);
+        assert_eq!(
+            errors,
+            vec![TxValidationError::TokenPreservation {
+                token_id: token(1, 1).token_id,
+                inputs: 100,
+                outputs: 101,
+            }]
+        );
+        assert_eq!(errors[0].rule_id(), 117);
+    }
+
+    #[test]
+    fn new_token_id() {
+        let value = *BoxValue::SAFE_USER_MIN.as_u64();
+        let input = input_box(value, vec![]);
+        let minted = Token {
+            token_id: input.box_id().into(),
+            amount: 100.try_into().unwrap(),
+        };
+        let tx = spend(
+            std::slice::from_ref(&input),
+            vec![output(value, vec![minted])],
+        );
+        assert!(tx
+            .validate(vec![input.clone()], vec![], &state_context(HEIGHT))
+            .is_ok());
+
+        let foreign = token(1, 100);
+        let tx = spend(
+            std::slice::from_ref(&input),
+            vec![output(value, vec![foreign.clone()])],
+        );
+        assert_eq!(
+            validation_errors(&tx, vec![input.clone()]),
+            vec![TxValidationError::InvalidNewTokenId {
+                token_id: foreign.token_id,
+                expected: input.box_id().into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn token_sum_overflow() {
+        let value = *BoxValue::SAFE_USER_MIN.as_u64();
+        let max_token = token(1, TokenAmount::MAX_RAW);
+        let inputs = vec![
+            make_box(true_tree(), value, vec![max_token.clone()], 0, 0),
+            make_box(true_tree(), value, vec![max_token.clone()], 0, 1),
+        ];
+        let tx = spend(&inputs, vec![output(value * 2, vec![max_token.clone()])]);
+        assert_eq!(
+            validation_errors(&tx, inputs),
+            vec![TxValidationError::InputTokenSumOverflow(max_token.token_id)]
+        );
+
+        let input = input_box(value * 2, vec![max_token.clone()]);
+        let tx = spend(
+            std::slice::from_ref(&input),
+            vec![
+                output(value, vec![max_token.clone()]),
+                output(value, vec![max_token.clone()]),
+            ],
+        );
+        assert_eq!(
+            tx.validate_stateless(),
+            vec![TxValidationError::OutputTokenSumOverflow(
+                max_token.token_id
+            )]
+        );
+    }
+
+    #[test]
+    fn cost_limit_exceeded() {
+        let value = *BoxValue::SAFE_USER_MIN.as_u64();
+        let input = input_box(value, vec![]);
+        let tx = spend(std::slice::from_ref(&input), vec![output(value, vec![])]);
+        let initial_cost = initial_tx_cost(&tx, &[&input], &Parameters::default());
+        let params = Parameters {
+            max_block_cost: initial_cost - 1,
+            ..Parameters::default()
+        };
+        let state_context = state_context(HEIGHT).with_parameters(params);
+        let errors = tx
+            .validate(vec![input], vec![], &state_context)
+            .unwrap_err()
+            .0;
+        assert_eq!(
+            errors,
+            vec![TxValidationError::CostLimitExceeded {
+                cost: initial_cost,
+                limit: initial_cost - 1,
+            }]
+        );
+        assert_eq!(errors[0].rule_id(), 119);
+    }
+
+    #[test]
+    fn script_reduced_to_false() {
+        let value = *BoxValue::SAFE_USER_MIN.as_u64();
+        let input = make_box(height_above_tree(HEIGHT as i32), value, vec![], 0, 0);
+        let tx = spend(std::slice::from_ref(&input), vec![output(value, vec![])]);
+        let errors = validation_errors(&tx, vec![input.clone()]);
+        assert!(matches!(
+            errors.as_slice(),
+            [TxValidationError::ScriptValidation { index: 0, reason }]
+                if reason.starts_with("script reduced to false")
+        ));
+        assert_eq!(errors[0].rule_id(), 119);
+        // the same script is satisfied a block later
+        assert!(tx
+            .validate(vec![input], vec![], &state_context(HEIGHT + 1))
+            .is_ok());
+    }
+
+    #[test]
+    fn invalid_proof() {
+        let secret = DlogProverInput::from_bytes(&[1u8; DlogProverInput::SIZE_BYTES]).unwrap();
+        let pk_tree = ErgoTree::try_from(Expr::Const(
+            SigmaBoolean::from(secret.public_image()).into(),
+        ))
+        .unwrap();
+        let value = *BoxValue::SAFE_USER_MIN.as_u64();
+        let input = make_box(pk_tree, value, vec![], 0, 0);
+        // challenge (24 bytes) and response (32 bytes) of a Schnorr proof
+        let tx = spend_with_proofs(
+            std::slice::from_ref(&input),
+            ProofBytes::Some(vec![1u8; 56]),
+            vec![output(value, vec![])],
+        );
+        assert_eq!(
+            validation_errors(&tx, vec![input]),
+            vec![TxValidationError::ScriptValidation {
+                index: 0,
+                reason: "invalid proof".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn missing_input_box() {
+        let value = *BoxValue::SAFE_USER_MIN.as_u64();
+        let input = input_box(value, vec![]);
+        let tx = spend(std::slice::from_ref(&input), vec![output(value, vec![])]);
+        let errors = tx
+            .validate(vec![], vec![], &state_context(HEIGHT))
+            .unwrap_err();
+        assert!(errors
+            .0
+            .contains(&TxValidationError::InputBoxNotFound(input.box_id())));
+    }
+}
@@ -4,6 +4,7 @@ pub mod box_selector;
 pub mod derivation_path;
 pub mod ext_pub_key;
 pub mod ext_secret_key;
+pub mod fee;
 pub mod miner_fee;
 pub mod mnemonic;
 #[cfg(feature = "mnemonic_gen")]
@@ -11,7 +12,9 @@ pub mod mnemonic_generator;
 pub mod multi_sig;
 pub mod secret_key;
 pub mod signing;
+pub mod state;
 pub mod tx_builder;
+pub mod tx_chain;
 pub mod tx_context;
 
 use ergotree_interpreter::sigma_protocol::private_input::PrivateInput;
@@ -39,6 +42,8 @@ use self::signing::sign_message;
 use self::signing::sign_reduced_transaction;
 use self::signing::sign_tx_input;
 use self::signing::TransactionContext;
+use self::tx_chain::sign_tx_chain;
+use self::tx_chain::TxChainError;
 
 /// Wallet
 pub struct Wallet {
@@ -60,6 +65,9 @@ pub enum WalletError {
 
     #[error("error parsing SecretKey from ExtSecretKey.bytes")]
     SecretKeyParsingError,
+
+    #[error("Transaction chain error: {0}")]
+    TxChainError(#[from] TxChainError),
 }
 
 impl Wallet {
@@ -100,6 +108,16 @@ impl Wallet {
             .map_err(WalletError::from)
     }
 
+    /// Signs a chain of transactions (built with [`tx_chain::TxChainBuilder`]), where later
+    /// transactions spend the outputs of the earlier ones
+    pub fn sign_tx_chain(
+        &self,
+        tx_chain: Vec<TransactionContext<UnsignedTransaction>>,
+        state_context: &ErgoStateContext,
+    ) -> Result<Vec<Transaction>, WalletError> {
+        sign_tx_chain(self.prover.as_ref(), tx_chain, state_context).map_err(WalletError::from)
+    }
+
     /// Signs a reduced transaction (generating proofs for inputs)
     pub fn sign_reduced_transaction(
         &self,
@@ -1,10 +1,18 @@
 //! Box selection for transaction inputs
 
+mod branch_and_bound;
+mod change;
+mod greedy;
+mod largest_first;
+mod random;
 mod simple;
+mod token_aware;
 
 use std::collections::HashMap;
 
 use bounded_vec::BoundedVec;
+pub use branch_and_bound::*;
+pub use change::*;
 use ergotree_ir::chain::ergo_box::box_value::BoxValue;
 use ergotree_ir::chain::ergo_box::BoxId;
 use ergotree_ir::chain::ergo_box::BoxTokens;
@@ -14,7 +22,10 @@ use ergotree_ir::chain::token::Token;
 use ergotree_ir::chain::token::TokenAmount;
 use ergotree_ir::chain::token::TokenAmountError;
 use ergotree_ir::chain::token::TokenId;
+pub use largest_first::*;
+pub use random::*;
 pub use simple::*;
+pub use token_aware::*;
 
 use thiserror::Error;
 
@@ -294,17 +305,54 @@ pub mod arbitrary {
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::panic)]
 mod tests {
+    use std::convert::TryFrom;
+    use std::convert::TryInto;
 
+    use ergo_chain_types::Digest32;
+    use ergotree_ir::chain::ergo_box::box_value::checked_sum;
     use ergotree_ir::chain::ergo_box::box_value::BoxValue;
     use ergotree_ir::chain::ergo_box::BoxTokens;
     use ergotree_ir::chain::token::Token;
+    use ergotree_ir::chain::token::TokenAmount;
+    use ergotree_ir::chain::token::TokenId;
+    use proptest::collection::vec;
     use proptest::prelude::*;
     use sigma_test_util::force_any_val;
 
+    use crate::wallet::box_selector::simple::check_input_preservation;
     use crate::wallet::box_selector::sum_tokens;
     use crate::wallet::box_selector::sum_tokens_from_boxes;
 
-    use super::ErgoBoxAssetsData;
+    use super::*;
+
+    /// Box assets with the given value (in nanoERGs) and tokens
+    pub(super) fn assets(value: u64, tokens: Vec<Token>) -> ErgoBoxAssetsData {
+        ErgoBoxAssetsData {
+            value: BoxValue::try_from(value).unwrap(),
+            tokens: BoxTokens::from_vec(tokens).ok(),
+        }
+    }
+
+    pub(super) fn token(token_id: TokenId, amount: u64) -> Token {
+        Token {
+            token_id,
+            amount: amount.try_into().unwrap(),
+        }
+    }
+
+    /// All box selectors (the random one with the given seed)
+    fn selectors(seed: u64) -> Vec<(&'static str, Box<dyn BoxSelector<ErgoBoxAssetsData>>)> {
+        vec![
+            ("simple", Box::new(SimpleBoxSelector::new())),
+            ("largest first", Box::new(LargestFirstBoxSelector::new())),
+            (
+                "branch and bound",
+                Box::new(BranchAndBoundBoxSelector::new()),
+            ),
+            ("token aware", Box::new(TokenAwareBoxSelector::new())),
+            ("random", Box::new(RandomBoxSelector::with_seed(seed))),
+        ]
+    }
 
     #[test]
     fn test_sum_tokens_repeating_token_id() {
@@ -324,11 +372,161 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_change_below_min_box_value() {
+        let min = *BoxValue::SAFE_USER_MIN.as_u64();
+        for (name, selector) in selectors(0) {
+            let r = selector.select(
+                vec![assets(min * 2, vec![])],
+                BoxValue::try_from(min * 2 - 1).unwrap(),
+                &[],
+            );
+            assert!(
+                matches!(r, Err(BoxSelectorError::NotEnoughCoinsForChangeBox(_))),
+                "{}: {:?}",
+                name,
+                r
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_preservation_value_mismatch() {
+        let min = *BoxValue::SAFE_USER_MIN.as_u64();
+        let selected = vec![assets(min * 3, vec![])];
+        assert!(check_input_preservation(
+            selected.as_slice(),
+            &[assets(min, vec![])],
+            BoxValue::try_from(min * 2).unwrap(),
+            &[]
+        )
+        .is_ok());
+        // one nanoERG is lost
+        assert!(check_input_preservation(
+            selected.as_slice(),
+            &[assets(min - 1, vec![])],
+            BoxValue::try_from(min * 2).unwrap(),
+            &[]
+        )
+        .is_err());
+        // one nanoERG is created
+        assert!(check_input_preservation(
+            selected.as_slice(),
+            &[assets(min + 1, vec![])],
+            BoxValue::try_from(min * 2).unwrap(),
+            &[]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_check_preservation_token_mismatch() {
+        let min = *BoxValue::SAFE_USER_MIN.as_u64();
+        let token_id = TokenId::from(Digest32::from([1u8; 32]));
+        let other_id = TokenId::from(Digest32::from([2u8; 32]));
+        let selected = vec![
+            assets(min * 2, vec![token(token_id, 5)]),
+            assets(min, vec![token(token_id, 5)]),
+        ];
+        let target = BoxValue::try_from(min * 2).unwrap();
+        assert!(check_input_preservation(
+            selected.as_slice(),
+            &[assets(min, vec![token(token_id, 7)])],
+            target,
+            &[token(token_id, 3)]
+        )
+        .is_ok());
+        // the change tokens are lost
+        assert!(check_input_preservation(
+            selected.as_slice(),
+            &[assets(min, vec![])],
+            target,
+            &[token(token_id, 3)]
+        )
+        .is_err());
+        // the target token is not among the selected boxes
+        assert!(check_input_preservation(
+            selected.as_slice(),
+            &[assets(min, vec![token(token_id, 10)])],
+            target,
+            &[token(other_id, 1)]
+        )
+        .is_err());
+        // the change box holds the token with the same amount but another id
+        assert!(check_input_preservation(
+            selected.as_slice(),
+            &[assets(min, vec![token(other_id, 7)])],
+            target,
+            &[token(token_id, 3)]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_check_preservation_token_amount_overflow() {
+        let min = *BoxValue::SAFE_USER_MIN.as_u64();
+        let max_amount = token(force_any_val::<TokenId>(), TokenAmount::MAX_RAW);
+        let selected = vec![
+            assets(min, vec![max_amount.clone()]),
+            assets(min, vec![max_amount.clone()]),
+        ];
+        let r = check_input_preservation(
+            selected.as_slice(),
+            &[],
+            BoxValue::try_from(min * 2).unwrap(),
+            &[max_amount.clone(), max_amount],
+        );
+        assert!(format!("{:?}", r.unwrap_err()).contains("TokenAmountError"));
+    }
+
     proptest! {
 
         #[test]
         fn sum_tokens_eq(b in any::<ErgoBoxAssetsData>()) {
             prop_assert_eq!(sum_tokens(b.tokens.as_ref().map(BoxTokens::as_ref)), sum_tokens_from_boxes(vec![b].as_slice()))
         }
+
+        #[test]
+        fn test_select_preserves_assets(inputs in
+                                        vec(any_with::<ErgoBoxAssetsData>(
+                                            (BoxValue::MIN_RAW * 1000 .. BoxValue::MIN_RAW * 10000).into()), 1..10),
+                                        seed in any::<u64>()) {
+            let all_inputs_val = checked_sum(inputs.iter().map(|b| b.value)).unwrap();
+            let target_balance = BoxValue::try_from(all_inputs_val.as_u64() / 2).unwrap();
+            let target_tokens: Vec<Token> = inputs[0].tokens.iter().flatten().take(1).cloned().collect();
+            for (name, selector) in selectors(seed) {
+                let selection = selector
+                    .select(inputs.clone(), target_balance, target_tokens.as_slice())
+                    .unwrap();
+                prop_assert_eq!(
+                    check_input_preservation(
+                        selection.boxes.as_slice(),
+                        selection.change_boxes.as_slice(),
+                        target_balance,
+                        target_tokens.as_slice()
+                    ),
+                    Ok(()),
+                    "{}",
+                    name
+                );
+            }
+        }
+
+        #[test]
+        fn test_select_not_enough_value(inputs in
+                                        vec(any_with::<ErgoBoxAssetsData>(
+                                            (BoxValue::MIN_RAW * 1000 .. BoxValue::MIN_RAW * 10000).into()), 1..10),
+                                        seed in any::<u64>()) {
+            let all_inputs_val = checked_sum(inputs.iter().map(|b| b.value)).unwrap();
+            let balance_too_much = all_inputs_val.checked_add(&BoxValue::SAFE_USER_MIN).unwrap();
+            for (name, selector) in selectors(seed) {
+                prop_assert_eq!(
+                    selector.select(inputs.clone(), balance_too_much, &[]),
+                    Err(BoxSelectorError::NotEnoughCoins(*BoxValue::SAFE_USER_MIN.as_u64())),
+                    "{}",
+                    name
+                );
+            }
+        }
     }
 }
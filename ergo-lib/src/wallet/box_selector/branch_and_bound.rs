@@ -0,0 +1,269 @@
+//! Branch-and-bound box selector, looks for the inputs matching the target exactly (no change)
+
+use std::collections::HashMap;
+
+use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+use ergotree_ir::chain::token::Token;
+use ergotree_ir::chain::token::TokenId;
+
+use super::greedy::selection_with_change;
+use super::greedy::SelectionTarget;
+use super::BoxSelection;
+use super::BoxSelector;
+use super::BoxSelectorError;
+use super::ErgoBoxAssets;
+use super::LargestFirstBoxSelector;
+
+/// Box selector searching (depth-first, larger boxes first) for a set of inputs with exactly the
+/// target value and tokens, so that no change box is needed. Falls back to
+/// [`LargestFirstBoxSelector`] if there is no such set or it's not found in `max_tries` steps.
+pub struct BranchAndBoundBoxSelector {
+    max_tries: usize,
+}
+
+impl BranchAndBoundBoxSelector {
+    /// Default limit of the search steps
+    pub const DEFAULT_MAX_TRIES: usize = 100_000;
+
+    /// Create new instance with [`Self::DEFAULT_MAX_TRIES`]
+    pub fn new() -> Self {
+        BranchAndBoundBoxSelector {
+            max_tries: Self::DEFAULT_MAX_TRIES,
+        }
+    }
+
+    /// Create new instance with the given limit of the search steps
+    pub fn with_max_tries(max_tries: usize) -> Self {
+        BranchAndBoundBoxSelector { max_tries }
+    }
+}
+
+impl Default for BranchAndBoundBoxSelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ErgoBoxAssets> BoxSelector<T> for BranchAndBoundBoxSelector {
+    fn select(
+        &self,
+        inputs: Vec<T>,
+        target_balance: BoxValue,
+        target_tokens: &[Token],
+    ) -> Result<BoxSelection<T>, BoxSelectorError> {
+        let target = SelectionTarget::new(target_balance, target_tokens)?;
+        // only the boxes without other tokens can be a part of an exact match
+        let mut candidates: Vec<Candidate> = inputs
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| {
+                b.tokens()
+                    .into_iter()
+                    .flatten()
+                    .all(|t| target.tokens.contains_key(&t.token_id))
+            })
+            .map(|(index, b)| Candidate {
+                index,
+                value: *b.value().as_u64(),
+                tokens: b
+                    .tokens()
+                    .into_iter()
+                    .flatten()
+                    .map(|t| (t.token_id, u64::from(t.amount)))
+                    .collect(),
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.value.cmp(&a.value));
+        match exact_match(&candidates, &target, self.max_tries) {
+            Some(mut indices) => {
+                indices.sort_unstable();
+                let selected = inputs
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(i, _)| indices.binary_search(i).is_ok())
+                    .map(|(_, b)| b)
+                    .collect();
+                selection_with_change(selected, target_balance, target_tokens)
+            }
+            None => LargestFirstBoxSelector::new().select(inputs, target_balance, target_tokens),
+        }
+    }
+}
+
+struct Candidate {
+    /// Index in the inputs
+    index: usize,
+    value: u64,
+    tokens: Vec<(TokenId, u64)>,
+}
+
+/// Depth-first search over the include/exclude decisions for each candidate (including first),
+/// returns the input indices of the candidates matching the target exactly
+fn exact_match(
+    candidates: &[Candidate],
+    target: &SelectionTarget,
+    max_tries: usize,
+) -> Option<Vec<usize>> {
+    let mut selected: Vec<usize> = vec![];
+    let mut value = 0u64;
+    let mut tokens: HashMap<TokenId, u64> = HashMap::new();
+    // total value of the candidates not decided on yet (from `depth` on)
+    let mut remaining: u64 = candidates.iter().map(|c| c.value).sum();
+    let mut depth = 0;
+    for _ in 0..max_tries {
+        let exceeded = value > target.balance
+            || tokens
+                .iter()
+                .any(|(id, amount)| target.tokens.get(id).map_or(true, |t| amount > t));
+        if !exceeded && value == target.balance && tokens == target.tokens {
+            return Some(selected.iter().map(|i| candidates[*i].index).collect());
+        }
+        if exceeded || value + remaining < target.balance || depth == candidates.len() {
+            // go back to the last included candidate and exclude it
+            while depth > 0 && selected.last() != Some(&(depth - 1)) {
+                depth -= 1;
+                remaining += candidates[depth].value;
+            }
+            let last = selected.pop()?;
+            value -= candidates[last].value;
+            for (id, amount) in &candidates[last].tokens {
+                if let Some(selected_amount) = tokens.get_mut(id) {
+                    *selected_amount -= amount;
+                    if *selected_amount == 0 {
+                        tokens.remove(id);
+                    }
+                }
+            }
+        } else {
+            let c = &candidates[depth];
+            remaining -= c.value;
+            value += c.value;
+            for (id, amount) in &c.tokens {
+                *tokens.entry(*id).or_insert(0) += amount;
+            }
+            selected.push(depth);
+            depth += 1;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ergo_chain_types::Digest32;
+    use ergotree_ir::chain::ergo_box::box_value::checked_sum;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    use crate::wallet::box_selector::simple::check_input_preservation;
+    use crate::wallet::box_selector::sum_value;
+    use crate::wallet::box_selector::tests::assets;
+    use crate::wallet::box_selector::tests::token;
+    use crate::wallet::box_selector::ErgoBoxAssetsData;
+
+    use super::*;
+
+    #[test]
+    fn test_exact_match_without_change() {
+        let min = *BoxValue::SAFE_USER_MIN.as_u64();
+        let inputs = vec![
+            assets(min * 10, vec![]),
+            assets(min * 4, vec![]),
+            assets(min * 6, vec![]),
+            assets(min * 3, vec![]),
+        ];
+        let selection = BranchAndBoundBoxSelector::new()
+            .select(inputs, BoxValue::try_from(min * 7).unwrap(), &[])
+            .unwrap();
+        assert!(selection.change_boxes.is_empty());
+        assert_eq!(sum_value(selection.boxes.as_slice()), min * 7);
+    }
+
+    #[test]
+    fn test_exact_match_with_tokens() {
+        let min = *BoxValue::SAFE_USER_MIN.as_u64();
+        let target_id = TokenId::from(Digest32::from([1u8; 32]));
+        let other_id = TokenId::from(Digest32::from([2u8; 32]));
+        let inputs = vec![
+            // right value, but an extra token
+            assets(min * 5, vec![token(other_id, 1)]),
+            assets(min * 2, vec![token(target_id, 3)]),
+            assets(min * 3, vec![]),
+            assets(min * 3, vec![token(target_id, 2)]),
+        ];
+        let selection = BranchAndBoundBoxSelector::new()
+            .select(
+                inputs,
+                BoxValue::try_from(min * 5).unwrap(),
+                &[token(target_id, 5)],
+            )
+            .unwrap();
+        assert!(selection.change_boxes.is_empty());
+        assert_eq!(selection.boxes.len(), 2);
+    }
+
+    #[test]
+    fn test_falls_back_to_largest_first() {
+        let min = *BoxValue::SAFE_USER_MIN.as_u64();
+        let inputs = vec![assets(min * 10, vec![]), assets(min * 4, vec![])];
+        let selection = BranchAndBoundBoxSelector::new()
+            .select(inputs, BoxValue::try_from(min * 3).unwrap(), &[])
+            .unwrap();
+        assert_eq!(sum_value(selection.boxes.as_slice()), min * 10);
+        assert_eq!(sum_value(selection.change_boxes.as_slice()), min * 7);
+    }
+
+    #[test]
+    fn test_max_tries() {
+        let min = *BoxValue::SAFE_USER_MIN.as_u64();
+        let inputs = vec![
+            assets(min * 8, vec![]),
+            assets(min * 4, vec![]),
+            assets(min * 2, vec![]),
+            assets(min, vec![]),
+        ];
+        let target = BoxValue::try_from(min * 3).unwrap();
+        // an exact match (2 + 1) is found after a few steps
+        assert!(BranchAndBoundBoxSelector::new()
+            .select(inputs.clone(), target, &[])
+            .unwrap()
+            .change_boxes
+            .is_empty());
+        assert!(!BranchAndBoundBoxSelector::with_max_tries(1)
+            .select(inputs, target, &[])
+            .unwrap()
+            .change_boxes
+            .is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn test_exact_match_preserves_assets(inputs in
+                                        vec(any_with::<ErgoBoxAssetsData>(
+                                            (BoxValue::MIN_RAW * 1000 .. BoxValue::MIN_RAW * 10000).into()), 1..10),
+                                        subset in vec(any::<bool>(), 10)) {
+            // target is either an exact sum of some inputs or the half of all inputs
+            let subset_value: u64 = inputs.iter().zip(&subset).filter(|(_, s)| **s).map(|(b, _)| *b.value.as_u64()).sum();
+            let all_inputs_val = checked_sum(inputs.iter().map(|b| b.value)).unwrap();
+            let target_balance = BoxValue::try_from(if subset_value > 0 { subset_value } else { all_inputs_val.as_u64() / 2 }).unwrap();
+            let selection = BranchAndBoundBoxSelector::new()
+                .select(inputs, target_balance, &[]);
+            match selection {
+                Ok(selection) => prop_assert_eq!(
+                    check_input_preservation(
+                        selection.boxes.as_slice(),
+                        selection.change_boxes.as_slice(),
+                        target_balance,
+                        &[]
+                    ),
+                    Ok(())
+                ),
+                // the rest of the inputs can be not enough for a change box (when there is no exact match)
+                Err(e) => prop_assert!(matches!(e, BoxSelectorError::NotEnoughCoinsForChangeBox(_))),
+            }
+        }
+    }
+}
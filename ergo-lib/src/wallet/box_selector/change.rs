@@ -0,0 +1,310 @@
+//! Splitting of the change into boxes within the per-box limits
+
+use std::cmp::max;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+use ergotree_ir::chain::ergo_box::BoxTokens;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::token::Token;
+use ergotree_ir::chain::token::TokenId;
+
+use crate::chain::parameters::Parameters;
+
+use super::ErgoBoxAssetsData;
+use super::NotEnoughCoinsForChangeBox;
+
+/// Size of the serialized P2PK script (header, `ProveDlog` and the public key), the box
+/// selectors estimate the change box size with
+pub const P2PK_ERGO_TREE_SIZE: usize = 36;
+
+/// Max. size of the serialized box fields except the script and tokens: value, creation height,
+/// tokens and registers count, transaction id and output index
+const BOX_FIELDS_MAX_SIZE: usize = 10 + 5 + 1 + 1 + 32 + 3;
+
+/// Max. serialized size of the change box with the given tokens (amounts) guarded by the script of
+/// `ergo_tree_size` bytes
+pub fn estimate_change_box_size(change_tokens: &[Token], ergo_tree_size: usize) -> usize {
+    change_tokens
+        .iter()
+        .map(|t| token_size(u64::from(t.amount)))
+        .fold(BOX_FIELDS_MAX_SIZE + ergo_tree_size, usize::saturating_add)
+}
+
+/// Minimal total value of the change boxes needed to hold the given tokens, i.e. the sum of the
+/// minimal values of the boxes the tokens are split into by [`split_change`]
+pub fn min_change_value(
+    change_tokens: &[Token],
+    ergo_tree_size: usize,
+    min_box_value: BoxValue,
+) -> u64 {
+    plan_change_boxes(
+        change_tokens.iter().map(|t| u64::from(t.amount)),
+        ergo_tree_size,
+    )
+    .iter()
+    .map(|b| b.min_value(min_box_value))
+    .sum()
+}
+
+/// Splits the change into as many boxes as needed to keep each box within
+/// [`ErgoBox::MAX_TOKENS_COUNT`] tokens and [`Parameters::MAX_BOX_SIZE`] bytes (for the script
+/// of `ergo_tree_size` bytes). Every box but the last one gets the minimal value for its size
+/// (but not less than `min_box_value`), the rest of the value goes to the last box.
+/// Fails if `change_value` is below [`min_change_value`].
+pub fn split_change(
+    change_value: BoxValue,
+    change_tokens: Vec<Token>,
+    ergo_tree_size: usize,
+    min_box_value: BoxValue,
+) -> Result<Vec<ErgoBoxAssetsData>, NotEnoughCoinsForChangeBox> {
+    let plan = plan_change_boxes(
+        change_tokens.iter().map(|t| u64::from(t.amount)),
+        ergo_tree_size,
+    );
+    let min_value: u64 = plan.iter().map(|b| b.min_value(min_box_value)).sum();
+    if *change_value.as_u64() < min_value {
+        return Err(NotEnoughCoinsForChangeBox(format!(
+            "change value {} is below the minimal value {} of {} change box(es)",
+            change_value.as_u64(),
+            min_value,
+            plan.len()
+        )));
+    }
+    let mut change_boxes = vec![];
+    let mut tokens_left = change_tokens.into_iter();
+    let mut value_left = *change_value.as_u64();
+    for (i, planned) in plan.iter().enumerate() {
+        let value = if i == plan.len() - 1 {
+            value_left
+        } else {
+            planned.min_value(min_box_value)
+        };
+        value_left -= value;
+        let tokens: Vec<Token> = tokens_left.by_ref().take(planned.tokens_count).collect();
+        change_boxes.push(ErgoBoxAssetsData {
+            value: BoxValue::try_from(value).map_err(|e| {
+                NotEnoughCoinsForChangeBox(format!("invalid change box value {}: {}", value, e))
+            })?,
+            tokens: BoxTokens::from_vec(tokens).ok(),
+        });
+    }
+    Ok(change_boxes)
+}
+
+/// Minimal value of the change with the tokens selected over the target (`selected_tokens`
+/// minus `target_tokens`), estimated for a P2PK change address
+pub(super) fn min_change_value_for_selected(
+    selected_tokens: &HashMap<TokenId, u64>,
+    target_tokens: &HashMap<TokenId, u64>,
+) -> u64 {
+    plan_change_boxes(
+        selected_tokens.iter().filter_map(|(id, amount)| {
+            let change = amount.saturating_sub(target_tokens.get(id).copied().unwrap_or(0));
+            (change > 0).then_some(change)
+        }),
+        P2PK_ERGO_TREE_SIZE,
+    )
+    .iter()
+    .map(|b| b.min_value(BoxValue::SAFE_USER_MIN))
+    .sum()
+}
+
+/// Number of tokens and the estimated size of the change box
+struct PlannedChangeBox {
+    tokens_count: usize,
+    size: usize,
+}
+
+impl PlannedChangeBox {
+    fn min_value(&self, min_box_value: BoxValue) -> u64 {
+        max(
+            (self.size as u64).saturating_mul(BoxValue::MIN_VALUE_PER_BOX_BYTE as u64),
+            *min_box_value.as_u64(),
+        )
+    }
+}
+
+/// Distributes the tokens (amounts, in order) over the change boxes, a box is filled until the
+/// next token exceeds the tokens count or box size limit. Always returns at least one box.
+fn plan_change_boxes(
+    token_amounts: impl Iterator<Item = u64>,
+    ergo_tree_size: usize,
+) -> Vec<PlannedChangeBox> {
+    let empty_box_size = BOX_FIELDS_MAX_SIZE + ergo_tree_size;
+    let mut boxes = vec![PlannedChangeBox {
+        tokens_count: 0,
+        size: empty_box_size,
+    }];
+    for amount in token_amounts {
+        let size = token_size(amount);
+        #[allow(clippy::unwrap_used)]
+        // there is always at least one box
+        let last = boxes.last_mut().unwrap();
+        if last.tokens_count > 0
+            && (last.tokens_count == ErgoBox::MAX_TOKENS_COUNT
+                || last.size + size > Parameters::MAX_BOX_SIZE)
+        {
+            boxes.push(PlannedChangeBox {
+                tokens_count: 1,
+                size: empty_box_size + size,
+            });
+        } else {
+            last.tokens_count += 1;
+            last.size += size;
+        }
+    }
+    boxes
+}
+
+/// Serialized token size (id and VLQ-encoded amount)
+fn token_size(amount: u64) -> usize {
+    let amount_bits = 64 - amount.leading_zeros() as usize;
+    TokenId::SIZE + max(1, amount_bits.div_ceil(7))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic)]
+mod tests {
+    use std::convert::TryInto;
+
+    use ergo_chain_types::Digest32;
+    use ergotree_ir::chain::address::AddressEncoder;
+    use ergotree_ir::chain::address::NetworkPrefix;
+    use ergotree_ir::serialization::SigmaSerializable;
+
+    use crate::chain::ergo_box::box_builder::ErgoBoxCandidateBuilder;
+    use crate::wallet::box_selector::sum_tokens_from_boxes;
+    use crate::wallet::box_selector::sum_value;
+
+    use super::*;
+
+    fn tokens(count: usize, amount: u64) -> Vec<Token> {
+        (0..count)
+            .map(|i| {
+                let mut id = [0u8; 32];
+                id[..8].copy_from_slice(&(i as u64).to_be_bytes());
+                Token {
+                    token_id: TokenId::from(Digest32::from(id)),
+                    amount: amount.try_into().unwrap(),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_p2pk_ergo_tree_size() {
+        let tree = AddressEncoder::new(NetworkPrefix::Mainnet)
+            .parse_address_from_str("9gmNsqrqdSppLUBqg2UzREmmivgqh1r3jmNcLAc53hk3YCvAGWE")
+            .unwrap()
+            .script()
+            .unwrap();
+        assert_eq!(
+            tree.sigma_serialize_bytes().unwrap().len(),
+            P2PK_ERGO_TREE_SIZE
+        );
+    }
+
+    #[test]
+    fn test_no_tokens_single_box() {
+        let change = split_change(
+            BoxValue::SAFE_USER_MIN,
+            vec![],
+            P2PK_ERGO_TREE_SIZE,
+            BoxValue::SAFE_USER_MIN,
+        )
+        .unwrap();
+        assert_eq!(
+            change,
+            vec![ErgoBoxAssetsData {
+                value: BoxValue::SAFE_USER_MIN,
+                tokens: None
+            }]
+        );
+    }
+
+    #[test]
+    fn test_split_by_tokens_count() {
+        // smallest amounts, so the box size limit is not reached
+        let change_tokens = tokens(ErgoBox::MAX_TOKENS_COUNT + 1, 1);
+        assert!(
+            estimate_change_box_size(&change_tokens[..ErgoBox::MAX_TOKENS_COUNT], 0)
+                <= Parameters::MAX_BOX_SIZE
+        );
+        let change = split_change(
+            BoxValue::SAFE_USER_MIN.checked_mul_u32(10).unwrap(),
+            change_tokens,
+            0,
+            BoxValue::SAFE_USER_MIN,
+        )
+        .unwrap();
+        assert_eq!(change.len(), 2);
+        assert_eq!(
+            change[0].tokens.as_ref().unwrap().len(),
+            ErgoBox::MAX_TOKENS_COUNT
+        );
+        assert_eq!(change[1].tokens.as_ref().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_split_by_box_size() {
+        // the largest amounts (9 bytes in VLQ), ~93 tokens per box with a P2PK script
+        let change_tokens = tokens(200, i64::MAX as u64);
+        let change_value = BoxValue::SAFE_USER_MIN.checked_mul_u32(10).unwrap();
+        let change = split_change(
+            change_value,
+            change_tokens.clone(),
+            P2PK_ERGO_TREE_SIZE,
+            BoxValue::SAFE_USER_MIN,
+        )
+        .unwrap();
+        assert_eq!(change.len(), 3);
+        assert_eq!(sum_value(change.as_slice()), *change_value.as_u64());
+        assert_eq!(
+            sum_tokens_from_boxes(change.as_slice()).unwrap().len(),
+            change_tokens.len()
+        );
+        let change_tree = AddressEncoder::new(NetworkPrefix::Mainnet)
+            .parse_address_from_str("9gmNsqrqdSppLUBqg2UzREmmivgqh1r3jmNcLAc53hk3YCvAGWE")
+            .unwrap()
+            .script()
+            .unwrap();
+        for b in change {
+            let tokens: Vec<Token> = b.tokens.clone().unwrap().to_vec();
+            assert!(
+                estimate_change_box_size(tokens.as_slice(), P2PK_ERGO_TREE_SIZE)
+                    <= Parameters::MAX_BOX_SIZE
+            );
+            let mut builder = ErgoBoxCandidateBuilder::new(b.value, change_tree.clone(), 1_000_000);
+            tokens.into_iter().for_each(|t| builder.add_token(t));
+            let candidate = builder.build().unwrap();
+            assert!(candidate.sigma_serialize_bytes().unwrap().len() < Parameters::MAX_BOX_SIZE);
+        }
+    }
+
+    #[test]
+    fn test_not_enough_coins_for_change_boxes() {
+        let change_tokens = tokens(200, i64::MAX as u64);
+        let min_value = min_change_value(
+            change_tokens.as_slice(),
+            P2PK_ERGO_TREE_SIZE,
+            BoxValue::SAFE_USER_MIN,
+        );
+        assert!(min_value > *BoxValue::SAFE_USER_MIN.as_u64() * 3);
+        assert!(split_change(
+            BoxValue::try_from(min_value).unwrap(),
+            change_tokens.clone(),
+            P2PK_ERGO_TREE_SIZE,
+            BoxValue::SAFE_USER_MIN,
+        )
+        .is_ok());
+        assert!(split_change(
+            BoxValue::try_from(min_value - 1).unwrap(),
+            change_tokens,
+            P2PK_ERGO_TREE_SIZE,
+            BoxValue::SAFE_USER_MIN,
+        )
+        .is_err());
+    }
+}
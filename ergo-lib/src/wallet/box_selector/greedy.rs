@@ -0,0 +1,220 @@
+//! Common parts of the box selectors picking inputs one by one
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::convert::TryInto;
+
+use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+use ergotree_ir::chain::token::Token;
+use ergotree_ir::chain::token::TokenAmount;
+use ergotree_ir::chain::token::TokenAmountError;
+use ergotree_ir::chain::token::TokenId;
+
+use super::change::min_change_value_for_selected;
+use super::change::P2PK_ERGO_TREE_SIZE;
+use super::min_change_value;
+use super::simple::check_input_preservation;
+use super::split_change;
+use super::sum_tokens;
+use super::sum_tokens_from_boxes;
+use super::sum_value;
+use super::BoxSelection;
+use super::BoxSelectorError;
+use super::ErgoBoxAssets;
+use super::NotEnoughCoinsForChangeBox;
+
+/// Value and token amounts to select
+pub(super) struct SelectionTarget {
+    pub(super) balance: u64,
+    pub(super) tokens: HashMap<TokenId, u64>,
+}
+
+impl SelectionTarget {
+    pub(super) fn new(
+        target_balance: BoxValue,
+        target_tokens: &[Token],
+    ) -> Result<Self, TokenAmountError> {
+        Ok(SelectionTarget {
+            balance: *target_balance.as_u64(),
+            tokens: sum_tokens(Some(target_tokens))?
+                .into_iter()
+                .map(|(id, amount)| (id, u64::from(amount)))
+                .collect(),
+        })
+    }
+}
+
+/// Value and token amounts of the inputs selected so far
+#[derive(Default)]
+pub(super) struct SelectedAssets {
+    pub(super) value: u64,
+    pub(super) tokens: HashMap<TokenId, u64>,
+}
+
+impl SelectedAssets {
+    pub(super) fn add<T: ErgoBoxAssets>(&mut self, b: &T) {
+        self.value = self.value.saturating_add(*b.value().as_u64());
+        for t in b.tokens().into_iter().flatten() {
+            let amount = self.tokens.entry(t.token_id).or_insert(0);
+            *amount = amount.saturating_add(u64::from(t.amount));
+        }
+    }
+
+    /// Whether the target token is not selected in full yet
+    pub(super) fn is_missing(&self, token_id: &TokenId, target: &SelectionTarget) -> bool {
+        target.tokens.get(token_id).map_or(false, |amount| {
+            self.tokens.get(token_id).copied().unwrap_or(0) < *amount
+        })
+    }
+
+    /// Whether some of the target tokens are not selected in full yet
+    pub(super) fn has_missing_tokens(&self, target: &SelectionTarget) -> bool {
+        target.tokens.keys().any(|id| self.is_missing(id, target))
+    }
+
+    /// Number of the target tokens (not selected in full yet) the box has
+    pub(super) fn missing_tokens_in<T: ErgoBoxAssets>(
+        &self,
+        b: &T,
+        target: &SelectionTarget,
+    ) -> usize {
+        b.tokens()
+            .into_iter()
+            .flatten()
+            .filter(|t| self.is_missing(&t.token_id, target))
+            .count()
+    }
+
+    /// Number of the tokens in the box which are neither targeted nor selected yet (i.e. the
+    /// tokens the box would add to the change)
+    pub(super) fn new_change_tokens_in<T: ErgoBoxAssets>(
+        &self,
+        b: &T,
+        target: &SelectionTarget,
+    ) -> usize {
+        b.tokens()
+            .into_iter()
+            .flatten()
+            .filter(|t| {
+                !target.tokens.contains_key(&t.token_id) && !self.tokens.contains_key(&t.token_id)
+            })
+            .count()
+    }
+
+    /// Whether the selected inputs cover the target and the change (if any) has enough coins
+    /// for the change box
+    pub(super) fn is_enough(&self, target: &SelectionTarget) -> bool {
+        if self.value < target.balance || self.has_missing_tokens(target) {
+            return false;
+        }
+        let has_token_change = self
+            .tokens
+            .iter()
+            .any(|(id, amount)| target.tokens.get(id).map_or(true, |t| amount > t));
+        let value_change = self.value - target.balance;
+        (value_change == 0 && !has_token_change)
+            || value_change >= min_change_value_for_selected(&self.tokens, &target.tokens)
+    }
+}
+
+/// Selects the inputs one by one until the targets are reached and the change (if any) has
+/// enough coins for the change box. `pick` returns the index of the next input among the
+/// remaining ones (`None` to stop).
+pub(super) fn select_greedy<T: ErgoBoxAssets>(
+    inputs: Vec<T>,
+    target_balance: BoxValue,
+    target_tokens: &[Token],
+    mut pick: impl FnMut(&[T], &SelectedAssets, &SelectionTarget) -> Option<usize>,
+) -> Result<BoxSelection<T>, BoxSelectorError> {
+    let target = SelectionTarget::new(target_balance, target_tokens)?;
+    let mut remaining = inputs;
+    let mut selected = vec![];
+    let mut assets = SelectedAssets::default();
+    while !assets.is_enough(&target) {
+        match pick(&remaining, &assets, &target) {
+            Some(index) => {
+                let b = remaining.remove(index);
+                assets.add(&b);
+                selected.push(b);
+            }
+            None => break,
+        }
+    }
+    selection_with_change(selected, target_balance, target_tokens)
+}
+
+/// Selection of the given inputs, the value and tokens over the target are returned in the
+/// change boxes. Fails with [`NotEnoughCoinsForChangeBox`] if there is a change, but its value is
+/// below [`min_change_value`] (every change box holds at least [`BoxValue::SAFE_USER_MIN`]).
+pub(super) fn selection_with_change<T: ErgoBoxAssets>(
+    selected_inputs: Vec<T>,
+    target_balance: BoxValue,
+    target_tokens: &[Token],
+) -> Result<BoxSelection<T>, BoxSelectorError> {
+    let selected_value = sum_value(selected_inputs.as_slice());
+    let target_value = *target_balance.as_u64();
+    if selected_value < target_value {
+        return Err(BoxSelectorError::NotEnoughCoins(
+            target_value - selected_value,
+        ));
+    }
+    let mut change_tokens = sum_tokens_from_boxes(selected_inputs.as_slice())?;
+    let mut missing_tokens = vec![];
+    for (id, target_amount) in sum_tokens(Some(target_tokens))? {
+        match change_tokens.get(&id).copied() {
+            Some(amount) if amount == target_amount => {
+                change_tokens.remove(&id);
+            }
+            Some(amount) if amount > target_amount => {
+                change_tokens.insert(id, amount.checked_sub(&target_amount)?);
+            }
+            amount => {
+                let missing = u64::from(target_amount) - amount.map_or(0, u64::from);
+                missing_tokens.push(Token::from((id, TokenAmount::try_from(missing)?)));
+            }
+        }
+    }
+    if !missing_tokens.is_empty() {
+        return Err(BoxSelectorError::NotEnoughTokens(missing_tokens));
+    }
+    let change_value = selected_value - target_value;
+    let change_boxes = if change_value == 0 && change_tokens.is_empty() {
+        vec![]
+    } else {
+        let change_tokens: Vec<Token> = change_tokens.into_iter().map(Token::from).collect();
+        let min_value = min_change_value(
+            change_tokens.as_slice(),
+            P2PK_ERGO_TREE_SIZE,
+            BoxValue::SAFE_USER_MIN,
+        );
+        if change_value < min_value {
+            return Err(NotEnoughCoinsForChangeBox(format!(
+                "change value {} is below the minimal value {} of the change box(es)",
+                change_value, min_value
+            ))
+            .into());
+        }
+        let change_value = BoxValue::try_from(change_value).map_err(|e| {
+            NotEnoughCoinsForChangeBox(format!("invalid change value {}: {}", change_value, e))
+        })?;
+        split_change(
+            change_value,
+            change_tokens,
+            P2PK_ERGO_TREE_SIZE,
+            BoxValue::SAFE_USER_MIN,
+        )?
+    };
+    check_input_preservation(
+        selected_inputs.as_slice(),
+        change_boxes.as_slice(),
+        target_balance,
+        target_tokens,
+    )?;
+    let selected_inputs_len = selected_inputs.len();
+    Ok(BoxSelection {
+        boxes: selected_inputs
+            .try_into()
+            .map_err(|_| BoxSelectorError::SelectedInputsOutOfBounds(selected_inputs_len))?,
+        change_boxes,
+    })
+}
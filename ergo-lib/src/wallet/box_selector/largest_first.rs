@@ -0,0 +1,102 @@
+//! Largest-first box selector, spends the fewest (largest) boxes
+
+use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+use ergotree_ir::chain::token::Token;
+
+use super::greedy::select_greedy;
+use super::BoxSelection;
+use super::BoxSelector;
+use super::BoxSelectorError;
+use super::ErgoBoxAssets;
+
+/// Box selector taking the boxes with the most of the missing target tokens first and then the
+/// boxes with the largest value, so the fewest inputs are spent
+#[derive(Default)]
+pub struct LargestFirstBoxSelector {}
+
+impl LargestFirstBoxSelector {
+    /// Create new instance
+    pub fn new() -> Self {
+        LargestFirstBoxSelector {}
+    }
+}
+
+impl<T: ErgoBoxAssets> BoxSelector<T> for LargestFirstBoxSelector {
+    fn select(
+        &self,
+        inputs: Vec<T>,
+        target_balance: BoxValue,
+        target_tokens: &[Token],
+    ) -> Result<BoxSelection<T>, BoxSelectorError> {
+        select_greedy(
+            inputs,
+            target_balance,
+            target_tokens,
+            |inputs, assets, target| {
+                if assets.has_missing_tokens(target) {
+                    inputs
+                        .iter()
+                        .enumerate()
+                        .map(|(i, b)| (i, assets.missing_tokens_in(b, target), *b.value().as_u64()))
+                        .filter(|(_, missing_tokens, _)| *missing_tokens > 0)
+                        .max_by_key(|(_, missing_tokens, value)| (*missing_tokens, *value))
+                        .map(|(i, _, _)| i)
+                } else {
+                    inputs
+                        .iter()
+                        .enumerate()
+                        .max_by_key(|(_, b)| *b.value().as_u64())
+                        .map(|(i, _)| i)
+                }
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ergotree_ir::chain::token::TokenId;
+    use sigma_test_util::force_any_val;
+
+    use crate::wallet::box_selector::sum_value;
+    use crate::wallet::box_selector::tests::assets;
+    use crate::wallet::box_selector::tests::token;
+
+    use super::*;
+
+    #[test]
+    fn test_selects_largest_boxes() {
+        let min = *BoxValue::SAFE_USER_MIN.as_u64();
+        let inputs = vec![
+            assets(min, vec![]),
+            assets(min * 10, vec![]),
+            assets(min * 2, vec![]),
+            assets(min * 5, vec![]),
+        ];
+        let selection = LargestFirstBoxSelector::new()
+            .select(inputs, BoxValue::try_from(min * 12).unwrap(), &[])
+            .unwrap();
+        assert_eq!(sum_value(selection.boxes.as_slice()), min * 15);
+        assert_eq!(selection.boxes.len(), 2);
+    }
+
+    #[test]
+    fn test_token_change_without_coins() {
+        let min = *BoxValue::SAFE_USER_MIN.as_u64();
+        let token_id = force_any_val::<TokenId>();
+        // the whole value is targeted, nothing is left for the box with the rest of the tokens
+        let inputs = vec![assets(min * 2, vec![token(token_id, 10)])];
+        let r = LargestFirstBoxSelector::new().select(
+            inputs,
+            BoxValue::try_from(min * 2).unwrap(),
+            &[token(token_id, 1)],
+        );
+        assert!(matches!(
+            r,
+            Err(BoxSelectorError::NotEnoughCoinsForChangeBox(_))
+        ));
+    }
+}
@@ -0,0 +1,115 @@
+//! Random box selector, doesn't reveal the wallet's coin ordering
+
+use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+use ergotree_ir::chain::token::Token;
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+
+use super::greedy::select_greedy;
+use super::BoxSelection;
+use super::BoxSelector;
+use super::BoxSelectorError;
+use super::ErgoBoxAssets;
+
+/// Box selector picking random boxes (the ones with the missing target tokens first) until the
+/// target is reached. The selected inputs don't depend on the box order or values, which makes
+/// linking the transactions of a wallet harder.
+#[derive(Default)]
+pub struct RandomBoxSelector {
+    seed: Option<u64>,
+}
+
+impl RandomBoxSelector {
+    /// Create new instance (seeded from the OS entropy on every selection)
+    pub fn new() -> Self {
+        RandomBoxSelector { seed: None }
+    }
+
+    /// Create new instance with a fixed seed (the same inputs are selected on every call)
+    pub fn with_seed(seed: u64) -> Self {
+        RandomBoxSelector { seed: Some(seed) }
+    }
+}
+
+impl<T: ErgoBoxAssets> BoxSelector<T> for RandomBoxSelector {
+    fn select(
+        &self,
+        inputs: Vec<T>,
+        target_balance: BoxValue,
+        target_tokens: &[Token],
+    ) -> Result<BoxSelection<T>, BoxSelectorError> {
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        select_greedy(
+            inputs,
+            target_balance,
+            target_tokens,
+            |inputs, assets, target| {
+                let candidates: Vec<usize> = if assets.has_missing_tokens(target) {
+                    inputs
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, b)| assets.missing_tokens_in(*b, target) > 0)
+                        .map(|(i, _)| i)
+                        .collect()
+                } else {
+                    (0..inputs.len()).collect()
+                };
+                if candidates.is_empty() {
+                    None
+                } else {
+                    Some(candidates[rng.gen_range(0..candidates.len())])
+                }
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic)]
+mod tests {
+    use ergotree_ir::chain::token::TokenId;
+    use sigma_test_util::force_any_val;
+
+    use crate::wallet::box_selector::sum_tokens_from_boxes;
+    use crate::wallet::box_selector::tests::assets;
+    use crate::wallet::box_selector::tests::token;
+    use crate::wallet::box_selector::ErgoBoxAssetsData;
+
+    use super::*;
+
+    #[test]
+    fn test_seeded_selection_is_reproducible() {
+        let inputs: Vec<ErgoBoxAssetsData> = (1..20)
+            .map(|i| assets(*BoxValue::SAFE_USER_MIN.as_u64() * i, vec![]))
+            .collect();
+        let target = BoxValue::SAFE_USER_MIN.checked_mul_u32(30).unwrap();
+        let s = RandomBoxSelector::with_seed(42);
+        assert_eq!(
+            s.select(inputs.clone(), target, &[]).unwrap(),
+            s.select(inputs, target, &[]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_selects_target_tokens() {
+        let min = *BoxValue::SAFE_USER_MIN.as_u64();
+        let token = token(force_any_val::<TokenId>(), 7);
+        let mut inputs: Vec<ErgoBoxAssetsData> = (0..10).map(|_| assets(min, vec![])).collect();
+        inputs.push(assets(min, vec![token.clone()]));
+        for seed in 0..10 {
+            let selection = RandomBoxSelector::with_seed(seed)
+                .select(inputs.clone(), BoxValue::SAFE_USER_MIN, &[token.clone()])
+                .unwrap();
+            assert_eq!(
+                sum_tokens_from_boxes(selection.boxes.as_slice())
+                    .unwrap()
+                    .get(&token.token_id),
+                Some(&token.amount)
+            );
+        }
+    }
+}
@@ -6,7 +6,6 @@ use std::convert::TryInto;
 
 use ergotree_ir::chain::ergo_box::box_value::BoxValue;
 use ergotree_ir::chain::ergo_box::BoxTokens;
-use ergotree_ir::chain::ergo_box::ErgoBox;
 use ergotree_ir::chain::token::Token;
 use ergotree_ir::chain::token::TokenAmount;
 use ergotree_ir::chain::token::TokenAmountError;
@@ -18,9 +17,12 @@ use crate::wallet::box_selector::sum_tokens_from_boxes;
 use crate::wallet::box_selector::sum_value;
 use crate::wallet::box_selector::ErgoBoxAssetsData;
 
+use super::change::min_change_value_for_selected;
+use super::split_change;
 use super::sum_tokens_from_hashmaps;
 use super::BoxSelectorError;
 use super::ErgoBoxAssets;
+use super::P2PK_ERGO_TREE_SIZE;
 use super::{BoxSelection, BoxSelector};
 
 /// Simple box selector, collects inputs(sorted by targeted assets) until target balance is reached
@@ -53,6 +55,12 @@ impl<T: ErgoBoxAssets + Clone> BoxSelector<T> for SimpleBoxSelector {
         // sum all target tokens into hash map (think repeating token ids)
         let mut target_tokens_left: HashMap<TokenId, TokenAmount> =
             sum_tokens(Some(target_tokens))?;
+        let target_token_amounts: HashMap<TokenId, u64> = target_tokens_left
+            .iter()
+            .map(|(id, amount)| (*id, u64::from(*amount)))
+            .collect();
+        // all tokens of the selected boxes (to estimate the change boxes value)
+        let mut selected_token_amounts: HashMap<TokenId, u64> = HashMap::new();
         let mut has_value_change = false;
         let mut has_token_change = false;
         let mut sorted_inputs = inputs;
@@ -81,7 +89,11 @@ impl<T: ErgoBoxAssets + Clone> BoxSelector<T> for SimpleBoxSelector {
             };
             if target_balance > selected_boxes_value
                 || (has_value_change || has_token_change)
-                    && (value_change_amt < *BoxValue::SAFE_USER_MIN.as_u64())
+                    && (value_change_amt
+                        < min_change_value_for_selected(
+                            &selected_token_amounts,
+                            &target_token_amounts,
+                        ))
                 || (!target_tokens_left.is_empty()
                     && b.tokens()
                         .into_iter()
@@ -124,6 +136,10 @@ impl<T: ErgoBoxAssets + Clone> BoxSelector<T> for SimpleBoxSelector {
                 {
                     has_token_change = true;
                 };
+                for t in b.tokens().into_iter().flatten() {
+                    let amount = selected_token_amounts.entry(t.token_id).or_insert(0);
+                    *amount = amount.saturating_add(u64::from(t.amount));
+                }
                 selected_inputs.push(b);
             };
         }
@@ -194,7 +210,7 @@ impl From<TokenAmountError> for CheckPreservationError {
 }
 
 /// Check if the selected inputs value and tokens are equal to the target + change
-fn check_input_preservation<T: ErgoBoxAssets>(
+pub(super) fn check_input_preservation<T: ErgoBoxAssets>(
     selected_inputs: &[T],
     change_boxes: &[ErgoBoxAssetsData],
     target_balance: BoxValue,
@@ -224,66 +240,20 @@ fn check_input_preservation<T: ErgoBoxAssets>(
 /// Not enough coins for change box(es)
 #[derive(Error, PartialEq, Eq, Debug, Clone)]
 #[error("Not enough coins for change box(es)")]
-pub struct NotEnoughCoinsForChangeBox(String);
+pub struct NotEnoughCoinsForChangeBox(pub(super) String);
 
-/// Split change tokens into a multiple boxes if over ErgoBox::MAX_TOKENS_COUNT distinct tokens
+/// Split change tokens into a multiple boxes if over ErgoBox::MAX_TOKENS_COUNT distinct tokens or
+/// the max. box size (see [`split_change`])
 fn make_change_boxes(
     change_value: BoxValue,
     change_tokens: HashMap<TokenId, TokenAmount>,
 ) -> Result<Vec<ErgoBoxAssetsData>, NotEnoughCoinsForChangeBox> {
-    if change_tokens.is_empty() {
-        Ok(vec![ErgoBoxAssetsData {
-            value: change_value,
-            tokens: None,
-        }])
-    } else if change_tokens.len() <= ErgoBox::MAX_TOKENS_COUNT {
-        #[allow(clippy::unwrap_used)]
-        // unwrap_used is ok here because we checked that change_tokens.len() <= ErgoBox::MAX_TOKENS_COUNT
-        Ok(vec![ErgoBoxAssetsData {
-            value: change_value,
-            tokens: Some(
-                BoxTokens::from_vec(change_tokens.into_iter().map(Token::from).collect()).unwrap(),
-            ),
-        }])
-    } else {
-        let mut change_boxes = vec![];
-        let mut change_tokens_left: Vec<Token> =
-            change_tokens.into_iter().map(Token::from).collect();
-        let mut change_value_left = change_value;
-        while !change_tokens_left.is_empty() {
-            if change_tokens_left.len() <= ErgoBox::MAX_TOKENS_COUNT {
-                #[allow(clippy::unwrap_used)]
-                // unwrap_used is ok here because we checked that change_tokens_left.len() <= ErgoBox::MAX_TOKENS_COUNT
-                let change_box = ErgoBoxAssetsData {
-                    value: change_value_left,
-                    tokens: Some(BoxTokens::from_vec(change_tokens_left).unwrap()),
-                };
-                change_boxes.push(change_box);
-                break;
-            } else {
-                #[allow(clippy::unwrap_used)] // safe for the box value upper bound
-                // doubled due to larger box size to accomodate so many tokens
-                let value = BoxValue::SAFE_USER_MIN.checked_mul_u32(2).unwrap();
-                let tokens_to_drain = ErgoBox::MAX_TOKENS_COUNT;
-                let drained_tokens: Vec<Token> =
-                    change_tokens_left.drain(..tokens_to_drain).collect();
-                #[allow(clippy::unwrap_used)]
-                // safe since tokens_to_drain is ErgoBox::MAX_TOKENS_COUNT
-                let change_box = ErgoBoxAssetsData {
-                    value,
-                    tokens: Some(BoxTokens::from_vec(drained_tokens).unwrap()),
-                };
-                change_boxes.push(change_box);
-                change_value_left = change_value_left.checked_sub(&value).map_err(|e| {
-                    NotEnoughCoinsForChangeBox(format!(
-                        "Not enough coins left ({:?}) for change box {:?}, error: {}",
-                        change_value_left, value, e
-                    ))
-                })?;
-            }
-        }
-        Ok(change_boxes)
-    }
+    split_change(
+        change_value,
+        change_tokens.into_iter().map(Token::from).collect(),
+        P2PK_ERGO_TREE_SIZE,
+        BoxValue::MIN,
+    )
 }
 
 #[cfg(test)]
@@ -0,0 +1,119 @@
+//! Token-aware box selector, keeps the unrelated tokens out of the change
+
+use std::cmp::Reverse;
+
+use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+use ergotree_ir::chain::token::Token;
+
+use super::greedy::select_greedy;
+use super::BoxSelection;
+use super::BoxSelector;
+use super::BoxSelectorError;
+use super::ErgoBoxAssets;
+
+/// Box selector minimizing the number of distinct tokens dragged into the change, i.e. the
+/// tokens which are not targeted. Prefers the boxes bringing the fewest new tokens, then the
+/// boxes with the most of the missing target tokens and then the boxes with the largest value.
+#[derive(Default)]
+pub struct TokenAwareBoxSelector {}
+
+impl TokenAwareBoxSelector {
+    /// Create new instance
+    pub fn new() -> Self {
+        TokenAwareBoxSelector {}
+    }
+}
+
+impl<T: ErgoBoxAssets> BoxSelector<T> for TokenAwareBoxSelector {
+    fn select(
+        &self,
+        inputs: Vec<T>,
+        target_balance: BoxValue,
+        target_tokens: &[Token],
+    ) -> Result<BoxSelection<T>, BoxSelectorError> {
+        select_greedy(
+            inputs,
+            target_balance,
+            target_tokens,
+            |inputs, assets, target| {
+                let has_missing_tokens = assets.has_missing_tokens(target);
+                inputs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, b)| (i, assets.missing_tokens_in(b, target), b))
+                    .filter(|(_, missing_tokens, _)| !has_missing_tokens || *missing_tokens > 0)
+                    .min_by_key(|(_, missing_tokens, b)| {
+                        (
+                            assets.new_change_tokens_in(*b, target),
+                            Reverse(*missing_tokens),
+                            Reverse(*b.value().as_u64()),
+                        )
+                    })
+                    .map(|(i, _, _)| i)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ergo_chain_types::Digest32;
+    use ergotree_ir::chain::token::TokenId;
+    use sigma_test_util::force_any_val;
+
+    use crate::wallet::box_selector::sum_tokens_from_boxes;
+    use crate::wallet::box_selector::tests::assets;
+    use crate::wallet::box_selector::tests::token;
+
+    use super::*;
+
+    #[test]
+    fn test_avoids_unrelated_tokens() {
+        let min = *BoxValue::SAFE_USER_MIN.as_u64();
+        let target_id = TokenId::from(Digest32::from([1u8; 32]));
+        let dust_id = TokenId::from(Digest32::from([2u8; 32]));
+        let inputs = vec![
+            // larger, but carries an unrelated token
+            assets(min * 10, vec![token(dust_id, 1)]),
+            assets(min * 3, vec![token(target_id, 5), token(dust_id, 1)]),
+            assets(min * 2, vec![token(target_id, 5)]),
+            assets(min * 4, vec![]),
+        ];
+        let selection = TokenAwareBoxSelector::new()
+            .select(
+                inputs,
+                BoxValue::try_from(min * 3).unwrap(),
+                &[token(target_id, 5)],
+            )
+            .unwrap();
+        let selected_tokens = sum_tokens_from_boxes(selection.boxes.as_slice()).unwrap();
+        assert!(!selected_tokens.contains_key(&dust_id));
+        assert_eq!(selection.boxes.len(), 2);
+        assert_eq!(
+            selection
+                .change_boxes
+                .iter()
+                .map(|b| *b.value.as_u64())
+                .sum::<u64>(),
+            min * 3
+        );
+    }
+
+    #[test]
+    fn test_not_enough_tokens() {
+        let min = *BoxValue::SAFE_USER_MIN.as_u64();
+        let token_id = force_any_val::<TokenId>();
+        let inputs = vec![assets(min * 2, vec![token(token_id, 3)])];
+        assert_eq!(
+            TokenAwareBoxSelector::new().select(
+                inputs,
+                BoxValue::SAFE_USER_MIN,
+                &[token(token_id, 5)]
+            ),
+            Err(BoxSelectorError::NotEnoughTokens(vec![token(token_id, 2)]))
+        );
+    }
+}
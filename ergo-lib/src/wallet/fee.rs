@@ -0,0 +1,469 @@
+//! Transaction fee estimation (per byte of the transaction size or per unit of its cost)
+
+use std::sync::Arc;
+
+use ergotree_interpreter::eval::costs::Cost;
+use ergotree_interpreter::eval::costs::Costs;
+use ergotree_interpreter::eval::env::Env;
+use ergotree_interpreter::eval::reduce_to_crypto_with_cost;
+use ergotree_interpreter::eval::EvalError;
+use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+use ergotree_ir::chain::ergo_box::box_value::BoxValueError;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::token::Token;
+use ergotree_ir::ergo_tree::ErgoTreeError;
+use thiserror::Error;
+
+use crate::chain::ergo_state_context::ErgoStateContext;
+use crate::chain::transaction::unsigned::UnsignedTransaction;
+use crate::chain::transaction::validation::initial_tx_cost;
+
+use super::box_selector::BoxSelection;
+use super::box_selector::BoxSelector;
+use super::box_selector::BoxSelectorError;
+use super::box_selector::ErgoBoxAssets;
+use super::box_selector::ErgoBoxId;
+use super::signing::make_context;
+use super::signing::ErgoTransaction;
+use super::tx_builder::TxBuilder;
+use super::tx_builder::TxBuilderError;
+use super::tx_context::TransactionContext;
+use super::tx_context::TransactionContextError;
+
+/// Max. number of the box selection and fee estimation rounds in [`select_with_fee_policy`]
+pub const MAX_FEE_ESTIMATION_ROUNDS: usize = 16;
+
+/// Fee rate (in nanoERGs)
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum FeeRate {
+    /// Per byte of the signed transaction size (see [`TxBuilder::estimate_tx_size_bytes`])
+    PerByte(u64),
+    /// Per unit of the transaction cost (see [`estimate_tx_cost`])
+    PerCostUnit(u64),
+}
+
+/// Fee policy, the fee by the rate within the given bounds
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct FeePolicy {
+    /// Fee rate
+    pub rate: FeeRate,
+    /// Minimal fee (if the fee by the rate is lower)
+    pub min_fee: BoxValue,
+    /// Upper bound of the fee, the estimation fails if the fee by the rate is higher
+    pub max_fee: BoxValue,
+}
+
+impl FeePolicy {
+    /// Fee per byte of the signed transaction size, with [`BoxValue::SAFE_USER_MIN`] minimal fee
+    /// and 1 ERG max. fee
+    pub fn per_byte(nano_ergs_per_byte: u64) -> Self {
+        FeePolicy::new(FeeRate::PerByte(nano_ergs_per_byte))
+    }
+
+    /// Fee per unit of the transaction cost, with [`BoxValue::SAFE_USER_MIN`] minimal fee and
+    /// 1 ERG max. fee
+    pub fn per_cost_unit(nano_ergs_per_cost_unit: u64) -> Self {
+        FeePolicy::new(FeeRate::PerCostUnit(nano_ergs_per_cost_unit))
+    }
+
+    fn new(rate: FeeRate) -> Self {
+        FeePolicy {
+            rate,
+            min_fee: BoxValue::SAFE_USER_MIN,
+            #[allow(clippy::unwrap_used)]
+            max_fee: BoxValue::new(BoxValue::UNITS_PER_ERGO as u64).unwrap(),
+        }
+    }
+
+    /// Set the minimal fee
+    pub fn with_min_fee(self, min_fee: BoxValue) -> Self {
+        FeePolicy { min_fee, ..self }
+    }
+
+    /// Set the max. fee
+    pub fn with_max_fee(self, max_fee: BoxValue) -> Self {
+        FeePolicy { max_fee, ..self }
+    }
+
+    /// Fee for the transaction of the given size (in bytes) and cost (in block cost units),
+    /// fails if it's over [`FeePolicy::max_fee`]
+    pub fn fee(&self, tx_size_bytes: usize, tx_cost: u64) -> Result<BoxValue, FeeEstimationError> {
+        let fee = match self.rate {
+            FeeRate::PerByte(rate) => rate.saturating_mul(tx_size_bytes as u64),
+            FeeRate::PerCostUnit(rate) => rate.saturating_mul(tx_cost),
+        }
+        .max(*self.min_fee.as_u64());
+        if fee > *self.max_fee.as_u64() {
+            return Err(FeeEstimationError::FeeOverMax {
+                fee,
+                max_fee: self.max_fee,
+            });
+        }
+        Ok(BoxValue::new(fee)?)
+    }
+}
+
+/// Boxes and the blockchain state the transaction cost is estimated with
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TxCostContext {
+    /// Blockchain state (current height, parameters, etc.)
+    pub state_context: ErgoStateContext,
+    /// Boxes of the transaction data inputs
+    pub data_boxes: Vec<ErgoBox>,
+}
+
+/// Selects the inputs and the fee by the policy: the fee is estimated for the transaction built
+/// with the selected inputs (for the outputs and the fee) and if it's higher than the fee the
+/// inputs were selected for, the selection is repeated with the new fee until the fee covers
+/// the transaction (but no more than [`MAX_FEE_ESTIMATION_ROUNDS`] times).
+/// `target_balance` and `target_tokens` - total value and tokens of the outputs (without the fee),
+/// `make_tx_builder` - creates the transaction builder for the selection and fee (sets outputs,
+/// data inputs, etc.),
+/// `cost_context` - required for [`FeeRate::PerCostUnit`].
+/// Returns the builder of the transaction paying the fee.
+pub fn select_with_fee_policy<S, B>(
+    fee_policy: &FeePolicy,
+    box_selector: &B,
+    inputs: Vec<S>,
+    target_balance: BoxValue,
+    target_tokens: &[Token],
+    make_tx_builder: impl Fn(BoxSelection<S>, BoxValue) -> TxBuilder<S>,
+    cost_context: Option<&TxCostContext>,
+) -> Result<TxBuilder<S>, FeeEstimationError>
+where
+    S: ErgoBoxAssets + ErgoBoxId + Clone + Into<ErgoBox>,
+    B: BoxSelector<S>,
+{
+    let mut fee = fee_policy.min_fee;
+    for _ in 0..MAX_FEE_ESTIMATION_ROUNDS {
+        let selection = box_selector.select(
+            inputs.clone(),
+            target_balance.checked_add(&fee)?,
+            target_tokens,
+        )?;
+        let tx_builder = make_tx_builder(selection, fee);
+        let required_fee = match fee_policy.rate {
+            FeeRate::PerByte(_) => fee_policy.fee(tx_builder.estimate_tx_size_bytes()?, 0)?,
+            FeeRate::PerCostUnit(_) => {
+                let cost_context = cost_context.ok_or(FeeEstimationError::MissingCostContext)?;
+                let boxes_to_spend: Vec<ErgoBox> = tx_builder
+                    .box_selection()
+                    .boxes
+                    .into_iter()
+                    .map(Into::into)
+                    .collect();
+                let tx_context = TransactionContext::new(
+                    tx_builder.clone().build()?,
+                    boxes_to_spend,
+                    cost_context.data_boxes.clone(),
+                )?;
+                fee_policy.fee(
+                    0,
+                    estimate_tx_cost(&tx_context, &cost_context.state_context)?,
+                )?
+            }
+        };
+        if required_fee.as_u64() <= fee.as_u64() {
+            return Ok(tx_builder);
+        }
+        fee = required_fee;
+    }
+    Err(FeeEstimationError::NotConverged(MAX_FEE_ESTIMATION_ROUNDS))
+}
+
+/// Estimated cost (in block cost units) of the transaction validation: the initial cost (inputs,
+/// data inputs, outputs, tokens) and the script execution cost of each input (see
+/// [`estimate_input_costs`])
+pub fn estimate_tx_cost(
+    tx_context: &TransactionContext<UnsignedTransaction>,
+    state_context: &ErgoStateContext,
+) -> Result<u64, FeeEstimationError> {
+    let initial_cost = initial_tx_cost(
+        &tx_context.spending_tx,
+        &tx_context.input_boxes(),
+        &state_context.parameters,
+    );
+    Ok(estimate_input_costs(tx_context, state_context)?
+        .into_iter()
+        .fold(initial_cost, u64::saturating_add))
+}
+
+/// Preflight of the inputs before signing: evaluates the script of each input and returns its
+/// estimated cost (in block cost units) including the signature verification. Fails if a script
+/// cannot be evaluated in the given context (or exceeds the max. block cost).
+pub fn estimate_input_costs(
+    tx_context: &TransactionContext<UnsignedTransaction>,
+    state_context: &ErgoStateContext,
+) -> Result<Vec<u64>, FeeEstimationError> {
+    tx_context
+        .spending_tx
+        .inputs_ids()
+        .iter()
+        .enumerate()
+        .map(|(input_index, box_id)| {
+            let input_box = tx_context
+                .get_input_box(box_id)
+                .ok_or(TransactionContextError::InputBoxNotFound(input_index))?;
+            let ctx = Arc::new(make_context(state_context, tx_context, input_index)?);
+            let expr = input_box
+                .ergo_tree
+                .proposition()
+                .map_err(|error| FeeEstimationError::ErgoTreeError { input_index, error })?;
+            let reduction = reduce_to_crypto_with_cost(
+                &expr,
+                &Env::empty(),
+                ctx,
+                0,
+                Some(state_context.parameters.max_block_cost),
+            )
+            .map_err(|error| FeeEstimationError::ScriptEvalError { input_index, error })?;
+            Ok(reduction
+                .cost
+                .saturating_add(Cost::to_block_cost(Costs::sigma_verify_cost(
+                    &reduction.sigma_prop,
+                ))))
+        })
+        .collect()
+}
+
+/// Errors of the fee estimation
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum FeeEstimationError {
+    #[error("Box selector error: {0}")]
+    BoxSelectorError(#[from] BoxSelectorError),
+    #[error("TxBuilder error: {0}")]
+    TxBuilderError(#[from] TxBuilderError),
+    #[error("Transaction context error: {0}")]
+    TransactionContextError(#[from] TransactionContextError),
+    #[error("Invalid box value: {0}")]
+    BoxValueError(#[from] BoxValueError),
+    #[error("Fee {fee} is over the max. fee {max_fee:?}")]
+    FeeOverMax { fee: u64, max_fee: BoxValue },
+    #[error("Fee did not converge in {0} rounds")]
+    NotConverged(usize),
+    #[error("Transaction cost context is required for the fee per cost unit")]
+    MissingCostContext,
+    #[error("Input {input_index} ErgoTree error: {error:?}")]
+    ErgoTreeError {
+        input_index: usize,
+        error: ErgoTreeError,
+    },
+    #[error("Input {input_index} script evaluation error: {error}")]
+    ScriptEvalError {
+        input_index: usize,
+        error: EvalError,
+    },
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ergotree_interpreter::sigma_protocol::prover::ContextExtension;
+    use ergotree_interpreter::sigma_protocol::prover::ProofBytes;
+    use ergotree_ir::chain::address::Address;
+    use ergotree_ir::chain::ergo_box::ErgoBoxCandidate;
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+    use ergotree_ir::chain::tx_id::TxId;
+    use ergotree_ir::ergo_tree::ErgoTree;
+    use ergotree_ir::mir::expr::Expr;
+    use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
+    use sigma_test_util::force_any_val;
+
+    use crate::chain::transaction::input::Input;
+    use crate::chain::transaction::Transaction;
+    use crate::wallet::box_selector::SimpleBoxSelector;
+
+    use super::*;
+
+    fn true_tree() -> ErgoTree {
+        ErgoTree::try_from(Expr::Const(SigmaBoolean::TrivialProp(true).into())).unwrap()
+    }
+
+    fn inputs(count: u64) -> Vec<ErgoBox> {
+        (0..count)
+            .map(|i| {
+                ErgoBox::new(
+                    BoxValue::SAFE_USER_MIN.checked_mul_u32(2).unwrap(),
+                    true_tree(),
+                    None,
+                    NonMandatoryRegisters::empty(),
+                    0,
+                    force_any_val::<TxId>(),
+                    i as u16,
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    fn output(value: BoxValue) -> ErgoBoxCandidate {
+        ErgoBoxCandidate {
+            value,
+            ergo_tree: true_tree(),
+            tokens: None,
+            additional_registers: NonMandatoryRegisters::empty(),
+            creation_height: 0,
+        }
+    }
+
+    fn select(
+        fee_policy: &FeePolicy,
+        inputs: Vec<ErgoBox>,
+        target_balance: BoxValue,
+        cost_context: Option<&TxCostContext>,
+    ) -> Result<TxBuilder<ErgoBox>, FeeEstimationError> {
+        let change_address = force_any_val::<Address>();
+        select_with_fee_policy(
+            fee_policy,
+            &SimpleBoxSelector::new(),
+            inputs,
+            target_balance,
+            &[],
+            |selection, fee| {
+                TxBuilder::new(
+                    selection,
+                    vec![output(target_balance)],
+                    0,
+                    fee,
+                    change_address.clone(),
+                )
+            },
+            cost_context,
+        )
+    }
+
+    #[test]
+    fn test_fee_per_byte() {
+        let target_balance = BoxValue::SAFE_USER_MIN.checked_mul_u32(20).unwrap();
+        let fee_policy = FeePolicy::per_byte(10_000);
+        let tx_builder = select(&fee_policy, inputs(100), target_balance, None).unwrap();
+        let tx_size = tx_builder.estimate_tx_size_bytes().unwrap();
+        // the fee is over the min. fee and pays for the transaction size
+        assert!(tx_builder.fee_amount() > BoxValue::SAFE_USER_MIN);
+        assert!(*tx_builder.fee_amount().as_u64() >= tx_size as u64 * 10_000);
+        // the selection covers the fee (the inputs are added for the fee of the larger transaction)
+        let tx = tx_builder.build().unwrap();
+        assert!(tx.inputs.len() > 11);
+    }
+
+    #[test]
+    fn test_min_fee() {
+        let fee_policy = FeePolicy::per_byte(1);
+        let tx_builder = select(&fee_policy, inputs(10), BoxValue::SAFE_USER_MIN, None).unwrap();
+        assert_eq!(tx_builder.fee_amount(), BoxValue::SAFE_USER_MIN);
+    }
+
+    #[test]
+    fn test_fee_over_max() {
+        let fee_policy = FeePolicy::per_byte(10_000).with_max_fee(BoxValue::SAFE_USER_MIN);
+        assert!(matches!(
+            select(&fee_policy, inputs(10), BoxValue::SAFE_USER_MIN, None),
+            Err(FeeEstimationError::FeeOverMax { .. })
+        ));
+    }
+
+    #[test]
+    fn test_not_enough_coins_for_fee() {
+        let fee_policy = FeePolicy::per_byte(10_000);
+        // enough for the outputs and the min. fee, but not for the fee by the size
+        let target_balance = BoxValue::SAFE_USER_MIN.checked_mul_u32(3).unwrap();
+        assert!(matches!(
+            select(&fee_policy, inputs(2), target_balance, None),
+            Err(FeeEstimationError::BoxSelectorError(
+                BoxSelectorError::NotEnoughCoins(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_fee_per_cost_unit() {
+        let state_context = force_any_val::<ErgoStateContext>();
+        let cost_context = TxCostContext {
+            state_context: state_context.clone(),
+            data_boxes: vec![],
+        };
+        let fee_policy = FeePolicy::per_cost_unit(100);
+        let all_inputs = inputs(10);
+        let tx_builder = select(
+            &fee_policy,
+            all_inputs.clone(),
+            BoxValue::SAFE_USER_MIN,
+            Some(&cost_context),
+        )
+        .unwrap();
+        let fee = tx_builder.fee_amount();
+        let unsigned_tx = tx_builder.build().unwrap();
+        let tx_context =
+            TransactionContext::new(unsigned_tx.clone(), all_inputs.clone(), vec![]).unwrap();
+        let input_costs = estimate_input_costs(&tx_context, &state_context).unwrap();
+        assert_eq!(input_costs.len(), unsigned_tx.inputs.len());
+        let tx_cost = estimate_tx_cost(&tx_context, &state_context).unwrap();
+        assert!(*fee.as_u64() >= tx_cost * 100);
+        assert!(fee > BoxValue::SAFE_USER_MIN);
+        // the estimated cost is the cost of the signed transaction validation
+        let inputs = unsigned_tx
+            .inputs
+            .clone()
+            .mapped(|i| Input::from_unsigned_input(i, ProofBytes::Empty));
+        let tx = Transaction::new(inputs, None, unsigned_tx.output_candidates).unwrap();
+        assert_eq!(tx.validate(all_inputs, vec![], &state_context), Ok(tx_cost));
+    }
+
+    #[test]
+    fn test_missing_cost_context() {
+        let fee_policy = FeePolicy::per_cost_unit(100);
+        assert!(matches!(
+            select(&fee_policy, inputs(10), BoxValue::SAFE_USER_MIN, None),
+            Err(FeeEstimationError::MissingCostContext)
+        ));
+    }
+
+    #[test]
+    fn test_script_preflight_error() {
+        // reduces to a non-boolean value
+        let invalid_tree = ErgoTree::try_from(Expr::Const(1i32.into())).unwrap();
+        let input = ErgoBox::new(
+            BoxValue::SAFE_USER_MIN.checked_mul_u32(2).unwrap(),
+            invalid_tree,
+            None,
+            NonMandatoryRegisters::empty(),
+            0,
+            force_any_val::<TxId>(),
+            0,
+        )
+        .unwrap();
+        let unsigned_tx = UnsignedTransaction::new_from_vec(
+            vec![crate::chain::transaction::UnsignedInput::new(
+                input.box_id(),
+                ContextExtension::empty(),
+            )],
+            vec![],
+            vec![output(BoxValue::SAFE_USER_MIN.checked_mul_u32(2).unwrap())],
+        )
+        .unwrap();
+        let tx_context = TransactionContext::new(unsigned_tx, vec![input], vec![]).unwrap();
+        assert!(matches!(
+            estimate_input_costs(&tx_context, &force_any_val::<ErgoStateContext>()),
+            Err(FeeEstimationError::ScriptEvalError { input_index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_fee_rate() {
+        let fee_policy = FeePolicy::per_byte(1000);
+        assert_eq!(
+            fee_policy.fee(2000, 0).unwrap(),
+            BoxValue::try_from(2_000_000u64).unwrap()
+        );
+        assert_eq!(fee_policy.fee(10, 0).unwrap(), BoxValue::SAFE_USER_MIN);
+        let fee_policy = FeePolicy::per_cost_unit(1000).with_min_fee(BoxValue::MIN);
+        assert_eq!(
+            fee_policy.fee(2000, 20).unwrap(),
+            BoxValue::try_from(20_000u64).unwrap()
+        );
+        assert!(fee_policy.fee(0, u64::MAX).is_err());
+    }
+}
@@ -24,7 +24,7 @@ use ergotree_interpreter::sigma_protocol::sig_serializer::parse_sig_compute_chal
 use ergotree_interpreter::sigma_protocol::unchecked_tree::UncheckedTree;
 use ergotree_interpreter::sigma_protocol::verifier::compute_commitments;
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use super::tx_context::TransactionContextError;
 
@@ -250,7 +250,7 @@ pub fn generate_commitments(
         let input_box = tx_context
             .get_input_box(&input.box_id)
             .ok_or(TransactionContextError::InputBoxNotFound(i))?;
-        let ctx = Rc::new(make_context(state_context, &tx_context, i)?);
+        let ctx = Arc::new(make_context(state_context, &tx_context, i)?);
         let tree = input_box.ergo_tree.clone();
         let exp = tree
             .proposition()
@@ -278,7 +278,7 @@ pub fn extract_hints(
         let input_box = tx_ctx
             .get_input_box(&input.box_id)
             .ok_or(TransactionContextError::InputBoxNotFound(i))?;
-        let ctx = Rc::new(make_context(state_context, tx_ctx, i)?);
+        let ctx = Arc::new(make_context(state_context, tx_ctx, i)?);
         let tree = input_box.ergo_tree.clone();
         let exp = tree
             .proposition()
@@ -414,7 +414,7 @@ mod tests {
     use ergotree_ir::types::stype::SType;
     use sigma_test_util::force_any_val;
     use std::convert::{TryFrom, TryInto};
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     #[test]
     fn extract_hint() {
@@ -466,7 +466,7 @@ mod tests {
         let bytes_m = Base16DecodedBytes::try_from("100208cd03c847c306a2f9a8087b4ae63261cc5acea9034000ba8d033b0fb033247e8aade908cd02f4b05f44eb9703db7fcf9c94b89566787a7188c7e48964821d485d9ef2f9e4c4ea0273007301").unwrap();
         let tree_m: ErgoTree = ErgoTree::sigma_parse_bytes(&bytes_m.0).unwrap();
 
-        let contx = Rc::new(force_any_val::<Context>());
+        let contx = Arc::new(force_any_val::<Context>());
         let exp = tree_m.proposition().unwrap();
         let reduction_result = reduce_to_crypto(&exp, &Env::empty(), contx).unwrap();
         let sigma_tree = reduction_result.sigma_prop;
@@ -656,7 +656,7 @@ mod tests {
 
     #[test]
     fn multi_sig_2() {
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
 
         let secret1 = DlogProverInput::random();
         let secret2 = DlogProverInput::random();
@@ -740,7 +740,7 @@ mod tests {
 
     #[test]
     fn multi_sig_and_3() {
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
 
         let secret1 = DlogProverInput::random();
         let secret2 = DlogProverInput::random();
@@ -904,7 +904,7 @@ mod tests {
 
     #[test]
     fn multi_dlog_dht() {
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
 
         let secret_alice = DlogProverInput::random();
         let secret_bob = DlogProverInput::random();
@@ -1027,7 +1027,7 @@ mod tests {
     #[test]
     fn multi_sig_atleast_2_out_of_3() {
         // from https://github.com/ScorexFoundation/sigmastate-interpreter/blob/78dd1e715038c2f95c518fb56977c6591b76e20c/sc/src/test/scala/sigmastate/utxo/DistributedSigSpecification.scala#L124
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
 
         let alice_secret = DlogProverInput::random();
         let bob_secret = DlogProverInput::random();
@@ -1146,7 +1146,7 @@ mod tests {
     fn multi_sig_atleast_3_out_of_4() {
         // from https://github.com/ScorexFoundation/sigmastate-interpreter/blob/78dd1e715038c2f95c518fb56977c6591b76e20c/sc/src/test/scala/sigmastate/utxo/DistributedSigSpecification.scala#L160-L205
 
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
 
         let alice_secret = DlogProverInput::random();
         let bob_secret = DlogProverInput::random();
@@ -1320,7 +1320,7 @@ mod tests {
     fn multi_sig_atleast_7_out_of_10_i692() {
         // based on
         // https://github.com/ScorexFoundation/sigmastate-interpreter/blob/78dd1e715038c2f95c518fb56977c6591b76e20c/sc/src/test/scala/sigmastate/utxo/DistributedSigSpecification.scala#L299-L389
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
 
         let sk1 = DlogProverInput::random();
         let pk1 = sk1.public_image();
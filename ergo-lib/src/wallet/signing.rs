@@ -11,7 +11,6 @@ use ergotree_interpreter::sigma_protocol::sig_serializer::SigParsingError;
 use ergotree_ir::chain::ergo_box::ErgoBox;
 use ergotree_ir::serialization::SigmaSerializationError;
 use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
-use std::rc::Rc;
 use std::sync::Arc;
 
 use crate::ergotree_ir::chain::ergo_box::BoxId;
@@ -159,6 +158,7 @@ pub fn make_context<T: ErgoTransaction>(
         pre_header: state_ctx.pre_header.clone(),
         extension,
         headers: state_ctx.headers.clone(),
+        validation_settings: state_ctx.validation_settings.clone(),
     })
 }
 
@@ -255,7 +255,7 @@ pub fn sign_tx_input(
     let input_box = tx_context
         .get_input_box(&unsigned_input.box_id)
         .ok_or(TransactionContextError::InputBoxNotFound(input_idx))?;
-    let ctx = Rc::new(make_context(state_context, tx_context, input_idx)?);
+    let ctx = Arc::new(make_context(state_context, tx_context, input_idx)?);
     let mut hints_bag = HintsBag::empty();
     if let Some(bag) = tx_hints {
         hints_bag = bag.all_hints_for_input(input_idx);
@@ -296,7 +296,9 @@ mod tests {
     use rand::thread_rng;
     use sigma_test_util::force_any_val;
 
+    use crate::chain::parameters::Parameters;
     use crate::chain::transaction::reduced::reduce_tx;
+    use crate::chain::transaction::validation::initial_tx_cost;
     use crate::chain::transaction::DataInput;
     use crate::chain::{
         ergo_box::box_builder::ErgoBoxCandidateBuilder, transaction::UnsignedInput,
@@ -308,7 +310,7 @@ mod tests {
     use ergotree_ir::mir::expr::Expr;
     use std::convert::TryFrom;
     use std::convert::TryInto;
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     fn verify_tx_proofs(
         tx: &Transaction,
@@ -324,7 +326,7 @@ mod tests {
             let res = verifier.verify(
                 &b.ergo_tree,
                 &Env::empty(),
-                Rc::new(force_any_val::<Context>()),
+                Arc::new(force_any_val::<Context>()),
                 input.spending_proof.proof.clone(),
                 &message,
             )?;
@@ -349,7 +351,7 @@ mod tests {
                              TxId::zero(),
                              0).unwrap()
             }).collect();
-            let prover = Rc::new(TestProver {
+            let prover = Arc::new(TestProver {
                 secrets: secrets.clone().into_iter().map(PrivateInput::DlogProverInput).collect(),
             });
             let inputs: Vec<UnsignedInput> = boxes_to_spend.clone().into_iter().map(UnsignedInput::from).collect();
@@ -561,13 +563,16 @@ mod tests {
         let ver_res = verifier.verify(
             &ergo_tree,
             &Env::empty(),
-            Rc::new(force_any_val::<Context>()),
+            Arc::new(force_any_val::<Context>()),
             tx.inputs.get(1).unwrap().spending_proof.proof.clone(),
             message.as_slice(),
         );
         assert!(ver_res.unwrap().result);
     }
 
+    /// Reduced 3-of-4 multi-sig transaction from issue 597
+    const MULTI_SIG_ISSUE_597_REDUCED_TX: &str = "ce04022f4cd0df4db787875b3a071e098b72ba4923bd2460e08184b34359563febe04700005e8269c8e2b975a43dc6e74a9c5b10b273313c6d32c1dd40c171fc0a8852ca0100000001a6ac381e6fa99929fd1477b3ba9499790a775e91d4c14c5aa86e9a118dfac8530480ade204100504000400040004000402d804d601b2a5730000d602e4c6a7041ad603e4c6a70510d604ad7202d901040ecdee7204ea02d19683020193c27201c2a7938cb2db63087201730100018cb2db6308a773020001eb02ea02d19683020193e4c67201041a720293e4c672010510720398b27203730300720498b272037304007204d18b0f010001021a04210302e57ca7ebf8cfa1802d4bc79a455008307a936b4f50f0629d9bef484fdd5189210399f5724bbc4d08c6e146d61449c05a3e0546868b1d4f83411f325187d5ca4f8521024e06e6c6073e13a03fa4629882a69108cd60e0a9fbb2e0fcc898ce68a7051b6621027a069cc972fc7816539a316ba1cfc0164656d63dd1873ee407670b0e8195f3bd100206088094ebdc030008cd0314368e16c9c99c5a6e20dda917aeb826b3a908becff543b3a36b38e6b3355ff5d18b0f0000c0843d1005040004000e36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ea02d192a39a8cc7a701730073011001020402d19683030193a38cc7b2a57300000193c2b2a57301007473027303830108cdeeac93b1a57304d18b0f0000c0af87c3210008cd0314368e16c9c99c5a6e20dda917aeb826b3a908becff543b3a36b38e6b3355ff5d18b0f00009702980304cd0302e57ca7ebf8cfa1802d4bc79a455008307a936b4f50f0629d9bef484fdd5189cd0399f5724bbc4d08c6e146d61449c05a3e0546868b1d4f83411f325187d5ca4f85cd024e06e6c6073e13a03fa4629882a69108cd60e0a9fbb2e0fcc898ce68a7051b66cd027a069cc972fc7816539a316ba1cfc0164656d63dd1873ee407670b0e8195f3bd9604cd0302e57ca7ebf8cfa1802d4bc79a455008307a936b4f50f0629d9bef484fdd5189cd0399f5724bbc4d08c6e146d61449c05a3e0546868b1d4f83411f325187d5ca4f85cd024e06e6c6073e13a03fa4629882a69108cd60e0a9fbb2e0fcc898ce68a7051b66cd027a069cc972fc7816539a316ba1cfc0164656d63dd1873ee407670b0e8195f3bdf39b03d3cb9e02d073";
+
     #[test]
     fn test_multi_sig_issue_597() {
         let secrets: Vec<SecretKey> = [
@@ -583,8 +588,56 @@ mod tests {
             SecretKey::dlog_from_bytes(sized_bytes).unwrap()
         })
         .collect();
-        let reduced = ReducedTransaction::sigma_parse_bytes(&base16::decode("ce04022f4cd0df4db787875b3a071e098b72ba4923bd2460e08184b34359563febe04700005e8269c8e2b975a43dc6e74a9c5b10b273313c6d32c1dd40c171fc0a8852ca0100000001a6ac381e6fa99929fd1477b3ba9499790a775e91d4c14c5aa86e9a118dfac8530480ade204100504000400040004000402d804d601b2a5730000d602e4c6a7041ad603e4c6a70510d604ad7202d901040ecdee7204ea02d19683020193c27201c2a7938cb2db63087201730100018cb2db6308a773020001eb02ea02d19683020193e4c67201041a720293e4c672010510720398b27203730300720498b272037304007204d18b0f010001021a04210302e57ca7ebf8cfa1802d4bc79a455008307a936b4f50f0629d9bef484fdd5189210399f5724bbc4d08c6e146d61449c05a3e0546868b1d4f83411f325187d5ca4f8521024e06e6c6073e13a03fa4629882a69108cd60e0a9fbb2e0fcc898ce68a7051b6621027a069cc972fc7816539a316ba1cfc0164656d63dd1873ee407670b0e8195f3bd100206088094ebdc030008cd0314368e16c9c99c5a6e20dda917aeb826b3a908becff543b3a36b38e6b3355ff5d18b0f0000c0843d1005040004000e36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ea02d192a39a8cc7a701730073011001020402d19683030193a38cc7b2a57300000193c2b2a57301007473027303830108cdeeac93b1a57304d18b0f0000c0af87c3210008cd0314368e16c9c99c5a6e20dda917aeb826b3a908becff543b3a36b38e6b3355ff5d18b0f00009702980304cd0302e57ca7ebf8cfa1802d4bc79a455008307a936b4f50f0629d9bef484fdd5189cd0399f5724bbc4d08c6e146d61449c05a3e0546868b1d4f83411f325187d5ca4f85cd024e06e6c6073e13a03fa4629882a69108cd60e0a9fbb2e0fcc898ce68a7051b66cd027a069cc972fc7816539a316ba1cfc0164656d63dd1873ee407670b0e8195f3bd9604cd0302e57ca7ebf8cfa1802d4bc79a455008307a936b4f50f0629d9bef484fdd5189cd0399f5724bbc4d08c6e146d61449c05a3e0546868b1d4f83411f325187d5ca4f85cd024e06e6c6073e13a03fa4629882a69108cd60e0a9fbb2e0fcc898ce68a7051b66cd027a069cc972fc7816539a316ba1cfc0164656d63dd1873ee407670b0e8195f3bdf39b03d3cb9e02d073").unwrap()).unwrap();
+        let reduced = ReducedTransaction::sigma_parse_bytes(
+            &base16::decode(MULTI_SIG_ISSUE_597_REDUCED_TX).unwrap(),
+        )
+        .unwrap();
         let prover = Wallet::from_secrets(secrets);
         assert!(prover.sign_reduced_transaction(reduced, None).is_ok());
     }
+
+    /// Not a reference cost fixture: the reduced transaction carries only the total cost, its input
+    /// boxes are not part of it and cannot be fetched offline. The inputs are rebuilt from what the
+    /// contract requires, so this only checks that `initial_tx_cost` is consistent with the cost
+    /// embedded in the issue 597 reduced transaction for these inputs.
+    #[test]
+    fn test_initial_tx_cost_consistent_with_issue_597_reduced_tx() {
+        let reduced = ReducedTransaction::sigma_parse_bytes(
+            &base16::decode(MULTI_SIG_ISSUE_597_REDUCED_TX).unwrap(),
+        )
+        .unwrap();
+        // tx cost serialized in the reduced transaction
+        assert_eq!(reduced.tx_cost(), 14800);
+        let tx = &reduced.unsigned_tx;
+        // assumed inputs: the multi-sig contract box keeps its NFT (the contract checks
+        // `OUTPUTS(0).tokens(0)._1 == SELF.tokens(0)._1`), the other input holds no tokens
+        let contract_output = tx.output_candidates.first();
+        let contract_box = ErgoBox::new(
+            contract_output.value,
+            contract_output.ergo_tree.clone(),
+            contract_output.tokens.clone(),
+            NonMandatoryRegisters::empty(),
+            0,
+            TxId::zero(),
+            0,
+        )
+        .unwrap();
+        let change_box = ErgoBox::new(
+            BoxValue::SAFE_USER_MIN,
+            contract_output.ergo_tree.clone(),
+            None,
+            NonMandatoryRegisters::empty(),
+            0,
+            TxId::zero(),
+            1,
+        )
+        .unwrap();
+        // interpreter init (10000) + 2 inputs (2 * 2000) + 4 outputs (4 * 100) + 4 token
+        // accesses (4 * 100): the NFT in the inputs and in the outputs, each charged per token
+        // and per distinct token id
+        assert_eq!(
+            initial_tx_cost(tx, &[&contract_box, &change_box], &Parameters::default()),
+            reduced.tx_cost() as u64
+        );
+    }
 }
@@ -0,0 +1,517 @@
+//! Wallet state: unspent boxes of the tracked scripts and tokens, updated by scanning blocks
+
+mod storage;
+
+pub use storage::*;
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use ergo_chain_types::BlockId;
+use ergotree_ir::chain::address::Address;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::chain::token::TokenAmount;
+use ergotree_ir::chain::token::TokenAmountError;
+use ergotree_ir::chain::token::TokenId;
+use ergotree_ir::chain::tx_id::TxId;
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::serialization::SigmaParsingError;
+use thiserror::Error;
+
+use crate::chain::block::FullBlock;
+use crate::chain::transaction::Transaction;
+
+use super::box_selector::sum_tokens_from_boxes;
+use super::box_selector::sum_value;
+
+/// Number of the last scanned blocks kept to roll back (as many as the node keeps)
+pub const MAX_ROLLBACK_DEPTH: u32 = 200;
+
+/// Tracks the unspent boxes guarded by the tracked scripts or holding the tracked tokens.
+/// Fed with the blocks (in order) and rollbacks, also accounts for the unconfirmed transactions
+/// spending the tracked boxes.
+pub struct WalletState<S: WalletStorage> {
+    storage: S,
+    tracked_trees: Vec<ErgoTree>,
+    tracked_tokens: HashSet<TokenId>,
+    unconfirmed_txs: Vec<Transaction>,
+}
+
+/// Total value and token amounts of the boxes
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct WalletBalance {
+    /// Value in nanoERGs
+    pub value: u64,
+    /// Token amounts
+    pub tokens: HashMap<TokenId, TokenAmount>,
+}
+
+impl<S: WalletStorage> WalletState<S> {
+    /// Create wallet state on top of the storage (tracking nothing)
+    pub fn new(storage: S) -> Self {
+        WalletState {
+            storage,
+            tracked_trees: vec![],
+            tracked_tokens: HashSet::new(),
+            unconfirmed_txs: vec![],
+        }
+    }
+
+    /// Track the boxes guarded by the address script
+    pub fn track_address(&mut self, address: &Address) -> Result<(), WalletStateError> {
+        self.track_ergo_tree(address.script()?);
+        Ok(())
+    }
+
+    /// Track the boxes guarded by the script
+    pub fn track_ergo_tree(&mut self, ergo_tree: ErgoTree) {
+        if !self.tracked_trees.contains(&ergo_tree) {
+            self.tracked_trees.push(ergo_tree);
+        }
+    }
+
+    /// Track the boxes holding the token (regardless of the script guarding them)
+    pub fn track_token(&mut self, token_id: TokenId) {
+        self.tracked_tokens.insert(token_id);
+    }
+
+    /// Returns true if the box is guarded by the tracked script or holds a tracked token
+    pub fn is_tracked(&self, b: &ErgoBox) -> bool {
+        self.tracked_trees.contains(&b.ergo_tree)
+            || b.tokens
+                .iter()
+                .flatten()
+                .any(|t| self.tracked_tokens.contains(&t.token_id))
+    }
+
+    /// Storage of the state
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Height of the last scanned block
+    pub fn height(&self) -> Result<Option<u32>, WalletStateError> {
+        Ok(self.storage.last_block()?.map(|b| b.height))
+    }
+
+    /// Scans the next block: tracked boxes created by the block become unspent, spent ones are
+    /// removed. The unconfirmed transactions spending the same boxes as the block (including the
+    /// ones in the block) are dropped. The block should extend the last scanned block.
+    pub fn apply_block(&mut self, block: &FullBlock) -> Result<(), WalletStateError> {
+        let header = &block.header;
+        if let Some(last) = self.storage.last_block()? {
+            if header.parent_id != last.block_id || header.height != last.height + 1 {
+                return Err(WalletStateError::UnexpectedBlock {
+                    block_id: header.id,
+                    height: header.height,
+                    last_block_id: last.block_id,
+                    last_height: last.height,
+                });
+            }
+        }
+        let unspent: HashMap<BoxId, ErgoBox> = self
+            .storage
+            .unspent_boxes()?
+            .into_iter()
+            .map(|b| (b.box_id(), b))
+            .collect();
+        let mut spent_ids: HashSet<BoxId> = HashSet::new();
+        let mut created_boxes: Vec<ErgoBox> = vec![];
+        let mut spent_boxes: Vec<ErgoBox> = vec![];
+        for tx in block.block_transactions.transactions.iter() {
+            for input in tx.inputs.iter() {
+                spent_ids.insert(input.box_id);
+                if let Some(pos) = created_boxes
+                    .iter()
+                    .position(|b| b.box_id() == input.box_id)
+                {
+                    // created and spent in the same block
+                    created_boxes.remove(pos);
+                } else if let Some(b) = unspent.get(&input.box_id) {
+                    spent_boxes.push(b.clone());
+                }
+            }
+            created_boxes.extend(tx.outputs.iter().filter(|b| self.is_tracked(b)).cloned());
+        }
+        self.unconfirmed_txs
+            .retain(|tx| !tx.inputs.iter().any(|i| spent_ids.contains(&i.box_id)));
+        self.storage.apply_block(
+            ScannedBlock {
+                block_id: header.id,
+                height: header.height,
+                created_boxes,
+                spent_boxes,
+            },
+            (header.height + 1).saturating_sub(MAX_ROLLBACK_DEPTH),
+        )?;
+        Ok(())
+    }
+
+    /// Reverts the scanned blocks above the given height
+    pub fn rollback(&mut self, height: u32) -> Result<(), WalletStateError> {
+        if let Some(first) = self.storage.first_block()? {
+            let min_height = first.height.saturating_sub(1);
+            if height < min_height {
+                return Err(WalletStateError::RollbackTooDeep { height, min_height });
+            }
+        }
+        self.storage.rollback_to(height)?;
+        Ok(())
+    }
+
+    /// Add the unconfirmed transaction, the tracked boxes it spends are no longer spendable and
+    /// its tracked outputs become spendable
+    pub fn add_unconfirmed_tx(&mut self, tx: Transaction) {
+        if !self.unconfirmed_txs.iter().any(|t| t.id() == tx.id()) {
+            self.unconfirmed_txs.push(tx);
+        }
+    }
+
+    /// Remove the unconfirmed transaction (e.g. dropped from the mempool)
+    pub fn remove_unconfirmed_tx(&mut self, tx_id: &TxId) {
+        self.unconfirmed_txs.retain(|tx| tx.id() != *tx_id);
+    }
+
+    /// Unconfirmed transactions (added and not yet included in the scanned blocks)
+    pub fn unconfirmed_txs(&self) -> &[Transaction] {
+        self.unconfirmed_txs.as_slice()
+    }
+
+    /// Confirmed unspent tracked boxes
+    pub fn unspent_boxes(&self) -> Result<Vec<ErgoBox>, WalletStateError> {
+        Ok(self.storage.unspent_boxes()?)
+    }
+
+    /// Boxes guarded by the tracked scripts to select the inputs from with
+    /// [`super::box_selector::BoxSelector`]: confirmed unspent boxes and the outputs of the
+    /// unconfirmed transactions, except the boxes spent by the unconfirmed transactions
+    pub fn spendable_boxes(&self) -> Result<Vec<ErgoBox>, WalletStateError> {
+        let unconfirmed_spent_ids: HashSet<BoxId> = self
+            .unconfirmed_txs
+            .iter()
+            .flat_map(|tx| tx.inputs.iter().map(|i| i.box_id))
+            .collect();
+        let unconfirmed_outputs = self
+            .unconfirmed_txs
+            .iter()
+            .flat_map(|tx| tx.outputs.iter().cloned());
+        Ok(self
+            .storage
+            .unspent_boxes()?
+            .into_iter()
+            .chain(unconfirmed_outputs)
+            .filter(|b| {
+                self.tracked_trees.contains(&b.ergo_tree)
+                    && !unconfirmed_spent_ids.contains(&b.box_id())
+            })
+            .collect())
+    }
+
+    /// Balance of the spendable boxes
+    pub fn balance(&self) -> Result<WalletBalance, WalletStateError> {
+        let boxes = self.spendable_boxes()?;
+        Ok(WalletBalance {
+            value: sum_value(boxes.as_slice()),
+            tokens: sum_tokens_from_boxes(boxes.as_slice())?,
+        })
+    }
+
+    /// Balance of the confirmed unspent boxes (including the ones holding the tracked tokens
+    /// under the scripts that are not tracked)
+    pub fn confirmed_balance(&self) -> Result<WalletBalance, WalletStateError> {
+        let boxes = self.storage.unspent_boxes()?;
+        Ok(WalletBalance {
+            value: sum_value(boxes.as_slice()),
+            tokens: sum_tokens_from_boxes(boxes.as_slice())?,
+        })
+    }
+}
+
+/// Wallet state errors
+#[derive(Error, Debug)]
+pub enum WalletStateError {
+    /// Storage error
+    #[error("Storage error: {0}")]
+    StorageError(#[from] WalletStorageError),
+    /// Address script parsing error
+    #[error("Address script parsing error: {0}")]
+    AddressScriptError(#[from] SigmaParsingError),
+    /// Token amount overflow on balance calculation
+    #[error("Token amount error: {0}")]
+    TokenAmountError(#[from] TokenAmountError),
+    /// Block does not extend the last scanned block
+    #[error("Block {block_id} at height {height} does not extend the last scanned block {last_block_id} at height {last_height}")]
+    UnexpectedBlock {
+        /// Block id
+        block_id: BlockId,
+        /// Block height
+        height: u32,
+        /// Last scanned block id
+        last_block_id: BlockId,
+        /// Last scanned block height
+        last_height: u32,
+    },
+    /// The blocks above the given height are no longer kept to roll back
+    #[error(
+        "Cannot roll back to height {height}, the lowest height to roll back to is {min_height}"
+    )]
+    RollbackTooDeep {
+        /// Height to roll back to
+        height: u32,
+        /// Lowest height to roll back to
+        min_height: u32,
+    },
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::convert::TryInto;
+
+    use ergo_chain_types::Digest32;
+    use ergo_chain_types::Header;
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+    use ergotree_ir::chain::token::Token;
+    use sigma_test_util::force_any_val;
+
+    use crate::chain::block::BlockTransactions;
+    use crate::chain::ergo_box::box_builder::ErgoBoxCandidateBuilder;
+    use crate::chain::transaction::UnsignedInput;
+    use crate::wallet::box_selector::BoxSelector;
+    use crate::wallet::box_selector::SimpleBoxSelector;
+    use crate::wallet::secret_key::SecretKey;
+
+    use super::*;
+
+    fn block_id(n: u8) -> BlockId {
+        BlockId(Digest32::from([n; 32]))
+    }
+
+    /// Block at `height` (its id and the parent id are derived from the height)
+    fn block(height: u32, transactions: Vec<Transaction>) -> FullBlock {
+        let mut header = force_any_val::<Header>();
+        header.id = block_id(height as u8);
+        header.parent_id = block_id(height as u8 - 1);
+        header.height = height;
+        FullBlock {
+            header,
+            block_transactions: BlockTransactions {
+                transactions: transactions.try_into().unwrap(),
+            },
+        }
+    }
+
+    /// Transaction spending the boxes, paying the values to the trees
+    fn tx(inputs: &[ErgoBox], outputs: Vec<(u64, ErgoTree)>) -> Transaction {
+        Transaction::new_from_vec(
+            inputs
+                .iter()
+                .map(|b| UnsignedInput::from(b.clone()).input_to_sign())
+                .collect(),
+            vec![],
+            outputs
+                .into_iter()
+                .map(|(value, tree)| {
+                    ErgoBoxCandidateBuilder::new(value.try_into().unwrap(), tree, 1)
+                        .build()
+                        .unwrap()
+                })
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    fn wallet_tree() -> ErgoTree {
+        SecretKey::random_dlog()
+            .get_address_from_public_image()
+            .script()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_scan_blocks_and_rollback() {
+        let tree = wallet_tree();
+        let other_tree = force_any_val::<ErgoTree>();
+        let mut state = WalletState::new(InMemoryWalletStorage::new());
+        state.track_ergo_tree(tree.clone());
+
+        let funding = force_any_val::<ErgoBox>();
+        let tx1 = tx(
+            &[funding],
+            vec![
+                (1_000_000_000, tree.clone()),
+                (2_000_000_000, other_tree.clone()),
+            ],
+        );
+        state.apply_block(&block(1, vec![tx1.clone()])).unwrap();
+        assert_eq!(state.height().unwrap(), Some(1));
+        assert_eq!(
+            state.unspent_boxes().unwrap(),
+            vec![tx1.outputs.first().clone()]
+        );
+
+        // spends the wallet box, pays back to the wallet
+        let tx2 = tx(
+            &[tx1.outputs.first().clone()],
+            vec![
+                (600_000_000, tree.clone()),
+                (400_000_000, other_tree.clone()),
+            ],
+        );
+        // spends the box created in the same block
+        let tx3 = tx(
+            &[tx2.outputs.first().clone()],
+            vec![(300_000_000, tree.clone()), (300_000_000, tree.clone())],
+        );
+        state
+            .apply_block(&block(2, vec![tx2, tx3.clone()]))
+            .unwrap();
+        assert_eq!(state.unspent_boxes().unwrap(), tx3.outputs.to_vec());
+        assert_eq!(state.balance().unwrap().value, 600_000_000);
+
+        state.rollback(1).unwrap();
+        assert_eq!(state.height().unwrap(), Some(1));
+        assert_eq!(
+            state.unspent_boxes().unwrap(),
+            vec![tx1.outputs.first().clone()]
+        );
+        assert_eq!(state.balance().unwrap().value, 1_000_000_000);
+        state.rollback(0).unwrap();
+        assert_eq!(state.height().unwrap(), None);
+        assert_eq!(state.unspent_boxes().unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_unexpected_block() {
+        let mut state = WalletState::new(InMemoryWalletStorage::new());
+        state.track_ergo_tree(wallet_tree());
+        let funding = force_any_val::<ErgoBox>();
+        state
+            .apply_block(&block(
+                1,
+                vec![tx(&[funding.clone()], vec![(1_000_000, wallet_tree())])],
+            ))
+            .unwrap();
+        assert!(matches!(
+            state.apply_block(&block(
+                3,
+                vec![tx(&[funding], vec![(1_000_000, wallet_tree())])]
+            )),
+            Err(WalletStateError::UnexpectedBlock {
+                height: 3,
+                last_height: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_rollback_too_deep() {
+        let mut state = WalletState::new(InMemoryWalletStorage::new());
+        let funding = force_any_val::<ErgoBox>();
+        for height in 1..=(MAX_ROLLBACK_DEPTH + 10) {
+            let mut b = block(
+                height,
+                vec![tx(&[funding.clone()], vec![(1_000_000, wallet_tree())])],
+            );
+            // ids derived from u8 heights wrap, make them unique
+            let mut id = [0u8; 32];
+            id[..4].copy_from_slice(&height.to_be_bytes());
+            b.header.id = BlockId(Digest32::from(id));
+            id[..4].copy_from_slice(&(height - 1).to_be_bytes());
+            b.header.parent_id = BlockId(Digest32::from(id));
+            state.apply_block(&b).unwrap();
+        }
+        assert_eq!(state.storage().first_block().unwrap().unwrap().height, 11);
+        assert!(matches!(
+            state.rollback(9),
+            Err(WalletStateError::RollbackTooDeep {
+                height: 9,
+                min_height: 10
+            })
+        ));
+        state.rollback(10).unwrap();
+        assert_eq!(state.height().unwrap(), None);
+    }
+
+    #[test]
+    fn test_tracked_tokens() {
+        let token = Token {
+            token_id: TokenId::from(Digest32::from([1; 32])),
+            amount: 100.try_into().unwrap(),
+        };
+        let contract_tree = force_any_val::<ErgoTree>();
+        let token_box = ErgoBox::new(
+            BoxValue::SAFE_USER_MIN,
+            contract_tree,
+            Some(vec![token.clone()].try_into().unwrap()),
+            NonMandatoryRegisters::empty(),
+            1,
+            force_any_val::<TxId>(),
+            0,
+        )
+        .unwrap();
+        let mut state = WalletState::new(InMemoryWalletStorage::new());
+        state.track_token(token.token_id);
+        assert!(state.is_tracked(&token_box));
+        let tx = Transaction::new_from_vec(
+            vec![UnsignedInput::from(force_any_val::<ErgoBox>()).input_to_sign()],
+            vec![],
+            vec![token_box.clone().into()],
+        )
+        .unwrap();
+        state.apply_block(&block(1, vec![tx])).unwrap();
+        let confirmed = state.confirmed_balance().unwrap();
+        assert_eq!(confirmed.tokens.get(&token.token_id), Some(&token.amount));
+        // the box is not guarded by the tracked script
+        assert_eq!(state.spendable_boxes().unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_unconfirmed_spends() {
+        let tree = wallet_tree();
+        let mut state = WalletState::new(InMemoryWalletStorage::new());
+        state.track_ergo_tree(tree.clone());
+        let tx1 = tx(
+            &[force_any_val::<ErgoBox>()],
+            vec![(1_000_000_000, tree.clone()), (1_000_000_000, tree.clone())],
+        );
+        state.apply_block(&block(1, vec![tx1.clone()])).unwrap();
+
+        let box_selection = SimpleBoxSelector::new()
+            .select(
+                state.spendable_boxes().unwrap(),
+                BoxValue::try_from(500_000_000u64).unwrap(),
+                &[],
+            )
+            .unwrap();
+        let spent = box_selection.boxes.as_vec().clone();
+        assert_eq!(spent.len(), 1);
+        let unconfirmed = tx(
+            spent.as_slice(),
+            vec![
+                (500_000_000, tree.clone()),
+                (500_000_000, force_any_val::<ErgoTree>()),
+            ],
+        );
+        state.add_unconfirmed_tx(unconfirmed.clone());
+        let spendable = state.spendable_boxes().unwrap();
+        assert_eq!(spendable.len(), 2);
+        assert!(!spendable.contains(&spent[0]));
+        assert!(spendable.contains(unconfirmed.outputs.first()));
+        assert_eq!(state.balance().unwrap().value, 1_500_000_000);
+        assert_eq!(state.confirmed_balance().unwrap().value, 2_000_000_000);
+
+        state.remove_unconfirmed_tx(&unconfirmed.id());
+        assert_eq!(state.balance().unwrap().value, 2_000_000_000);
+
+        // dropped once included in a block
+        state.add_unconfirmed_tx(unconfirmed.clone());
+        state.apply_block(&block(2, vec![unconfirmed])).unwrap();
+        assert!(state.unconfirmed_txs().is_empty());
+        assert_eq!(state.balance().unwrap().value, 1_500_000_000);
+        assert_eq!(state.confirmed_balance().unwrap().value, 1_500_000_000);
+    }
+}
@@ -0,0 +1,345 @@
+//! Storage of the wallet state (unspent boxes and the scanned blocks to roll back)
+
+use std::collections::VecDeque;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+use std::io;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+use ergo_chain_types::BlockId;
+use ergo_chain_types::Digest32;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::serialization::sigma_byte_reader::SigmaByteRead;
+use ergotree_ir::serialization::sigma_byte_writer::SigmaByteWrite;
+use ergotree_ir::serialization::SigmaParsingError;
+use ergotree_ir::serialization::SigmaSerializable;
+use ergotree_ir::serialization::SigmaSerializationError;
+use ergotree_ir::serialization::SigmaSerializeResult;
+use sigma_ser::ScorexSerializable;
+use thiserror::Error;
+
+/// Changes of the tracked boxes made by a block
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ScannedBlock {
+    /// Block id
+    pub block_id: BlockId,
+    /// Block height
+    pub height: u32,
+    /// Tracked boxes created by the block (and not spent in the same block)
+    pub created_boxes: Vec<ErgoBox>,
+    /// Tracked boxes spent by the block (to restore them on rollback)
+    pub spent_boxes: Vec<ErgoBox>,
+}
+
+/// Storage of the wallet state. Applying a block adds its created boxes to the unspent boxes and
+/// removes its spent boxes, rolling back a block reverts it. Each call is a single change of the
+/// stored state (e.g. one write of the file).
+pub trait WalletStorage {
+    /// Unspent tracked boxes
+    fn unspent_boxes(&self) -> Result<Vec<ErgoBox>, WalletStorageError>;
+    /// The oldest scanned block kept to roll back
+    fn first_block(&self) -> Result<Option<ScannedBlock>, WalletStorageError>;
+    /// The last scanned block
+    fn last_block(&self) -> Result<Option<ScannedBlock>, WalletStorageError>;
+    /// Applies the next block and drops the scanned blocks below `prune_below_height` (they can
+    /// no longer be rolled back)
+    fn apply_block(
+        &mut self,
+        block: ScannedBlock,
+        prune_below_height: u32,
+    ) -> Result<(), WalletStorageError>;
+    /// Reverts the scanned blocks above the given height, returns them (the last one first)
+    fn rollback_to(&mut self, height: u32) -> Result<Vec<ScannedBlock>, WalletStorageError>;
+}
+
+/// Wallet state kept in memory
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct InMemoryWalletStorage {
+    unspent_boxes: Vec<ErgoBox>,
+    blocks: VecDeque<ScannedBlock>,
+}
+
+impl InMemoryWalletStorage {
+    /// Create empty storage
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl WalletStorage for InMemoryWalletStorage {
+    fn unspent_boxes(&self) -> Result<Vec<ErgoBox>, WalletStorageError> {
+        Ok(self.unspent_boxes.clone())
+    }
+
+    fn first_block(&self) -> Result<Option<ScannedBlock>, WalletStorageError> {
+        Ok(self.blocks.front().cloned())
+    }
+
+    fn last_block(&self) -> Result<Option<ScannedBlock>, WalletStorageError> {
+        Ok(self.blocks.back().cloned())
+    }
+
+    fn apply_block(
+        &mut self,
+        block: ScannedBlock,
+        prune_below_height: u32,
+    ) -> Result<(), WalletStorageError> {
+        let spent_ids: Vec<BoxId> = block.spent_boxes.iter().map(|b| b.box_id()).collect();
+        self.unspent_boxes
+            .retain(|b| !spent_ids.contains(&b.box_id()));
+        self.unspent_boxes
+            .extend(block.created_boxes.iter().cloned());
+        self.blocks.push_back(block);
+        self.blocks.retain(|b| b.height >= prune_below_height);
+        Ok(())
+    }
+
+    fn rollback_to(&mut self, height: u32) -> Result<Vec<ScannedBlock>, WalletStorageError> {
+        let mut reverted = vec![];
+        while let Some(block) = self.blocks.pop_back() {
+            if block.height <= height {
+                self.blocks.push_back(block);
+                break;
+            }
+            let created_ids: Vec<BoxId> = block.created_boxes.iter().map(|b| b.box_id()).collect();
+            self.unspent_boxes
+                .retain(|b| !created_ids.contains(&b.box_id()));
+            self.unspent_boxes.extend(block.spent_boxes.iter().cloned());
+            reverted.push(block);
+        }
+        Ok(reverted)
+    }
+}
+
+/// Wallet state kept in memory and saved to a file on every change (once per applied block or
+/// rollback)
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct FileWalletStorage {
+    path: PathBuf,
+    data: InMemoryWalletStorage,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileWalletStorage {
+    /// Loads the state from the file, or starts with the empty state if the file does not exist
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, WalletStorageError> {
+        let path = path.as_ref().to_path_buf();
+        let data = match fs::read(&path) {
+            Ok(bytes) => InMemoryWalletStorage::sigma_parse_bytes(&bytes)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => InMemoryWalletStorage::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(FileWalletStorage { path, data })
+    }
+
+    /// Path of the file
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    /// Writes the state to a temporary file and renames it, so the file is never left half-written
+    fn save(&self) -> Result<(), WalletStorageError> {
+        let bytes = self.data.sigma_serialize_bytes()?;
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl WalletStorage for FileWalletStorage {
+    fn unspent_boxes(&self) -> Result<Vec<ErgoBox>, WalletStorageError> {
+        self.data.unspent_boxes()
+    }
+
+    fn first_block(&self) -> Result<Option<ScannedBlock>, WalletStorageError> {
+        self.data.first_block()
+    }
+
+    fn last_block(&self) -> Result<Option<ScannedBlock>, WalletStorageError> {
+        self.data.last_block()
+    }
+
+    fn apply_block(
+        &mut self,
+        block: ScannedBlock,
+        prune_below_height: u32,
+    ) -> Result<(), WalletStorageError> {
+        self.data.apply_block(block, prune_below_height)?;
+        self.save()
+    }
+
+    fn rollback_to(&mut self, height: u32) -> Result<Vec<ScannedBlock>, WalletStorageError> {
+        let reverted = self.data.rollback_to(height)?;
+        if !reverted.is_empty() {
+            self.save()?;
+        }
+        Ok(reverted)
+    }
+}
+
+fn serialize_boxes<W: SigmaByteWrite>(boxes: &[ErgoBox], w: &mut W) -> SigmaSerializeResult {
+    w.put_usize_as_u32_unwrapped(boxes.len())?;
+    boxes.iter().try_for_each(|b| b.sigma_serialize(w))
+}
+
+fn parse_boxes<R: SigmaByteRead>(r: &mut R) -> Result<Vec<ErgoBox>, SigmaParsingError> {
+    let count = r.get_u32()?;
+    (0..count).map(|_| ErgoBox::sigma_parse(r)).collect()
+}
+
+impl SigmaSerializable for ScannedBlock {
+    fn sigma_serialize<W: SigmaByteWrite>(&self, w: &mut W) -> SigmaSerializeResult {
+        self.block_id.0.scorex_serialize(w)?;
+        w.put_u32(self.height)?;
+        serialize_boxes(&self.created_boxes, w)?;
+        serialize_boxes(&self.spent_boxes, w)
+    }
+
+    fn sigma_parse<R: SigmaByteRead>(r: &mut R) -> Result<Self, SigmaParsingError> {
+        let block_id = BlockId(Digest32::scorex_parse(r)?);
+        let height = r.get_u32()?;
+        let created_boxes = parse_boxes(r)?;
+        let spent_boxes = parse_boxes(r)?;
+        Ok(ScannedBlock {
+            block_id,
+            height,
+            created_boxes,
+            spent_boxes,
+        })
+    }
+}
+
+impl SigmaSerializable for InMemoryWalletStorage {
+    fn sigma_serialize<W: SigmaByteWrite>(&self, w: &mut W) -> SigmaSerializeResult {
+        serialize_boxes(&self.unspent_boxes, w)?;
+        w.put_usize_as_u32_unwrapped(self.blocks.len())?;
+        self.blocks.iter().try_for_each(|b| b.sigma_serialize(w))
+    }
+
+    fn sigma_parse<R: SigmaByteRead>(r: &mut R) -> Result<Self, SigmaParsingError> {
+        let unspent_boxes = parse_boxes(r)?;
+        let blocks_count = r.get_u32()?;
+        let blocks = (0..blocks_count)
+            .map(|_| ScannedBlock::sigma_parse(r))
+            .collect::<Result<VecDeque<ScannedBlock>, SigmaParsingError>>()?;
+        Ok(InMemoryWalletStorage {
+            unspent_boxes,
+            blocks,
+        })
+    }
+}
+
+/// Wallet storage errors
+#[derive(Error, Debug)]
+pub enum WalletStorageError {
+    /// IO error
+    #[error("IO error: {0}")]
+    IoError(#[from] io::Error),
+    /// Serialization error
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] SigmaSerializationError),
+    /// Parsing error
+    #[error("Parsing error: {0}")]
+    ParsingError(#[from] SigmaParsingError),
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    #[cfg(not(target_arch = "wasm32"))]
+    use std::time::SystemTime;
+    #[cfg(not(target_arch = "wasm32"))]
+    use std::time::UNIX_EPOCH;
+
+    use sigma_test_util::force_any_val;
+
+    use super::*;
+
+    fn scanned_block(
+        height: u32,
+        created_boxes: Vec<ErgoBox>,
+        spent_boxes: Vec<ErgoBox>,
+    ) -> ScannedBlock {
+        ScannedBlock {
+            block_id: BlockId(force_any_val::<Digest32>()),
+            height,
+            created_boxes,
+            spent_boxes,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn temp_file_path() -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!(
+            "ergo-wallet-state-{}-{}",
+            std::process::id(),
+            nanos
+        ))
+    }
+
+    #[test]
+    fn test_apply_rollback() {
+        let b1 = force_any_val::<ErgoBox>();
+        let b2 = force_any_val::<ErgoBox>();
+        let mut storage = InMemoryWalletStorage::new();
+        storage
+            .apply_block(scanned_block(1, vec![b1.clone()], vec![]), 0)
+            .unwrap();
+        storage
+            .apply_block(scanned_block(2, vec![b2.clone()], vec![b1.clone()]), 0)
+            .unwrap();
+        assert_eq!(storage.unspent_boxes().unwrap(), vec![b2.clone()]);
+        let reverted = storage.rollback_to(1).unwrap();
+        assert_eq!(
+            reverted.iter().map(|b| b.height).collect::<Vec<_>>(),
+            vec![2]
+        );
+        assert_eq!(storage.unspent_boxes().unwrap(), vec![b1.clone()]);
+        storage
+            .apply_block(scanned_block(2, vec![b2], vec![b1]), 2)
+            .unwrap();
+        assert_eq!(storage.first_block().unwrap().unwrap().height, 2);
+        assert_eq!(storage.rollback_to(0).unwrap().len(), 1);
+        assert_eq!(storage.rollback_to(0).unwrap(), vec![]);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_file_storage_reopen() {
+        let path = temp_file_path();
+        let mut storage = FileWalletStorage::open(&path).unwrap();
+        assert_eq!(storage.unspent_boxes().unwrap(), vec![]);
+        storage
+            .apply_block(
+                scanned_block(1, vec![force_any_val::<ErgoBox>()], vec![]),
+                0,
+            )
+            .unwrap();
+        storage
+            .apply_block(
+                scanned_block(
+                    2,
+                    vec![force_any_val::<ErgoBox>()],
+                    vec![force_any_val::<ErgoBox>()],
+                ),
+                0,
+            )
+            .unwrap();
+        let reopened = FileWalletStorage::open(&path).unwrap();
+        assert_eq!(reopened, storage);
+        assert_eq!(reopened.last_block().unwrap().unwrap().height, 2);
+        fs::remove_file(&path).unwrap();
+    }
+}
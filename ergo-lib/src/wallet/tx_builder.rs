@@ -23,15 +23,19 @@ use thiserror::Error;
 
 use crate::chain::contract::Contract;
 use crate::chain::ergo_box::box_builder::{ErgoBoxCandidateBuilder, ErgoBoxCandidateBuilderError};
+use crate::chain::parameters::Parameters;
 use crate::chain::transaction::unsigned::UnsignedTransaction;
 use crate::chain::transaction::{DataInput, Input, Transaction, UnsignedInput};
 
+use super::box_selector::estimate_change_box_size;
+use super::box_selector::split_change;
 use super::box_selector::subtract_tokens;
 use super::box_selector::sum_tokens_from_boxes;
 use super::box_selector::sum_value;
 use super::box_selector::BoxSelection;
 use super::box_selector::ErgoBoxAssets;
 use super::box_selector::ErgoBoxId;
+use super::box_selector::NotEnoughCoinsForChangeBox;
 use super::miner_fee::MINERS_FEE_BASE16_BYTES;
 
 /// Unsigned transaction builder
@@ -170,23 +174,30 @@ impl<S: ErgoBoxAssets + ErgoBoxId + Clone> TxBuilder<S> {
 
         let mut output_candidates = self.output_candidates.clone();
         let change_address_ergo_tree = Contract::pay_to_address(&self.change_address)?.ergo_tree();
-        let change_boxes: Result<Vec<ErgoBoxCandidate>, ErgoBoxCandidateBuilderError> = self
-            .box_selection
-            .change_boxes
-            .iter()
-            .map(|b| {
+        let change_ergo_tree_size = change_address_ergo_tree.sigma_serialize_bytes()?.len();
+        for b in &self.box_selection.change_boxes {
+            let tokens: Vec<Token> = b.tokens().into_iter().flatten().collect();
+            // box selectors estimate the change box size for a P2PK address,
+            // split the change further if it's too large with the actual change address
+            let change_boxes = if estimate_change_box_size(&tokens, change_ergo_tree_size)
+                > Parameters::MAX_BOX_SIZE
+            {
+                split_change(b.value, tokens, change_ergo_tree_size, BoxValue::MIN)?
+            } else {
+                vec![b.clone()]
+            };
+            for change_box in change_boxes {
                 let mut candidate = ErgoBoxCandidateBuilder::new(
-                    b.value,
+                    change_box.value,
                     change_address_ergo_tree.clone(),
                     self.current_height,
                 );
-                for token in b.tokens().into_iter().flatten() {
-                    candidate.add_token(token.clone());
+                for token in change_box.tokens().into_iter().flatten() {
+                    candidate.add_token(token);
                 }
-                candidate.build()
-            })
-            .collect();
-        output_candidates.append(&mut change_boxes?);
+                output_candidates.push(candidate.build()?);
+            }
+        }
 
         // add miner's fee
         let miner_fee_box = new_miner_fee_box(self.fee_amount, self.current_height)?;
@@ -314,6 +325,8 @@ pub enum TxBuilderError {
     TokensInOutputsExceedInputs(TokenAmountError),
     #[error("Coins in outputs are less than coins in inputs for {0} nanoERGs")]
     NotEnoughCoinsInOutputs(u64),
+    #[error("Not enough coins for change box(es): {0:?}")]
+    NotEnoughCoinsForChangeBox(#[from] NotEnoughCoinsForChangeBox),
 }
 
 /// Sums up the tokens into a hash map
@@ -381,7 +394,11 @@ mod tests {
 
     use std::convert::TryInto;
 
+    use ergo_chain_types::Digest32;
+    use ergotree_ir::chain::address::AddressEncoder;
+    use ergotree_ir::chain::address::NetworkPrefix;
     use ergotree_ir::chain::ergo_box::box_value::checked_sum;
+    use ergotree_ir::chain::ergo_box::BoxTokens;
     use ergotree_ir::chain::ergo_box::ErgoBox;
     use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
     use ergotree_ir::chain::token::arbitrary::ArbTokenIdParam;
@@ -392,7 +409,7 @@ mod tests {
     use sigma_test_util::force_any_val;
     use sigma_test_util::force_any_val_with;
 
-    use crate::wallet::box_selector::{BoxSelector, SimpleBoxSelector};
+    use crate::wallet::box_selector::{BoxSelector, ErgoBoxAssetsData, SimpleBoxSelector};
 
     use super::*;
 
@@ -574,6 +591,58 @@ mod tests {
         let _ = tx_builder.build().unwrap();
     }
 
+    #[test]
+    fn test_split_change_over_max_box_size() {
+        // largest token amounts, so the change box exceeds the max. box size before the max. tokens count
+        let tokens: Vec<Token> = (0..100u8)
+            .map(|i| Token {
+                token_id: TokenId::from(Digest32::from([i; 32])),
+                amount: TokenAmount::MAX_RAW.try_into().unwrap(),
+            })
+            .collect();
+        let input_box = ErgoBox::new(
+            BoxValue::SAFE_USER_MIN.checked_mul_u32(10).unwrap(),
+            force_any_val::<ErgoTree>(),
+            BoxTokens::from_vec(tokens.clone()).ok(),
+            NonMandatoryRegisters::empty(),
+            1,
+            force_any_val::<TxId>(),
+            0,
+        )
+        .unwrap();
+        let tx_fee = BoxValue::SAFE_USER_MIN;
+        let out_box_value = BoxValue::SAFE_USER_MIN;
+        // single change box holding all the tokens
+        let box_selection = BoxSelection {
+            boxes: vec![input_box].try_into().unwrap(),
+            change_boxes: vec![ErgoBoxAssetsData {
+                value: BoxValue::SAFE_USER_MIN.checked_mul_u32(8).unwrap(),
+                tokens: BoxTokens::from_vec(tokens.clone()).ok(),
+            }],
+        };
+        let out_box = ErgoBoxCandidateBuilder::new(out_box_value, force_any_val::<ErgoTree>(), 0)
+            .build()
+            .unwrap();
+        let change_address = AddressEncoder::new(NetworkPrefix::Mainnet)
+            .parse_address_from_str("9gmNsqrqdSppLUBqg2UzREmmivgqh1r3jmNcLAc53hk3YCvAGWE")
+            .unwrap();
+        let tx = TxBuilder::new(box_selection, vec![out_box], 0, tx_fee, change_address)
+            .build()
+            .unwrap();
+        // output, two change boxes and the miner's fee
+        assert_eq!(tx.output_candidates.len(), 4);
+        assert!(tx
+            .output_candidates
+            .iter()
+            .all(|b| b.sigma_serialize_bytes().unwrap().len() < Parameters::MAX_BOX_SIZE));
+        assert_eq!(
+            sum_tokens_from_boxes(tx.output_candidates.as_slice())
+                .unwrap()
+                .len(),
+            tokens.len()
+        );
+    }
+
     #[test]
     fn test_token_burn_permit_wo_burn() {
         let token_pair = Token {
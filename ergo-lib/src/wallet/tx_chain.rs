@@ -0,0 +1,258 @@
+//! Chain of unconfirmed transactions (later transactions spend the outputs of the earlier ones)
+
+use ergotree_interpreter::sigma_protocol::prover::Prover;
+use ergotree_ir::chain::ergo_box::BoxId;
+use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::serialization::SigmaParsingError;
+use thiserror::Error;
+
+use crate::chain::ergo_state_context::ErgoStateContext;
+use crate::chain::transaction::unsigned::UnsignedTransaction;
+use crate::chain::transaction::Transaction;
+
+use super::miner_fee::MINERS_FEE_ADDRESS;
+use super::signing::sign_transaction;
+use super::signing::ErgoTransaction;
+use super::signing::TransactionContext;
+use super::signing::TxSigningError;
+use super::tx_context::TransactionContextError;
+
+/// Builder of a chain of unconfirmed transactions. Keeps the set of the spendable boxes: the
+/// boxes spent by an added transaction are removed from it and the outputs of the transaction
+/// (except the miner's fee box) are added to it, so the next transactions can spend them.
+/// The ids of the outputs are computed with [`UnsignedTransaction::id`], which is the id of the
+/// signed transaction as well.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TxChainBuilder {
+    spendable_boxes: Vec<ErgoBox>,
+    tx_contexts: Vec<TransactionContext<UnsignedTransaction>>,
+}
+
+impl TxChainBuilder {
+    /// Create new builder, `spendable_boxes` - confirmed boxes the chain can spend
+    pub fn new(spendable_boxes: Vec<ErgoBox>) -> Self {
+        TxChainBuilder {
+            spendable_boxes,
+            tx_contexts: vec![],
+        }
+    }
+
+    /// Boxes the next transaction can spend (i.e. to select the inputs from with
+    /// [`super::box_selector::BoxSelector`])
+    pub fn spendable_boxes(&self) -> &[ErgoBox] {
+        self.spendable_boxes.as_slice()
+    }
+
+    /// Transactions added so far
+    pub fn transactions(&self) -> Vec<UnsignedTransaction> {
+        self.tx_contexts
+            .iter()
+            .map(|ctx| ctx.spending_tx.clone())
+            .collect()
+    }
+
+    /// Add the next transaction of the chain, its inputs should be among
+    /// [`TxChainBuilder::spendable_boxes`]. `data_boxes` - boxes of the transaction data inputs
+    /// (can be the outputs of the previous transactions).
+    /// Returns the outputs of the transaction.
+    pub fn add_tx(
+        &mut self,
+        tx: UnsignedTransaction,
+        data_boxes: Vec<ErgoBox>,
+    ) -> Result<Vec<ErgoBox>, TxChainError> {
+        let boxes_to_spend = tx
+            .inputs
+            .iter()
+            .map(|input| {
+                self.spendable_boxes
+                    .iter()
+                    .find(|b| b.box_id() == input.box_id)
+                    .cloned()
+                    .ok_or(TxChainError::InputBoxNotSpendable(input.box_id))
+            })
+            .collect::<Result<Vec<ErgoBox>, TxChainError>>()?;
+        let tx_context = TransactionContext::new(tx, boxes_to_spend, data_boxes)?;
+        let outputs = tx_context.spending_tx.outputs().to_vec();
+        let spent_ids = tx_context.spending_tx.inputs_ids();
+        self.spendable_boxes
+            .retain(|b| !spent_ids.iter().any(|id| *id == b.box_id()));
+        let miners_fee_tree = MINERS_FEE_ADDRESS.script()?;
+        self.spendable_boxes.extend(
+            outputs
+                .iter()
+                .filter(|b| b.ergo_tree != miners_fee_tree)
+                .cloned(),
+        );
+        self.tx_contexts.push(tx_context);
+        Ok(outputs)
+    }
+
+    /// Transactions with their input and data input boxes (to sign)
+    pub fn build(self) -> Vec<TransactionContext<UnsignedTransaction>> {
+        self.tx_contexts
+    }
+}
+
+/// Signs the chain of transactions (in order), each with its own transaction context
+pub fn sign_tx_chain(
+    prover: &dyn Prover,
+    tx_chain: Vec<TransactionContext<UnsignedTransaction>>,
+    state_context: &ErgoStateContext,
+) -> Result<Vec<Transaction>, TxChainError> {
+    tx_chain
+        .into_iter()
+        .enumerate()
+        .map(|(tx_index, tx_context)| {
+            sign_transaction(prover, tx_context, state_context, None)
+                .map_err(|error| TxChainError::SigningError { tx_index, error })
+        })
+        .collect()
+}
+
+/// Errors of the transaction chain building and signing
+#[derive(Error, Debug)]
+pub enum TxChainError {
+    /// Input box is not among the spendable boxes (not available or spent by another transaction)
+    #[error("Input box {0:?} is not spendable")]
+    InputBoxNotSpendable(BoxId),
+    /// Transaction context error
+    #[error("TransactionContextError: {0}")]
+    TransactionContextError(#[from] TransactionContextError),
+    /// Miner's fee script parsing error
+    #[error("Miner's fee script parsing error: {0}")]
+    MinersFeeScriptError(#[from] SigmaParsingError),
+    /// Error on signing a transaction of the chain
+    #[error("Transaction {tx_index} signing error: {error}")]
+    SigningError {
+        /// Index of the transaction in the chain
+        tx_index: usize,
+        /// Signing error
+        error: TxSigningError,
+    },
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic)]
+mod tests {
+    use ergotree_ir::chain::address::Address;
+    use ergotree_ir::chain::ergo_box::box_value::BoxValue;
+    use ergotree_ir::chain::ergo_box::NonMandatoryRegisters;
+    use ergotree_ir::chain::tx_id::TxId;
+    use sigma_test_util::force_any_val;
+
+    use crate::chain::ergo_box::box_builder::ErgoBoxCandidateBuilder;
+    use crate::chain::transaction::verify_tx_input;
+    use crate::wallet::box_selector::BoxSelector;
+    use crate::wallet::box_selector::SimpleBoxSelector;
+    use crate::wallet::secret_key::SecretKey;
+    use crate::wallet::tx_builder::TxBuilder;
+    use crate::wallet::Wallet;
+
+    use super::*;
+
+    /// Pays `value` to `address` from the spendable boxes of the chain, the change goes back to
+    /// `address`
+    fn pay(chain: &TxChainBuilder, address: &Address, value: BoxValue) -> UnsignedTransaction {
+        let fee = BoxValue::SAFE_USER_MIN;
+        let box_selection = SimpleBoxSelector::new()
+            .select(
+                chain.spendable_boxes().to_vec(),
+                value.checked_add(&fee).unwrap(),
+                &[],
+            )
+            .unwrap();
+        let output = ErgoBoxCandidateBuilder::new(value, address.script().unwrap(), 1)
+            .build()
+            .unwrap();
+        TxBuilder::new(box_selection, vec![output], 1, fee, address.clone())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_sign_tx_chain() {
+        let secret = SecretKey::random_dlog();
+        let address = secret.get_address_from_public_image();
+        let input_box = ErgoBox::new(
+            BoxValue::SAFE_USER_MIN.checked_mul_u32(10).unwrap(),
+            address.script().unwrap(),
+            None,
+            NonMandatoryRegisters::empty(),
+            1,
+            force_any_val::<TxId>(),
+            0,
+        )
+        .unwrap();
+        let mut chain = TxChainBuilder::new(vec![input_box.clone()]);
+        let tx1 = pay(&chain, &address, BoxValue::SAFE_USER_MIN);
+        let tx1_outputs = chain.add_tx(tx1.clone(), vec![]).unwrap();
+        assert!(tx1_outputs.iter().all(|b| b.transaction_id == tx1.id()));
+        // the miner's fee box is not spendable, the rest of the outputs are
+        assert_eq!(chain.spendable_boxes().len(), tx1_outputs.len() - 1);
+        assert!(!chain
+            .spendable_boxes()
+            .iter()
+            .any(|b| b.box_id() == input_box.box_id()));
+
+        let tx2 = pay(&chain, &address, BoxValue::SAFE_USER_MIN);
+        assert!(tx2
+            .inputs
+            .iter()
+            .all(|i| tx1_outputs.iter().any(|b| b.box_id() == i.box_id)));
+        chain.add_tx(tx2.clone(), vec![]).unwrap();
+        assert_eq!(chain.transactions(), vec![tx1, tx2]);
+
+        let tx_contexts = chain.build();
+        let state_context = force_any_val::<ErgoStateContext>();
+        let wallet = Wallet::from_secrets(vec![secret]);
+        let signed = wallet
+            .sign_tx_chain(tx_contexts.clone(), &state_context)
+            .unwrap();
+        assert_eq!(signed.len(), 2);
+        for (tx, unsigned_ctx) in signed.iter().zip(tx_contexts) {
+            assert_eq!(tx.id(), unsigned_ctx.spending_tx.id());
+            let boxes_to_spend = tx
+                .inputs
+                .iter()
+                .map(|i| unsigned_ctx.get_input_box(&i.box_id).unwrap())
+                .collect();
+            let tx_context = TransactionContext::new(tx.clone(), boxes_to_spend, vec![]).unwrap();
+            for idx in 0..tx.inputs.len() {
+                assert!(
+                    verify_tx_input(&tx_context, &state_context, idx)
+                        .unwrap()
+                        .result
+                );
+            }
+        }
+        // the second transaction spends the outputs of the signed first one
+        assert!(signed[1]
+            .inputs
+            .iter()
+            .all(|i| signed[0].outputs.iter().any(|b| b.box_id() == i.box_id)));
+    }
+
+    #[test]
+    fn test_double_spend() {
+        let secret = SecretKey::random_dlog();
+        let address = secret.get_address_from_public_image();
+        let input_box = ErgoBox::new(
+            BoxValue::SAFE_USER_MIN.checked_mul_u32(10).unwrap(),
+            address.script().unwrap(),
+            None,
+            NonMandatoryRegisters::empty(),
+            1,
+            force_any_val::<TxId>(),
+            0,
+        )
+        .unwrap();
+        let mut chain = TxChainBuilder::new(vec![input_box.clone()]);
+        let tx = pay(&chain, &address, BoxValue::SAFE_USER_MIN);
+        chain.add_tx(tx.clone(), vec![]).unwrap();
+        assert!(matches!(
+            chain.add_tx(tx, vec![]),
+            Err(TxChainError::InputBoxNotSpendable(id)) if id == input_box.box_id()
+        ));
+        assert_eq!(chain.transactions().len(), 1);
+    }
+}
@@ -75,6 +75,15 @@ impl<T: ErgoTransaction> TransactionContext<T> {
             .find(|b| b.box_id() == *box_id)
             .cloned()
     }
+
+    /// Boxes spent by the transaction inputs, in the inputs order
+    pub(crate) fn input_boxes(&self) -> Vec<&ErgoBox> {
+        self.spending_tx
+            .inputs_ids()
+            .iter()
+            .filter_map(|box_id| self.boxes_to_spend.iter().find(|b| b.box_id() == *box_id))
+            .collect()
+    }
 }
 
 /// Transaction context errors
@@ -1,5 +1,3 @@
-use crate::error::pretty_error_desc;
-
 use super::syntax::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
 use text_size::TextRange;
 
@@ -13,10 +11,6 @@ impl AstError {
     pub fn new(msg: String, span: TextRange) -> Self {
         AstError { msg, span }
     }
-
-    pub fn pretty_desc(&self, source: &str) -> String {
-        pretty_error_desc(source, self.span, &self.msg)
-    }
 }
 
 #[derive(Debug)]
@@ -31,8 +25,50 @@ impl Root {
         }
     }
 
-    pub fn children(&self) -> impl Iterator<Item = Expr> {
-        self.0.children().filter_map(Expr::cast)
+    pub fn stmts(&self) -> impl Iterator<Item = Stmt> {
+        self.0.children().filter_map(Stmt::cast)
+    }
+
+    pub fn span(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
+
+#[derive(Debug)]
+pub enum Stmt {
+    VariableDef(VariableDef),
+    Expr(Expr),
+}
+
+impl Stmt {
+    pub fn cast(node: SyntaxNode) -> Option<Self> {
+        if node.kind() == SyntaxKind::VariableDef {
+            Some(Self::VariableDef(VariableDef(node)))
+        } else {
+            Expr::cast(node).map(Self::Expr)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct VariableDef(SyntaxNode);
+
+impl VariableDef {
+    pub fn name(&self) -> Result<SyntaxToken, AstError> {
+        ident_token(&self.0)
+    }
+
+    pub fn type_ref(&self) -> Option<TypeRef> {
+        self.0.children().find_map(TypeRef::cast)
+    }
+
+    pub fn rhs(&self) -> Result<Expr, AstError> {
+        self.0.children().find_map(Expr::cast).ok_or_else(|| {
+            AstError::new(
+                format!("Cannot find rhs in {:?}", self.0.children()),
+                self.span(),
+            )
+        })
     }
 
     pub fn span(&self) -> TextRange {
@@ -45,11 +81,7 @@ pub struct Ident(SyntaxNode);
 
 impl Ident {
     pub fn name(&self) -> Result<SyntaxToken, AstError> {
-        self.0
-            .children_with_tokens()
-            .filter_map(SyntaxElement::into_token)
-            .find(|token| token.kind() == SyntaxKind::Ident)
-            .ok_or_else(|| AstError::new(format!("Empty Ident.name in: {:?}", self.0), self.span()))
+        ident_token(&self.0)
     }
 
     pub fn span(&self) -> TextRange {
@@ -57,14 +89,33 @@ impl Ident {
     }
 }
 
+fn ident_token(node: &SyntaxNode) -> Result<SyntaxToken, AstError> {
+    node.children_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .find(|token| token.kind() == SyntaxKind::Ident)
+        .ok_or_else(|| {
+            AstError::new(
+                format!("Empty Ident.name in: {:?}", node),
+                node.text_range(),
+            )
+        })
+}
+
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug)]
 pub enum Expr {
     Ident(Ident),
     BinaryExpr(BinaryExpr),
     Literal(Literal),
-    // ParenExpr(ParenExpr),
-    // UnaryExpr(UnaryExpr),
+    ParenExpr(ParenExpr),
+    BlockExpr(BlockExpr),
+    LambdaExpr(LambdaExpr),
+    SelectExpr(SelectExpr),
+    CallExpr(CallExpr),
+    ApplyTypesExpr(ApplyTypesExpr),
+    UnaryExpr(UnaryExpr),
+    IfExpr(IfExpr),
+    TupleExpr(TupleExpr),
 }
 
 impl Expr {
@@ -74,20 +125,40 @@ impl Expr {
             SyntaxKind::InfixExpr => Self::BinaryExpr(BinaryExpr(node)),
             SyntaxKind::IntNumber => Self::Literal(Literal(node)),
             SyntaxKind::LongNumber => Self::Literal(Literal(node)),
-            // SyntaxKind::ParenExpr => Self::ParenExpr(ParenExpr(node)),
-            // SyntaxKind::PrefixExpr => Self::UnaryExpr(UnaryExpr(node)),
+            SyntaxKind::TrueKw => Self::Literal(Literal(node)),
+            SyntaxKind::FalseKw => Self::Literal(Literal(node)),
+            SyntaxKind::StringLiteral => Self::Literal(Literal(node)),
+            SyntaxKind::ParenExpr => Self::ParenExpr(ParenExpr(node)),
+            SyntaxKind::BlockExpr => Self::BlockExpr(BlockExpr(node)),
+            SyntaxKind::LambdaExpr => Self::LambdaExpr(LambdaExpr(node)),
+            SyntaxKind::SelectExpr => Self::SelectExpr(SelectExpr(node)),
+            SyntaxKind::CallExpr => Self::CallExpr(CallExpr(node)),
+            SyntaxKind::ApplyTypesExpr => Self::ApplyTypesExpr(ApplyTypesExpr(node)),
+            SyntaxKind::PrefixExpr => Self::UnaryExpr(UnaryExpr(node)),
+            SyntaxKind::IfExpr => Self::IfExpr(IfExpr(node)),
+            SyntaxKind::TupleExpr => Self::TupleExpr(TupleExpr(node)),
             _ => return None,
         };
 
         Some(result)
     }
 
-    // pub fn span(&self) -> TextRange {
-    //     match self {
-    //         Expr::Ident(node) => node.0.text_range(),
-    //         _ => todo!(),
-    //     }
-    // }
+    pub fn span(&self) -> TextRange {
+        match self {
+            Expr::Ident(node) => node.span(),
+            Expr::BinaryExpr(node) => node.span(),
+            Expr::Literal(node) => node.span(),
+            Expr::ParenExpr(node) => node.span(),
+            Expr::BlockExpr(node) => node.span(),
+            Expr::LambdaExpr(node) => node.span(),
+            Expr::SelectExpr(node) => node.span(),
+            Expr::CallExpr(node) => node.span(),
+            Expr::ApplyTypesExpr(node) => node.span(),
+            Expr::UnaryExpr(node) => node.span(),
+            Expr::IfExpr(node) => node.span(),
+            Expr::TupleExpr(node) => node.span(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -127,7 +198,14 @@ impl BinaryExpr {
                         | SyntaxKind::Minus
                         | SyntaxKind::Star
                         | SyntaxKind::Slash
-                        | SyntaxKind::And,
+                        | SyntaxKind::And
+                        | SyntaxKind::Or
+                        | SyntaxKind::DoubleEquals
+                        | SyntaxKind::NotEquals
+                        | SyntaxKind::Less
+                        | SyntaxKind::LessEquals
+                        | SyntaxKind::Greater
+                        | SyntaxKind::GreaterEquals,
                 )
             })
             .ok_or_else(|| {
@@ -147,6 +225,8 @@ impl BinaryExpr {
 pub enum LiteralValue {
     Int(i32),
     Long(i64),
+    Bool(bool),
+    Str(String),
 }
 
 #[derive(Debug)]
@@ -154,8 +234,17 @@ pub struct Literal(SyntaxNode);
 
 impl Literal {
     pub fn parse(&self) -> Result<LiteralValue, AstError> {
+        match self.0.kind() {
+            SyntaxKind::TrueKw => return Ok(LiteralValue::Bool(true)),
+            SyntaxKind::FalseKw => return Ok(LiteralValue::Bool(false)),
+            _ => (),
+        }
         let text = self.0.first_token().unwrap().text().to_string();
-        if text.ends_with('L') {
+        if self.0.kind() == SyntaxKind::StringLiteral {
+            text.strip_prefix('"')
+                .and_then(|t| t.strip_suffix('"'))
+                .map(|t| LiteralValue::Str(t.to_string()))
+        } else if text.ends_with('L') {
             text.strip_suffix('L')
                 .unwrap()
                 .parse()
@@ -177,27 +266,280 @@ impl Literal {
     }
 }
 
-// #[derive(Debug)]
-// pub struct ParenExpr(SyntaxNode);
+#[derive(Debug)]
+pub struct ParenExpr(SyntaxNode);
 
-// impl ParenExpr {
-//     pub fn expr(&self) -> Option<Expr> {
-//         self.0.children().find_map(Expr::cast)
-//     }
-// }
+impl ParenExpr {
+    pub fn expr(&self) -> Result<Expr, AstError> {
+        self.0.children().find_map(Expr::cast).ok_or_else(|| {
+            AstError::new(
+                format!("Empty parentheses: {:?}", self.0),
+                self.0.text_range(),
+            )
+        })
+    }
 
-// #[derive(Debug)]
-// pub struct UnaryExpr(SyntaxNode);
+    pub fn span(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
 
-// impl UnaryExpr {
-//     pub fn expr(&self) -> Option<Expr> {
-//         self.0.children().find_map(Expr::cast)
-//     }
+/// Tuple literal `(a, b, ...)`
+#[derive(Debug)]
+pub struct TupleExpr(SyntaxNode);
 
-//     pub fn op(&self) -> Option<SyntaxToken> {
-//         self.0
-//             .children_with_tokens()
-//             .filter_map(SyntaxElement::into_token)
-//             .find(|token| token.kind() == SyntaxKind::Minus)
-//     }
-// }
+impl TupleExpr {
+    pub fn items(&self) -> impl Iterator<Item = Expr> {
+        self.0.children().filter_map(Expr::cast)
+    }
+
+    pub fn span(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
+
+/// Block of statements (in braces, or the body of a lambda in braces)
+#[derive(Debug)]
+pub struct BlockExpr(SyntaxNode);
+
+impl BlockExpr {
+    pub fn stmts(&self) -> impl Iterator<Item = Stmt> {
+        self.0.children().filter_map(Stmt::cast)
+    }
+
+    pub fn span(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
+
+#[derive(Debug)]
+pub struct LambdaExpr(SyntaxNode);
+
+impl LambdaExpr {
+    pub fn params(&self) -> impl Iterator<Item = Param> {
+        self.0
+            .children()
+            .filter(|node| node.kind() == SyntaxKind::ParamList)
+            .flat_map(|node| node.children())
+            .filter(|node| node.kind() == SyntaxKind::Param)
+            .map(Param)
+    }
+
+    pub fn body(&self) -> Result<Expr, AstError> {
+        self.0.children().find_map(Expr::cast).ok_or_else(|| {
+            AstError::new(
+                format!("Cannot find lambda body in {:?}", self.0),
+                self.0.text_range(),
+            )
+        })
+    }
+
+    pub fn span(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
+
+#[derive(Debug)]
+pub struct Param(SyntaxNode);
+
+impl Param {
+    pub fn name(&self) -> Result<SyntaxToken, AstError> {
+        ident_token(&self.0)
+    }
+
+    pub fn type_ref(&self) -> Result<TypeRef, AstError> {
+        self.0.children().find_map(TypeRef::cast).ok_or_else(|| {
+            AstError::new(
+                format!("Cannot find parameter type in {:?}", self.0),
+                self.0.text_range(),
+            )
+        })
+    }
+}
+
+/// Field or method selection `obj.name`
+#[derive(Debug)]
+pub struct SelectExpr(SyntaxNode);
+
+impl SelectExpr {
+    pub fn obj(&self) -> Result<Expr, AstError> {
+        self.0.children().find_map(Expr::cast).ok_or_else(|| {
+            AstError::new(
+                format!("Cannot find selection object in {:?}", self.0),
+                self.0.text_range(),
+            )
+        })
+    }
+
+    pub fn field(&self) -> Result<SyntaxToken, AstError> {
+        ident_token(&self.0)
+    }
+
+    pub fn span(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
+
+/// Application `f(a, b)` (function call, method call or collection indexing)
+#[derive(Debug)]
+pub struct CallExpr(SyntaxNode);
+
+impl CallExpr {
+    pub fn func(&self) -> Result<Expr, AstError> {
+        self.0.children().find_map(Expr::cast).ok_or_else(|| {
+            AstError::new(
+                format!("Cannot find applied expression in {:?}", self.0),
+                self.0.text_range(),
+            )
+        })
+    }
+
+    pub fn args(&self) -> impl Iterator<Item = Expr> {
+        self.0
+            .children()
+            .filter(|node| node.kind() == SyntaxKind::ArgList)
+            .flat_map(|node| node.children())
+            .filter_map(Expr::cast)
+    }
+
+    pub fn span(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
+
+/// Explicit type arguments `e[T]`, i.e. `SELF.R4[Int]`
+#[derive(Debug)]
+pub struct ApplyTypesExpr(SyntaxNode);
+
+impl ApplyTypesExpr {
+    pub fn expr(&self) -> Result<Expr, AstError> {
+        self.0.children().find_map(Expr::cast).ok_or_else(|| {
+            AstError::new(
+                format!("Cannot find expression in {:?}", self.0),
+                self.0.text_range(),
+            )
+        })
+    }
+
+    pub fn type_args(&self) -> impl Iterator<Item = TypeRef> {
+        self.0
+            .children()
+            .filter(|node| node.kind() == SyntaxKind::TypeArgList)
+            .flat_map(|node| node.children())
+            .filter_map(TypeRef::cast)
+    }
+
+    pub fn span(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
+
+/// Type reference, i.e. `Int`, `Coll[Byte]` or `(Int, Long)`
+#[derive(Debug)]
+pub struct TypeRef(SyntaxNode);
+
+impl TypeRef {
+    pub fn cast(node: SyntaxNode) -> Option<Self> {
+        if node.kind() == SyntaxKind::TypeRef {
+            Some(Self(node))
+        } else {
+            None
+        }
+    }
+
+    pub fn name(&self) -> Result<SyntaxToken, AstError> {
+        ident_token(&self.0)
+    }
+
+    pub fn args(&self) -> impl Iterator<Item = TypeRef> {
+        self.0
+            .children()
+            .filter(|node| node.kind() == SyntaxKind::TypeArgList)
+            .flat_map(|node| node.children())
+            .filter_map(TypeRef::cast)
+    }
+
+    /// Item types of the tuple type `(Int, Long)`, `None` for other types
+    pub fn tuple_items(&self) -> Option<impl Iterator<Item = TypeRef>> {
+        if self.0.first_token()?.kind() == SyntaxKind::LParen {
+            Some(self.0.children().filter_map(TypeRef::cast))
+        } else {
+            None
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.0.text().to_string().trim_end().to_string()
+    }
+
+    pub fn span(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
+
+/// Prefix operator `-expr` or `!expr`
+#[derive(Debug)]
+pub struct UnaryExpr(SyntaxNode);
+
+impl UnaryExpr {
+    pub fn expr(&self) -> Result<Expr, AstError> {
+        self.0.children().find_map(Expr::cast).ok_or_else(|| {
+            AstError::new(
+                format!("Cannot find operand in {:?}", self.0),
+                self.0.text_range(),
+            )
+        })
+    }
+
+    pub fn op(&self) -> Result<SyntaxToken, AstError> {
+        self.0
+            .children_with_tokens()
+            .filter_map(SyntaxElement::into_token)
+            .find(|token| matches!(token.kind(), SyntaxKind::Minus | SyntaxKind::Bang))
+            .ok_or_else(|| {
+                AstError::new(
+                    format!("Cannot find unary op in {:?}", self.0),
+                    self.0.text_range(),
+                )
+            })
+    }
+
+    pub fn span(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
+
+/// `if (condition) then_branch else else_branch`
+#[derive(Debug)]
+pub struct IfExpr(SyntaxNode);
+
+impl IfExpr {
+    pub fn condition(&self) -> Result<Expr, AstError> {
+        self.nth_expr(0, "condition")
+    }
+
+    pub fn then_branch(&self) -> Result<Expr, AstError> {
+        self.nth_expr(1, "then branch")
+    }
+
+    pub fn else_branch(&self) -> Result<Expr, AstError> {
+        self.nth_expr(2, "else branch")
+    }
+
+    fn nth_expr(&self, n: usize, name: &str) -> Result<Expr, AstError> {
+        self.0
+            .children()
+            .filter_map(Expr::cast)
+            .nth(n)
+            .ok_or_else(|| {
+                AstError::new(
+                    format!("Cannot find {} in {:?}", name, self.0),
+                    self.0.text_range(),
+                )
+            })
+    }
+
+    pub fn span(&self) -> TextRange {
+        self.0.text_range()
+    }
+}
@@ -0,0 +1,85 @@
+//! ErgoScript formatter
+//!
+//! Usage: `ergoscript-fmt [--check] [FILE]...`
+//!
+//! Formats the files in place, or stdin to stdout if no files are given. With `--check` the files
+//! are not changed, the ones that are not formatted are listed and the exit code is 1.
+
+use std::io::Read;
+use std::io::Write;
+use std::process::exit;
+
+use ergoscript_compiler::formatter::try_format;
+
+fn main() {
+    let mut check = false;
+    let mut files = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--check" => check = true,
+            "-h" | "--help" => {
+                println!("Usage: ergoscript-fmt [--check] [FILE]...");
+                return;
+            }
+            _ => files.push(arg),
+        }
+    }
+    if files.is_empty() {
+        exit(format_stdin(check));
+    }
+    let mut code = 0;
+    for file in files {
+        code = code.max(format_file(&file, check));
+    }
+    exit(code)
+}
+
+fn format_stdin(check: bool) -> i32 {
+    let mut source = String::new();
+    if let Err(e) = std::io::stdin().read_to_string(&mut source) {
+        eprintln!("error: cannot read stdin: {}", e);
+        return 2;
+    }
+    match try_format(&source) {
+        Ok(formatted) if check => i32::from(formatted != source),
+        Ok(formatted) => match std::io::stdout().write_all(formatted.as_bytes()) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("error: cannot write stdout: {}", e);
+                2
+            }
+        },
+        Err(e) => {
+            eprintln!("<stdin>:\n{}", e.pretty_desc(&source));
+            2
+        }
+    }
+}
+
+fn format_file(file: &str, check: bool) -> i32 {
+    let source = match std::fs::read_to_string(file) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("error: cannot read {}: {}", file, e);
+            return 2;
+        }
+    };
+    let formatted = match try_format(&source) {
+        Ok(formatted) => formatted,
+        Err(e) => {
+            eprintln!("{}:\n{}", file, e.pretty_desc(&source));
+            return 2;
+        }
+    };
+    if formatted == source {
+        0
+    } else if check {
+        println!("{}", file);
+        1
+    } else if let Err(e) = std::fs::write(file, formatted) {
+        eprintln!("error: cannot write {}: {}", file, e);
+        2
+    } else {
+        0
+    }
+}
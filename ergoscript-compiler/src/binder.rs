@@ -1,61 +1,189 @@
+use ergotree_ir::chain::address::Address;
+use ergotree_ir::chain::address::AddressEncoder;
+use ergotree_ir::mir::constant::Constant;
+use ergotree_ir::mir::constant::ConstantPlaceholder;
 use rowan::TextRange;
+use thiserror::Error;
 
-use crate::error::pretty_error_desc;
+use std::cell::RefCell;
+
+use crate::error::impl_diagnostic;
+use crate::error::ErrorKind;
+use crate::error::Label;
 use crate::hir;
+use crate::hir::Apply;
 use crate::hir::Expr;
 use crate::hir::ExprKind;
 use crate::hir::GlobalVars;
+use crate::hir::Literal;
 use crate::script_env::ScriptEnv;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Error)]
+#[error("{msg}")]
 pub struct BinderError {
+    kind: ErrorKind,
     msg: String,
     span: TextRange,
+    labels: Vec<Label>,
 }
 
 impl BinderError {
-    pub fn new(msg: String, span: TextRange) -> Self {
-        Self { msg, span }
-    }
-
-    pub fn pretty_desc(&self, source: &str) -> String {
-        pretty_error_desc(source, self.span, &self.msg)
+    pub fn new(kind: ErrorKind, msg: String, span: TextRange) -> Self {
+        Self {
+            kind,
+            msg,
+            span,
+            labels: Vec::new(),
+        }
     }
 }
 
+impl_diagnostic!(BinderError);
+
 pub struct Binder {
     env: ScriptEnv,
+    /// Identifiers (from the env) bound to the constant placeholders, with the placeholder id
+    /// being the index in this list
+    template_params: Vec<String>,
 }
 
 impl Binder {
     pub fn new(env: ScriptEnv) -> Self {
-        Binder { env }
+        Binder {
+            env,
+            template_params: Vec::new(),
+        }
     }
 
-    pub fn bind(&self, expr: Expr) -> Result<Expr, BinderError> {
-        rewrite(expr, &self.env)
+    /// Binds the given identifiers of the env to the constant placeholders instead of the
+    /// constants
+    pub fn with_template_params(self, template_params: Vec<String>) -> Self {
+        Binder {
+            template_params,
+            ..self
+        }
+    }
+
+    /// Binds the identifiers, reports the errors of all the compile time evaluated functions
+    pub fn bind(&self, expr: Expr) -> Result<Expr, Vec<BinderError>> {
+        rewrite(expr, &self.env, &self.template_params)
     }
 }
 
-fn rewrite(expr: Expr, env: &ScriptEnv) -> Result<Expr, BinderError> {
-    hir::rewrite(expr, |e| {
-        Ok(match &e.kind {
+fn rewrite(
+    expr: Expr,
+    env: &ScriptEnv,
+    template_params: &[String],
+) -> Result<Expr, Vec<BinderError>> {
+    // an invalid call is left as is and the rest of the expression is still bound
+    let errors = RefCell::new(Vec::new());
+    let res = hir::rewrite(expr, |e| {
+        Ok::<_, Vec<BinderError>>(match &e.kind {
             ExprKind::Ident(ident) => match env.get(ident) {
-                Some(_) => todo!(),
-                None => match ident.as_ref() {
-                    "HEIGHT" => {
-                        let v = GlobalVars::Height;
-                        let tpe = v.tpe();
-                        Some(Expr {
-                            kind: v.into(),
-                            span: e.span,
-                            tpe: tpe.into(),
-                        })
+                Some(c) => Some(Expr {
+                    kind: match template_params.iter().position(|p| p == ident) {
+                        Some(id) => ExprKind::ConstantPlaceholder(ConstantPlaceholder {
+                            id: id as u32,
+                            tpe: c.tpe.clone(),
+                        }),
+                        None => ExprKind::Constant(c.clone()),
+                    },
+                    span: e.span,
+                    tpe: Some(c.tpe.clone()),
+                }),
+                None => global_var(ident).map(|v| {
+                    let tpe = v.tpe();
+                    Expr {
+                        kind: v.into(),
+                        span: e.span,
+                        tpe: tpe.into(),
+                    }
+                }),
+            },
+            ExprKind::Apply(Apply { func, args }) => match (&func.kind, &args[..]) {
+                (
+                    ExprKind::Ident(name),
+                    [Expr {
+                        kind: ExprKind::Literal(Literal::Str(s)),
+                        ..
+                    }],
+                ) if env.get(name).is_none() => match eval_string_func(name, s, e.span) {
+                    Ok(bound) => bound,
+                    Err(error) => {
+                        errors.borrow_mut().push(error);
+                        None
                     }
-                    _ => None,
                 },
+                _ => None,
             },
             _ => None,
         })
+    })?;
+    let errors = errors.into_inner();
+    if errors.is_empty() {
+        Ok(res)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Evaluates the functions of a string literal (`PK`, `fromBase16`, `fromBase58`) at compile time
+fn eval_string_func(name: &str, s: &str, span: TextRange) -> Result<Option<Expr>, BinderError> {
+    let c: Constant = match name {
+        "PK" => match AddressEncoder::unchecked_parse_address_from_str(s) {
+            Ok(Address::P2Pk(pd)) => pd.into(),
+            Ok(_) => {
+                return Err(BinderError::new(
+                    ErrorKind::InvalidLiteral,
+                    format!("Expected P2PK address, got {}", s),
+                    span,
+                ))
+            }
+            Err(e) => {
+                return Err(BinderError::new(
+                    ErrorKind::InvalidLiteral,
+                    format!("Invalid address {}: {}", s, e),
+                    span,
+                ))
+            }
+        },
+        "fromBase16" => base16::decode(s)
+            .map_err(|e| {
+                BinderError::new(
+                    ErrorKind::InvalidLiteral,
+                    format!("Invalid Base16 string: {}", e),
+                    span,
+                )
+            })?
+            .into(),
+        "fromBase58" => bs58::decode(s)
+            .into_vec()
+            .map_err(|e| {
+                BinderError::new(
+                    ErrorKind::InvalidLiteral,
+                    format!("Invalid Base58 string: {}", e),
+                    span,
+                )
+            })?
+            .into(),
+        _ => return Ok(None),
+    };
+    let tpe = c.tpe.clone();
+    Ok(Some(Expr {
+        kind: c.into(),
+        span,
+        tpe: Some(tpe),
+    }))
+}
+
+pub(crate) fn global_var(ident: &str) -> Option<GlobalVars> {
+    Some(match ident {
+        "HEIGHT" => GlobalVars::Height,
+        "INPUTS" => GlobalVars::Inputs,
+        "OUTPUTS" => GlobalVars::Outputs,
+        "SELF" => GlobalVars::SelfBox,
+        "CONTEXT" => GlobalVars::Context,
+        "MinerPubkey" => GlobalVars::MinerPubkey,
+        _ => return None,
     })
 }
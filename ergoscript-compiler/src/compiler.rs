@@ -4,6 +4,9 @@ use super::binder::BinderError;
 use super::hir::HirLoweringError;
 use crate::ast;
 use crate::binder::Binder;
+use crate::error::labeled_spans;
+use crate::error::pretty_error_desc;
+use crate::error::ErrorKind;
 use crate::hir;
 use crate::mir;
 use crate::parser::parse_error::ParseError;
@@ -11,68 +14,223 @@ use crate::script_env::ScriptEnv;
 use crate::type_infer::assign_type;
 use crate::type_infer::TypeInferenceError;
 use std::convert::TryInto;
+use std::fmt;
 
 extern crate derive_more;
 use derive_more::From;
+use ergotree_ir::contract_template::ContractTemplate;
+use ergotree_ir::contract_template::ContractTemplateError;
 use ergotree_ir::ergo_tree::ErgoTree;
 use ergotree_ir::ergo_tree::ErgoTreeError;
+use ergotree_ir::mir::constant::Constant;
 use ergotree_ir::type_check::TypeCheckError;
+use miette::Diagnostic;
+use miette::LabeledSpan;
 use mir::lower::MirLoweringError;
+use text_size::TextRange;
+
+/// Error on the IR passes (type checking, ErgoTree or contract template building). The IR has no
+/// source spans, so the error is reported for the whole source code.
+#[derive(Debug, PartialEq, Eq)]
+pub struct IrError<E> {
+    /// Error of the IR pass
+    pub error: E,
+    kind: ErrorKind,
+    msg: String,
+    span: TextRange,
+}
+
+impl<E> IrError<E> {
+    fn new(error: E, kind: ErrorKind, msg: String, source: &str) -> Self {
+        IrError {
+            error,
+            kind,
+            msg,
+            span: TextRange::up_to((source.len() as u32).into()),
+        }
+    }
+}
+
+impl<E> fmt::Display for IrError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.msg)
+    }
+}
+
+impl<E: fmt::Debug> std::error::Error for IrError<E> {}
+
+impl<E: fmt::Debug> Diagnostic for IrError<E> {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new(self.kind.code()))
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        Some(labeled_spans(self.span, &[]))
+    }
+}
+
+impl IrError<TypeCheckError> {
+    pub(crate) fn type_check(error: TypeCheckError, source: &str) -> Self {
+        let msg = error.pretty_desc();
+        IrError::new(error, ErrorKind::TypeCheck, msg, source)
+    }
+}
+
+impl IrError<ErgoTreeError> {
+    pub(crate) fn ergo_tree(error: ErgoTreeError, source: &str) -> Self {
+        let msg = error.to_string();
+        IrError::new(error, ErrorKind::ErgoTree, msg, source)
+    }
+}
+
+impl IrError<ContractTemplateError> {
+    fn contract_template(error: ContractTemplateError, source: &str) -> Self {
+        let msg = error.to_string();
+        IrError::new(error, ErrorKind::ContractTemplate, msg, source)
+    }
+}
 
 /// Compilation errors
 #[derive(Debug, PartialEq, Eq, From)]
 pub enum CompileError {
-    /// Parser error
+    /// Parser errors
     ParseError(Vec<ParseError>),
-    /// Error on AST to HIR lowering
-    HirLoweringError(HirLoweringError),
-    /// Error on binder pass
-    BinderError(BinderError),
-    /// Error on type inference pass
-    TypeInferenceError(TypeInferenceError),
+    /// Errors on AST to HIR lowering
+    HirLoweringError(Vec<HirLoweringError>),
+    /// Errors on binder pass
+    BinderError(Vec<BinderError>),
+    /// Errors on type inference pass
+    TypeInferenceError(Vec<TypeInferenceError>),
     /// Error on HIT to MIR lowering
     MirLoweringError(MirLoweringError),
     /// Error on type checking
-    TypeCheckError(TypeCheckError),
+    TypeCheckError(IrError<TypeCheckError>),
     /// ErgoTree error
-    ErgoTreeError(ErgoTreeError),
+    ErgoTreeError(IrError<ErgoTreeError>),
+    /// Contract template error
+    ContractTemplateError(IrError<ContractTemplateError>),
 }
 
 impl CompileError {
     /// Pretty formatted error with CST/AST/IR, etc.
     pub fn pretty_desc(&self, source: &str) -> String {
+        self.diagnostics()
+            .into_iter()
+            .map(|e| pretty_error_desc(source, e))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// All the reported errors (i.e. every parse error), with the source spans
+    pub fn diagnostics(&self) -> Vec<&dyn Diagnostic> {
+        fn all<E: Diagnostic>(errors: &[E]) -> Vec<&dyn Diagnostic> {
+            errors.iter().map(|e| e as &dyn Diagnostic).collect()
+        }
         match self {
-            CompileError::ParseError(errors) => {
-                errors.iter().map(|e| e.pretty_desc(source)).collect()
-            }
-            CompileError::HirLoweringError(e) => e.pretty_desc(source),
-            CompileError::BinderError(e) => e.pretty_desc(source),
-            CompileError::TypeInferenceError(e) => e.pretty_desc(source),
-            CompileError::MirLoweringError(e) => e.pretty_desc(source),
-            CompileError::TypeCheckError(e) => e.pretty_desc(),
-            CompileError::ErgoTreeError(e) => format!("{:?}", e),
+            CompileError::ParseError(errors) => all(errors),
+            CompileError::HirLoweringError(errors) => all(errors),
+            CompileError::BinderError(errors) => all(errors),
+            CompileError::TypeInferenceError(errors) => all(errors),
+            CompileError::MirLoweringError(e) => vec![e],
+            CompileError::TypeCheckError(e) => vec![e],
+            CompileError::ErgoTreeError(e) => vec![e],
+            CompileError::ContractTemplateError(e) => vec![e],
+        }
+    }
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.diagnostics().first() {
+            Some(e) => write!(f, "{}", e),
+            None => write!(f, "{:?}", self),
         }
     }
 }
 
+impl std::error::Error for CompileError {}
+
+/// The first error is reported as the main one and the rest as related
+impl Diagnostic for CompileError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.diagnostics().first().and_then(|e| e.code())
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.diagnostics().first().and_then(|e| e.help())
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        self.diagnostics().first().and_then(|e| e.labels())
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        Some(Box::new(self.diagnostics().into_iter().skip(1)))
+    }
+}
+
 /// Compiles given source code to [`ergotree_ir::mir::expr::Expr`], or returns an error
 pub fn compile_expr(
     source: &str,
     env: ScriptEnv,
+) -> Result<ergotree_ir::mir::expr::Expr, CompileError> {
+    compile_expr_with(source, Binder::new(env))
+}
+
+fn compile_expr_with(
+    source: &str,
+    binder: Binder,
 ) -> Result<ergotree_ir::mir::expr::Expr, CompileError> {
     let hir = compile_hir(source)?;
-    let binder = Binder::new(env);
     let bind = binder.bind(hir)?;
     let typed = assign_type(bind)?;
     let mir = mir::lower::lower(typed)?;
-    let res = ergotree_ir::type_check::type_check(mir)?;
+    let res =
+        ergotree_ir::type_check::type_check(mir).map_err(|e| IrError::type_check(e, source))?;
     Ok(res)
 }
 
 /// Compiles given source code to [`ErgoTree`], or returns an error
 pub fn compile(source: &str, env: ScriptEnv) -> Result<ErgoTree, CompileError> {
     let expr = compile_expr(source, env)?;
-    Ok(expr.try_into()?)
+    Ok(expr.try_into().map_err(|e| IrError::ergo_tree(e, source))?)
+}
+
+/// Compiles given source code to [`ErgoTree`] optimized with
+/// [`ergotree_ir::optimizer::optimize`], or returns an error. The tree (and its address) differs
+/// from the one produced by [`compile`].
+pub fn compile_optimized(source: &str, env: ScriptEnv) -> Result<ErgoTree, CompileError> {
+    let expr = compile_expr(source, env)?;
+    Ok(ergotree_ir::optimizer::optimize(expr)
+        .try_into()
+        .map_err(|e| IrError::ergo_tree(e, source))?)
+}
+
+/// Compiles given source code to [`ContractTemplate`] (EIP-5), or returns an error. The
+/// identifiers starting with `$` in the env are the template parameters (named without the `$`)
+/// with their values as the defaults, every other constant has a fixed (default) value.
+pub fn compile_template(
+    source: &str,
+    env: ScriptEnv,
+    name: String,
+    description: String,
+) -> Result<ContractTemplate, CompileError> {
+    let params: Vec<(String, Constant)> = env
+        .template_params()
+        .into_iter()
+        .map(|(ident, value)| (ident.to_string(), value.clone()))
+        .collect();
+    let binder =
+        Binder::new(env).with_template_params(params.iter().map(|(p, _)| p.clone()).collect());
+    let expr = compile_expr_with(source, binder)?;
+    let params = params
+        .into_iter()
+        .map(|(ident, value)| (ident.trim_start_matches('$').to_string(), value))
+        .collect();
+    Ok(
+        ContractTemplate::from_expr(None, name, description, params, &expr)
+            .map_err(|e| IrError::contract_template(e, source))?,
+    )
 }
 
 pub(crate) fn compile_hir(source: &str) -> Result<hir::Expr, CompileError> {
@@ -96,6 +254,17 @@ fn check(input: &str, expected_tree: expect_test::Expect) {
     expected_tree.assert_eq(&expected_out);
 }
 
+/// Same as [`check`], but with the compiled expression pretty printed
+#[cfg(test)]
+fn check_pretty(input: &str, expected: expect_test::Expect) {
+    let res = compile_expr(input, ScriptEnv::new());
+
+    let expected_out = res
+        .map(|expr| expr.pretty_print().unwrap().1)
+        .unwrap_or_else(|e| e.pretty_desc(input));
+    expected.assert_eq(&expected_out);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,12 +284,1074 @@ mod tests {
     #[test]
     fn test_parser_error() {
         check(
-            "HSB.HEIGHT",
+            "HSB#HEIGHT",
+            expect![[r#"
+                ergoscript::unexpected_token
+
+                  x error: expected ‘.’, ‘(’, ‘[’, ‘+’, ‘-’, ‘*’, ‘/’, ‘==’, ‘!=’, ‘<’, ‘<=’, ‘>’, ‘>=’, ‘&&’, ‘||’, ‘;’, ‘val’, number, number, ‘true’, ‘false’, string, identifier, ‘!’, ‘if’ or ‘{’, but found an
+                  | unrecognized token
+                   ,----
+                 1 | HSB#HEIGHT
+                   :    ^
+                   `----
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_block() {
+        check(
+            r#"{
+                val h = HEIGHT
+                val next = h + 1
+                next
+            }"#,
+            expect![[r#"
+                BlockValue(
+                    Spanned {
+                        source_span: SourceSpan {
+                            offset: 0,
+                            length: 0,
+                        },
+                        expr: BlockValue {
+                            items: [
+                                ValDef(
+                                    Spanned {
+                                        source_span: SourceSpan {
+                                            offset: 0,
+                                            length: 0,
+                                        },
+                                        expr: ValDef {
+                                            id: ValId(
+                                                1,
+                                            ),
+                                            rhs: GlobalVars(
+                                                Height,
+                                            ),
+                                        },
+                                    },
+                                ),
+                                ValDef(
+                                    Spanned {
+                                        source_span: SourceSpan {
+                                            offset: 0,
+                                            length: 0,
+                                        },
+                                        expr: ValDef {
+                                            id: ValId(
+                                                2,
+                                            ),
+                                            rhs: BinOp(
+                                                Spanned {
+                                                    source_span: SourceSpan {
+                                                        offset: 0,
+                                                        length: 0,
+                                                    },
+                                                    expr: BinOp {
+                                                        kind: Arith(
+                                                            Plus,
+                                                        ),
+                                                        left: ValUse(
+                                                            ValUse {
+                                                                val_id: ValId(
+                                                                    1,
+                                                                ),
+                                                                tpe: SInt,
+                                                            },
+                                                        ),
+                                                        right: Const(
+                                                            "1: SInt",
+                                                        ),
+                                                    },
+                                                },
+                                            ),
+                                        },
+                                    },
+                                ),
+                            ],
+                            result: ValUse(
+                                ValUse {
+                                    val_id: ValId(
+                                        2,
+                                    ),
+                                    tpe: SInt,
+                                },
+                            ),
+                        },
+                    },
+                )"#]],
+        );
+    }
+
+    #[test]
+    fn test_block_roundtrip() {
+        use ergotree_ir::serialization::SigmaSerializable;
+        let tree = compile(
+            "{ val f = { (x: Int) => x * 2 }; val a = HEIGHT + 1; a }",
+            ScriptEnv::new(),
+        )
+        .unwrap();
+        let bytes = tree.sigma_serialize_bytes().unwrap();
+        assert_eq!(ErgoTree::sigma_parse_bytes(&bytes).unwrap(), tree);
+    }
+
+    #[test]
+    fn test_compile_optimized() {
+        use ergotree_ir::serialization::SigmaSerializable;
+        let source =
+            "{ val unused = HEIGHT > 1; val limit = 10 * 1000; sigmaProp(HEIGHT > limit) }";
+        let tree = compile(source, ScriptEnv::new()).unwrap();
+        let optimized = compile_optimized(source, ScriptEnv::new()).unwrap();
+        assert!(
+            optimized.sigma_serialize_bytes().unwrap().len()
+                < tree.sigma_serialize_bytes().unwrap().len()
+        );
+        expect!["sigmaProp(HEIGHT > 10000)"]
+            .assert_eq(&optimized.proposition().unwrap().to_string_pretty());
+    }
+
+    #[test]
+    fn test_compile_template() {
+        use ergotree_ir::ergo_tree::ErgoTreeVersion;
+        use std::collections::HashMap;
+        let mut env = ScriptEnv::new();
+        env.insert("$deadline".to_string(), 500.into());
+        env.insert("$minValue".to_string(), 1000000i64.into());
+        let template = compile_template(
+            "sigmaProp(HEIGHT > $deadline && $deadline < 1000000 && SELF.value >= $minValue)",
+            env,
+            "deadline".to_string(),
+            "Spendable after the deadline".to_string(),
+        )
+        .unwrap();
+        let params: Vec<(&str, u32)> = template
+            .parameters()
+            .iter()
+            .map(|p| (p.name.as_str(), p.constant_index))
+            .collect();
+        assert_eq!(params, vec![("deadline", 0), ("minValue", 1)]);
+        let tree = template
+            .apply_template(
+                Some(ErgoTreeVersion::V0),
+                &HashMap::from([("deadline".to_string(), 700.into())]),
+            )
+            .unwrap();
+        expect!["sigmaProp(HEIGHT > 700 && 700 < 1000000 && SELF.value >= 1000000)"]
+            .assert_eq(&tree.proposition().unwrap().to_string_pretty());
+    }
+
+    #[test]
+    fn test_unresolved_ident() {
+        check(
+            "{ val a = 1; b }",
+            expect![[r#"
+                ergoscript::unknown_ident
+
+                  x Cannot assign type for variable 'b' because it is not found in env
+                   ,----
+                 1 | { val a = 1; b }
+                   :              ^^
+                   `----
+                  help: check the spelling, define it with `val` before the use or pass its value in the ScriptEnv
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_val_type_mismatch() {
+        check(
+            "{ val a: Long = 1; a }",
+            expect![[r#"
+                ergoscript::type_mismatch
+
+                  x Type mismatch for 'a': declared SLong, found SInt
+                   ,----
+                 1 | { val a: Long = 1; a }
+                   :                 |
+                   :                 `-- found SInt
+                   `----
+                  help: the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_expr_before_block_result() {
+        check(
+            "{ 1; 2 }",
+            expect![[r#"
+                ergoscript::invalid_syntax
+
+                  x Only `val` definitions are allowed before the block result
+                   ,----
+                 1 | { 1; 2 }
+                   :   ^
+                   `----
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_box_property() {
+        check_pretty("SELF.value", expect!["SELF.value"]);
+        check(
+            "SELF.value",
+            expect![[r#"
+            ExtractAmount(
+                ExtractAmount {
+                    input: GlobalVars(
+                        SelfBox,
+                    ),
+                },
+            )"#]],
+        );
+    }
+
+    #[test]
+    fn test_numeric_conversion() {
+        check(
+            "HEIGHT.toLong",
+            expect![[r#"
+            Upcast(
+                Upcast {
+                    input: GlobalVars(
+                        Height,
+                    ),
+                    tpe: SLong,
+                },
+            )"#]],
+        );
+        check(
+            "HEIGHT.toByte",
+            expect![[r#"
+            Downcast(
+                Downcast {
+                    input: GlobalVars(
+                        Height,
+                    ),
+                    tpe: SByte,
+                },
+            )"#]],
+        );
+        check(
+            "HEIGHT.toInt",
+            expect![[r#"
+            GlobalVars(
+                Height,
+            )"#]],
+        );
+    }
+
+    #[test]
+    fn test_register() {
+        check_pretty(
+            "OUTPUTS(0).R4[Coll[Byte]].get",
+            expect!["OUTPUTS(0).getReg(4).get"],
+        );
+    }
+
+    #[test]
+    fn test_coll_size() {
+        check_pretty("INPUTS.size", expect!["INPUTS.size"]);
+    }
+
+    #[test]
+    fn test_context_property() {
+        check_pretty("CONTEXT.dataInputs", expect!["CONTEXT.dataInputs"]);
+    }
+
+    #[test]
+    fn test_token_id() {
+        check_pretty("SELF.tokens(0)._1", expect!["SELF.tokens(0)._1"]);
+    }
+
+    #[test]
+    fn test_method_call() {
+        check_pretty(
+            "INPUTS.zip(OUTPUTS)(0)._2.value",
+            expect!["INPUTS.zip(OUTPUTS)(0)._2.value"],
+        );
+    }
+
+    #[test]
+    fn test_lambda_with_box_arg() {
+        check_pretty(
+            "{ (b: Box) => b.R4[Int].get + 1 }",
+            expect![[r#"
+            {
+                (v1: Box) => 
+                  v1.getReg(4).get + 1
+                }
+        "#]],
+        );
+    }
+
+    #[test]
+    fn test_method_call_roundtrip() {
+        use ergotree_ir::serialization::SigmaSerializable;
+        let tree = compile(
+            "{ val out = OUTPUTS(0); out.tokens(0)._2 + out.R5[Long].get + INPUTS.zip(OUTPUTS).size.toLong }",
+            ScriptEnv::new(),
+        )
+        .unwrap();
+        let bytes = tree.sigma_serialize_bytes().unwrap();
+        assert_eq!(ErgoTree::sigma_parse_bytes(&bytes).unwrap(), tree);
+    }
+
+    #[test]
+    fn test_register_without_type() {
+        check(
+            "SELF.R4",
+            expect![[r#"
+                ergoscript::cannot_infer_type
+
+                  x Register type should be specified, i.e. R4[Int]
+                   ,----
+                 1 | SELF.R4
+                   : ^^^^^^^
+                   `----
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_unknown_property() {
+        check(
+            "SELF.amount",
+            expect![[r#"
+                ergoscript::unknown_member
+
+                  x Cannot find property 'amount' in SBox
+                   ,----
+                 1 | SELF.amount
+                   : ^^^^^^^^^^^
+                   `----
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_non_int_index() {
+        check(
+            "INPUTS(1L)",
+            expect![[r#"
+                ergoscript::type_mismatch
+
+                  x Collection index should be Int, got SLong
+                   ,----
+                 1 | INPUTS(1L)
+                   :        ^^
+                   `----
+                  help: the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_apply_to_non_function() {
+        check(
+            "HEIGHT(1)",
             expect![[r#"
-                error: expected ‘+’, ‘-’, ‘*’, ‘/’, ‘val’, number, number, identifier, ‘-’ or ‘(’, but found an unrecognized token
-                line: 1
-                HSB.HEIGHT
-                  ^^"#]],
+                ergoscript::type_mismatch
+
+                  x Cannot apply arguments to SInt
+                   ,----
+                 1 | HEIGHT(1)
+                   : ^^^^^^^^^
+                   `----
+                  help: the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_if_else() {
+        check_pretty(
+            "if (HEIGHT > 100) SELF.value else 0L",
+            expect!["if (HEIGHT > 100) SELF.value else 0"],
+        );
+    }
+
+    #[test]
+    fn test_logical_ops() {
+        check_pretty(
+            "HEIGHT >= 1 && HEIGHT <= 2 || HEIGHT == 3 && HEIGHT != 4 && HEIGHT < 5",
+            expect!["HEIGHT >= 1 && HEIGHT <= 2 || HEIGHT == 3 && HEIGHT != 4 && HEIGHT < 5"],
+        );
+    }
+
+    #[test]
+    fn test_sigma_and_bool() {
+        check_pretty(
+            "PK(\"9f4QF8AD1nQ3nJahQVkMj8hFSVVzVom77b52JU7EW71Zexg6N8v\") && HEIGHT > 100",
+            expect![[r#"
+                allOf(
+                  SigmaProp(ProofOfKnowledge(ProveDlog(ProveDlog { h: EC:02472963123ce32c057907c7a7268bc09f45d9ca57819d3327b9e7497d7b1cc347 }))), 
+                  sigmaProp(HEIGHT > 100), 
+                )"#]],
+        );
+    }
+
+    #[test]
+    fn test_sigma_or() {
+        check_pretty(
+            "sigmaProp(HEIGHT > 100) || proveDlog(CONTEXT.preHeader.minerPk)",
+            expect![[r#"
+                anyOf(
+                  sigmaProp(HEIGHT > 100), 
+                  proveDlog(CONTEXT.preHeader.minerPk), 
+                )"#]],
         );
     }
+
+    #[test]
+    fn test_hash_functions() {
+        check_pretty(
+            "blake2b256(SELF.propositionBytes) == sha256(fromBase16(\"0102\")) || fromBase58(\"2\") == SELF.id",
+            expect![[r#"blake2b256(SELF.propBytes) == sha256("0102") || "01" == SELF.id"#]],
+        );
+    }
+
+    #[test]
+    fn test_min_max() {
+        check_pretty(
+            "min(HEIGHT, 10) < max(HEIGHT, 5)",
+            expect!["min(HEIGHT, 10) < max(HEIGHT, 5)"],
+        );
+        check(
+            "min(HEIGHT, 10) < max(HEIGHT, 5)",
+            expect![[r#"
+            BinOp(
+                Spanned {
+                    source_span: SourceSpan {
+                        offset: 0,
+                        length: 0,
+                    },
+                    expr: BinOp {
+                        kind: Relation(
+                            Lt,
+                        ),
+                        left: BinOp(
+                            Spanned {
+                                source_span: SourceSpan {
+                                    offset: 0,
+                                    length: 0,
+                                },
+                                expr: BinOp {
+                                    kind: Arith(
+                                        Min,
+                                    ),
+                                    left: GlobalVars(
+                                        Height,
+                                    ),
+                                    right: Const(
+                                        "10: SInt",
+                                    ),
+                                },
+                            },
+                        ),
+                        right: BinOp(
+                            Spanned {
+                                source_span: SourceSpan {
+                                    offset: 0,
+                                    length: 0,
+                                },
+                                expr: BinOp {
+                                    kind: Arith(
+                                        Max,
+                                    ),
+                                    left: GlobalVars(
+                                        Height,
+                                    ),
+                                    right: Const(
+                                        "5: SInt",
+                                    ),
+                                },
+                            },
+                        ),
+                    },
+                },
+            )"#]],
+        );
+    }
+
+    #[test]
+    fn test_miner_pubkey_and_sigma_prop_bytes() {
+        check_pretty(
+            "MinerPubkey == proveDlog(decodePoint(MinerPubkey)).propBytes",
+            expect!["MINER_PUBKEY == proveDlog(decodePoint(MINER_PUBKEY)).propBytes"],
+        );
+        check(
+            "MinerPubkey == proveDlog(decodePoint(MinerPubkey)).propBytes",
+            expect![[r#"
+                BinOp(
+                    Spanned {
+                        source_span: SourceSpan {
+                            offset: 0,
+                            length: 0,
+                        },
+                        expr: BinOp {
+                            kind: Relation(
+                                Eq,
+                            ),
+                            left: GlobalVars(
+                                MinerPubKey,
+                            ),
+                            right: SigmaPropBytes(
+                                SigmaPropBytes {
+                                    input: CreateProveDlog(
+                                        CreateProveDlog {
+                                            input: DecodePoint(
+                                                DecodePoint {
+                                                    input: GlobalVars(
+                                                        MinerPubKey,
+                                                    ),
+                                                },
+                                            ),
+                                        },
+                                    ),
+                                },
+                            ),
+                        },
+                    },
+                )"#]],
+        );
+    }
+
+    #[test]
+    fn test_subst_constants() {
+        check_pretty(
+            "substConstants(SELF.propositionBytes, Coll(0), Coll(1))",
+            expect!["substConstants(SELF.propBytes, Coll[Int](0), Coll[Int](1))"],
+        );
+        check(
+            "substConstants(SELF.propositionBytes, Coll(0), Coll(1))",
+            expect![[r#"
+                SubstConstants(
+                    Spanned {
+                        source_span: SourceSpan {
+                            offset: 0,
+                            length: 0,
+                        },
+                        expr: SubstConstants {
+                            script_bytes: ExtractScriptBytes(
+                                ExtractScriptBytes {
+                                    input: GlobalVars(
+                                        SelfBox,
+                                    ),
+                                },
+                            ),
+                            positions: Collection(
+                                Exprs {
+                                    elem_tpe: SInt,
+                                    items: [
+                                        Const(
+                                            "0: SInt",
+                                        ),
+                                    ],
+                                },
+                            ),
+                            new_values: Collection(
+                                Exprs {
+                                    elem_tpe: SInt,
+                                    items: [
+                                        Const(
+                                            "1: SInt",
+                                        ),
+                                    ],
+                                },
+                            ),
+                        },
+                    },
+                )"#]],
+        );
+    }
+
+    #[test]
+    fn test_prove_dh_tuple() {
+        check_pretty(
+            "{ val g = CONTEXT.preHeader.minerPk; proveDHTuple(g, g, g, g) }",
+            expect![[r#"
+                {
+                  val v1 = CONTEXT.preHeader.minerPk
+                  proveDHTuple(v1, v1, v1, v1)
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_sigma_roundtrip() {
+        use ergotree_ir::serialization::SigmaSerializable;
+        let tree = compile(
+            "if (-HEIGHT < 0 && !false) PK(\"9f4QF8AD1nQ3nJahQVkMj8hFSVVzVom77b52JU7EW71Zexg6N8v\") || sigmaProp(HEIGHT > 100) else sigmaProp(false)",
+            ScriptEnv::new(),
+        )
+        .unwrap();
+        let bytes = tree.sigma_serialize_bytes().unwrap();
+        assert_eq!(ErgoTree::sigma_parse_bytes(&bytes).unwrap(), tree);
+    }
+
+    #[test]
+    fn test_if_branches_type_mismatch() {
+        check(
+            "if (true) 1 else 1L",
+            expect![[r#"
+                ergoscript::type_mismatch
+
+                  x Branches of if should have the same type, got SInt and SLong
+                   ,----
+                 1 | if (true) 1 else 1L
+                   :           ^|     ^|
+                   :            |      `-- expected SInt because of the then branch, found SLong
+                   :            `-- this is SInt
+                   `----
+                  help: the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_non_boolean_condition() {
+        check(
+            "if (HEIGHT) 1 else 2",
+            expect![[r#"
+                ergoscript::type_mismatch
+
+                  x Condition should be Boolean, got SInt
+                   ,----
+                 1 | if (HEIGHT) 1 else 2
+                   :     ^^^^^^
+                   `----
+                  help: the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_logical_op_type_mismatch() {
+        check(
+            "HEIGHT && true",
+            expect![[r#"
+                ergoscript::type_mismatch
+
+                  x Type mismatch: SInt And SBoolean
+                   ,----
+                 1 | HEIGHT && true
+                   : ^^^|^^^   ^^|^
+                   :    |        `-- SBoolean
+                   :    `-- SInt
+                   `----
+                  help: the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_predef_func_type_mismatch() {
+        check(
+            "sigmaProp(HEIGHT)",
+            expect![[r#"
+                ergoscript::type_mismatch
+
+                  x Type mismatch: expected arguments [SBoolean], got [SInt]
+                   ,----
+                 1 | sigmaProp(HEIGHT)
+                   :           ^^^|^^
+                   :              `-- expected SBoolean, found SInt
+                   `----
+                  help: the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_pk_invalid_address() {
+        check(
+            "PK(\"abc\")",
+            expect![[r#"
+                ergoscript::invalid_literal
+
+                  x Invalid address abc: Invalid size of the decoded byte array
+                   ,----
+                 1 | PK("abc")
+                   : ^^^^^^^^^
+                   `----
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_string_literal_outside_of_pk() {
+        check(
+            "{ val a = \"abc\"; a }",
+            expect![[r#"
+                ergoscript::invalid_literal
+
+                  x String literals are only allowed as arguments of PK, fromBase16 and fromBase58
+                   ,----
+                 1 | { val a = "abc"; a }
+                   :           ^^^^^
+                   `----
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_coll_map_filter() {
+        check_pretty(
+            "OUTPUTS.filter({ (b: Box) => b.value > 100L }).map({ (b: Box) => b.value })",
+            expect![[r#"
+                OUTPUTS.filter({
+                    (v1: Box) => 
+                      v1.value > 100
+                    }
+                  ).map({
+                    (v1: Box) => 
+                      v1.value
+                    }
+                )"#]],
+        );
+    }
+
+    #[test]
+    fn test_coll_exists_forall() {
+        check_pretty(
+            "INPUTS.exists({ (b: Box) => b.tokens.size > 0 }) && OUTPUTS.forall({ (b: Box) => b.value >= 1000L })",
+            expect![[r#"
+                INPUTS.exists({
+                    (v1: Box) => 
+                      v1.tokens.size > 0
+                    }
+                ) && OUTPUTS.forall({
+                      (v1: Box) => 
+                        v1.value >= 1000
+                      }
+                )"#]],
+        );
+    }
+
+    #[test]
+    fn test_coll_fold() {
+        check_pretty(
+            "OUTPUTS.fold(0L, { (acc: Long, b: Box) => acc + b.value })",
+            expect![[r#"
+                OUTPUTS.fold(0)({
+                    (v1: (Long, Box)) => 
+                      v1._1 + v1._2.value
+                    }
+                  )"#]],
+        );
+    }
+
+    #[test]
+    fn test_coll_slice_append_indices() {
+        check_pretty(
+            "SELF.propositionBytes.slice(0, 2).append(SELF.id).indices",
+            expect!["SELF.propBytes.slice(0, 2).append(SELF.id).indices"],
+        );
+    }
+
+    #[test]
+    fn test_coll_flat_map_zip() {
+        check_pretty(
+            "OUTPUTS.flatMap({ (b: Box) => b.propositionBytes }).zip(SELF.id).size",
+            expect![[r#"
+                OUTPUTS.flatMap({
+                    (v1: Box) => 
+                      v1.propBytes
+                    }
+                ).zip(SELF.id).size"#]],
+        );
+    }
+
+    #[test]
+    fn test_tuple() {
+        check_pretty(
+            "(HEIGHT, SELF.value)._2",
+            expect!["(HEIGHT, SELF.value)._2"],
+        );
+    }
+
+    #[test]
+    fn test_option() {
+        check_pretty(
+            "if (SELF.R4[Int].isDefined) SELF.R4[Int].get else SELF.R5[Int].getOrElse(0)",
+            expect!["if (SELF.getReg(4).isDefined()) SELF.getReg(4).get else SELF.getReg(5).getOrElse(0)"],
+        );
+    }
+
+    #[test]
+    fn test_oracle_contract_roundtrip() {
+        use ergotree_ir::serialization::SigmaSerializable;
+        let tree = compile(
+            r#"{
+              val dataPoints = INPUTS.filter({ (b: Box) => b.R6[Long].isDefined })
+              val rates = dataPoints.map({ (b: Box) => b.R6[Long].get })
+              val sum = rates.fold(0L, { (acc: Long, rate: Long) => acc + rate })
+              val average = sum / rates.size.toLong
+              sigmaProp(rates.size >= 4 && OUTPUTS(0).R4[Long].get == average
+                && OUTPUTS.slice(1, OUTPUTS.size).forall({ (b: Box) => b.value >= SELF.value }))
+            }"#,
+            ScriptEnv::new(),
+        )
+        .unwrap();
+        let bytes = tree.sigma_serialize_bytes().unwrap();
+        assert_eq!(ErgoTree::sigma_parse_bytes(&bytes).unwrap(), tree);
+    }
+
+    #[test]
+    fn test_dex_contract_roundtrip() {
+        use ergotree_ir::serialization::SigmaSerializable;
+        let tree = compile(
+            r#"{
+              val tokenPrice = 1000L
+              val buyer = PK("9f4QF8AD1nQ3nJahQVkMj8hFSVVzVom77b52JU7EW71Zexg6N8v")
+              val tokens = OUTPUTS.flatMap({ (b: Box) => b.tokens })
+              val paid = (OUTPUTS(0).propositionBytes, tokens.exists({ (t: (Coll[Byte], Long)) => t._2 >= tokenPrice }))
+              buyer || sigmaProp(paid._2 && paid._1.size > 0)
+            }"#,
+            ScriptEnv::new(),
+        )
+        .unwrap();
+        let bytes = tree.sigma_serialize_bytes().unwrap();
+        assert_eq!(ErgoTree::sigma_parse_bytes(&bytes).unwrap(), tree);
+    }
+
+    #[test]
+    fn test_coll_method_type_mismatch() {
+        check(
+            "OUTPUTS.map({ (i: Int) => i })",
+            expect![[r#"
+                ergoscript::type_mismatch
+
+                  x Method 'map' of SColl(SBox) cannot be applied to arguments [SFunc(SFunc { t_dom: [SInt], t_range: SInt, tpe_params: [] })]
+                   ,----
+                 1 | OUTPUTS.map({ (i: Int) => i })
+                   : ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+                   `----
+                  help: the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_fold_op_not_lambda() {
+        check(
+            "{ val f = (acc: Long, b: Box) => acc; OUTPUTS.fold(0L, f) }",
+            expect![[r#"
+                ergoscript::internal
+
+                  x MIR error: fold operation should be a lambda with two arguments
+                   ,----
+                 1 | { val f = (acc: Long, b: Box) => acc; OUTPUTS.fold(0L, f) }
+                   :                                                        ^
+                   `----
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_multiline_error() {
+        check(
+            r#"{
+  val a = if (HEIGHT > 100) {
+    1
+  } else {
+    2L
+  }
+  a
+}"#,
+            expect![[r#"
+                ergoscript::type_mismatch
+
+                  x Branches of if should have the same type, got SInt and SLong
+                   ,-[2:1]
+                 2 |       val a = if (HEIGHT > 100) {
+                 3 | ,->     1
+                 4 | |->   } else {
+                   : `---- this is SInt
+                 5 | ,->     2L
+                 6 | |->   }
+                   : `---- expected SInt because of the then branch, found SLong
+                 7 |       a
+                   `----
+                  help: the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_multiple_errors() {
+        check(
+            "{ val a: Int = 1L; val b = HEIGHT && 1; val c = -true; a + b }",
+            expect![[r#"
+                ergoscript::type_mismatch
+
+                  x Type mismatch for 'a': declared SInt, found SLong
+                   ,----
+                 1 | { val a: Int = 1L; val b = HEIGHT && 1; val c = -true; a + b }
+                   :                ^|
+                   :                 `-- found SLong
+                   `----
+                  help: the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt
+
+                ergoscript::type_mismatch
+
+                  x Type mismatch: SInt And SInt
+                   ,----
+                 1 | { val a: Int = 1L; val b = HEIGHT && 1; val c = -true; a + b }
+                   :                            ^^^|^^^   |
+                   :                               |      `-- SInt
+                   :                               `-- SInt
+                   `----
+                  help: the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt
+
+                ergoscript::type_mismatch
+
+                  x Type mismatch: Neg SBoolean
+                   ,----
+                 1 | { val a: Int = 1L; val b = HEIGHT && 1; val c = -true; a + b }
+                   :                                                  ^^|^
+                   :                                                    `-- found SBoolean
+                   `----
+                  help: the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let err = compile_expr("{ val a = 1 + 1L; val b = !1; a }", ScriptEnv::new()).unwrap_err();
+        assert_eq!(err.diagnostics().len(), 2);
+        assert_eq!(
+            err.code().map(|code| code.to_string()),
+            Some("ergoscript::type_mismatch".to_string())
+        );
+        assert!(err.help().is_some());
+        assert_eq!(err.labels().map(|labels| labels.count()), Some(2));
+        assert_eq!(err.related().map(|related| related.count()), Some(1));
+    }
+
+    #[test]
+    fn test_multiple_binder_errors() {
+        check(
+            r#"{ val a = PK("invalid"); val b = fromBase16("xyz"); sigmaProp(a && b) }"#,
+            expect![[r#"
+                ergoscript::invalid_literal
+
+                  x Invalid address invalid: Base58 decoding error: provided string contained invalid character 'l' at byte 4
+                   ,----
+                 1 | { val a = PK("invalid"); val b = fromBase16("xyz"); sigmaProp(a && b) }
+                   :           ^^^^^^^^^^^^^
+                   `----
+
+                ergoscript::invalid_literal
+
+                  x Invalid Base16 string: Base16 data cannot have length 3 (must be even)
+                   ,----
+                 1 | { val a = PK("invalid"); val b = fromBase16("xyz"); sigmaProp(a && b) }
+                   :                                  ^^^^^^^^^^^^^^^^^
+                   `----
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_multiple_hir_errors() {
+        check(
+            "{ val a: Foo = 1; val b: (Int) = 2; val c = { }; a }",
+            expect![[r#"
+                ergoscript::unknown_type
+
+                  x Unknown type: Foo
+                   ,----
+                 1 | { val a: Foo = 1; val b: (Int) = 2; val c = { }; a }
+                   :          ^^^^
+                   `----
+
+                ergoscript::invalid_tuple
+
+                  x Tuple type should have 2 to 255 items: (Int)
+                   ,----
+                 1 | { val a: Foo = 1; val b: (Int) = 2; val c = { }; a }
+                   :                          ^^^^^^
+                   `----
+
+                ergoscript::invalid_syntax
+
+                  x Empty block
+                   ,----
+                 1 | { val a: Foo = 1; val b: (Int) = 2; val c = { }; a }
+                   :                                             ^^^
+                   `----
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_unknown_ident_help() {
+        check(
+            "HEIGTH + 1",
+            expect![[r#"
+            ergoscript::unknown_ident
+
+              x Cannot assign type for variable 'HEIGTH' because it is not found in env
+               ,----
+             1 | HEIGTH + 1
+               : ^^^^^^^
+               `----
+              help: check the spelling, define it with `val` before the use or pass its value in the ScriptEnv
+        "#]],
+        );
+    }
+
+    #[test]
+    fn test_wrong_arg_count() {
+        check(
+            "{ val f = (a: Int, b: Int) => a + b; f(1) + OUTPUTS.slice(1) }",
+            expect![[r#"
+                ergoscript::wrong_arg_count
+
+                  x Expected 2 arguments [SInt, SInt], got 1 [SInt]
+                   ,----
+                 1 | { val f = (a: Int, b: Int) => a + b; f(1) + OUTPUTS.slice(1) }
+                   :                                      ^^^^^
+                   `----
+                  help: check the number of arguments in the function or method signature
+
+                ergoscript::wrong_arg_count
+
+                  x Method 'slice' of SColl(SBox) cannot be applied to arguments [SInt]
+                   ,----
+                 1 | { val f = (a: Int, b: Int) => a + b; f(1) + OUTPUTS.slice(1) }
+                   :                                             ^^^^^^^^^^^^^^^^^
+                   `----
+                  help: check the number of arguments in the function or method signature
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_type_check_error_diagnostic() {
+        let source = "HEIGHT";
+        let err = CompileError::TypeCheckError(IrError::type_check(
+            TypeCheckError::new("expected Boolean".to_string()),
+            source,
+        ));
+        assert_eq!(
+            err.code().map(|code| code.to_string()),
+            Some("ergoscript::type_check".to_string())
+        );
+        expect![[r#"
+            ergoscript::type_check
+
+              x expected Boolean
+               ,----
+             1 | HEIGHT
+               : ^^^^^^
+               `----
+        "#]]
+        .assert_eq(&err.pretty_desc(source));
+    }
 }
@@ -0,0 +1,1092 @@
+//! ErgoTree decompiler to ErgoScript
+
+use ergotree_ir::chain::address::Address;
+use ergotree_ir::chain::address::AddressEncoder;
+use ergotree_ir::chain::address::NetworkPrefix;
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::ergo_tree::ErgoTreeError;
+use ergotree_ir::mir::bin_op::ArithOp;
+use ergotree_ir::mir::bin_op::BinOp;
+use ergotree_ir::mir::bin_op::BinOpKind;
+use ergotree_ir::mir::bin_op::BitOp;
+use ergotree_ir::mir::bin_op::LogicalOp;
+use ergotree_ir::mir::bin_op::RelationOp;
+use ergotree_ir::mir::block::BlockValue;
+use ergotree_ir::mir::coll_fold::Fold;
+use ergotree_ir::mir::collection::Collection;
+use ergotree_ir::mir::constant::Constant;
+use ergotree_ir::mir::constant::Literal;
+use ergotree_ir::mir::constant::TryExtractFrom;
+use ergotree_ir::mir::expr::Expr;
+use ergotree_ir::mir::func_value::FuncValue;
+use ergotree_ir::mir::global_vars::GlobalVars;
+use ergotree_ir::mir::select_field::SelectField;
+use ergotree_ir::mir::traverse::for_each_descendant;
+use ergotree_ir::mir::val_def::ValId;
+use ergotree_ir::mir::val_use::ValUse;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaProofOfKnowledgeTree;
+use ergotree_ir::types::stype::SType;
+use thiserror::Error;
+
+use crate::script_env::ScriptEnv;
+
+/// ErgoScript source of the decompiled tree
+#[derive(Debug, PartialEq, Clone)]
+pub struct DecompiledScript {
+    /// ErgoScript source
+    pub source: String,
+    /// Constants without a literal syntax (i.e. `Coll[Int]`, `GroupElement`, negative numbers)
+    /// referenced in the source by name (`c0`, `c1`, ...)
+    pub constants: Vec<(String, Constant)>,
+}
+
+impl DecompiledScript {
+    /// Environment with the named constants to compile the source with
+    pub fn script_env(&self) -> ScriptEnv {
+        let mut env = ScriptEnv::new();
+        for (name, constant) in &self.constants {
+            env.insert(name.clone(), constant.clone());
+        }
+        env
+    }
+}
+
+/// Decompilation errors
+#[derive(Debug, PartialEq, Eq, Clone, Error)]
+pub enum DecompileError {
+    /// The tree cannot be parsed
+    #[error("ErgoTree error: {0}")]
+    ErgoTreeError(#[from] ErgoTreeError),
+    /// Expression that cannot appear in this position (i.e. `ValDef` outside of a block)
+    #[error("Unexpected expression: {0}")]
+    UnexpectedExpr(String),
+}
+
+/// Decompiles the tree to ErgoScript. Values and lambda arguments are named after their ids
+/// (`v1`, `v2`, ...), so compiling the source of a tree produced by
+/// [`compile`](crate::compiler::compile) yields a tree with the same template bytes.
+/// Public keys are printed as `PK(...)` with the P2PK addresses of the given network.
+pub fn decompile(
+    tree: &ErgoTree,
+    network_prefix: NetworkPrefix,
+) -> Result<DecompiledScript, DecompileError> {
+    decompile_expr(&tree.proposition()?, network_prefix)
+}
+
+/// Decompiles the expression to ErgoScript (see [`decompile`])
+pub fn decompile_expr(
+    expr: &Expr,
+    network_prefix: NetworkPrefix,
+) -> Result<DecompiledScript, DecompileError> {
+    let mut decompiler = Decompiler {
+        network_prefix,
+        constants: Vec::new(),
+        fold_args: Vec::new(),
+    };
+    let code = decompiler.expr(expr, 0)?;
+    Ok(DecompiledScript {
+        source: code.text,
+        constants: decompiler.constants,
+    })
+}
+
+/// `if` and lambdas without braces extend as far to the right as possible
+const PREC_LOWEST: u8 = 0;
+/// Prefix operators (`!`, `-`, `~`)
+const PREC_PREFIX: u8 = 13;
+/// Literals, identifiers, blocks and postfix operations (`.`, call, type arguments)
+const PREC_ATOM: u8 = 14;
+
+/// Decompiled expression and its precedence
+struct Code {
+    text: String,
+    prec: u8,
+}
+
+impl Code {
+    fn atom(text: String) -> Self {
+        Code {
+            text,
+            prec: PREC_ATOM,
+        }
+    }
+
+    /// Text in parentheses if the expression binds weaker than `min_prec`
+    fn wrap(self, min_prec: u8) -> String {
+        if self.prec < min_prec {
+            format!("({})", self.text)
+        } else {
+            self.text
+        }
+    }
+}
+
+struct Decompiler {
+    network_prefix: NetworkPrefix,
+    constants: Vec<(String, Constant)>,
+    /// Tuple arguments of the enclosing fold operations, their fields are printed as the
+    /// `acc<id>` and `item<id>` arguments
+    fold_args: Vec<ValId>,
+}
+
+impl Decompiler {
+    fn expr(&mut self, expr: &Expr, indent: usize) -> Result<Code, DecompileError> {
+        Ok(match expr {
+            Expr::Const(c) => self.constant(c),
+            Expr::ConstPlaceholder(p) => {
+                return Err(DecompileError::UnexpectedExpr(format!(
+                    "constant placeholder {}",
+                    p.id
+                )))
+            }
+            Expr::Context => Code::atom("CONTEXT".to_string()),
+            Expr::Global => Code::atom("Global".to_string()),
+            Expr::GlobalVars(v) => Code::atom(
+                match v {
+                    GlobalVars::Height => "HEIGHT",
+                    GlobalVars::Inputs => "INPUTS",
+                    GlobalVars::Outputs => "OUTPUTS",
+                    GlobalVars::SelfBox => "SELF",
+                    GlobalVars::MinerPubKey => "MinerPubkey",
+                    GlobalVars::GroupGenerator => "groupGenerator",
+                }
+                .to_string(),
+            ),
+            Expr::ValUse(v) => Code::atom(val_name(v.val_id)),
+            Expr::BlockValue(b) => self.block(&b.expr, indent)?,
+            Expr::ValDef(_) | Expr::FunDef(_) => {
+                return Err(DecompileError::UnexpectedExpr(format!(
+                    "value definition outside of a block: {:?}",
+                    expr
+                )))
+            }
+            Expr::FuncValue(f) => self.lambda(f, indent)?,
+            Expr::Apply(a) => {
+                let func = self.expr(&a.func, indent)?.wrap(PREC_ATOM);
+                Code::atom(format!("{}({})", func, self.args(&a.args, indent)?))
+            }
+            Expr::If(i) => Code {
+                text: format!(
+                    "if ({}) {} else {}",
+                    self.expr(&i.condition, indent)?.text,
+                    self.expr(&i.true_branch, indent)?.text,
+                    self.expr(&i.false_branch, indent)?.text
+                ),
+                prec: PREC_LOWEST,
+            },
+            Expr::BinOp(op) => self.bin_op(&op.expr, indent)?,
+            Expr::LogicalNot(op) => self.prefix("!", &op.expr.input, indent)?,
+            Expr::Negation(op) => self.prefix("-", &op.expr.input, indent)?,
+            Expr::BitInversion(op) => self.prefix("~", &op.input, indent)?,
+            Expr::SigmaAnd(op) => self.infix_all("&&", op.items.as_slice(), indent)?,
+            Expr::SigmaOr(op) => self.infix_all("||", op.items.as_slice(), indent)?,
+            Expr::Tuple(t) => Code::atom(format!("({})", self.args(t.items.as_slice(), indent)?)),
+            Expr::Collection(Collection::BoolConstants(items)) => Code::atom(format!(
+                "Coll({})",
+                items
+                    .iter()
+                    .map(|b| b.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )),
+            Expr::Collection(Collection::Exprs { elem_tpe, items }) if items.is_empty() => {
+                Code::atom(format!("Coll[{}]()", elem_tpe))
+            }
+            Expr::Collection(Collection::Exprs { items, .. }) => {
+                self.func("Coll", items.iter(), indent)?
+            }
+            Expr::And(op) => self.func("allOf", [&*op.expr.input], indent)?,
+            Expr::Or(op) => self.func("anyOf", [&*op.expr.input], indent)?,
+            Expr::XorOf(op) => self.func("xorOf", [&*op.input], indent)?,
+            Expr::Xor(op) => self.func("xor", [&*op.left, &*op.right], indent)?,
+            Expr::Atleast(op) => self.func("atLeast", [&*op.bound, &*op.input], indent)?,
+            Expr::BoolToSigmaProp(op) => self.func("sigmaProp", [&*op.input], indent)?,
+            Expr::CreateProveDlog(op) => self.func("proveDlog", [&*op.input], indent)?,
+            Expr::CreateProveDhTuple(op) => {
+                self.func("proveDHTuple", [&*op.g, &*op.h, &*op.u, &*op.v], indent)?
+            }
+            Expr::DecodePoint(op) => self.func("decodePoint", [&*op.input], indent)?,
+            Expr::CalcBlake2b256(op) => self.func("blake2b256", [&*op.input], indent)?,
+            Expr::CalcSha256(op) => self.func("sha256", [&*op.input], indent)?,
+            Expr::LongToByteArray(op) => self.func("longToByteArray", [&*op.input], indent)?,
+            Expr::ByteArrayToLong(op) => self.func("byteArrayToLong", [&*op.expr.input], indent)?,
+            Expr::ByteArrayToBigInt(op) => {
+                self.func("byteArrayToBigInt", [&*op.expr.input], indent)?
+            }
+            Expr::SubstConstants(op) => self.func(
+                "substConstants",
+                [
+                    &*op.expr.script_bytes,
+                    &*op.expr.positions,
+                    &*op.expr.new_values,
+                ],
+                indent,
+            )?,
+            Expr::TreeLookup(op) => self.func(
+                "treeLookup",
+                [&*op.expr.tree, &*op.expr.key, &*op.expr.proof],
+                indent,
+            )?,
+            Expr::CreateAvlTree(op) => self.func(
+                "avlTree",
+                [&*op.flags, &*op.digest, &*op.key_length]
+                    .into_iter()
+                    .chain(op.value_length.as_deref()),
+                indent,
+            )?,
+            Expr::SomeValue(op) => self.func("Some", [&*op.input], indent)?,
+            Expr::NoneValue(op) => Code::atom(format!("None[{}]", op.elem_tpe)),
+            Expr::GetVar(op) => {
+                Code::atom(format!("getVar[{}]({})", op.expr.var_tpe, op.expr.var_id))
+            }
+            Expr::DeserializeContext(op) => {
+                Code::atom(format!("executeFromVar[{}]({})", op.tpe, op.id))
+            }
+            Expr::DeserializeRegister(op) => match &op.default {
+                Some(default) => Code::atom(format!(
+                    "executeFromSelfRegWithDefault[{}]({}, {})",
+                    op.tpe,
+                    op.reg,
+                    self.expr(default, indent)?.text
+                )),
+                None => Code::atom(format!("executeFromSelfReg[{}]({})", op.tpe, op.reg)),
+            },
+            Expr::MethodCall(mc) => {
+                let mc = &mc.expr;
+                let name = match mc.method.explicit_type_args() {
+                    [] => mc.method.name().to_string(),
+                    tpe_vars => format!(
+                        "{}[{}]",
+                        mc.method.name(),
+                        tpe_vars
+                            .iter()
+                            .map(|v| mc
+                                .explicit_type_args
+                                .get(v)
+                                .map(|t| t.to_string())
+                                .unwrap_or_else(|| v.as_string()))
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                };
+                self.method(&mc.obj, &name, &mc.args, indent)?
+            }
+            Expr::PropertyCall(pc) => self.property(&pc.expr.obj, pc.expr.method.name(), indent)?,
+            Expr::ExtractAmount(op) => self.property(&op.input, "value", indent)?,
+            Expr::ExtractScriptBytes(op) => self.property(&op.input, "propositionBytes", indent)?,
+            Expr::ExtractBytes(op) => self.property(&op.input, "bytes", indent)?,
+            Expr::ExtractBytesWithNoRef(op) => {
+                self.property(&op.input, "bytesWithoutRef", indent)?
+            }
+            Expr::ExtractId(op) => self.property(&op.input, "id", indent)?,
+            Expr::ExtractCreationInfo(op) => self.property(&op.input, "creationInfo", indent)?,
+            Expr::ExtractRegisterAs(op) => self.property(
+                &op.expr.input,
+                &format!("R{}[{}]", op.expr.register_id, op.expr.elem_tpe),
+                indent,
+            )?,
+            Expr::OptionGet(op) => self.property(&op.expr.input, "get", indent)?,
+            Expr::OptionIsDefined(op) => self.property(&op.expr.input, "isDefined", indent)?,
+            Expr::OptionGetOrElse(op) => {
+                self.method(&op.expr.input, "getOrElse", [&*op.expr.default], indent)?
+            }
+            Expr::SizeOf(op) => self.property(&op.input, "size", indent)?,
+            Expr::SelectField(op) => self.select_field(&op.expr, indent)?,
+            Expr::ByIndex(op) => {
+                let op = &op.expr;
+                match &op.default {
+                    Some(default) => {
+                        self.method(&op.input, "getOrElse", [&*op.index, &**default], indent)?
+                    }
+                    None => {
+                        let input = self.expr(&op.input, indent)?.wrap(PREC_ATOM);
+                        Code::atom(format!("{}({})", input, self.expr(&op.index, indent)?.text))
+                    }
+                }
+            }
+            Expr::Slice(op) => self.method(
+                &op.expr.input,
+                "slice",
+                [&*op.expr.from, &*op.expr.until],
+                indent,
+            )?,
+            Expr::Append(op) => self.method(&op.expr.input, "append", [&*op.expr.col_2], indent)?,
+            Expr::Map(op) => self.method(&op.expr.input, "map", [&*op.expr.mapper], indent)?,
+            Expr::Filter(op) => {
+                self.method(&op.expr.input, "filter", [&*op.expr.condition], indent)?
+            }
+            Expr::Exists(op) => {
+                self.method(&op.expr.input, "exists", [&*op.expr.condition], indent)?
+            }
+            Expr::ForAll(op) => {
+                self.method(&op.expr.input, "forall", [&*op.expr.condition], indent)?
+            }
+            Expr::Fold(op) => self.fold(&op.expr, indent)?,
+            Expr::CollShift(op) => {
+                self.method(&op.input, &op.kind.to_string(), [&*op.shift], indent)?
+            }
+            Expr::Upcast(op) => self.property(&op.input, &format!("to{}", op.tpe), indent)?,
+            Expr::Downcast(op) => self.property(&op.input, &format!("to{}", op.tpe), indent)?,
+            Expr::SigmaPropBytes(op) => self.property(&op.input, "propBytes", indent)?,
+            Expr::SigmaPropIsProven(op) => self.property(&op.input, "isProven", indent)?,
+            Expr::MultiplyGroup(op) => self.method(&op.left, "multiply", [&*op.right], indent)?,
+            Expr::Exponentiate(op) => self.method(&op.left, "exp", [&*op.right], indent)?,
+            Expr::ModQ(op) => self.property(&op.input, "modQ", indent)?,
+            Expr::ModQArithOp(op) => {
+                self.method(&op.left, &op.kind.to_string(), [&*op.right], indent)?
+            }
+        })
+    }
+
+    /// Literal if there is a syntax for the value, otherwise a reference to the named constant
+    fn constant(&mut self, c: &Constant) -> Code {
+        let literal = match (&c.tpe, &c.v) {
+            (SType::SBoolean, Literal::Boolean(v)) => Some(v.to_string()),
+            (SType::SInt, Literal::Int(v)) if *v >= 0 => Some(v.to_string()),
+            (SType::SLong, Literal::Long(v)) if *v >= 0 => Some(format!("{}L", v)),
+            (SType::SColl(elem_tpe), v) if **elem_tpe == SType::SByte => {
+                Vec::<u8>::try_extract_from(v.clone())
+                    .ok()
+                    .map(|bytes| format!("fromBase16(\"{}\")", base16::encode_lower(&bytes)))
+            }
+            (SType::SSigmaProp, Literal::SigmaProp(sp)) => match sp.value() {
+                SigmaBoolean::ProofOfKnowledge(SigmaProofOfKnowledgeTree::ProveDlog(pk)) => {
+                    Some(format!(
+                        "PK(\"{}\")",
+                        AddressEncoder::encode_address_as_string(
+                            self.network_prefix,
+                            &Address::P2Pk(pk.clone())
+                        )
+                    ))
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        Code::atom(literal.unwrap_or_else(|| {
+            let name = format!("c{}", self.constants.len());
+            self.constants.push((name.clone(), c.clone()));
+            name
+        }))
+    }
+
+    fn bin_op(&mut self, op: &BinOp, indent: usize) -> Result<Code, DecompileError> {
+        let prec = match op.kind {
+            BinOpKind::Arith(ArithOp::Max) => {
+                return self.func("max", [&*op.left, &*op.right], indent)
+            }
+            BinOpKind::Arith(ArithOp::Min) => {
+                return self.func("min", [&*op.left, &*op.right], indent)
+            }
+            BinOpKind::Logical(LogicalOp::Or) | BinOpKind::Bit(BitOp::BitOr) => 1,
+            BinOpKind::Logical(LogicalOp::Xor) | BinOpKind::Bit(BitOp::BitXor) => 2,
+            BinOpKind::Logical(LogicalOp::And) | BinOpKind::Bit(BitOp::BitAnd) => 3,
+            BinOpKind::Relation(RelationOp::Eq | RelationOp::NEq) => 5,
+            BinOpKind::Relation(_)
+            | BinOpKind::Bit(
+                BitOp::BitShiftLeft | BitOp::BitShiftRight | BitOp::BitShiftRightZeroed,
+            ) => 7,
+            BinOpKind::Arith(ArithOp::Plus | ArithOp::Minus) => 9,
+            BinOpKind::Arith(ArithOp::Multiply | ArithOp::Divide | ArithOp::Modulo) => 11,
+        };
+        // operators are left-associative
+        let left = self.expr(&op.left, indent)?.wrap(prec);
+        let right = self.expr(&op.right, indent)?.wrap(prec + 1);
+        Ok(Code {
+            text: format!("{} {} {}", left, op.kind, right),
+            prec,
+        })
+    }
+
+    /// `a && b && c` for the sigma conjectures
+    fn infix_all(
+        &mut self,
+        op: &str,
+        items: &[Expr],
+        indent: usize,
+    ) -> Result<Code, DecompileError> {
+        let prec = if op == "&&" { 3 } else { 1 };
+        let mut operands = Vec::with_capacity(items.len());
+        for (idx, item) in items.iter().enumerate() {
+            let min_prec = if idx == 0 { prec } else { prec + 1 };
+            operands.push(self.expr(item, indent)?.wrap(min_prec));
+        }
+        Ok(Code {
+            text: operands.join(&format!(" {} ", op)),
+            prec,
+        })
+    }
+
+    fn prefix(&mut self, op: &str, input: &Expr, indent: usize) -> Result<Code, DecompileError> {
+        Ok(Code {
+            text: format!("{}{}", op, self.expr(input, indent)?.wrap(PREC_ATOM)),
+            prec: PREC_PREFIX,
+        })
+    }
+
+    /// Predefined function call `name(args)`
+    fn func<'a>(
+        &mut self,
+        name: &str,
+        args: impl IntoIterator<Item = &'a Expr>,
+        indent: usize,
+    ) -> Result<Code, DecompileError> {
+        Ok(Code::atom(format!(
+            "{}({})",
+            name,
+            self.args(args, indent)?
+        )))
+    }
+
+    /// `obj.name`
+    fn property(&mut self, obj: &Expr, name: &str, indent: usize) -> Result<Code, DecompileError> {
+        let obj = self.expr(obj, indent)?.wrap(PREC_ATOM);
+        Ok(Code::atom(format!("{}.{}", obj, name)))
+    }
+
+    /// `obj.name(args)`
+    fn method<'a>(
+        &mut self,
+        obj: &Expr,
+        name: &str,
+        args: impl IntoIterator<Item = &'a Expr>,
+        indent: usize,
+    ) -> Result<Code, DecompileError> {
+        let obj = self.expr(obj, indent)?.wrap(PREC_ATOM);
+        Ok(Code::atom(format!(
+            "{}.{}({})",
+            obj,
+            name,
+            self.args(args, indent)?
+        )))
+    }
+
+    fn args<'a>(
+        &mut self,
+        args: impl IntoIterator<Item = &'a Expr>,
+        indent: usize,
+    ) -> Result<String, DecompileError> {
+        let args = args
+            .into_iter()
+            .map(|arg| self.expr(arg, indent).map(|code| code.text))
+            .collect::<Result<Vec<String>, DecompileError>>()?;
+        Ok(args.join(", "))
+    }
+
+    fn select_field(&mut self, op: &SelectField, indent: usize) -> Result<Code, DecompileError> {
+        let field = op.field_index.zero_based_index() + 1;
+        if let Expr::ValUse(v) = &*op.input {
+            if self.fold_args.contains(&v.val_id) && field <= 2 {
+                return Ok(Code::atom(fold_arg_name(v.val_id, field)));
+            }
+        }
+        self.property(&op.input, &format!("_{}", field), indent)
+    }
+
+    /// Block with every value on a separate line
+    fn block(&mut self, block: &BlockValue, indent: usize) -> Result<Code, DecompileError> {
+        let body = self.block_body(&block.items, &block.result, indent + INDENT)?;
+        Ok(Code::atom(format!(
+            "{{\n{}\n{}}}",
+            body,
+            " ".repeat(indent)
+        )))
+    }
+
+    /// Value definitions and the result (each line indented)
+    fn block_body(
+        &mut self,
+        items: &[Expr],
+        result: &Expr,
+        indent: usize,
+    ) -> Result<String, DecompileError> {
+        let mut lines = Vec::with_capacity(items.len() + 1);
+        for item in items {
+            let (id, rhs) = match item {
+                Expr::ValDef(v) => (&v.expr.id, &v.expr.rhs),
+                Expr::FunDef(f) => (&f.id, &f.rhs),
+                _ => {
+                    return Err(DecompileError::UnexpectedExpr(format!(
+                        "block item is not a value definition: {:?}",
+                        item
+                    )))
+                }
+            };
+            let rhs = self.expr(rhs, indent)?.text;
+            lines.push(format!(
+                "{}val {} = {}",
+                " ".repeat(indent),
+                val_name(*id),
+                rhs
+            ));
+        }
+        let result = self.expr(result, indent)?.text;
+        // otherwise the result would be parsed as a continuation of the last value, i.e.
+        // `val a = b\n(c, d)` as `val a = b(c, d)`
+        if result.starts_with('(') || result.starts_with('-') {
+            if let Some(last) = lines.last_mut() {
+                last.push(';');
+            }
+        }
+        lines.push(format!("{}{}", " ".repeat(indent), result));
+        Ok(lines.join("\n"))
+    }
+
+    /// `{ (v1: Int, v2: Int) => ... }`
+    fn lambda(&mut self, func: &FuncValue, indent: usize) -> Result<Code, DecompileError> {
+        let args = func
+            .args()
+            .iter()
+            .map(|arg| (val_name(arg.idx), arg.tpe.clone()))
+            .collect::<Vec<(String, SType)>>();
+        match func.body() {
+            Expr::BlockValue(b) => {
+                self.lambda_with_body(&args, &b.expr.items, &b.expr.result, indent)
+            }
+            body => self.lambda_with_body(&args, &[], body, indent),
+        }
+    }
+
+    fn lambda_with_body(
+        &mut self,
+        args: &[(String, SType)],
+        items: &[Expr],
+        result: &Expr,
+        indent: usize,
+    ) -> Result<Code, DecompileError> {
+        let params = args
+            .iter()
+            .map(|(name, tpe)| format!("{}: {}", name, tpe))
+            .collect::<Vec<String>>()
+            .join(", ");
+        if items.is_empty() {
+            let body = self.expr(result, indent)?.text;
+            if !body.contains('\n') {
+                return Ok(Code::atom(format!("{{ ({}) => {} }}", params, body)));
+            }
+        }
+        let body = self.block_body(items, result, indent + INDENT)?;
+        Ok(Code::atom(format!(
+            "{{ ({}) =>\n{}\n{}}}",
+            params,
+            body,
+            " ".repeat(indent)
+        )))
+    }
+
+    /// Fold operation takes a single tuple argument in MIR (`(acc, item)`), but a lambda with two
+    /// arguments in ErgoScript
+    fn fold(&mut self, fold: &Fold, indent: usize) -> Result<Code, DecompileError> {
+        let obj = self.expr(&fold.input, indent)?.wrap(PREC_ATOM);
+        let zero = self.expr(&fold.zero, indent)?.text;
+        let fold_op = self.fold_op(&fold.fold_op, indent)?.text;
+        Ok(Code::atom(format!("{}.fold({}, {})", obj, zero, fold_op)))
+    }
+
+    fn fold_op(&mut self, fold_op: &Expr, indent: usize) -> Result<Code, DecompileError> {
+        let (acc_tpe, item_tpe) = match fold_op.tpe() {
+            SType::SFunc(sfunc) => match sfunc.t_dom.as_slice() {
+                [SType::STuple(tuple)] if tuple.items.len() == 2 => {
+                    (tuple.items.first().clone(), tuple.items.last().clone())
+                }
+                _ => return Err(invalid_fold_op(fold_op)),
+            },
+            _ => return Err(invalid_fold_op(fold_op)),
+        };
+        if let Expr::FuncValue(func) = fold_op {
+            // `(t: (R, T)) => ...` using only `t._1` and `t._2` produced by the compilers for
+            // `(acc: R, item: T) => ...`
+            if let [arg] = func.args() {
+                if only_fields_used(func.body(), arg.idx) {
+                    let args = [
+                        (fold_arg_name(arg.idx, 1), acc_tpe),
+                        (fold_arg_name(arg.idx, 2), item_tpe),
+                    ];
+                    self.fold_args.push(arg.idx);
+                    let res = match func.body() {
+                        Expr::BlockValue(b) => {
+                            self.lambda_with_body(&args, &b.expr.items, &b.expr.result, indent)
+                        }
+                        body => self.lambda_with_body(&args, &[], body, indent),
+                    };
+                    self.fold_args.pop();
+                    return res;
+                }
+            }
+        }
+        // otherwise apply the operation to the `(acc, item)` tuple
+        let op = self.expr(fold_op, indent)?.wrap(PREC_ATOM);
+        Ok(Code::atom(format!(
+            "{{ (acc: {}, item: {}) => {}((acc, item)) }}",
+            acc_tpe, item_tpe, op
+        )))
+    }
+}
+
+/// Indent of the block items
+const INDENT: usize = 2;
+
+fn val_name(id: ValId) -> String {
+    format!("v{}", id)
+}
+
+/// Name of the fold operation argument for the field (1-based) of the tuple argument
+fn fold_arg_name(arg_id: ValId, field: usize) -> String {
+    match field {
+        1 => format!("acc{}", arg_id),
+        _ => format!("item{}", arg_id),
+    }
+}
+
+/// Whether the argument is only used as `arg._1` and `arg._2` in the body
+fn only_fields_used(body: &Expr, arg_id: ValId) -> bool {
+    let is_arg =
+        |expr: &Expr| matches!(expr, Expr::ValUse(ValUse { val_id, .. }) if *val_id == arg_id);
+    let (mut uses, mut field_uses) = (0, 0);
+    for_each_descendant(body, &mut |expr| match expr {
+        Expr::SelectField(sf)
+            if is_arg(&sf.expr.input) && sf.expr.field_index.zero_based_index() < 2 =>
+        {
+            field_uses += 1
+        }
+        expr if is_arg(expr) => uses += 1,
+        _ => (),
+    });
+    uses == field_uses
+}
+
+fn invalid_fold_op(fold_op: &Expr) -> DecompileError {
+    DecompileError::UnexpectedExpr(format!(
+        "fold operation should be a function of (R, T) tuple, got {:?}",
+        fold_op.tpe()
+    ))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::compiler::compile;
+    use ergotree_ir::mir::val_def::ValDef;
+    use ergotree_ir::serialization::SigmaSerializable;
+    use expect_test::expect;
+
+    /// Decompiles the compiled source and checks that compiling the decompiled source yields the
+    /// same template
+    fn check(source: &str, expected: expect_test::Expect) {
+        let tree = compile(source, ScriptEnv::new()).unwrap();
+        let decompiled = decompile(&tree, NetworkPrefix::Mainnet).unwrap();
+        let recompiled = compile(&decompiled.source, decompiled.script_env())
+            .unwrap_or_else(|e| panic!("{}", e.pretty_desc(&decompiled.source)));
+        assert_eq!(
+            recompiled.template_bytes().unwrap(),
+            tree.template_bytes().unwrap(),
+            "decompiled:\n{}",
+            decompiled.source
+        );
+        assert_eq!(recompiled.proposition(), tree.proposition());
+        expected.assert_eq(&decompiled.source);
+    }
+
+    #[test]
+    fn test_constants() {
+        check(
+            "sigmaProp(HEIGHT > 100 && SELF.value >= 10L && OUTPUTS(0).propositionBytes == fromBase16(\"0008cd\"))",
+            expect![[r#"sigmaProp(HEIGHT > 100 && SELF.value >= 10L && OUTPUTS(0).propositionBytes == fromBase16("0008cd"))"#]],
+        );
+    }
+
+    #[test]
+    fn test_pk() {
+        check(
+            "PK(\"9f5ZKbECVTm25JTRQHDHGM5ehC8tUw5g1fCBQ4aaE792rWBFrjK\") || sigmaProp(HEIGHT > 10)",
+            expect![[
+                r#"PK("9f5ZKbECVTm25JTRQHDHGM5ehC8tUw5g1fCBQ4aaE792rWBFrjK") || sigmaProp(HEIGHT > 10)"#
+            ]],
+        );
+    }
+
+    #[test]
+    fn test_pk_testnet() {
+        let tree = compile(
+            "PK(\"9f5ZKbECVTm25JTRQHDHGM5ehC8tUw5g1fCBQ4aaE792rWBFrjK\")",
+            ScriptEnv::new(),
+        )
+        .unwrap();
+        let decompiled = decompile(&tree, NetworkPrefix::Testnet).unwrap();
+        expect![[r#"PK("3WwAhuMczx7xcMWkGcbW23rsdeQaFBGiH5dH8fc6RtAXY9mT2CQR")"#]]
+            .assert_eq(&decompiled.source);
+        let recompiled = compile(&decompiled.source, decompiled.script_env()).unwrap();
+        assert_eq!(recompiled, tree);
+    }
+
+    #[test]
+    fn test_named_constants() {
+        let mut env = ScriptEnv::new();
+        env.insert("minHeight".to_string(), (-1i32).into());
+        env.insert("values".to_string(), vec![1i64, 2].into());
+        let tree = compile("sigmaProp(HEIGHT > minHeight && values.size == 2)", env).unwrap();
+        let decompiled = decompile(&tree, NetworkPrefix::Mainnet).unwrap();
+        expect!["sigmaProp(HEIGHT > c0 && c1.size == 2)"].assert_eq(&decompiled.source);
+        assert_eq!(
+            decompiled.constants,
+            vec![
+                ("c0".to_string(), (-1i32).into()),
+                ("c1".to_string(), vec![1i64, 2].into())
+            ]
+        );
+        let recompiled = compile(&decompiled.source, decompiled.script_env()).unwrap();
+        assert_eq!(recompiled, tree);
+    }
+
+    #[test]
+    fn test_precedence() {
+        check(
+            "sigmaProp((HEIGHT - (1 + 2)) * 3 / (4 * 5) == -(HEIGHT + 1) && !(true || false))",
+            expect![[
+                r#"sigmaProp((HEIGHT - (1 + 2)) * 3 / (4 * 5) == -(HEIGHT + 1) && !(true || false))"#
+            ]],
+        );
+    }
+
+    #[test]
+    fn test_block() {
+        check(
+            r#"{
+                val out = OUTPUTS(0)
+                val limit = { val h = HEIGHT; h + 10 }
+                val pair = (out.value, limit)
+                sigmaProp(pair._1 > 0L && (pair._2, 1)._1 > 5)
+            }"#,
+            expect![[r#"
+                {
+                  val v1 = OUTPUTS(0)
+                  val v3 = {
+                    val v2 = HEIGHT
+                    v2 + 10
+                  }
+                  val v4 = (v1.value, v3)
+                  sigmaProp(v4._1 > 0L && (v4._2, 1)._1 > 5)
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn test_tuple_result() {
+        check(
+            "{ val f = { (a: Int) => { val b = a + 1; (b, a) } }; sigmaProp(f(1)._1 > 0) }",
+            expect![[r#"
+                {
+                  val v1 = { (v1: Int) =>
+                    val v2 = v1 + 1;
+                    (v2, v1)
+                  }
+                  sigmaProp(v1(1)._1 > 0)
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn test_collections() {
+        check(
+            r#"{
+                val values = OUTPUTS.map({ (b: Box) => b.value })
+                val total = values.fold(0L, { (acc: Long, v: Long) => acc + v })
+                val big = values.filter({ (v: Long) => v > 100L })
+                sigmaProp(total > 0L && big.exists({ (v: Long) => v > 1000L }) &&
+                  INPUTS.forall({ (b: Box) => b.tokens.size > 0 }) && values.slice(0, 2).append(big).size > 1)
+            }"#,
+            expect![[r#"
+                {
+                  val v1 = OUTPUTS.map({ (v1: Box) => v1.value })
+                  val v2 = v1.fold(0L, { (acc2: Long, item2: Long) => acc2 + item2 })
+                  val v3 = v1.filter({ (v3: Long) => v3 > 100L })
+                  sigmaProp(v2 > 0L && v3.exists({ (v4: Long) => v4 > 1000L }) && INPUTS.forall({ (v4: Box) => v4.tokens.size > 0 }) && v1.slice(0, 2).append(v3).size > 1)
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn test_options() {
+        check(
+            "sigmaProp(SELF.R4[Int].isDefined && SELF.R4[Int].get > 0 && SELF.R5[Long].getOrElse(0L) > 1L)",
+            expect![[r#"sigmaProp(SELF.R4[Int].isDefined && SELF.R4[Int].get > 0 && SELF.R5[Long].getOrElse(0L) > 1L)"#]],
+        );
+    }
+
+    #[test]
+    fn test_if() {
+        check(
+            "sigmaProp(if (HEIGHT > 10) { val h = HEIGHT; h > 20 } else false)",
+            expect![[r#"
+                sigmaProp(if (HEIGHT > 10) {
+                  val v1 = HEIGHT
+                  v1 > 20
+                } else false)"#]],
+        );
+    }
+
+    #[test]
+    fn test_sigma_ops() {
+        check(
+            r#"{
+                val pk = PK("9f5ZKbECVTm25JTRQHDHGM5ehC8tUw5g1fCBQ4aaE792rWBFrjK")
+                anyOf(Coll(HEIGHT > 1, HEIGHT < 100)) && atLeast(1, Coll(pk, pk)) && allOf(Coll(true, HEIGHT > 0))
+            }"#,
+            expect![[r#"
+                {
+                  val v1 = PK("9f5ZKbECVTm25JTRQHDHGM5ehC8tUw5g1fCBQ4aaE792rWBFrjK")
+                  sigmaProp(anyOf(Coll(HEIGHT > 1, HEIGHT < 100))) && atLeast(1, Coll(v1, v1)) && sigmaProp(allOf(Coll(true, HEIGHT > 0)))
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn test_dex_like_contract() {
+        check(
+            r#"{
+                val buyer = PK("9f5ZKbECVTm25JTRQHDHGM5ehC8tUw5g1fCBQ4aaE792rWBFrjK")
+                val tokenId = fromBase16("0123456789abcdef")
+                val out = OUTPUTS(0)
+                val tokens = out.tokens
+                val tokenAmount = tokens.fold(0L, { (acc: Long, t: (Coll[Byte], Long)) =>
+                    if (t._1 == tokenId) acc + t._2 else acc
+                })
+                val paid = out.propositionBytes == SELF.propositionBytes && tokenAmount >= 100L
+                buyer || sigmaProp(paid && out.R4[Coll[Byte]].get == SELF.id)
+            }"#,
+            expect![[r#"
+                {
+                  val v1 = PK("9f5ZKbECVTm25JTRQHDHGM5ehC8tUw5g1fCBQ4aaE792rWBFrjK")
+                  val v2 = fromBase16("0123456789abcdef")
+                  val v3 = OUTPUTS(0)
+                  val v4 = v3.tokens
+                  val v5 = v4.fold(0L, { (acc5: Long, item5: (Coll[Byte], Long)) => if (item5._1 == v2) acc5 + item5._2 else acc5 })
+                  val v6 = v3.propositionBytes == SELF.propositionBytes && v5 >= 100L
+                  v1 || sigmaProp(v6 && v3.R4[Coll[Byte]].get == SELF.id)
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn test_unsupported_expr() {
+        let expr: Expr = ValDef {
+            id: 1.into(),
+            rhs: Box::new(Expr::Const(1i32.into())),
+        }
+        .into();
+        assert!(matches!(
+            decompile_expr(&expr, NetworkPrefix::Mainnet),
+            Err(DecompileError::UnexpectedExpr(_))
+        ));
+    }
+
+    /// Decompiles the mainnet tree (compiled by the reference compiler) and checks that compiling
+    /// the decompiled source yields the same proposition (constants are segregated in the
+    /// mainnet trees, so the template bytes differ)
+    fn check_mainnet(tree_hex: &str, expected: expect_test::Expect) {
+        let tree = ErgoTree::sigma_parse_bytes(&base16::decode(tree_hex).unwrap()).unwrap();
+        let decompiled = decompile(&tree, NetworkPrefix::Mainnet).unwrap();
+        let recompiled = compile(&decompiled.source, decompiled.script_env())
+            .unwrap_or_else(|e| panic!("{}", e.pretty_desc(&decompiled.source)));
+        assert_eq!(
+            recompiled.proposition(),
+            tree.proposition(),
+            "decompiled:\n{}",
+            decompiled.source
+        );
+        expected.assert_eq(&decompiled.source);
+    }
+
+    #[test]
+    fn test_mainnet_p2pk_with_height() {
+        // P2PK spendable after the box is 720 blocks old
+        check_mainnet(
+            concat!(
+                "100204a00b08cd021dde34603426402615658f1d970cfa7c7bd92ac81a8b16eeebff264d59ce4604",
+                "ea02d192a39a8cc7a70173007301",
+            ),
+            expect![[
+                r#"sigmaProp(HEIGHT >= SELF.creationInfo._1 + 720) && PK("9ekDTCzgNvasQ3xrW6PuCzbhSRsiv6CJuxjbuQKupu6vxY58jmv")"#
+            ]],
+        );
+    }
+
+    #[test]
+    fn test_mainnet_miner_fee() {
+        // Miner fee
+        check_mainnet(
+            concat!(
+                "1005040004000e36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d9",
+                "59f2815b16f81798ea02d192a39a8cc7a701730073011001020402d19683030193a38cc7b2a57300",
+                "000193c2b2a57301007473027303830108cdeeac93b1a57304",
+            ),
+            expect![[
+                r#"sigmaProp(allOf(Coll(HEIGHT == OUTPUTS(0).creationInfo._1, OUTPUTS(0).propositionBytes == substConstants(fromBase16("100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ea02d192a39a8cc7a70173007301"), c0, Coll(proveDlog(decodePoint(MinerPubkey)))), OUTPUTS.size == 1)))"#
+            ]],
+        );
+    }
+
+    #[test]
+    fn test_mainnet_emission() {
+        // Emission
+        check_mainnet(
+            concat!(
+                "101004020e36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2",
+                "815b16f81798ea02d192a39a8cc7a7017300730110010204020404040004c0fd4f05808c82f5f603",
+                "0580b8c9e5ae040580f882ad16040204c0944004c0f407040004000580f882ad16d19683030191a3",
+                "8cc7a7019683020193c2b2a57300007473017302830108cdeeac93a38cc7b2a57303000197830201",
+                "9683040193b1a5730493c2a7c2b2a573050093958fa3730673079973089c73097e9a730a9d99a373",
+                "0b730c0599c1a7c1b2a5730d00938cc7b2a5730e0001a390c1a7730f",
+            ),
+            expect![[
+                r#"sigmaProp(allOf(Coll(HEIGHT > SELF.creationInfo._1, allOf(Coll(OUTPUTS(1).propositionBytes == substConstants(fromBase16("100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ea02d192a39a8cc7a70173007301"), c0, Coll(proveDlog(decodePoint(MinerPubkey)))), HEIGHT == OUTPUTS(1).creationInfo._1)), anyOf(Coll(allOf(Coll(OUTPUTS.size == 2, SELF.propositionBytes == OUTPUTS(0).propositionBytes, (if (HEIGHT < 655200) 67500000000L else 75000000000L - 3000000000L * (1 + (HEIGHT - 525600) / 64800).toLong) == SELF.value - OUTPUTS(0).value, OUTPUTS(0).creationInfo._1 == HEIGHT)), SELF.value <= 3000000000L)))))"#
+            ]],
+        );
+    }
+
+    #[test]
+    fn test_mainnet_oracle_datapoint() {
+        // Oracle datapoint checked against the oracle pool box in the data inputs
+        check_mainnet(
+            concat!(
+                "100604000400050004000e20b662db51cf2dc39f110a021c2a31c74f0a1a18ffffbf73e8a051a7b8",
+                "c0f09ebc0e2079974b2314c531e62776e6bc4babff35b37b178cebf0976fc0f416ff34ddbc4fd803",
+                "d601b2a5730000d602e4c6a70407d603b2db6501fe730100ea02d1ededededed93e4c67201040772",
+                "0293e4c67201050ec5720391e4c672010605730293c27201c2a793db63087201db6308a7ed938cb2",
+                "db6308720373030001730493cbc272037305cd7202",
+            ),
+            expect![[r#"
+                {
+                  val v1 = OUTPUTS(0)
+                  val v2 = SELF.R4[GroupElement].get
+                  val v3 = CONTEXT.dataInputs(0)
+                  sigmaProp(v1.R4[GroupElement].get == v2 && v1.R5[Coll[Byte]].get == v3.id && v1.R6[Long].get > 0L && v1.propositionBytes == SELF.propositionBytes && v1.tokens == SELF.tokens && (v3.tokens(0)._1 == fromBase16("b662db51cf2dc39f110a021c2a31c74f0a1a18ffffbf73e8a051a7b8c0f09ebc") && blake2b256(v3.propositionBytes) == fromBase16("79974b2314c531e62776e6bc4babff35b37b178cebf0976fc0f416ff34ddbc4f"))) && proveDlog(v2)
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn test_mainnet_payout_split() {
+        // Payout split between three public keys (fold over the inputs)
+        check_mainnet(
+            concat!(
+                "100e04000500059a0505d00f04020404040608cd03c6543ac8e8059748b1c6209ee419dd49a19ffa",
+                "f5712a2f34a9412016a3a1d96708cd035b736bebf0c5393f78329f6894af84d1864c7496cc65ddc2",
+                "50ef60cdd75df52008cd021b63e19ab452c84cdc6687242e8494957b1f11e3750c8c184a8425f8a8",
+                "171d9b05060580ade2040580a8d6b907040ad806d601b2a5730000d602b0a47301d9010241639a8c",
+                "720201c18c720202d6039d9c730272027303d604b2a5730400d605b2a5730500d606b2a5730600d1",
+                "968306019683020193c17201720393c27201d073079683020193c17204720393c27204d073089683",
+                "020193c17205720393c27205d073099683020192c17206999972029c730a7203730b93c2a7c27206",
+                "927202730c93b1a5730d",
+            ),
+            expect![[r#"
+                {
+                  val v1 = OUTPUTS(0)
+                  val v2 = INPUTS.fold(0L, { (acc2: Long, item2: Box) => acc2 + item2.value })
+                  val v3 = 333L * v2 / 1000L
+                  val v4 = OUTPUTS(1)
+                  val v5 = OUTPUTS(2)
+                  val v6 = OUTPUTS(3)
+                  sigmaProp(allOf(Coll(allOf(Coll(v1.value == v3, v1.propositionBytes == PK("9hy9jt1Vuq3fZr4rSYAUqo1r2dAJBBdazV6cL8FNuBQEvM6wXfR").propBytes)), allOf(Coll(v4.value == v3, v4.propositionBytes == PK("9hA5gTKrx1YsTDjYiSnYqsAWawMq1GbvaemobybpCZ8qyHFBXKF").propBytes)), allOf(Coll(v5.value == v3, v5.propositionBytes == PK("9ej8AEGCpNxPaqfgisJTU2RmYG91bWfK1hu2xT34i5Xdw4czidX").propBytes)), allOf(Coll(v6.value >= v2 - 3L * v3 - 5000000L, SELF.propositionBytes == v6.propositionBytes)), v2 >= 1000000000L, OUTPUTS.size == 5)))
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn test_mainnet_token_sell_order() {
+        // Token sell order (`BigInt` arithmetic, map and fold over the outputs)
+        check_mainnet(
+            concat!(
+                "101808cd0279aed8dea2b2a25316d5d49d13bf51c0b2c1dc696974bb4b0c07b5894e998e56040005",
+                "e0e0a447040404060402040004000e201d5afc59838920bb5ef2a8f9d63825a55b1d48e269d7cece",
+                "e335d637c3ff5f3f0e20003bd19d0187117f130b62e1bcab0939929ff5c7709f843c5c4dd1589492",
+                "85d005e201058c85a2010514040404c60f06010104d00f05e0e0a44704c60f0e691005040004000e",
+                "36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f817",
+                "98ea02d192a39a8cc7a701730073011001020402d19683030193a38cc7b2a57300000193c2b2a573",
+                "01007473027303830108cdeeac93b1a57304050005000580ade2040100d803d6017300d602b2a473",
+                "0100d6037302eb027201d195ed93b1a4730393b1db630872027304d804d604db63087202d605b2a5",
+                "730500d606b2db63087205730600d6077e8c72060206edededededed938cb2720473070001730893",
+                "c27205d07201938c72060173099272077e730a06927ec172050699997ec1a7069d9c72077e730b06",
+                "7e730c067e720306909c9c7e8cb27204730d0002067e7203067e730e069c9a7207730f9a9c7ec172",
+                "02067e7310067e9c73117e7312050690b0ada5d90108639593c272087313c1720873147315d90108",
+                "599a8c7208018c72080273167317",
+            ),
+            expect![[r#"
+                {
+                  val v1 = PK("9fSek6bWQ2yusFHyJARD95KPTCrn5rfEav6msGZpxQZQvcBADQ9")
+                  val v2 = INPUTS(0)
+                  val v3 = 74750000L
+                  v1 || sigmaProp(if (INPUTS.size == 2 && v2.tokens.size == 3) {
+                    val v4 = v2.tokens
+                    val v5 = OUTPUTS(1)
+                    val v6 = v5.tokens(0)
+                    val v7 = v6._2.toBigInt
+                    v4(0)._1 == fromBase16("1d5afc59838920bb5ef2a8f9d63825a55b1d48e269d7cecee335d637c3ff5f3f") && v5.propositionBytes == v1.propBytes && v6._1 == fromBase16("003bd19d0187117f130b62e1bcab0939929ff5c7709f843c5c4dd158949285d0") && v7 >= 113L.toBigInt && v5.value.toBigInt >= SELF.value.toBigInt - v7 * 1327430L.toBigInt / 10L.toBigInt - v3.toBigInt && v4(2)._2.toBigInt * v3.toBigInt * 995.toBigInt <= (v7 + c0) * (v2.value.toBigInt * 1000.toBigInt + (74750000L * 995.toLong).toBigInt) && OUTPUTS.map({ (v8: Box) => if (v8.propositionBytes == fromBase16("1005040004000e36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ea02d192a39a8cc7a701730073011001020402d19683030193a38cc7b2a57300000193c2b2a57301007473027303830108cdeeac93b1a57304")) v8.value else 0L }).fold(0L, { (acc8: Long, item8: Long) => acc8 + item8 }) <= 5000000L
+                  } else false)
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn test_mainnet_sigmausd_bank() {
+        // SigmaUSD bank (`min` and a block in the `if` branch)
+        check_mainnet(
+            concat!(
+                "101f0400040004020402040004000402050005000580dac4090580dac409050005c00c05c8010400",
+                "0e20b662db51cf2dc39f110a021c2a31c74f0a1a18ffffbf73e8a051a7b8c0f09ebc0580dac40904",
+                "040404050005feffffffffffffffff01050005e807050005e807050005a0060101050005c00c05a0",
+                "06d81ed601b2db6501fe730000d602b2a5730100d603c17202d604db6308a7d605b27204730200d6",
+                "068c720502d607db63087202d608b27207730300d6098c720802d60a9472067209d60bb272047304",
+                "00d60c8c720b02d60db27207730500d60e8c720d02d60f94720c720ed610e4c6a70505d611e4c672",
+                "020505d612e4c6a70405d613e4c672020405d614b2a5730600d615e4c672140405d61695720a7307",
+                "7215d61795720a72157308d61899c1a77309d619e4c672140505d61a997203730ad61be4c6720104",
+                "05d61ca172189c7212721bd61d9c7213721bd61e9593721d730b730c9d9c721a730d721dd1ededed",
+                "938cb2db63087201730e0001730fedededed9272037310edec720a720fefed720a720fed939a7206",
+                "72109a72097211939a720c72129a720e7213eded939a721272167213939a721072177211939a7218",
+                "7219721aeded938c720d018c720b01938c7208018c720501938cb27207731100018cb27204731200",
+                "0193721995720f9ca1721b95937212731373149d721c72127216d801d61f997218721c9c9593721f",
+                "7315731695937210731773189d721f7210721795720f95917216731992721e731a731b9591721773",
+                "1c90721e731d92721e731e",
+            ),
+            expect![[r#"
+                {
+                  val v1 = CONTEXT.dataInputs(0)
+                  val v2 = OUTPUTS(0)
+                  val v3 = v2.value
+                  val v4 = SELF.tokens
+                  val v5 = v4(1)
+                  val v6 = v5._2
+                  val v7 = v2.tokens
+                  val v8 = v7(1)
+                  val v9 = v8._2
+                  val v10 = v6 != v9
+                  val v11 = v4(0)
+                  val v12 = v11._2
+                  val v13 = v7(0)
+                  val v14 = v13._2
+                  val v15 = v12 != v14
+                  val v16 = SELF.R5[Long].get
+                  val v17 = v2.R5[Long].get
+                  val v18 = SELF.R4[Long].get
+                  val v19 = v2.R4[Long].get
+                  val v20 = OUTPUTS(1)
+                  val v21 = v20.R4[Long].get
+                  val v22 = if (v10) 0L else v21
+                  val v23 = if (v10) v21 else 0L
+                  val v24 = SELF.value - 10000000L
+                  val v25 = v20.R5[Long].get
+                  val v26 = v3 - 10000000L
+                  val v27 = v1.R4[Long].get
+                  val v28 = min(v24, v18 * v27)
+                  val v29 = v19 * v27
+                  val v30 = if (v29 == 0L) 800L else v26 * 100L / v29
+                  sigmaProp(v1.tokens(0)._1 == fromBase16("b662db51cf2dc39f110a021c2a31c74f0a1a18ffffbf73e8a051a7b8c0f09ebc") && (v3 >= 10000000L && ((v10 || v15) && !(v10 && v15)) && (v6 + v16 == v9 + v17 && v12 + v18 == v14 + v19) && (v18 + v22 == v19 && v16 + v23 == v17 && v24 + v25 == v26) && (v13._1 == v11._1 && v8._1 == v5._1 && v7(2)._1 == v4(2)._1)) && v25 == (if (v15) min(v27, if (v18 == 0L) 9223372036854775807L else v28 / v18) * v22 else {
+                    val v31 = v24 - v28;
+                    (if (v31 == 0L) 500L else if (v16 == 0L) 500L else v31 / v16) * v23
+                  }) && (if (v15) if (v22 > 0L) v30 >= 400L else true else if (v23 > 0L) v30 <= 800L else v30 >= 400L))
+                }"#]],
+        );
+    }
+}
@@ -1,23 +1,217 @@
-use line_col::LineColLookup;
+use std::fmt;
+
+use miette::Diagnostic;
+use miette::GraphicalReportHandler;
+use miette::GraphicalTheme;
+use miette::LabeledSpan;
+use miette::SourceCode;
+use miette::SourceSpan;
 use rowan::TextRange;
 
-pub fn pretty_error_desc(source: &str, span: TextRange, error_msg: &str) -> String {
-    let line_col_lookup = LineColLookup::new(source);
-    let start_zero_based: usize = usize::from(span.start()) - 1;
-    let end_zero_based: usize = usize::from(span.end()) - 1;
-    let (line_start, col_start) = line_col_lookup.get(start_zero_based);
-    let (line_end, col_end) = line_col_lookup.get(end_zero_based);
-    if line_end != line_start {
-        return "Multiline error spans are not yet supported".to_string();
-    }
-    let source_line = source.lines().nth(line_start - 1).unwrap();
-    let highlight = format!("{0:^>span$}", "^", span = col_end - col_start + 1);
-    format!(
-        "{0}\nline: {1}\n{2}\n{3:>ident$}",
-        error_msg,
-        line_start,
-        source_line,
-        highlight,
-        ident = col_start + 1,
-    )
+/// Secondary label of the error, i.e. the type of an operand in a type mismatch
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Label {
+    pub msg: String,
+    pub span: TextRange,
+}
+
+pub fn source_span(span: TextRange) -> SourceSpan {
+    (usize::from(span.start()), usize::from(span.len())).into()
+}
+
+/// Secondary labels of the error, or the error span if there are none (the labels are usually
+/// inside the error span and would overlap with it)
+pub fn labeled_spans<'a>(
+    span: TextRange,
+    labels: &'a [Label],
+) -> Box<dyn Iterator<Item = LabeledSpan> + 'a> {
+    if labels.is_empty() {
+        Box::new(std::iter::once(LabeledSpan::new_with_span(
+            None,
+            source_span(span),
+        )))
+    } else {
+        Box::new(
+            labels
+                .iter()
+                .map(|l| LabeledSpan::new_with_span(Some(l.msg.clone()), source_span(l.span))),
+        )
+    }
+}
+
+/// Kind of the compilation error, reported as the diagnostic code
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ErrorKind {
+    /// Token not allowed by the grammar
+    UnexpectedToken,
+    /// Syntax that cannot be lowered, i.e. a block without the result expression
+    InvalidSyntax,
+    /// Type name that is not defined
+    UnknownType,
+    /// Identifier that is not defined in the script or in the env
+    UnknownIdent,
+    /// Property or method that the type does not have
+    UnknownMember,
+    /// Malformed string literal (address, Base16 or Base58) or a string literal out of place
+    InvalidLiteral,
+    /// Operand or argument of an unexpected type
+    TypeMismatch,
+    /// Function or method applied to a wrong number of arguments
+    WrongArgCount,
+    /// Type that cannot be inferred or given explicitly, i.e. of an empty collection
+    CannotInferType,
+    /// Tuple with less than 2 or more than 255 items
+    InvalidTuple,
+    /// Expression that the compiler cannot lower to ErgoTree (a compiler bug)
+    Internal,
+    /// ErgoTree type checking of the compiled expression
+    TypeCheck,
+    /// ErgoTree building (i.e. constant segregation) of the compiled expression
+    ErgoTree,
+    /// Contract template building of the compiled expression
+    ContractTemplate,
+}
+
+impl ErrorKind {
+    /// Diagnostic code, i.e. `ergoscript::type_mismatch`
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorKind::UnexpectedToken => "ergoscript::unexpected_token",
+            ErrorKind::InvalidSyntax => "ergoscript::invalid_syntax",
+            ErrorKind::UnknownType => "ergoscript::unknown_type",
+            ErrorKind::UnknownIdent => "ergoscript::unknown_ident",
+            ErrorKind::UnknownMember => "ergoscript::unknown_member",
+            ErrorKind::InvalidLiteral => "ergoscript::invalid_literal",
+            ErrorKind::TypeMismatch => "ergoscript::type_mismatch",
+            ErrorKind::WrongArgCount => "ergoscript::wrong_arg_count",
+            ErrorKind::CannotInferType => "ergoscript::cannot_infer_type",
+            ErrorKind::InvalidTuple => "ergoscript::invalid_tuple",
+            ErrorKind::Internal => "ergoscript::internal",
+            ErrorKind::TypeCheck => "ergoscript::type_check",
+            ErrorKind::ErgoTree => "ergoscript::ergo_tree",
+            ErrorKind::ContractTemplate => "ergoscript::contract_template",
+        }
+    }
+
+    /// Hint on fixing the error
+    pub fn help(&self) -> Option<&'static str> {
+        match self {
+            ErrorKind::UnknownIdent => Some(
+                "check the spelling, define it with `val` before the use or pass its value in the ScriptEnv",
+            ),
+            ErrorKind::TypeMismatch => Some(
+                "the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt",
+            ),
+            ErrorKind::WrongArgCount => {
+                Some("check the number of arguments in the function or method signature")
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Implements [`Diagnostic`] for the error with `kind`, `msg`, `span` and `labels` fields
+macro_rules! impl_diagnostic {
+    ($error:ty) => {
+        impl miette::Diagnostic for $error {
+            fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+                Some(Box::new(self.kind.code()))
+            }
+
+            fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+                self.kind
+                    .help()
+                    .map(|help| Box::new(help) as Box<dyn std::fmt::Display>)
+            }
+
+            fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+                Some(crate::error::labeled_spans(self.span, &self.labels))
+            }
+        }
+    };
+}
+
+pub(crate) use impl_diagnostic;
+
+/// Both results, or the errors of both
+pub fn both<A, B, E>(a: Result<A, Vec<E>>, b: Result<B, Vec<E>>) -> Result<(A, B), Vec<E>> {
+    match (a, b) {
+        (Ok(a), Ok(b)) => Ok((a, b)),
+        (a, b) => Err(a.err().into_iter().chain(b.err()).flatten().collect()),
+    }
+}
+
+/// All the results, or the errors of all of them
+pub fn all<T, E>(results: impl IntoIterator<Item = Result<T, Vec<E>>>) -> Result<Vec<T>, Vec<E>> {
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+    let mut failed = false;
+    for res in results {
+        match res {
+            Ok(value) => values.push(value),
+            Err(errs) => {
+                failed = true;
+                errors.extend(errs);
+            }
+        }
+    }
+    if failed {
+        Err(errors)
+    } else {
+        Ok(values)
+    }
+}
+
+/// Renders the error with the labelled source code
+pub fn pretty_error_desc(source: &str, error: &dyn Diagnostic) -> String {
+    let mut out = String::new();
+    let report = WithSource { error, source };
+    match GraphicalReportHandler::new_themed(GraphicalTheme::none())
+        .render_report(&mut out, &report)
+    {
+        Ok(()) => out,
+        Err(_) => error.to_string(),
+    }
+}
+
+/// Attaches the source code to the error for rendering
+struct WithSource<'a> {
+    error: &'a dyn Diagnostic,
+    source: &'a str,
+}
+
+impl fmt::Debug for WithSource<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.error, f)
+    }
+}
+
+impl fmt::Display for WithSource<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.error, f)
+    }
+}
+
+impl std::error::Error for WithSource<'_> {}
+
+impl Diagnostic for WithSource<'_> {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.error.code()
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.error.help()
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.source)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        self.error.labels()
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        self.error.related()
+    }
 }
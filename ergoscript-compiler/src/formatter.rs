@@ -0,0 +1,737 @@
+//! ErgoScript source formatter
+//!
+//! Formats the lossless syntax tree: normalizes the indentation and spacing, breaks the lines
+//! longer than [`MAX_WIDTH`] (long method chains are broken before each `.`) and keeps every
+//! comment (trailing comments stay on their line, the others are placed on their own line).
+
+mod doc;
+
+use crate::compiler::CompileError;
+use crate::parser::parse;
+use crate::syntax::SyntaxElement;
+use crate::syntax::SyntaxKind;
+use crate::syntax::SyntaxNode;
+use crate::syntax::SyntaxToken;
+
+use self::doc::Doc;
+
+/// Maximum line width
+pub const MAX_WIDTH: usize = 100;
+
+/// Formats the source code, returns it unchanged if it cannot be parsed (see [`try_format`])
+pub fn format(source: &str) -> String {
+    try_format(source).unwrap_or_else(|_| source.to_string())
+}
+
+/// Formats the source code, or returns the parse errors
+pub fn try_format(source: &str) -> Result<String, CompileError> {
+    let parse = parse(source);
+    if !parse.errors.is_empty() {
+        return Err(CompileError::ParseError(parse.errors));
+    }
+    let root = parse.syntax();
+    let (leading, elems) = elements(&root);
+    let mut docs = leading;
+    let blank_line_after_leading = elems
+        .first()
+        .and_then(Elem::first_token)
+        .map_or(false, |token| newlines_before(&token) > 1);
+    if !docs.is_empty() && blank_line_after_leading {
+        docs.push(Doc::EmptyLine);
+    }
+    docs.extend(stmts(&elems));
+    Ok(doc::print(&docs, MAX_WIDTH))
+}
+
+/// Non-trivia child (node or token) with the comments following it
+struct Elem {
+    element: SyntaxElement,
+    comments: Vec<Doc>,
+}
+
+impl Elem {
+    fn kind(&self) -> SyntaxKind {
+        self.element.kind()
+    }
+
+    fn node(&self) -> Option<&SyntaxNode> {
+        self.element.as_node()
+    }
+
+    fn first_token(&self) -> Option<SyntaxToken> {
+        match &self.element {
+            rowan::NodeOrToken::Node(node) => node.first_token(),
+            rowan::NodeOrToken::Token(token) => Some(token.clone()),
+        }
+    }
+
+    /// Layout of the element followed by its comments
+    fn doc(&self) -> Vec<Doc> {
+        let mut docs = match &self.element {
+            rowan::NodeOrToken::Node(node) => node_doc(node),
+            rowan::NodeOrToken::Token(token) => vec![token_doc(token)],
+        };
+        docs.extend(self.comments.iter().cloned());
+        docs
+    }
+}
+
+/// Comments before the first non-trivia child, and the non-trivia children with the comments
+/// following them
+fn elements(node: &SyntaxNode) -> (Vec<Doc>, Vec<Elem>) {
+    let hoisted = if node.kind() == SyntaxKind::Root {
+        Vec::new()
+    } else {
+        hoisted_comments(node)
+    };
+    let mut leading = Vec::new();
+    let mut elems: Vec<Elem> = Vec::new();
+    for element in node.children_with_tokens() {
+        match element {
+            rowan::NodeOrToken::Token(token) if token.kind() == SyntaxKind::Whitespace => (),
+            rowan::NodeOrToken::Token(token) if token.kind() == SyntaxKind::Comment => {
+                if hoisted.contains(&token) {
+                    continue;
+                }
+                match elems.last_mut() {
+                    Some(elem) => elem.comments.extend(comment_doc(&token, false)),
+                    None => leading.extend(comment_doc(&token, true)),
+                }
+            }
+            rowan::NodeOrToken::Node(child) => {
+                // comments on their own lines after the last child are placed after the parent
+                let comments = hoisted_comments(&child)
+                    .iter()
+                    .filter(|comment| !hoisted.contains(comment))
+                    .flat_map(|comment| comment_doc(comment, false))
+                    .collect();
+                elems.push(Elem {
+                    element: child.into(),
+                    comments,
+                })
+            }
+            element => elems.push(Elem {
+                element,
+                comments: Vec::new(),
+            }),
+        }
+    }
+    (leading, elems)
+}
+
+/// Comments at the end of the node, starting with the first one on its own line
+fn hoisted_comments(node: &SyntaxNode) -> Vec<SyntaxToken> {
+    let mut hoisted = Vec::new();
+    let mut own_line = false;
+    for token in node
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+    {
+        match token.kind() {
+            SyntaxKind::Whitespace => (),
+            SyntaxKind::Comment => {
+                own_line = own_line || newlines_before(&token) > 0;
+                if own_line {
+                    hoisted.push(token);
+                }
+            }
+            _ => {
+                own_line = false;
+                hoisted.clear();
+            }
+        }
+    }
+    hoisted
+}
+
+/// Number of line breaks in the whitespace preceding the token
+fn newlines_before(token: &SyntaxToken) -> usize {
+    token
+        .prev_token()
+        .filter(|t| t.kind() == SyntaxKind::Whitespace)
+        .map_or(0, |t| t.text().matches('\n').count())
+}
+
+/// Comment placed on the same line as the preceding code if it was there, otherwise on its own
+/// line (after a blank line if it was there)
+fn comment_doc(token: &SyntaxToken, at_start: bool) -> Vec<Doc> {
+    let separator = match newlines_before(token) {
+        0 if !at_start => Doc::text(" "),
+        0 | 1 => Doc::HardLine,
+        _ => Doc::EmptyLine,
+    };
+    vec![separator, Doc::Comment(token.text().trim_end().to_string())]
+}
+
+/// Statements (of the root or a block) on separate lines, blank lines between them are kept
+fn stmts(elems: &[Elem]) -> Vec<Doc> {
+    let stmts: Vec<&Elem> = elems
+        .iter()
+        .filter(|e| e.kind() != SyntaxKind::Semicolon)
+        .collect();
+    let mut docs = Vec::new();
+    let mut stmt_idx = 0;
+    for elem in elems {
+        if elem.kind() == SyntaxKind::Semicolon {
+            // the comments of the dropped separator are kept
+            docs.extend(elem.comments.iter().cloned());
+            continue;
+        }
+        if stmt_idx > 0 {
+            docs.push(if blank_line_between(stmts[stmt_idx - 1], elem) {
+                Doc::EmptyLine
+            } else {
+                Doc::HardLine
+            });
+        }
+        match elem.node() {
+            Some(node) => docs.extend(node_doc(node)),
+            None => docs.push(Doc::text(elem.element.to_string())),
+        }
+        // otherwise the next statement would be parsed as a continuation of this one,
+        // i.e. `val a = b; (c, d)` as `val a = b(c, d)`
+        let next_needs_separator = stmts.get(stmt_idx + 1).map_or(false, |next| {
+            matches!(
+                next.first_token().map(|t| t.kind()),
+                Some(SyntaxKind::LParen) | Some(SyntaxKind::Minus)
+            )
+        });
+        if next_needs_separator {
+            docs.push(Doc::text(";"));
+        }
+        docs.extend(elem.comments.iter().cloned());
+        stmt_idx += 1;
+    }
+    docs
+}
+
+/// Whether there is a blank line before the next element (after the comments following the
+/// previous one)
+fn blank_line_between(prev: &Elem, next: &Elem) -> bool {
+    let last_token = match &prev.element {
+        rowan::NodeOrToken::Node(node) => node
+            .descendants_with_tokens()
+            .filter_map(|e| e.into_token())
+            .filter(|t| !is_trivia(t.kind()))
+            .last(),
+        rowan::NodeOrToken::Token(token) => Some(token.clone()),
+    };
+    let next_start = next.element.text_range().start();
+    let mut newlines = 0;
+    let mut token = last_token.and_then(|t| t.next_token());
+    while let Some(t) = token {
+        if t.text_range().start() >= next_start {
+            break;
+        }
+        match t.kind() {
+            SyntaxKind::Whitespace => newlines += t.text().matches('\n').count(),
+            _ => newlines = 0,
+        }
+        token = t.next_token();
+    }
+    newlines > 1
+}
+
+fn is_trivia(kind: SyntaxKind) -> bool {
+    matches!(kind, SyntaxKind::Whitespace | SyntaxKind::Comment)
+}
+
+fn token_doc(token: &SyntaxToken) -> Doc {
+    Doc::text(match token.kind() {
+        SyntaxKind::Comma => ", ",
+        SyntaxKind::Colon => ": ",
+        SyntaxKind::Equals => " = ",
+        SyntaxKind::FatArrow => " => ",
+        SyntaxKind::ValKw => "val ",
+        _ => token.text(),
+    })
+}
+
+fn node_doc(node: &SyntaxNode) -> Vec<Doc> {
+    let (_, elems) = elements(node);
+    match node.kind() {
+        SyntaxKind::BlockExpr => block(&elems),
+        SyntaxKind::LambdaExpr => lambda(&elems),
+        SyntaxKind::InfixExpr => infix(node),
+        SyntaxKind::SelectExpr | SyntaxKind::CallExpr | SyntaxKind::ApplyTypesExpr => {
+            postfix_chain(node)
+        }
+        SyntaxKind::ArgList | SyntaxKind::TupleExpr => delimited_list(&elems),
+        SyntaxKind::IfExpr => if_expr(&elems),
+        // `val a: T = rhs`, parameters, types, literals, etc.
+        _ => elems.iter().flat_map(Elem::doc).collect(),
+    }
+}
+
+/// `{ stmts }` or `{ (args) => stmts }` (or the lambda body without braces)
+fn block(elems: &[Elem]) -> Vec<Doc> {
+    let (open, inner, close) = match elems {
+        [open, inner @ .., close]
+            if open.kind() == SyntaxKind::LBrace && close.kind() == SyntaxKind::RBrace =>
+        {
+            (open, inner, close)
+        }
+        // lambda body in braces
+        _ => return stmts(elems),
+    };
+    let mut docs = open.doc();
+    if let [lambda] = inner {
+        let (_, lambda_elems) = lambda.node().map(elements).unwrap_or_default();
+        if let [params @ .., arrow, body] = lambda_elems.as_slice() {
+            if arrow.kind() == SyntaxKind::FatArrow {
+                // `{ (args) => body }`
+                docs.push(Doc::text(" "));
+                docs.extend(params.iter().flat_map(Elem::doc));
+                docs.push(Doc::text(" =>"));
+                docs.extend(arrow.comments.iter().cloned());
+                let (_, body_elems) = body.node().map(elements).unwrap_or_default();
+                let mut body_docs = stmts(&body_elems);
+                body_docs.extend(body.comments.iter().cloned());
+                body_docs.extend(lambda.comments.iter().cloned());
+                docs.extend(block_body(body_docs, is_single_expr(&body_elems), close));
+                return vec![Doc::Group(docs)];
+            }
+        }
+    }
+    if inner.is_empty() {
+        docs.extend(close.doc());
+        return docs;
+    }
+    docs.extend(block_body(stmts(inner), is_single_expr(inner), close));
+    vec![Doc::Group(docs)]
+}
+
+fn is_single_expr(stmt_elems: &[Elem]) -> bool {
+    matches!(stmt_elems, [stmt] if stmt.kind() != SyntaxKind::VariableDef)
+}
+
+/// Statements of the block followed by the closing brace, a single expression stays on the
+/// line if it fits
+fn block_body(stmt_docs: Vec<Doc>, single_expr: bool, close: &Elem) -> Vec<Doc> {
+    let separator = if single_expr {
+        Doc::Line
+    } else {
+        Doc::HardLine
+    };
+    let mut docs = vec![Doc::Indent(
+        std::iter::once(separator.clone())
+            .chain(stmt_docs)
+            .collect(),
+    )];
+    docs.push(separator);
+    docs.extend(close.doc());
+    docs
+}
+
+/// Lambda `(args) => body` (not in braces)
+fn lambda(elems: &[Elem]) -> Vec<Doc> {
+    match elems {
+        [params @ .., arrow, body] if arrow.kind() == SyntaxKind::FatArrow => {
+            let mut docs: Vec<Doc> = params.iter().flat_map(Elem::doc).collect();
+            docs.push(Doc::text(" =>"));
+            docs.extend(arrow.comments.iter().cloned());
+            if body.kind() == SyntaxKind::BlockExpr {
+                docs.push(Doc::text(" "));
+                docs.extend(body.doc());
+                docs
+            } else {
+                docs.push(Doc::Indent(
+                    std::iter::once(Doc::Line).chain(body.doc()).collect(),
+                ));
+                vec![Doc::Group(docs)]
+            }
+        }
+        _ => elems.iter().flat_map(Elem::doc).collect(),
+    }
+}
+
+/// Chain of the operators of the same precedence `a && b && c`, broken after the operators
+/// (comparisons are not broken, their operands are)
+fn infix(node: &SyntaxNode) -> Vec<Doc> {
+    // operands and operators of the left-associative chain, in reverse order
+    let mut rev_items: Vec<Vec<Doc>> = Vec::new();
+    let mut node = node.clone();
+    let first = loop {
+        let (_, elems) = elements(&node);
+        let (lhs, op, rhs) = match elems.as_slice() {
+            [lhs, op, rhs] => (lhs, op, rhs),
+            _ => return elems.iter().flat_map(Elem::doc).collect(),
+        };
+        let mut op_doc = vec![Doc::text(format!(" {}", op.element))];
+        op_doc.extend(op.comments.iter().cloned());
+        op_doc.push(if is_comparison(op.kind()) && op.comments.is_empty() {
+            Doc::text(" ")
+        } else {
+            Doc::Line
+        });
+        rev_items.push(rhs.doc());
+        rev_items.push(op_doc);
+        let same_precedence = lhs
+            .node()
+            .filter(|lhs| lhs.kind() == SyntaxKind::InfixExpr)
+            .and_then(infix_op)
+            .map_or(false, |lhs_op| precedence(lhs_op) == precedence(op.kind()));
+        match lhs.node() {
+            Some(lhs_node) if same_precedence && lhs.comments.is_empty() => node = lhs_node.clone(),
+            _ => break lhs.doc(),
+        }
+    };
+    let mut rest = Vec::new();
+    while let (Some(op), Some(operand)) = (rev_items.pop(), rev_items.pop()) {
+        rest.extend(op);
+        rest.extend(operand);
+    }
+    let mut docs = first;
+    docs.push(Doc::Indent(rest));
+    vec![Doc::Group(docs)]
+}
+
+fn infix_op(node: &SyntaxNode) -> Option<SyntaxKind> {
+    node.children_with_tokens()
+        .filter_map(|e| e.into_token())
+        .map(|t| t.kind())
+        .find(|kind| precedence(*kind).is_some())
+}
+
+fn is_comparison(op: SyntaxKind) -> bool {
+    precedence(op).map_or(false, |p| p == 5 || p == 7)
+}
+
+fn precedence(op: SyntaxKind) -> Option<u8> {
+    Some(match op {
+        SyntaxKind::Or => 1,
+        SyntaxKind::And => 3,
+        SyntaxKind::DoubleEquals | SyntaxKind::NotEquals => 5,
+        SyntaxKind::Less
+        | SyntaxKind::LessEquals
+        | SyntaxKind::Greater
+        | SyntaxKind::GreaterEquals => 7,
+        SyntaxKind::Plus | SyntaxKind::Minus => 9,
+        SyntaxKind::Star | SyntaxKind::Slash => 11,
+        _ => return None,
+    })
+}
+
+/// `obj.a(x).b[T].c`, a chain with several method calls is broken before each `.` if it doesn't
+/// fit
+fn postfix_chain(node: &SyntaxNode) -> Vec<Doc> {
+    // postfix parts in reverse order
+    let mut rev_parts: Vec<&'static str> = Vec::new();
+    let mut rev_docs: Vec<Vec<Doc>> = Vec::new();
+    let mut node = node.clone();
+    let receiver = loop {
+        let (_, elems) = elements(&node);
+        let (inner, postfix) = match elems.split_first() {
+            Some((inner, postfix)) => (inner, postfix),
+            None => return Vec::new(),
+        };
+        rev_docs.push(postfix.iter().flat_map(Elem::doc).collect());
+        rev_parts.push(match node.kind() {
+            SyntaxKind::SelectExpr => ".",
+            SyntaxKind::CallExpr => "()",
+            _ => "[]",
+        });
+        match inner.node() {
+            Some(inner_node)
+                if matches!(
+                    inner_node.kind(),
+                    SyntaxKind::SelectExpr | SyntaxKind::CallExpr | SyntaxKind::ApplyTypesExpr
+                ) && inner.comments.is_empty() =>
+            {
+                node = inner_node.clone()
+            }
+            _ => break inner.doc(),
+        }
+    };
+    let parts: Vec<&str> = rev_parts.iter().rev().cloned().collect();
+    // `.method(...)` calls
+    let calls = parts
+        .windows(2)
+        .filter(|w| w[0] == "." && w[1] == "()")
+        .count();
+    let mut docs = receiver;
+    if calls < 2 {
+        docs.extend(rev_docs.into_iter().rev().flatten());
+        return docs;
+    }
+    let mut chain = Vec::new();
+    for (part, part_docs) in parts.iter().zip(rev_docs.into_iter().rev()) {
+        if *part == "." {
+            chain.push(Doc::SoftLine);
+        }
+        chain.extend(part_docs);
+    }
+    docs.push(Doc::Indent(chain));
+    vec![Doc::Group(docs)]
+}
+
+/// `(a, b, c)`, broken after the commas if it doesn't fit; a single block argument stays on the
+/// line
+fn delimited_list(elems: &[Elem]) -> Vec<Doc> {
+    let (open, items, close) = match elems {
+        [open, items @ .., close] => (open, items, close),
+        _ => return elems.iter().flat_map(Elem::doc).collect(),
+    };
+    let mut docs = open.doc();
+    if let [item] = items {
+        if matches!(item.kind(), SyntaxKind::BlockExpr | SyntaxKind::LambdaExpr) {
+            docs.extend(item.doc());
+            docs.extend(close.doc());
+            return docs;
+        }
+    }
+    let mut inner = vec![Doc::SoftLine];
+    for item in items {
+        if item.kind() == SyntaxKind::Comma {
+            inner.push(Doc::text(","));
+            inner.extend(item.comments.iter().cloned());
+            inner.push(Doc::Line);
+        } else {
+            inner.extend(item.doc());
+        }
+    }
+    docs.push(Doc::Indent(inner));
+    docs.push(Doc::SoftLine);
+    docs.extend(close.doc());
+    vec![Doc::Group(docs)]
+}
+
+/// `if (cond) a else b`, the branches are indented on separate lines if they don't fit
+fn if_expr(elems: &[Elem]) -> Vec<Doc> {
+    let (if_kw, open, cond, close, then_branch, else_kw, else_branch) = match elems {
+        [if_kw, open, cond, close, then_branch, else_kw, else_branch] => {
+            (if_kw, open, cond, close, then_branch, else_kw, else_branch)
+        }
+        _ => return elems.iter().flat_map(Elem::doc).collect(),
+    };
+    let mut docs = if_kw.doc();
+    docs.push(Doc::text(" "));
+    docs.extend(open.doc());
+    docs.extend(cond.doc());
+    docs.extend(close.doc());
+    let then_is_block = then_branch.kind() == SyntaxKind::BlockExpr;
+    if then_is_block {
+        docs.push(Doc::text(" "));
+        docs.extend(then_branch.doc());
+        docs.push(Doc::text(" "));
+    } else {
+        docs.push(Doc::Indent(
+            std::iter::once(Doc::Line)
+                .chain(then_branch.doc())
+                .collect(),
+        ));
+        docs.push(Doc::Line);
+    }
+    docs.extend(else_kw.doc());
+    if matches!(
+        else_branch.kind(),
+        SyntaxKind::BlockExpr | SyntaxKind::IfExpr
+    ) {
+        docs.push(Doc::text(" "));
+        docs.extend(else_branch.doc());
+    } else {
+        docs.push(Doc::Indent(
+            std::iter::once(Doc::Line)
+                .chain(else_branch.doc())
+                .collect(),
+        ));
+    }
+    vec![Doc::Group(docs)]
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::compiler::compile_expr;
+    use crate::script_env::ScriptEnv;
+    use expect_test::expect;
+
+    /// Checks the formatted source, that formatting is idempotent, keeps every comment and
+    /// doesn't change the compiled expression
+    fn check(source: &str, expected: expect_test::Expect) {
+        let formatted = try_format(source).unwrap();
+        expected.assert_eq(&formatted);
+        assert_eq!(
+            format(&formatted),
+            formatted,
+            "formatting is not idempotent"
+        );
+        assert_eq!(comments(&formatted), comments(source));
+        if let Ok(expr) = compile_expr(source, ScriptEnv::new()) {
+            assert_eq!(
+                compile_expr(&formatted, ScriptEnv::new())
+                    .unwrap_or_else(|e| panic!("{}", e.pretty_desc(&formatted))),
+                expr
+            );
+        }
+    }
+
+    fn comments(source: &str) -> Vec<String> {
+        parse(source)
+            .syntax()
+            .descendants_with_tokens()
+            .filter_map(|e| e.into_token())
+            .filter(|t| t.kind() == SyntaxKind::Comment)
+            .map(|t| t.text().trim_end().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn spacing() {
+        check(
+            "  val   a:Int=HEIGHT+1 ;val b=  -a*2\na>b&&!(a==b)",
+            expect![[r#"
+                val a: Int = HEIGHT + 1
+                val b = -a * 2
+                a > b && !(a == b)
+            "#]],
+        );
+    }
+
+    #[test]
+    fn blank_lines() {
+        check(
+            "val a = 1\n\n\n\nval b = 2\nval c = 3\n\na + b + c",
+            expect![[r#"
+                val a = 1
+
+                val b = 2
+                val c = 3
+
+                a + b + c
+            "#]],
+        );
+    }
+
+    #[test]
+    fn separator_kept_before_paren() {
+        check(
+            "val a = 1; (a, 2)._1 > 0",
+            expect![[r#"
+                val a = 1;
+                (a, 2)._1 > 0
+            "#]],
+        );
+    }
+
+    #[test]
+    fn comments_kept() {
+        check(
+            r#"// leading
+
+  val a = HEIGHT   // trailing
+        // own line
+val b = a + // after the operator
+  1 ;  // after the separator
+
+// before the result
+b > a"#,
+            expect![[r#"
+                // leading
+
+                val a = HEIGHT // trailing
+                // own line
+                val b = a + // after the operator
+                  1 // after the separator
+
+                // before the result
+                b > a
+            "#]],
+        );
+    }
+
+    #[test]
+    fn blocks() {
+        check(
+            "{val a = 1\n    val b = { a+1 }\n b}",
+            expect![[r#"
+                {
+                  val a = 1
+                  val b = { a + 1 }
+                  b
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn lambdas() {
+        check(
+            "OUTPUTS.exists({(b:Box)=>b.value>1})",
+            expect![[r#"
+                OUTPUTS.exists({ (b: Box) => b.value > 1 })
+            "#]],
+        );
+        check(
+            "val f = {(x:Int)=>\nval y = x*2\ny+1}\nf(1) > 2",
+            expect![[r#"
+                val f = { (x: Int) =>
+                  val y = x * 2
+                  y + 1
+                }
+                f(1) > 2
+            "#]],
+        );
+    }
+
+    #[test]
+    fn if_else() {
+        check(
+            "if(HEIGHT>1)  2 else{3}",
+            expect![[r#"
+                if (HEIGHT > 1) 2 else { 3 }
+            "#]],
+        );
+        check(
+            "if (HEIGHT > 100000000) { HEIGHT + 100000000 + 100000000 } else if (HEIGHT > 10000000) HEIGHT + 100000000 + 100000000 + 100000000 else 1",
+            expect![[r#"
+                if (HEIGHT > 100000000) { HEIGHT + 100000000 + 100000000 } else if (HEIGHT > 10000000)
+                  HEIGHT + 100000000 + 100000000 + 100000000
+                else
+                  1
+            "#]],
+        );
+    }
+
+    #[test]
+    fn long_method_chain() {
+        check(
+            "OUTPUTS.filter({ (b: Box) => b.value > 1000000 }).map({ (b: Box) => b.value }).fold(0L, { (acc: Long, v: Long) => acc + v }) > 10000000L",
+            expect![[r#"
+                OUTPUTS
+                  .filter({ (b: Box) => b.value > 1000000 })
+                  .map({ (b: Box) => b.value })
+                  .fold(0L, { (acc: Long, v: Long) => acc + v }) > 10000000L
+            "#]],
+        );
+        check(
+            "OUTPUTS.map({ (b: Box) => b.value }).size > 1",
+            expect![[r#"
+                OUTPUTS.map({ (b: Box) => b.value }).size > 1
+            "#]],
+        );
+    }
+
+    #[test]
+    fn long_boolean_condition() {
+        check(
+            "sigmaProp(HEIGHT > 100000000 && OUTPUTS.size > 100000000 && INPUTS.size > 100000000 && SELF.value > 100000000L)",
+            expect![[r#"
+                sigmaProp(
+                  HEIGHT > 100000000 &&
+                    OUTPUTS.size > 100000000 &&
+                    INPUTS.size > 100000000 &&
+                    SELF.value > 100000000L
+                )
+            "#]],
+        );
+    }
+
+    #[test]
+    fn unparseable_source_unchanged() {
+        let source = "val a = \n  (1 +";
+        assert!(try_format(source).is_err());
+        assert_eq!(format(source), source);
+    }
+}
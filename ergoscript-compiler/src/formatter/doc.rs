@@ -0,0 +1,165 @@
+//! Layout document and its printer (Wadler-style "prettier printer")
+
+/// Layout of the formatted source
+#[derive(Debug, Clone)]
+pub enum Doc {
+    /// Text without line breaks
+    Text(String),
+    /// Space, or a line break if the enclosing group doesn't fit
+    Line,
+    /// Nothing, or a line break if the enclosing group doesn't fit
+    SoftLine,
+    /// Line break (consecutive ones are merged)
+    HardLine,
+    /// Blank line (consecutive ones are merged)
+    EmptyLine,
+    /// Line comment, always followed by a line break (breaks the enclosing groups)
+    Comment(String),
+    /// Indented content (after the line breaks)
+    Indent(Vec<Doc>),
+    /// Content printed on one line if it fits (up to the first hard line break)
+    Group(Vec<Doc>),
+}
+
+impl Doc {
+    pub fn text(text: impl Into<String>) -> Doc {
+        Doc::Text(text.into())
+    }
+
+    fn contains_comment(&self) -> bool {
+        match self {
+            Doc::Comment(_) => true,
+            Doc::Indent(docs) | Doc::Group(docs) => docs.iter().any(Doc::contains_comment),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+/// Indentation step
+const INDENT: &str = "  ";
+
+/// Prints the document, lines longer than the `width` are broken where possible
+pub fn print(docs: &[Doc], width: usize) -> String {
+    let mut printer = Printer {
+        out: String::new(),
+        width,
+        column: 0,
+        pending_indent: None,
+    };
+    let mut cmds: Vec<(usize, Mode, &Doc)> =
+        docs.iter().rev().map(|d| (0, Mode::Break, d)).collect();
+    while let Some((indent, mode, doc)) = cmds.pop() {
+        match doc {
+            Doc::Text(text) => printer.text(text),
+            Doc::Comment(text) => {
+                printer.text(text);
+                printer.newline(indent);
+            }
+            Doc::Line if mode == Mode::Flat => {
+                if printer.pending_indent.is_none() {
+                    printer.text(" ");
+                }
+            }
+            Doc::SoftLine if mode == Mode::Flat => (),
+            Doc::Line | Doc::SoftLine | Doc::HardLine => printer.newline(indent),
+            Doc::EmptyLine => printer.empty_line(indent),
+            Doc::Indent(docs) => {
+                cmds.extend(docs.iter().rev().map(|d| (indent + 1, mode, d)));
+            }
+            Doc::Group(docs) => {
+                let force_break = docs.iter().any(Doc::contains_comment);
+                let mode = if !force_break
+                    && (mode == Mode::Flat
+                        || fits(docs, &cmds, printer.width.saturating_sub(printer.column)))
+                {
+                    Mode::Flat
+                } else {
+                    Mode::Break
+                };
+                cmds.extend(docs.iter().rev().map(|d| (indent, mode, d)));
+            }
+        }
+    }
+    let mut out = printer.out.trim_end().to_string();
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// Whether the group fits the remaining width on one line (up to the next line break of the
+/// following content)
+fn fits(group: &[Doc], rest: &[(usize, Mode, &Doc)], width: usize) -> bool {
+    let mut remaining = width as isize;
+    let mut cmds: Vec<(Mode, &Doc)> = group.iter().rev().map(|d| (Mode::Flat, d)).collect();
+    let mut rest = rest.iter().rev();
+    loop {
+        let (mode, doc) = match cmds.pop() {
+            Some(cmd) => cmd,
+            None => match rest.next() {
+                Some((_, mode, doc)) => (*mode, *doc),
+                None => return true,
+            },
+        };
+        match doc {
+            Doc::Text(text) => remaining -= text.chars().count() as isize,
+            Doc::Line if mode == Mode::Flat => remaining -= 1,
+            Doc::SoftLine if mode == Mode::Flat => (),
+            Doc::Line | Doc::SoftLine | Doc::HardLine | Doc::EmptyLine => return true,
+            Doc::Comment(_) => return remaining >= 0,
+            Doc::Indent(docs) => cmds.extend(docs.iter().rev().map(|d| (mode, d))),
+            Doc::Group(docs) => {
+                let mode = if docs.iter().any(Doc::contains_comment) {
+                    Mode::Break
+                } else {
+                    mode
+                };
+                cmds.extend(docs.iter().rev().map(|d| (mode, d)));
+            }
+        }
+        if remaining < 0 {
+            return false;
+        }
+    }
+}
+
+struct Printer {
+    out: String,
+    width: usize,
+    column: usize,
+    /// Indentation of the current line, written before its first text
+    pending_indent: Option<usize>,
+}
+
+impl Printer {
+    fn text(&mut self, text: &str) {
+        if let Some(indent) = self.pending_indent.take() {
+            self.out.push_str(&INDENT.repeat(indent));
+        }
+        self.out.push_str(text);
+        self.column += text.chars().count();
+    }
+
+    fn newline(&mut self, indent: usize) {
+        if self.pending_indent.is_none() && !self.out.is_empty() {
+            let trimmed_len = self.out.trim_end_matches(' ').len();
+            self.out.truncate(trimmed_len);
+            self.out.push('\n');
+        }
+        self.pending_indent = Some(indent);
+        self.column = indent * INDENT.len();
+    }
+
+    fn empty_line(&mut self, indent: usize) {
+        self.newline(indent);
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+}
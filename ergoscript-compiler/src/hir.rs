@@ -3,30 +3,38 @@
 
 mod rewrite;
 
+use std::convert::TryFrom;
+
+use ergotree_ir::mir::constant::Constant;
+use ergotree_ir::mir::constant::ConstantPlaceholder;
+use ergotree_ir::types::smethod::SMethod;
+use ergotree_ir::types::stuple::STuple;
 use ergotree_ir::types::stype::SType;
 pub use rewrite::rewrite;
 
 use super::ast;
 use crate::ast::AstError;
-use crate::error::pretty_error_desc;
+use crate::error::all;
+use crate::error::both;
+use crate::error::impl_diagnostic;
+use crate::error::ErrorKind;
+use crate::error::Label;
 use crate::syntax::SyntaxKind;
 use text_size::TextRange;
+use thiserror::Error;
 
 extern crate derive_more;
 use derive_more::From;
 
-pub fn lower(ast: ast::Root) -> Result<Expr, HirLoweringError> {
-    let exprs: Vec<ast::Expr> = ast.children().collect();
-    if exprs.len() > 1 {
-        return Err(HirLoweringError::new(
-            format!("More than one root expr found: {:?}", exprs),
-            ast.span(),
-        ));
+/// Lowers the AST, reports the errors of all the statements and sub-expressions
+pub fn lower(ast: ast::Root) -> Result<Expr, Vec<HirLoweringError>> {
+    let stmts: Vec<ast::Stmt> = ast.stmts().collect();
+    if stmts.is_empty() {
+        return Err(
+            AstError::new(format!("Cannot parse empty root: {:?}", ast), ast.span()).into(),
+        );
     }
-    let first_expr = exprs
-        .first()
-        .ok_or_else(|| AstError::new(format!("Cannot parse empty root: {:?}", ast), ast.span()))?;
-    Expr::lower(first_expr)
+    Block::lower(stmts, ast.span())
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -36,61 +44,127 @@ pub struct Expr {
     pub tpe: Option<SType>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Error)]
+#[error("{msg}")]
 pub struct HirLoweringError {
+    kind: ErrorKind,
     msg: String,
     span: TextRange,
+    labels: Vec<Label>,
 }
 
 impl HirLoweringError {
-    pub fn new(msg: String, span: TextRange) -> Self {
-        HirLoweringError { msg, span }
-    }
-
-    pub fn pretty_desc(&self, source: &str) -> String {
-        pretty_error_desc(source, self.span, &self.msg)
+    pub fn new(kind: ErrorKind, msg: String, span: TextRange) -> Self {
+        HirLoweringError {
+            kind,
+            msg,
+            span,
+            labels: Vec::new(),
+        }
     }
 }
 
+impl_diagnostic!(HirLoweringError);
+
 impl From<AstError> for HirLoweringError {
     fn from(ast: AstError) -> Self {
-        HirLoweringError::new(format!("AST error: {0}", ast.msg), ast.span)
+        HirLoweringError::new(
+            ErrorKind::InvalidSyntax,
+            format!("AST error: {0}", ast.msg),
+            ast.span,
+        )
+    }
+}
+
+impl From<HirLoweringError> for Vec<HirLoweringError> {
+    fn from(e: HirLoweringError) -> Self {
+        vec![e]
+    }
+}
+
+impl From<AstError> for Vec<HirLoweringError> {
+    fn from(e: AstError) -> Self {
+        vec![e.into()]
     }
 }
 
 impl Expr {
-    pub fn lower(expr: &ast::Expr) -> Result<Expr, HirLoweringError> {
-        match expr {
-            ast::Expr::BinaryExpr(ast) => Ok(Expr {
-                kind: Binary::lower(ast)?.into(),
-                span: ast.span(),
-                tpe: None,
-            }),
-            ast::Expr::Ident(ast) => {
-                let name = ast.name()?;
-                Ok(Expr {
-                    kind: ExprKind::Ident(name.text().to_string()),
-                    span: ast.span(),
-                    tpe: None,
-                })
-            }
+    pub fn lower(expr: &ast::Expr) -> Result<Expr, Vec<HirLoweringError>> {
+        let kind: ExprKind = match expr {
+            ast::Expr::BinaryExpr(ast) => Binary::lower(ast)?.into(),
+            ast::Expr::Ident(ast) => ExprKind::Ident(ast.name()?.text().to_string()),
             ast::Expr::Literal(ast) => {
                 let v = ast.parse()?;
-                let expr = match v {
-                    ast::LiteralValue::Int(v) => Expr {
-                        kind: Literal::Int(v).into(),
-                        span: ast.span(),
-                        tpe: Some(SType::SInt),
-                    },
-                    ast::LiteralValue::Long(v) => Expr {
-                        kind: Literal::Long(v).into(),
-                        span: ast.span(),
-                        tpe: Some(SType::SLong),
-                    },
+                let (literal, tpe) = match v {
+                    ast::LiteralValue::Int(v) => (Literal::Int(v), Some(SType::SInt)),
+                    ast::LiteralValue::Long(v) => (Literal::Long(v), Some(SType::SLong)),
+                    ast::LiteralValue::Bool(v) => (Literal::Bool(v), Some(SType::SBoolean)),
+                    // string literals are only allowed as arguments of the functions evaluated
+                    // at compile time (see binder)
+                    ast::LiteralValue::Str(v) => (Literal::Str(v), None),
                 };
-                Ok(expr)
+                return Ok(Expr {
+                    kind: literal.into(),
+                    span: ast.span(),
+                    tpe,
+                });
             }
-        }
+            ast::Expr::ParenExpr(ast) => return Expr::lower(&ast.expr()?),
+            ast::Expr::BlockExpr(ast) => return Block::lower(ast.stmts().collect(), ast.span()),
+            ast::Expr::LambdaExpr(ast) => Lambda::lower(ast)?.into(),
+            ast::Expr::SelectExpr(ast) => Select {
+                obj: Box::new(Expr::lower(&ast.obj()?)?),
+                field: ast.field()?.text().to_string(),
+            }
+            .into(),
+            ast::Expr::CallExpr(ast) => {
+                let (func, args) = both(
+                    Expr::lower(&ast.func()?),
+                    all(ast.args().map(|arg| Expr::lower(&arg))),
+                )?;
+                Apply {
+                    func: Box::new(func),
+                    args,
+                }
+                .into()
+            }
+            ast::Expr::UnaryExpr(ast) => Unary::lower(ast)?.into(),
+            ast::Expr::IfExpr(ast) => {
+                let (condition, (then_branch, else_branch)) = both(
+                    Expr::lower(&ast.condition()?),
+                    both(
+                        Expr::lower(&ast.then_branch()?),
+                        Expr::lower(&ast.else_branch()?),
+                    ),
+                )?;
+                If {
+                    condition: Box::new(condition),
+                    then_branch: Box::new(then_branch),
+                    else_branch: Box::new(else_branch),
+                }
+                .into()
+            }
+            ast::Expr::TupleExpr(ast) => Tuple {
+                items: all(ast.items().map(|item| Expr::lower(&item)))?,
+            }
+            .into(),
+            ast::Expr::ApplyTypesExpr(ast) => {
+                let (input, tpe_args) = both(
+                    Expr::lower(&ast.expr()?),
+                    all(ast.type_args().map(|t| lower_type(&t))),
+                )?;
+                ApplyTypes {
+                    input: Box::new(input),
+                    tpe_args,
+                }
+                .into()
+            }
+        };
+        Ok(Expr {
+            kind,
+            span: expr.span(),
+            tpe: None,
+        })
     }
 
     #[cfg(test)]
@@ -114,48 +188,350 @@ pub struct Binary {
 }
 
 impl Binary {
-    fn lower(ast: &ast::BinaryExpr) -> Result<Binary, HirLoweringError> {
+    fn lower(ast: &ast::BinaryExpr) -> Result<Binary, Vec<HirLoweringError>> {
         let syntax_token = ast.op()?;
         let op = match syntax_token.kind() {
             SyntaxKind::Plus => BinaryOp::Plus,
             SyntaxKind::Minus => BinaryOp::Minus,
             SyntaxKind::Star => BinaryOp::Multiply,
             SyntaxKind::Slash => BinaryOp::Divide,
+            SyntaxKind::DoubleEquals => BinaryOp::Eq,
+            SyntaxKind::NotEquals => BinaryOp::NotEq,
+            SyntaxKind::Less => BinaryOp::Lt,
+            SyntaxKind::LessEquals => BinaryOp::Le,
+            SyntaxKind::Greater => BinaryOp::Gt,
+            SyntaxKind::GreaterEquals => BinaryOp::Ge,
+            SyntaxKind::And => BinaryOp::And,
+            SyntaxKind::Or => BinaryOp::Or,
             _ => {
                 return Err(HirLoweringError::new(
+                    ErrorKind::InvalidSyntax,
                     format!("unknown binary operator: {:?}", ast.op()),
                     syntax_token.text_range(),
-                ))
+                )
+                .into())
             }
         };
 
-        let lhs = Expr::lower(&ast.lhs()?);
-        let rhs = Expr::lower(&ast.rhs()?);
+        let (lhs, rhs) = both(Expr::lower(&ast.lhs()?), Expr::lower(&ast.rhs()?))?;
 
         Ok(Binary {
             op: Spanned {
                 node: op,
                 span: syntax_token.text_range(),
             },
-            lhs: Box::new(lhs?),
-            rhs: Box::new(rhs?),
+            lhs: Box::new(lhs),
+            rhs: Box::new(rhs),
         })
     }
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct Unary {
+    pub op: Spanned<UnaryOp>,
+    pub expr: Box<Expr>,
+}
+
+impl Unary {
+    fn lower(ast: &ast::UnaryExpr) -> Result<Unary, Vec<HirLoweringError>> {
+        let syntax_token = ast.op()?;
+        let op = match syntax_token.kind() {
+            SyntaxKind::Minus => UnaryOp::Neg,
+            SyntaxKind::Bang => UnaryOp::Not,
+            _ => {
+                return Err(HirLoweringError::new(
+                    ErrorKind::InvalidSyntax,
+                    format!("unknown unary operator: {:?}", syntax_token),
+                    syntax_token.text_range(),
+                )
+                .into())
+            }
+        };
+        Ok(Unary {
+            op: Spanned {
+                node: op,
+                span: syntax_token.text_range(),
+            },
+            expr: Box::new(Expr::lower(&ast.expr()?)?),
+        })
+    }
+}
+
+/// `if (condition) then_branch else else_branch`
+#[derive(Debug, PartialEq, Clone)]
+pub struct If {
+    pub condition: Box<Expr>,
+    pub then_branch: Box<Expr>,
+    pub else_branch: Box<Expr>,
+}
+
+/// Tuple literal `(a, b, ...)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Tuple {
+    pub items: Vec<Expr>,
+}
+
+/// Block of `val` definitions followed by the result expression
+#[derive(Debug, PartialEq, Clone)]
+pub struct Block {
+    pub bindings: Vec<Val>,
+    pub result: Box<Expr>,
+}
+
+impl Block {
+    /// Lowers the statements of a block (or the root), returns the result expression if there
+    /// are no `val` definitions. The errors of all the statements are reported.
+    fn lower(stmts: Vec<ast::Stmt>, span: TextRange) -> Result<Expr, Vec<HirLoweringError>> {
+        let mut stmts = stmts;
+        let result = match stmts.pop() {
+            Some(ast::Stmt::Expr(result)) => Expr::lower(&result),
+            Some(ast::Stmt::VariableDef(val)) => Err(HirLoweringError::new(
+                ErrorKind::InvalidSyntax,
+                "Block should end with an expression".to_string(),
+                val.span(),
+            )
+            .into()),
+            None => Err(HirLoweringError::new(
+                ErrorKind::InvalidSyntax,
+                "Empty block".to_string(),
+                span,
+            )
+            .into()),
+        };
+        let bindings = all(stmts.iter().map(|stmt| match stmt {
+            ast::Stmt::VariableDef(val) => Val::lower(val),
+            ast::Stmt::Expr(expr) => Err(HirLoweringError::new(
+                ErrorKind::InvalidSyntax,
+                "Only `val` definitions are allowed before the block result".to_string(),
+                expr.span(),
+            )
+            .into()),
+        }));
+        let (bindings, result) = both(bindings, result)?;
+        if bindings.is_empty() {
+            return Ok(result);
+        }
+        Ok(Expr {
+            kind: Block {
+                bindings,
+                result: Box::new(result),
+            }
+            .into(),
+            span,
+            tpe: None,
+        })
+    }
+}
+
+/// `val name: tpe = rhs` definition
+#[derive(Debug, PartialEq, Clone)]
+pub struct Val {
+    pub name: String,
+    /// Declared type (if any)
+    pub given_tpe: Option<SType>,
+    pub rhs: Box<Expr>,
+    pub span: TextRange,
+}
+
+impl Val {
+    fn lower(ast: &ast::VariableDef) -> Result<Val, Vec<HirLoweringError>> {
+        let name = ast.name()?.text().to_string();
+        let (given_tpe, rhs) = both(
+            ast.type_ref().map(|t| lower_type(&t)).transpose(),
+            Expr::lower(&ast.rhs()?),
+        )?;
+        Ok(Val {
+            name,
+            given_tpe,
+            rhs: Box::new(rhs),
+            span: ast.span(),
+        })
+    }
+}
+
+/// Anonymous function
+#[derive(Debug, PartialEq, Clone)]
+pub struct Lambda {
+    /// Argument names and types
+    pub args: Vec<(String, SType)>,
+    pub body: Box<Expr>,
+}
+
+impl Lambda {
+    fn lower(ast: &ast::LambdaExpr) -> Result<Lambda, Vec<HirLoweringError>> {
+        let args = all(ast.params().map(|param| {
+            Ok((
+                param.name()?.text().to_string(),
+                lower_type(&param.type_ref()?)?,
+            ))
+        }));
+        let (args, body) = both(args, Expr::lower(&ast.body()?))?;
+        Ok(Lambda {
+            args,
+            body: Box::new(body),
+        })
+    }
+}
+
+/// Field or method selection `obj.field` (resolved by the type inference)
+#[derive(Debug, PartialEq, Clone)]
+pub struct Select {
+    pub obj: Box<Expr>,
+    pub field: String,
+}
+
+/// Application `func(args)`, a function call, or a method call or collection indexing (resolved
+/// by the type inference)
+#[derive(Debug, PartialEq, Clone)]
+pub struct Apply {
+    pub func: Box<Expr>,
+    pub args: Vec<Expr>,
+}
+
+/// Explicit type arguments `input[T1, ...]` (resolved by the type inference)
+#[derive(Debug, PartialEq, Clone)]
+pub struct ApplyTypes {
+    pub input: Box<Expr>,
+    pub tpe_args: Vec<SType>,
+}
+
+/// Built-in operation resolved by the type inference (lowered to the corresponding MIR node)
+#[derive(Debug, PartialEq, Clone)]
+pub struct Builtin {
+    pub op: BuiltinOp,
+    pub args: Vec<Expr>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum BuiltinOp {
+    /// Object property or method, the object is the first argument
+    Method(SMethod),
+    /// Box register `R0`..`R9` value (as `Option`)
+    ExtractRegisterAs(i8),
+    /// Collection element
+    ByIndex,
+    /// Tuple field (1-based index)
+    SelectField(u8),
+    /// Collection size
+    SizeOf,
+    /// Option value
+    OptionGet,
+    /// `sigmaProp(bool)`
+    BoolToSigmaProp,
+    /// `proveDlog(ge)`
+    CreateProveDlog,
+    /// `proveDHTuple(g, h, u, v)`
+    CreateProveDhTuple,
+    /// `atLeast(bound, props)`
+    Atleast,
+    /// `allOf(conditions)`
+    AllOf,
+    /// `anyOf(conditions)`
+    AnyOf,
+    /// `&&` with a `SigmaProp` operand
+    SigmaAnd,
+    /// `||` with a `SigmaProp` operand
+    SigmaOr,
+    /// `blake2b256(bytes)`
+    CalcBlake2b256,
+    /// `sha256(bytes)`
+    CalcSha256,
+    /// `Coll(items)`
+    Collection,
+    /// `coll.map(f)`
+    Map,
+    /// `coll.filter(p)`
+    Filter,
+    /// `coll.exists(p)`
+    Exists,
+    /// `coll.forall(p)`
+    ForAll,
+    /// `coll.fold(zero, op)`
+    Fold,
+    /// `coll.slice(from, until)`
+    Slice,
+    /// `coll.append(other)`
+    Append,
+    /// `opt.isDefined`
+    OptionIsDefined,
+    /// `opt.getOrElse(default)`
+    OptionGetOrElse,
+    /// `min(x, y)`
+    Min,
+    /// `max(x, y)`
+    Max,
+    /// `decodePoint(bytes)`
+    DecodePoint,
+    /// `substConstants(scriptBytes, positions, newValues)`
+    SubstConstants,
+    /// `prop.propBytes`
+    SigmaPropBytes,
+    /// `prop.isProven`
+    SigmaPropIsProven,
+}
+
+fn lower_type(ast: &ast::TypeRef) -> Result<SType, Vec<HirLoweringError>> {
+    if let Some(items) = ast.tuple_items() {
+        let items = all(items.map(|t| lower_type(&t)))?;
+        return STuple::try_from(items).map(SType::STuple).map_err(|_| {
+            HirLoweringError::new(
+                ErrorKind::InvalidTuple,
+                format!("Tuple type should have 2 to 255 items: {}", ast.text()),
+                ast.span(),
+            )
+            .into()
+        });
+    }
+    let name = ast.name()?;
+    let args = all(ast.args().map(|t| lower_type(&t)))?;
+    Ok(match (name.text(), args.as_slice()) {
+        ("Any", []) => SType::SAny,
+        ("Unit", []) => SType::SUnit,
+        ("Boolean", []) => SType::SBoolean,
+        ("Byte", []) => SType::SByte,
+        ("Short", []) => SType::SShort,
+        ("Int", []) => SType::SInt,
+        ("Long", []) => SType::SLong,
+        ("BigInt", []) => SType::SBigInt,
+        ("GroupElement", []) => SType::SGroupElement,
+        ("SigmaProp", []) => SType::SSigmaProp,
+        ("Box", []) => SType::SBox,
+        ("AvlTree", []) => SType::SAvlTree,
+        ("Context", []) => SType::SContext,
+        ("Header", []) => SType::SHeader,
+        ("PreHeader", []) => SType::SPreHeader,
+        ("Coll", [elem_tpe]) => SType::SColl(elem_tpe.clone().into()),
+        ("Option", [elem_tpe]) => SType::SOption(elem_tpe.clone().into()),
+        _ => {
+            return Err(HirLoweringError::new(
+                ErrorKind::UnknownType,
+                format!("Unknown type: {}", ast.text()),
+                ast.span(),
+            )
+            .into())
+        }
+    })
+}
+
 #[derive(Debug, PartialEq, From, Clone)]
 pub enum ExprKind {
     Ident(String),
     Binary(Binary),
     GlobalVars(GlobalVars),
     Literal(Literal),
-    // ...
-    // Block
-    // ValNode
-    // Select
-    // ApplyTypes
-    // MethodCallLike
-    // Lambda
+    Block(Block),
+    Lambda(Lambda),
+    Select(Select),
+    Apply(Apply),
+    ApplyTypes(ApplyTypes),
+    Builtin(Builtin),
+    Unary(Unary),
+    If(If),
+    Tuple(Tuple),
+    /// Value computed at compile time, i.e. `PK("...")`
+    Constant(Constant),
+    /// Contract template parameter (`$name`), i.e. placeholder of the segregated constant
+    ConstantPlaceholder(ConstantPlaceholder),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -164,16 +540,30 @@ pub enum BinaryOp {
     Minus,
     Multiply,
     Divide,
+    Eq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
 }
 
-// #[derive(Debug, PartialEq, Clone)]
-// pub enum UnaryOp {
-//     Neg,
-// }
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum GlobalVars {
     Height,
+    Inputs,
+    Outputs,
+    SelfBox,
+    Context,
+    MinerPubkey,
 }
 
 impl GlobalVars {
@@ -181,6 +571,11 @@ impl GlobalVars {
     pub fn tpe(&self) -> SType {
         match self {
             GlobalVars::Height => SType::SInt,
+            GlobalVars::Inputs => SType::SColl(SType::SBox.into()),
+            GlobalVars::Outputs => SType::SColl(SType::SBox.into()),
+            GlobalVars::SelfBox => SType::SBox,
+            GlobalVars::Context => SType::SContext,
+            GlobalVars::MinerPubkey => SType::SColl(SType::SByte.into()),
         }
     }
 }
@@ -189,6 +584,8 @@ impl GlobalVars {
 pub enum Literal {
     Int(i32),
     Long(i64),
+    Bool(bool),
+    Str(String),
 }
 
 #[cfg(test)]
@@ -224,4 +621,185 @@ mod tests {
             }"#]],
         );
     }
+
+    #[test]
+    fn block() {
+        check(
+            "{ val a: Int = 1; a }",
+            expect![[r#"
+            Expr {
+                kind: Block(
+                    Block {
+                        bindings: [
+                            Val {
+                                name: "a",
+                                given_tpe: Some(
+                                    SInt,
+                                ),
+                                rhs: Expr {
+                                    kind: Literal(
+                                        Int(
+                                            1,
+                                        ),
+                                    ),
+                                    span: 15..16,
+                                    tpe: Some(
+                                        SInt,
+                                    ),
+                                },
+                                span: 2..16,
+                            },
+                        ],
+                        result: Expr {
+                            kind: Ident(
+                                "a",
+                            ),
+                            span: 18..20,
+                            tpe: None,
+                        },
+                    },
+                ),
+                span: 0..21,
+                tpe: None,
+            }"#]],
+        );
+    }
+
+    #[test]
+    fn block_without_vals() {
+        check(
+            "{ 1 }",
+            expect![[r#"
+            Expr {
+                kind: Literal(
+                    Int(
+                        1,
+                    ),
+                ),
+                span: 2..4,
+                tpe: Some(
+                    SInt,
+                ),
+            }"#]],
+        );
+    }
+
+    #[test]
+    fn lambda() {
+        check(
+            "{ (b: Coll[Byte]) => b }",
+            expect![[r#"
+            Expr {
+                kind: Lambda(
+                    Lambda {
+                        args: [
+                            (
+                                "b",
+                                SColl(
+                                    SByte,
+                                ),
+                            ),
+                        ],
+                        body: Expr {
+                            kind: Ident(
+                                "b",
+                            ),
+                            span: 21..23,
+                            tpe: None,
+                        },
+                    },
+                ),
+                span: 2..23,
+                tpe: None,
+            }"#]],
+        );
+    }
+
+    #[test]
+    fn block_ends_with_val() {
+        check(
+            "{ val a = 1 }",
+            expect![[r#"
+                ergoscript::invalid_syntax
+
+                  x Block should end with an expression
+                   ,----
+                 1 | { val a = 1 }
+                   :   ^^^^^^^^^^
+                   `----
+            "#]],
+        );
+    }
+
+    #[test]
+    fn unknown_type() {
+        check(
+            "(a: Coll[Int, Int]) => a",
+            expect![[r#"
+                ergoscript::unknown_type
+
+                  x Unknown type: Coll[Int, Int]
+                   ,----
+                 1 | (a: Coll[Int, Int]) => a
+                   :     ^^^^^^^^^^^^^^
+                   `----
+            "#]],
+        );
+    }
+
+    #[test]
+    fn select_and_apply() {
+        check(
+            "OUTPUTS(0).R4[Int]",
+            expect![[r#"
+            Expr {
+                kind: ApplyTypes(
+                    ApplyTypes {
+                        input: Expr {
+                            kind: Select(
+                                Select {
+                                    obj: Expr {
+                                        kind: Apply(
+                                            Apply {
+                                                func: Expr {
+                                                    kind: Ident(
+                                                        "OUTPUTS",
+                                                    ),
+                                                    span: 0..7,
+                                                    tpe: None,
+                                                },
+                                                args: [
+                                                    Expr {
+                                                        kind: Literal(
+                                                            Int(
+                                                                0,
+                                                            ),
+                                                        ),
+                                                        span: 8..9,
+                                                        tpe: Some(
+                                                            SInt,
+                                                        ),
+                                                    },
+                                                ],
+                                            },
+                                        ),
+                                        span: 0..10,
+                                        tpe: None,
+                                    },
+                                    field: "R4",
+                                },
+                            ),
+                            span: 0..13,
+                            tpe: None,
+                        },
+                        tpe_args: [
+                            SInt,
+                        ],
+                    },
+                ),
+                span: 0..18,
+                tpe: None,
+            }"#]],
+        );
+    }
 }
@@ -1,29 +1,103 @@
+use super::Apply;
+use super::ApplyTypes;
 use super::Binary;
+use super::Block;
+use super::Builtin;
 use super::Expr;
 use super::ExprKind;
+use super::If;
+use super::Lambda;
+use super::Select;
+use super::Tuple;
+use super::Unary;
+use super::Val;
 
 // pub fn hir_rewrite_safe<F: Fn(&Expr) -> Option<Expr>>(e: Expr, f: F) -> Expr {
 //     let f_wrap = |e| Result::<Option<Expr>, BinderError>::Ok(f(e));
 //     hir_rewrite(e, f_wrap).unwrap()
 // }
 
+/// Rewrites the expression top-down, i.e. `f` is applied to the expression and then to the
+/// children of the result
 pub fn rewrite<E, F: Fn(&Expr) -> Result<Option<Expr>, E>>(e: Expr, f: F) -> Result<Expr, E> {
-    let e = f(&e)?.unwrap_or(e);
-    Ok(match &e.kind {
-        ExprKind::Binary(binary) => match (f(&binary.lhs)?, f(&binary.rhs)?) {
-            (None, None) => e,
-            (l, r) => Expr {
-                kind: Binary {
-                    op: binary.op.clone(),
-                    lhs: Box::new(l.unwrap_or(*binary.lhs.clone())),
-                    rhs: Box::new(r.unwrap_or(*binary.rhs.clone())),
-                }
-                .into(),
-                ..e
-            },
-        },
-        ExprKind::Ident(_) => f(&e)?.unwrap_or(e), // TODO: duplicate call to f?
-        ExprKind::GlobalVars(_) => f(&e)?.unwrap_or(e),
-        ExprKind::Literal(_) => f(&e)?.unwrap_or(e),
-    })
+    rewrite_rec(e, &f)
+}
+
+fn rewrite_rec<E, F: Fn(&Expr) -> Result<Option<Expr>, E>>(e: Expr, f: &F) -> Result<Expr, E> {
+    let Expr { kind, span, tpe } = f(&e)?.unwrap_or(e);
+    let kind = match kind {
+        ExprKind::Binary(binary) => Binary {
+            op: binary.op,
+            lhs: Box::new(rewrite_rec(*binary.lhs, f)?),
+            rhs: Box::new(rewrite_rec(*binary.rhs, f)?),
+        }
+        .into(),
+        ExprKind::Block(block) => Block {
+            bindings: block
+                .bindings
+                .into_iter()
+                .map(|val| {
+                    Ok(Val {
+                        rhs: Box::new(rewrite_rec(*val.rhs, f)?),
+                        ..val
+                    })
+                })
+                .collect::<Result<Vec<Val>, E>>()?,
+            result: Box::new(rewrite_rec(*block.result, f)?),
+        }
+        .into(),
+        ExprKind::Lambda(lambda) => Lambda {
+            args: lambda.args,
+            body: Box::new(rewrite_rec(*lambda.body, f)?),
+        }
+        .into(),
+        ExprKind::Select(select) => Select {
+            obj: Box::new(rewrite_rec(*select.obj, f)?),
+            field: select.field,
+        }
+        .into(),
+        ExprKind::Apply(apply) => Apply {
+            func: Box::new(rewrite_rec(*apply.func, f)?),
+            args: rewrite_all(apply.args, f)?,
+        }
+        .into(),
+        ExprKind::ApplyTypes(apply_types) => ApplyTypes {
+            input: Box::new(rewrite_rec(*apply_types.input, f)?),
+            tpe_args: apply_types.tpe_args,
+        }
+        .into(),
+        ExprKind::Builtin(builtin) => Builtin {
+            op: builtin.op,
+            args: rewrite_all(builtin.args, f)?,
+        }
+        .into(),
+        ExprKind::Unary(unary) => Unary {
+            op: unary.op,
+            expr: Box::new(rewrite_rec(*unary.expr, f)?),
+        }
+        .into(),
+        ExprKind::If(if_expr) => If {
+            condition: Box::new(rewrite_rec(*if_expr.condition, f)?),
+            then_branch: Box::new(rewrite_rec(*if_expr.then_branch, f)?),
+            else_branch: Box::new(rewrite_rec(*if_expr.else_branch, f)?),
+        }
+        .into(),
+        ExprKind::Tuple(tuple) => Tuple {
+            items: rewrite_all(tuple.items, f)?,
+        }
+        .into(),
+        k @ (ExprKind::Ident(_)
+        | ExprKind::GlobalVars(_)
+        | ExprKind::Literal(_)
+        | ExprKind::Constant(_)
+        | ExprKind::ConstantPlaceholder(_)) => k,
+    };
+    Ok(Expr { kind, span, tpe })
+}
+
+fn rewrite_all<E, F: Fn(&Expr) -> Result<Option<Expr>, E>>(
+    exprs: Vec<Expr>,
+    f: &F,
+) -> Result<Vec<Expr>, E> {
+    exprs.into_iter().map(|e| rewrite_rec(e, f)).collect()
 }
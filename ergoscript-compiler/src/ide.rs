@@ -0,0 +1,592 @@
+//! Source code analysis for editors (diagnostics, types on hover, completion, go to definition)
+
+use std::convert::TryInto;
+use std::ops::Range;
+
+use ergotree_ir::ergo_tree::ErgoTree;
+use ergotree_ir::types::stype::SType;
+use ergotree_ir::types::stype_companion::STypeCompanion;
+use text_size::TextRange;
+
+use crate::binder::global_var;
+use crate::binder::Binder;
+use crate::compiler::compile_hir;
+use crate::compiler::CompileError;
+use crate::compiler::IrError;
+use crate::hir::Expr;
+use crate::hir::ExprKind;
+use crate::mir;
+use crate::script_env::ScriptEnv;
+use crate::type_infer::assign_type;
+
+/// Error or warning in the source code
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Diagnostic {
+    /// Error message
+    pub message: String,
+    /// Error code, i.e. `ergoscript::type_mismatch`
+    pub code: Option<String>,
+    /// Hint on how to fix the error
+    pub help: Option<String>,
+    /// Byte range in the source code
+    pub range: Range<usize>,
+    /// Related source code ranges with descriptions (i.e. types of the operands)
+    pub labels: Vec<(Range<usize>, String)>,
+}
+
+/// Completion candidate
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Completion {
+    /// Member name
+    pub label: String,
+    /// Member type, i.e. `Long` for `SELF.value`, or `(Int) => Box` for `INPUTS.apply`
+    pub detail: String,
+}
+
+/// Results of all the compiler passes over the source code
+#[derive(Debug)]
+pub struct Analysis {
+    source: String,
+    /// Expression with the resolved identifiers (if the source is parsed)
+    bound: Option<Expr>,
+    /// Expression with the assigned types (if there are no type errors)
+    typed: Option<Expr>,
+    /// Compiled tree or the error of the first failed pass
+    tree: Result<ErgoTree, CompileError>,
+}
+
+impl Analysis {
+    /// Runs the compiler passes over the source code
+    pub fn new(source: &str, env: ScriptEnv) -> Self {
+        let bound = match compile_hir(source).and_then(|hir| Ok(Binder::new(env).bind(hir)?)) {
+            Ok(bound) => bound,
+            Err(e) => {
+                return Analysis {
+                    source: source.to_string(),
+                    bound: None,
+                    typed: None,
+                    tree: Err(e),
+                }
+            }
+        };
+        let typed = match assign_type(bound.clone()) {
+            Ok(typed) => typed,
+            Err(e) => {
+                return Analysis {
+                    source: source.to_string(),
+                    bound: Some(bound),
+                    typed: None,
+                    tree: Err(e.into()),
+                }
+            }
+        };
+        let tree = lower(typed.clone(), source);
+        Analysis {
+            source: source.to_string(),
+            bound: Some(bound),
+            typed: Some(typed),
+            tree,
+        }
+    }
+
+    /// Compiled tree (if there are no errors)
+    pub fn ergo_tree(&self) -> Option<&ErgoTree> {
+        self.tree.as_ref().ok()
+    }
+
+    /// Errors of the first failed compiler pass
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let error = match &self.tree {
+            Ok(_) => return Vec::new(),
+            Err(error) => error,
+        };
+        error
+            .diagnostics()
+            .into_iter()
+            .map(|d| {
+                let labels = d
+                    .labels()
+                    .map(|labels| {
+                        labels
+                            .map(|l| {
+                                let range = self.trim(l.offset()..l.offset() + l.len());
+                                (range, l.label().unwrap_or_default().to_string())
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                // the error span, or the span of all the labels
+                let range = labels
+                    .iter()
+                    .map(|(range, _)| range.clone())
+                    .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+                    .unwrap_or(0..0);
+                Diagnostic {
+                    message: d.to_string(),
+                    code: d.code().map(|code| code.to_string()),
+                    help: d.help().map(|help| help.to_string()),
+                    range,
+                    labels: labels
+                        .into_iter()
+                        .filter(|(_, label)| !label.is_empty())
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Type of the innermost expression at the offset
+    pub fn type_at(&self, offset: usize) -> Option<(Range<usize>, SType)> {
+        let mut expr = self.typed.as_ref()?;
+        if !contains(expr.span, offset) {
+            return None;
+        }
+        'outer: loop {
+            if let ExprKind::Block(block) = &expr.kind {
+                // `val` name (outside of the right-hand side)
+                for val in &block.bindings {
+                    if contains(val.span, offset) && !contains(val.rhs.span, offset) {
+                        let tpe = val.given_tpe.clone().or_else(|| val.rhs.tpe.clone())?;
+                        return Some((self.range(val.span), tpe));
+                    }
+                }
+            }
+            for child in children(expr) {
+                if contains(child.span, offset) {
+                    expr = child;
+                    continue 'outer;
+                }
+            }
+            return Some((self.range(expr.span), expr.tpe.clone()?));
+        }
+    }
+
+    /// Range of the `val` definition for the identifier at the offset
+    pub fn definition(&self, offset: usize) -> Option<Range<usize>> {
+        let mut scope: Vec<(&str, Option<TextRange>)> = Vec::new();
+        definition_in(self.bound.as_ref()?, offset, &mut scope).map(|span| self.range(span))
+    }
+
+    /// Members of the value before the `.` at the offset (i.e. `SELF.` or `SELF.val`). The
+    /// analysis may be of an earlier version of the source (i.e. the last one without errors),
+    /// since the source being typed usually can't be parsed.
+    pub fn completions(&self, source: &str, offset: usize) -> Vec<Completion> {
+        let before = source.get(..offset).unwrap_or_default();
+        // skip the member name being typed
+        let before = before.trim_end_matches(is_ident_char);
+        let receiver = match before.strip_suffix('.') {
+            Some(receiver) => receiver,
+            None => return Vec::new(),
+        };
+        let name_start = receiver
+            .rfind(|c: char| !is_ident_char(c))
+            .map(|idx| idx + 1)
+            .unwrap_or(0);
+        let name = &receiver[name_start..];
+        match self.ident_type(name, name_start) {
+            Some(tpe) => members(&tpe),
+            None => Vec::new(),
+        }
+    }
+
+    /// Byte range of the span without the trailing whitespace (spans of the syntax nodes include
+    /// it)
+    fn range(&self, span: TextRange) -> Range<usize> {
+        self.trim(usize::from(span.start())..usize::from(span.end()))
+    }
+
+    fn trim(&self, range: Range<usize>) -> Range<usize> {
+        let text = self.source.get(range.clone()).unwrap_or_default();
+        range.start..range.start + text.trim_end().len()
+    }
+
+    /// Type of the global variable, or of the last `val` or lambda argument with the name defined
+    /// before the offset
+    fn ident_type(&self, name: &str, offset: usize) -> Option<SType> {
+        if let Some(global) = global_var(name) {
+            return Some(global.tpe());
+        }
+        let mut found = None;
+        let mut stack = self.typed.iter().collect::<Vec<&Expr>>();
+        while let Some(expr) = stack.pop() {
+            if usize::from(expr.span.start()) > offset {
+                continue;
+            }
+            match &expr.kind {
+                ExprKind::Block(block) => {
+                    for val in &block.bindings {
+                        if val.name == name && usize::from(val.span.start()) < offset {
+                            found = val.given_tpe.clone().or_else(|| val.rhs.tpe.clone());
+                        }
+                    }
+                }
+                ExprKind::Lambda(lambda) => {
+                    for (arg, tpe) in &lambda.args {
+                        if arg == name {
+                            found = Some(tpe.clone());
+                        }
+                    }
+                }
+                _ => (),
+            }
+            stack.extend(children(expr).into_iter().rev());
+        }
+        found
+    }
+}
+
+/// Lowers the typed expression to [`ErgoTree`]
+fn lower(typed: Expr, source: &str) -> Result<ErgoTree, CompileError> {
+    let mir = mir::lower::lower(typed)?;
+    let expr =
+        ergotree_ir::type_check::type_check(mir).map_err(|e| IrError::type_check(e, source))?;
+    Ok(expr.try_into().map_err(|e| IrError::ergo_tree(e, source))?)
+}
+
+/// Properties and methods of the type (built-in ones and from the type companion)
+fn members(tpe: &SType) -> Vec<Completion> {
+    let member = |label: &str, detail: String| Completion {
+        label: label.to_string(),
+        detail,
+    };
+    let mut completions = match tpe {
+        SType::STuple(tuple) => tuple
+            .items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| member(&format!("_{}", idx + 1), item.to_string()))
+            .collect(),
+        SType::SColl(elem_tpe) => vec![
+            member("size", SType::SInt.to_string()),
+            member("map", format!("(({}) => B) => Coll[B]", elem_tpe)),
+            member("filter", format!("(({}) => Boolean) => {}", elem_tpe, tpe)),
+            member("exists", format!("(({}) => Boolean) => Boolean", elem_tpe)),
+            member("forall", format!("(({}) => Boolean) => Boolean", elem_tpe)),
+            member("fold", format!("(B, (B, {}) => B) => B", elem_tpe)),
+            member("slice", format!("(Int, Int) => {}", tpe)),
+            member("append", format!("({}) => {}", tpe, tpe)),
+        ],
+        SType::SOption(elem_tpe) => vec![
+            member("get", elem_tpe.to_string()),
+            member("isDefined", SType::SBoolean.to_string()),
+            member("getOrElse", format!("({}) => {}", elem_tpe, elem_tpe)),
+        ],
+        SType::SBox => (0..=9)
+            .map(|idx| member(&format!("R{}", idx), "Option[T]".to_string()))
+            .collect(),
+        _ => Vec::new(),
+    };
+    for method in STypeCompanion::for_type(tpe)
+        .map(|companion| companion.methods())
+        .unwrap_or_default()
+    {
+        if completions.iter().any(|c| c.label == method.name()) {
+            continue;
+        }
+        let sfunc = method.tpe();
+        // the first argument is the object
+        let detail = match sfunc.t_dom.get(1..) {
+            Some([]) | None => sfunc.t_range.to_string(),
+            Some(args) => format!(
+                "({}) => {}",
+                args.iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                sfunc.t_range
+            ),
+        };
+        completions.push(member(method.name(), detail));
+    }
+    completions
+}
+
+/// Looks up the identifier at the offset in the `val` definitions in scope (`None` for lambda
+/// arguments)
+fn definition_in<'a>(
+    expr: &'a Expr,
+    offset: usize,
+    scope: &mut Vec<(&'a str, Option<TextRange>)>,
+) -> Option<TextRange> {
+    if !contains(expr.span, offset) {
+        return None;
+    }
+    let scope_len = scope.len();
+    let found = match &expr.kind {
+        ExprKind::Ident(name) => scope
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .and_then(|(_, span)| *span),
+        ExprKind::Block(block) => {
+            let mut found = None;
+            for val in &block.bindings {
+                if contains(val.rhs.span, offset) {
+                    found = definition_in(&val.rhs, offset, scope);
+                    break;
+                }
+                scope.push((&val.name, Some(val.span)));
+            }
+            found.or_else(|| definition_in(&block.result, offset, scope))
+        }
+        ExprKind::Lambda(lambda) => {
+            scope.extend(lambda.args.iter().map(|(name, _)| (name.as_str(), None)));
+            definition_in(&lambda.body, offset, scope)
+        }
+        _ => children(expr)
+            .into_iter()
+            .find_map(|child| definition_in(child, offset, scope)),
+    };
+    scope.truncate(scope_len);
+    found
+}
+
+fn children(expr: &Expr) -> Vec<&Expr> {
+    match &expr.kind {
+        ExprKind::Ident(_)
+        | ExprKind::GlobalVars(_)
+        | ExprKind::Literal(_)
+        | ExprKind::Constant(_)
+        | ExprKind::ConstantPlaceholder(_) => Vec::new(),
+        ExprKind::Binary(binary) => vec![&binary.lhs, &binary.rhs],
+        ExprKind::Block(block) => block
+            .bindings
+            .iter()
+            .map(|val| &*val.rhs)
+            .chain(std::iter::once(&*block.result))
+            .collect(),
+        ExprKind::Lambda(lambda) => vec![&lambda.body],
+        ExprKind::Select(select) => vec![&select.obj],
+        ExprKind::Apply(apply) => std::iter::once(&*apply.func)
+            .chain(apply.args.iter())
+            .collect(),
+        ExprKind::ApplyTypes(apply_types) => vec![&apply_types.input],
+        ExprKind::Builtin(builtin) => builtin.args.iter().collect(),
+        ExprKind::Unary(unary) => vec![&unary.expr],
+        ExprKind::If(if_expr) => vec![
+            &if_expr.condition,
+            &if_expr.then_branch,
+            &if_expr.else_branch,
+        ],
+        ExprKind::Tuple(tuple) => tuple.items.iter().collect(),
+    }
+}
+
+fn contains(span: TextRange, offset: usize) -> bool {
+    usize::from(span.start()) <= offset && offset <= usize::from(span.end())
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use expect_test::expect;
+
+    /// Source with the `$0` cursor marker removed, and the cursor offset
+    fn with_cursor(source: &str) -> (String, usize) {
+        let offset = source.find("$0").unwrap();
+        (source.replace("$0", ""), offset)
+    }
+
+    #[test]
+    fn test_no_diagnostics() {
+        let analysis = Analysis::new("sigmaProp(HEIGHT > 1)", ScriptEnv::new());
+        assert_eq!(analysis.diagnostics(), vec![]);
+        assert!(analysis.ergo_tree().is_some());
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let source = "{ val a = 1 + true; val b = 2L - 1; sigmaProp(b > 1) }";
+        let analysis = Analysis::new(source, ScriptEnv::new());
+        expect![[r#"
+            [
+                Diagnostic {
+                    message: "Type mismatch: SInt Plus SBoolean",
+                    code: Some(
+                        "ergoscript::type_mismatch",
+                    ),
+                    help: Some(
+                        "the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt",
+                    ),
+                    range: 10..18,
+                    labels: [
+                        (
+                            10..11,
+                            "SInt",
+                        ),
+                        (
+                            14..18,
+                            "SBoolean",
+                        ),
+                    ],
+                },
+                Diagnostic {
+                    message: "Type mismatch: SLong Minus SInt",
+                    code: Some(
+                        "ergoscript::type_mismatch",
+                    ),
+                    help: Some(
+                        "the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt",
+                    ),
+                    range: 28..34,
+                    labels: [
+                        (
+                            28..30,
+                            "SLong",
+                        ),
+                        (
+                            33..34,
+                            "SInt",
+                        ),
+                    ],
+                },
+            ]"#]]
+        .assert_eq(&format!("{:#?}", analysis.diagnostics()));
+        assert!(analysis.ergo_tree().is_none());
+    }
+
+    #[test]
+    fn test_parse_error_diagnostics() {
+        let analysis = Analysis::new("sigmaProp(HEIGHT >)", ScriptEnv::new());
+        let diagnostics = analysis.diagnostics();
+        assert_eq!(
+            diagnostics[0].code.as_deref(),
+            Some("ergoscript::unexpected_token")
+        );
+        assert_eq!(diagnostics[0].range, 18..19);
+    }
+
+    #[test]
+    fn test_type_at() {
+        let (source, offset) = with_cursor("{ val out = OUTPUTS(0); sigmaProp(out.va$0lue > 1L) }");
+        let analysis = Analysis::new(&source, ScriptEnv::new());
+        let (range, tpe) = analysis.type_at(offset).unwrap();
+        assert_eq!(&source[range], "out.value");
+        assert_eq!(tpe, SType::SLong);
+    }
+
+    #[test]
+    fn test_type_at_val_name() {
+        let (source, offset) = with_cursor("{ val o$0ut = OUTPUTS(0); sigmaProp(out.value > 1L) }");
+        let analysis = Analysis::new(&source, ScriptEnv::new());
+        let (range, tpe) = analysis.type_at(offset).unwrap();
+        assert_eq!(&source[range], "val out = OUTPUTS(0)");
+        assert_eq!(tpe, SType::SBox);
+    }
+
+    #[test]
+    fn test_type_at_lambda_arg() {
+        let (source, offset) =
+            with_cursor("sigmaProp(OUTPUTS.exists({ (b: Box) => b$0.value > 1L }))");
+        let analysis = Analysis::new(&source, ScriptEnv::new());
+        let (range, tpe) = analysis.type_at(offset).unwrap();
+        assert_eq!(&source[range], "b");
+        assert_eq!(tpe, SType::SBox);
+    }
+
+    #[test]
+    fn test_definition() {
+        let (source, offset) =
+            with_cursor("{ val a = 1; val b = { val a = 2; a$0 + 1 }; sigmaProp(a + b > 1) }");
+        let analysis = Analysis::new(&source, ScriptEnv::new());
+        let range = analysis.definition(offset).unwrap();
+        assert_eq!(&source[range.clone()], "val a = 2");
+        assert_eq!(range.start, 23);
+    }
+
+    #[test]
+    fn test_definition_outer_scope() {
+        let (source, offset) =
+            with_cursor("{ val a = 1; val b = { val c = 2; c + 1 }; sigmaProp(a$0 + b > 1) }");
+        let analysis = Analysis::new(&source, ScriptEnv::new());
+        let range = analysis.definition(offset).unwrap();
+        assert_eq!(&source[range], "val a = 1");
+    }
+
+    #[test]
+    fn test_definition_lambda_arg() {
+        let (source, offset) =
+            with_cursor("{ val b = 1; sigmaProp(OUTPUTS.exists({ (b: Box) => b$0.value > 1L })) }");
+        let analysis = Analysis::new(&source, ScriptEnv::new());
+        assert_eq!(analysis.definition(offset), None);
+    }
+
+    fn check_completions(source: &str, analyzed: &str, expected: expect_test::Expect) {
+        let (source, offset) = with_cursor(source);
+        let analysis = Analysis::new(analyzed, ScriptEnv::new());
+        let completions = analysis
+            .completions(&source, offset)
+            .into_iter()
+            .map(|c| format!("{}: {}", c.label, c.detail))
+            .collect::<Vec<String>>();
+        expected.assert_eq(&completions.join("\n"));
+    }
+
+    #[test]
+    fn test_self_completions() {
+        check_completions(
+            "sigmaProp(SELF.$0",
+            "sigmaProp(SELF.",
+            expect![[r#"
+                R0: Option[T]
+                R1: Option[T]
+                R2: Option[T]
+                R3: Option[T]
+                R4: Option[T]
+                R5: Option[T]
+                R6: Option[T]
+                R7: Option[T]
+                R8: Option[T]
+                R9: Option[T]
+                getReg: (Byte) => Option[T]
+                value: Long
+                propositionBytes: Coll[Byte]
+                bytes: Coll[Byte]
+                bytesWithoutRef: Coll[Byte]
+                id: Coll[Byte]
+                creationInfo: (Int, Coll[Byte])
+                tokens: Coll[(Coll[Byte], Long)]"#]],
+        );
+    }
+
+    #[test]
+    fn test_context_completions() {
+        check_completions(
+            "sigmaProp(CONTEXT.data$0",
+            "",
+            expect![[r#"
+                dataInputs: Coll[Box]
+                headers: Coll[Header]
+                preHeader: PreHeader
+                INPUTS: Coll[Box]
+                OUTPUTS: Coll[Box]
+                HEIGHT: Int
+                SELF: Box
+                selfBoxIndex: Int
+                LastBlockUtxoRootHash: AvlTree
+                minerPubKey: Coll[Byte]
+                getVar: (Byte) => Option[T]"#]],
+        );
+    }
+
+    #[test]
+    fn test_val_completions() {
+        check_completions(
+            "{ val pair = (1, OUTPUTS(0)); sigmaProp(pair.$0 }",
+            "{ val pair = (1, OUTPUTS(0)); sigmaProp(true) }",
+            expect![[r#"
+                _1: Int
+                _2: Box"#]],
+        );
+    }
+
+    #[test]
+    fn test_no_completions() {
+        check_completions("sigmaProp(HEIGHT > 1$0", "", expect![[r#""#]]);
+    }
+}
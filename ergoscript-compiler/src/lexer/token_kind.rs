@@ -5,7 +5,7 @@ use std::fmt;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Logos)]
 pub enum TokenKind {
-    #[regex("[ \n]+")]
+    #[regex("[ \t\r\n]+")]
     Whitespace,
 
     #[token("def")]
@@ -14,7 +14,19 @@ pub enum TokenKind {
     #[token("val")]
     ValKw,
 
-    #[regex("[A-Za-z][A-Za-z0-9]*")]
+    #[token("if")]
+    IfKw,
+
+    #[token("else")]
+    ElseKw,
+
+    #[token("true")]
+    TrueKw,
+
+    #[token("false")]
+    FalseKw,
+
+    #[regex("\\$?[A-Za-z_][A-Za-z0-9_]*")]
     Ident,
 
     #[regex("[0-9]+")]
@@ -23,6 +35,9 @@ pub enum TokenKind {
     #[regex("[0-9]+L")]
     LongNumber,
 
+    #[regex(r#""[^"]*""#)]
+    StringLiteral,
+
     #[token("+")]
     Plus,
 
@@ -38,9 +53,48 @@ pub enum TokenKind {
     #[token("&&")]
     And,
 
+    #[token("||")]
+    Or,
+
+    #[token("!")]
+    Bang,
+
     #[token("=")]
     Equals,
 
+    #[token("==")]
+    DoubleEquals,
+
+    #[token("!=")]
+    NotEquals,
+
+    #[token("<")]
+    Less,
+
+    #[token("<=")]
+    LessEquals,
+
+    #[token(">")]
+    Greater,
+
+    #[token(">=")]
+    GreaterEquals,
+
+    #[token("=>")]
+    FatArrow,
+
+    #[token(":")]
+    Colon,
+
+    #[token(",")]
+    Comma,
+
+    #[token(";")]
+    Semicolon,
+
+    #[token(".")]
+    Dot,
+
     #[token("(")]
     LParen,
 
@@ -53,6 +107,12 @@ pub enum TokenKind {
     #[token("}")]
     RBrace,
 
+    #[token("[")]
+    LBracket,
+
+    #[token("]")]
+    RBracket,
+
     #[regex("//.*")]
     Comment,
 
@@ -72,19 +132,39 @@ impl fmt::Display for TokenKind {
             Self::Whitespace => "whitespace",
             Self::FnKw => "‘def’",
             Self::ValKw => "‘val’",
+            Self::IfKw => "‘if’",
+            Self::ElseKw => "‘else’",
+            Self::TrueKw => "‘true’",
+            Self::FalseKw => "‘false’",
             Self::Ident => "identifier",
             Self::IntNumber => "number",
             Self::LongNumber => "number",
+            Self::StringLiteral => "string",
             Self::Plus => "‘+’",
             Self::Minus => "‘-’",
             Self::Star => "‘*’",
             Self::Slash => "‘/’",
             Self::And => "‘&&’",
+            Self::Or => "‘||’",
+            Self::Bang => "‘!’",
             Self::Equals => "‘=’",
+            Self::DoubleEquals => "‘==’",
+            Self::NotEquals => "‘!=’",
+            Self::Less => "‘<’",
+            Self::LessEquals => "‘<=’",
+            Self::Greater => "‘>’",
+            Self::GreaterEquals => "‘>=’",
+            Self::FatArrow => "‘=>’",
+            Self::Colon => "‘:’",
+            Self::Comma => "‘,’",
+            Self::Semicolon => "‘;’",
+            Self::Dot => "‘.’",
             Self::LParen => "‘(’",
             Self::RParen => "‘)’",
             Self::LBrace => "‘{’",
             Self::RBrace => "‘}’",
+            Self::LBracket => "‘[’",
+            Self::RBracket => "‘]’",
             Self::Comment => "comment",
             Self::Error => "an unrecognized token",
         })
@@ -109,6 +189,11 @@ mod tests {
         check("  \n ", TokenKind::Whitespace);
     }
 
+    #[test]
+    fn lex_tabs_and_carriage_returns() {
+        check("\t\r\n", TokenKind::Whitespace);
+    }
+
     #[test]
     fn lex_fn_keyword() {
         check("def", TokenKind::FnKw);
@@ -119,6 +204,26 @@ mod tests {
         check("val", TokenKind::ValKw);
     }
 
+    #[test]
+    fn lex_if_else_keywords() {
+        check("if", TokenKind::IfKw);
+        check("else", TokenKind::ElseKw);
+    }
+
+    #[test]
+    fn lex_boolean_literals() {
+        check("true", TokenKind::TrueKw);
+        check("false", TokenKind::FalseKw);
+    }
+
+    #[test]
+    fn lex_string_literal() {
+        check(
+            r#""9f4QF8AD1nQ3nJahQVkMj8hFSVVzVom77b52JU7EW71Zexg6N8v""#,
+            TokenKind::StringLiteral,
+        );
+    }
+
     #[test]
     fn lex_alphabetic_identifier() {
         check("abcd", TokenKind::Ident);
@@ -134,6 +239,17 @@ mod tests {
         check("ABCdef", TokenKind::Ident);
     }
 
+    #[test]
+    fn lex_identifier_with_underscores() {
+        check("_1", TokenKind::Ident);
+        check("min_value", TokenKind::Ident);
+    }
+
+    #[test]
+    fn lex_template_parameter() {
+        check("$deadline", TokenKind::Ident);
+    }
+
     #[test]
     fn lex_single_char_identifier() {
         check("x", TokenKind::Ident);
@@ -169,6 +285,47 @@ mod tests {
         check("=", TokenKind::Equals);
     }
 
+    #[test]
+    fn lex_logical_operators() {
+        check("||", TokenKind::Or);
+        check("!", TokenKind::Bang);
+    }
+
+    #[test]
+    fn lex_comparison_operators() {
+        check("==", TokenKind::DoubleEquals);
+        check("!=", TokenKind::NotEquals);
+        check("<", TokenKind::Less);
+        check("<=", TokenKind::LessEquals);
+        check(">", TokenKind::Greater);
+        check(">=", TokenKind::GreaterEquals);
+    }
+
+    #[test]
+    fn lex_fat_arrow() {
+        check("=>", TokenKind::FatArrow);
+    }
+
+    #[test]
+    fn lex_colon() {
+        check(":", TokenKind::Colon);
+    }
+
+    #[test]
+    fn lex_comma() {
+        check(",", TokenKind::Comma);
+    }
+
+    #[test]
+    fn lex_semicolon() {
+        check(";", TokenKind::Semicolon);
+    }
+
+    #[test]
+    fn lex_dot() {
+        check(".", TokenKind::Dot);
+    }
+
     #[test]
     fn lex_left_parenthesis() {
         check("(", TokenKind::LParen);
@@ -189,6 +346,16 @@ mod tests {
         check("}", TokenKind::RBrace);
     }
 
+    #[test]
+    fn lex_left_bracket() {
+        check("[", TokenKind::LBracket);
+    }
+
+    #[test]
+    fn lex_right_bracket() {
+        check("]", TokenKind::RBracket);
+    }
+
     #[test]
     fn lex_comment() {
         check("// foo", TokenKind::Comment);
@@ -24,4 +24,7 @@ pub(crate) mod syntax;
 pub(crate) mod type_infer;
 
 pub mod compiler;
+pub mod decompiler;
+pub mod formatter;
+pub mod ide;
 pub mod script_env;
@@ -1,45 +1,143 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::convert::TryInto;
+
+use ergotree_ir::mir::and::And;
+use ergotree_ir::mir::apply::Apply;
+use ergotree_ir::mir::atleast::Atleast;
 use ergotree_ir::mir::bin_op::ArithOp;
 use ergotree_ir::mir::bin_op::BinOp;
 use ergotree_ir::mir::bin_op::BinOpKind;
+use ergotree_ir::mir::bin_op::LogicalOp;
+use ergotree_ir::mir::bin_op::RelationOp;
+use ergotree_ir::mir::block::BlockValue;
+use ergotree_ir::mir::bool_to_sigma::BoolToSigmaProp;
+use ergotree_ir::mir::calc_blake2b256::CalcBlake2b256;
+use ergotree_ir::mir::calc_sha256::CalcSha256;
+use ergotree_ir::mir::coll_append::Append;
+use ergotree_ir::mir::coll_by_index::ByIndex;
+use ergotree_ir::mir::coll_exists::Exists;
+use ergotree_ir::mir::coll_filter::Filter;
+use ergotree_ir::mir::coll_fold::Fold;
+use ergotree_ir::mir::coll_forall::ForAll;
+use ergotree_ir::mir::coll_map::Map;
+use ergotree_ir::mir::coll_size::SizeOf;
+use ergotree_ir::mir::coll_slice::Slice;
+use ergotree_ir::mir::collection::Collection;
 use ergotree_ir::mir::constant::Constant;
+use ergotree_ir::mir::create_prove_dh_tuple::CreateProveDhTuple;
+use ergotree_ir::mir::create_provedlog::CreateProveDlog;
+use ergotree_ir::mir::decode_point::DecodePoint;
+use ergotree_ir::mir::downcast::Downcast;
 use ergotree_ir::mir::expr::Expr;
+use ergotree_ir::mir::expr::InvalidArgumentError;
+use ergotree_ir::mir::extract_amount::ExtractAmount;
+use ergotree_ir::mir::extract_bytes::ExtractBytes;
+use ergotree_ir::mir::extract_bytes_with_no_ref::ExtractBytesWithNoRef;
+use ergotree_ir::mir::extract_creation_info::ExtractCreationInfo;
+use ergotree_ir::mir::extract_id::ExtractId;
+use ergotree_ir::mir::extract_reg_as::ExtractRegisterAs;
+use ergotree_ir::mir::extract_script_bytes::ExtractScriptBytes;
+use ergotree_ir::mir::func_value::FuncArg;
+use ergotree_ir::mir::func_value::FuncValue;
 use ergotree_ir::mir::global_vars::GlobalVars;
+use ergotree_ir::mir::if_op::If;
+use ergotree_ir::mir::logical_not::LogicalNot;
+use ergotree_ir::mir::method_call::MethodCall;
+use ergotree_ir::mir::negation::Negation;
+use ergotree_ir::mir::option_get::OptionGet;
+use ergotree_ir::mir::option_get_or_else::OptionGetOrElse;
+use ergotree_ir::mir::option_is_defined::OptionIsDefined;
+use ergotree_ir::mir::or::Or;
+use ergotree_ir::mir::property_call::PropertyCall;
+use ergotree_ir::mir::select_field::SelectField;
+use ergotree_ir::mir::sigma_and::SigmaAnd;
+use ergotree_ir::mir::sigma_or::SigmaOr;
+use ergotree_ir::mir::sigma_prop_bytes::SigmaPropBytes;
+use ergotree_ir::mir::sigma_prop_is_proven::SigmaPropIsProven;
+use ergotree_ir::mir::subst_const::SubstConstants;
+use ergotree_ir::mir::tuple::Tuple;
+use ergotree_ir::mir::unary_op::OneArgOpTryBuild;
+use ergotree_ir::mir::upcast::Upcast;
+use ergotree_ir::mir::val_def::ValDef;
+use ergotree_ir::mir::val_def::ValId;
+use ergotree_ir::mir::val_use::ValUse;
+use ergotree_ir::types::stuple::STuple;
+use ergotree_ir::types::stype::SType;
 use hir::BinaryOp;
+use hir::BuiltinOp;
+use hir::UnaryOp;
 use rowan::TextRange;
+use thiserror::Error;
 
-use crate::error::pretty_error_desc;
+use crate::error::impl_diagnostic;
+use crate::error::ErrorKind;
+use crate::error::Label;
 use crate::hir;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Error)]
+#[error("{msg}")]
 pub struct MirLoweringError {
+    kind: ErrorKind,
     msg: String,
     span: TextRange,
+    labels: Vec<Label>,
 }
 
 impl MirLoweringError {
     pub fn new(msg: String, span: TextRange) -> Self {
-        Self { msg, span }
-    }
-
-    pub fn pretty_desc(&self, source: &str) -> String {
-        pretty_error_desc(source, self.span, &self.msg)
+        Self {
+            kind: ErrorKind::Internal,
+            msg,
+            span,
+            labels: Vec::new(),
+        }
     }
 }
 
+impl_diagnostic!(MirLoweringError);
+
+/// References to the values in scope: block `val`s and lambda arguments, and the fields of the
+/// tuple argument for the fold operation arguments
+type ValEnv = HashMap<String, Expr>;
+
 pub fn lower(hir_expr: hir::Expr) -> Result<Expr, MirLoweringError> {
+    let mut next_val_id = 1;
+    lower_in(hir_expr, &ValEnv::new(), &mut next_val_id)
+}
+
+fn lower_in(
+    hir_expr: hir::Expr,
+    env: &ValEnv,
+    next_val_id: &mut u32,
+) -> Result<Expr, MirLoweringError> {
+    let hir_tpe = hir_expr.tpe.clone().ok_or_else(|| {
+        MirLoweringError::new(
+            format!("MIR error: missing tpe for HIR: {0:?}", hir_expr),
+            hir_expr.span,
+        )
+    })?;
     let mir: Expr = match &hir_expr.kind {
         hir::ExprKind::GlobalVars(hir) => match hir {
             hir::GlobalVars::Height => GlobalVars::Height.into(),
+            hir::GlobalVars::Inputs => GlobalVars::Inputs.into(),
+            hir::GlobalVars::Outputs => GlobalVars::Outputs.into(),
+            hir::GlobalVars::SelfBox => GlobalVars::SelfBox.into(),
+            hir::GlobalVars::Context => Expr::Context,
+            hir::GlobalVars::MinerPubkey => GlobalVars::MinerPubKey.into(),
+        },
+        hir::ExprKind::Ident(name) => match env.get(name) {
+            Some(val) => val.clone(),
+            None => {
+                return Err(MirLoweringError::new(
+                    format!("MIR error: Unresolved Ident {0:?}", hir_expr),
+                    hir_expr.span,
+                ))
+            }
         },
-        hir::ExprKind::Ident(_) => {
-            return Err(MirLoweringError::new(
-                format!("MIR error: Unresolved Ident {0:?}", hir_expr),
-                hir_expr.span,
-            ))
-        }
         hir::ExprKind::Binary(hir) => {
-            let l = lower(*hir.lhs.clone())?;
-            let r = lower(*hir.rhs.clone())?;
+            let l = lower_in(*hir.lhs.clone(), env, next_val_id)?;
+            let r = lower_in(*hir.rhs.clone(), env, next_val_id)?;
             BinOp {
                 kind: hir.op.node.clone().into(),
                 left: l.into(),
@@ -51,16 +149,120 @@ pub fn lower(hir_expr: hir::Expr) -> Result<Expr, MirLoweringError> {
             let constant: Constant = match *hir {
                 hir::Literal::Int(v) => v.into(),
                 hir::Literal::Long(v) => v.into(),
+                hir::Literal::Bool(v) => v.into(),
+                hir::Literal::Str(_) => {
+                    return Err(MirLoweringError::new(
+                        format!("MIR error: unexpected string literal {0:?}", hir_expr),
+                        hir_expr.span,
+                    ))
+                }
             };
             constant.into()
         }
+        hir::ExprKind::Constant(constant) => constant.clone().into(),
+        hir::ExprKind::ConstantPlaceholder(placeholder) => placeholder.clone().into(),
+        hir::ExprKind::Unary(hir) => {
+            let input = lower_in(*hir.expr.clone(), env, next_val_id)?;
+            match hir.op.node {
+                UnaryOp::Neg => Negation::try_build(input).map(Expr::from),
+                UnaryOp::Not => LogicalNot::try_build(input).map(Expr::from),
+            }
+            .map_err(|e| invalid_argument(e, hir_expr.span))?
+        }
+        hir::ExprKind::If(hir) => If {
+            condition: lower_in(*hir.condition.clone(), env, next_val_id)?.into(),
+            true_branch: lower_in(*hir.then_branch.clone(), env, next_val_id)?.into(),
+            false_branch: lower_in(*hir.else_branch.clone(), env, next_val_id)?.into(),
+        }
+        .into(),
+        hir::ExprKind::Block(hir) => {
+            let mut env = env.clone();
+            let mut items = Vec::with_capacity(hir.bindings.len());
+            for val in &hir.bindings {
+                let rhs = lower_in(*val.rhs.clone(), &env, next_val_id)?;
+                let id = fresh_val_id(next_val_id);
+                env.insert(
+                    val.name.clone(),
+                    ValUse {
+                        val_id: id,
+                        tpe: rhs.tpe(),
+                    }
+                    .into(),
+                );
+                items.push(
+                    ValDef {
+                        id,
+                        rhs: rhs.into(),
+                    }
+                    .into(),
+                );
+            }
+            let result = lower_in(*hir.result.clone(), &env, next_val_id)?;
+            BlockValue {
+                items,
+                result: result.into(),
+            }
+            .into()
+        }
+        hir::ExprKind::Lambda(hir) => {
+            // ids of the arguments and the values in the body are not used outside of the lambda,
+            // so they are taken again by the following values (as the reference compiler does)
+            let next_val_id = &mut next_val_id.clone();
+            let mut env = env.clone();
+            let args = hir
+                .args
+                .iter()
+                .map(|(name, tpe)| {
+                    let idx = fresh_val_id(next_val_id);
+                    env.insert(
+                        name.clone(),
+                        ValUse {
+                            val_id: idx,
+                            tpe: tpe.clone(),
+                        }
+                        .into(),
+                    );
+                    FuncArg {
+                        idx,
+                        tpe: tpe.clone(),
+                    }
+                })
+                .collect();
+            let body = lower_in(*hir.body.clone(), &env, next_val_id)?;
+            FuncValue::new(args, body).into()
+        }
+        hir::ExprKind::Apply(hir) => {
+            let func = lower_in(*hir.func.clone(), env, next_val_id)?;
+            let args = lower_all(&hir.args, env, next_val_id)?;
+            Apply::new(func, args)
+                .map_err(|e| invalid_argument(e, hir_expr.span))?
+                .into()
+        }
+        hir::ExprKind::Tuple(hir) => {
+            let items = lower_all(&hir.items, env, next_val_id)?;
+            Tuple::new(items)
+                .map_err(|e| invalid_argument(e, hir_expr.span))?
+                .into()
+        }
+        hir::ExprKind::Builtin(hir) => {
+            let args = match (&hir.op, &hir.args[..]) {
+                (BuiltinOp::Fold, [input, zero, fold_op]) => vec![
+                    lower_in(input.clone(), env, next_val_id)?,
+                    lower_in(zero.clone(), env, next_val_id)?,
+                    lower_fold_op(fold_op, env, next_val_id)?,
+                ],
+                _ => lower_all(&hir.args, env, next_val_id)?,
+            };
+            lower_builtin(&hir.op, args, hir_tpe.clone())
+                .map_err(|e| invalid_argument(e, hir_expr.span))?
+        }
+        hir::ExprKind::Select(_) | hir::ExprKind::ApplyTypes(_) => {
+            return Err(MirLoweringError::new(
+                format!("MIR error: unresolved {0:?}", hir_expr),
+                hir_expr.span,
+            ))
+        }
     };
-    let hir_tpe = hir_expr.tpe.clone().ok_or_else(|| {
-        MirLoweringError::new(
-            format!("MIR error: missing tpe for HIR: {0:?}", hir_expr),
-            hir_expr.span,
-        )
-    })?;
     if mir.tpe() == hir_tpe {
         Ok(mir)
     } else {
@@ -75,6 +277,278 @@ pub fn lower(hir_expr: hir::Expr) -> Result<Expr, MirLoweringError> {
     }
 }
 
+fn lower_all(
+    hir_exprs: &[hir::Expr],
+    env: &ValEnv,
+    next_val_id: &mut u32,
+) -> Result<Vec<Expr>, MirLoweringError> {
+    hir_exprs
+        .iter()
+        .map(|hir_expr| lower_in(hir_expr.clone(), env, next_val_id))
+        .collect()
+}
+
+/// Fold operation `(acc: R, item: T) => ...` takes a single tuple argument in MIR, i.e.
+/// `(t: (R, T)) => ...` with `acc` and `item` replaced by `t._1` and `t._2`
+fn lower_fold_op(
+    hir_expr: &hir::Expr,
+    env: &ValEnv,
+    next_val_id: &mut u32,
+) -> Result<Expr, MirLoweringError> {
+    let lambda = match &hir_expr.kind {
+        hir::ExprKind::Lambda(lambda) if lambda.args.len() == 2 => lambda,
+        _ => {
+            return Err(MirLoweringError::new(
+                "MIR error: fold operation should be a lambda with two arguments".to_string(),
+                hir_expr.span,
+            ))
+        }
+    };
+    let next_val_id = &mut next_val_id.clone();
+    let arg_tpe: SType = STuple::pair(lambda.args[0].1.clone(), lambda.args[1].1.clone()).into();
+    let arg = ValUse {
+        val_id: fresh_val_id(next_val_id),
+        tpe: arg_tpe.clone(),
+    };
+    let mut env = env.clone();
+    for (field_index, (name, _)) in (1u8..).zip(&lambda.args) {
+        let field = field_index
+            .try_into()
+            .map_err(|_| {
+                InvalidArgumentError(format!("invalid tuple field index: {}", field_index))
+            })
+            .and_then(|field_index| SelectField::new(arg.clone().into(), field_index))
+            .map_err(|e| invalid_argument(e, hir_expr.span))?;
+        env.insert(name.clone(), field.into());
+    }
+    let body = lower_in(*lambda.body.clone(), &env, next_val_id)?;
+    let func = FuncValue::new(
+        vec![FuncArg {
+            idx: arg.val_id,
+            tpe: arg_tpe,
+        }],
+        body,
+    );
+    Ok(func.into())
+}
+
+fn lower_builtin(
+    op: &BuiltinOp,
+    args: Vec<Expr>,
+    tpe: SType,
+) -> Result<Expr, InvalidArgumentError> {
+    Ok(match op {
+        BuiltinOp::Method(method) => {
+            let mut args = args.into_iter();
+            let obj = args
+                .next()
+                .ok_or_else(|| InvalidArgumentError(format!("{:?}: missing object", op)))?;
+            let args: Vec<Expr> = args.collect();
+            if args.is_empty() && obj.tpe() == SType::SBox {
+                // box properties with the dedicated nodes (as the reference compiler does)
+                match method.name() {
+                    "value" => return Ok(ExtractAmount::try_build(obj)?.into()),
+                    "propositionBytes" => return Ok(ExtractScriptBytes::try_build(obj)?.into()),
+                    "bytes" => return Ok(ExtractBytes::try_build(obj)?.into()),
+                    "bytesWithoutRef" => return Ok(ExtractBytesWithNoRef::try_build(obj)?.into()),
+                    "id" => return Ok(ExtractId::try_build(obj)?.into()),
+                    "creationInfo" => return Ok(ExtractCreationInfo::try_build(obj)?.into()),
+                    _ => (),
+                }
+            }
+            if let (Some(from), Some(to)) = (numeric_rank(&obj.tpe()), numeric_rank(&tpe)) {
+                // `toByte`, .., `toBigInt` conversions
+                if args.is_empty() && method.name() == format!("to{}", tpe) {
+                    return Ok(match from.cmp(&to) {
+                        std::cmp::Ordering::Less => Upcast::new(obj, tpe)?.into(),
+                        std::cmp::Ordering::Equal => obj,
+                        std::cmp::Ordering::Greater => Downcast::new(obj, tpe)?.into(),
+                    });
+                }
+            }
+            if args.is_empty() {
+                PropertyCall::new(obj, method.clone())?.into()
+            } else {
+                MethodCall::new(obj, method.clone(), args)?.into()
+            }
+        }
+        BuiltinOp::ExtractRegisterAs(register_id) => {
+            let [input] = exact_args(op, args)?;
+            ExtractRegisterAs::new(input, *register_id, tpe)?.into()
+        }
+        BuiltinOp::ByIndex => {
+            let [input, index] = exact_args(op, args)?;
+            ByIndex::new(input, index, None)?.into()
+        }
+        BuiltinOp::SelectField(field_index) => {
+            let [input] = exact_args(op, args)?;
+            let field_index = (*field_index).try_into().map_err(|_| {
+                InvalidArgumentError(format!("invalid tuple field index: {}", field_index))
+            })?;
+            SelectField::new(input, field_index)?.into()
+        }
+        BuiltinOp::SizeOf => {
+            let [input] = exact_args(op, args)?;
+            SizeOf::try_build(input)?.into()
+        }
+        BuiltinOp::OptionGet => {
+            let [input] = exact_args(op, args)?;
+            OptionGet::try_build(input)?.into()
+        }
+        BuiltinOp::BoolToSigmaProp => {
+            let [input] = exact_args(op, args)?;
+            BoolToSigmaProp::try_build(input)?.into()
+        }
+        BuiltinOp::CreateProveDlog => {
+            let [input] = exact_args(op, args)?;
+            CreateProveDlog::try_build(input)?.into()
+        }
+        BuiltinOp::CreateProveDhTuple => {
+            let [g, h, u, v] = exact_args(op, args)?;
+            CreateProveDhTuple::new(g, h, u, v)?.into()
+        }
+        BuiltinOp::Atleast => {
+            let [bound, input] = exact_args(op, args)?;
+            Atleast::new(bound, input)?.into()
+        }
+        BuiltinOp::AllOf => {
+            let [input] = exact_args(op, args)?;
+            And {
+                input: input.into(),
+            }
+            .into()
+        }
+        BuiltinOp::AnyOf => {
+            let [input] = exact_args(op, args)?;
+            Or {
+                input: input.into(),
+            }
+            .into()
+        }
+        BuiltinOp::SigmaAnd => SigmaAnd::new(args)?.into(),
+        BuiltinOp::SigmaOr => SigmaOr::new(args)?.into(),
+        BuiltinOp::CalcBlake2b256 => {
+            let [input] = exact_args(op, args)?;
+            CalcBlake2b256::try_build(input)?.into()
+        }
+        BuiltinOp::CalcSha256 => {
+            let [input] = exact_args(op, args)?;
+            CalcSha256::try_build(input)?.into()
+        }
+        BuiltinOp::Map => {
+            let [input, mapper] = exact_args(op, args)?;
+            Map::new(input, mapper)?.into()
+        }
+        BuiltinOp::Filter => {
+            let [input, condition] = exact_args(op, args)?;
+            Filter::new(input, condition)?.into()
+        }
+        BuiltinOp::Exists => {
+            let [input, condition] = exact_args(op, args)?;
+            Exists::new(input, condition)?.into()
+        }
+        BuiltinOp::ForAll => {
+            let [input, condition] = exact_args(op, args)?;
+            ForAll::new(input, condition)?.into()
+        }
+        BuiltinOp::Fold => {
+            let [input, zero, fold_op] = exact_args(op, args)?;
+            Fold::new(input, zero, fold_op)?.into()
+        }
+        BuiltinOp::Slice => {
+            let [input, from, until] = exact_args(op, args)?;
+            Slice::new(input, from, until)?.into()
+        }
+        BuiltinOp::Append => {
+            let [input, col_2] = exact_args(op, args)?;
+            Append::new(input, col_2)?.into()
+        }
+        BuiltinOp::OptionIsDefined => {
+            let [input] = exact_args(op, args)?;
+            OptionIsDefined::try_build(input)?.into()
+        }
+        BuiltinOp::OptionGetOrElse => {
+            let [input, default] = exact_args(op, args)?;
+            OptionGetOrElse::new(input, default)?.into()
+        }
+        BuiltinOp::Min | BuiltinOp::Max => {
+            let [left, right] = exact_args(op, args)?;
+            let kind = if *op == BuiltinOp::Min {
+                ArithOp::Min
+            } else {
+                ArithOp::Max
+            };
+            BinOp {
+                kind: BinOpKind::Arith(kind),
+                left: left.into(),
+                right: right.into(),
+            }
+            .into()
+        }
+        BuiltinOp::DecodePoint => {
+            let [input] = exact_args(op, args)?;
+            DecodePoint::try_build(input)?.into()
+        }
+        BuiltinOp::SubstConstants => {
+            let [script_bytes, positions, new_values] = exact_args(op, args)?;
+            SubstConstants::new(script_bytes, positions, new_values)?.into()
+        }
+        BuiltinOp::SigmaPropBytes => {
+            let [input] = exact_args(op, args)?;
+            SigmaPropBytes::try_build(input)?.into()
+        }
+        BuiltinOp::SigmaPropIsProven => {
+            let [input] = exact_args(op, args)?;
+            SigmaPropIsProven::try_build(input)?.into()
+        }
+        BuiltinOp::Collection => match tpe {
+            SType::SColl(elem_tpe) => Collection::new(*elem_tpe, args)?.into(),
+            _ => {
+                return Err(InvalidArgumentError(format!(
+                    "{:?}: expected collection type, got {:?}",
+                    op, tpe
+                )))
+            }
+        },
+    })
+}
+
+/// Position of the numeric type in `Byte` < `Short` < `Int` < `Long` < `BigInt`
+fn numeric_rank(tpe: &SType) -> Option<u8> {
+    Some(match tpe {
+        SType::SByte => 0,
+        SType::SShort => 1,
+        SType::SInt => 2,
+        SType::SLong => 3,
+        SType::SBigInt => 4,
+        _ => return None,
+    })
+}
+
+fn exact_args<const N: usize>(
+    op: &BuiltinOp,
+    args: Vec<Expr>,
+) -> Result<[Expr; N], InvalidArgumentError> {
+    <[Expr; N]>::try_from(args).map_err(|args| {
+        InvalidArgumentError(format!(
+            "{:?}: expected {} arguments, got {}",
+            op,
+            N,
+            args.len()
+        ))
+    })
+}
+
+fn invalid_argument(e: InvalidArgumentError, span: TextRange) -> MirLoweringError {
+    MirLoweringError::new(format!("MIR error: {}", e), span)
+}
+
+fn fresh_val_id(next_val_id: &mut u32) -> ValId {
+    let id = ValId(*next_val_id);
+    *next_val_id += 1;
+    id
+}
+
 impl From<hir::BinaryOp> for BinOpKind {
     fn from(op: hir::BinaryOp) -> Self {
         match op {
@@ -82,6 +556,14 @@ impl From<hir::BinaryOp> for BinOpKind {
             BinaryOp::Minus => ArithOp::Minus.into(),
             BinaryOp::Multiply => ArithOp::Multiply.into(),
             BinaryOp::Divide => ArithOp::Divide.into(),
+            BinaryOp::Eq => RelationOp::Eq.into(),
+            BinaryOp::NotEq => RelationOp::NEq.into(),
+            BinaryOp::Lt => RelationOp::Lt.into(),
+            BinaryOp::Le => RelationOp::Le.into(),
+            BinaryOp::Gt => RelationOp::Gt.into(),
+            BinaryOp::Ge => RelationOp::Ge.into(),
+            BinaryOp::And => LogicalOp::And.into(),
+            BinaryOp::Or => LogicalOp::Or.into(),
         }
     }
 }
@@ -207,4 +689,411 @@ mod tests {
                 )"#]],
         );
     }
+
+    #[test]
+    fn block() {
+        check(
+            "{ val a = 1; val b = a + 2; b }",
+            expect![[r#"
+                BlockValue(
+                    Spanned {
+                        source_span: SourceSpan {
+                            offset: 0,
+                            length: 0,
+                        },
+                        expr: BlockValue {
+                            items: [
+                                ValDef(
+                                    Spanned {
+                                        source_span: SourceSpan {
+                                            offset: 0,
+                                            length: 0,
+                                        },
+                                        expr: ValDef {
+                                            id: ValId(
+                                                1,
+                                            ),
+                                            rhs: Const(
+                                                "1: SInt",
+                                            ),
+                                        },
+                                    },
+                                ),
+                                ValDef(
+                                    Spanned {
+                                        source_span: SourceSpan {
+                                            offset: 0,
+                                            length: 0,
+                                        },
+                                        expr: ValDef {
+                                            id: ValId(
+                                                2,
+                                            ),
+                                            rhs: BinOp(
+                                                Spanned {
+                                                    source_span: SourceSpan {
+                                                        offset: 0,
+                                                        length: 0,
+                                                    },
+                                                    expr: BinOp {
+                                                        kind: Arith(
+                                                            Plus,
+                                                        ),
+                                                        left: ValUse(
+                                                            ValUse {
+                                                                val_id: ValId(
+                                                                    1,
+                                                                ),
+                                                                tpe: SInt,
+                                                            },
+                                                        ),
+                                                        right: Const(
+                                                            "2: SInt",
+                                                        ),
+                                                    },
+                                                },
+                                            ),
+                                        },
+                                    },
+                                ),
+                            ],
+                            result: ValUse(
+                                ValUse {
+                                    val_id: ValId(
+                                        2,
+                                    ),
+                                    tpe: SInt,
+                                },
+                            ),
+                        },
+                    },
+                )"#]],
+        );
+    }
+
+    #[test]
+    fn lambda() {
+        check(
+            "{ (a: Int) => val b = a + 1; b }",
+            expect![[r#"
+            FuncValue(
+                FuncValue {
+                    args: [
+                        FuncArg {
+                            idx: ValId(
+                                1,
+                            ),
+                            tpe: SInt,
+                        },
+                    ],
+                    body: BlockValue(
+                        Spanned {
+                            source_span: SourceSpan {
+                                offset: 0,
+                                length: 0,
+                            },
+                            expr: BlockValue {
+                                items: [
+                                    ValDef(
+                                        Spanned {
+                                            source_span: SourceSpan {
+                                                offset: 0,
+                                                length: 0,
+                                            },
+                                            expr: ValDef {
+                                                id: ValId(
+                                                    2,
+                                                ),
+                                                rhs: BinOp(
+                                                    Spanned {
+                                                        source_span: SourceSpan {
+                                                            offset: 0,
+                                                            length: 0,
+                                                        },
+                                                        expr: BinOp {
+                                                            kind: Arith(
+                                                                Plus,
+                                                            ),
+                                                            left: ValUse(
+                                                                ValUse {
+                                                                    val_id: ValId(
+                                                                        1,
+                                                                    ),
+                                                                    tpe: SInt,
+                                                                },
+                                                            ),
+                                                            right: Const(
+                                                                "1: SInt",
+                                                            ),
+                                                        },
+                                                    },
+                                                ),
+                                            },
+                                        },
+                                    ),
+                                ],
+                                result: ValUse(
+                                    ValUse {
+                                        val_id: ValId(
+                                            2,
+                                        ),
+                                        tpe: SInt,
+                                    },
+                                ),
+                            },
+                        },
+                    ),
+                    tpe: SFunc(
+                        SFunc {
+                            t_dom: [
+                                SInt,
+                            ],
+                            t_range: SInt,
+                            tpe_params: [],
+                        },
+                    ),
+                },
+            )"#]],
+        );
+    }
+
+    #[test]
+    fn val_shadowing() {
+        check(
+            "{ val a = 1; val f = { (a: Long) => a }; a }",
+            expect![[r#"
+                BlockValue(
+                    Spanned {
+                        source_span: SourceSpan {
+                            offset: 0,
+                            length: 0,
+                        },
+                        expr: BlockValue {
+                            items: [
+                                ValDef(
+                                    Spanned {
+                                        source_span: SourceSpan {
+                                            offset: 0,
+                                            length: 0,
+                                        },
+                                        expr: ValDef {
+                                            id: ValId(
+                                                1,
+                                            ),
+                                            rhs: Const(
+                                                "1: SInt",
+                                            ),
+                                        },
+                                    },
+                                ),
+                                ValDef(
+                                    Spanned {
+                                        source_span: SourceSpan {
+                                            offset: 0,
+                                            length: 0,
+                                        },
+                                        expr: ValDef {
+                                            id: ValId(
+                                                2,
+                                            ),
+                                            rhs: FuncValue(
+                                                FuncValue {
+                                                    args: [
+                                                        FuncArg {
+                                                            idx: ValId(
+                                                                2,
+                                                            ),
+                                                            tpe: SLong,
+                                                        },
+                                                    ],
+                                                    body: ValUse(
+                                                        ValUse {
+                                                            val_id: ValId(
+                                                                2,
+                                                            ),
+                                                            tpe: SLong,
+                                                        },
+                                                    ),
+                                                    tpe: SFunc(
+                                                        SFunc {
+                                                            t_dom: [
+                                                                SLong,
+                                                            ],
+                                                            t_range: SLong,
+                                                            tpe_params: [],
+                                                        },
+                                                    ),
+                                                },
+                                            ),
+                                        },
+                                    },
+                                ),
+                            ],
+                            result: ValUse(
+                                ValUse {
+                                    val_id: ValId(
+                                        1,
+                                    ),
+                                    tpe: SInt,
+                                },
+                            ),
+                        },
+                    },
+                )"#]],
+        );
+    }
+
+    #[test]
+    fn by_index() {
+        check(
+            "{ (i: Int) => INPUTS(i) }",
+            expect![[r#"
+            FuncValue(
+                FuncValue {
+                    args: [
+                        FuncArg {
+                            idx: ValId(
+                                1,
+                            ),
+                            tpe: SInt,
+                        },
+                    ],
+                    body: ByIndex(
+                        Spanned {
+                            source_span: SourceSpan {
+                                offset: 0,
+                                length: 0,
+                            },
+                            expr: ByIndex {
+                                input: GlobalVars(
+                                    Inputs,
+                                ),
+                                index: ValUse(
+                                    ValUse {
+                                        val_id: ValId(
+                                            1,
+                                        ),
+                                        tpe: SInt,
+                                    },
+                                ),
+                                default: None,
+                                input_elem_tpe: SBox,
+                            },
+                        },
+                    ),
+                    tpe: SFunc(
+                        SFunc {
+                            t_dom: [
+                                SInt,
+                            ],
+                            t_range: SBox,
+                            tpe_params: [],
+                        },
+                    ),
+                },
+            )"#]],
+        );
+    }
+
+    #[test]
+    fn logical_not() {
+        check(
+            "!(HEIGHT > 1)",
+            expect![[r#"
+            LogicalNot(
+                Spanned {
+                    source_span: SourceSpan {
+                        offset: 0,
+                        length: 0,
+                    },
+                    expr: LogicalNot {
+                        input: BinOp(
+                            Spanned {
+                                source_span: SourceSpan {
+                                    offset: 0,
+                                    length: 0,
+                                },
+                                expr: BinOp {
+                                    kind: Relation(
+                                        Gt,
+                                    ),
+                                    left: GlobalVars(
+                                        Height,
+                                    ),
+                                    right: Const(
+                                        "1: SInt",
+                                    ),
+                                },
+                            },
+                        ),
+                    },
+                },
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn atleast() {
+        check(
+            "atLeast(1, Coll(sigmaProp(allOf(Coll(true, false))), sigmaProp(anyOf(Coll(false)))))",
+            expect![[r#"
+                Atleast(
+                    Atleast {
+                        bound: Const(
+                            "1: SInt",
+                        ),
+                        input: Collection(
+                            Exprs {
+                                elem_tpe: SSigmaProp,
+                                items: [
+                                    BoolToSigmaProp(
+                                        BoolToSigmaProp {
+                                            input: And(
+                                                Spanned {
+                                                    source_span: SourceSpan {
+                                                        offset: 0,
+                                                        length: 0,
+                                                    },
+                                                    expr: And {
+                                                        input: Collection(
+                                                            BoolConstants(
+                                                                [
+                                                                    true,
+                                                                    false,
+                                                                ],
+                                                            ),
+                                                        ),
+                                                    },
+                                                },
+                                            ),
+                                        },
+                                    ),
+                                    BoolToSigmaProp(
+                                        BoolToSigmaProp {
+                                            input: Or(
+                                                Spanned {
+                                                    source_span: SourceSpan {
+                                                        offset: 0,
+                                                        length: 0,
+                                                    },
+                                                    expr: Or {
+                                                        input: Collection(
+                                                            BoolConstants(
+                                                                [
+                                                                    false,
+                                                                ],
+                                                            ),
+                                                        ),
+                                                    },
+                                                },
+                                            ),
+                                        },
+                                    ),
+                                ],
+                            },
+                        ),
+                    },
+                )"#]],
+        )
+    }
 }
@@ -90,10 +90,17 @@ impl<'t, 'input> Parser<'t, 'input> {
     }
 
     fn at(&mut self, kind: TokenKind) -> bool {
-        self.expected_kinds.push(kind);
+        if !self.expected_kinds.contains(&kind) {
+            self.expected_kinds.push(kind);
+        }
         self.peek() == Some(kind)
     }
 
+    /// Lookahead at the n-th token (without adding it to the expected kinds)
+    fn nth_at(&mut self, n: usize, kind: TokenKind) -> bool {
+        self.source.peek_nth_kind(n) == Some(kind)
+    }
+
     fn at_set(&mut self, set: &[TokenKind]) -> bool {
         self.peek().map_or(false, |k| set.contains(&k))
     }
@@ -1,5 +1,6 @@
 mod expr;
 mod stmt;
+mod types;
 
 use crate::lexer::TokenKind;
 use crate::parser::Parser;
@@ -11,7 +12,11 @@ pub fn root(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
 
     while !p.at_end() {
-        stmt::stmt(p);
+        if p.at(TokenKind::Semicolon) {
+            p.bump();
+        } else {
+            stmt::stmt(p);
+        }
     }
 
     m.complete(p, SyntaxKind::Root)
@@ -32,4 +37,26 @@ mod tests {
                 Ident@0..6 "HEIGHT""#]],
         );
     }
+
+    #[test]
+    fn parse_statements_separated_by_semicolons() {
+        check(
+            "val a = 1; a",
+            expect![[r#"
+            Root@0..12
+              VariableDef@0..9
+                ValKw@0..3 "val"
+                Whitespace@3..4 " "
+                Ident@4..5 "a"
+                Whitespace@5..6 " "
+                Equals@6..7 "="
+                Whitespace@7..8 " "
+                IntNumber@8..9
+                  IntNumber@8..9 "1"
+              Semicolon@9..10 ";"
+              Whitespace@10..11 " "
+              Ident@11..12
+                Ident@11..12 "a""#]],
+        );
+    }
 }
@@ -63,6 +63,13 @@ fn expr_binding_power(p: &mut Parser, minimum_binding_power: u8) -> Option<Compl
     let mut lhs = lhs(p)?;
 
     loop {
+        // Postfix operators (`.`, call and type arguments) bind tighter than any prefix or
+        // infix operator
+        if p.at(TokenKind::Dot) || p.at(TokenKind::LParen) || p.at(TokenKind::LBracket) {
+            lhs = postfix_expr(p, lhs);
+            continue;
+        }
+
         let op = if p.at(TokenKind::Plus) {
             BinaryOp::Add
         } else if p.at(TokenKind::Minus) {
@@ -71,6 +78,22 @@ fn expr_binding_power(p: &mut Parser, minimum_binding_power: u8) -> Option<Compl
             BinaryOp::Mul
         } else if p.at(TokenKind::Slash) {
             BinaryOp::Div
+        } else if p.at(TokenKind::DoubleEquals) {
+            BinaryOp::Eq
+        } else if p.at(TokenKind::NotEquals) {
+            BinaryOp::NotEq
+        } else if p.at(TokenKind::Less) {
+            BinaryOp::Lt
+        } else if p.at(TokenKind::LessEquals) {
+            BinaryOp::Le
+        } else if p.at(TokenKind::Greater) {
+            BinaryOp::Gt
+        } else if p.at(TokenKind::GreaterEquals) {
+            BinaryOp::Ge
+        } else if p.at(TokenKind::And) {
+            BinaryOp::And
+        } else if p.at(TokenKind::Or) {
+            BinaryOp::Or
         } else {
             // We’re not at an operator; we don’t know what to do next, so we return and let the
             // caller decide.
@@ -108,15 +131,27 @@ fn lhs(p: &mut Parser) -> Option<CompletedMarker> {
         int_number(p)
     } else if p.at(TokenKind::LongNumber) {
         long_number(p)
+    } else if p.at(TokenKind::TrueKw) || p.at(TokenKind::FalseKw) {
+        bool_literal(p)
+    } else if p.at(TokenKind::StringLiteral) {
+        string_literal(p)
     } else if p.at(TokenKind::Ident) {
         ident(p)
         // variable_ref(p)
         // } else if p.at(TokenKind::ValKw) {
         //     variable_ref(p)
-    } else if p.at(TokenKind::Minus) {
+    } else if p.at(TokenKind::Minus) || p.at(TokenKind::Bang) {
         prefix_expr(p)
+    } else if p.at(TokenKind::IfKw) {
+        if_expr(p)
     } else if p.at(TokenKind::LParen) {
-        paren_expr(p)
+        if at_lambda_params(p) {
+            lambda_expr(p)
+        } else {
+            paren_expr(p)
+        }
+    } else if p.at(TokenKind::LBrace) {
+        block_expr(p)
     } else {
         p.error();
         return None;
@@ -130,25 +165,38 @@ enum BinaryOp {
     Sub,
     Mul,
     Div,
+    Eq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
 }
 
 impl BinaryOp {
     fn binding_power(&self) -> (u8, u8) {
         match self {
-            Self::Add | Self::Sub => (1, 2),
-            Self::Mul | Self::Div => (3, 4),
+            Self::Or => (1, 2),
+            Self::And => (3, 4),
+            Self::Eq | Self::NotEq => (5, 6),
+            Self::Lt | Self::Le | Self::Gt | Self::Ge => (7, 8),
+            Self::Add | Self::Sub => (9, 10),
+            Self::Mul | Self::Div => (11, 12),
         }
     }
 }
 
 enum UnaryOp {
     Neg,
+    Not,
 }
 
 impl UnaryOp {
     fn binding_power(&self) -> ((), u8) {
         match self {
-            Self::Neg => ((), 5),
+            Self::Neg | Self::Not => ((), 13),
         }
     }
 }
@@ -167,6 +215,25 @@ fn long_number(p: &mut Parser) -> CompletedMarker {
     m.complete(p, SyntaxKind::LongNumber)
 }
 
+fn bool_literal(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::TrueKw) || p.at(TokenKind::FalseKw));
+    let kind = if p.at(TokenKind::TrueKw) {
+        SyntaxKind::TrueKw
+    } else {
+        SyntaxKind::FalseKw
+    };
+    let m = p.start();
+    p.bump();
+    m.complete(p, kind)
+}
+
+fn string_literal(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::StringLiteral));
+    let m = p.start();
+    p.bump();
+    m.complete(p, SyntaxKind::StringLiteral)
+}
+
 // fn variable_ref(p: &mut Parser) -> CompletedMarker {
 //     assert!(p.at(TokenKind::Ident));
 
@@ -184,11 +251,15 @@ fn ident(p: &mut Parser) -> CompletedMarker {
 }
 
 fn prefix_expr(p: &mut Parser) -> CompletedMarker {
-    assert!(p.at(TokenKind::Minus));
+    assert!(p.at(TokenKind::Minus) || p.at(TokenKind::Bang));
 
     let m = p.start();
 
-    let op = UnaryOp::Neg;
+    let op = if p.at(TokenKind::Minus) {
+        UnaryOp::Neg
+    } else {
+        UnaryOp::Not
+    };
     let ((), right_binding_power) = op.binding_power();
 
     // Eat the operator’s token.
@@ -199,15 +270,148 @@ fn prefix_expr(p: &mut Parser) -> CompletedMarker {
     m.complete(p, SyntaxKind::PrefixExpr)
 }
 
+/// `if (cond) expr else expr`
+fn if_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::IfKw));
+
+    let m = p.start();
+    p.bump();
+    p.expect(TokenKind::LParen);
+    expr_binding_power(p, 0);
+    p.expect(TokenKind::RParen);
+    expr_binding_power(p, 0);
+    p.expect(TokenKind::ElseKw);
+    expr_binding_power(p, 0);
+
+    m.complete(p, SyntaxKind::IfExpr)
+}
+
+/// Expression in parentheses `(a)`, or a tuple literal `(a, b, ...)`
 fn paren_expr(p: &mut Parser) -> CompletedMarker {
     assert!(p.at(TokenKind::LParen));
 
     let m = p.start();
     p.bump();
     expr_binding_power(p, 0);
+    if !p.at(TokenKind::Comma) {
+        p.expect(TokenKind::RParen);
+        return m.complete(p, SyntaxKind::ParenExpr);
+    }
+    while p.at(TokenKind::Comma) {
+        p.bump();
+        expr_binding_power(p, 0);
+    }
+    p.expect(TokenKind::RParen);
+
+    m.complete(p, SyntaxKind::TupleExpr)
+}
+
+/// Field/method selection `obj.name`, application `f(a, b)` or type arguments `e[T]`
+fn postfix_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
+    let m = lhs.precede(p);
+    if p.at(TokenKind::Dot) {
+        p.bump();
+        p.expect(TokenKind::Ident);
+        m.complete(p, SyntaxKind::SelectExpr)
+    } else if p.at(TokenKind::LParen) {
+        arg_list(p);
+        m.complete(p, SyntaxKind::CallExpr)
+    } else {
+        types::type_arg_list(p);
+        m.complete(p, SyntaxKind::ApplyTypesExpr)
+    }
+}
+
+fn arg_list(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::LParen));
+
+    let m = p.start();
+    p.bump();
+    while !p.at(TokenKind::RParen) && !p.at_end() {
+        expr_binding_power(p, 0);
+        if !p.at(TokenKind::RParen) && !p.at_end() {
+            p.expect(TokenKind::Comma);
+        }
+    }
     p.expect(TokenKind::RParen);
 
-    m.complete(p, SyntaxKind::ParenExpr)
+    m.complete(p, SyntaxKind::ArgList)
+}
+
+/// Lookahead for the lambda parameters, i.e. `(x: Int` or `()` followed by `=>`
+fn at_lambda_params(p: &mut Parser) -> bool {
+    p.nth_at(0, TokenKind::LParen)
+        && ((p.nth_at(1, TokenKind::Ident) && p.nth_at(2, TokenKind::Colon))
+            || (p.nth_at(1, TokenKind::RParen) && p.nth_at(2, TokenKind::FatArrow)))
+}
+
+/// Block `{ val a = ...; ...; result }`, or a lambda in braces `{ (a: Int) => ... }` (the
+/// lambda body extends to the closing brace)
+fn block_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::LBrace));
+
+    let m = p.start();
+    p.bump();
+    if at_lambda_params(p) {
+        let lambda = p.start();
+        param_list(p);
+        p.expect(TokenKind::FatArrow);
+        let body = p.start();
+        block_stmts(p);
+        body.complete(p, SyntaxKind::BlockExpr);
+        lambda.complete(p, SyntaxKind::LambdaExpr);
+    } else {
+        block_stmts(p);
+    }
+    p.expect(TokenKind::RBrace);
+
+    m.complete(p, SyntaxKind::BlockExpr)
+}
+
+fn block_stmts(p: &mut Parser) {
+    while !p.at(TokenKind::RBrace) && !p.at_end() {
+        if p.at(TokenKind::Semicolon) {
+            p.bump();
+        } else {
+            stmt::stmt(p);
+        }
+    }
+}
+
+/// Lambda `(a: Int, b: Int) => expr`
+fn lambda_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::LParen));
+
+    let m = p.start();
+    param_list(p);
+    p.expect(TokenKind::FatArrow);
+    expr_binding_power(p, 0);
+
+    m.complete(p, SyntaxKind::LambdaExpr)
+}
+
+fn param_list(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::LParen));
+
+    let m = p.start();
+    p.bump();
+    while !p.at(TokenKind::RParen) && !p.at_end() {
+        param(p);
+        if !p.at(TokenKind::RParen) && !p.at_end() {
+            p.expect(TokenKind::Comma);
+        }
+    }
+    p.expect(TokenKind::RParen);
+
+    m.complete(p, SyntaxKind::ParamList)
+}
+
+fn param(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    p.expect(TokenKind::Ident);
+    p.expect(TokenKind::Colon);
+    types::type_ref(p);
+    m.complete(p, SyntaxKind::Param)
 }
 
 #[cfg(test)]
@@ -387,8 +591,8 @@ mod tests {
                       IntNumber@1..2
                         IntNumber@1..2 "2"
                       Plus@2..3 "+"
-                error: expected number, number, identifier, ‘-’ or ‘(’
-                error: expected ‘)’"#]],
+                error: expected number, number, ‘true’, ‘false’, string, identifier, ‘-’, ‘!’, ‘if’, ‘(’ or ‘{’
+                error: expected ‘,’ or ‘)’"#]],
         );
     }
 
@@ -472,4 +676,516 @@ mod tests {
                       RParen@6..7 ")""#]],
         );
     }
+
+    #[test]
+    fn parse_block() {
+        check(
+            "{ val a = 1; a + 2 }",
+            expect![[r#"
+                Root@0..20
+                  BlockExpr@0..20
+                    LBrace@0..1 "{"
+                    Whitespace@1..2 " "
+                    VariableDef@2..11
+                      ValKw@2..5 "val"
+                      Whitespace@5..6 " "
+                      Ident@6..7 "a"
+                      Whitespace@7..8 " "
+                      Equals@8..9 "="
+                      Whitespace@9..10 " "
+                      IntNumber@10..11
+                        IntNumber@10..11 "1"
+                    Semicolon@11..12 ";"
+                    Whitespace@12..13 " "
+                    InfixExpr@13..19
+                      Ident@13..15
+                        Ident@13..14 "a"
+                        Whitespace@14..15 " "
+                      Plus@15..16 "+"
+                      Whitespace@16..17 " "
+                      IntNumber@17..19
+                        IntNumber@17..18 "2"
+                        Whitespace@18..19 " "
+                    RBrace@19..20 "}""#]],
+        );
+    }
+
+    #[test]
+    fn parse_multiline_block() {
+        check(
+            "{\n  val a = 1\n  val b = a\n  b\n}",
+            expect![[r#"
+                Root@0..31
+                  BlockExpr@0..31
+                    LBrace@0..1 "{"
+                    Whitespace@1..4 "\n  "
+                    VariableDef@4..16
+                      ValKw@4..7 "val"
+                      Whitespace@7..8 " "
+                      Ident@8..9 "a"
+                      Whitespace@9..10 " "
+                      Equals@10..11 "="
+                      Whitespace@11..12 " "
+                      IntNumber@12..16
+                        IntNumber@12..13 "1"
+                        Whitespace@13..16 "\n  "
+                    VariableDef@16..28
+                      ValKw@16..19 "val"
+                      Whitespace@19..20 " "
+                      Ident@20..21 "b"
+                      Whitespace@21..22 " "
+                      Equals@22..23 "="
+                      Whitespace@23..24 " "
+                      Ident@24..28
+                        Ident@24..25 "a"
+                        Whitespace@25..28 "\n  "
+                    Ident@28..30
+                      Ident@28..29 "b"
+                      Whitespace@29..30 "\n"
+                    RBrace@30..31 "}""#]],
+        );
+    }
+
+    #[test]
+    fn parse_unclosed_block() {
+        check(
+            "{ val a = 1",
+            expect![[r#"
+                Root@0..11
+                  BlockExpr@0..11
+                    LBrace@0..1 "{"
+                    Whitespace@1..2 " "
+                    VariableDef@2..11
+                      ValKw@2..5 "val"
+                      Whitespace@5..6 " "
+                      Ident@6..7 "a"
+                      Whitespace@7..8 " "
+                      Equals@8..9 "="
+                      Whitespace@9..10 " "
+                      IntNumber@10..11
+                        IntNumber@10..11 "1"
+                error: expected ‘.’, ‘(’, ‘[’, ‘+’, ‘-’, ‘*’, ‘/’, ‘==’, ‘!=’, ‘<’, ‘<=’, ‘>’, ‘>=’, ‘&&’, ‘||’ or ‘}’"#]],
+        );
+    }
+
+    #[test]
+    fn parse_lambda() {
+        check(
+            "(a: Int, b: Int) => a + b",
+            expect![[r#"
+            Root@0..25
+              LambdaExpr@0..25
+                ParamList@0..17
+                  LParen@0..1 "("
+                  Param@1..7
+                    Ident@1..2 "a"
+                    Colon@2..3 ":"
+                    Whitespace@3..4 " "
+                    TypeRef@4..7
+                      Ident@4..7 "Int"
+                  Comma@7..8 ","
+                  Whitespace@8..9 " "
+                  Param@9..15
+                    Ident@9..10 "b"
+                    Colon@10..11 ":"
+                    Whitespace@11..12 " "
+                    TypeRef@12..15
+                      Ident@12..15 "Int"
+                  RParen@15..16 ")"
+                  Whitespace@16..17 " "
+                FatArrow@17..19 "=>"
+                Whitespace@19..20 " "
+                InfixExpr@20..25
+                  Ident@20..22
+                    Ident@20..21 "a"
+                    Whitespace@21..22 " "
+                  Plus@22..23 "+"
+                  Whitespace@23..24 " "
+                  Ident@24..25
+                    Ident@24..25 "b""#]],
+        );
+    }
+
+    #[test]
+    fn parse_lambda_without_params() {
+        check(
+            "() => 1",
+            expect![[r#"
+            Root@0..7
+              LambdaExpr@0..7
+                ParamList@0..3
+                  LParen@0..1 "("
+                  RParen@1..2 ")"
+                  Whitespace@2..3 " "
+                FatArrow@3..5 "=>"
+                Whitespace@5..6 " "
+                IntNumber@6..7
+                  IntNumber@6..7 "1""#]],
+        );
+    }
+
+    #[test]
+    fn parse_lambda_in_braces() {
+        check(
+            "{ (b: Box) => val a = 1; a }",
+            expect![[r#"
+                Root@0..28
+                  BlockExpr@0..28
+                    LBrace@0..1 "{"
+                    Whitespace@1..2 " "
+                    LambdaExpr@2..27
+                      ParamList@2..11
+                        LParen@2..3 "("
+                        Param@3..9
+                          Ident@3..4 "b"
+                          Colon@4..5 ":"
+                          Whitespace@5..6 " "
+                          TypeRef@6..9
+                            Ident@6..9 "Box"
+                        RParen@9..10 ")"
+                        Whitespace@10..11 " "
+                      FatArrow@11..13 "=>"
+                      Whitespace@13..14 " "
+                      BlockExpr@14..27
+                        VariableDef@14..23
+                          ValKw@14..17 "val"
+                          Whitespace@17..18 " "
+                          Ident@18..19 "a"
+                          Whitespace@19..20 " "
+                          Equals@20..21 "="
+                          Whitespace@21..22 " "
+                          IntNumber@22..23
+                            IntNumber@22..23 "1"
+                        Semicolon@23..24 ";"
+                        Whitespace@24..25 " "
+                        Ident@25..27
+                          Ident@25..26 "a"
+                          Whitespace@26..27 " "
+                    RBrace@27..28 "}""#]],
+        );
+    }
+
+    #[test]
+    fn parse_lambda_missing_param_type() {
+        check(
+            "(a: ) => a",
+            expect![[r#"
+                Root@0..10
+                  LambdaExpr@0..10
+                    ParamList@0..10
+                      LParen@0..1 "("
+                      Param@1..6
+                        Ident@1..2 "a"
+                        Colon@2..3 ":"
+                        Whitespace@3..4 " "
+                        TypeRef@4..6
+                          Error@4..6
+                            RParen@4..5 ")"
+                            Whitespace@5..6 " "
+                      Error@6..9
+                        FatArrow@6..8 "=>"
+                        Whitespace@8..9 " "
+                      Param@9..10
+                        Ident@9..10 "a"
+                        TypeRef@10..10
+                error: expected ‘(’ or identifier, but found ‘)’
+                error: expected ‘[’, ‘)’ or ‘,’, but found ‘=>’
+                error: expected ‘:’
+                error: expected ‘(’ or identifier
+                error: expected ‘[’ or ‘)’
+                error: expected ‘=>’
+                error: expected number, number, ‘true’, ‘false’, string, identifier, ‘-’, ‘!’, ‘if’, ‘(’ or ‘{’"#]],
+        );
+    }
+
+    #[test]
+    fn parse_select() {
+        check(
+            "SELF.value",
+            expect![[r#"
+            Root@0..10
+              SelectExpr@0..10
+                Ident@0..4
+                  Ident@0..4 "SELF"
+                Dot@4..5 "."
+                Ident@5..10 "value""#]],
+        );
+    }
+
+    #[test]
+    fn parse_call() {
+        check(
+            "f(1, a)",
+            expect![[r#"
+            Root@0..7
+              CallExpr@0..7
+                Ident@0..1
+                  Ident@0..1 "f"
+                ArgList@1..7
+                  LParen@1..2 "("
+                  IntNumber@2..3
+                    IntNumber@2..3 "1"
+                  Comma@3..4 ","
+                  Whitespace@4..5 " "
+                  Ident@5..6
+                    Ident@5..6 "a"
+                  RParen@6..7 ")""#]],
+        );
+    }
+
+    #[test]
+    fn parse_postfix_chain() {
+        check(
+            "OUTPUTS(0).R4[Coll[Byte]].get",
+            expect![[r#"
+            Root@0..29
+              SelectExpr@0..29
+                ApplyTypesExpr@0..25
+                  SelectExpr@0..13
+                    CallExpr@0..10
+                      Ident@0..7
+                        Ident@0..7 "OUTPUTS"
+                      ArgList@7..10
+                        LParen@7..8 "("
+                        IntNumber@8..9
+                          IntNumber@8..9 "0"
+                        RParen@9..10 ")"
+                    Dot@10..11 "."
+                    Ident@11..13 "R4"
+                  TypeArgList@13..25
+                    LBracket@13..14 "["
+                    TypeRef@14..24
+                      Ident@14..18 "Coll"
+                      TypeArgList@18..24
+                        LBracket@18..19 "["
+                        TypeRef@19..23
+                          Ident@19..23 "Byte"
+                        RBracket@23..24 "]"
+                    RBracket@24..25 "]"
+                Dot@25..26 "."
+                Ident@26..29 "get""#]],
+        );
+    }
+
+    #[test]
+    fn parse_postfix_binds_tighter_than_prefix_and_infix() {
+        check(
+            "-a.b * c(1)",
+            expect![[r#"
+            Root@0..11
+              InfixExpr@0..11
+                PrefixExpr@0..5
+                  Minus@0..1 "-"
+                  SelectExpr@1..5
+                    Ident@1..2
+                      Ident@1..2 "a"
+                    Dot@2..3 "."
+                    Ident@3..4 "b"
+                    Whitespace@4..5 " "
+                Star@5..6 "*"
+                Whitespace@6..7 " "
+                CallExpr@7..11
+                  Ident@7..8
+                    Ident@7..8 "c"
+                  ArgList@8..11
+                    LParen@8..9 "("
+                    IntNumber@9..10
+                      IntNumber@9..10 "1"
+                    RParen@10..11 ")""#]],
+        );
+    }
+
+    #[test]
+    fn parse_select_missing_name() {
+        check(
+            "SELF.",
+            expect![[r#"
+            Root@0..5
+              SelectExpr@0..5
+                Ident@0..4
+                  Ident@0..4 "SELF"
+                Dot@4..5 "."
+            error: expected identifier"#]],
+        );
+    }
+
+    #[test]
+    fn parse_unclosed_call() {
+        check(
+            "f(1",
+            expect![[r#"
+                Root@0..3
+                  CallExpr@0..3
+                    Ident@0..1
+                      Ident@0..1 "f"
+                    ArgList@1..3
+                      LParen@1..2 "("
+                      IntNumber@2..3
+                        IntNumber@2..3 "1"
+                error: expected ‘.’, ‘(’, ‘[’, ‘+’, ‘-’, ‘*’, ‘/’, ‘==’, ‘!=’, ‘<’, ‘<=’, ‘>’, ‘>=’, ‘&&’, ‘||’ or ‘)’"#]],
+        );
+    }
+
+    #[test]
+    fn parse_logical_and_comparison_precedence() {
+        check(
+            "a || b && c == d + 1 < e",
+            expect![[r#"
+            Root@0..24
+              InfixExpr@0..24
+                Ident@0..2
+                  Ident@0..1 "a"
+                  Whitespace@1..2 " "
+                Or@2..4 "||"
+                Whitespace@4..5 " "
+                InfixExpr@5..24
+                  Ident@5..7
+                    Ident@5..6 "b"
+                    Whitespace@6..7 " "
+                  And@7..9 "&&"
+                  Whitespace@9..10 " "
+                  InfixExpr@10..24
+                    Ident@10..12
+                      Ident@10..11 "c"
+                      Whitespace@11..12 " "
+                    DoubleEquals@12..14 "=="
+                    Whitespace@14..15 " "
+                    InfixExpr@15..24
+                      InfixExpr@15..21
+                        Ident@15..17
+                          Ident@15..16 "d"
+                          Whitespace@16..17 " "
+                        Plus@17..18 "+"
+                        Whitespace@18..19 " "
+                        IntNumber@19..21
+                          IntNumber@19..20 "1"
+                          Whitespace@20..21 " "
+                      Less@21..22 "<"
+                      Whitespace@22..23 " "
+                      Ident@23..24
+                        Ident@23..24 "e""#]],
+        );
+    }
+
+    #[test]
+    fn parse_not() {
+        check(
+            "!true",
+            expect![[r#"
+            Root@0..5
+              PrefixExpr@0..5
+                Bang@0..1 "!"
+                TrueKw@1..5
+                  TrueKw@1..5 "true""#]],
+        );
+    }
+
+    #[test]
+    fn parse_if_else() {
+        check(
+            "if (a > 1) a else -a",
+            expect![[r#"
+            Root@0..20
+              IfExpr@0..20
+                IfKw@0..2 "if"
+                Whitespace@2..3 " "
+                LParen@3..4 "("
+                InfixExpr@4..9
+                  Ident@4..6
+                    Ident@4..5 "a"
+                    Whitespace@5..6 " "
+                  Greater@6..7 ">"
+                  Whitespace@7..8 " "
+                  IntNumber@8..9
+                    IntNumber@8..9 "1"
+                RParen@9..10 ")"
+                Whitespace@10..11 " "
+                Ident@11..13
+                  Ident@11..12 "a"
+                  Whitespace@12..13 " "
+                ElseKw@13..17 "else"
+                Whitespace@17..18 " "
+                PrefixExpr@18..20
+                  Minus@18..19 "-"
+                  Ident@19..20
+                    Ident@19..20 "a""#]],
+        );
+    }
+
+    #[test]
+    fn parse_if_without_else() {
+        check(
+            "if (a) 1",
+            expect![[r#"
+            Root@0..8
+              IfExpr@0..8
+                IfKw@0..2 "if"
+                Whitespace@2..3 " "
+                LParen@3..4 "("
+                Ident@4..5
+                  Ident@4..5 "a"
+                RParen@5..6 ")"
+                Whitespace@6..7 " "
+                IntNumber@7..8
+                  IntNumber@7..8 "1"
+            error: expected ‘.’, ‘(’, ‘[’, ‘+’, ‘-’, ‘*’, ‘/’, ‘==’, ‘!=’, ‘<’, ‘<=’, ‘>’, ‘>=’, ‘&&’, ‘||’ or ‘else’
+            error: expected number, number, ‘true’, ‘false’, string, identifier, ‘-’, ‘!’, ‘if’, ‘(’ or ‘{’"#]],
+        );
+    }
+
+    #[test]
+    fn parse_string_literal() {
+        check(
+            r#"PK("9f4QF8AD1nQ3nJahQVkMj8hFSVVzVom77b52JU7EW71Zexg6N8v")"#,
+            expect![[r#"
+            Root@0..57
+              CallExpr@0..57
+                Ident@0..2
+                  Ident@0..2 "PK"
+                ArgList@2..57
+                  LParen@2..3 "("
+                  StringLiteral@3..56
+                    StringLiteral@3..56 "\"9f4QF8AD1nQ3nJahQVkM ..."
+                  RParen@56..57 ")""#]],
+        );
+    }
+
+    #[test]
+    fn parse_tuple() {
+        check(
+            "(1, a)._2",
+            expect![[r#"
+            Root@0..9
+              SelectExpr@0..9
+                TupleExpr@0..6
+                  LParen@0..1 "("
+                  IntNumber@1..2
+                    IntNumber@1..2 "1"
+                  Comma@2..3 ","
+                  Whitespace@3..4 " "
+                  Ident@4..5
+                    Ident@4..5 "a"
+                  RParen@5..6 ")"
+                Dot@6..7 "."
+                Ident@7..9 "_2""#]],
+        );
+    }
+
+    #[test]
+    fn parse_unclosed_tuple() {
+        check(
+            "(1, 2",
+            expect![[r#"
+            Root@0..5
+              TupleExpr@0..5
+                LParen@0..1 "("
+                IntNumber@1..2
+                  IntNumber@1..2 "1"
+                Comma@2..3 ","
+                Whitespace@3..4 " "
+                IntNumber@4..5
+                  IntNumber@4..5 "2"
+            error: expected ‘.’, ‘(’, ‘[’, ‘+’, ‘-’, ‘*’, ‘/’, ‘==’, ‘!=’, ‘<’, ‘<=’, ‘>’, ‘>=’, ‘&&’, ‘||’, ‘,’ or ‘)’"#]],
+        );
+    }
 }
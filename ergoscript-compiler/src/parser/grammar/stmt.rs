@@ -14,6 +14,10 @@ fn variable_def(p: &mut Parser) -> CompletedMarker {
     p.bump();
 
     p.expect(TokenKind::Ident);
+    if p.at(TokenKind::Colon) {
+        p.bump();
+        types::type_ref(p);
+    }
     p.expect(TokenKind::Equals);
 
     expr::expr(p);
@@ -43,4 +47,26 @@ mod tests {
                   Ident@10..13 "bar""#]],
         );
     }
+
+    #[test]
+    fn parse_variable_definition_with_type() {
+        check(
+            "val foo: Int = 1",
+            expect![[r#"
+                Root@0..16
+                  VariableDef@0..16
+                    ValKw@0..3 "val"
+                    Whitespace@3..4 " "
+                    Ident@4..7 "foo"
+                    Colon@7..8 ":"
+                    Whitespace@8..9 " "
+                    TypeRef@9..13
+                      Ident@9..12 "Int"
+                      Whitespace@12..13 " "
+                    Equals@13..14 "="
+                    Whitespace@14..15 " "
+                    IntNumber@15..16
+                      IntNumber@15..16 "1""#]],
+        );
+    }
 }
@@ -0,0 +1,136 @@
+use super::*;
+
+/// Type reference, i.e. `Int`, `Coll[Byte]` or `(Int, Long)`
+pub(super) fn type_ref(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    if p.at(TokenKind::LParen) {
+        p.bump();
+        type_ref(p);
+        while p.at(TokenKind::Comma) {
+            p.bump();
+            type_ref(p);
+        }
+        p.expect(TokenKind::RParen);
+    } else {
+        p.expect(TokenKind::Ident);
+        if p.at(TokenKind::LBracket) {
+            type_arg_list(p);
+        }
+    }
+    m.complete(p, SyntaxKind::TypeRef)
+}
+
+pub(super) fn type_arg_list(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(TokenKind::LBracket));
+    let m = p.start();
+    p.bump();
+    type_ref(p);
+    while p.at(TokenKind::Comma) {
+        p.bump();
+        type_ref(p);
+    }
+    p.expect(TokenKind::RBracket);
+    m.complete(p, SyntaxKind::TypeArgList)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::check;
+    use expect_test::expect;
+
+    #[test]
+    fn parse_tuple_type() {
+        check(
+            "val a: (Coll[Byte], Long) = b",
+            expect![[r#"
+            Root@0..29
+              VariableDef@0..29
+                ValKw@0..3 "val"
+                Whitespace@3..4 " "
+                Ident@4..5 "a"
+                Colon@5..6 ":"
+                Whitespace@6..7 " "
+                TypeRef@7..26
+                  LParen@7..8 "("
+                  TypeRef@8..18
+                    Ident@8..12 "Coll"
+                    TypeArgList@12..18
+                      LBracket@12..13 "["
+                      TypeRef@13..17
+                        Ident@13..17 "Byte"
+                      RBracket@17..18 "]"
+                  Comma@18..19 ","
+                  Whitespace@19..20 " "
+                  TypeRef@20..24
+                    Ident@20..24 "Long"
+                  RParen@24..25 ")"
+                  Whitespace@25..26 " "
+                Equals@26..27 "="
+                Whitespace@27..28 " "
+                Ident@28..29
+                  Ident@28..29 "b""#]],
+        );
+    }
+
+    #[test]
+    fn parse_generic_type() {
+        check(
+            "val a: Coll[Option[Int]] = b",
+            expect![[r#"
+                Root@0..28
+                  VariableDef@0..28
+                    ValKw@0..3 "val"
+                    Whitespace@3..4 " "
+                    Ident@4..5 "a"
+                    Colon@5..6 ":"
+                    Whitespace@6..7 " "
+                    TypeRef@7..25
+                      Ident@7..11 "Coll"
+                      TypeArgList@11..25
+                        LBracket@11..12 "["
+                        TypeRef@12..23
+                          Ident@12..18 "Option"
+                          TypeArgList@18..23
+                            LBracket@18..19 "["
+                            TypeRef@19..22
+                              Ident@19..22 "Int"
+                            RBracket@22..23 "]"
+                        RBracket@23..24 "]"
+                        Whitespace@24..25 " "
+                    Equals@25..26 "="
+                    Whitespace@26..27 " "
+                    Ident@27..28
+                      Ident@27..28 "b""#]],
+        );
+    }
+
+    #[test]
+    fn parse_unclosed_type_args() {
+        check(
+            "val a: Coll[Int = b",
+            expect![[r#"
+                Root@0..19
+                  VariableDef@0..19
+                    ValKw@0..3 "val"
+                    Whitespace@3..4 " "
+                    Ident@4..5 "a"
+                    Colon@5..6 ":"
+                    Whitespace@6..7 " "
+                    TypeRef@7..18
+                      Ident@7..11 "Coll"
+                      TypeArgList@11..18
+                        LBracket@11..12 "["
+                        TypeRef@12..16
+                          Ident@12..15 "Int"
+                          Whitespace@15..16 " "
+                        Error@16..18
+                          Equals@16..17 "="
+                          Whitespace@17..18 " "
+                    Error@18..19
+                      Ident@18..19 "b"
+                error: expected ‘[’, ‘,’ or ‘]’, but found ‘=’
+                error: expected ‘=’, but found identifier
+                error: expected number, number, ‘true’, ‘false’, string, identifier, ‘-’, ‘!’, ‘if’, ‘(’ or ‘{’"#]],
+        );
+    }
+}
@@ -1,7 +1,10 @@
+use miette::Diagnostic;
+use miette::LabeledSpan;
 use std::fmt;
 use text_size::TextRange;
 
-use crate::error::pretty_error_desc;
+use crate::error::labeled_spans;
+use crate::error::ErrorKind;
 use crate::lexer::TokenKind;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -11,9 +14,15 @@ pub struct ParseError {
     pub span: TextRange,
 }
 
-impl ParseError {
-    pub fn pretty_desc(&self, source: &str) -> String {
-        pretty_error_desc(source, self.span, &self.to_string())
+impl std::error::Error for ParseError {}
+
+impl Diagnostic for ParseError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new(ErrorKind::UnexpectedToken.code()))
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        Some(labeled_spans(self.span, &[]))
     }
 }
 
@@ -27,6 +27,16 @@ impl<'t, 'input> Source<'t, 'input> {
         self.peek_kind_raw()
     }
 
+    /// Kind of the n-th (zero-based) non-trivia token ahead
+    pub fn peek_nth_kind(&mut self, n: usize) -> Option<TokenKind> {
+        self.eat_trivia();
+        self.tokens[self.cursor..]
+            .iter()
+            .map(|Token { kind, .. }| *kind)
+            .filter(|kind| !kind.is_trivia())
+            .nth(n)
+    }
+
     pub fn peek_token(&mut self) -> Option<&Token> {
         self.eat_trivia();
         self.peek_token_raw()
@@ -23,4 +23,21 @@ impl ScriptEnv {
     pub fn get(&self, ident: &str) -> Option<&Constant> {
         self.0.get(ident)
     }
+
+    /// Adds the value([`Constant`]) for the given identifier
+    pub fn insert(&mut self, ident: String, value: Constant) {
+        self.0.insert(ident, value);
+    }
+
+    /// Template parameters (identifiers starting with `$`) with their values, sorted by name
+    pub fn template_params(&self) -> Vec<(&str, &Constant)> {
+        let mut params: Vec<(&str, &Constant)> = self
+            .0
+            .iter()
+            .filter(|(ident, _)| ident.starts_with('$'))
+            .map(|(ident, value)| (ident.as_str(), value))
+            .collect();
+        params.sort_by_key(|(ident, _)| *ident);
+        params
+    }
 }
@@ -9,19 +9,39 @@ pub enum SyntaxKind {
     Whitespace,
     FnKw,
     ValKw,
+    IfKw,
+    ElseKw,
+    TrueKw,
+    FalseKw,
     Ident,
     IntNumber,
     LongNumber,
+    StringLiteral,
     Plus,
     Minus,
     Star,
     Slash,
     And,
+    Or,
+    Bang,
     Equals,
+    DoubleEquals,
+    NotEquals,
+    Less,
+    LessEquals,
+    Greater,
+    GreaterEquals,
+    FatArrow,
+    Colon,
+    Comma,
+    Semicolon,
+    Dot,
     LParen,
     RParen,
     LBrace,
     RBrace,
+    LBracket,
+    RBracket,
     Comment,
     Error,
     Root,
@@ -29,6 +49,18 @@ pub enum SyntaxKind {
     ParenExpr,
     PrefixExpr,
     VariableDef,
+    BlockExpr,
+    LambdaExpr,
+    ParamList,
+    Param,
+    TypeRef,
+    TypeArgList,
+    SelectExpr,
+    CallExpr,
+    ArgList,
+    ApplyTypesExpr,
+    IfExpr,
+    TupleExpr,
 }
 
 impl From<TokenKind> for SyntaxKind {
@@ -37,21 +69,41 @@ impl From<TokenKind> for SyntaxKind {
             TokenKind::Whitespace => Self::Whitespace,
             TokenKind::FnKw => Self::FnKw,
             TokenKind::ValKw => Self::ValKw,
+            TokenKind::IfKw => Self::IfKw,
+            TokenKind::ElseKw => Self::ElseKw,
+            TokenKind::TrueKw => Self::TrueKw,
+            TokenKind::FalseKw => Self::FalseKw,
             TokenKind::Ident => Self::Ident,
             TokenKind::IntNumber => Self::IntNumber,
             TokenKind::LongNumber => Self::LongNumber,
+            TokenKind::StringLiteral => Self::StringLiteral,
             TokenKind::Plus => Self::Plus,
             TokenKind::Minus => Self::Minus,
             TokenKind::Star => Self::Star,
             TokenKind::Slash => Self::Slash,
             TokenKind::Equals => Self::Equals,
+            TokenKind::DoubleEquals => Self::DoubleEquals,
+            TokenKind::NotEquals => Self::NotEquals,
+            TokenKind::Less => Self::Less,
+            TokenKind::LessEquals => Self::LessEquals,
+            TokenKind::Greater => Self::Greater,
+            TokenKind::GreaterEquals => Self::GreaterEquals,
+            TokenKind::FatArrow => Self::FatArrow,
+            TokenKind::Colon => Self::Colon,
+            TokenKind::Comma => Self::Comma,
+            TokenKind::Semicolon => Self::Semicolon,
+            TokenKind::Dot => Self::Dot,
             TokenKind::LParen => Self::LParen,
             TokenKind::RParen => Self::RParen,
             TokenKind::LBrace => Self::LBrace,
             TokenKind::RBrace => Self::RBrace,
+            TokenKind::LBracket => Self::LBracket,
+            TokenKind::RBracket => Self::RBracket,
             TokenKind::Comment => Self::Comment,
             TokenKind::Error => Self::Error,
             TokenKind::And => Self::And,
+            TokenKind::Or => Self::Or,
+            TokenKind::Bang => Self::Bang,
         }
     }
 }
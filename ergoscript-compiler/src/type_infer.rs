@@ -1,50 +1,935 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use ergotree_ir::types::sfunc::SFunc;
+use ergotree_ir::types::smethod::SMethod;
+use ergotree_ir::types::stuple::STuple;
+use ergotree_ir::types::stype::SType;
+use ergotree_ir::types::stype_companion::STypeCompanion;
+use ergotree_ir::types::stype_param::STypeVar;
+use ergotree_ir::types::type_unify::unify_many;
 use rowan::TextRange;
+use thiserror::Error;
 
-use crate::error::pretty_error_desc;
-use crate::hir;
+use crate::error::all;
+use crate::error::both;
+use crate::error::impl_diagnostic;
+use crate::error::ErrorKind;
+use crate::error::Label;
+use crate::hir::Apply;
+use crate::hir::ApplyTypes;
 use crate::hir::Binary;
+use crate::hir::BinaryOp;
+use crate::hir::Block;
+use crate::hir::Builtin;
+use crate::hir::BuiltinOp;
 use crate::hir::Expr;
 use crate::hir::ExprKind;
+use crate::hir::If;
+use crate::hir::Lambda;
+use crate::hir::Literal;
+use crate::hir::Select;
+use crate::hir::Spanned;
+use crate::hir::Tuple;
+use crate::hir::Unary;
+use crate::hir::UnaryOp;
+use crate::hir::Val;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Error)]
+#[error("{msg}")]
 pub struct TypeInferenceError {
+    kind: ErrorKind,
     msg: String,
     span: TextRange,
+    labels: Vec<Label>,
 }
 
 impl TypeInferenceError {
-    pub fn new(msg: String, span: TextRange) -> Self {
-        Self { msg, span }
-    }
-
-    pub fn pretty_desc(&self, source: &str) -> String {
-        pretty_error_desc(source, self.span, &self.msg)
-    }
-}
-
-pub fn assign_type(expr: Expr) -> Result<Expr, TypeInferenceError> {
-    hir::rewrite(expr, |e| {
-        Ok(match &e.kind {
-            ExprKind::Binary(Binary { op, lhs, rhs }) => match op.node {
-                hir::BinaryOp::Plus => {
-                    let l = assign_type(*lhs.clone())?;
-                    let r = assign_type(*rhs.clone())?;
-                    let tpe = l.tpe.clone();
-                    Some(Expr {
-                        kind: Binary {
-                            op: op.clone(),
-                            lhs: l.into(),
-                            rhs: r.into(),
-                        }
-                        .into(),
-                        span: e.span,
-                        tpe,
-                    })
+    pub fn new(kind: ErrorKind, msg: String, span: TextRange) -> Self {
+        Self {
+            kind,
+            msg,
+            span,
+            labels: Vec::new(),
+        }
+    }
+
+    /// Adds a secondary label (i.e. the type of the operand) to the error
+    pub fn with_label(mut self, msg: String, span: TextRange) -> Self {
+        self.labels.push(Label { msg, span });
+        self
+    }
+}
+
+impl_diagnostic!(TypeInferenceError);
+
+impl From<TypeInferenceError> for Vec<TypeInferenceError> {
+    fn from(e: TypeInferenceError) -> Self {
+        vec![e]
+    }
+}
+
+/// Types of the values (block `val`s and lambda arguments) in scope, `None` if the type is unknown
+/// because of an already reported error in the value definition
+type TypeEnv = HashMap<String, Option<SType>>;
+
+/// Assigns types to all expressions. The inference recovers from an error in a block `val`
+/// definition (or an operand) and continues with the rest, reporting all the errors found.
+pub fn assign_type(expr: Expr) -> Result<Expr, Vec<TypeInferenceError>> {
+    assign_type_in(expr, &TypeEnv::new())
+}
+
+/// An empty list of errors is returned if the expression uses a value with an already reported
+/// error (i.e. a `val` with invalid definition)
+fn assign_type_in(expr: Expr, env: &TypeEnv) -> Result<Expr, Vec<TypeInferenceError>> {
+    let span = expr.span;
+    Ok(match expr.kind {
+        ExprKind::Binary(Binary { op, lhs, rhs }) => {
+            let (l, r) = both(assign_type_in(*lhs, env), assign_type_in(*rhs, env))?;
+            binary(op, l, r, span)?
+        }
+        ExprKind::Unary(Unary { op, expr }) => {
+            let expr = assign_type_in(*expr, env)?;
+            let tpe = expect_type(&expr)?;
+            let is_valid = match op.node {
+                UnaryOp::Neg => tpe.is_numeric(),
+                UnaryOp::Not => tpe == SType::SBoolean,
+            };
+            if !is_valid {
+                return Err(TypeInferenceError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("Type mismatch: {:?} {:?}", op.node, tpe),
+                    span,
+                )
+                .with_label(format!("found {:?}", tpe), expr.span)
+                .into());
+            }
+            Expr {
+                kind: Unary {
+                    op,
+                    expr: expr.into(),
                 }
-                _ => todo!(),
+                .into(),
+                span,
+                tpe: Some(tpe),
+            }
+        }
+        ExprKind::If(If {
+            condition,
+            then_branch,
+            else_branch,
+        }) => {
+            let (condition, (then_branch, else_branch)) = both(
+                assign_type_in(*condition, env),
+                both(
+                    assign_type_in(*then_branch, env),
+                    assign_type_in(*else_branch, env),
+                ),
+            )?;
+            let mut errors = Vec::new();
+            let condition_tpe = expect_type(&condition)?;
+            if condition_tpe != SType::SBoolean {
+                errors.push(TypeInferenceError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("Condition should be Boolean, got {:?}", condition_tpe),
+                    condition.span,
+                ));
+            }
+            let tpe = expect_type(&then_branch)?;
+            let else_tpe = expect_type(&else_branch)?;
+            if tpe != else_tpe {
+                errors.push(
+                    TypeInferenceError::new(
+                        ErrorKind::TypeMismatch,
+                        format!(
+                            "Branches of if should have the same type, got {:?} and {:?}",
+                            tpe, else_tpe
+                        ),
+                        span,
+                    )
+                    .with_label(format!("this is {:?}", tpe), then_branch.span)
+                    .with_label(
+                        format!(
+                            "expected {:?} because of the then branch, found {:?}",
+                            tpe, else_tpe
+                        ),
+                        else_branch.span,
+                    ),
+                );
+            }
+            if !errors.is_empty() {
+                return Err(errors);
+            }
+            Expr {
+                kind: If {
+                    condition: condition.into(),
+                    then_branch: then_branch.into(),
+                    else_branch: else_branch.into(),
+                }
+                .into(),
+                span,
+                tpe: Some(tpe),
+            }
+        }
+        ExprKind::Ident(ref name) => match env.get(name) {
+            Some(Some(tpe)) => Expr {
+                tpe: Some(tpe.clone()),
+                ..expr
             },
-            _ => None,
-        })
+            // the error in the value definition is already reported
+            Some(None) => return Err(Vec::new()),
+            None => {
+                return Err(TypeInferenceError::new(
+                    ErrorKind::UnknownIdent,
+                    format!(
+                        "Cannot assign type for variable '{}' because it is not found in env",
+                        name
+                    ),
+                    span,
+                )
+                .into())
+            }
+        },
+        ExprKind::Block(Block { bindings, result }) => {
+            let mut env = env.clone();
+            let mut typed_bindings = Vec::with_capacity(bindings.len());
+            let mut errors = Vec::new();
+            for val in bindings {
+                let typed_rhs =
+                    assign_type_in(*val.rhs, &env).and_then(|rhs| Ok((expect_type(&rhs)?, rhs)));
+                let (tpe, rhs) = match typed_rhs {
+                    Ok(typed_rhs) => typed_rhs,
+                    Err(rhs_errors) => {
+                        // continue with the declared type (if any)
+                        errors.extend(rhs_errors);
+                        env.insert(val.name.clone(), val.given_tpe.clone());
+                        continue;
+                    }
+                };
+                if let Some(given_tpe) = &val.given_tpe {
+                    if *given_tpe != tpe {
+                        errors.push(
+                            TypeInferenceError::new(
+                                ErrorKind::TypeMismatch,
+                                format!(
+                                    "Type mismatch for '{}': declared {:?}, found {:?}",
+                                    val.name, given_tpe, tpe
+                                ),
+                                val.span,
+                            )
+                            .with_label(format!("found {:?}", tpe), rhs.span),
+                        );
+                    }
+                }
+                env.insert(val.name.clone(), Some(val.given_tpe.clone().unwrap_or(tpe)));
+                typed_bindings.push(Val {
+                    rhs: rhs.into(),
+                    ..val
+                });
+            }
+            let result = assign_type_in(*result, &env);
+            if !errors.is_empty() {
+                errors.extend(result.err().unwrap_or_default());
+                return Err(errors);
+            }
+            let result = result?;
+            let tpe = result.tpe.clone();
+            Expr {
+                kind: Block {
+                    bindings: typed_bindings,
+                    result: result.into(),
+                }
+                .into(),
+                span,
+                tpe,
+            }
+        }
+        ExprKind::Lambda(Lambda { args, body }) => {
+            let mut env = env.clone();
+            env.extend(
+                args.iter()
+                    .map(|(name, tpe)| (name.clone(), Some(tpe.clone()))),
+            );
+            let body = assign_type_in(*body, &env)?;
+            let t_dom = args.iter().map(|(_, tpe)| tpe.clone()).collect();
+            let tpe = SFunc::new(t_dom, expect_type(&body)?).into();
+            Expr {
+                kind: Lambda {
+                    args,
+                    body: body.into(),
+                }
+                .into(),
+                span,
+                tpe: Some(tpe),
+            }
+        }
+        ExprKind::Select(Select { obj, field }) => {
+            let obj = assign_type_in(*obj, env)?;
+            select(obj, &field, span)?
+        }
+        ExprKind::Tuple(Tuple { items }) => {
+            let items = all(items.into_iter().map(|item| assign_type_in(item, env)))?;
+            let item_tpes = items
+                .iter()
+                .map(expect_type)
+                .collect::<Result<Vec<SType>, TypeInferenceError>>()?;
+            let tpe = STuple::try_from(item_tpes).map_err(|_| {
+                TypeInferenceError::new(
+                    ErrorKind::InvalidTuple,
+                    format!("Tuple should have 2 to 255 items, got {}", items.len()),
+                    span,
+                )
+            })?;
+            Expr {
+                kind: Tuple { items }.into(),
+                span,
+                tpe: Some(tpe.into()),
+            }
+        }
+        ExprKind::Apply(Apply { func, args }) => {
+            let args = all(args.into_iter().map(|arg| assign_type_in(arg, env)))?;
+            if let ExprKind::Ident(name) = &func.kind {
+                if !env.contains_key(name) {
+                    if let Some((op, tpe)) = predef_func(name, &args, span)? {
+                        return Ok(Expr {
+                            kind: Builtin { op, args }.into(),
+                            span,
+                            tpe: Some(tpe),
+                        });
+                    }
+                }
+            }
+            let Expr {
+                kind: func_kind,
+                span: func_span,
+                tpe: func_tpe,
+            } = *func;
+            match func_kind {
+                // `obj.name(args)` is a method call if the object has such method, otherwise
+                // it's an application of `obj.name`
+                ExprKind::Select(Select { obj, field }) => {
+                    let obj = assign_type_in(*obj, env)?;
+                    let obj_tpe = expect_type(&obj)?;
+                    if let Some((op, signature)) = builtin_method(&obj_tpe, &field) {
+                        builtin_method_call(obj, &field, op, signature, args, span)?
+                    } else if let Some(method) = find_method(&obj_tpe, &field, args.len()) {
+                        method_call(obj, method, args, span)?
+                    } else if let Some(method) = find_method_by_name(&obj_tpe, &field) {
+                        return Err(TypeInferenceError::new(
+                            ErrorKind::WrongArgCount,
+                            format!(
+                                "Method '{}' of {:?} expects {} arguments, got {}",
+                                field,
+                                obj_tpe,
+                                method.tpe().t_dom.len() - 1,
+                                args.len()
+                            ),
+                            span,
+                        )
+                        .into());
+                    } else {
+                        apply(select(obj, &field, func_span)?, args, span)?
+                    }
+                }
+                func_kind => {
+                    let func = assign_type_in(
+                        Expr {
+                            kind: func_kind,
+                            span: func_span,
+                            tpe: func_tpe,
+                        },
+                        env,
+                    )?;
+                    apply(func, args, span)?
+                }
+            }
+        }
+        ExprKind::ApplyTypes(ApplyTypes { input, tpe_args }) => {
+            let register = match (input.kind, &tpe_args[..]) {
+                (ExprKind::Select(Select { obj, field }), [elem_tpe]) => {
+                    register_id(&field).map(|id| (obj, id, elem_tpe.clone()))
+                }
+                _ => None,
+            };
+            let (obj, register_id, elem_tpe) = register.ok_or_else(|| {
+                TypeInferenceError::new(
+                    ErrorKind::InvalidSyntax,
+                    "Type arguments are only supported for box registers, i.e. SELF.R4[Int]"
+                        .to_string(),
+                    span,
+                )
+            })?;
+            let obj = assign_type_in(*obj, env)?;
+            let obj_tpe = expect_type(&obj)?;
+            if obj_tpe != SType::SBox {
+                return Err(TypeInferenceError::new(
+                    ErrorKind::UnknownMember,
+                    format!(
+                        "Register R{} is only available for Box, got {:?}",
+                        register_id, obj_tpe
+                    ),
+                    span,
+                )
+                .into());
+            }
+            Expr {
+                kind: Builtin {
+                    op: BuiltinOp::ExtractRegisterAs(register_id),
+                    args: vec![obj],
+                }
+                .into(),
+                span,
+                tpe: Some(SType::SOption(elem_tpe.into())),
+            }
+        }
+        ExprKind::Literal(Literal::Str(_)) => {
+            return Err(TypeInferenceError::new(
+                ErrorKind::InvalidLiteral,
+                "String literals are only allowed as arguments of PK, fromBase16 and fromBase58"
+                    .to_string(),
+                span,
+            )
+            .into())
+        }
+        ExprKind::Builtin(_)
+        | ExprKind::GlobalVars(_)
+        | ExprKind::Literal(_)
+        | ExprKind::Constant(_)
+        | ExprKind::ConstantPlaceholder(_) => expr,
+    })
+}
+
+fn binary(
+    op: Spanned<BinaryOp>,
+    l: Expr,
+    r: Expr,
+    span: TextRange,
+) -> Result<Expr, TypeInferenceError> {
+    let l_tpe = expect_type(&l)?;
+    let r_tpe = expect_type(&r)?;
+    let type_mismatch = || {
+        TypeInferenceError::new(
+            ErrorKind::TypeMismatch,
+            format!("Type mismatch: {:?} {:?} {:?}", l_tpe, op.node, r_tpe),
+            span,
+        )
+        .with_label(format!("{:?}", l_tpe), l.span)
+        .with_label(format!("{:?}", r_tpe), r.span)
+    };
+    let tpe = match op.node {
+        BinaryOp::Plus | BinaryOp::Minus | BinaryOp::Multiply | BinaryOp::Divide => {
+            if l_tpe != r_tpe || !l_tpe.is_numeric() {
+                return Err(type_mismatch());
+            }
+            l_tpe.clone()
+        }
+        BinaryOp::Eq | BinaryOp::NotEq => {
+            if l_tpe != r_tpe {
+                return Err(type_mismatch());
+            }
+            SType::SBoolean
+        }
+        BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+            if l_tpe != r_tpe || !l_tpe.is_numeric() {
+                return Err(type_mismatch());
+            }
+            SType::SBoolean
+        }
+        BinaryOp::And | BinaryOp::Or => match (&l_tpe, &r_tpe) {
+            (SType::SBoolean, SType::SBoolean) => SType::SBoolean,
+            // with a `SigmaProp` operand the result is `SigmaProp`, i.e. `pk && HEIGHT > 100`
+            (SType::SBoolean | SType::SSigmaProp, SType::SBoolean | SType::SSigmaProp) => {
+                let sigma_op = if op.node == BinaryOp::And {
+                    BuiltinOp::SigmaAnd
+                } else {
+                    BuiltinOp::SigmaOr
+                };
+                return Ok(Expr {
+                    kind: Builtin {
+                        op: sigma_op,
+                        args: vec![to_sigma_prop(l), to_sigma_prop(r)],
+                    }
+                    .into(),
+                    span,
+                    tpe: Some(SType::SSigmaProp),
+                });
+            }
+            _ => return Err(type_mismatch()),
+        },
+    };
+    Ok(Expr {
+        kind: Binary {
+            op,
+            lhs: l.into(),
+            rhs: r.into(),
+        }
+        .into(),
+        span,
+        tpe: Some(tpe),
+    })
+}
+
+/// Wraps the Boolean expression into `sigmaProp`
+fn to_sigma_prop(expr: Expr) -> Expr {
+    if expr.tpe == Some(SType::SSigmaProp) {
+        return expr;
+    }
+    let span = expr.span;
+    Expr {
+        kind: Builtin {
+            op: BuiltinOp::BoolToSigmaProp,
+            args: vec![expr],
+        }
+        .into(),
+        span,
+        tpe: Some(SType::SSigmaProp),
+    }
+}
+
+/// Resolves a call of the predefined function (i.e. `sigmaProp(HEIGHT > 100)`) to the built-in
+/// operation and the result type, `None` if there is no function with such name
+fn predef_func(
+    name: &str,
+    args: &[Expr],
+    span: TextRange,
+) -> Result<Option<(BuiltinOp, SType)>, TypeInferenceError> {
+    let arg_tpes = args
+        .iter()
+        .map(expect_type)
+        .collect::<Result<Vec<SType>, TypeInferenceError>>()?;
+    let bytes = || SType::SColl(SType::SByte.into());
+    let (op, expected_arg_tpes, tpe) = match name {
+        "sigmaProp" => (
+            BuiltinOp::BoolToSigmaProp,
+            vec![SType::SBoolean],
+            SType::SSigmaProp,
+        ),
+        "proveDlog" => (
+            BuiltinOp::CreateProveDlog,
+            vec![SType::SGroupElement],
+            SType::SSigmaProp,
+        ),
+        "proveDHTuple" => (
+            BuiltinOp::CreateProveDhTuple,
+            vec![SType::SGroupElement; 4],
+            SType::SSigmaProp,
+        ),
+        "atLeast" => (
+            BuiltinOp::Atleast,
+            vec![SType::SInt, SType::SColl(SType::SSigmaProp.into())],
+            SType::SSigmaProp,
+        ),
+        "allOf" => (
+            BuiltinOp::AllOf,
+            vec![SType::SColl(SType::SBoolean.into())],
+            SType::SBoolean,
+        ),
+        "anyOf" => (
+            BuiltinOp::AnyOf,
+            vec![SType::SColl(SType::SBoolean.into())],
+            SType::SBoolean,
+        ),
+        "blake2b256" => (BuiltinOp::CalcBlake2b256, vec![bytes()], bytes()),
+        "sha256" => (BuiltinOp::CalcSha256, vec![bytes()], bytes()),
+        "min" | "max" => {
+            let tpe = arg_tpes
+                .first()
+                .filter(|tpe| tpe.is_numeric())
+                .cloned()
+                .ok_or_else(|| {
+                    TypeInferenceError::new(
+                        ErrorKind::TypeMismatch,
+                        format!("Expected numeric arguments, got {:?}", arg_tpes),
+                        span,
+                    )
+                })?;
+            let op = if name == "min" {
+                BuiltinOp::Min
+            } else {
+                BuiltinOp::Max
+            };
+            (op, vec![tpe.clone(); 2], tpe)
+        }
+        "decodePoint" => (BuiltinOp::DecodePoint, vec![bytes()], SType::SGroupElement),
+        "substConstants" => {
+            let new_values_tpe = match arg_tpes.get(2) {
+                Some(tpe @ SType::SColl(_)) => tpe.clone(),
+                _ => SType::SColl(SType::SAny.into()),
+            };
+            (
+                BuiltinOp::SubstConstants,
+                vec![bytes(), SType::SColl(SType::SInt.into()), new_values_tpe],
+                bytes(),
+            )
+        }
+        "Coll" => {
+            let elem_tpe = arg_tpes.first().cloned().ok_or_else(|| {
+                TypeInferenceError::new(
+                    ErrorKind::CannotInferType,
+                    "Cannot infer the element type of an empty collection".to_string(),
+                    span,
+                )
+            })?;
+            (
+                BuiltinOp::Collection,
+                vec![elem_tpe.clone(); arg_tpes.len()],
+                SType::SColl(elem_tpe.into()),
+            )
+        }
+        _ => return Ok(None),
+    };
+    if arg_tpes != expected_arg_tpes {
+        return Err(arguments_mismatch(
+            &expected_arg_tpes,
+            args,
+            &arg_tpes,
+            span,
+        ));
+    }
+    Ok(Some((op, tpe)))
+}
+
+/// Error for the arguments not matching the expected types, with every mismatched argument
+/// labelled, or for the wrong number of arguments
+fn arguments_mismatch(
+    expected: &[SType],
+    args: &[Expr],
+    arg_tpes: &[SType],
+    span: TextRange,
+) -> TypeInferenceError {
+    if expected.len() != args.len() {
+        return TypeInferenceError::new(
+            ErrorKind::WrongArgCount,
+            format!(
+                "Expected {} arguments {:?}, got {} {:?}",
+                expected.len(),
+                expected,
+                args.len(),
+                arg_tpes
+            ),
+            span,
+        );
+    }
+    let mut error = TypeInferenceError::new(
+        ErrorKind::TypeMismatch,
+        format!(
+            "Type mismatch: expected arguments {:?}, got {:?}",
+            expected, arg_tpes
+        ),
+        span,
+    );
+    for ((expected_tpe, arg_tpe), arg) in expected.iter().zip(arg_tpes).zip(args) {
+        if arg_tpe != expected_tpe {
+            error = error.with_label(
+                format!("expected {:?}, found {:?}", expected_tpe, arg_tpe),
+                arg.span,
+            );
+        }
+    }
+    error
+}
+
+/// Resolves `obj.field` to a tuple field, a built-in property or an object property
+fn select(obj: Expr, field: &str, span: TextRange) -> Result<Expr, TypeInferenceError> {
+    let obj_tpe = expect_type(&obj)?;
+    let builtin = match &obj_tpe {
+        SType::STuple(tuple) => tuple_field_index(field).and_then(|idx| {
+            tuple
+                .items
+                .get(idx as usize - 1)
+                .map(|field_tpe| (BuiltinOp::SelectField(idx), field_tpe.clone()))
+        }),
+        SType::SColl(_) if field == "size" => Some((BuiltinOp::SizeOf, SType::SInt)),
+        SType::SOption(elem_tpe) if field == "get" => {
+            Some((BuiltinOp::OptionGet, *elem_tpe.clone()))
+        }
+        SType::SOption(_) if field == "isDefined" => {
+            Some((BuiltinOp::OptionIsDefined, SType::SBoolean))
+        }
+        SType::SSigmaProp if field == "propBytes" => {
+            Some((BuiltinOp::SigmaPropBytes, SType::SColl(SType::SByte.into())))
+        }
+        SType::SSigmaProp if field == "isProven" => {
+            Some((BuiltinOp::SigmaPropIsProven, SType::SBoolean))
+        }
+        SType::SBox if register_id(field).is_some() => {
+            return Err(TypeInferenceError::new(
+                ErrorKind::CannotInferType,
+                format!("Register type should be specified, i.e. {}[Int]", field),
+                span,
+            ))
+        }
+        _ => None,
+    };
+    if let Some((op, tpe)) = builtin {
+        return Ok(Expr {
+            kind: Builtin {
+                op,
+                args: vec![obj],
+            }
+            .into(),
+            span,
+            tpe: Some(tpe),
+        });
+    }
+    match find_method(&obj_tpe, field, 0) {
+        Some(method) => method_call(obj, method, vec![], span),
+        None => Err(TypeInferenceError::new(
+            ErrorKind::UnknownMember,
+            format!("Cannot find property '{}' in {:?}", field, obj_tpe),
+            span,
+        )),
+    }
+}
+
+/// Resolves `func(args)` to a collection element or a function call
+fn apply(func: Expr, args: Vec<Expr>, span: TextRange) -> Result<Expr, TypeInferenceError> {
+    let func_tpe = expect_type(&func)?;
+    match (func_tpe, &args[..]) {
+        (SType::SColl(elem_tpe), [index]) => {
+            let index_tpe = expect_type(index)?;
+            if index_tpe != SType::SInt {
+                return Err(TypeInferenceError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("Collection index should be Int, got {:?}", index_tpe),
+                    index.span,
+                ));
+            }
+            Ok(Expr {
+                kind: Builtin {
+                    op: BuiltinOp::ByIndex,
+                    args: vec![func, args[0].clone()],
+                }
+                .into(),
+                span,
+                tpe: Some(*elem_tpe),
+            })
+        }
+        (SType::SFunc(sfunc), _) => {
+            let arg_tpes = args
+                .iter()
+                .map(expect_type)
+                .collect::<Result<Vec<SType>, TypeInferenceError>>()?;
+            if arg_tpes != sfunc.t_dom {
+                return Err(arguments_mismatch(&sfunc.t_dom, &args, &arg_tpes, span));
+            }
+            Ok(Expr {
+                kind: Apply {
+                    func: func.into(),
+                    args,
+                }
+                .into(),
+                span,
+                tpe: Some(*sfunc.t_range),
+            })
+        }
+        (func_tpe, _) => Err(TypeInferenceError::new(
+            ErrorKind::TypeMismatch,
+            format!("Cannot apply arguments to {:?}", func_tpe),
+            span,
+        )),
+    }
+}
+
+/// Collection and option operations that have dedicated MIR nodes (instead of `MethodCall`),
+/// the signature includes the object type as the first argument
+fn builtin_method(obj_tpe: &SType, name: &str) -> Option<(BuiltinOp, SFunc)> {
+    let iv = || SType::STypeVar(STypeVar::iv());
+    let ov = || SType::STypeVar(STypeVar::ov());
+    let coll = || SType::SColl(iv().into());
+    let predicate = || SFunc::new(vec![iv()], SType::SBoolean).into();
+    let (op, t_dom, t_range) = match (obj_tpe, name) {
+        (SType::SColl(_), "map") => (
+            BuiltinOp::Map,
+            vec![coll(), SFunc::new(vec![iv()], ov()).into()],
+            SType::SColl(ov().into()),
+        ),
+        (SType::SColl(_), "filter") => (BuiltinOp::Filter, vec![coll(), predicate()], coll()),
+        (SType::SColl(_), "exists") => (
+            BuiltinOp::Exists,
+            vec![coll(), predicate()],
+            SType::SBoolean,
+        ),
+        (SType::SColl(_), "forall") => (
+            BuiltinOp::ForAll,
+            vec![coll(), predicate()],
+            SType::SBoolean,
+        ),
+        (SType::SColl(_), "fold") => (
+            BuiltinOp::Fold,
+            vec![coll(), ov(), SFunc::new(vec![ov(), iv()], ov()).into()],
+            ov(),
+        ),
+        (SType::SColl(_), "slice") => (
+            BuiltinOp::Slice,
+            vec![coll(), SType::SInt, SType::SInt],
+            coll(),
+        ),
+        (SType::SColl(_), "append") => (BuiltinOp::Append, vec![coll(), coll()], coll()),
+        (SType::SOption(_), "getOrElse") => (
+            BuiltinOp::OptionGetOrElse,
+            vec![SType::SOption(iv().into()), iv()],
+            iv(),
+        ),
+        _ => return None,
+    };
+    Some((op, SFunc::new(t_dom, t_range)))
+}
+
+fn builtin_method_call(
+    obj: Expr,
+    name: &str,
+    op: BuiltinOp,
+    signature: SFunc,
+    args: Vec<Expr>,
+    span: TextRange,
+) -> Result<Expr, TypeInferenceError> {
+    let obj_tpe = expect_type(&obj)?;
+    let arg_tpes = args
+        .iter()
+        .map(expect_type)
+        .collect::<Result<Vec<SType>, TypeInferenceError>>()?;
+    let mut actual_tpes = vec![obj_tpe.clone()];
+    actual_tpes.extend(arg_tpes.clone());
+    let kind = if signature.t_dom.len() == actual_tpes.len() {
+        ErrorKind::TypeMismatch
+    } else {
+        ErrorKind::WrongArgCount
+    };
+    let cannot_apply = || {
+        TypeInferenceError::new(
+            kind,
+            format!(
+                "Method '{}' of {:?} cannot be applied to arguments {:?}",
+                name, obj_tpe, arg_tpes
+            ),
+            span,
+        )
+    };
+    let subst =
+        unify_many(signature.t_dom.clone(), actual_tpes.clone()).map_err(|_| cannot_apply())?;
+    let expected_tpes: Vec<SType> = signature
+        .t_dom
+        .into_iter()
+        .map(|tpe| tpe.with_subst(&subst))
+        .collect();
+    if expected_tpes != actual_tpes {
+        return Err(cannot_apply());
+    }
+    let tpe = (*signature.t_range).with_subst(&subst);
+    if has_type_vars(&tpe) {
+        return Err(TypeInferenceError::new(
+            ErrorKind::CannotInferType,
+            format!("Cannot infer the result type of method '{}'", name),
+            span,
+        ));
+    }
+    let mut method_args = vec![obj];
+    method_args.extend(args);
+    Ok(Expr {
+        kind: Builtin {
+            op,
+            args: method_args,
+        }
+        .into(),
+        span,
+        tpe: Some(tpe),
+    })
+}
+
+/// Object method (or property) with the given name and number of arguments
+fn find_method(obj_tpe: &SType, name: &str, args_count: usize) -> Option<SMethod> {
+    STypeCompanion::for_type(obj_tpe)?
+        .method_by_name(name)
+        .filter(|method| method.tpe().t_dom.len() == args_count + 1)
+}
+
+/// Object method with the given name and any number of arguments (properties are not
+/// included, `obj.property(args)` applies the arguments to the property value)
+fn find_method_by_name(obj_tpe: &SType, name: &str) -> Option<SMethod> {
+    STypeCompanion::for_type(obj_tpe)?
+        .method_by_name(name)
+        .filter(|method| method.tpe().t_dom.len() > 1)
+}
+
+fn method_call(
+    obj: Expr,
+    method: SMethod,
+    args: Vec<Expr>,
+    span: TextRange,
+) -> Result<Expr, TypeInferenceError> {
+    let obj_tpe = expect_type(&obj)?;
+    let arg_tpes = args
+        .iter()
+        .map(expect_type)
+        .collect::<Result<Vec<SType>, TypeInferenceError>>()?;
+    let name = method.name();
+    let method = method
+        .specialize_for(obj_tpe.clone(), arg_tpes.clone())
+        .map_err(|_| {
+            TypeInferenceError::new(
+                ErrorKind::TypeMismatch,
+                format!(
+                    "Method '{}' of {:?} cannot be applied to arguments {:?}",
+                    name, obj_tpe, arg_tpes
+                ),
+                span,
+            )
+        })?;
+    let tpe = *method.tpe().t_range.clone();
+    if has_type_vars(&tpe) {
+        return Err(TypeInferenceError::new(
+            ErrorKind::CannotInferType,
+            format!("Cannot infer the result type of method '{}'", name),
+            span,
+        ));
+    }
+    let mut method_args = vec![obj];
+    method_args.extend(args);
+    Ok(Expr {
+        kind: Builtin {
+            op: BuiltinOp::Method(method),
+            args: method_args,
+        }
+        .into(),
+        span,
+        tpe: Some(tpe),
+    })
+}
+
+/// Index of the tuple field `_1`, `_2`, ...
+fn tuple_field_index(field: &str) -> Option<u8> {
+    field
+        .strip_prefix('_')
+        .and_then(|idx| idx.parse().ok())
+        .filter(|idx| *idx >= 1)
+}
+
+/// Register id of `R0`..`R9`
+fn register_id(field: &str) -> Option<i8> {
+    field
+        .strip_prefix('R')
+        .filter(|id| id.len() == 1)
+        .and_then(|id| id.parse().ok())
+}
+
+fn has_type_vars(tpe: &SType) -> bool {
+    match tpe {
+        SType::STypeVar(_) => true,
+        SType::SOption(elem_tpe) | SType::SColl(elem_tpe) => has_type_vars(elem_tpe),
+        SType::STuple(tuple) => tuple.items.iter().any(has_type_vars),
+        SType::SFunc(sfunc) => sfunc.t_dom_plus_range().iter().any(has_type_vars),
+        _ => false,
+    }
+}
+
+fn expect_type(expr: &Expr) -> Result<SType, TypeInferenceError> {
+    expr.tpe.clone().ok_or_else(|| {
+        TypeInferenceError::new(
+            ErrorKind::CannotInferType,
+            format!("Cannot infer type of {:?}", expr.kind),
+            expr.span,
+        )
     })
 }
 
@@ -53,7 +938,7 @@ pub fn check(input: &str, expected_tree: expect_test::Expect) {
     let parse = super::parser::parse(input);
     let syntax = parse.syntax();
     let root = crate::ast::Root::cast(syntax).unwrap();
-    let hir = hir::lower(root).unwrap();
+    let hir = crate::hir::lower(root).unwrap();
     let binder = crate::binder::Binder::new(crate::script_env::ScriptEnv::new());
     let bind = binder.bind(hir).unwrap();
     let res = assign_type(bind).unwrap();
@@ -104,4 +989,302 @@ mod tests {
             }"#]],
         );
     }
+
+    #[test]
+    fn block() {
+        check(
+            "{ val a = 1L; val b = a; b + a }",
+            expect![[r#"
+                Expr {
+                    kind: Block(
+                        Block {
+                            bindings: [
+                                Val {
+                                    name: "a",
+                                    given_tpe: None,
+                                    rhs: Expr {
+                                        kind: Literal(
+                                            Long(
+                                                1,
+                                            ),
+                                        ),
+                                        span: 10..12,
+                                        tpe: Some(
+                                            SLong,
+                                        ),
+                                    },
+                                    span: 2..12,
+                                },
+                                Val {
+                                    name: "b",
+                                    given_tpe: None,
+                                    rhs: Expr {
+                                        kind: Ident(
+                                            "a",
+                                        ),
+                                        span: 22..23,
+                                        tpe: Some(
+                                            SLong,
+                                        ),
+                                    },
+                                    span: 14..23,
+                                },
+                            ],
+                            result: Expr {
+                                kind: Binary(
+                                    Binary {
+                                        op: Spanned {
+                                            node: Plus,
+                                            span: 27..28,
+                                        },
+                                        lhs: Expr {
+                                            kind: Ident(
+                                                "b",
+                                            ),
+                                            span: 25..27,
+                                            tpe: Some(
+                                                SLong,
+                                            ),
+                                        },
+                                        rhs: Expr {
+                                            kind: Ident(
+                                                "a",
+                                            ),
+                                            span: 29..31,
+                                            tpe: Some(
+                                                SLong,
+                                            ),
+                                        },
+                                    },
+                                ),
+                                span: 25..31,
+                                tpe: Some(
+                                    SLong,
+                                ),
+                            },
+                        },
+                    ),
+                    span: 0..32,
+                    tpe: Some(
+                        SLong,
+                    ),
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn lambda() {
+        check(
+            "(a: Int, b: Int) => a * b",
+            expect![[r#"
+            Expr {
+                kind: Lambda(
+                    Lambda {
+                        args: [
+                            (
+                                "a",
+                                SInt,
+                            ),
+                            (
+                                "b",
+                                SInt,
+                            ),
+                        ],
+                        body: Expr {
+                            kind: Binary(
+                                Binary {
+                                    op: Spanned {
+                                        node: Multiply,
+                                        span: 22..23,
+                                    },
+                                    lhs: Expr {
+                                        kind: Ident(
+                                            "a",
+                                        ),
+                                        span: 20..22,
+                                        tpe: Some(
+                                            SInt,
+                                        ),
+                                    },
+                                    rhs: Expr {
+                                        kind: Ident(
+                                            "b",
+                                        ),
+                                        span: 24..25,
+                                        tpe: Some(
+                                            SInt,
+                                        ),
+                                    },
+                                },
+                            ),
+                            span: 20..25,
+                            tpe: Some(
+                                SInt,
+                            ),
+                        },
+                    },
+                ),
+                span: 0..25,
+                tpe: Some(
+                    SFunc(
+                        SFunc {
+                            t_dom: [
+                                SInt,
+                                SInt,
+                            ],
+                            t_range: SInt,
+                            tpe_params: [],
+                        },
+                    ),
+                ),
+            }"#]],
+        );
+    }
+
+    #[test]
+    fn select_tuple_field() {
+        check(
+            "SELF.tokens(0)._2",
+            expect![[r#"
+            Expr {
+                kind: Builtin(
+                    Builtin {
+                        op: SelectField(
+                            2,
+                        ),
+                        args: [
+                            Expr {
+                                kind: Builtin(
+                                    Builtin {
+                                        op: ByIndex,
+                                        args: [
+                                            Expr {
+                                                kind: Builtin(
+                                                    Builtin {
+                                                        op: Method(
+                                                            SMethod {
+                                                                obj_type: Box,
+                                                                method_raw: SMethodDesc {
+                                                                    name: "tokens",
+                                                                    method_id: MethodId(
+                                                                        8,
+                                                                    ),
+                                                                    tpe: SFunc {
+                                                                        t_dom: [
+                                                                            SBox,
+                                                                        ],
+                                                                        t_range: SColl(
+                                                                            STuple(
+                                                                                [
+                                                                                    SColl(
+                                                                                        SByte,
+                                                                                    ),
+                                                                                    SLong,
+                                                                                ],
+                                                                            ),
+                                                                        ),
+                                                                        tpe_params: [],
+                                                                    },
+                                                                    explicit_type_args: [],
+                                                                    min_version: ErgoTreeVersion(
+                                                                        0,
+                                                                    ),
+                                                                },
+                                                            },
+                                                        ),
+                                                        args: [
+                                                            Expr {
+                                                                kind: GlobalVars(
+                                                                    SelfBox,
+                                                                ),
+                                                                span: 0..4,
+                                                                tpe: Some(
+                                                                    SBox,
+                                                                ),
+                                                            },
+                                                        ],
+                                                    },
+                                                ),
+                                                span: 0..11,
+                                                tpe: Some(
+                                                    SColl(
+                                                        STuple(
+                                                            [
+                                                                SColl(
+                                                                    SByte,
+                                                                ),
+                                                                SLong,
+                                                            ],
+                                                        ),
+                                                    ),
+                                                ),
+                                            },
+                                            Expr {
+                                                kind: Literal(
+                                                    Int(
+                                                        0,
+                                                    ),
+                                                ),
+                                                span: 12..13,
+                                                tpe: Some(
+                                                    SInt,
+                                                ),
+                                            },
+                                        ],
+                                    },
+                                ),
+                                span: 0..14,
+                                tpe: Some(
+                                    STuple(
+                                        [
+                                            SColl(
+                                                SByte,
+                                            ),
+                                            SLong,
+                                        ],
+                                    ),
+                                ),
+                            },
+                        ],
+                    },
+                ),
+                span: 0..17,
+                tpe: Some(
+                    SLong,
+                ),
+            }"#]],
+        );
+    }
+
+    #[test]
+    fn coll_size() {
+        check(
+            "INPUTS.size",
+            expect![[r#"
+            Expr {
+                kind: Builtin(
+                    Builtin {
+                        op: SizeOf,
+                        args: [
+                            Expr {
+                                kind: GlobalVars(
+                                    Inputs,
+                                ),
+                                span: 0..6,
+                                tpe: Some(
+                                    SColl(
+                                        SBox,
+                                    ),
+                                ),
+                            },
+                        ],
+                    },
+                ),
+                span: 0..11,
+                tpe: Some(
+                    SInt,
+                ),
+            }"#]],
+        );
+    }
 }
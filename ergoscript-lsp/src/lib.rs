@@ -0,0 +1,27 @@
+//! ErgoScript language server (LSP over stdio)
+//!
+//! Publishes compiler diagnostics on every change, shows expression types on hover, completes
+//! object members after `.`, goes to `val` definitions and shows the compiled ErgoTree size and
+//! P2S address as a code lens.
+
+// Coding conventions
+#![forbid(unsafe_code)]
+#![deny(non_upper_case_globals)]
+#![deny(non_camel_case_types)]
+#![deny(non_snake_case)]
+#![deny(unused_mut)]
+#![deny(dead_code)]
+#![deny(unused_imports)]
+#![deny(missing_docs)]
+// Clippy exclusions
+#![allow(clippy::unit_arg)]
+#![deny(rustdoc::broken_intra_doc_links)]
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::todo)]
+#![deny(clippy::unimplemented)]
+#![deny(clippy::panic)]
+
+pub(crate) mod line_index;
+pub mod server;
+pub mod transport;
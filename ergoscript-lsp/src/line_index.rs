@@ -0,0 +1,91 @@
+//! Conversion between byte offsets and LSP positions (line and UTF-16 column)
+
+use std::ops::Range;
+
+use serde_json::json;
+use serde_json::Value;
+
+pub(crate) struct LineIndex<'a> {
+    text: &'a str,
+    /// Byte offsets of the line starts
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub(crate) fn new(text: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(idx, _)| idx + 1))
+            .collect();
+        LineIndex { text, line_starts }
+    }
+
+    /// LSP `Position` of the byte offset
+    pub(crate) fn position(&self, offset: usize) -> Value {
+        let offset = offset.min(self.text.len());
+        let line = self
+            .line_starts
+            .partition_point(|start| *start <= offset)
+            .saturating_sub(1);
+        let line_start = self.line_starts[line];
+        let character: usize = self
+            .text
+            .get(line_start..offset)
+            .unwrap_or_default()
+            .chars()
+            .map(char::len_utf16)
+            .sum();
+        json!({ "line": line, "character": character })
+    }
+
+    /// LSP `Range` of the byte range
+    pub(crate) fn range(&self, range: &Range<usize>) -> Value {
+        json!({ "start": self.position(range.start), "end": self.position(range.end) })
+    }
+
+    /// Byte offset of the LSP `Position` (clamped to the line end)
+    pub(crate) fn offset(&self, position: &Value) -> Option<usize> {
+        let line = position.get("line")?.as_u64()? as usize;
+        let character = position.get("character")?.as_u64()? as usize;
+        let line_start = *self.line_starts.get(line)?;
+        let line_text = self.text[line_start..]
+            .split_inclusive('\n')
+            .next()
+            .unwrap_or_default();
+        let mut utf16_len = 0;
+        for (idx, c) in line_text.char_indices() {
+            if utf16_len >= character || c == '\n' {
+                return Some(line_start + idx);
+            }
+            utf16_len += c.len_utf16();
+        }
+        Some(line_start + line_text.len())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let text = "{\n  val ü = 1\n  ü𝔸 + 1 }";
+        let index = LineIndex::new(text);
+        for (offset, _) in text.char_indices() {
+            let position = index.position(offset);
+            assert_eq!(index.offset(&position), Some(offset), "{}", position);
+        }
+        assert_eq!(
+            index.position(text.find('+').unwrap()),
+            json!({"line": 2, "character": 6})
+        );
+    }
+
+    #[test]
+    fn test_past_line_end() {
+        let index = LineIndex::new("ab\ncd");
+        assert_eq!(index.offset(&json!({"line": 0, "character": 10})), Some(2));
+        assert_eq!(index.offset(&json!({"line": 1, "character": 10})), Some(5));
+        assert_eq!(index.offset(&json!({"line": 2, "character": 0})), None);
+    }
+}
@@ -0,0 +1,14 @@
+//! ErgoScript language server binary, talks LSP over stdin/stdout
+
+use std::io;
+
+use ergoscript_lsp::server::Server;
+
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    if let Err(e) = Server::new().run(&mut stdin.lock(), &mut stdout.lock()) {
+        eprintln!("ergoscript-lsp: {}", e);
+        std::process::exit(1);
+    }
+}
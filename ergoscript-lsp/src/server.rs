@@ -0,0 +1,630 @@
+//! Language server state and request handlers
+
+use std::collections::HashMap;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+
+use ergoscript_compiler::ide::Analysis;
+use ergoscript_compiler::script_env::ScriptEnv;
+use ergotree_ir::chain::address::Address;
+use ergotree_ir::chain::address::AddressEncoder;
+use ergotree_ir::chain::address::NetworkPrefix;
+use ergotree_ir::serialization::SigmaSerializable;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::line_index::LineIndex;
+use crate::transport::read_message;
+use crate::transport::write_message;
+
+/// JSON-RPC error code for unknown methods
+const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code for invalid parameters
+const INVALID_PARAMS: i64 = -32602;
+/// `DiagnosticSeverity.Error`
+const SEVERITY_ERROR: u8 = 1;
+/// `TextDocumentSyncKind.Full`
+const SYNC_FULL: u8 = 1;
+/// `CompletionItemKind.Property`
+const COMPLETION_PROPERTY: u8 = 10;
+
+/// Opened document
+struct Document {
+    text: String,
+    analysis: Analysis,
+    /// Analysis of the last version without errors (used for completion while typing)
+    last_valid: Option<Analysis>,
+}
+
+/// Language server
+pub struct Server {
+    documents: HashMap<String, Document>,
+    /// Network of the P2S address shown for the compiled script
+    network: NetworkPrefix,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Server {
+    /// Server with no opened documents
+    pub fn new() -> Self {
+        Server {
+            documents: HashMap::new(),
+            network: NetworkPrefix::Mainnet,
+        }
+    }
+
+    /// Handles the messages from the input until the `exit` notification or the end of the input
+    pub fn run(&mut self, input: &mut impl BufRead, output: &mut impl Write) -> io::Result<()> {
+        while let Some(message) = read_message(input)? {
+            if message.get("method").and_then(Value::as_str) == Some("exit") {
+                break;
+            }
+            for response in self.handle(&message) {
+                write_message(output, &response)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles the request or notification, returns the messages to send to the client (the
+    /// response and/or notifications)
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let params = message.get("params").unwrap_or(&Value::Null);
+        let id = match message.get("id") {
+            Some(id) => id,
+            None => return self.handle_notification(method, params),
+        };
+        let result = match method {
+            "initialize" => Ok(self.initialize(params)),
+            "shutdown" => Ok(Value::Null),
+            "textDocument/hover" => self.with_position(params, hover),
+            "textDocument/completion" => self.with_position(params, completion),
+            "textDocument/definition" => self.with_position(params, definition),
+            "textDocument/codeLens" => self.code_lens(params),
+            _ => Err((METHOD_NOT_FOUND, format!("Unknown method: {}", method))),
+        };
+        vec![match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        }]
+    }
+
+    fn initialize(&mut self, params: &Value) -> Value {
+        if let Some(network) = params
+            .pointer("/initializationOptions/network")
+            .and_then(Value::as_str)
+        {
+            self.network = match network {
+                "testnet" => NetworkPrefix::Testnet,
+                _ => NetworkPrefix::Mainnet,
+            };
+        }
+        json!({
+            "capabilities": {
+                "textDocumentSync": SYNC_FULL,
+                "hoverProvider": true,
+                "completionProvider": { "triggerCharacters": ["."] },
+                "definitionProvider": true,
+                "codeLensProvider": { "resolveProvider": false },
+            },
+            "serverInfo": {
+                "name": "ergoscript-lsp",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = match params.pointer("/textDocument/uri").and_then(Value::as_str) {
+            Some(uri) => uri.to_string(),
+            None => return Vec::new(),
+        };
+        match method {
+            "textDocument/didOpen" => {
+                let text = params
+                    .pointer("/textDocument/text")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                vec![self.update(uri, text.to_string())]
+            }
+            "textDocument/didChange" => {
+                // full sync, the last change has the whole text
+                let text = params
+                    .get("contentChanges")
+                    .and_then(Value::as_array)
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.get("text"))
+                    .and_then(Value::as_str);
+                match text {
+                    Some(text) => vec![self.update(uri, text.to_string())],
+                    None => Vec::new(),
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                vec![publish_diagnostics(&uri, Vec::new())]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Analyzes the new text of the document, returns the diagnostics notification
+    fn update(&mut self, uri: String, text: String) -> Value {
+        let analysis = Analysis::new(&text, ScriptEnv::new());
+        let index = LineIndex::new(&text);
+        let diagnostics = analysis
+            .diagnostics()
+            .into_iter()
+            .map(|d| {
+                let related = d
+                    .labels
+                    .iter()
+                    .map(|(range, message)| {
+                        json!({
+                            "location": { "uri": uri, "range": index.range(range) },
+                            "message": message,
+                        })
+                    })
+                    .collect::<Vec<Value>>();
+                // LSP diagnostics have no separate field for the hint
+                let message = match &d.help {
+                    Some(help) => format!("{}\nhelp: {}", d.message, help),
+                    None => d.message,
+                };
+                json!({
+                    "range": index.range(&d.range),
+                    "severity": SEVERITY_ERROR,
+                    "code": d.code,
+                    "source": "ergoscript",
+                    "message": message,
+                    "relatedInformation": related,
+                })
+            })
+            .collect();
+        let notification = publish_diagnostics(&uri, diagnostics);
+        let last_valid = match self.documents.remove(&uri) {
+            Some(doc) if doc.analysis.ergo_tree().is_some() => Some(doc.analysis),
+            Some(doc) => doc.last_valid,
+            None => None,
+        };
+        self.documents.insert(
+            uri,
+            Document {
+                text,
+                analysis,
+                last_valid,
+            },
+        );
+        notification
+    }
+
+    /// Calls the handler with the document and the byte offset of the position in the request
+    fn with_position(
+        &self,
+        params: &Value,
+        handler: fn(&Document, &LineIndex, &str, usize) -> Value,
+    ) -> Result<Value, (i64, String)> {
+        let (uri, doc) = self.document(params)?;
+        let index = LineIndex::new(&doc.text);
+        let offset = params
+            .get("position")
+            .and_then(|position| index.offset(position))
+            .ok_or_else(|| (INVALID_PARAMS, "Invalid position".to_string()))?;
+        Ok(handler(doc, &index, uri, offset))
+    }
+
+    fn document<'a>(&'a self, params: &'a Value) -> Result<(&'a str, &'a Document), (i64, String)> {
+        let uri = params
+            .pointer("/textDocument/uri")
+            .and_then(Value::as_str)
+            .ok_or_else(|| (INVALID_PARAMS, "Missing textDocument.uri".to_string()))?;
+        let doc = self
+            .documents
+            .get(uri)
+            .ok_or_else(|| (INVALID_PARAMS, format!("Unknown document: {}", uri)))?;
+        Ok((uri, doc))
+    }
+
+    /// ErgoTree size and P2S address of the compiled script at the start of the document
+    fn code_lens(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (_, doc) = self.document(params)?;
+        let tree = match doc.analysis.ergo_tree() {
+            Some(tree) => tree,
+            None => return Ok(json!([])),
+        };
+        let title = match (
+            tree.sigma_serialize_bytes(),
+            Address::recreate_from_ergo_tree(tree),
+        ) {
+            (Ok(bytes), Ok(address)) => format!(
+                "ErgoTree: {} bytes, address: {}",
+                bytes.len(),
+                AddressEncoder::encode_address_as_string(self.network, &address)
+            ),
+            (Err(e), _) => format!("ErgoTree serialization error: {}", e),
+            (_, Err(e)) => format!("Address error: {}", e),
+        };
+        Ok(json!([{
+            "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 0 } },
+            "command": { "title": title, "command": "" },
+        }]))
+    }
+}
+
+fn hover(doc: &Document, index: &LineIndex, _uri: &str, offset: usize) -> Value {
+    match doc.analysis.type_at(offset) {
+        Some((range, tpe)) => json!({
+            "contents": { "kind": "markdown", "value": format!("```ergoscript\n{}\n```", tpe) },
+            "range": index.range(&range),
+        }),
+        None => Value::Null,
+    }
+}
+
+fn completion(doc: &Document, _index: &LineIndex, _uri: &str, offset: usize) -> Value {
+    let analysis = match &doc.last_valid {
+        Some(last_valid) if doc.analysis.ergo_tree().is_none() => last_valid,
+        _ => &doc.analysis,
+    };
+    analysis
+        .completions(&doc.text, offset)
+        .into_iter()
+        .map(|c| {
+            json!({
+                "label": c.label,
+                "kind": COMPLETION_PROPERTY,
+                "detail": c.detail,
+            })
+        })
+        .collect()
+}
+
+fn definition(doc: &Document, index: &LineIndex, uri: &str, offset: usize) -> Value {
+    match doc.analysis.definition(offset) {
+        Some(range) => json!({ "uri": uri, "range": index.range(&range) }),
+        None => Value::Null,
+    }
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use expect_test::expect;
+
+    const URI: &str = "file:///contract.es";
+
+    fn request(id: u64, method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+    }
+
+    fn notification(method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "method": method, "params": params })
+    }
+
+    fn open(server: &mut Server, text: &str) -> Vec<Value> {
+        server.handle(&notification(
+            "textDocument/didOpen",
+            json!({ "textDocument": { "uri": URI, "languageId": "ergoscript", "version": 1, "text": text } }),
+        ))
+    }
+
+    fn change(server: &mut Server, text: &str) -> Vec<Value> {
+        server.handle(&notification(
+            "textDocument/didChange",
+            json!({ "textDocument": { "uri": URI, "version": 2 }, "contentChanges": [{ "text": text }] }),
+        ))
+    }
+
+    fn at_position(server: &mut Server, method: &str, line: u64, character: u64) -> Value {
+        let params = json!({
+            "textDocument": { "uri": URI },
+            "position": { "line": line, "character": character },
+        });
+        let mut responses = server.handle(&request(1, method, params));
+        assert_eq!(responses.len(), 1);
+        responses.remove(0)["result"].take()
+    }
+
+    #[test]
+    fn test_session_over_stdio() {
+        let messages = [
+            request(1, "initialize", json!({ "capabilities": {} })),
+            notification("initialized", json!({})),
+            notification(
+                "textDocument/didOpen",
+                json!({ "textDocument": { "uri": URI, "languageId": "ergoscript", "version": 1, "text": "sigmaProp(HEIGHT > 1)" } }),
+            ),
+            request(2, "shutdown", Value::Null),
+            notification("exit", Value::Null),
+            // not handled after `exit`
+            request(3, "shutdown", Value::Null),
+        ];
+        let mut input = Vec::new();
+        for message in &messages {
+            write_message(&mut input, message).unwrap();
+        }
+        let mut output = Vec::new();
+        Server::new()
+            .run(&mut input.as_slice(), &mut output)
+            .unwrap();
+        let mut output = output.as_slice();
+        let mut responses = Vec::new();
+        while let Some(response) = read_message(&mut output).unwrap() {
+            responses.push(response);
+        }
+        expect![[r#"
+            [
+              {
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                  "capabilities": {
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "completionProvider": {
+                      "triggerCharacters": [
+                        "."
+                      ]
+                    },
+                    "definitionProvider": true,
+                    "codeLensProvider": {
+                      "resolveProvider": false
+                    }
+                  },
+                  "serverInfo": {
+                    "name": "ergoscript-lsp",
+                    "version": "0.1.0"
+                  }
+                }
+              },
+              {
+                "jsonrpc": "2.0",
+                "method": "textDocument/publishDiagnostics",
+                "params": {
+                  "uri": "file:///contract.es",
+                  "diagnostics": []
+                }
+              },
+              {
+                "jsonrpc": "2.0",
+                "id": 2,
+                "result": null
+              }
+            ]"#]]
+        .assert_eq(&serde_json::to_string_pretty(&responses).unwrap());
+    }
+
+    #[test]
+    fn test_unknown_method() {
+        let responses = Server::new().handle(&request(7, "workspace/symbol", json!({})));
+        assert_eq!(responses[0]["error"]["code"], json!(METHOD_NOT_FOUND));
+        assert_eq!(responses[0]["id"], json!(7));
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let mut server = Server::new();
+        let notifications = open(&mut server, "{\n  val a = 1\n  sigmaProp(a + true)\n}");
+        expect![[r#"
+            [
+              {
+                "jsonrpc": "2.0",
+                "method": "textDocument/publishDiagnostics",
+                "params": {
+                  "uri": "file:///contract.es",
+                  "diagnostics": [
+                    {
+                      "range": {
+                        "start": {
+                          "line": 2,
+                          "character": 12
+                        },
+                        "end": {
+                          "line": 2,
+                          "character": 20
+                        }
+                      },
+                      "severity": 1,
+                      "code": "ergoscript::type_mismatch",
+                      "source": "ergoscript",
+                      "message": "Type mismatch: SInt Plus SBoolean\nhelp: the types should match exactly, numeric values are converted explicitly with toByte, toShort, toInt, toLong or toBigInt",
+                      "relatedInformation": [
+                        {
+                          "location": {
+                            "uri": "file:///contract.es",
+                            "range": {
+                              "start": {
+                                "line": 2,
+                                "character": 12
+                              },
+                              "end": {
+                                "line": 2,
+                                "character": 13
+                              }
+                            }
+                          },
+                          "message": "SInt"
+                        },
+                        {
+                          "location": {
+                            "uri": "file:///contract.es",
+                            "range": {
+                              "start": {
+                                "line": 2,
+                                "character": 16
+                              },
+                              "end": {
+                                "line": 2,
+                                "character": 20
+                              }
+                            }
+                          },
+                          "message": "SBoolean"
+                        }
+                      ]
+                    }
+                  ]
+                }
+              }
+            ]"#]]
+        .assert_eq(&serde_json::to_string_pretty(&notifications).unwrap());
+        let notifications = change(&mut server, "{\n  val a = 1\n  sigmaProp(a > 0)\n}");
+        assert_eq!(notifications[0]["params"]["diagnostics"], json!([]));
+    }
+
+    #[test]
+    fn test_hover() {
+        let mut server = Server::new();
+        open(
+            &mut server,
+            "{\n  val out = OUTPUTS(0)\n  sigmaProp(out.value > 1L)\n}",
+        );
+        expect![[r#"
+            {
+              "contents": {
+                "kind": "markdown",
+                "value": "```ergoscript\nLong\n```"
+              },
+              "range": {
+                "start": {
+                  "line": 2,
+                  "character": 12
+                },
+                "end": {
+                  "line": 2,
+                  "character": 21
+                }
+              }
+            }"#]]
+        .assert_eq(
+            &serde_json::to_string_pretty(&at_position(&mut server, "textDocument/hover", 2, 16))
+                .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_completion_while_typing() {
+        let mut server = Server::new();
+        open(
+            &mut server,
+            "{\n  val out = OUTPUTS(0)\n  sigmaProp(true)\n}",
+        );
+        change(
+            &mut server,
+            "{\n  val out = OUTPUTS(0)\n  sigmaProp(out.\n}",
+        );
+        let completions = at_position(&mut server, "textDocument/completion", 2, 16);
+        let labels = completions
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["label"].as_str().unwrap())
+            .collect::<Vec<&str>>();
+        assert!(labels.contains(&"value"));
+        assert!(labels.contains(&"tokens"));
+        assert!(labels.contains(&"R4"));
+        let completions = at_position(&mut server, "textDocument/completion", 2, 12);
+        assert_eq!(completions, json!([]));
+    }
+
+    #[test]
+    fn test_context_completion() {
+        let mut server = Server::new();
+        open(&mut server, "sigmaProp(CONTEXT.)");
+        let completions = at_position(&mut server, "textDocument/completion", 0, 18);
+        expect![[r#"
+            {
+              "label": "dataInputs",
+              "kind": 10,
+              "detail": "Coll[Box]"
+            }"#]]
+        .assert_eq(&serde_json::to_string_pretty(&completions.as_array().unwrap()[0]).unwrap());
+    }
+
+    #[test]
+    fn test_definition() {
+        let mut server = Server::new();
+        open(
+            &mut server,
+            "{\n  val out = OUTPUTS(0)\n  sigmaProp(out.value > 1L)\n}",
+        );
+        expect![[r#"
+            {
+              "uri": "file:///contract.es",
+              "range": {
+                "start": {
+                  "line": 1,
+                  "character": 2
+                },
+                "end": {
+                  "line": 1,
+                  "character": 22
+                }
+              }
+            }"#]]
+        .assert_eq(
+            &serde_json::to_string_pretty(&at_position(
+                &mut server,
+                "textDocument/definition",
+                2,
+                13,
+            ))
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_code_lens() {
+        let mut server = Server::new();
+        open(&mut server, "sigmaProp(HEIGHT > 100)");
+        let params = json!({ "textDocument": { "uri": URI } });
+        let responses = server.handle(&request(1, "textDocument/codeLens", params.clone()));
+        expect![[r#""ErgoTree: 10 bytes, address: 5yE8zxMTsrGEPw5WM5ET""#]]
+            .assert_eq(&responses[0]["result"][0]["command"]["title"].to_string());
+        change(&mut server, "sigmaProp(HEIGHT > )");
+        let responses = server.handle(&request(2, "textDocument/codeLens", params));
+        assert_eq!(responses[0]["result"], json!([]));
+    }
+
+    #[test]
+    fn test_testnet_address() {
+        let mut server = Server::new();
+        server.handle(&request(
+            1,
+            "initialize",
+            json!({ "initializationOptions": { "network": "testnet" } }),
+        ));
+        open(&mut server, "sigmaProp(HEIGHT > 100)");
+        let params = json!({ "textDocument": { "uri": URI } });
+        let responses = server.handle(&request(2, "textDocument/codeLens", params));
+        let title = responses[0]["result"][0]["command"]["title"]
+            .as_str()
+            .unwrap();
+        let address = title.rsplit(' ').next().unwrap();
+        let encoder = AddressEncoder::new(NetworkPrefix::Testnet);
+        assert!(encoder.parse_address_from_str(address).is_ok());
+    }
+}
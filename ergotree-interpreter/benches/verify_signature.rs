@@ -0,0 +1,105 @@
+//! Benchmarks of the signature verification, compares the batch verification of Schnorr and
+//! Diffie-Hellman tuple proofs against verifying them one by one
+
+#![allow(clippy::unwrap_used)]
+#![allow(missing_docs)]
+
+use criterion::black_box;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use ergotree_interpreter::sigma_protocol::batch_verifier::verify_batch;
+use ergotree_interpreter::sigma_protocol::private_input::DhTupleProverInput;
+use ergotree_interpreter::sigma_protocol::private_input::DlogProverInput;
+use ergotree_interpreter::sigma_protocol::private_input::PrivateInput;
+use ergotree_interpreter::sigma_protocol::prover::hint::HintsBag;
+use ergotree_interpreter::sigma_protocol::prover::Prover;
+use ergotree_interpreter::sigma_protocol::prover::TestProver;
+use ergotree_interpreter::sigma_protocol::sig_serializer::parse_sig_compute_challenges;
+use ergotree_interpreter::sigma_protocol::unchecked_tree::UncheckedTree;
+use ergotree_interpreter::sigma_protocol::verifier::compute_commitments;
+use ergotree_interpreter::sigma_protocol::verifier::verify_signature;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
+
+const MESSAGE: &[u8] = b"message to sign";
+
+fn sign(secret: PrivateInput) -> (SigmaBoolean, Vec<u8>) {
+    let sigma_tree: SigmaBoolean = secret.public_image();
+    let prover = TestProver {
+        secrets: vec![secret],
+    };
+    let proof: Vec<u8> = prover
+        .generate_proof(sigma_tree.clone(), MESSAGE, &HintsBag::empty())
+        .unwrap()
+        .into();
+    assert!(verify_signature(sigma_tree.clone(), MESSAGE, &proof).unwrap());
+    (sigma_tree, proof)
+}
+
+fn verify(c: &mut Criterion, name: &str, secret: PrivateInput) {
+    let (sigma_tree, proof) = sign(secret);
+    c.bench_function(name, |b| {
+        b.iter(|| verify_signature(black_box(sigma_tree.clone()), MESSAGE, black_box(&proof)))
+    });
+}
+
+fn verify_dlog(c: &mut Criterion) {
+    verify(
+        c,
+        "verify_signature ProveDlog",
+        DlogProverInput::random().into(),
+    );
+}
+
+fn verify_dht(c: &mut Criterion) {
+    verify(
+        c,
+        "verify_signature ProveDhTuple",
+        DhTupleProverInput::random().into(),
+    );
+}
+
+/// The same proofs verified one by one and in a batch. The batch needs the proof trees with the
+/// commitments, they are computed beforehand (a prover or a relay can provide them).
+fn batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batch of ProveDlog and ProveDhTuple proofs");
+    for size in [16, 64, 256] {
+        let signed: Vec<(SigmaBoolean, Vec<u8>)> = (0..size)
+            .map(|i| {
+                if i % 2 == 0 {
+                    sign(DlogProverInput::random().into())
+                } else {
+                    sign(DhTupleProverInput::random().into())
+                }
+            })
+            .collect();
+        let trees: Vec<(SigmaBoolean, UncheckedTree, &[u8])> = signed
+            .iter()
+            .map(|(sigma_tree, proof)| {
+                let tree = parse_sig_compute_challenges(sigma_tree, proof.clone()).unwrap();
+                (sigma_tree.clone(), compute_commitments(tree), MESSAGE)
+            })
+            .collect();
+        assert!(verify_batch(&trees).is_ok());
+        group.bench_with_input(
+            BenchmarkId::new("sequential", size),
+            &signed,
+            |b, signed| {
+                b.iter(|| {
+                    signed.iter().all(|(sigma_tree, proof)| {
+                        verify_signature(black_box(sigma_tree.clone()), MESSAGE, black_box(proof))
+                            .unwrap()
+                    })
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("batch", size), &trees, |b, trees| {
+            b.iter(|| verify_batch(black_box(trees)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, verify_dlog, verify_dht, batch);
+criterion_main!(benches);
@@ -226,7 +226,7 @@ mod tests {
         let encoder = AddressEncoder::new(NetworkPrefix::Mainnet);
         let addr = encoder.parse_address_from_str(p2s_addr_str).unwrap();
         let _ = addr.script().unwrap().proposition().unwrap();
-        // let ctx = Rc::new(force_any_val::<Context>());
+        // let ctx = Arc::new(force_any_val::<Context>());
         // let _ = reduce_to_crypto(&script, &Env::empty(), ctx).unwrap();
     }
 
@@ -280,7 +280,7 @@ mod tests {
         let encoder = AddressEncoder::new(NetworkPrefix::Mainnet);
         let addr = encoder.parse_address_from_str(p2s_str).unwrap();
         let _ = addr.script().unwrap().proposition().unwrap();
-        // let ctx = Rc::new(force_any_val::<Context>());
+        // let ctx = Arc::new(force_any_val::<Context>());
         // let res = reduce_to_crypto(&script, &Env::empty(), ctx).unwrap();
         // match res.sigma_prop {
         //     SigmaBoolean::TrivialProp(b) => assert!(b),
@@ -2,17 +2,19 @@
 use ergotree_ir::mir::constant::TryExtractInto;
 use ergotree_ir::sigma_protocol::sigma_boolean::SigmaProp;
 use std::fmt::Display;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use ergotree_ir::mir::expr::Expr;
 use ergotree_ir::mir::value::Value;
 use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
 
 use cost_accum::CostAccumulator;
+use costs::Cost;
 use ergotree_ir::types::smethod::SMethod;
 
 use self::context::Context;
 use self::env::Env;
+use self::trace::EvalTrace;
 
 /// Context(blockchain) for the interpreter
 pub mod context;
@@ -37,11 +39,14 @@ pub(crate) mod coll_filter;
 pub(crate) mod coll_fold;
 pub(crate) mod coll_forall;
 pub(crate) mod coll_map;
+pub(crate) mod coll_shift;
 pub(crate) mod coll_size;
 pub(crate) mod coll_slice;
 pub(crate) mod collection;
-pub(crate) mod cost_accum;
-pub(crate) mod costs;
+/// Cost accumulator
+pub mod cost_accum;
+/// JIT costs of ErgoTree operations
+pub mod costs;
 pub(crate) mod create_avl_tree;
 pub(crate) mod create_prove_dh_tuple;
 pub(crate) mod create_provedlog;
@@ -66,11 +71,13 @@ pub(crate) mod if_op;
 pub(crate) mod logical_not;
 pub(crate) mod long_to_byte_array;
 pub(crate) mod method_call;
+pub(crate) mod mod_q;
 pub(crate) mod multiply_group;
 pub(crate) mod negation;
 pub(crate) mod option_get;
 pub(crate) mod option_get_or_else;
 pub(crate) mod option_is_defined;
+pub(crate) mod option_some;
 pub(crate) mod or;
 pub(crate) mod property_call;
 pub(crate) mod savltree;
@@ -84,9 +91,12 @@ pub(crate) mod sheader;
 pub(crate) mod sigma_and;
 pub(crate) mod sigma_or;
 pub(crate) mod sigma_prop_bytes;
+pub(crate) mod sigma_prop_is_proven;
+pub(crate) mod snumeric;
 pub(crate) mod soption;
 pub(crate) mod spreheader;
 pub(crate) mod subst_const;
+pub mod trace;
 pub(crate) mod tree_lookup;
 pub(crate) mod tuple;
 pub(crate) mod upcast;
@@ -129,19 +139,38 @@ pub struct ReductionResult {
 pub fn reduce_to_crypto(
     expr: &Expr,
     env: &Env,
-    ctx: Rc<Context>,
+    ctx: Arc<Context>,
+) -> Result<ReductionResult, EvalError> {
+    reduce_to_crypto_with_cost(expr, env, ctx, 0, None)
+}
+
+/// Evaluate the given expression by reducing it to SigmaBoolean value.
+/// The reported cost includes `initial_cost` (i.e. the transaction initial cost and the cost of
+/// the already validated transaction inputs). Evaluation fails with
+/// [`EvalError::CostError`] if the accumulated cost exceeds `cost_limit`.
+/// Both `initial_cost` and `cost_limit` are in block cost units.
+pub fn reduce_to_crypto_with_cost(
+    expr: &Expr,
+    env: &Env,
+    ctx: Arc<Context>,
+    initial_cost: u64,
+    cost_limit: Option<u64>,
 ) -> Result<ReductionResult, EvalError> {
     let ctx_clone = ctx.clone();
-    fn inner(expr: &Expr, env: &Env, ctx: Rc<Context>) -> Result<ReductionResult, EvalError> {
-        let cost_accum = CostAccumulator::new(0, None);
+    let inner = |expr: &Expr, ctx: Arc<Context>| -> Result<ReductionResult, EvalError> {
+        let cost_accum = CostAccumulator::new(
+            Cost::from_block_cost(initial_cost),
+            cost_limit.map(Cost::from_block_cost),
+        );
         let mut ectx = EvalContext::new(ctx, cost_accum);
         let mut env_mut = env.clone();
         expr.eval(&mut env_mut, &mut ectx)
             .and_then(|v| -> Result<ReductionResult, EvalError> {
+                let cost = Cost::to_block_cost(ectx.cost_accum.total_cost());
                 match v {
                     Value::Boolean(b) => Ok(ReductionResult {
                         sigma_prop: SigmaBoolean::TrivialProp(b),
-                        cost: 0,
+                        cost,
                         diag: ReductionDiagnosticInfo {
                             env: env_mut.clone(),
                             pretty_printed_expr: None,
@@ -149,7 +178,7 @@ pub fn reduce_to_crypto(
                     }),
                     Value::SigmaProp(sp) => Ok(ReductionResult {
                         sigma_prop: sp.value().clone(),
-                        cost: 0,
+                        cost,
                         diag: ReductionDiagnosticInfo {
                             env: env_mut.clone(),
                             pretty_printed_expr: None,
@@ -158,9 +187,9 @@ pub fn reduce_to_crypto(
                     _ => Err(EvalError::InvalidResultType),
                 }
             })
-    }
+    };
 
-    let res = inner(expr, env, ctx);
+    let res = inner(expr, ctx);
     if let Ok(reduction) = res {
         if reduction.sigma_prop == SigmaBoolean::TrivialProp(false) {
             let (_, printed_expr_str) = expr
@@ -182,7 +211,7 @@ pub fn reduce_to_crypto(
     let (spanned_expr, printed_expr_str) = expr
         .pretty_print()
         .map_err(|e| EvalError::Misc(e.to_string()))?;
-    inner(&spanned_expr, env, ctx_clone)
+    inner(&spanned_expr, ctx_clone)
         .map_err(|e| e.wrap_spanned_with_src(printed_expr_str.to_string()))
 }
 
@@ -196,13 +225,27 @@ pub fn extract_sigma_boolean(expr: &Expr) -> Result<SigmaBoolean, EvalError> {
 
 #[derive(Debug)]
 pub(crate) struct EvalContext {
-    pub(crate) ctx: Rc<Context>,
+    pub(crate) ctx: Arc<Context>,
     pub(crate) cost_accum: CostAccumulator,
+    /// Evaluated expressions (recorded only if tracing is enabled)
+    pub(crate) trace: Option<EvalTrace>,
 }
 
 impl EvalContext {
-    pub fn new(ctx: Rc<Context>, cost_accum: CostAccumulator) -> Self {
-        EvalContext { ctx, cost_accum }
+    pub fn new(ctx: Arc<Context>, cost_accum: CostAccumulator) -> Self {
+        EvalContext {
+            ctx,
+            cost_accum,
+            trace: None,
+        }
+    }
+
+    /// Enable recording of the evaluated expressions
+    pub fn with_trace(self) -> Self {
+        EvalContext {
+            trace: Some(EvalTrace::default()),
+            ..self
+        }
     }
 }
 
@@ -213,6 +256,21 @@ pub(crate) trait Evaluable {
     fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError>;
 }
 
+/// Check that the method is available in the activated script version
+fn check_method_version(method: &SMethod, ectx: &EvalContext) -> Result<(), EvalError> {
+    let activated = ectx.ctx.activated_script_version();
+    if method.min_version() > activated {
+        return Err(EvalError::ScriptVersionError(format!(
+            "method {}.{} requires script version {}, activated version is {}",
+            method.obj_type.type_name(),
+            method.name(),
+            method.min_version(),
+            activated
+        )));
+    }
+    Ok(())
+}
+
 type EvalFn =
     fn(env: &mut Env, ctx: &mut EvalContext, Value, Vec<Value>) -> Result<Value, EvalError>;
 
@@ -250,6 +308,11 @@ fn smethod_eval_fn(method: &SMethod) -> Result<EvalFn, EvalError> {
             scontext::LAST_BLOCK_UTXO_ROOT_HASH_PROPERTY_METHOD_ID => {
                 self::scontext::LAST_BLOCK_UTXO_ROOT_HASH_EVAL_FN
             }
+            scontext::INPUTS_PROPERTY_METHOD_ID => self::scontext::INPUTS_EVAL_FN,
+            scontext::OUTPUTS_PROPERTY_METHOD_ID => self::scontext::OUTPUTS_EVAL_FN,
+            scontext::HEIGHT_PROPERTY_METHOD_ID => self::scontext::HEIGHT_EVAL_FN,
+            scontext::SELF_PROPERTY_METHOD_ID => self::scontext::SELF_EVAL_FN,
+            scontext::MINER_PUBKEY_PROPERTY_METHOD_ID => self::scontext::MINER_PUBKEY_EVAL_FN,
             method_id => {
                 return Err(EvalError::NotFound(format!(
                     "Eval fn: unknown method id in SContext: {:?}",
@@ -261,6 +324,21 @@ fn smethod_eval_fn(method: &SMethod) -> Result<EvalFn, EvalError> {
             sbox::VALUE_METHOD_ID => self::sbox::VALUE_EVAL_FN,
             sbox::GET_REG_METHOD_ID => self::sbox::GET_REG_EVAL_FN,
             sbox::TOKENS_METHOD_ID => self::sbox::TOKENS_EVAL_FN,
+            sbox::PROPOSITION_BYTES_METHOD_ID => self::sbox::PROPOSITION_BYTES_EVAL_FN,
+            sbox::BYTES_METHOD_ID => self::sbox::BYTES_EVAL_FN,
+            sbox::BYTES_WITHOUT_REF_METHOD_ID => self::sbox::BYTES_WITHOUT_REF_EVAL_FN,
+            sbox::ID_METHOD_ID => self::sbox::ID_EVAL_FN,
+            sbox::CREATION_INFO_METHOD_ID => self::sbox::CREATION_INFO_EVAL_FN,
+            sbox::R0_METHOD_ID => self::sbox::register_eval_fn::<0>,
+            sbox::R1_METHOD_ID => self::sbox::register_eval_fn::<1>,
+            sbox::R2_METHOD_ID => self::sbox::register_eval_fn::<2>,
+            sbox::R3_METHOD_ID => self::sbox::register_eval_fn::<3>,
+            sbox::R4_METHOD_ID => self::sbox::register_eval_fn::<4>,
+            sbox::R5_METHOD_ID => self::sbox::register_eval_fn::<5>,
+            sbox::R6_METHOD_ID => self::sbox::register_eval_fn::<6>,
+            sbox::R7_METHOD_ID => self::sbox::register_eval_fn::<7>,
+            sbox::R8_METHOD_ID => self::sbox::register_eval_fn::<8>,
+            sbox::R9_METHOD_ID => self::sbox::register_eval_fn::<9>,
             method_id => {
                 return Err(EvalError::NotFound(format!(
                     "Eval fn: unknown method id in SBox: {:?}",
@@ -276,6 +354,10 @@ fn smethod_eval_fn(method: &SMethod) -> Result<EvalFn, EvalError> {
             scoll::PATCH_METHOD_ID => self::scoll::PATCH_EVAL_FN,
             scoll::UPDATED_METHOD_ID => self::scoll::UPDATED_EVAL_FN,
             scoll::UPDATE_MANY_METHOD_ID => self::scoll::UPDATE_MANY_EVAL_FN,
+            scoll::REVERSE_METHOD_ID => self::scoll::REVERSE_EVAL_FN,
+            scoll::STARTS_WITH_METHOD_ID => self::scoll::STARTS_WITH_EVAL_FN,
+            scoll::ENDS_WITH_METHOD_ID => self::scoll::ENDS_WITH_EVAL_FN,
+            scoll::GET_METHOD_ID => self::scoll::GET_EVAL_FN,
             method_id => {
                 return Err(EvalError::NotFound(format!(
                     "Eval fn: unknown method id in SCollection: {:?}",
@@ -286,6 +368,8 @@ fn smethod_eval_fn(method: &SMethod) -> Result<EvalFn, EvalError> {
         sgroup_elem::TYPE_CODE => match method.method_id() {
             sgroup_elem::GET_ENCODED_METHOD_ID => self::sgroup_elem::GET_ENCODED_EVAL_FN,
             sgroup_elem::NEGATE_METHOD_ID => self::sgroup_elem::NEGATE_EVAL_FN,
+            sgroup_elem::EXPONENTIATE_METHOD_ID => self::sgroup_elem::EXPONENTIATE_EVAL_FN,
+            sgroup_elem::MULTIPLY_METHOD_ID => self::sgroup_elem::MULTIPLY_EVAL_FN,
             method_id => {
                 return Err(EvalError::NotFound(format!(
                     "Eval fn: unknown method id in SGroupElement: {:?}",
@@ -319,6 +403,7 @@ fn smethod_eval_fn(method: &SMethod) -> Result<EvalFn, EvalError> {
             sheader::POW_DISTANCE_METHOD_ID => self::sheader::POW_DISTANCE_EVAL_FN,
             sheader::POW_NONCE_METHOD_ID => self::sheader::POW_NONCE_EVAL_FN,
             sheader::VOTES_METHOD_ID => self::sheader::VOTES_EVAL_FN,
+            sheader::CHECK_POW_METHOD_ID => self::sheader::CHECK_POW_EVAL_FN,
             method_id => {
                 return Err(EvalError::NotFound(format!(
                     "Eval fn: method {:?} with method id {:?} not found in SHeader",
@@ -346,6 +431,23 @@ fn smethod_eval_fn(method: &SMethod) -> Result<EvalFn, EvalError> {
         sglobal::TYPE_CODE => match method.method_id() {
             sglobal::GROUP_GENERATOR_METHOD_ID => self::sglobal::GROUP_GENERATOR_EVAL_FN,
             sglobal::XOR_METHOD_ID => self::sglobal::XOR_EVAL_FN,
+            sglobal::SERIALIZE_METHOD_ID => self::sglobal::SERIALIZE_EVAL_FN,
+            sglobal::FROM_BIG_ENDIAN_BYTES_METHOD_ID => match *method.tpe().t_range {
+                stype::SType::SByte => self::sglobal::FROM_BIG_ENDIAN_BYTES_BYTE_EVAL_FN,
+                stype::SType::SShort => self::sglobal::FROM_BIG_ENDIAN_BYTES_SHORT_EVAL_FN,
+                stype::SType::SInt => self::sglobal::FROM_BIG_ENDIAN_BYTES_INT_EVAL_FN,
+                stype::SType::SLong => self::sglobal::FROM_BIG_ENDIAN_BYTES_LONG_EVAL_FN,
+                stype::SType::SBigInt => self::sglobal::FROM_BIG_ENDIAN_BYTES_BIGINT_EVAL_FN,
+                stype::SType::SUnsignedBigInt => {
+                    self::sglobal::FROM_BIG_ENDIAN_BYTES_UNSIGNED_BIGINT_EVAL_FN
+                }
+                ref tpe => {
+                    return Err(EvalError::NotFound(format!(
+                        "Eval fn: fromBigEndianBytes is not defined for {:?}",
+                        tpe
+                    )))
+                }
+            },
             method_id => {
                 return Err(EvalError::NotFound(format!(
                     "Eval fn: method {:?} with method id {:?} not found in SGlobal",
@@ -354,6 +456,34 @@ fn smethod_eval_fn(method: &SMethod) -> Result<EvalFn, EvalError> {
                 )))
             }
         },
+        snumeric::BYTE_TYPE_CODE
+        | snumeric::SHORT_TYPE_CODE
+        | snumeric::INT_TYPE_CODE
+        | snumeric::LONG_TYPE_CODE
+        | snumeric::BIGINT_TYPE_CODE
+        | snumeric::UNSIGNED_BIGINT_TYPE_CODE => match method.method_id() {
+            snumeric::TO_BYTE_METHOD_ID => self::snumeric::TO_BYTE_EVAL_FN,
+            snumeric::TO_SHORT_METHOD_ID => self::snumeric::TO_SHORT_EVAL_FN,
+            snumeric::TO_INT_METHOD_ID => self::snumeric::TO_INT_EVAL_FN,
+            snumeric::TO_LONG_METHOD_ID => self::snumeric::TO_LONG_EVAL_FN,
+            snumeric::TO_BIGINT_METHOD_ID => self::snumeric::TO_BIGINT_EVAL_FN,
+            snumeric::TO_BYTES_METHOD_ID => self::snumeric::TO_BYTES_EVAL_FN,
+            snumeric::TO_BITS_METHOD_ID => self::snumeric::TO_BITS_EVAL_FN,
+            snumeric::BITWISE_INVERSE_METHOD_ID => self::snumeric::BITWISE_INVERSE_EVAL_FN,
+            snumeric::BITWISE_OR_METHOD_ID => self::snumeric::BITWISE_OR_EVAL_FN,
+            snumeric::BITWISE_AND_METHOD_ID => self::snumeric::BITWISE_AND_EVAL_FN,
+            snumeric::BITWISE_XOR_METHOD_ID => self::snumeric::BITWISE_XOR_EVAL_FN,
+            snumeric::SHIFT_LEFT_METHOD_ID => self::snumeric::SHIFT_LEFT_EVAL_FN,
+            snumeric::SHIFT_RIGHT_METHOD_ID => self::snumeric::SHIFT_RIGHT_EVAL_FN,
+            method_id => {
+                return Err(EvalError::NotFound(format!(
+                    "Eval fn: method {:?} with method id {:?} not found in {}",
+                    method.name(),
+                    method_id,
+                    method.obj_type.type_name(),
+                )))
+            }
+        },
         type_id => {
             return Err(EvalError::NotFound(format!(
                 "Eval fn: unknown type id {:?}",
@@ -373,24 +503,28 @@ pub(crate) mod tests {
 
     use super::env::Env;
     use super::*;
+    use ergotree_ir::ergo_tree::ErgoTree;
+    use ergotree_ir::ergo_tree::ErgoTreeVersion;
     use ergotree_ir::mir::bin_op::BinOp;
     use ergotree_ir::mir::bin_op::BinOpKind;
     use ergotree_ir::mir::bin_op::RelationOp;
     use ergotree_ir::mir::block::BlockValue;
     use ergotree_ir::mir::constant::TryExtractFrom;
     use ergotree_ir::mir::constant::TryExtractInto;
+    use ergotree_ir::mir::global_vars::GlobalVars;
     use ergotree_ir::mir::val_def::ValDef;
     use ergotree_ir::mir::val_use::ValUse;
+    use ergotree_ir::serialization::SigmaSerializable;
     use ergotree_ir::types::stype::SType;
     use expect_test::expect;
     use sigma_test_util::force_any_val;
 
     pub fn eval_out_wo_ctx<T: TryExtractFrom<Value>>(expr: &Expr) -> T {
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         eval_out(expr, ctx)
     }
 
-    pub fn eval_out<T: TryExtractFrom<Value>>(expr: &Expr, ctx: Rc<Context>) -> T {
+    pub fn eval_out<T: TryExtractFrom<Value>>(expr: &Expr, ctx: Arc<Context>) -> T {
         let cost_accum = CostAccumulator::new(0, None);
         let mut ectx = EvalContext::new(ctx, cost_accum);
         let mut env = Env::empty();
@@ -402,7 +536,7 @@ pub(crate) mod tests {
 
     pub fn try_eval_out<T: TryExtractFrom<Value>>(
         expr: &Expr,
-        ctx: Rc<Context>,
+        ctx: Arc<Context>,
     ) -> Result<T, EvalError> {
         let cost_accum = CostAccumulator::new(0, None);
         let mut ectx = EvalContext::new(ctx, cost_accum);
@@ -412,10 +546,45 @@ pub(crate) mod tests {
     }
 
     pub fn try_eval_out_wo_ctx<T: TryExtractFrom<Value>>(expr: &Expr) -> Result<T, EvalError> {
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         try_eval_out(expr, ctx)
     }
 
+    /// Arbitrary context with the given activated script version
+    pub fn force_any_ctx_with_version(version: ErgoTreeVersion) -> Context {
+        let mut ctx = force_any_val::<Context>();
+        ctx.pre_header.version = version.value() + 1;
+        ctx
+    }
+
+    pub fn eval_out_v6<T: TryExtractFrom<Value>>(expr: &Expr) -> T {
+        let ctx = force_any_ctx_with_version(ErgoTreeVersion::V6_SOFT_FORK_VERSION);
+        eval_out(expr, Arc::new(ctx))
+    }
+
+    pub fn try_eval_out_v6<T: TryExtractFrom<Value>>(expr: &Expr) -> Result<T, EvalError> {
+        let ctx = force_any_ctx_with_version(ErgoTreeVersion::V6_SOFT_FORK_VERSION);
+        try_eval_out(expr, Arc::new(ctx))
+    }
+
+    #[test]
+    fn reduction_cost() {
+        let expr: Expr = BinOp {
+            kind: BinOpKind::Relation(RelationOp::Gt),
+            left: Box::new(GlobalVars::Height.into()),
+            right: Box::new(100i32.into()),
+        }
+        .into();
+        let ctx = Arc::new(force_any_val::<Context>());
+        let res = reduce_to_crypto(&expr, &Env::empty(), ctx.clone()).unwrap();
+        // (HEIGHT (26) + Constant (5) + GT (20)) / 10
+        assert_eq!(res.cost, 5);
+        let res_with_initial_cost =
+            reduce_to_crypto_with_cost(&expr, &Env::empty(), ctx.clone(), 1000, None).unwrap();
+        assert_eq!(res_with_initial_cost.cost, 1005);
+        assert!(reduce_to_crypto_with_cost(&expr, &Env::empty(), ctx, 0, Some(4)).is_err());
+    }
+
     #[test]
     fn diag_on_reduced_to_false() {
         let bin_op: Expr = BinOp {
@@ -441,7 +610,7 @@ pub(crate) mod tests {
             }
             .into(),
         );
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let res = reduce_to_crypto(&block, &Env::empty(), ctx).unwrap();
         assert!(res.sigma_prop == SigmaBoolean::TrivialProp(false));
         expect![[r#"
@@ -456,4 +625,53 @@ pub(crate) mod tests {
         "#]]
         .assert_eq(&res.diag.to_string());
     }
+
+    #[test]
+    fn eval_tree_with_v4_opcodes() {
+        // see ergotree_ir::serialization::expr::tests::parse_tree_with_v4_opcodes
+        let tree_bytes = base16::decode("00d1edcfd10101ede6de0402ed93e806012a06010106012bed93f7040204020404edefe6df0493d801d70101670154040272010402").unwrap();
+        let tree = ErgoTree::sigma_parse_bytes(&tree_bytes).unwrap();
+        let ctx = Arc::new(force_any_val::<Context>());
+        let res = reduce_to_crypto(&tree.proposition().unwrap(), &Env::empty(), ctx).unwrap();
+        assert_eq!(res.sigma_prop, SigmaBoolean::TrivialProp(true));
+    }
+
+    #[test]
+    fn optimized_tree_reduces_to_the_same_result() {
+        use ergoscript_compiler::compiler::compile_expr;
+        use ergoscript_compiler::script_env::ScriptEnv;
+        use ergotree_ir::optimizer::optimize;
+
+        let sources = [
+            "{ val a = HEIGHT + 1; val unused = HEIGHT > 2; sigmaProp(a > 1 && a < 100000000) }",
+            "{ val x = 2 * 3 + 4; sigmaProp(HEIGHT > x || OUTPUTS.size > x) }",
+            "{ val out = OUTPUTS(0); sigmaProp(if (HEIGHT > 10) out.value > 1L else false) }",
+            "sigmaProp(INPUTS(0).value + SELF.value > 1L && INPUTS(0).value + SELF.value < 2L)",
+            "sigmaProp(OUTPUTS.exists({ (b: Box) => b.value > SELF.value && b.value < SELF.value * 2L }))",
+            "sigmaProp(2147483647 + 1 > HEIGHT)",
+            "sigmaProp(SELF.propositionBytes == fromBase16(\"07070707070707070707070707070707070707070707070707070707070707070707\") || OUTPUTS(0).propositionBytes == fromBase16(\"07070707070707070707070707070707070707070707070707070707070707070707\"))",
+            "sigmaProp(SELF.propositionBytes != fromBase16(\"0102030405060708090a0b0c0d0e0f10\") && OUTPUTS(0).propositionBytes != fromBase16(\"0102030405060708090a0b0c0d0e0f10\"))",
+        ];
+        for _ in 0..10 {
+            let ctx = Arc::new(force_any_val::<Context>());
+            for source in sources {
+                let expr = compile_expr(source, ScriptEnv::new()).unwrap();
+                let optimized = optimize(expr.clone());
+                let res = reduce_to_crypto(&expr, &Env::empty(), ctx.clone());
+                let optimized_res = reduce_to_crypto(&optimized, &Env::empty(), ctx.clone());
+                assert_eq!(
+                    res.is_ok(),
+                    optimized_res.is_ok(),
+                    "{}: {:?} vs {:?}",
+                    source,
+                    res,
+                    optimized_res
+                );
+                if let (Ok(res), Ok(optimized_res)) = (res, optimized_res) {
+                    assert_eq!(optimized_res.sigma_prop, res.sigma_prop, "{}", source);
+                    assert!(optimized_res.cost <= res.cost, "{}", source);
+                }
+            }
+        }
+    }
 }
@@ -2,6 +2,7 @@ use ergotree_ir::mir::and::And;
 use ergotree_ir::mir::constant::TryExtractInto;
 use ergotree_ir::mir::value::Value;
 
+use crate::eval::costs::Costs;
 use crate::eval::env::Env;
 use crate::eval::EvalContext;
 use crate::eval::EvalError;
@@ -10,6 +11,9 @@ use crate::eval::Evaluable;
 impl Evaluable for And {
     fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
         let input_v = self.input.eval(env, ctx)?;
+        if let Value::Coll(coll) = &input_v {
+            ctx.cost_accum.add_seq_cost(Costs::AND, coll.len())?;
+        }
         let input_v_bools = input_v.try_extract_into::<Vec<bool>>()?;
         Ok(input_v_bools.iter().all(|b| *b).into())
     }
@@ -18,7 +22,7 @@ impl Evaluable for And {
 #[allow(clippy::panic)]
 #[cfg(test)]
 mod tests {
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     use crate::eval::context::Context;
     use crate::eval::tests::eval_out;
@@ -35,7 +39,7 @@ mod tests {
         #[test]
         fn eval(bools in collection::vec(any::<bool>(), 0..10)) {
             let expr: Expr = And {input: Expr::Const(bools.clone().into()).into()}.into();
-            let ctx = Rc::new(force_any_val::<Context>());
+            let ctx = Arc::new(force_any_val::<Context>());
             let res = eval_out::<bool>(&expr, ctx);
             prop_assert_eq!(res, bools.iter().all(|b| *b));
         }
@@ -8,13 +8,18 @@ use ergotree_ir::mir::bin_op::{BinOp, BitOp};
 use ergotree_ir::mir::constant::TryExtractFrom;
 use ergotree_ir::mir::constant::TryExtractInto;
 use ergotree_ir::mir::value::Value;
+use ergotree_ir::unsignedbigint256::UnsignedBigInt256;
 use eval::costs::Costs;
+use num_bigint::BigInt;
 use num_traits::CheckedAdd;
 use num_traits::CheckedDiv;
 use num_traits::CheckedMul;
 use num_traits::CheckedRem;
 use num_traits::CheckedSub;
 use num_traits::Num;
+use num_traits::PrimInt;
+use num_traits::ToPrimitive;
+use std::convert::TryFrom;
 
 use crate::eval;
 use crate::eval::env::Env;
@@ -98,6 +103,40 @@ where
     Ok(op(lv_raw, rv_raw).into())
 }
 
+fn shift_amount_err<T: std::fmt::Display>(rv_raw: T, bits: u32) -> EvalError {
+    EvalError::ArithmeticException(format!(
+        "shift amount {0} is out of range [0, {1})",
+        rv_raw, bits
+    ))
+}
+
+fn eval_bit_shift<T, F>(lv_raw: T, rv: Value, op: F) -> Result<Value, EvalError>
+where
+    T: PrimInt + TryExtractFrom<Value> + Into<Value> + std::fmt::Display,
+    F: FnOnce(T, u32) -> T,
+{
+    let rv_raw = rv.try_extract_into::<T>()?;
+    let bits = T::zero().count_zeros();
+    match rv_raw.to_u32() {
+        Some(n) if n < bits => Ok(op(lv_raw, n).into()),
+        _ => Err(shift_amount_err(rv_raw, bits)),
+    }
+}
+
+fn eval_bigint_shift<F>(lv_raw: BigInt256, rv: Value, op: F) -> Result<Value, EvalError>
+where
+    F: FnOnce(BigInt, usize) -> BigInt,
+{
+    let rv_raw = rv.try_extract_into::<BigInt256>()?;
+    let bits = 256;
+    match BigInt::from(rv_raw.clone()).to_u32() {
+        Some(n) if n < bits => BigInt256::try_from(op(lv_raw.into(), n as usize))
+            .map(Value::BigInt)
+            .map_err(EvalError::ArithmeticException),
+        _ => Err(shift_amount_err(rv_raw, bits)),
+    }
+}
+
 fn eval_ge(lv: Value, rv: Value) -> Result<Value, EvalError> {
     match lv {
         Value::Byte(lv_raw) => Ok((lv_raw >= rv.try_extract_into::<i8>()?).into()),
@@ -105,6 +144,9 @@ fn eval_ge(lv: Value, rv: Value) -> Result<Value, EvalError> {
         Value::Int(lv_raw) => Ok((lv_raw >= rv.try_extract_into::<i32>()?).into()),
         Value::Long(lv_raw) => Ok((lv_raw >= rv.try_extract_into::<i64>()?).into()),
         Value::BigInt(lv_raw) => Ok((lv_raw >= rv.try_extract_into::<BigInt256>()?).into()),
+        Value::UnsignedBigInt(lv_raw) => {
+            Ok((lv_raw >= rv.try_extract_into::<UnsignedBigInt256>()?).into())
+        }
         _ => Err(EvalError::UnexpectedValue(format!(
             "expected BinOp::left to be numeric value, got {0:?}",
             lv
@@ -119,6 +161,9 @@ fn eval_gt(lv: Value, rv: Value) -> Result<Value, EvalError> {
         Value::Int(lv_raw) => Ok((lv_raw > rv.try_extract_into::<i32>()?).into()),
         Value::Long(lv_raw) => Ok((lv_raw > rv.try_extract_into::<i64>()?).into()),
         Value::BigInt(lv_raw) => Ok((lv_raw > rv.try_extract_into::<BigInt256>()?).into()),
+        Value::UnsignedBigInt(lv_raw) => {
+            Ok((lv_raw > rv.try_extract_into::<UnsignedBigInt256>()?).into())
+        }
         _ => Err(EvalError::UnexpectedValue(format!(
             "expected BinOp::left to be numeric value, got {0:?}",
             lv
@@ -133,6 +178,9 @@ fn eval_lt(lv: Value, rv: Value) -> Result<Value, EvalError> {
         Value::Int(lv_raw) => Ok((lv_raw < rv.try_extract_into::<i32>()?).into()),
         Value::Long(lv_raw) => Ok((lv_raw < rv.try_extract_into::<i64>()?).into()),
         Value::BigInt(lv_raw) => Ok((lv_raw < rv.try_extract_into::<BigInt256>()?).into()),
+        Value::UnsignedBigInt(lv_raw) => {
+            Ok((lv_raw < rv.try_extract_into::<UnsignedBigInt256>()?).into())
+        }
         _ => Err(EvalError::UnexpectedValue(format!(
             "expected BinOp::left to be numeric value, got {0:?}",
             lv
@@ -147,6 +195,9 @@ fn eval_le(lv: Value, rv: Value) -> Result<Value, EvalError> {
         Value::Int(lv_raw) => Ok((lv_raw <= rv.try_extract_into::<i32>()?).into()),
         Value::Long(lv_raw) => Ok((lv_raw <= rv.try_extract_into::<i64>()?).into()),
         Value::BigInt(lv_raw) => Ok((lv_raw <= rv.try_extract_into::<BigInt256>()?).into()),
+        Value::UnsignedBigInt(lv_raw) => {
+            Ok((lv_raw <= rv.try_extract_into::<UnsignedBigInt256>()?).into())
+        }
         _ => Err(EvalError::UnexpectedValue(format!(
             "expected BinOp::left to be numeric value, got {0:?}",
             lv
@@ -172,7 +223,6 @@ where
 
 impl Evaluable for BinOp {
     fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
-        ctx.cost_accum.add(Costs::DEFAULT.eq_const_size)?;
         let lv = self.left.eval(env, ctx)?;
         // using closure to keep right value from evaluation (for lazy AND, OR, XOR)
         let mut rv = || self.right.eval(env, ctx);
@@ -193,8 +243,16 @@ impl Evaluable for BinOp {
                 )),
             },
             BinOpKind::Relation(op) => match op {
-                RelationOp::Eq => Ok(Value::Boolean(lv == rv()?)),
-                RelationOp::NEq => Ok(Value::Boolean(lv != rv()?)),
+                RelationOp::Eq => {
+                    let rv = rv()?;
+                    ctx.cost_accum.add(Costs::equality_cost(&lv, &rv))?;
+                    Ok(Value::Boolean(lv == rv))
+                }
+                RelationOp::NEq => {
+                    let rv = rv()?;
+                    ctx.cost_accum.add(Costs::equality_cost(&lv, &rv))?;
+                    Ok(Value::Boolean(lv != rv))
+                }
                 RelationOp::Gt => eval_gt(lv, rv()?),
                 RelationOp::Lt => eval_lt(lv, rv()?),
                 RelationOp::Ge => eval_ge(lv, rv()?),
@@ -207,6 +265,7 @@ impl Evaluable for BinOp {
                     Value::Int(lv_raw) => eval_plus(lv_raw, rv()?),
                     Value::Long(lv_raw) => eval_plus(lv_raw, rv()?),
                     Value::BigInt(lv_raw) => eval_plus(lv_raw, rv()?),
+                    Value::UnsignedBigInt(lv_raw) => eval_plus(lv_raw, rv()?),
                     _ => Err(EvalError::UnexpectedValue(format!(
                         "expected BinOp::left to be numeric value, got {0:?}",
                         lv
@@ -218,6 +277,7 @@ impl Evaluable for BinOp {
                     Value::Int(lv_raw) => eval_minus(lv_raw, rv()?),
                     Value::Long(lv_raw) => eval_minus(lv_raw, rv()?),
                     Value::BigInt(lv_raw) => eval_minus(lv_raw, rv()?),
+                    Value::UnsignedBigInt(lv_raw) => eval_minus(lv_raw, rv()?),
                     _ => Err(EvalError::UnexpectedValue(format!(
                         "expected BinOp::left to be numeric value, got {0:?}",
                         lv
@@ -229,6 +289,7 @@ impl Evaluable for BinOp {
                     Value::Int(lv_raw) => eval_mul(lv_raw, rv()?),
                     Value::Long(lv_raw) => eval_mul(lv_raw, rv()?),
                     Value::BigInt(lv_raw) => eval_mul(lv_raw, rv()?),
+                    Value::UnsignedBigInt(lv_raw) => eval_mul(lv_raw, rv()?),
                     _ => Err(EvalError::UnexpectedValue(format!(
                         "expected BinOp::left to be numeric value, got {0:?}",
                         lv
@@ -241,6 +302,7 @@ impl Evaluable for BinOp {
                     Value::Long(lv_raw) => eval_div(lv_raw, rv()?),
                     // MIN / -1  can actually overflow
                     Value::BigInt(lv_raw) => eval_div(lv_raw, rv()?),
+                    Value::UnsignedBigInt(lv_raw) => eval_div(lv_raw, rv()?),
                     _ => Err(EvalError::UnexpectedValue(format!(
                         "expected BinOp::left to be numeric value, got {0:?}",
                         lv
@@ -252,6 +314,7 @@ impl Evaluable for BinOp {
                     Value::Int(lv_raw) => eval_max(lv_raw, rv()?),
                     Value::Long(lv_raw) => eval_max(lv_raw, rv()?),
                     Value::BigInt(lv_raw) => eval_max(lv_raw, rv()?),
+                    Value::UnsignedBigInt(lv_raw) => eval_max(lv_raw, rv()?),
                     _ => Err(EvalError::UnexpectedValue(format!(
                         "expected BinOp::left to be numeric value, got {0:?}",
                         lv
@@ -263,6 +326,7 @@ impl Evaluable for BinOp {
                     Value::Int(lv_raw) => eval_min(lv_raw, rv()?),
                     Value::Long(lv_raw) => eval_min(lv_raw, rv()?),
                     Value::BigInt(lv_raw) => eval_min(lv_raw, rv()?),
+                    Value::UnsignedBigInt(lv_raw) => eval_min(lv_raw, rv()?),
                     _ => Err(EvalError::UnexpectedValue(format!(
                         "expected BinOp::left to be numeric value, got {0:?}",
                         lv
@@ -274,6 +338,7 @@ impl Evaluable for BinOp {
                     Value::Int(lv_raw) => eval_mod(lv_raw, rv()?),
                     Value::Long(lv_raw) => eval_mod(lv_raw, rv()?),
                     Value::BigInt(lv_raw) => eval_mod(lv_raw, rv()?),
+                    Value::UnsignedBigInt(lv_raw) => eval_mod(lv_raw, rv()?),
                     _ => Err(EvalError::UnexpectedValue(format!(
                         "expected BinOp::left to be numeric value, got {0:?}",
                         lv
@@ -287,6 +352,7 @@ impl Evaluable for BinOp {
                     Value::Int(lv_raw) => eval_bit_op(lv_raw, rv()?, |l, r| l & r),
                     Value::Long(lv_raw) => eval_bit_op(lv_raw, rv()?, |l, r| l & r),
                     Value::BigInt(lv_raw) => eval_bit_op(lv_raw, rv()?, |l, r| l & r),
+                    Value::UnsignedBigInt(lv_raw) => eval_bit_op(lv_raw, rv()?, |l, r| &l & &r),
                     _ => Err(EvalError::UnexpectedValue(format!(
                         "expected BinOp::left to be numeric value, got {0:?}",
                         lv
@@ -298,6 +364,7 @@ impl Evaluable for BinOp {
                     Value::Int(lv_raw) => eval_bit_op(lv_raw, rv()?, |l, r| l | r),
                     Value::Long(lv_raw) => eval_bit_op(lv_raw, rv()?, |l, r| l | r),
                     Value::BigInt(lv_raw) => eval_bit_op(lv_raw, rv()?, |l, r| l | r),
+                    Value::UnsignedBigInt(lv_raw) => eval_bit_op(lv_raw, rv()?, |l, r| &l | &r),
                     _ => Err(EvalError::UnexpectedValue(format!(
                         "expected BinOp::left to be numeric value, got {0:?}",
                         lv
@@ -309,11 +376,44 @@ impl Evaluable for BinOp {
                     Value::Int(lv_raw) => eval_bit_op(lv_raw, rv()?, |l, r| l ^ r),
                     Value::Long(lv_raw) => eval_bit_op(lv_raw, rv()?, |l, r| l ^ r),
                     Value::BigInt(lv_raw) => eval_bit_op(lv_raw, rv()?, |l, r| l ^ r),
+                    Value::UnsignedBigInt(lv_raw) => eval_bit_op(lv_raw, rv()?, |l, r| &l ^ &r),
                     _ => Err(EvalError::UnexpectedValue(format!(
                         "expected BinOp::left to be numeric value, got {0:?}",
                         lv
                     ))),
                 },
+                BitOp::BitShiftLeft => match lv {
+                    Value::Byte(lv_raw) => eval_bit_shift(lv_raw, rv()?, |l, n| l.signed_shl(n)),
+                    Value::Short(lv_raw) => eval_bit_shift(lv_raw, rv()?, |l, n| l.signed_shl(n)),
+                    Value::Int(lv_raw) => eval_bit_shift(lv_raw, rv()?, |l, n| l.signed_shl(n)),
+                    Value::Long(lv_raw) => eval_bit_shift(lv_raw, rv()?, |l, n| l.signed_shl(n)),
+                    Value::BigInt(lv_raw) => eval_bigint_shift(lv_raw, rv()?, |l, n| l << n),
+                    _ => Err(EvalError::UnexpectedValue(format!(
+                        "expected BinOp::left to be numeric value, got {0:?}",
+                        lv
+                    ))),
+                },
+                BitOp::BitShiftRight => match lv {
+                    Value::Byte(lv_raw) => eval_bit_shift(lv_raw, rv()?, |l, n| l.signed_shr(n)),
+                    Value::Short(lv_raw) => eval_bit_shift(lv_raw, rv()?, |l, n| l.signed_shr(n)),
+                    Value::Int(lv_raw) => eval_bit_shift(lv_raw, rv()?, |l, n| l.signed_shr(n)),
+                    Value::Long(lv_raw) => eval_bit_shift(lv_raw, rv()?, |l, n| l.signed_shr(n)),
+                    Value::BigInt(lv_raw) => eval_bigint_shift(lv_raw, rv()?, |l, n| l >> n),
+                    _ => Err(EvalError::UnexpectedValue(format!(
+                        "expected BinOp::left to be numeric value, got {0:?}",
+                        lv
+                    ))),
+                },
+                BitOp::BitShiftRightZeroed => match lv {
+                    Value::Byte(lv_raw) => eval_bit_shift(lv_raw, rv()?, |l, n| l.unsigned_shr(n)),
+                    Value::Short(lv_raw) => eval_bit_shift(lv_raw, rv()?, |l, n| l.unsigned_shr(n)),
+                    Value::Int(lv_raw) => eval_bit_shift(lv_raw, rv()?, |l, n| l.unsigned_shr(n)),
+                    Value::Long(lv_raw) => eval_bit_shift(lv_raw, rv()?, |l, n| l.unsigned_shr(n)),
+                    _ => Err(EvalError::UnexpectedValue(format!(
+                        "expected BinOp::left to be Byte, Short, Int or Long value, got {0:?}",
+                        lv
+                    ))),
+                },
             },
         }
     }
@@ -332,7 +432,7 @@ mod tests {
     use num_traits::Bounded;
     use proptest::prelude::*;
     use sigma_test_util::force_any_val;
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     fn check_eq_neq(left: Constant, right: Constant) -> bool {
         let eq_op: Expr = BinOp {
@@ -341,14 +441,14 @@ mod tests {
             right: Box::new(right.clone().into()),
         }
         .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let neq_op: Expr = BinOp {
             kind: BinOpKind::Relation(RelationOp::NEq),
             left: Box::new(left.into()),
             right: Box::new(right.into()),
         }
         .into();
-        let ctx1 = Rc::new(force_any_val::<Context>());
+        let ctx1 = Arc::new(force_any_val::<Context>());
         eval_out::<bool>(&eq_op, ctx) && !eval_out::<bool>(&neq_op, ctx1)
     }
 
@@ -417,7 +517,7 @@ mod tests {
             ),
         }
         .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         assert!(eval_out::<bool>(&e, ctx));
     }
 
@@ -437,7 +537,7 @@ mod tests {
             ),
         }
         .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         assert!(!eval_out::<bool>(&e, ctx));
     }
 
@@ -452,7 +552,7 @@ mod tests {
             right: Box::new(right.into().into()),
         }
         .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         try_eval_out::<T>(&expr, ctx)
     }
 
@@ -467,7 +567,7 @@ mod tests {
             right: Box::new(right.into().into()),
         }
         .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         try_eval_out::<T>(&expr, ctx)
     }
 
@@ -478,7 +578,7 @@ mod tests {
             right: Box::new(right.into().into()),
         }
         .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         eval_out::<bool>(&expr, ctx)
     }
 
@@ -489,7 +589,7 @@ mod tests {
             right: Box::new(right.into().into()),
         }
         .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         eval_out::<bool>(&expr, ctx)
     }
 
@@ -531,6 +631,29 @@ mod tests {
         assert_eq!(eval_bit_op(BitOp::BitAnd, max(), min()), Ok(b(0)));
         assert_eq!(eval_bit_op(BitOp::BitOr, max(), min()), Ok(b(-1)));
         assert_eq!(eval_bit_op(BitOp::BitXor, max(), min()), Ok(b(-1)));
+
+        assert!(eval_bit_op(BitOp::BitShiftLeft, max(), b(1)).is_err());
+        assert_eq!(eval_bit_op(BitOp::BitShiftLeft, b(1), b(8)), Ok(b(256)));
+        assert_eq!(eval_bit_op(BitOp::BitShiftRight, min(), b(255)), Ok(b(-1)));
+        assert_eq!(eval_bit_op(BitOp::BitShiftRight, b(-3), b(1)), Ok(b(-2)));
+        assert!(eval_bit_op(BitOp::BitShiftRight, b(1), b(256)).is_err());
+        assert!(eval_bit_op(BitOp::BitShiftRightZeroed, b(1), b(1)).is_err());
+    }
+
+    #[test]
+    fn test_bit_shifts() {
+        assert_eq!(eval_bit_op(BitOp::BitShiftLeft, 1i8, 7i8), Ok(i8::MIN));
+        assert_eq!(eval_bit_op(BitOp::BitShiftRight, -128i8, 7i8), Ok(-1i8));
+        assert_eq!(
+            eval_bit_op(BitOp::BitShiftRightZeroed, -128i8, 7i8),
+            Ok(1i8)
+        );
+        assert_eq!(
+            eval_bit_op(BitOp::BitShiftRightZeroed, -1i64, 63i64),
+            Ok(1i64)
+        );
+        assert!(eval_bit_op(BitOp::BitShiftLeft, 1i32, 32i32).is_err());
+        assert!(eval_bit_op(BitOp::BitShiftRight, 1i16, -1i16).is_err());
     }
 
     proptest! {
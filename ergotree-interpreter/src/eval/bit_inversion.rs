@@ -15,6 +15,7 @@ impl Evaluable for BitInversion {
             Value::Int(v) => Ok(Value::Int(!v)),
             Value::Long(v) => Ok(Value::Long(!v)),
             Value::BigInt(v) => Ok(Value::BigInt(!v)),
+            Value::UnsignedBigInt(v) => Ok(Value::UnsignedBigInt(!v)),
             _ => Err(EvalError::UnexpectedValue(format!(
                 "Expected BitInversion input to be numeric value, got {:?}",
                 input_v
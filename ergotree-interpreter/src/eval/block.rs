@@ -1,8 +1,7 @@
 use ergotree_ir::mir::block::BlockValue;
-use ergotree_ir::mir::constant::TryExtractInto;
-use ergotree_ir::mir::val_def::ValDef;
+use ergotree_ir::mir::expr::Expr;
+use ergotree_ir::mir::val_def::ValId;
 use ergotree_ir::mir::value::Value;
-use ergotree_ir::source_span::Spanned;
 use hashbrown::HashMap;
 
 use crate::eval::env::Env;
@@ -10,6 +9,18 @@ use crate::eval::EvalContext;
 use crate::eval::EvalError;
 use crate::eval::Evaluable;
 
+/// Variable id and bound expression of the block statement (`ValDef` or `FunDef`)
+fn block_item_parts(item: &Expr) -> Result<(ValId, &Expr), EvalError> {
+    match item {
+        Expr::ValDef(val_def) => Ok((val_def.expr().id, val_def.expr().rhs.as_ref())),
+        Expr::FunDef(fun_def) => Ok((fun_def.id, fun_def.rhs.as_ref())),
+        _ => Err(EvalError::UnexpectedExpr(format!(
+            "expected BlockValue item to be ValDef or FunDef, got {:?}",
+            item
+        ))),
+    }
+}
+
 impl Evaluable for BlockValue {
     fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
         // The start of the top-level block of statements does not contain any
@@ -18,11 +29,9 @@ impl Evaluable for BlockValue {
 
         if is_top_level_block {
             for i in &self.items {
-                // TODO: new try_extract_spanned_into?
-                let spanned_val_def = &i.clone().try_extract_into::<Spanned<ValDef>>()?;
-                let val_def = spanned_val_def.expr();
-                let v: Value = val_def.rhs.eval(env, ctx)?;
-                env.insert(val_def.id, v);
+                let (idx, rhs) = block_item_parts(i)?;
+                let v: Value = rhs.eval(env, ctx)?;
+                env.insert(idx, v);
             }
             // Keep all `ValDef`s introduced in this block
             self.result.eval(env, ctx)
@@ -31,11 +40,8 @@ impl Evaluable for BlockValue {
             let mut new_variables = vec![];
 
             for i in &self.items {
-                // TODO: new try_extract_spanned_into?
-                let spanned_val_def = &i.clone().try_extract_into::<Spanned<ValDef>>()?;
-                let val_def = spanned_val_def.expr();
-                let idx = val_def.id;
-                let v: Value = val_def.rhs.eval(env, ctx)?;
+                let (idx, rhs) = block_item_parts(i)?;
+                let v: Value = rhs.eval(env, ctx)?;
                 if let Some(old_val) = env.get(idx) {
                     existing_variables.insert(idx, old_val.clone());
                 } else {
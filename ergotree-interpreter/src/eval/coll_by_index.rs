@@ -52,7 +52,6 @@ mod tests {
     use crate::eval::context::Context;
     use crate::eval::tests::eval_out;
     use crate::eval::tests::eval_out_wo_ctx;
-    use std::rc::Rc;
     use std::sync::Arc;
 
     #[test]
@@ -60,7 +59,7 @@ mod tests {
         let expr: Expr = ByIndex::new(GlobalVars::Outputs.into(), Expr::Const(0i32.into()), None)
             .unwrap()
             .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         assert_eq!(
             eval_out::<Arc<ErgoBox>>(&expr, ctx.clone()).box_id(),
             ctx.outputs.get(0).unwrap().box_id()
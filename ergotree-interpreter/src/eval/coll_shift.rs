@@ -0,0 +1,173 @@
+use ergotree_ir::mir::coll_shift::CollShift;
+use ergotree_ir::mir::coll_shift::CollShiftOp;
+use ergotree_ir::mir::constant::TryExtractInto;
+use ergotree_ir::mir::value::CollKind;
+use ergotree_ir::mir::value::Value;
+use ergotree_ir::types::stype::SType;
+
+use crate::eval::costs::Costs;
+use crate::eval::env::Env;
+use crate::eval::EvalContext;
+use crate::eval::EvalError;
+use crate::eval::Evaluable;
+
+fn bytes_to_bits(bytes: &[i8]) -> Vec<bool> {
+    bytes
+        .iter()
+        .flat_map(|b| (0..8).rev().map(move |i| (*b as u8 >> i) & 1 == 1))
+        .collect()
+}
+
+fn bits_to_bytes(bits: &[bool]) -> Vec<i8> {
+    bits.chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .fold(0u8, |acc, bit| (acc << 1) | u8::from(*bit)) as i8
+        })
+        .collect()
+}
+
+/// Shift or rotate the bit string by `n` positions, the length is preserved
+fn shift_bits(bits: Vec<bool>, op: CollShiftOp, n: usize) -> Vec<bool> {
+    let len = bits.len();
+    if len == 0 {
+        return bits;
+    }
+    match op {
+        CollShiftOp::ShiftLeft => {
+            let n = n.min(len);
+            let mut res = bits[n..].to_vec();
+            res.resize(len, false);
+            res
+        }
+        CollShiftOp::ShiftRight | CollShiftOp::ShiftRightZeroed => {
+            let n = n.min(len);
+            let fill = op == CollShiftOp::ShiftRight && bits[0];
+            let mut res = vec![fill; n];
+            res.extend_from_slice(&bits[..len - n]);
+            res
+        }
+        CollShiftOp::RotateLeft => {
+            let mut res = bits;
+            res.rotate_left(n % len);
+            res
+        }
+        CollShiftOp::RotateRight => {
+            let mut res = bits;
+            res.rotate_right(n % len);
+            res
+        }
+    }
+}
+
+impl Evaluable for CollShift {
+    fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
+        let input_v = self.input.eval(env, ctx)?;
+        let shift_v = self.shift.eval(env, ctx)?;
+        let shift = shift_v.try_extract_into::<i32>()?;
+        if shift < 0 {
+            return Err(EvalError::UnexpectedValue(format!(
+                "CollShift: expected non-negative shift, got {}",
+                shift
+            )));
+        }
+        let elem_tpe = match &input_v {
+            Value::Coll(coll) => {
+                ctx.cost_accum.add_seq_cost(Costs::COLL_SHIFT, coll.len())?;
+                coll.elem_tpe().clone()
+            }
+            _ => {
+                return Err(EvalError::UnexpectedValue(format!(
+                    "CollShift: expected input to be Value::Coll, got {:?}",
+                    input_v
+                )))
+            }
+        };
+        match elem_tpe {
+            SType::SByte => {
+                let bits = bytes_to_bits(&input_v.try_extract_into::<Vec<i8>>()?);
+                Ok(bits_to_bytes(&shift_bits(bits, self.kind, shift as usize)).into())
+            }
+            SType::SBoolean => {
+                let bits = input_v.try_extract_into::<Vec<bool>>()?;
+                let items = shift_bits(bits, self.kind, shift as usize)
+                    .into_iter()
+                    .map(Value::Boolean)
+                    .collect();
+                Ok(Value::Coll(CollKind::WrappedColl {
+                    elem_tpe: SType::SBoolean,
+                    items,
+                }))
+            }
+            _ => Err(EvalError::UnexpectedValue(format!(
+                "CollShift: expected Coll[Byte] or Coll[Boolean], got Coll[{:?}]",
+                elem_tpe
+            ))),
+        }
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::tests::eval_out_wo_ctx;
+    use ergotree_ir::mir::expr::Expr;
+
+    fn eval_bytes(kind: CollShiftOp, input: Vec<i8>, shift: i32) -> Vec<i8> {
+        let expr: Expr = CollShift::new(kind, input.into(), shift.into())
+            .unwrap()
+            .into();
+        eval_out_wo_ctx::<Vec<i8>>(&expr)
+    }
+
+    fn eval_bools(kind: CollShiftOp, input: Vec<bool>, shift: i32) -> Vec<bool> {
+        let expr: Expr = CollShift::new(kind, input.into(), shift.into())
+            .unwrap()
+            .into();
+        eval_out_wo_ctx::<Vec<bool>>(&expr)
+    }
+
+    #[test]
+    fn eval_coll_byte() {
+        let input = vec![0b0000_0001, 0b1000_0000u8 as i8];
+        assert_eq!(
+            eval_bytes(CollShiftOp::ShiftLeft, input.clone(), 1),
+            vec![0b0000_0011, 0]
+        );
+        assert_eq!(
+            eval_bytes(CollShiftOp::ShiftRightZeroed, input.clone(), 1),
+            vec![0, 0b1100_0000u8 as i8]
+        );
+        assert_eq!(
+            eval_bytes(CollShiftOp::RotateRight, input.clone(), 8),
+            vec![0b1000_0000u8 as i8, 0b0000_0001]
+        );
+        assert_eq!(
+            eval_bytes(CollShiftOp::RotateLeft, input.clone(), 16),
+            input
+        );
+        assert_eq!(
+            eval_bytes(CollShiftOp::ShiftRight, vec![-128, 0], 4),
+            vec![-8, 0]
+        );
+    }
+
+    #[test]
+    fn eval_coll_bool() {
+        let input = vec![true, false, false];
+        assert_eq!(
+            eval_bools(CollShiftOp::ShiftRight, input.clone(), 1),
+            vec![true, true, false]
+        );
+        assert_eq!(
+            eval_bools(CollShiftOp::ShiftLeft, input.clone(), 5),
+            vec![false, false, false]
+        );
+        assert_eq!(
+            eval_bools(CollShiftOp::RotateLeft, input, 1),
+            vec![false, false, true]
+        );
+    }
+}
@@ -3,6 +3,7 @@ use ergotree_ir::mir::constant::TryExtractInto;
 use ergotree_ir::mir::value::CollKind;
 use ergotree_ir::mir::value::Value;
 
+use crate::eval::costs::Costs;
 use crate::eval::env::Env;
 use crate::eval::EvalContext;
 use crate::eval::EvalError;
@@ -22,6 +23,9 @@ impl Evaluable for Slice {
         }?;
         let from = from_v.try_extract_into::<i32>()?;
         let until = until_v.try_extract_into::<i32>()?;
+        let slice_len = until.min(input_vec.len() as i32) - from.max(0);
+        ctx.cost_accum
+            .add_seq_cost(Costs::SLICE, slice_len.max(0) as usize)?;
         // intersection of the range with collection bounds
         // to preserve the Scala version semantics of slice op
         // see https://github.com/ergoplatform/sigma-rust/issues/724
@@ -4,6 +4,8 @@ use crate::sigma_protocol::prover::ContextExtension;
 use bounded_vec::BoundedVec;
 use ergo_chain_types::{Header, PreHeader};
 use ergotree_ir::chain::ergo_box::ErgoBox;
+use ergotree_ir::ergo_tree::ErgoTreeVersion;
+use ergotree_ir::validation::ValidationSettings;
 
 /// BoundedVec type for Tx inputs, output_candidates and outputs
 pub type TxIoVec<T> = BoundedVec<T, 1, { u16::MAX as usize }>;
@@ -27,9 +29,16 @@ pub struct Context {
     pub headers: [Header; 10],
     /// prover-defined key-value pairs, that may be used inside a script
     pub extension: ContextExtension,
+    /// Validation rules statuses voted by miners (used to detect soft-fork conditions)
+    pub validation_settings: ValidationSettings,
 }
 
 impl Context {
+    /// ErgoTree version activated on the network (derived from the block version)
+    pub fn activated_script_version(&self) -> ErgoTreeVersion {
+        ErgoTreeVersion::from_block_version(self.pre_header.version)
+    }
+
     /// Return a new Context with given context extension
     pub fn with_extension(self, ext: ContextExtension) -> Self {
         Context {
@@ -83,6 +92,7 @@ mod arbitrary {
                             pre_header,
                             extension,
                             headers,
+                            validation_settings: ValidationSettings::default(),
                         }
                     },
                 )
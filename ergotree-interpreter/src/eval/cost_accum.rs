@@ -1,7 +1,8 @@
-use super::costs::{Cost, Costs};
+use super::costs::{Cost, Costs, PerItemCost};
 use ergotree_ir::mir::expr::Expr;
 use thiserror::Error;
 
+/// Accumulates the cost of the evaluation (in JIT cost units) and checks it against the limit
 #[derive(Debug)]
 pub struct CostAccumulator {
     costs: Costs,
@@ -9,13 +10,16 @@ pub struct CostAccumulator {
     limit: Option<u64>,
 }
 
+/// Cost accumulation errors
 #[derive(Error, PartialEq, Eq, Debug, Clone)]
 pub enum CostError {
+    /// Accumulated cost exceeded the limit
     #[error("Limit ({0}) exceeded")]
     LimitExceeded(u64),
 }
 
 impl CostAccumulator {
+    /// Create new accumulator, `initial_cost` and `cost_limit` are in JIT cost units
     pub fn new(initial_cost: u64, cost_limit: Option<u64>) -> CostAccumulator {
         CostAccumulator {
             costs: Costs::DEFAULT,
@@ -24,13 +28,25 @@ impl CostAccumulator {
         }
     }
 
+    /// Add the cost charged before the evaluation of the given expression
     pub fn add_cost_of(&mut self, expr: &Expr) -> Result<(), CostError> {
         let cost = self.costs.cost_of(expr);
         self.add(cost)
     }
 
+    /// Add the cost of processing `n_items` items
+    pub fn add_seq_cost(&mut self, cost: PerItemCost, n_items: usize) -> Result<(), CostError> {
+        self.add(cost.cost(n_items))
+    }
+
+    /// Add the given cost
     pub fn add(&mut self, cost: Cost) -> Result<(), CostError> {
-        self.accum += u32::from(cost) as u64;
+        self.add_raw(u32::from(cost) as u64)
+    }
+
+    /// Add the cost given in JIT cost units
+    pub fn add_raw(&mut self, cost: u64) -> Result<(), CostError> {
+        self.accum = self.accum.saturating_add(cost);
         if let Some(limit) = self.limit {
             if self.accum > limit {
                 return Err(CostError::LimitExceeded(limit));
@@ -38,4 +54,30 @@ impl CostAccumulator {
         }
         Ok(())
     }
+
+    /// Accumulated cost (in JIT cost units)
+    pub fn total_cost(&self) -> u64 {
+        self.accum
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_exceeded() {
+        let mut accum = CostAccumulator::new(10, Some(20));
+        assert!(accum.add(Cost::new(10)).is_ok());
+        assert_eq!(accum.total_cost(), 20);
+        assert_eq!(accum.add(Cost::new(1)), Err(CostError::LimitExceeded(20)));
+    }
+
+    #[test]
+    fn seq_cost() {
+        let mut accum = CostAccumulator::new(0, None);
+        accum.add_seq_cost(PerItemCost::new(10, 2, 4), 9).unwrap();
+        assert_eq!(accum.total_cost(), 16);
+    }
 }
@@ -1,22 +1,782 @@
+//! JIT costing of ErgoTree operations (as introduced in v5.0 of the reference implementation)
+
+use ergotree_ir::mir::bin_op::ArithOp;
+use ergotree_ir::mir::bin_op::BinOpKind;
+use ergotree_ir::mir::bin_op::LogicalOp;
+use ergotree_ir::mir::bin_op::RelationOp;
+use ergotree_ir::mir::collection::Collection;
 use ergotree_ir::mir::expr::Expr;
+use ergotree_ir::mir::global_vars::GlobalVars;
+use ergotree_ir::mir::value::Value;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaConjecture;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaProofOfKnowledgeTree;
+use ergotree_ir::types::smethod::SMethod;
+use ergotree_ir::types::stype::SType;
 
 extern crate derive_more;
 use derive_more::{From, Into};
 
-#[derive(PartialEq, Eq, Debug, Clone, From, Into)]
+/// Cost of an operation in JIT cost units.
+/// One block cost unit (used in block/transaction limits) equals 10 JIT cost units.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, From, Into)]
 pub struct Cost(u32);
 
+impl Cost {
+    /// Scale of JIT cost units relative to block cost units
+    pub const BLOCK_COST_SCALE: u64 = 10;
+
+    /// Create new cost value (in JIT cost units)
+    pub const fn new(v: u32) -> Self {
+        Cost(v)
+    }
+
+    /// Convert JIT cost units to block cost units
+    pub fn to_block_cost(accum: u64) -> u64 {
+        accum / Self::BLOCK_COST_SCALE
+    }
+
+    /// Convert block cost units to JIT cost units
+    pub fn from_block_cost(block_cost: u64) -> u64 {
+        block_cost.saturating_mul(Self::BLOCK_COST_SCALE)
+    }
+}
+
+/// Cost of an operation that depends on the number of processed items
+/// (i.e. collection size, byte array length).
+/// Items are processed in chunks of `chunk_size` and each chunk costs `per_chunk`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct PerItemCost {
+    /// Cost paid regardless of the number of items
+    pub base: Cost,
+    /// Cost of each chunk of items
+    pub per_chunk: Cost,
+    /// Number of items in one chunk
+    pub chunk_size: usize,
+}
+
+impl PerItemCost {
+    /// Create new per item cost
+    pub const fn new(base: u32, per_chunk: u32, chunk_size: usize) -> Self {
+        PerItemCost {
+            base: Cost(base),
+            per_chunk: Cost(per_chunk),
+            chunk_size,
+        }
+    }
+
+    /// Number of chunks for the given number of items (at least one chunk is always charged)
+    pub fn chunks(&self, n_items: usize) -> usize {
+        n_items.saturating_sub(1) / self.chunk_size.max(1) + 1
+    }
+
+    /// Total cost of processing `n_items`
+    pub fn cost(&self, n_items: usize) -> Cost {
+        let chunks = self.chunks(n_items) as u64;
+        let total = self.base.0 as u64 + chunks.saturating_mul(self.per_chunk.0 as u64);
+        Cost(total.min(u32::MAX as u64) as u32)
+    }
+}
+
+/// Cost that depends on the type of the operands (i.e. BigInt operations cost more)
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct TypeBasedCost {
+    /// Cost for primitive numeric types (Byte, Short, Int, Long)
+    pub primitive: Cost,
+    /// Cost for BigInt
+    pub big_int: Cost,
+}
+
+impl TypeBasedCost {
+    /// Create new type based cost
+    pub const fn new(primitive: u32, big_int: u32) -> Self {
+        TypeBasedCost {
+            primitive: Cost(primitive),
+            big_int: Cost(big_int),
+        }
+    }
+
+    /// Cost for the operands of the given type
+    pub fn cost(&self, tpe: &SType) -> Cost {
+        match tpe {
+            SType::SBigInt | SType::SUnsignedBigInt => self.big_int,
+            _ => self.primitive,
+        }
+    }
+}
+
+/// Kind of the cost of an operation
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum CostKind {
+    /// Cost doesn't depend on the arguments
+    Fixed(Cost),
+    /// Cost depends on the number of processed items (charged during the evaluation)
+    PerItem(PerItemCost),
+    /// Cost depends on the operand types
+    TypeBased(TypeBasedCost),
+    /// Cost depends on the evaluated values (charged during the evaluation)
+    Dynamic,
+}
+
+/// JIT costs of ErgoTree operations
 #[derive(Debug)]
 pub struct Costs {
-    pub eq_const_size: Cost,
+    /// Cost of the interpreter initialization and transaction validation (block cost units),
+    /// charged once per transaction
+    pub tx_base_cost: u64,
+    /// Cost of each transaction input (block cost units)
+    pub tx_input_cost: u64,
+    /// Cost of each transaction data input (block cost units)
+    pub tx_data_input_cost: u64,
+    /// Cost of each transaction output (block cost units)
+    pub tx_output_cost: u64,
+    /// Cost of each token access during transaction validation (block cost units)
+    pub tx_token_access_cost: u64,
 }
 
 impl Costs {
+    /// Default costs as used by the reference implementation (v5.0)
     pub const DEFAULT: Costs = Costs {
-        eq_const_size: Cost(1),
+        tx_base_cost: 10_000,
+        tx_input_cost: 2_000,
+        tx_data_input_cost: 100,
+        tx_output_cost: 100,
+        tx_token_access_cost: 100,
     };
 
-    pub fn cost_of(&self, _: &Expr) -> Cost {
-        Cost(1)
+    /// Cost of `Constant`
+    pub const CONSTANT: Cost = Cost(5);
+    /// Cost of `ConstantPlaceholder`
+    pub const CONSTANT_PLACEHOLDER: Cost = Cost(1);
+    /// Cost of `ValUse`
+    pub const VAL_USE: Cost = Cost(5);
+    /// Cost of adding a value to the environment (ValDef in a block, lambda argument)
+    pub const ADD_TO_ENVIRONMENT: Cost = Cost(5);
+    /// Cost of `BlockValue` (per `ValDef` item)
+    pub const BLOCK_VALUE: PerItemCost = PerItemCost::new(1, 1, 10);
+    /// Cost of `FuncValue`
+    pub const FUNC_VALUE: Cost = Cost(5);
+    /// Cost of `Apply`
+    pub const APPLY: Cost = Cost(30);
+    /// Cost of `SelectField`
+    pub const SELECT_FIELD: Cost = Cost(8);
+    /// Cost of `Tuple`
+    pub const TUPLE: Cost = Cost(15);
+    /// Cost of `ConcreteCollection` (per item)
+    pub const CONCRETE_COLLECTION: PerItemCost = PerItemCost::new(20, 1, 32);
+    /// Cost of `If`
+    pub const IF: Cost = Cost(10);
+    /// Cost of `OptionGet`
+    pub const OPTION_GET: Cost = Cost(15);
+    /// Cost of `OptionGetOrElse`
+    pub const OPTION_GET_OR_ELSE: Cost = Cost(20);
+    /// Cost of `OptionIsDefined`
+    pub const OPTION_IS_DEFINED: Cost = Cost(10);
+    /// Cost of `GetVar`
+    pub const GET_VAR: Cost = Cost(100);
+    /// Cost of `DeserializeContext`
+    pub const DESERIALIZE_CONTEXT: Cost = Cost(100);
+    /// Cost of `DeserializeRegister`
+    pub const DESERIALIZE_REGISTER: Cost = Cost(100);
+    /// Cost of `SubstConstants` (per substituted constant)
+    pub const SUBST_CONSTANTS: PerItemCost = PerItemCost::new(100, 100, 1);
+    /// Cost of `Context`
+    pub const CONTEXT: Cost = Cost(1);
+    /// Cost of `Global`
+    pub const GLOBAL: Cost = Cost(5);
+    /// Cost of `HEIGHT`
+    pub const HEIGHT: Cost = Cost(26);
+    /// Cost of `INPUTS`
+    pub const INPUTS: Cost = Cost(10);
+    /// Cost of `OUTPUTS`
+    pub const OUTPUTS: Cost = Cost(10);
+    /// Cost of `SELF`
+    pub const SELF_BOX: Cost = Cost(10);
+    /// Cost of `minerPubKey`
+    pub const MINER_PUB_KEY: Cost = Cost(20);
+    /// Cost of `groupGenerator`
+    pub const GROUP_GENERATOR: Cost = Cost(10);
+    /// Cost of `SizeOf`
+    pub const SIZE_OF: Cost = Cost(14);
+    /// Cost of `ByIndex`
+    pub const BY_INDEX: Cost = Cost(30);
+    /// Cost of `Slice` (per item of the result)
+    pub const SLICE: PerItemCost = PerItemCost::new(10, 2, 100);
+    /// Cost of `Append` (per item of the result)
+    pub const APPEND: PerItemCost = PerItemCost::new(20, 2, 100);
+    /// Cost of `Map` (per item of the input)
+    pub const MAP: PerItemCost = PerItemCost::new(20, 1, 10);
+    /// Cost of `Filter` (per item of the input)
+    pub const FILTER: PerItemCost = PerItemCost::new(20, 1, 10);
+    /// Cost of `Exists` (per item of the input)
+    pub const EXISTS: PerItemCost = PerItemCost::new(3, 1, 10);
+    /// Cost of `ForAll` (per item of the input)
+    pub const FOR_ALL: PerItemCost = PerItemCost::new(3, 1, 10);
+    /// Cost of `Fold` (per item of the input)
+    pub const FOLD: PerItemCost = PerItemCost::new(3, 1, 10);
+    /// Cost of `AND` of a boolean collection (per item)
+    pub const AND: PerItemCost = PerItemCost::new(10, 5, 32);
+    /// Cost of `OR` of a boolean collection (per item)
+    pub const OR: PerItemCost = PerItemCost::new(10, 5, 32);
+    /// Cost of `XorOf` of a boolean collection (per item)
+    pub const XOR_OF: PerItemCost = PerItemCost::new(20, 5, 32);
+    /// Cost of `Xor` of two byte arrays (per byte)
+    pub const XOR: PerItemCost = PerItemCost::new(10, 2, 128);
+    /// Cost of `AtLeast` (per item of the input)
+    pub const ATLEAST: PerItemCost = PerItemCost::new(20, 3, 5);
+    /// Cost of `SigmaAnd` (per item)
+    pub const SIGMA_AND: PerItemCost = PerItemCost::new(10, 2, 1);
+    /// Cost of `SigmaOr` (per item)
+    pub const SIGMA_OR: PerItemCost = PerItemCost::new(10, 2, 1);
+    /// Cost of `SigmaPropBytes` (per node of the sigma proposition)
+    pub const SIGMA_PROP_BYTES: PerItemCost = PerItemCost::new(35, 6, 1);
+    /// Cost of `CalcBlake2b256` (per byte of the input)
+    pub const CALC_BLAKE2B256: PerItemCost = PerItemCost::new(20, 7, 128);
+    /// Cost of `CalcSha256` (per byte of the input)
+    pub const CALC_SHA256: PerItemCost = PerItemCost::new(80, 8, 64);
+    /// Cost of `BinAnd`, `BinOr` (lazy boolean operations)
+    pub const BIN_LOGICAL: Cost = Cost(20);
+    /// Cost of `BinXor`
+    pub const BIN_XOR: Cost = Cost(20);
+    /// Cost of `LogicalNot`
+    pub const LOGICAL_NOT: Cost = Cost(15);
+    /// Cost of `BoolToSigmaProp`
+    pub const BOOL_TO_SIGMA_PROP: Cost = Cost(15);
+    /// Cost of `CreateProveDlog`
+    pub const CREATE_PROVE_DLOG: Cost = Cost(10);
+    /// Cost of `CreateProveDhTuple`
+    pub const CREATE_PROVE_DH_TUPLE: Cost = Cost(20);
+    /// Cost of `DecodePoint`
+    pub const DECODE_POINT: Cost = Cost(300);
+    /// Cost of `Exponentiate`
+    pub const EXPONENTIATE: Cost = Cost(900);
+    /// Cost of `MultiplyGroup`
+    pub const MULTIPLY_GROUP: Cost = Cost(40);
+    /// Cost of `Upcast`
+    pub const UPCAST: Cost = Cost(10);
+    /// Cost of `Downcast`
+    pub const DOWNCAST: Cost = Cost(10);
+    /// Cost of `Negation`
+    pub const NEGATION: Cost = Cost(30);
+    /// Cost of `BitInversion`
+    pub const BIT_INVERSION: Cost = Cost(30);
+    /// Cost of `LongToByteArray`
+    pub const LONG_TO_BYTE_ARRAY: Cost = Cost(17);
+    /// Cost of `ByteArrayToLong`
+    pub const BYTE_ARRAY_TO_LONG: Cost = Cost(16);
+    /// Cost of `ByteArrayToBigInt`
+    pub const BYTE_ARRAY_TO_BIGINT: Cost = Cost(30);
+    /// Cost of `ExtractAmount`
+    pub const EXTRACT_AMOUNT: Cost = Cost(8);
+    /// Cost of `ExtractScriptBytes`
+    pub const EXTRACT_SCRIPT_BYTES: Cost = Cost(12);
+    /// Cost of `ExtractBytes`
+    pub const EXTRACT_BYTES: Cost = Cost(12);
+    /// Cost of `ExtractBytesWithNoRef`
+    pub const EXTRACT_BYTES_WITH_NO_REF: Cost = Cost(12);
+    /// Cost of `ExtractId`
+    pub const EXTRACT_ID: Cost = Cost(12);
+    /// Cost of `ExtractCreationInfo`
+    pub const EXTRACT_CREATION_INFO: Cost = Cost(16);
+    /// Cost of `ExtractRegisterAs`
+    pub const EXTRACT_REGISTER_AS: Cost = Cost(50);
+    /// Cost of `CreateAvlTree`
+    pub const CREATE_AVL_TREE: Cost = Cost(10);
+    /// Cost of `TreeLookup`
+    pub const TREE_LOOKUP: Cost = Cost(40);
+    /// Cost of `ModQ`
+    pub const MOD_Q: Cost = Cost(30);
+    /// Cost of `PlusModQ`, `MinusModQ`
+    pub const MOD_Q_ARITH: Cost = Cost(40);
+    /// Cost of collection shifts and rotations (per item of the input)
+    pub const COLL_SHIFT: PerItemCost = PerItemCost::new(20, 2, 16);
+    /// Cost of `SomeValue`
+    pub const SOME_VALUE: Cost = Cost(5);
+    /// Cost of `NoneValue`
+    pub const NONE_VALUE: Cost = Cost(5);
+    /// Cost of `SigmaPropIsProven`
+    pub const SIGMA_PROP_IS_PROVEN: Cost = Cost(10);
+    /// Cost of `MethodCall`/`PropertyCall` dispatch (method cost is charged separately)
+    pub const METHOD_CALL: Cost = Cost(4);
+    /// Cost of `+` and `-`
+    pub const ARITH_PLUS_MINUS: TypeBasedCost = TypeBasedCost::new(15, 20);
+    /// Cost of `*`
+    pub const ARITH_MULTIPLY: TypeBasedCost = TypeBasedCost::new(15, 25);
+    /// Cost of `/` and `%`
+    pub const ARITH_DIVIDE_MODULO: TypeBasedCost = TypeBasedCost::new(15, 25);
+    /// Cost of `min` and `max`
+    pub const ARITH_MIN_MAX: TypeBasedCost = TypeBasedCost::new(5, 10);
+    /// Cost of `<`, `<=`, `>`, `>=`
+    pub const RELATION_COMPARE: TypeBasedCost = TypeBasedCost::new(20, 30);
+    /// Cost of bitwise `|`, `&`, `^` and bit shifts
+    pub const BIT_OP: TypeBasedCost = TypeBasedCost::new(20, 30);
+
+    /// Cost of equality of primitive values
+    pub const EQ_PRIM: Cost = Cost(3);
+    /// Cost of equality of BigInt values
+    pub const EQ_BIG_INT: Cost = Cost(5);
+    /// Cost of equality of GroupElement values
+    pub const EQ_GROUP_ELEMENT: Cost = Cost(172);
+    /// Cost of equality of Box values
+    pub const EQ_BOX: Cost = Cost(6);
+    /// Cost of equality of AvlTree values
+    pub const EQ_AVL_TREE: Cost = Cost(9);
+    /// Cost of equality of Header/PreHeader values
+    pub const EQ_HEADER: Cost = Cost(12);
+    /// Cost of equality of tuples (per tuple, items are charged separately)
+    pub const EQ_TUPLE: Cost = Cost(4);
+    /// Cost of equality of options (items are charged separately)
+    pub const EQ_OPTION: Cost = Cost(3);
+    /// Cost of equality of sigma propositions (per node)
+    pub const EQ_SIGMA_PROP: PerItemCost = PerItemCost::new(10, 2, 1);
+    /// Cost of equality of byte arrays (per byte)
+    pub const EQ_COLL_BYTE: PerItemCost = PerItemCost::new(15, 2, 128);
+    /// Cost of equality of collections (per item, items are charged separately)
+    pub const EQ_COLL: PerItemCost = PerItemCost::new(10, 2, 1);
+
+    /// Cost of verification of `ProveDlog` signature (JIT cost units)
+    pub const PROVE_DLOG_VERIFY: Cost = Cost(10_000);
+    /// Cost of verification of `ProveDhTuple` signature (JIT cost units)
+    pub const PROVE_DH_TUPLE_VERIFY: Cost = Cost(20_000);
+    /// Cost of verification of a conjecture node (JIT cost units, per child node)
+    pub const SIGMA_CONJECTURE_VERIFY: PerItemCost = PerItemCost::new(10, 2, 1);
+
+    /// Kind of the cost of the given expression node.
+    /// Fixed and type based costs are charged before the node evaluation,
+    /// per item and dynamic costs are charged by the node during the evaluation.
+    pub fn cost_kind(expr: &Expr) -> CostKind {
+        use CostKind::*;
+        match expr {
+            Expr::Const(_) => Fixed(Self::CONSTANT),
+            Expr::ConstPlaceholder(_) => Fixed(Self::CONSTANT_PLACEHOLDER),
+            Expr::SubstConstants(_) => PerItem(Self::SUBST_CONSTANTS),
+            Expr::ByteArrayToLong(_) => Fixed(Self::BYTE_ARRAY_TO_LONG),
+            Expr::ByteArrayToBigInt(_) => Fixed(Self::BYTE_ARRAY_TO_BIGINT),
+            Expr::LongToByteArray(_) => Fixed(Self::LONG_TO_BYTE_ARRAY),
+            Expr::Collection(_) => PerItem(Self::CONCRETE_COLLECTION),
+            Expr::Tuple(_) => Fixed(Self::TUPLE),
+            Expr::CalcBlake2b256(_) => PerItem(Self::CALC_BLAKE2B256),
+            Expr::CalcSha256(_) => PerItem(Self::CALC_SHA256),
+            Expr::Context => Fixed(Self::CONTEXT),
+            Expr::Global => Fixed(Self::GLOBAL),
+            Expr::GlobalVars(v) => Fixed(match v {
+                GlobalVars::Inputs => Self::INPUTS,
+                GlobalVars::Outputs => Self::OUTPUTS,
+                GlobalVars::Height => Self::HEIGHT,
+                GlobalVars::SelfBox => Self::SELF_BOX,
+                GlobalVars::MinerPubKey => Self::MINER_PUB_KEY,
+                GlobalVars::GroupGenerator => Self::GROUP_GENERATOR,
+            }),
+            Expr::FuncValue(_) => Fixed(Self::FUNC_VALUE),
+            Expr::Apply(_) => Fixed(Self::APPLY),
+            Expr::MethodCall(_) => Dynamic,
+            Expr::PropertyCall(_) => Dynamic,
+            Expr::BlockValue(_) => PerItem(Self::BLOCK_VALUE),
+            Expr::ValDef(_) => Fixed(Self::ADD_TO_ENVIRONMENT),
+            Expr::ValUse(_) => Fixed(Self::VAL_USE),
+            Expr::If(_) => Fixed(Self::IF),
+            Expr::BinOp(op) => match op.expr().kind {
+                BinOpKind::Arith(ArithOp::Plus) | BinOpKind::Arith(ArithOp::Minus) => {
+                    TypeBased(Self::ARITH_PLUS_MINUS)
+                }
+                BinOpKind::Arith(ArithOp::Multiply) => TypeBased(Self::ARITH_MULTIPLY),
+                BinOpKind::Arith(ArithOp::Divide) | BinOpKind::Arith(ArithOp::Modulo) => {
+                    TypeBased(Self::ARITH_DIVIDE_MODULO)
+                }
+                BinOpKind::Arith(ArithOp::Min) | BinOpKind::Arith(ArithOp::Max) => {
+                    TypeBased(Self::ARITH_MIN_MAX)
+                }
+                BinOpKind::Relation(RelationOp::Eq) | BinOpKind::Relation(RelationOp::NEq) => {
+                    Dynamic
+                }
+                BinOpKind::Relation(_) => TypeBased(Self::RELATION_COMPARE),
+                BinOpKind::Logical(LogicalOp::And) | BinOpKind::Logical(LogicalOp::Or) => {
+                    Fixed(Self::BIN_LOGICAL)
+                }
+                BinOpKind::Logical(LogicalOp::Xor) => Fixed(Self::BIN_XOR),
+                BinOpKind::Bit(_) => TypeBased(Self::BIT_OP),
+            },
+            Expr::And(_) => PerItem(Self::AND),
+            Expr::Or(_) => PerItem(Self::OR),
+            Expr::Xor(_) => PerItem(Self::XOR),
+            Expr::Atleast(_) => PerItem(Self::ATLEAST),
+            Expr::LogicalNot(_) => Fixed(Self::LOGICAL_NOT),
+            Expr::Negation(_) => Fixed(Self::NEGATION),
+            Expr::BitInversion(_) => Fixed(Self::BIT_INVERSION),
+            Expr::OptionGet(_) => Fixed(Self::OPTION_GET),
+            Expr::OptionIsDefined(_) => Fixed(Self::OPTION_IS_DEFINED),
+            Expr::OptionGetOrElse(_) => Fixed(Self::OPTION_GET_OR_ELSE),
+            Expr::ExtractAmount(_) => Fixed(Self::EXTRACT_AMOUNT),
+            Expr::ExtractRegisterAs(_) => Fixed(Self::EXTRACT_REGISTER_AS),
+            Expr::ExtractBytes(_) => Fixed(Self::EXTRACT_BYTES),
+            Expr::ExtractBytesWithNoRef(_) => Fixed(Self::EXTRACT_BYTES_WITH_NO_REF),
+            Expr::ExtractScriptBytes(_) => Fixed(Self::EXTRACT_SCRIPT_BYTES),
+            Expr::ExtractCreationInfo(_) => Fixed(Self::EXTRACT_CREATION_INFO),
+            Expr::ExtractId(_) => Fixed(Self::EXTRACT_ID),
+            Expr::ByIndex(_) => Fixed(Self::BY_INDEX),
+            Expr::SizeOf(_) => Fixed(Self::SIZE_OF),
+            Expr::Slice(_) => PerItem(Self::SLICE),
+            Expr::Fold(_) => PerItem(Self::FOLD),
+            Expr::Map(_) => PerItem(Self::MAP),
+            Expr::Filter(_) => PerItem(Self::FILTER),
+            Expr::Exists(_) => PerItem(Self::EXISTS),
+            Expr::ForAll(_) => PerItem(Self::FOR_ALL),
+            Expr::SelectField(_) => Fixed(Self::SELECT_FIELD),
+            Expr::BoolToSigmaProp(_) => Fixed(Self::BOOL_TO_SIGMA_PROP),
+            Expr::Upcast(_) => Fixed(Self::UPCAST),
+            Expr::Downcast(_) => Fixed(Self::DOWNCAST),
+            Expr::CreateProveDlog(_) => Fixed(Self::CREATE_PROVE_DLOG),
+            Expr::CreateProveDhTuple(_) => Fixed(Self::CREATE_PROVE_DH_TUPLE),
+            Expr::SigmaPropBytes(_) => PerItem(Self::SIGMA_PROP_BYTES),
+            Expr::DecodePoint(_) => Fixed(Self::DECODE_POINT),
+            Expr::SigmaAnd(_) => PerItem(Self::SIGMA_AND),
+            Expr::SigmaOr(_) => PerItem(Self::SIGMA_OR),
+            Expr::GetVar(_) => Fixed(Self::GET_VAR),
+            Expr::DeserializeRegister(_) => Fixed(Self::DESERIALIZE_REGISTER),
+            Expr::DeserializeContext(_) => Fixed(Self::DESERIALIZE_CONTEXT),
+            Expr::MultiplyGroup(_) => Fixed(Self::MULTIPLY_GROUP),
+            Expr::Exponentiate(_) => Fixed(Self::EXPONENTIATE),
+            Expr::XorOf(_) => PerItem(Self::XOR_OF),
+            Expr::TreeLookup(_) => Fixed(Self::TREE_LOOKUP),
+            Expr::CreateAvlTree(_) => Fixed(Self::CREATE_AVL_TREE),
+            Expr::Append(_) => PerItem(Self::APPEND),
+            Expr::FunDef(_) => Fixed(Self::ADD_TO_ENVIRONMENT),
+            Expr::ModQ(_) => Fixed(Self::MOD_Q),
+            Expr::ModQArithOp(_) => Fixed(Self::MOD_Q_ARITH),
+            Expr::CollShift(_) => PerItem(Self::COLL_SHIFT),
+            Expr::SomeValue(_) => Fixed(Self::SOME_VALUE),
+            Expr::NoneValue(_) => Fixed(Self::NONE_VALUE),
+            Expr::SigmaPropIsProven(_) => Fixed(Self::SIGMA_PROP_IS_PROVEN),
+        }
+    }
+
+    /// Cost charged before the evaluation of the given expression node
+    /// (fixed and type based costs, per item costs with statically known number of items)
+    pub fn cost_of(&self, expr: &Expr) -> Cost {
+        match Self::cost_kind(expr) {
+            CostKind::Fixed(c) => c,
+            CostKind::TypeBased(c) => match expr {
+                Expr::BinOp(op) => c.cost(&op.expr().left.tpe()),
+                _ => c.cost(&expr.tpe()),
+            },
+            CostKind::PerItem(c) => match expr {
+                Expr::BlockValue(op) => c.cost(op.expr().items.len()),
+                Expr::Collection(Collection::BoolConstants(bools)) => c.cost(bools.len()),
+                Expr::Collection(Collection::Exprs { items, .. }) => c.cost(items.len()),
+                Expr::SigmaAnd(op) => c.cost(op.items.len()),
+                Expr::SigmaOr(op) => c.cost(op.items.len()),
+                _ => Cost(0),
+            },
+            CostKind::Dynamic => Cost(0),
+        }
+    }
+
+    /// Cost of the method call (charged after the object is evaluated).
+    /// For collection methods the number of items is the size of the collection.
+    pub fn method_cost_kind(method: &SMethod) -> CostKind {
+        use ergotree_ir::types::*;
+        use CostKind::*;
+        let method_id = method.method_id();
+        match method.obj_type.type_code() {
+            scoll::TYPE_CODE => match method_id {
+                scoll::INDEX_OF_METHOD_ID => PerItem(PerItemCost::new(20, 10, 2)),
+                scoll::FLATMAP_METHOD_ID => PerItem(PerItemCost::new(60, 10, 8)),
+                scoll::ZIP_METHOD_ID => PerItem(PerItemCost::new(10, 1, 10)),
+                scoll::INDICES_METHOD_ID => PerItem(PerItemCost::new(20, 2, 16)),
+                scoll::PATCH_METHOD_ID => PerItem(PerItemCost::new(30, 2, 10)),
+                scoll::UPDATED_METHOD_ID => PerItem(PerItemCost::new(20, 1, 10)),
+                scoll::UPDATE_MANY_METHOD_ID => PerItem(PerItemCost::new(20, 2, 10)),
+                scoll::REVERSE_METHOD_ID => PerItem(PerItemCost::new(10, 1, 10)),
+                scoll::STARTS_WITH_METHOD_ID | scoll::ENDS_WITH_METHOD_ID => {
+                    PerItem(PerItemCost::new(20, 2, 10))
+                }
+                scoll::GET_METHOD_ID => Fixed(Cost(14)),
+                _ => Fixed(Cost(20)),
+            },
+            sbox::TYPE_CODE => match method_id {
+                sbox::VALUE_METHOD_ID => Fixed(Self::EXTRACT_AMOUNT),
+                sbox::PROPOSITION_BYTES_METHOD_ID => Fixed(Self::EXTRACT_SCRIPT_BYTES),
+                sbox::BYTES_METHOD_ID => Fixed(Self::EXTRACT_BYTES),
+                sbox::BYTES_WITHOUT_REF_METHOD_ID => Fixed(Self::EXTRACT_BYTES_WITH_NO_REF),
+                sbox::ID_METHOD_ID => Fixed(Self::EXTRACT_ID),
+                sbox::CREATION_INFO_METHOD_ID => Fixed(Self::EXTRACT_CREATION_INFO),
+                sbox::TOKENS_METHOD_ID => Fixed(Cost(15)),
+                // getReg and R0..R9
+                _ => Fixed(Self::EXTRACT_REGISTER_AS),
+            },
+            savltree::TYPE_CODE => match method_id {
+                savltree::DIGEST_METHOD_ID
+                | savltree::ENABLED_OPERATIONS_METHOD_ID
+                | savltree::KEY_LENGTH_METHOD_ID
+                | savltree::VALUE_LENGTH_OPT_METHOD_ID
+                | savltree::IS_INSERT_ALLOWED_METHOD_ID
+                | savltree::IS_UPDATE_ALLOWED_METHOD_ID
+                | savltree::IS_REMOVE_ALLOWED_METHOD_ID => Fixed(Cost(15)),
+                savltree::UPDATE_DIGEST_METHOD_ID | savltree::UPDATE_OPERATIONS_METHOD_ID => {
+                    Fixed(Cost(40))
+                }
+                // lookups and modifications are charged per processed key
+                _ => PerItem(PerItemCost::new(40, 10, 1)),
+            },
+            scontext::TYPE_CODE => Fixed(Cost(15)),
+            sheader::TYPE_CODE => match method_id {
+                sheader::CHECK_POW_METHOD_ID => Fixed(Cost(700)),
+                _ => Fixed(Cost(10)),
+            },
+            spreheader::TYPE_CODE => Fixed(Cost(10)),
+            sgroup_elem::TYPE_CODE => match method_id {
+                sgroup_elem::GET_ENCODED_METHOD_ID => Fixed(Cost(250)),
+                sgroup_elem::NEGATE_METHOD_ID => Fixed(Cost(45)),
+                sgroup_elem::EXPONENTIATE_METHOD_ID => Fixed(Self::EXPONENTIATE),
+                sgroup_elem::MULTIPLY_METHOD_ID => Fixed(Self::MULTIPLY_GROUP),
+                _ => Fixed(Cost(10)),
+            },
+            soption::TYPE_CODE => Fixed(Cost(20)),
+            sglobal::TYPE_CODE => match method_id {
+                sglobal::XOR_METHOD_ID => PerItem(PerItemCost::new(10, 2, 128)),
+                _ => Fixed(Cost(10)),
+            },
+            _ => Fixed(Cost(10)),
+        }
+    }
+
+    /// Cost of the method call on the evaluated object and arguments
+    pub fn method_cost(method: &SMethod, obj: &Value, args: &[Value]) -> Cost {
+        match Self::method_cost_kind(method) {
+            CostKind::Fixed(c) => c,
+            CostKind::TypeBased(c) => c.cost(&method.tpe().t_range),
+            CostKind::PerItem(c) => c.cost(method_items_count(method, obj, args)),
+            CostKind::Dynamic => Cost(0),
+        }
+    }
+
+    /// Cost of the equality comparison of the given values (`==`, `!=`)
+    pub fn equality_cost(l: &Value, r: &Value) -> Cost {
+        fn add(a: Cost, b: Cost) -> Cost {
+            Cost(a.0.saturating_add(b.0))
+        }
+        match (l, r) {
+            (Value::BigInt(_), _) | (Value::UnsignedBigInt(_), _) => Costs::EQ_BIG_INT,
+            (Value::GroupElement(_), _) => Costs::EQ_GROUP_ELEMENT,
+            (Value::CBox(_), _) => Costs::EQ_BOX,
+            (Value::AvlTree(_), _) => Costs::EQ_AVL_TREE,
+            (Value::Header(_), _) | (Value::PreHeader(_), _) => Costs::EQ_HEADER,
+            (Value::SigmaProp(l), _) => {
+                Costs::EQ_SIGMA_PROP.cost(sigma_boolean_nodes_count(l.value()))
+            }
+            (Value::Tup(l_items), Value::Tup(r_items)) => l_items
+                .iter()
+                .zip(r_items.iter())
+                .fold(Costs::EQ_TUPLE, |acc, (l, r)| {
+                    add(acc, Costs::equality_cost(l, r))
+                }),
+            (Value::Opt(l_opt), Value::Opt(r_opt)) => match (l_opt.as_ref(), r_opt.as_ref()) {
+                (Some(l), Some(r)) => add(Costs::EQ_OPTION, Costs::equality_cost(l, r)),
+                _ => Costs::EQ_OPTION,
+            },
+            (Value::Coll(l_coll), Value::Coll(r_coll)) => {
+                if l_coll.len() != r_coll.len() {
+                    // different sizes are detected without comparing the items
+                    return Costs::EQ_COLL.cost(0);
+                }
+                match l_coll.elem_tpe() {
+                    SType::SByte => Costs::EQ_COLL_BYTE.cost(l_coll.len()),
+                    _ => l_coll
+                        .as_vec()
+                        .iter()
+                        .zip(r_coll.as_vec().iter())
+                        .fold(Costs::EQ_COLL.cost(l_coll.len()), |acc, (l, r)| {
+                            add(acc, Costs::equality_cost(l, r))
+                        }),
+                }
+            }
+            _ => Costs::EQ_PRIM,
+        }
+    }
+
+    /// Cost of the signature verification for the given sigma proposition (JIT cost units)
+    pub fn sigma_verify_cost(sb: &SigmaBoolean) -> u64 {
+        match sb {
+            SigmaBoolean::TrivialProp(_) => 0,
+            SigmaBoolean::ProofOfKnowledge(SigmaProofOfKnowledgeTree::ProveDlog(_)) => {
+                Self::PROVE_DLOG_VERIFY.0 as u64
+            }
+            SigmaBoolean::ProofOfKnowledge(SigmaProofOfKnowledgeTree::ProveDhTuple(_)) => {
+                Self::PROVE_DH_TUPLE_VERIFY.0 as u64
+            }
+            SigmaBoolean::SigmaConjecture(conj) => {
+                let children: Vec<&SigmaBoolean> = match conj {
+                    SigmaConjecture::Cand(c) => c.items.iter().collect(),
+                    SigmaConjecture::Cor(c) => c.items.iter().collect(),
+                    SigmaConjecture::Cthreshold(c) => c.children.iter().collect(),
+                };
+                children.iter().fold(
+                    Self::SIGMA_CONJECTURE_VERIFY.cost(children.len()).0 as u64,
+                    |acc, c| acc + Self::sigma_verify_cost(c),
+                )
+            }
+        }
+    }
+}
+
+/// Number of nodes in the sigma proposition tree
+pub(crate) fn sigma_boolean_nodes_count(sb: &SigmaBoolean) -> usize {
+    match sb {
+        SigmaBoolean::SigmaConjecture(SigmaConjecture::Cand(c)) => {
+            1 + c.items.iter().map(sigma_boolean_nodes_count).sum::<usize>()
+        }
+        SigmaBoolean::SigmaConjecture(SigmaConjecture::Cor(c)) => {
+            1 + c.items.iter().map(sigma_boolean_nodes_count).sum::<usize>()
+        }
+        SigmaBoolean::SigmaConjecture(SigmaConjecture::Cthreshold(c)) => {
+            1 + c
+                .children
+                .iter()
+                .map(sigma_boolean_nodes_count)
+                .sum::<usize>()
+        }
+        _ => 1,
+    }
+}
+
+/// Number of items processed by a method with the per-item cost: the length of the collection
+/// the method is called on, the number of keys (entries) for the batch `AvlTree` operations and
+/// the number of bytes for `Global.xor`
+fn method_items_count(method: &SMethod, obj: &Value, args: &[Value]) -> usize {
+    use ergotree_ir::types::*;
+    let first_arg_len = || match args.first() {
+        Some(Value::Coll(coll)) => coll.len(),
+        _ => 1,
+    };
+    match obj {
+        Value::Coll(coll) => coll.len(),
+        Value::AvlTree(_) => match method.method_id() {
+            savltree::GET_MANY_METHOD_ID
+            | savltree::INSERT_METHOD_ID
+            | savltree::UPDATE_METHOD_ID
+            | savltree::REMOVE_METHOD_ID => first_arg_len(),
+            // `contains` and `get` look up a single key
+            _ => 1,
+        },
+        Value::Global if method.method_id() == sglobal::XOR_METHOD_ID => first_arg_len(),
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn per_item_cost_chunks() {
+        let c = PerItemCost::new(10, 2, 4);
+        assert_eq!(c.cost(0), Cost(12));
+        assert_eq!(c.cost(1), Cost(12));
+        assert_eq!(c.cost(4), Cost(12));
+        assert_eq!(c.cost(5), Cost(14));
+        assert_eq!(c.cost(9), Cost(16));
+    }
+
+    #[test]
+    fn type_based_cost() {
+        assert_eq!(Costs::ARITH_PLUS_MINUS.cost(&SType::SInt), Cost(15));
+        assert_eq!(Costs::ARITH_PLUS_MINUS.cost(&SType::SBigInt), Cost(20));
+    }
+
+    #[test]
+    fn coll_method_costs() {
+        use ergotree_ir::types::scoll;
+        use ergotree_ir::types::stype_companion::STypeCompanion;
+        use CostKind::*;
+        // costs of the SCollection methods in the reference implementation
+        let expected = vec![
+            (
+                scoll::INDEX_OF_METHOD_ID,
+                PerItem(PerItemCost::new(20, 10, 2)),
+            ),
+            (
+                scoll::FLATMAP_METHOD_ID,
+                PerItem(PerItemCost::new(60, 10, 8)),
+            ),
+            (scoll::ZIP_METHOD_ID, PerItem(PerItemCost::new(10, 1, 10))),
+            (
+                scoll::INDICES_METHOD_ID,
+                PerItem(PerItemCost::new(20, 2, 16)),
+            ),
+            (
+                scoll::UPDATED_METHOD_ID,
+                PerItem(PerItemCost::new(20, 1, 10)),
+            ),
+            (
+                scoll::UPDATE_MANY_METHOD_ID,
+                PerItem(PerItemCost::new(20, 2, 10)),
+            ),
+            (scoll::PATCH_METHOD_ID, PerItem(PerItemCost::new(30, 2, 10))),
+            (
+                scoll::REVERSE_METHOD_ID,
+                PerItem(PerItemCost::new(10, 1, 10)),
+            ),
+            (
+                scoll::STARTS_WITH_METHOD_ID,
+                PerItem(PerItemCost::new(20, 2, 10)),
+            ),
+            (
+                scoll::ENDS_WITH_METHOD_ID,
+                PerItem(PerItemCost::new(20, 2, 10)),
+            ),
+            (scoll::GET_METHOD_ID, Fixed(Cost(14))),
+        ];
+        // every method has an explicit cost, none falls back to the default `Fixed(Cost(20))`
+        let costs: Vec<_> = STypeCompanion::Coll
+            .methods()
+            .iter()
+            .map(|m| (m.method_id(), Costs::method_cost_kind(m)))
+            .collect();
+        assert_eq!(costs, expected);
+    }
+
+    #[test]
+    fn method_cost_per_processed_item() {
+        use ergotree_ir::mir::avl_tree_data::{AvlTreeData, AvlTreeFlags};
+        use ergotree_ir::types::{savltree, sglobal};
+        let tree = Value::AvlTree(Box::new(AvlTreeData {
+            digest: ergo_chain_types::ADDigest::zero(),
+            tree_flags: AvlTreeFlags::new(true, true, true),
+            key_length: 1,
+            value_length_opt: None,
+        }));
+        let keys = |n: usize| -> Value { vec![vec![0u8]; n].into() };
+        let proof: Value = vec![0u8; 10].into();
+        // 40 + 10 per key
+        assert_eq!(
+            Costs::method_cost(&savltree::GET_MANY_METHOD, &tree, &[keys(5), proof.clone()]),
+            Cost(90)
+        );
+        assert_eq!(
+            Costs::method_cost(&savltree::REMOVE_METHOD, &tree, &[keys(2), proof.clone()]),
+            Cost(60)
+        );
+        // a single key, whatever its length
+        let key: Value = vec![0u8; 32].into();
+        assert_eq!(
+            Costs::method_cost(&savltree::GET_METHOD, &tree, &[key, proof]),
+            Cost(50)
+        );
+        // 10 + 2 per 128 bytes chunk
+        let bytes: Value = vec![0i8; 300].into();
+        assert_eq!(
+            Costs::method_cost(
+                &sglobal::XOR_METHOD,
+                &Value::Global,
+                &[bytes.clone(), bytes]
+            ),
+            Cost(16)
+        );
+    }
+
+    #[test]
+    fn block_cost_conversion() {
+        assert_eq!(Cost::to_block_cost(12345), 1234);
+        assert_eq!(Cost::from_block_cost(1234), 12340);
     }
 }
@@ -40,7 +40,7 @@ impl Evaluable for DeserializeContext {
 #[allow(clippy::unwrap_used)]
 #[cfg(test)]
 mod tests {
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     use ergotree_ir::mir::constant::Constant;
     use ergotree_ir::mir::global_vars::GlobalVars;
@@ -67,7 +67,7 @@ mod tests {
                 .collect(),
         };
         let ctx = force_any_val::<Context>().with_extension(ctx_ext);
-        assert!(try_eval_out::<bool>(&expr, Rc::new(ctx)).unwrap());
+        assert!(try_eval_out::<bool>(&expr, Arc::new(ctx)).unwrap());
     }
 
     #[test]
@@ -78,7 +78,7 @@ mod tests {
         }
         .into();
         let ctx = force_any_val::<Context>().with_extension(ContextExtension::empty());
-        assert!(try_eval_out::<bool>(&expr, Rc::new(ctx)).is_err());
+        assert!(try_eval_out::<bool>(&expr, Arc::new(ctx)).is_err());
     }
 
     #[test]
@@ -94,7 +94,7 @@ mod tests {
             values: [(1u8, ctx_ext_val)].iter().cloned().collect(),
         };
         let ctx = force_any_val::<Context>().with_extension(ctx_ext);
-        assert!(try_eval_out::<bool>(&expr, Rc::new(ctx)).is_err());
+        assert!(try_eval_out::<bool>(&expr, Arc::new(ctx)).is_err());
     }
 
     #[test]
@@ -113,6 +113,6 @@ mod tests {
                 .collect(),
         };
         let ctx = force_any_val::<Context>().with_extension(ctx_ext);
-        assert!(try_eval_out::<Value>(&expr, Rc::new(ctx)).is_err());
+        assert!(try_eval_out::<Value>(&expr, Arc::new(ctx)).is_err());
     }
 }
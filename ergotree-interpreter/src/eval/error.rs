@@ -50,6 +50,9 @@ pub enum EvalError {
     /// Misc error
     #[error("error: {0}")]
     Misc(String),
+    /// Method or type is not available in the activated script version
+    #[error("Script version error: {0}")]
+    ScriptVersionError(String),
     /// Sigma serialization error
     #[error("Serialization error: {0}")]
     SigmaSerializationError(#[from] SigmaSerializationError),
@@ -163,9 +166,8 @@ impl EvalError {
         })
     }
 
-    /// Wrap eval error with source code
+    /// Wrap eval error with source code (errors without source span are returned as is)
     pub fn wrap_spanned_with_src(self, source: String) -> Self {
-        #[allow(clippy::panic)]
         match self {
             EvalError::Spanned(e) => EvalError::SpannedWithSource(SpannedWithSourceEvalError {
                 error: e.error,
@@ -173,7 +175,7 @@ impl EvalError {
                 env: e.env,
                 source,
             }),
-            e => panic!("Expected Spanned, got {:?}", e),
+            e => e,
         }
     }
 }
@@ -195,7 +197,7 @@ impl<T> ExtResultEvalError<T> for Result<T, EvalError> {
 #[allow(clippy::unwrap_used, unused_imports, dead_code)]
 #[cfg(test)]
 mod tests {
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     use ergotree_ir::mir::coll_by_index::ByIndex;
     use ergotree_ir::mir::global_vars::GlobalVars;
@@ -222,7 +224,7 @@ mod tests {
         let mut w = PosTrackingWriter::new();
         let spanned_expr = expr.print(&mut w).unwrap();
         dbg!(&spanned_expr);
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let err_raw: SpannedEvalError = try_eval_out::<i32>(&spanned_expr, ctx)
             .err()
             .unwrap()
@@ -241,7 +243,7 @@ mod tests {
         let mut w = PosTrackingWriter::new();
         let spanned_expr = expr.print(&mut w).unwrap();
         dbg!(&spanned_expr);
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let err_raw: SpannedEvalError = try_eval_out::<i32>(&spanned_expr, ctx)
             .err()
             .unwrap()
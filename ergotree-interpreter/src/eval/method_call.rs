@@ -1,6 +1,10 @@
 use ergotree_ir::mir::method_call::MethodCall;
 use ergotree_ir::mir::value::Value;
+use ergotree_ir::types::scontext;
+use ergotree_ir::types::stype_companion::STypeCompanion;
 
+use super::check_method_version;
+use super::costs::Costs;
 use super::smethod_eval_fn;
 use super::Env;
 use super::EvalContext;
@@ -9,10 +13,22 @@ use super::Evaluable;
 
 impl Evaluable for MethodCall {
     fn eval(&self, env: &mut Env, ectx: &mut EvalContext) -> Result<Value, EvalError> {
+        check_method_version(&self.method, ectx)?;
         let ov = self.obj.eval(env, ectx)?;
-        let argsv: Result<Vec<Value>, EvalError> =
-            self.args.iter().map(|arg| arg.eval(env, ectx)).collect();
-        smethod_eval_fn(&self.method)?(env, ectx, ov, argsv?)
+        ectx.cost_accum.add(Costs::METHOD_CALL)?;
+        let argsv = self
+            .args
+            .iter()
+            .map(|arg| arg.eval(env, ectx))
+            .collect::<Result<Vec<Value>, EvalError>>()?;
+        ectx.cost_accum
+            .add(Costs::method_cost(&self.method, &ov, &argsv))?;
+        if self.method.obj_type == STypeCompanion::Context
+            && self.method.method_id() == scontext::GET_VAR_METHOD_ID
+        {
+            return super::scontext::get_var(ectx, ov, argsv, &self.method.tpe().t_range);
+        }
+        smethod_eval_fn(&self.method)?(env, ectx, ov, argsv)
     }
 }
 
@@ -20,7 +36,7 @@ impl Evaluable for MethodCall {
 #[cfg(test)]
 #[cfg(feature = "arbitrary")]
 mod tests {
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     use ergotree_ir::mir::constant::Constant;
     use ergotree_ir::mir::expr::Expr;
@@ -45,7 +61,7 @@ mod tests {
         .unwrap()
         .into();
         let option_get_expr: Expr = OptionGet::try_build(mc).unwrap().into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         assert_eq!(
             eval_out::<i64>(&option_get_expr, ctx.clone()),
             ctx.self_box.value.as_i64()
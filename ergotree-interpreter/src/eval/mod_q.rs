@@ -0,0 +1,124 @@
+use std::convert::TryFrom;
+
+use ergotree_ir::bigint256::BigInt256;
+use ergotree_ir::mir::constant::TryExtractInto;
+use ergotree_ir::mir::mod_q::ModQ;
+use ergotree_ir::mir::mod_q::ModQArithOp;
+use ergotree_ir::mir::mod_q::ModQArithOpKind;
+use ergotree_ir::mir::value::Value;
+use ergotree_ir::sigma_protocol::dlog_group::order;
+use num_bigint::BigInt;
+
+use crate::eval::env::Env;
+use crate::eval::EvalContext;
+use crate::eval::EvalError;
+use crate::eval::Evaluable;
+
+/// Returns `v mod q` as 256-bit signed integer. The residues in `[0, 2^255)` are returned as is
+/// and the ones in `[2^255, q)`, which do not fit, as the congruent negative value `r - q`. So
+/// every residue is representable and is returned unchanged by `ModQ`.
+fn mod_q(v: BigInt) -> Result<Value, EvalError> {
+    let q = order();
+    let res = ((v % &q) + &q) % &q;
+    BigInt256::try_from(res.clone())
+        .or_else(|_| BigInt256::try_from(res - &q))
+        .map(Value::BigInt)
+        .map_err(EvalError::ArithmeticException)
+}
+
+impl Evaluable for ModQ {
+    fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
+        let input_v = self.input.eval(env, ctx)?;
+        let input = input_v.try_extract_into::<BigInt256>()?;
+        mod_q(input.into())
+    }
+}
+
+impl Evaluable for ModQArithOp {
+    fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
+        let left: BigInt = self
+            .left
+            .eval(env, ctx)?
+            .try_extract_into::<BigInt256>()?
+            .into();
+        let right: BigInt = self
+            .right
+            .eval(env, ctx)?
+            .try_extract_into::<BigInt256>()?
+            .into();
+        match self.kind {
+            ModQArithOpKind::Plus => mod_q(left + right),
+            ModQArithOpKind::Minus => mod_q(left - right),
+        }
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::tests::try_eval_out_wo_ctx;
+    use ergotree_ir::mir::expr::Expr;
+    use ergotree_ir::mir::unary_op::OneArgOpTryBuild;
+    use num_traits::Bounded;
+
+    fn b(v: i64) -> BigInt256 {
+        v.into()
+    }
+
+    fn eval_mod_q_arith(kind: ModQArithOpKind, l: BigInt256, r: BigInt256) -> BigInt256 {
+        let expr: Expr = ModQArithOp::new(kind, Expr::Const(l.into()), Expr::Const(r.into()))
+            .unwrap()
+            .into();
+        try_eval_out_wo_ctx::<BigInt256>(&expr).unwrap()
+    }
+
+    #[test]
+    fn eval_mod_q() {
+        let expr: Expr = ModQ::try_build(Expr::Const(b(42).into())).unwrap().into();
+        assert_eq!(try_eval_out_wo_ctx::<BigInt256>(&expr).unwrap(), b(42));
+    }
+
+    fn eval_mod_q_of(v: BigInt256) -> BigInt256 {
+        let expr: Expr = ModQ::try_build(Expr::Const(v.into())).unwrap().into();
+        try_eval_out_wo_ctx::<BigInt256>(&expr).unwrap()
+    }
+
+    #[test]
+    fn eval_mod_q_whole_range() {
+        let q = order();
+        for v in [
+            b(0),
+            b(1),
+            b(-1),
+            b(42),
+            b(-42),
+            BigInt256::max_value(),
+            BigInt256::min_value(),
+        ] {
+            let res = eval_mod_q_of(v.clone());
+            // congruent to the input and returned unchanged
+            assert_eq!(
+                (BigInt::from(res.clone()) - BigInt::from(v)) % &q,
+                BigInt::from(0)
+            );
+            assert_eq!(eval_mod_q_of(res.clone()), res);
+        }
+        // q - 1
+        assert_eq!(eval_mod_q_of(b(-1)), b(-1));
+        // 2^255 (does not fit) is returned as 2^255 - q
+        let res = eval_mod_q_arith(ModQArithOpKind::Plus, BigInt256::max_value(), b(1));
+        assert_eq!(
+            BigInt::from(res) + &q,
+            BigInt::from(BigInt256::max_value()) + 1
+        );
+        assert_eq!(eval_mod_q_arith(ModQArithOpKind::Plus, b(-1), b(1)), b(0));
+        assert_eq!(eval_mod_q_arith(ModQArithOpKind::Minus, b(0), b(1)), b(-1));
+    }
+
+    #[test]
+    fn eval_plus_minus_mod_q() {
+        assert_eq!(eval_mod_q_arith(ModQArithOpKind::Plus, b(2), b(3)), b(5));
+        assert_eq!(eval_mod_q_arith(ModQArithOpKind::Minus, b(5), b(3)), b(2));
+    }
+}
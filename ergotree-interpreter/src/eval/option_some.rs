@@ -0,0 +1,53 @@
+use ergotree_ir::mir::option_some::NoneValue;
+use ergotree_ir::mir::option_some::SomeValue;
+use ergotree_ir::mir::value::Value;
+
+use crate::eval::env::Env;
+use crate::eval::EvalContext;
+use crate::eval::EvalError;
+use crate::eval::Evaluable;
+
+impl Evaluable for SomeValue {
+    fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
+        let v = self.input.eval(env, ctx)?;
+        Ok(Value::Opt(Box::new(Some(v))))
+    }
+}
+
+impl Evaluable for NoneValue {
+    fn eval(&self, _env: &mut Env, _ctx: &mut EvalContext) -> Result<Value, EvalError> {
+        Ok(Value::Opt(Box::new(None)))
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::tests::eval_out_wo_ctx;
+    use ergotree_ir::mir::expr::Expr;
+    use ergotree_ir::mir::option_get_or_else::OptionGetOrElse;
+    use ergotree_ir::mir::unary_op::OneArgOpTryBuild;
+    use ergotree_ir::types::stype::SType;
+
+    #[test]
+    fn eval_some() {
+        let expr: Expr = SomeValue::try_build(Expr::Const(1i64.into()))
+            .unwrap()
+            .into();
+        assert_eq!(eval_out_wo_ctx::<Option<i64>>(&expr), Some(1i64));
+    }
+
+    #[test]
+    fn eval_none() {
+        let none: Expr = NoneValue {
+            elem_tpe: SType::SLong,
+        }
+        .into();
+        assert_eq!(eval_out_wo_ctx::<Option<i64>>(&none), None);
+        let expr: Expr = OptionGetOrElse::new(none, Expr::Const(2i64.into()))
+            .unwrap()
+            .into();
+        assert_eq!(eval_out_wo_ctx::<i64>(&expr), 2i64);
+    }
+}
@@ -2,6 +2,7 @@ use ergotree_ir::mir::constant::TryExtractInto;
 use ergotree_ir::mir::or::Or;
 use ergotree_ir::mir::value::Value;
 
+use crate::eval::costs::Costs;
 use crate::eval::env::Env;
 use crate::eval::EvalContext;
 use crate::eval::EvalError;
@@ -10,6 +11,9 @@ use crate::eval::Evaluable;
 impl Evaluable for Or {
     fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
         let input_v = self.input.eval(env, ctx)?;
+        if let Value::Coll(coll) = &input_v {
+            ctx.cost_accum.add_seq_cost(Costs::OR, coll.len())?;
+        }
         let input_v_bools = input_v.try_extract_into::<Vec<bool>>()?;
         Ok(input_v_bools.iter().any(|b| *b).into())
     }
@@ -25,14 +29,14 @@ mod tests {
     use proptest::collection;
     use proptest::prelude::*;
     use sigma_test_util::force_any_val;
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     proptest! {
 
         #[test]
         fn eval(bools in collection::vec(any::<bool>(), 0..10)) {
             let expr: Expr = Or {input: Expr::Const(bools.clone().into()).into()}.into();
-            let ctx = Rc::new(force_any_val::<Context>());
+            let ctx = Arc::new(force_any_val::<Context>());
             let res = eval_out::<bool>(&expr, ctx);
             prop_assert_eq!(res, bools.iter().any(|b| *b));
         }
@@ -1,6 +1,8 @@
 use ergotree_ir::mir::property_call::PropertyCall;
 use ergotree_ir::mir::value::Value;
 
+use super::check_method_version;
+use super::costs::Costs;
 use super::smethod_eval_fn;
 use super::Env;
 use super::EvalContext;
@@ -9,7 +11,11 @@ use super::Evaluable;
 
 impl Evaluable for PropertyCall {
     fn eval(&self, env: &mut Env, ectx: &mut EvalContext) -> Result<Value, EvalError> {
+        check_method_version(&self.method, ectx)?;
         let ov = self.obj.eval(env, ectx)?;
+        ectx.cost_accum.add(Costs::METHOD_CALL)?;
+        ectx.cost_accum
+            .add(Costs::method_cost(&self.method, &ov, &[]))?;
         smethod_eval_fn(&self.method)?(env, ectx, ov, vec![])
     }
 }
@@ -24,7 +30,6 @@ mod tests {
     use ergotree_ir::mir::expr::Expr;
     use ergotree_ir::types::scontext;
     use sigma_test_util::force_any_val;
-    use std::rc::Rc;
     use std::sync::Arc;
 
     #[test]
@@ -32,7 +37,7 @@ mod tests {
         let pc: Expr = PropertyCall::new(Expr::Context, scontext::DATA_INPUTS_PROPERTY.clone())
             .unwrap()
             .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let expected = ctx
             .data_inputs
             .clone()
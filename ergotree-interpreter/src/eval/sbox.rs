@@ -1,11 +1,14 @@
 use std::convert::TryInto;
 use std::sync::Arc;
 
+use crate::eval::env::Env;
+use crate::eval::EvalContext;
 use crate::eval::EvalError;
 
 use ergotree_ir::chain::ergo_box::ErgoBox;
 use ergotree_ir::mir::constant::TryExtractInto;
 use ergotree_ir::mir::value::Value;
+use ergotree_ir::serialization::SigmaSerializable;
 
 use super::EvalFn;
 
@@ -21,6 +24,20 @@ pub(crate) static GET_REG_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
         .cloned()
         .ok_or_else(|| EvalError::NotFound("register index is missing".to_string()))?
         .try_extract_into::<i8>()?;
+    get_reg(obj, reg_id)
+};
+
+/// Eval fn for `Box.R0` .. `Box.R9` properties
+pub(crate) fn register_eval_fn<const REG_ID: i8>(
+    _env: &mut Env,
+    _ctx: &mut EvalContext,
+    obj: Value,
+    _args: Vec<Value>,
+) -> Result<Value, EvalError> {
+    get_reg(obj, REG_ID)
+}
+
+fn get_reg(obj: Value, reg_id: i8) -> Result<Value, EvalError> {
     let reg_id = reg_id.try_into().map_err(|e| {
         EvalError::RegisterIdOutOfBounds(format!(
             "register index {reg_id} is out of bounds: {:?} ",
@@ -38,6 +55,39 @@ pub(crate) static GET_REG_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
             })?
             .map(|c| Value::from(c.v)),
     )))
+}
+
+pub(crate) static PROPOSITION_BYTES_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
+    Ok(obj
+        .try_extract_into::<Arc<ErgoBox>>()?
+        .script_bytes()?
+        .into())
+};
+
+pub(crate) static BYTES_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
+    Ok(obj
+        .try_extract_into::<Arc<ErgoBox>>()?
+        .sigma_serialize_bytes()?
+        .into())
+};
+
+pub(crate) static BYTES_WITHOUT_REF_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
+    Ok(obj
+        .try_extract_into::<Arc<ErgoBox>>()?
+        .bytes_without_ref()?
+        .into())
+};
+
+pub(crate) static ID_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
+    let bytes: Vec<i8> = obj.try_extract_into::<Arc<ErgoBox>>()?.box_id().into();
+    Ok(bytes.into())
+};
+
+pub(crate) static CREATION_INFO_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
+    Ok(obj
+        .try_extract_into::<Arc<ErgoBox>>()?
+        .creation_info()
+        .into())
 };
 
 pub(crate) static TOKENS_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
@@ -52,19 +102,23 @@ mod tests {
     use ergotree_ir::mir::expr::Expr;
     use ergotree_ir::mir::global_vars::GlobalVars;
     use ergotree_ir::mir::property_call::PropertyCall;
+    use ergotree_ir::serialization::SigmaSerializable;
     use ergotree_ir::types::sbox;
+    use ergotree_ir::types::smethod::SMethod;
+    use ergotree_ir::types::stype::SType;
+    use ergotree_ir::types::stype_param::STypeVar;
     use sigma_test_util::force_any_val;
 
     use crate::eval::context::Context;
     use crate::eval::tests::eval_out;
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     #[test]
     fn eval_box_value() {
         let expr: Expr = PropertyCall::new(GlobalVars::SelfBox.into(), sbox::VALUE_METHOD.clone())
             .unwrap()
             .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         assert_eq!(
             eval_out::<i64>(&expr, ctx.clone()),
             ctx.self_box.value.as_i64()
@@ -76,10 +130,58 @@ mod tests {
         let expr: Expr = PropertyCall::new(GlobalVars::SelfBox.into(), sbox::TOKENS_METHOD.clone())
             .unwrap()
             .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         assert_eq!(
             eval_out::<Vec<(Vec<i8>, i64)>>(&expr, ctx.clone()),
             ctx.self_box.tokens_raw()
         );
     }
+
+    #[test]
+    fn eval_box_bytes_properties() {
+        let ctx = Arc::new(force_any_val::<Context>());
+        let prop = |m: &SMethod| -> Expr {
+            PropertyCall::new(GlobalVars::SelfBox.into(), m.clone())
+                .unwrap()
+                .into()
+        };
+        assert_eq!(
+            eval_out::<Vec<i8>>(&prop(&sbox::PROPOSITION_BYTES_METHOD), ctx.clone()),
+            ctx.self_box.script_bytes().unwrap()
+        );
+        assert_eq!(
+            eval_out::<Vec<u8>>(&prop(&sbox::BYTES_METHOD), ctx.clone()),
+            ctx.self_box.sigma_serialize_bytes().unwrap()
+        );
+        assert_eq!(
+            eval_out::<Vec<i8>>(&prop(&sbox::BYTES_WITHOUT_REF_METHOD), ctx.clone()),
+            ctx.self_box.bytes_without_ref().unwrap()
+        );
+        let id: Vec<i8> = ctx.self_box.box_id().into();
+        assert_eq!(
+            eval_out::<Vec<i8>>(&prop(&sbox::ID_METHOD), ctx.clone()),
+            id
+        );
+        assert_eq!(
+            eval_out::<(i32, Vec<i8>)>(&prop(&sbox::CREATION_INFO_METHOD), ctx.clone()),
+            ctx.self_box.creation_info()
+        );
+    }
+
+    #[test]
+    fn eval_box_registers() {
+        let ctx = Arc::new(force_any_val::<Context>());
+        let expr: Expr = PropertyCall::new(
+            GlobalVars::SelfBox.into(),
+            sbox::REGISTER_METHODS[0]
+                .clone()
+                .with_concrete_types(&[(STypeVar::t(), SType::SLong)].iter().cloned().collect()),
+        )
+        .unwrap()
+        .into();
+        assert_eq!(
+            eval_out::<Option<i64>>(&expr, ctx.clone()),
+            Some(ctx.self_box.value.as_i64())
+        );
+    }
 }
@@ -6,6 +6,7 @@ use ergotree_ir::mir::expr::Expr;
 use ergotree_ir::mir::value::CollKind;
 use ergotree_ir::mir::value::Value;
 use ergotree_ir::types::stuple::STuple;
+use ergotree_ir::types::stype::SType;
 use ergotree_ir::types::stype::SType::SInt;
 
 use super::EvalFn;
@@ -330,6 +331,55 @@ pub(crate) static UPDATE_MANY_EVAL_FN: EvalFn =
         Ok(Value::Coll(CollKind::from_vec(input_tpe, res)?))
     };
 
+fn coll_vals(method: &str, v: Value) -> Result<(SType, Vec<Value>), EvalError> {
+    match v {
+        Value::Coll(coll) => Ok((coll.elem_tpe().clone(), coll.as_vec())),
+        _ => Err(EvalError::UnexpectedValue(format!(
+            "{method}: expected Value::Coll, got: {0:?}",
+            v
+        ))),
+    }
+}
+
+pub(crate) static REVERSE_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
+    let (input_tpe, mut vals) = coll_vals("reverse", obj)?;
+    vals.reverse();
+    Ok(Value::Coll(CollKind::from_vec(input_tpe, vals)?))
+};
+
+pub(crate) static STARTS_WITH_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
+    let (_, vals) = coll_vals("startsWith", obj)?;
+    let prefix_arg = args
+        .get(0)
+        .cloned()
+        .ok_or_else(|| EvalError::NotFound("startsWith: missing first arg".to_string()))?;
+    let (_, prefix) = coll_vals("startsWith", prefix_arg)?;
+    Ok(vals.starts_with(&prefix).into())
+};
+
+pub(crate) static ENDS_WITH_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
+    let (_, vals) = coll_vals("endsWith", obj)?;
+    let suffix_arg = args
+        .get(0)
+        .cloned()
+        .ok_or_else(|| EvalError::NotFound("endsWith: missing first arg".to_string()))?;
+    let (_, suffix) = coll_vals("endsWith", suffix_arg)?;
+    Ok(vals.ends_with(&suffix).into())
+};
+
+pub(crate) static GET_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
+    let (_, vals) = coll_vals("get", obj)?;
+    let index = args
+        .get(0)
+        .cloned()
+        .ok_or_else(|| EvalError::NotFound("get: missing first arg (index)".to_string()))?
+        .try_extract_into::<i32>()?;
+    let elem = usize::try_from(index)
+        .ok()
+        .and_then(|i| vals.get(i).cloned());
+    Ok(Value::Opt(Box::new(elem)))
+};
+
 #[allow(clippy::unwrap_used)]
 #[cfg(test)]
 #[cfg(feature = "arbitrary")]
@@ -343,10 +393,11 @@ mod tests {
     use ergotree_ir::mir::val_use::ValUse;
     use ergotree_ir::mir::value::CollKind;
     use ergotree_ir::types::scoll;
+    use ergotree_ir::types::smethod::SMethod;
     use ergotree_ir::types::stype::SType;
     use ergotree_ir::types::stype_param::STypeVar;
 
-    use crate::eval::tests::{eval_out_wo_ctx, try_eval_out_wo_ctx};
+    use crate::eval::tests::{eval_out_v6, eval_out_wo_ctx, try_eval_out_wo_ctx};
 
     #[test]
     fn eval_index_of() {
@@ -751,4 +802,50 @@ mod tests {
         .into();
         assert!(try_eval_out_wo_ctx::<Vec<i64>>(&expr).is_err());
     }
+
+    fn long_coll_call(method: &SMethod, args: Vec<Expr>) -> Expr {
+        let coll_const: Constant = vec![1i64, 2i64, 3i64].into();
+        MethodCall::new(
+            coll_const.into(),
+            method
+                .clone()
+                .with_concrete_types(&[(STypeVar::t(), SType::SLong)].iter().cloned().collect()),
+            args,
+        )
+        .unwrap()
+        .into()
+    }
+
+    #[test]
+    fn eval_reverse() {
+        let expr = long_coll_call(&scoll::REVERSE_METHOD, vec![]);
+        assert_eq!(eval_out_v6::<Vec<i64>>(&expr), vec![3i64, 2, 1]);
+    }
+
+    #[test]
+    fn eval_starts_ends_with() {
+        let prefix: Constant = vec![1i64, 2i64].into();
+        let suffix: Constant = vec![2i64, 3i64].into();
+        let expr = long_coll_call(&scoll::STARTS_WITH_METHOD, vec![prefix.clone().into()]);
+        assert!(eval_out_v6::<bool>(&expr));
+        let expr = long_coll_call(&scoll::STARTS_WITH_METHOD, vec![suffix.clone().into()]);
+        assert!(!eval_out_v6::<bool>(&expr));
+        let expr = long_coll_call(&scoll::ENDS_WITH_METHOD, vec![suffix.into()]);
+        assert!(eval_out_v6::<bool>(&expr));
+        let expr = long_coll_call(&scoll::ENDS_WITH_METHOD, vec![prefix.into()]);
+        assert!(!eval_out_v6::<bool>(&expr));
+    }
+
+    #[test]
+    fn eval_get() {
+        let expr = long_coll_call(&scoll::GET_METHOD, vec![1i32.into()]);
+        assert_eq!(eval_out_v6::<Option<i64>>(&expr), Some(2));
+        let expr = long_coll_call(&scoll::GET_METHOD, vec![3i32.into()]);
+        assert_eq!(eval_out_v6::<Option<i64>>(&expr), None);
+        let expr = long_coll_call(&scoll::GET_METHOD, vec![(-1i32).into()]);
+        assert_eq!(eval_out_v6::<Option<i64>>(&expr), None);
+        // not available before v6.0 activation
+        let expr = long_coll_call(&scoll::REVERSE_METHOD, vec![]);
+        assert!(try_eval_out_wo_ctx::<Vec<i64>>(&expr).is_err());
+    }
 }
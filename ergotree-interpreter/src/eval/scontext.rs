@@ -1,9 +1,13 @@
 use ergotree_ir::mir::avl_tree_data::AvlTreeData;
 use ergotree_ir::mir::avl_tree_data::AvlTreeFlags;
+use ergotree_ir::mir::constant::TryExtractFromError;
+use ergotree_ir::mir::constant::TryExtractInto;
 use ergotree_ir::mir::value::CollKind;
 use ergotree_ir::mir::value::Value;
+use ergotree_ir::serialization::SigmaSerializable;
 use ergotree_ir::types::stype::SType;
 
+use super::EvalContext;
 use super::EvalError;
 use super::EvalFn;
 
@@ -24,6 +28,56 @@ pub(crate) static DATA_INPUTS_EVAL_FN: EvalFn = |_env, ctx, obj, _args| {
     }))
 };
 
+pub(crate) static INPUTS_EVAL_FN: EvalFn = |_env, ctx, obj, _args| {
+    if obj != Value::Context {
+        return Err(EvalError::UnexpectedValue(format!(
+            "Context.INPUTS: expected object of Value::Context, got {:?}",
+            obj
+        )));
+    }
+    Ok(ctx.ctx.inputs.as_vec().clone().into())
+};
+
+pub(crate) static OUTPUTS_EVAL_FN: EvalFn = |_env, ctx, obj, _args| {
+    if obj != Value::Context {
+        return Err(EvalError::UnexpectedValue(format!(
+            "Context.OUTPUTS: expected object of Value::Context, got {:?}",
+            obj
+        )));
+    }
+    Ok(ctx.ctx.outputs.clone().into())
+};
+
+pub(crate) static HEIGHT_EVAL_FN: EvalFn = |_env, ctx, obj, _args| {
+    if obj != Value::Context {
+        return Err(EvalError::UnexpectedValue(format!(
+            "Context.HEIGHT: expected object of Value::Context, got {:?}",
+            obj
+        )));
+    }
+    Ok((ctx.ctx.height as i32).into())
+};
+
+pub(crate) static SELF_EVAL_FN: EvalFn = |_env, ctx, obj, _args| {
+    if obj != Value::Context {
+        return Err(EvalError::UnexpectedValue(format!(
+            "Context.SELF: expected object of Value::Context, got {:?}",
+            obj
+        )));
+    }
+    Ok(ctx.ctx.self_box.clone().into())
+};
+
+pub(crate) static MINER_PUBKEY_EVAL_FN: EvalFn = |_env, ctx, obj, _args| {
+    if obj != Value::Context {
+        return Err(EvalError::UnexpectedValue(format!(
+            "Context.minerPubKey: expected object of Value::Context, got {:?}",
+            obj
+        )));
+    }
+    Ok(ctx.ctx.pre_header.miner_pk.sigma_serialize_bytes()?.into())
+};
+
 pub(crate) static SELF_BOX_INDEX_EVAL_FN: EvalFn = |_env, ctx, obj, _args| {
     if obj != Value::Context {
         return Err(EvalError::UnexpectedValue(format!(
@@ -87,20 +141,67 @@ pub(crate) static LAST_BLOCK_UTXO_ROOT_HASH_EVAL_FN: EvalFn = |_env, ctx, obj, _
     })))
 };
 
+/// Eval `Context.getVar[T](id)`. Unlike the other methods it needs the concrete `T` of the call,
+/// so it is dispatched from [`ergotree_ir::mir::method_call::MethodCall`] evaluation with the
+/// call's result type `Option[T]`
+pub(crate) fn get_var(
+    ctx: &EvalContext,
+    obj: Value,
+    args: Vec<Value>,
+    res_tpe: &SType,
+) -> Result<Value, EvalError> {
+    if obj != Value::Context {
+        return Err(EvalError::UnexpectedValue(format!(
+            "Context.getVar: expected object of Value::Context, got {:?}",
+            obj
+        )));
+    }
+    let var_tpe = match res_tpe {
+        SType::SOption(tpe) => tpe.as_ref(),
+        tpe => {
+            return Err(EvalError::UnexpectedValue(format!(
+                "Context.getVar: expected result type Option[T], got {:?}",
+                tpe
+            )))
+        }
+    };
+    let var_id = args
+        .first()
+        .cloned()
+        .ok_or_else(|| EvalError::NotFound("Context.getVar: var id is missing".to_string()))?
+        .try_extract_into::<i8>()? as u8;
+    match ctx.ctx.extension.values.get(&var_id) {
+        None => Ok(Value::Opt(None.into())),
+        Some(v) if v.tpe == *var_tpe => Ok((Some(v.v.clone())).into()),
+        Some(v) => Err(TryExtractFromError(format!(
+            "Context.getVar: expected extension value id {} to have type {:?}, found {:?} in context extension map {}",
+            var_id, var_tpe, v, ctx.ctx.extension
+        ))
+        .into()),
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "arbitrary")]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use crate::eval::context::{Context, TxIoVec};
-    use crate::eval::tests::eval_out;
+    use crate::eval::tests::{eval_out, force_any_ctx_with_version, try_eval_out};
     use ergo_chain_types::{Header, PreHeader};
     use ergotree_ir::chain::ergo_box::ErgoBox;
+    use ergotree_ir::ergo_tree::ErgoTreeVersion;
     use ergotree_ir::mir::avl_tree_data::{AvlTreeData, AvlTreeFlags};
+    use ergotree_ir::mir::constant::Constant;
     use ergotree_ir::mir::expr::Expr;
+    use ergotree_ir::mir::method_call::MethodCall;
     use ergotree_ir::mir::property_call::PropertyCall;
+    use ergotree_ir::serialization::SigmaSerializable;
     use ergotree_ir::types::scontext;
+    use ergotree_ir::types::smethod::SMethod;
+    use ergotree_ir::types::stype::SType;
+    use ergotree_ir::types::stype_param::STypeVar;
     use sigma_test_util::force_any_val;
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     fn make_ctx_inputs_includes_self_box() -> Context {
         let ctx = force_any_val::<Context>();
@@ -124,7 +225,7 @@ mod tests {
             PropertyCall::new(Expr::Context, scontext::SELF_BOX_INDEX_PROPERTY.clone())
                 .unwrap()
                 .into();
-        let rc = Rc::new(make_ctx_inputs_includes_self_box());
+        let rc = Arc::new(make_ctx_inputs_includes_self_box());
         assert_eq!(eval_out::<i32>(&expr, rc), 1);
     }
 
@@ -133,7 +234,7 @@ mod tests {
         let expr: Expr = PropertyCall::new(Expr::Context, scontext::HEADERS_PROPERTY.clone())
             .expect("internal error: `headers` method has parameters length != 1")
             .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         assert_eq!(eval_out::<[Header; 10]>(&expr, ctx.clone()), ctx.headers);
     }
 
@@ -142,7 +243,7 @@ mod tests {
         let expr: Expr = PropertyCall::new(Expr::Context, scontext::PRE_HEADER_PROPERTY.clone())
             .unwrap()
             .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         assert_eq!(eval_out::<PreHeader>(&expr, ctx.clone()), ctx.pre_header);
     }
 
@@ -154,7 +255,7 @@ mod tests {
         )
         .unwrap()
         .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let digest = ctx.headers[0].state_root;
         let tree_flags = AvlTreeFlags::new(true, true, true);
         let avl_tree_data = AvlTreeData {
@@ -165,4 +266,70 @@ mod tests {
         };
         assert_eq!(eval_out::<AvlTreeData>(&expr, ctx), avl_tree_data);
     }
+
+    #[test]
+    fn eval_tx_properties() {
+        let ctx = Arc::new(force_any_val::<Context>());
+        let prop =
+            |m: &SMethod| -> Expr { PropertyCall::new(Expr::Context, m.clone()).unwrap().into() };
+        assert_eq!(
+            eval_out::<Vec<Arc<ErgoBox>>>(&prop(&scontext::INPUTS_PROPERTY), ctx.clone()),
+            *ctx.inputs.as_vec()
+        );
+        assert_eq!(
+            eval_out::<Vec<Arc<ErgoBox>>>(&prop(&scontext::OUTPUTS_PROPERTY), ctx.clone()),
+            ctx.outputs
+        );
+        assert_eq!(
+            eval_out::<i32>(&prop(&scontext::HEIGHT_PROPERTY), ctx.clone()),
+            ctx.height as i32
+        );
+        assert_eq!(
+            eval_out::<Arc<ErgoBox>>(&prop(&scontext::SELF_PROPERTY), ctx.clone()),
+            ctx.self_box
+        );
+        assert_eq!(
+            eval_out::<Vec<u8>>(&prop(&scontext::MINER_PUBKEY_PROPERTY), ctx.clone()),
+            ctx.pre_header.miner_pk.sigma_serialize_bytes().unwrap()
+        );
+    }
+
+    fn get_var_call(var_id: i8, tpe: SType) -> Expr {
+        MethodCall::new(
+            Expr::Context,
+            scontext::GET_VAR_METHOD.clone(),
+            vec![Constant::from(var_id).into()],
+        )
+        .unwrap()
+        .with_explicit_type_args([(STypeVar::t(), tpe)].into_iter().collect())
+        .unwrap()
+        .into()
+    }
+
+    fn ctx_with_var(version: ErgoTreeVersion) -> Arc<Context> {
+        let mut ctx = force_any_ctx_with_version(version);
+        ctx.extension.values.clear();
+        ctx.extension.values.insert(3, 123i32.into());
+        Arc::new(ctx)
+    }
+
+    #[test]
+    fn eval_get_var() {
+        let ctx = ctx_with_var(ErgoTreeVersion::V6_SOFT_FORK_VERSION);
+        assert_eq!(
+            eval_out::<Option<i32>>(&get_var_call(3, SType::SInt), ctx.clone()),
+            Some(123)
+        );
+        assert_eq!(
+            eval_out::<Option<i32>>(&get_var_call(4, SType::SInt), ctx.clone()),
+            None
+        );
+        assert!(try_eval_out::<Option<i64>>(&get_var_call(3, SType::SLong), ctx).is_err());
+    }
+
+    #[test]
+    fn eval_get_var_before_v6() {
+        let ctx = ctx_with_var(ErgoTreeVersion::V2);
+        assert!(try_eval_out::<Option<i32>>(&get_var_call(3, SType::SInt), ctx).is_err());
+    }
 }
@@ -1,6 +1,18 @@
+use std::convert::TryFrom;
+
 use crate::eval::EvalError;
 
+use ergotree_ir::bigint256::BigInt256;
+use ergotree_ir::mir::constant::Constant;
+use ergotree_ir::mir::constant::TryExtractInto;
 use ergotree_ir::mir::value::{CollKind, NativeColl, Value};
+use ergotree_ir::serialization::data::DataSerializer;
+use ergotree_ir::serialization::sigma_byte_writer::SigmaByteWrite;
+use ergotree_ir::serialization::sigma_byte_writer::SigmaByteWriter;
+use ergotree_ir::types::stype::SType;
+use ergotree_ir::unsignedbigint256::UnsignedBigInt256;
+use num_bigint::BigInt;
+use sigma_util::AsVecU8;
 
 use ergo_chain_types::ec_point::generator;
 
@@ -52,6 +64,105 @@ pub(crate) static XOR_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
     }
 };
 
+pub(crate) static SERIALIZE_EVAL_FN: EvalFn = |_env, ctx, obj, args| {
+    if obj != Value::Global {
+        return Err(EvalError::UnexpectedValue(format!(
+            "sglobal.serialize expected obj to be Value::Global, got {:?}",
+            obj
+        )));
+    }
+    let arg = args
+        .get(0)
+        .cloned()
+        .ok_or_else(|| EvalError::NotFound("serialize: missing first arg".to_string()))?;
+    let c = Constant::try_from(arg).map_err(EvalError::Misc)?;
+    let mut data = Vec::new();
+    let mut w = SigmaByteWriter::new(&mut data, None);
+    w.set_tree_version(ctx.ctx.activated_script_version());
+    DataSerializer::sigma_serialize(&c.v, &mut w)?;
+    Ok(data.into())
+};
+
+/// Parse numeric value of type `tpe` from its big-endian representation
+fn from_big_endian_bytes(tpe: &SType, obj: Value, args: Vec<Value>) -> Result<Value, EvalError> {
+    if obj != Value::Global {
+        return Err(EvalError::UnexpectedValue(format!(
+            "sglobal.fromBigEndianBytes expected obj to be Value::Global, got {:?}",
+            obj
+        )));
+    }
+    let bytes = args
+        .get(0)
+        .cloned()
+        .ok_or_else(|| EvalError::NotFound("fromBigEndianBytes: missing first arg".to_string()))?
+        .try_extract_into::<Vec<i8>>()?
+        .as_vec_u8();
+    let size_err = |expected: &str| {
+        EvalError::UnexpectedValue(format!(
+            "fromBigEndianBytes: expected {expected} bytes for {tpe:?}, got {}",
+            bytes.len()
+        ))
+    };
+    Ok(match tpe {
+        SType::SByte => {
+            i8::from_be_bytes(<[u8; 1]>::try_from(bytes.as_slice()).map_err(|_| size_err("1"))?)
+                .into()
+        }
+        SType::SShort => {
+            i16::from_be_bytes(<[u8; 2]>::try_from(bytes.as_slice()).map_err(|_| size_err("2"))?)
+                .into()
+        }
+        SType::SInt => {
+            i32::from_be_bytes(<[u8; 4]>::try_from(bytes.as_slice()).map_err(|_| size_err("4"))?)
+                .into()
+        }
+        SType::SLong => {
+            i64::from_be_bytes(<[u8; 8]>::try_from(bytes.as_slice()).map_err(|_| size_err("8"))?)
+                .into()
+        }
+        SType::SBigInt => {
+            if bytes.is_empty() || bytes.len() > 32 {
+                return Err(size_err("1..=32"));
+            }
+            BigInt256::try_from(BigInt::from_signed_bytes_be(&bytes))
+                .map_err(EvalError::Misc)?
+                .into()
+        }
+        SType::SUnsignedBigInt => {
+            if bytes.is_empty() || bytes.len() > UnsignedBigInt256::MAX_SIZE_BYTES {
+                return Err(size_err("1..=32"));
+            }
+            UnsignedBigInt256::from_bytes_be(&bytes)
+                .map_err(EvalError::Misc)?
+                .into()
+        }
+        _ => {
+            return Err(EvalError::UnexpectedValue(format!(
+                "fromBigEndianBytes: unsupported type {:?}",
+                tpe
+            )))
+        }
+    })
+}
+
+pub(crate) static FROM_BIG_ENDIAN_BYTES_BYTE_EVAL_FN: EvalFn =
+    |_env, _ctx, obj, args| from_big_endian_bytes(&SType::SByte, obj, args);
+
+pub(crate) static FROM_BIG_ENDIAN_BYTES_SHORT_EVAL_FN: EvalFn =
+    |_env, _ctx, obj, args| from_big_endian_bytes(&SType::SShort, obj, args);
+
+pub(crate) static FROM_BIG_ENDIAN_BYTES_INT_EVAL_FN: EvalFn =
+    |_env, _ctx, obj, args| from_big_endian_bytes(&SType::SInt, obj, args);
+
+pub(crate) static FROM_BIG_ENDIAN_BYTES_LONG_EVAL_FN: EvalFn =
+    |_env, _ctx, obj, args| from_big_endian_bytes(&SType::SLong, obj, args);
+
+pub(crate) static FROM_BIG_ENDIAN_BYTES_BIGINT_EVAL_FN: EvalFn =
+    |_env, _ctx, obj, args| from_big_endian_bytes(&SType::SBigInt, obj, args);
+
+pub(crate) static FROM_BIG_ENDIAN_BYTES_UNSIGNED_BIGINT_EVAL_FN: EvalFn =
+    |_env, _ctx, obj, args| from_big_endian_bytes(&SType::SUnsignedBigInt, obj, args);
+
 #[allow(clippy::unwrap_used)]
 #[cfg(test)]
 #[cfg(feature = "arbitrary")]
@@ -60,11 +171,16 @@ mod tests {
     use ergotree_ir::mir::expr::Expr;
     use ergotree_ir::mir::method_call::MethodCall;
     use ergotree_ir::mir::property_call::PropertyCall;
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     use crate::eval::context::Context;
-    use crate::eval::tests::eval_out;
+    use crate::eval::tests::{eval_out, eval_out_v6, try_eval_out_v6, try_eval_out_wo_ctx};
+    use ergotree_ir::mir::constant::Constant;
+    use ergotree_ir::mir::value::Value;
     use ergotree_ir::types::sglobal;
+    use ergotree_ir::types::stype::SType;
+    use ergotree_ir::types::stype_param::STypeVar;
+    use ergotree_ir::unsignedbigint256::UnsignedBigInt256;
     use sigma_test_util::force_any_val;
 
     #[test]
@@ -72,7 +188,7 @@ mod tests {
         let expr: Expr = PropertyCall::new(Expr::Global, sglobal::GROUP_GENERATOR_METHOD.clone())
             .unwrap()
             .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         assert_eq!(
             eval_out::<EcPoint>(&expr, ctx),
             ergo_chain_types::ec_point::generator()
@@ -92,7 +208,71 @@ mod tests {
         )
         .unwrap()
         .into();
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         assert_eq!(eval_out::<Vec<i8>>(&expr, ctx), expected_xor);
     }
+
+    fn serialize_call(arg: Constant) -> Expr {
+        let method = sglobal::SERIALIZE_METHOD
+            .clone()
+            .specialize_for(SType::SGlobal, vec![arg.tpe.clone()])
+            .unwrap();
+        MethodCall::new(Expr::Global, method, vec![arg.into()])
+            .unwrap()
+            .into()
+    }
+
+    fn from_big_endian_bytes_call(tpe: SType, bytes: Vec<i8>) -> Expr {
+        MethodCall::new(
+            Expr::Global,
+            sglobal::FROM_BIG_ENDIAN_BYTES_METHOD.clone(),
+            vec![bytes.into()],
+        )
+        .unwrap()
+        .with_explicit_type_args([(STypeVar::t(), tpe)].into_iter().collect())
+        .unwrap()
+        .into()
+    }
+
+    #[test]
+    fn eval_serialize() {
+        assert_eq!(
+            eval_out_v6::<Vec<i8>>(&serialize_call(1i32.into())),
+            vec![2]
+        );
+        assert_eq!(
+            eval_out_v6::<Vec<i8>>(&serialize_call(Some(5i64).into())),
+            vec![1, 10]
+        );
+        assert!(try_eval_out_wo_ctx::<Vec<i8>>(&serialize_call(1i32.into())).is_err());
+    }
+
+    #[test]
+    fn eval_from_big_endian_bytes() {
+        assert_eq!(
+            eval_out_v6::<i16>(&from_big_endian_bytes_call(SType::SShort, vec![1, 2])),
+            0x0102
+        );
+        assert_eq!(
+            eval_out_v6::<i64>(&from_big_endian_bytes_call(
+                SType::SLong,
+                vec![-1, -1, -1, -1, -1, -1, -1, -2]
+            )),
+            -2
+        );
+        assert_eq!(
+            eval_out_v6::<UnsignedBigInt256>(&from_big_endian_bytes_call(
+                SType::SUnsignedBigInt,
+                vec![-1; 32]
+            )),
+            UnsignedBigInt256::max_value()
+        );
+        assert!(
+            try_eval_out_v6::<Value>(&from_big_endian_bytes_call(SType::SInt, vec![1, 2])).is_err()
+        );
+        assert!(
+            try_eval_out_v6::<Value>(&from_big_endian_bytes_call(SType::SBigInt, vec![1; 33]))
+                .is_err()
+        );
+    }
 }
@@ -3,6 +3,8 @@ use crate::eval::EvalError;
 use ergo_chain_types::EcPoint;
 use ergotree_ir::mir::value::Value;
 use ergotree_ir::serialization::SigmaSerializable;
+use ergotree_ir::sigma_protocol::dlog_group;
+use k256::Scalar;
 
 use super::EvalFn;
 
@@ -18,6 +20,40 @@ pub(crate) static GET_ENCODED_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
     Ok(Value::from(encoded))
 };
 
+pub(crate) static EXPONENTIATE_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
+    let arg = args
+        .get(0)
+        .cloned()
+        .ok_or_else(|| EvalError::NotFound("exp: missing exponent argument".to_string()))?;
+    let exp_scalar: Option<Scalar> = match arg.clone() {
+        Value::BigInt(bi) => dlog_group::bigint256_to_scalar(bi),
+        _ => None,
+    };
+    match (obj.clone(), exp_scalar) {
+        (Value::GroupElement(ec_point), Some(exp)) => {
+            Ok(ergo_chain_types::ec_point::exponentiate(&ec_point, &exp).into())
+        }
+        _ => Err(EvalError::UnexpectedValue(format!(
+            "GroupElement.exp: expected GroupElement and BigInt (positive, <= 256 bit), got: {0:?}",
+            (obj, arg)
+        ))),
+    }
+};
+
+pub(crate) static MULTIPLY_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
+    let arg = args
+        .get(0)
+        .cloned()
+        .ok_or_else(|| EvalError::NotFound("multiply: missing argument".to_string()))?;
+    match (obj.clone(), arg.clone()) {
+        (Value::GroupElement(left), Value::GroupElement(right)) => Ok((*left * &*right).into()),
+        _ => Err(EvalError::UnexpectedValue(format!(
+            "GroupElement.multiply: expected GroupElement arguments, got: {0:?}",
+            (obj, arg)
+        ))),
+    }
+};
+
 pub(crate) static NEGATE_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
     let negated: EcPoint = match obj {
         Value::GroupElement(ec_point) => Ok(-(*ec_point)),
@@ -39,6 +75,7 @@ mod tests {
 
     use crate::eval::tests::eval_out_wo_ctx;
     use ergo_chain_types::EcPoint;
+    use ergotree_ir::bigint256::BigInt256;
     use ergotree_ir::serialization::SigmaSerializable;
     use sigma_test_util::force_any_val;
 
@@ -72,4 +109,33 @@ mod tests {
         .into();
         assert_eq!(-input, eval_out_wo_ctx::<EcPoint>(&expr))
     }
+
+    #[test]
+    fn eval_exp() {
+        let input = force_any_val::<EcPoint>();
+        let exp = BigInt256::from(3i64);
+        let expr: Expr = MethodCall::new(
+            input.clone().into(),
+            sgroup_elem::EXPONENTIATE_METHOD.clone(),
+            vec![Expr::Const(exp.into())],
+        )
+        .unwrap()
+        .into();
+        let expected = input.clone() * &input * &input;
+        assert_eq!(eval_out_wo_ctx::<EcPoint>(&expr), expected)
+    }
+
+    #[test]
+    fn eval_multiply() {
+        let left = force_any_val::<EcPoint>();
+        let right = force_any_val::<EcPoint>();
+        let expr: Expr = MethodCall::new(
+            left.clone().into(),
+            sgroup_elem::MULTIPLY_METHOD.clone(),
+            vec![right.clone().into()],
+        )
+        .unwrap()
+        .into();
+        assert_eq!(eval_out_wo_ctx::<EcPoint>(&expr), left * &right)
+    }
 }
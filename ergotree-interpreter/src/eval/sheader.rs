@@ -3,7 +3,10 @@
 use std::convert::TryInto;
 
 use ergo_chain_types::Header;
+use ergo_nipopow::{decode_compact_bits, NipopowAlgos};
+use ergotree_ir::sigma_protocol::dlog_group::order;
 use ergotree_ir::{bigint256::BigInt256, mir::constant::TryExtractInto};
+use num_traits::Zero;
 
 use super::{EvalError, EvalFn};
 
@@ -92,12 +95,25 @@ pub(crate) static VOTES_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
     Ok(Into::<Vec<u8>>::into(header.votes).into())
 };
 
+pub(crate) static CHECK_POW_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
+    let header = obj.try_extract_into::<Header>()?;
+    let target_b = decode_compact_bits(header.n_bits);
+    if target_b.is_zero() {
+        return Ok(false.into());
+    }
+    let hit = NipopowAlgos::default()
+        .pow_scheme
+        .pow_hit(&header)
+        .map_err(|e| EvalError::Misc(format!("checkPow: {:?}", e)))?;
+    Ok((hit < order() / target_b).into())
+};
+
 #[cfg(test)]
 #[cfg(feature = "arbitrary")]
 #[allow(clippy::expect_used, clippy::panic, clippy::unwrap_used)]
 mod tests {
     use std::convert::{TryFrom, TryInto};
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     use ergo_chain_types::{BlockId, Digest, Digest32, EcPoint, Votes};
     use ergotree_ir::{
@@ -110,14 +126,16 @@ mod tests {
 
     use crate::eval::{
         context::Context,
-        tests::{eval_out, try_eval_out_wo_ctx},
+        tests::{eval_out, force_any_ctx_with_version, try_eval_out, try_eval_out_wo_ctx},
+        EvalError,
     };
+    use ergotree_ir::ergo_tree::ErgoTreeVersion;
 
     // Index in Context.headers array
     const HEADER_INDEX: usize = 0;
 
     // Evaluates `Header.minerPk`, `Header.powOnetimePk`
-    fn eval_header_pks(ctx: Rc<Context>) -> [Box<EcPoint>; 2] {
+    fn eval_header_pks(ctx: Arc<Context>) -> [Box<EcPoint>; 2] {
         let miner_pk = eval_out::<EcPoint>(
             &create_get_header_property_expr(sheader::MINER_PK_PROPERTY.clone()),
             ctx.clone(),
@@ -130,7 +148,7 @@ mod tests {
     }
 
     // Evaluates `Header.AdProofsRoot`, `Header.transactionRoot`, `Header.extensionRoot`
-    fn eval_header_roots(ctx: Rc<Context>) -> [Digest32; 3] {
+    fn eval_header_roots(ctx: Arc<Context>) -> [Digest32; 3] {
         vec![
             sheader::AD_PROOFS_ROOT_PROPERTY.clone(),
             sheader::TRANSACTIONS_ROOT_PROPERTY.clone(),
@@ -145,7 +163,7 @@ mod tests {
     }
 
     // Evaluates `Header.id` and `Header.parentId`
-    fn eval_header_ids(ctx: Rc<Context>) -> [BlockId; 2] {
+    fn eval_header_ids(ctx: Arc<Context>) -> [BlockId; 2] {
         let id = eval_out::<Vec<i8>>(
             &create_get_header_property_expr(sheader::ID_PROPERTY.clone()),
             ctx.clone(),
@@ -198,14 +216,14 @@ mod tests {
     #[test]
     fn test_eval_version() {
         let expr = create_get_header_property_expr(sheader::VERSION_PROPERTY.clone());
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let version = ctx.headers[HEADER_INDEX].version as i8;
         assert_eq!(version, eval_out::<i8>(&expr, ctx));
     }
 
     #[test]
     fn test_eval_ids() {
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let expected = ctx
             .headers
             .get(HEADER_INDEX)
@@ -217,7 +235,7 @@ mod tests {
 
     #[test]
     fn test_eval_roots() {
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let expected = ctx
             .headers
             .get(HEADER_INDEX)
@@ -230,7 +248,7 @@ mod tests {
     #[test]
     fn test_eval_state_root() {
         let expr = create_get_header_property_expr(sheader::STATE_ROOT_PROPERTY.clone());
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let expected = ctx.headers[HEADER_INDEX].state_root;
         let actual = digest_from_bytes_signed::<33>(eval_out::<Vec<i8>>(&expr, ctx));
         assert_eq!(expected, actual);
@@ -239,7 +257,7 @@ mod tests {
     #[test]
     fn test_eval_timestamp() {
         let expr = create_get_header_property_expr(sheader::TIMESTAMP_PROPERTY.clone());
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let expected = ctx.headers[HEADER_INDEX].timestamp as i64;
         let actual = eval_out::<i64>(&expr, ctx);
         assert_eq!(expected, actual);
@@ -248,7 +266,7 @@ mod tests {
     #[test]
     fn test_eval_n_bits() {
         let expr = create_get_header_property_expr(sheader::N_BITS_PROPERTY.clone());
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let expected = ctx.headers[HEADER_INDEX].n_bits as i64;
         let actual = eval_out::<i64>(&expr, ctx);
         assert_eq!(expected, actual);
@@ -257,7 +275,7 @@ mod tests {
     #[test]
     fn test_eval_height() {
         let expr = create_get_header_property_expr(sheader::HEIGHT_PROPERTY.clone());
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let expected = ctx.headers[HEADER_INDEX].height as i32;
         let actual = eval_out::<i32>(&expr, ctx);
         assert_eq!(expected, actual);
@@ -265,7 +283,7 @@ mod tests {
 
     #[test]
     fn test_eval_pks() {
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let expected = ctx
             .headers
             .get(HEADER_INDEX)
@@ -286,7 +304,7 @@ mod tests {
     #[test]
     fn test_eval_pow_distance() {
         let expr = create_get_header_property_expr(sheader::POW_DISTANCE_PROPERTY.clone());
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let expected = ctx.headers[HEADER_INDEX]
             .autolykos_solution
             .pow_distance
@@ -302,7 +320,7 @@ mod tests {
     #[test]
     fn test_eval_pow_nonce() {
         let expr = create_get_header_property_expr(sheader::POW_NONCE_PROPERTY.clone());
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let expected = ctx.headers[HEADER_INDEX].autolykos_solution.nonce.clone();
         let actual = eval_out::<Vec<i8>>(&expr, ctx).as_vec_u8();
         assert_eq!(expected, actual);
@@ -311,7 +329,7 @@ mod tests {
     #[test]
     fn test_eval_votes() {
         let expr = create_get_header_property_expr(sheader::VOTES_PROPERTY.clone());
-        let ctx = Rc::new(force_any_val::<Context>());
+        let ctx = Arc::new(force_any_val::<Context>());
         let expected = ctx.headers[HEADER_INDEX].votes.clone();
         let actual = {
             let votes_bytes = eval_out::<Vec<i8>>(&expr, ctx).as_vec_u8();
@@ -347,4 +365,22 @@ mod tests {
         let expr = create_get_header_property_expr(unknown_property);
         assert!(try_eval_out_wo_ctx::<i8>(&expr).is_err());
     }
+
+    fn eval_check_pow(header_json: &str, version: ErgoTreeVersion) -> Result<bool, EvalError> {
+        let mut ctx = force_any_ctx_with_version(version);
+        ctx.headers[HEADER_INDEX] = serde_json::from_str(header_json).unwrap();
+        let expr = create_get_header_property_expr(sheader::CHECK_POW_METHOD.clone());
+        try_eval_out::<bool>(&expr, Arc::new(ctx))
+    }
+
+    #[test]
+    fn test_eval_check_pow() {
+        // test vectors from ergo-nipopow AutolykosPowScheme tests
+        let valid = r#"{"extensionId":"00cce45975d87414e8bdd8146bc88815be59cd9fe37a125b5021101e05675a18","difficulty":"16384","votes":"000000","timestamp":4928911477310178288,"size":223,"stateRoot":"5c8c00b8403d3701557181c8df800001b6d5009e2201c6ff807d71808c00019780","height":614400,"nBits":37748736,"version":2,"id":"5603a937ec1988220fc44fb5022fb82d5565b961f005ebb55d85bd5a9e6f801f","adProofsRoot":"5d3f80dcff7f5e7f59007294c180808d0158d1ff6ba10000f901c7f0ef87dcff","transactionsRoot":"f17fffacb6ff7f7f1180d2ff7f1e24ffffe1ff937f807f0797b9ff6ebdae007e","extensionHash":"1480887f80007f4b01cf7f013ff1ffff564a0000b9a54f00770e807f41ff88c0","powSolutions":{"pk":"03bedaee069ff4829500b3c07c4d5fe6b3ea3d3bf76c5c28c1d4dcdb1bed0ade0c","n":"0000000000003105"},"adProofsId":"dec129290a763f4de41f04e87e2b661dd59758af6bdd00dd51f5d97c3a8cb9b5","transactionsId":"eba1dd82cf51147232e09c1f72b37c554c30f63274d5093bff36849a83472a42","parentId":"ac2101807f0000ca01ff0119db227f202201007f62000177a080005d440896d0"}"#;
+        let invalid = r#"{"extensionId":"277907e4e5e42f27e928e6101cc4fec173bee5d7728794b73d7448c339c380e5","difficulty":"1325481984","votes":"000000","timestamp":1611225263165,"size":219,"stateRoot":"c0d0b5eafd07b22487dac66628669c42a242b90bef3e1fcdc76d83140d58b6bc0e","height":2870,"nBits":72286528,"version":2,"id":"5b0ce6711de6b926f60b67040cc4512804517785df375d063f1bf1d75588af3a","adProofsRoot":"49453875a43035c7640dee2f905efe06128b00d41acd2c8df13691576d4fd85c","transactionsRoot":"770cbb6e18673ed025d386487f15d3252115d9a6f6c9b947cf3d04731dd6ab75","extensionHash":"9bc7d54583c5d44bb62a7be0473cd78d601822a626afc13b636f2cbff0d87faf","powSolutions":{"pk":"0288114b0586efea9f86e4587f2071bc1c85fb77e15eba96b2769733e0daf57903","w":"0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798","n":"000100000580a91b","d":0},"adProofsId":"4fc36d59bf26a672e01fbfde1445bd66f50e0f540f24102e1e27d0be1a99dfbf","transactionsId":"d196ef8a7ef582ab1fdab4ef807715183705301c6ae2ff0dcbe8f1d577ba081f","parentId":"ab19e6c7a4062979dddb534df83f236d1b949c7cef18bcf434a67e87c593eef9"}"#;
+        let v6 = ErgoTreeVersion::V6_SOFT_FORK_VERSION;
+        assert_eq!(eval_check_pow(valid, v6), Ok(true));
+        assert_eq!(eval_check_pow(invalid, v6), Ok(false));
+        assert!(eval_check_pow(valid, ErgoTreeVersion::V2).is_err());
+    }
 }
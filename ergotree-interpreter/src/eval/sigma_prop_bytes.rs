@@ -1,6 +1,8 @@
 use ergotree_ir::mir::sigma_prop_bytes::SigmaPropBytes;
 use ergotree_ir::mir::value::Value;
 
+use crate::eval::costs::sigma_boolean_nodes_count;
+use crate::eval::costs::Costs;
 use crate::eval::env::Env;
 use crate::eval::EvalContext;
 use crate::eval::EvalError;
@@ -10,7 +12,13 @@ impl Evaluable for SigmaPropBytes {
     fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
         let input_v = self.input.eval(env, ctx)?;
         match input_v {
-            Value::SigmaProp(sigma_prop) => Ok(sigma_prop.prop_bytes()?.into()),
+            Value::SigmaProp(sigma_prop) => {
+                ctx.cost_accum.add_seq_cost(
+                    Costs::SIGMA_PROP_BYTES,
+                    sigma_boolean_nodes_count(sigma_prop.value()),
+                )?;
+                Ok(sigma_prop.prop_bytes()?.into())
+            }
             _ => Err(EvalError::UnexpectedValue(format!(
                 "Expected SigmaPropBytes input to be Value::SigmaProp, got {0:?}",
                 input_v
@@ -0,0 +1,60 @@
+use ergotree_ir::mir::sigma_prop_is_proven::SigmaPropIsProven;
+use ergotree_ir::mir::value::Value;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
+
+use crate::eval::env::Env;
+use crate::eval::EvalContext;
+use crate::eval::EvalError;
+use crate::eval::Evaluable;
+
+impl Evaluable for SigmaPropIsProven {
+    fn eval(&self, env: &mut Env, ctx: &mut EvalContext) -> Result<Value, EvalError> {
+        let input_v = self.input.eval(env, ctx)?;
+        match input_v {
+            Value::SigmaProp(sigma_prop) => match sigma_prop.value() {
+                SigmaBoolean::TrivialProp(b) => Ok(Value::Boolean(*b)),
+                sb => Err(EvalError::UnexpectedValue(format!(
+                    "SigmaPropIsProven: cannot decide if non-trivial proposition is proven: {:?}",
+                    sb
+                ))),
+            },
+            _ => Err(EvalError::UnexpectedValue(format!(
+                "Expected SigmaPropIsProven input to be Value::SigmaProp, got {0:?}",
+                input_v
+            ))),
+        }
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::tests::eval_out_wo_ctx;
+    use crate::eval::tests::try_eval_out_wo_ctx;
+    use ergotree_ir::mir::bool_to_sigma::BoolToSigmaProp;
+    use ergotree_ir::mir::create_provedlog::CreateProveDlog;
+    use ergotree_ir::mir::expr::Expr;
+    use ergotree_ir::mir::global_vars::GlobalVars;
+    use ergotree_ir::mir::unary_op::OneArgOpTryBuild;
+
+    fn is_proven_expr(input: Expr) -> Expr {
+        SigmaPropIsProven::try_build(input).unwrap().into()
+    }
+
+    #[test]
+    fn eval_trivial() {
+        let prop: Expr = BoolToSigmaProp::try_build(Expr::Const(true.into()))
+            .unwrap()
+            .into();
+        assert!(eval_out_wo_ctx::<bool>(&is_proven_expr(prop)));
+    }
+
+    #[test]
+    fn eval_non_trivial() {
+        let prop: Expr = CreateProveDlog::try_build(GlobalVars::GroupGenerator.into())
+            .unwrap()
+            .into();
+        assert!(try_eval_out_wo_ctx::<bool>(&is_proven_expr(prop)).is_err());
+    }
+}
@@ -0,0 +1,477 @@
+use std::convert::TryFrom;
+
+use ergotree_ir::bigint256::BigInt256;
+use ergotree_ir::mir::value::Value;
+use ergotree_ir::unsignedbigint256::UnsignedBigInt256;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+
+use super::EvalError;
+use super::EvalFn;
+
+fn to_bigint(method: &str, obj: &Value) -> Result<BigInt, EvalError> {
+    match obj {
+        Value::Byte(v) => Ok(BigInt::from(*v)),
+        Value::Short(v) => Ok(BigInt::from(*v)),
+        Value::Int(v) => Ok(BigInt::from(*v)),
+        Value::Long(v) => Ok(BigInt::from(*v)),
+        Value::BigInt(v) => Ok(BigInt::from(v.clone())),
+        Value::UnsignedBigInt(v) => Ok(BigInt::from(v.clone())),
+        _ => Err(EvalError::UnexpectedValue(format!(
+            "{method}: expected numeric value, got {:?}",
+            obj
+        ))),
+    }
+}
+
+fn overflow_err(method: &str, v: &BigInt) -> EvalError {
+    EvalError::ArithmeticException(format!("{method}: {v} is out of range"))
+}
+
+/// Big-endian two's complement representation of the numeric value
+fn to_be_bytes(obj: &Value) -> Result<Vec<u8>, EvalError> {
+    match obj {
+        Value::Byte(v) => Ok(v.to_be_bytes().to_vec()),
+        Value::Short(v) => Ok(v.to_be_bytes().to_vec()),
+        Value::Int(v) => Ok(v.to_be_bytes().to_vec()),
+        Value::Long(v) => Ok(v.to_be_bytes().to_vec()),
+        Value::BigInt(v) => Ok(BigInt::from(v.clone()).to_signed_bytes_be()),
+        Value::UnsignedBigInt(v) => Ok(v.to_bytes_be()),
+        _ => Err(EvalError::UnexpectedValue(format!(
+            "toBytes: expected numeric value, got {:?}",
+            obj
+        ))),
+    }
+}
+
+pub(crate) static TO_BYTE_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
+    let v = to_bigint("toByte", &obj)?;
+    Ok(v.to_i8().ok_or_else(|| overflow_err("toByte", &v))?.into())
+};
+
+pub(crate) static TO_SHORT_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
+    let v = to_bigint("toShort", &obj)?;
+    Ok(v.to_i16()
+        .ok_or_else(|| overflow_err("toShort", &v))?
+        .into())
+};
+
+pub(crate) static TO_INT_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
+    let v = to_bigint("toInt", &obj)?;
+    Ok(v.to_i32().ok_or_else(|| overflow_err("toInt", &v))?.into())
+};
+
+pub(crate) static TO_LONG_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
+    let v = to_bigint("toLong", &obj)?;
+    Ok(v.to_i64().ok_or_else(|| overflow_err("toLong", &v))?.into())
+};
+
+pub(crate) static TO_BIGINT_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
+    let v = to_bigint("toBigInt", &obj)?;
+    Ok(BigInt256::try_from(v.clone())
+        .map_err(|_| overflow_err("toBigInt", &v))?
+        .into())
+};
+
+pub(crate) static TO_BYTES_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| Ok(to_be_bytes(&obj)?.into());
+
+pub(crate) static TO_BITS_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
+    let bits: Vec<bool> = to_be_bytes(&obj)?
+        .iter()
+        .flat_map(|b| (0..8).rev().map(move |i| (b >> i) & 1 == 1))
+        .collect();
+    Ok(bits.into())
+};
+
+fn bitwise_op(
+    method: &str,
+    obj: Value,
+    args: Vec<Value>,
+    op: impl Fn(&BigInt, &BigInt) -> BigInt,
+) -> Result<Value, EvalError> {
+    let arg = args
+        .get(0)
+        .cloned()
+        .ok_or_else(|| EvalError::NotFound(format!("{method}: missing argument")))?;
+    // operands are of the same type, so the result always fits
+    let res = op(&to_bigint(method, &obj)?, &to_bigint(method, &arg)?);
+    let res_err = || overflow_err(method, &res);
+    Ok(match obj {
+        Value::Byte(_) => res.to_i8().ok_or_else(res_err)?.into(),
+        Value::Short(_) => res.to_i16().ok_or_else(res_err)?.into(),
+        Value::Int(_) => res.to_i32().ok_or_else(res_err)?.into(),
+        Value::Long(_) => res.to_i64().ok_or_else(res_err)?.into(),
+        Value::BigInt(_) => BigInt256::try_from(res.clone())
+            .map_err(|_| res_err())?
+            .into(),
+        Value::UnsignedBigInt(_) => UnsignedBigInt256::try_from(res.clone())
+            .map_err(|_| res_err())?
+            .into(),
+        _ => {
+            return Err(EvalError::UnexpectedValue(format!(
+                "{method}: expected numeric value, got {:?}",
+                obj
+            )))
+        }
+    })
+}
+
+/// Shift amount checked against the bit size of the receiver type
+fn shift_bits(method: &str, obj: &Value, args: &[Value]) -> Result<u32, EvalError> {
+    let bits: i32 = match obj {
+        Value::Byte(_) => 8,
+        Value::Short(_) => 16,
+        Value::Int(_) => 32,
+        Value::Long(_) => 64,
+        _ => 256,
+    };
+    match args.get(0) {
+        Some(Value::Int(n)) if *n >= 0 && *n < bits => Ok(*n as u32),
+        Some(Value::Int(n)) => Err(EvalError::ArithmeticException(format!(
+            "{method}: shift {n} is out of range [0, {bits})"
+        ))),
+        arg => Err(EvalError::UnexpectedValue(format!(
+            "{method}: expected Int argument, got {:?}",
+            arg
+        ))),
+    }
+}
+
+pub(crate) static BITWISE_INVERSE_EVAL_FN: EvalFn = |_env, _ctx, obj, _args| {
+    Ok(match obj {
+        Value::Byte(v) => (!v).into(),
+        Value::Short(v) => (!v).into(),
+        Value::Int(v) => (!v).into(),
+        Value::Long(v) => (!v).into(),
+        Value::BigInt(v) => (!v).into(),
+        Value::UnsignedBigInt(v) => (!v).into(),
+        _ => {
+            return Err(EvalError::UnexpectedValue(format!(
+                "bitwiseInverse: expected numeric value, got {:?}",
+                obj
+            )))
+        }
+    })
+};
+
+pub(crate) static BITWISE_OR_EVAL_FN: EvalFn =
+    |_env, _ctx, obj, args| bitwise_op("bitwiseOr", obj, args, |a, b| a | b);
+
+pub(crate) static BITWISE_AND_EVAL_FN: EvalFn =
+    |_env, _ctx, obj, args| bitwise_op("bitwiseAnd", obj, args, |a, b| a & b);
+
+pub(crate) static BITWISE_XOR_EVAL_FN: EvalFn =
+    |_env, _ctx, obj, args| bitwise_op("bitwiseXor", obj, args, |a, b| a ^ b);
+
+pub(crate) static SHIFT_LEFT_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
+    let n = shift_bits("shiftLeft", &obj, &args)?;
+    Ok(match obj {
+        Value::Byte(v) => v.wrapping_shl(n).into(),
+        Value::Short(v) => v.wrapping_shl(n).into(),
+        Value::Int(v) => v.wrapping_shl(n).into(),
+        Value::Long(v) => v.wrapping_shl(n).into(),
+        Value::BigInt(v) => {
+            let res = BigInt::from(v) << n;
+            BigInt256::try_from(res.clone())
+                .map_err(|_| overflow_err("shiftLeft", &res))?
+                .into()
+        }
+        Value::UnsignedBigInt(v) => v.shl_truncated(n as usize).into(),
+        _ => {
+            return Err(EvalError::UnexpectedValue(format!(
+                "shiftLeft: expected numeric value, got {:?}",
+                obj
+            )))
+        }
+    })
+};
+
+pub(crate) static SHIFT_RIGHT_EVAL_FN: EvalFn = |_env, _ctx, obj, args| {
+    let n = shift_bits("shiftRight", &obj, &args)?;
+    Ok(match obj {
+        Value::Byte(v) => (v >> n).into(),
+        Value::Short(v) => (v >> n).into(),
+        Value::Int(v) => (v >> n).into(),
+        Value::Long(v) => (v >> n).into(),
+        Value::BigInt(v) => {
+            let res = BigInt::from(v) >> n;
+            BigInt256::try_from(res.clone())
+                .map_err(|_| overflow_err("shiftRight", &res))?
+                .into()
+        }
+        Value::UnsignedBigInt(v) => v.shr_bits(n as usize).into(),
+        _ => {
+            return Err(EvalError::UnexpectedValue(format!(
+                "shiftRight: expected numeric value, got {:?}",
+                obj
+            )))
+        }
+    })
+};
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+mod tests {
+    use ergotree_ir::mir::constant::Constant;
+    use ergotree_ir::mir::expr::Expr;
+    use ergotree_ir::mir::method_call::MethodCall;
+    use ergotree_ir::mir::property_call::PropertyCall;
+    use ergotree_ir::mir::value::Value;
+    use ergotree_ir::types::smethod::MethodId;
+    use ergotree_ir::types::snumeric;
+    use ergotree_ir::types::stype_companion::STypeCompanion;
+
+    use crate::eval::tests::eval_out_v6;
+    use crate::eval::tests::eval_out_wo_ctx;
+    use crate::eval::tests::try_eval_out_v6;
+    use crate::eval::tests::try_eval_out_wo_ctx;
+
+    use super::*;
+
+    fn call(companion: STypeCompanion, method_id: MethodId, obj: Constant) -> Expr {
+        PropertyCall::new(obj.into(), companion.method_by_id(&method_id).unwrap())
+            .unwrap()
+            .into()
+    }
+
+    fn call_with_arg(
+        companion: STypeCompanion,
+        method_id: MethodId,
+        obj: Constant,
+        arg: Constant,
+    ) -> Expr {
+        MethodCall::new(
+            obj.into(),
+            companion.method_by_id(&method_id).unwrap(),
+            vec![arg.into()],
+        )
+        .unwrap()
+        .into()
+    }
+
+    #[test]
+    fn eval_conversions() {
+        assert_eq!(
+            eval_out_wo_ctx::<i64>(&call(
+                STypeCompanion::Byte,
+                snumeric::TO_LONG_METHOD_ID,
+                (-5i8).into()
+            )),
+            -5
+        );
+        assert_eq!(
+            eval_out_wo_ctx::<i8>(&call(
+                STypeCompanion::Int,
+                snumeric::TO_BYTE_METHOD_ID,
+                127i32.into()
+            )),
+            127
+        );
+        assert_eq!(
+            eval_out_wo_ctx::<BigInt256>(&call(
+                STypeCompanion::Long,
+                snumeric::TO_BIGINT_METHOD_ID,
+                i64::MIN.into()
+            )),
+            BigInt256::from(i64::MIN)
+        );
+        assert_eq!(
+            eval_out_wo_ctx::<i16>(&call(
+                STypeCompanion::BigInt,
+                snumeric::TO_SHORT_METHOD_ID,
+                BigInt256::from(-300i64).into()
+            )),
+            -300
+        );
+    }
+
+    #[test]
+    fn eval_conversion_overflow() {
+        assert!(try_eval_out_wo_ctx::<Value>(&call(
+            STypeCompanion::Int,
+            snumeric::TO_BYTE_METHOD_ID,
+            128i32.into()
+        ))
+        .is_err());
+        assert!(try_eval_out_wo_ctx::<Value>(&call(
+            STypeCompanion::BigInt,
+            snumeric::TO_LONG_METHOD_ID,
+            BigInt256::try_from(BigInt::from(i64::MAX) + 1)
+                .unwrap()
+                .into()
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn eval_to_bytes() {
+        assert_eq!(
+            eval_out_v6::<Vec<u8>>(&call(
+                STypeCompanion::Long,
+                snumeric::TO_BYTES_METHOD_ID,
+                0x0102_0304_0506_0708i64.into()
+            )),
+            vec![1, 2, 3, 4, 5, 6, 7, 8]
+        );
+        assert_eq!(
+            eval_out_v6::<Vec<u8>>(&call(
+                STypeCompanion::Short,
+                snumeric::TO_BYTES_METHOD_ID,
+                (-2i16).into()
+            )),
+            vec![0xff, 0xfe]
+        );
+        assert_eq!(
+            eval_out_v6::<Vec<u8>>(&call(
+                STypeCompanion::BigInt,
+                snumeric::TO_BYTES_METHOD_ID,
+                BigInt256::from(256i64).into()
+            )),
+            vec![1, 0]
+        );
+    }
+
+    #[test]
+    fn eval_to_bits() {
+        assert_eq!(
+            eval_out_v6::<Vec<bool>>(&call(
+                STypeCompanion::Byte,
+                snumeric::TO_BITS_METHOD_ID,
+                5i8.into()
+            )),
+            vec![false, false, false, false, false, true, false, true]
+        );
+    }
+
+    #[test]
+    fn eval_bitwise_ops() {
+        assert_eq!(
+            eval_out_v6::<i32>(&call(
+                STypeCompanion::Int,
+                snumeric::BITWISE_INVERSE_METHOD_ID,
+                0i32.into()
+            )),
+            -1
+        );
+        assert_eq!(
+            eval_out_v6::<i8>(&call_with_arg(
+                STypeCompanion::Byte,
+                snumeric::BITWISE_OR_METHOD_ID,
+                0b0101i8.into(),
+                0b0011i8.into()
+            )),
+            0b0111
+        );
+        assert_eq!(
+            eval_out_v6::<i64>(&call_with_arg(
+                STypeCompanion::Long,
+                snumeric::BITWISE_AND_METHOD_ID,
+                (-1i64).into(),
+                42i64.into()
+            )),
+            42
+        );
+        assert_eq!(
+            eval_out_v6::<BigInt256>(&call_with_arg(
+                STypeCompanion::BigInt,
+                snumeric::BITWISE_XOR_METHOD_ID,
+                BigInt256::from(6i64).into(),
+                BigInt256::from(3i64).into()
+            )),
+            BigInt256::from(5i64)
+        );
+    }
+
+    #[test]
+    fn eval_shifts() {
+        assert_eq!(
+            eval_out_v6::<i8>(&call_with_arg(
+                STypeCompanion::Byte,
+                snumeric::SHIFT_LEFT_METHOD_ID,
+                0x41i8.into(),
+                1i32.into()
+            )),
+            -126
+        );
+        assert_eq!(
+            eval_out_v6::<i32>(&call_with_arg(
+                STypeCompanion::Int,
+                snumeric::SHIFT_RIGHT_METHOD_ID,
+                (-8i32).into(),
+                2i32.into()
+            )),
+            -2
+        );
+        assert!(try_eval_out_v6::<Value>(&call_with_arg(
+            STypeCompanion::Short,
+            snumeric::SHIFT_LEFT_METHOD_ID,
+            1i16.into(),
+            16i32.into()
+        ))
+        .is_err());
+        // BigInt overflow is an error, UnsignedBigInt bits beyond 256 are dropped
+        assert!(try_eval_out_v6::<Value>(&call_with_arg(
+            STypeCompanion::BigInt,
+            snumeric::SHIFT_LEFT_METHOD_ID,
+            BigInt256::from(1i64).into(),
+            255i32.into()
+        ))
+        .is_err());
+        assert_eq!(
+            eval_out_v6::<UnsignedBigInt256>(&call_with_arg(
+                STypeCompanion::UnsignedBigInt,
+                snumeric::SHIFT_LEFT_METHOD_ID,
+                UnsignedBigInt256::max_value().into(),
+                255i32.into()
+            )),
+            UnsignedBigInt256::max_value()
+                .shr_bits(255)
+                .shl_truncated(255)
+        );
+    }
+
+    #[test]
+    fn eval_unsigned_bigint() {
+        let v = UnsignedBigInt256::from(0x0102u64);
+        assert_eq!(
+            eval_out_v6::<Vec<u8>>(&call(
+                STypeCompanion::UnsignedBigInt,
+                snumeric::TO_BYTES_METHOD_ID,
+                v.clone().into()
+            )),
+            vec![1, 2]
+        );
+        assert_eq!(
+            eval_out_v6::<i16>(&call(
+                STypeCompanion::UnsignedBigInt,
+                snumeric::TO_SHORT_METHOD_ID,
+                v.into()
+            )),
+            0x0102
+        );
+        assert!(try_eval_out_v6::<Value>(&call(
+            STypeCompanion::UnsignedBigInt,
+            snumeric::TO_BIGINT_METHOD_ID,
+            UnsignedBigInt256::max_value().into()
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn v6_methods_rejected_before_activation() {
+        // arbitrary context has block version 1 (script version 0)
+        assert!(try_eval_out_wo_ctx::<Value>(&call(
+            STypeCompanion::Int,
+            snumeric::BITWISE_INVERSE_METHOD_ID,
+            0i32.into()
+        ))
+        .is_err());
+        assert!(try_eval_out_wo_ctx::<Value>(&call(
+            STypeCompanion::Long,
+            snumeric::TO_BYTES_METHOD_ID,
+            0i64.into()
+        ))
+        .is_err());
+    }
+}
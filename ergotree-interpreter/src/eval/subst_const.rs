@@ -1,3 +1,4 @@
+use crate::eval::costs::Costs;
 use crate::eval::env::Env;
 use crate::eval::EvalContext;
 use crate::eval::EvalError;
@@ -41,6 +42,9 @@ impl Evaluable for SubstConstants {
             )));
         };
 
+        ctx.cost_accum
+            .add_seq_cost(Costs::SUBST_CONSTANTS, positions.len())?;
+
         if new_constants.len() != positions.len() {
             return Err(EvalError::Misc(format!(
                 "SubstConstants: `positions.len()` (== {}) and `new_values.len()` (== {}) differ",
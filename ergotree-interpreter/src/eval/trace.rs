@@ -0,0 +1,335 @@
+//! Evaluation trace (opt-in debugging mode of the interpreter)
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ergotree_ir::mir::expr::Expr;
+use ergotree_ir::mir::traverse::for_each_descendant;
+use ergotree_ir::mir::value::Value;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
+use ergotree_ir::source_span::SourceSpan;
+
+use super::context::Context;
+use super::cost_accum::CostAccumulator;
+use super::env::Env;
+use super::EvalContext;
+use super::EvalError;
+use super::Evaluable;
+
+/// Evaluation of a single expression node
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TraceEntry {
+    /// Source position of the expression in the pretty printed script (empty if the node does
+    /// not track its position), identifies the node in the traced expression
+    /// (see [`TracedReduction::entry_expr`])
+    pub span: SourceSpan,
+    /// Nesting level of the node (0 for the root)
+    pub depth: usize,
+    /// Produced value or the error message
+    pub result: Result<Value, String>,
+    /// Cost (in JIT cost units) charged for the node, including its sub-expressions
+    pub cost: u64,
+}
+
+impl TraceEntry {
+    fn is_false(&self) -> bool {
+        match &self.result {
+            Ok(Value::Boolean(b)) => !b,
+            Ok(Value::SigmaProp(sp)) => *sp.value() == SigmaBoolean::TrivialProp(false),
+            _ => false,
+        }
+    }
+}
+
+/// Evaluated expressions in the order of completion (sub-expressions come before the
+/// expression itself, the root is the last one)
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct EvalTrace {
+    entries: Vec<TraceEntry>,
+    depth: usize,
+}
+
+impl EvalTrace {
+    /// Recorded entries
+    pub fn entries(&self) -> &[TraceEntry] {
+        &self.entries
+    }
+
+    pub(crate) fn enter(&mut self) -> usize {
+        let depth = self.depth;
+        self.depth += 1;
+        depth
+    }
+
+    pub(crate) fn exit(
+        &mut self,
+        expr: &Expr,
+        depth: usize,
+        result: &Result<Value, EvalError>,
+        cost: u64,
+    ) {
+        self.depth = depth;
+        self.entries.push(TraceEntry {
+            span: expr.span(),
+            depth,
+            result: result.clone().map_err(|e| e.to_string()),
+            cost,
+        });
+    }
+
+    /// Indices of the direct sub-expressions of the entry with the given index
+    fn children(&self, idx: usize) -> Vec<usize> {
+        let depth = self.entries[idx].depth;
+        let mut res: Vec<usize> = self.entries[..idx]
+            .iter()
+            .enumerate()
+            .rev()
+            .take_while(|(_, e)| e.depth > depth)
+            .filter(|(_, e)| e.depth == depth + 1)
+            .map(|(i, _)| i)
+            .collect();
+        res.reverse();
+        res
+    }
+
+    /// Indices of the entries from the root to the sub-expression that made the script fail.
+    /// If evaluation failed with an error, it's the innermost expression that produced the
+    /// error. If the script reduced to `false`, it's the innermost `false` sub-expression that
+    /// is followed from the root through `false` sub-expressions.
+    /// Empty if the script did not fail.
+    pub fn failure_path(&self) -> Vec<usize> {
+        let root = match self.entries.len().checked_sub(1) {
+            Some(root) => root,
+            None => return vec![],
+        };
+        let root_entry = &self.entries[root];
+        let is_failed: fn(&TraceEntry) -> bool = if root_entry.result.is_err() {
+            |e| e.result.is_err()
+        } else if root_entry.is_false() {
+            TraceEntry::is_false
+        } else {
+            return vec![];
+        };
+        let mut path = vec![root];
+        let mut current = root;
+        while let Some(child) = self
+            .children(current)
+            .into_iter()
+            .rev()
+            .find(|i| is_failed(&self.entries[*i]))
+        {
+            path.push(child);
+            current = child;
+        }
+        path
+    }
+
+    /// The innermost sub-expression that made the script fail (see [`Self::failure_path`])
+    pub fn failing_entry(&self) -> Option<&TraceEntry> {
+        self.failure_path().last().map(|i| &self.entries[*i])
+    }
+}
+
+/// Script reduction with the evaluation trace
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TracedReduction {
+    /// Reduction result
+    pub result: Result<SigmaBoolean, EvalError>,
+    /// Evaluation trace (spans refer to `source`)
+    pub trace: EvalTrace,
+    /// Pretty printed script
+    pub source: String,
+    /// Evaluated expression (with the spans in `source`)
+    pub expr: Expr,
+}
+
+impl TracedReduction {
+    const MAX_VALUE_LEN: usize = 64;
+
+    /// Expression node of the trace entry (the outermost one if several nodes have the same
+    /// span), `None` if the node does not track its position
+    pub fn entry_expr(&self, entry: &TraceEntry) -> Option<&Expr> {
+        if entry.span.length == 0 {
+            return None;
+        }
+        let mut found = None;
+        for_each_descendant(&self.expr, &mut |e| {
+            if found.is_none() && e.span() == entry.span {
+                found = Some(e);
+            }
+        });
+        found
+    }
+
+    /// Pretty printed script where every line is followed by the values of the
+    /// sub-expressions that start on that line (the last evaluation is shown if a
+    /// sub-expression was evaluated several times). The sub-expression that made the script
+    /// fail is marked.
+    pub fn annotated_listing(&self) -> String {
+        let entries = self.trace.entries();
+        let failing = self
+            .trace
+            .failure_path()
+            .into_iter()
+            .rev()
+            .find(|i| entries[*i].span.length > 0);
+        let mut last_by_span: HashMap<(usize, usize), usize> = HashMap::new();
+        for (i, e) in entries.iter().enumerate() {
+            if e.span.length > 0 {
+                last_by_span.insert((e.span.offset, e.span.length), i);
+            }
+        }
+        let mut annotated: Vec<usize> = last_by_span.into_values().collect();
+        annotated.sort_by_key(|i| {
+            (
+                entries[*i].span.offset,
+                std::cmp::Reverse(entries[*i].span.length),
+            )
+        });
+
+        let mut res = String::new();
+        let mut line_start = 0;
+        for line in self.source.split('\n') {
+            let line_end = line_start + line.len();
+            res.push_str(line);
+            res.push('\n');
+            for i in annotated
+                .iter()
+                .filter(|i| (line_start..line_end).contains(&entries[**i].span.offset))
+            {
+                let e = &entries[*i];
+                let column = e.span.offset - line_start;
+                let width = e.span.length.min(line_end - e.span.offset).max(1);
+                let outcome = match &e.result {
+                    Ok(v) => Self::truncated(v.to_string()),
+                    Err(msg) => format!("error: {}", Self::truncated(msg.clone())),
+                };
+                res.push_str(&format!(
+                    "{}{} {} [cost: {}]{}\n",
+                    " ".repeat(column),
+                    "^".repeat(width),
+                    outcome,
+                    e.cost,
+                    if failing == Some(*i) {
+                        " <-- failed"
+                    } else {
+                        ""
+                    }
+                ));
+            }
+            line_start = line_end + 1;
+        }
+        res
+    }
+
+    fn truncated(s: String) -> String {
+        if s.chars().count() > Self::MAX_VALUE_LEN {
+            let mut t: String = s.chars().take(Self::MAX_VALUE_LEN).collect();
+            t.push_str("...");
+            t
+        } else {
+            s
+        }
+    }
+}
+
+/// Evaluate the given expression by reducing it to SigmaBoolean value, recording every
+/// evaluated sub-expression. The expression is pretty printed first, so that the
+/// sub-expressions have source spans in the printed script.
+pub fn reduce_to_crypto_with_trace(
+    expr: &Expr,
+    env: &Env,
+    ctx: Arc<Context>,
+) -> Result<TracedReduction, EvalError> {
+    let (spanned_expr, source) = expr
+        .pretty_print()
+        .map_err(|e| EvalError::Misc(e.to_string()))?;
+    let cost_accum = CostAccumulator::new(0, None);
+    let mut ectx = EvalContext::new(ctx, cost_accum).with_trace();
+    let mut env_mut = env.clone();
+    let result = spanned_expr
+        .eval(&mut env_mut, &mut ectx)
+        .and_then(|v| match v {
+            Value::Boolean(b) => Ok(SigmaBoolean::TrivialProp(b)),
+            Value::SigmaProp(sp) => Ok(sp.value().clone()),
+            _ => Err(EvalError::InvalidResultType),
+        });
+    Ok(TracedReduction {
+        result,
+        trace: ectx.trace.unwrap_or_default(),
+        source,
+        expr: spanned_expr,
+    })
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use ergotree_ir::mir::bin_op::BinOp;
+    use ergotree_ir::mir::bin_op::LogicalOp;
+    use ergotree_ir::mir::bin_op::RelationOp;
+    use ergotree_ir::mir::global_vars::GlobalVars;
+    use expect_test::expect;
+    use sigma_test_util::force_any_val;
+
+    fn height_check(height: u32) -> TracedReduction {
+        let expr: Expr = BinOp {
+            kind: LogicalOp::And.into(),
+            left: Box::new(
+                BinOp {
+                    kind: RelationOp::Eq.into(),
+                    left: Box::new(Expr::Const(1i32.into())),
+                    right: Box::new(Expr::Const(1i32.into())),
+                }
+                .into(),
+            ),
+            right: Box::new(
+                BinOp {
+                    kind: RelationOp::Gt.into(),
+                    left: Box::new(GlobalVars::Height.into()),
+                    right: Box::new(Expr::Const(100i32.into())),
+                }
+                .into(),
+            ),
+        }
+        .into();
+        let ctx = Context {
+            height,
+            ..force_any_val::<Context>()
+        };
+        reduce_to_crypto_with_trace(&expr, &Env::empty(), Arc::new(ctx)).unwrap()
+    }
+
+    #[test]
+    fn trace_false_reduction() {
+        let traced = height_check(10);
+        assert_eq!(traced.result, Ok(SigmaBoolean::TrivialProp(false)));
+        let failing = traced.trace.failing_entry().unwrap();
+        assert_eq!(
+            &traced.source[failing.span.offset..][..failing.span.length],
+            "HEIGHT > 100"
+        );
+        assert!(matches!(
+            traced.entry_expr(failing),
+            Some(Expr::BinOp(op)) if op.expr.kind == RelationOp::Gt.into()
+        ));
+        expect![[r#"
+            1 == 1 && HEIGHT > 100
+            ^^^^^^^^^^^^^^^^^^^^^^ false [cost: 84]
+            ^^^^^^ true [cost: 13]
+                      ^^^^^^^^^^^^ false [cost: 51] <-- failed
+        "#]]
+        .assert_eq(&traced.annotated_listing());
+    }
+
+    #[test]
+    fn trace_true_reduction() {
+        let traced = height_check(101);
+        assert_eq!(traced.result, Ok(SigmaBoolean::TrivialProp(true)));
+        assert!(traced.trace.failing_entry().is_none());
+        assert_eq!(traced.trace.entries().last().unwrap().depth, 0);
+        assert!(!traced.annotated_listing().contains("failed"));
+    }
+}
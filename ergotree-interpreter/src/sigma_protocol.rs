@@ -6,6 +6,7 @@ pub mod private_input;
 pub mod prover;
 pub mod verifier;
 
+pub mod batch_verifier;
 pub(crate) mod challenge;
 mod crypto_utils;
 pub mod dht_protocol;
@@ -0,0 +1,371 @@
+//! Batch verification of proofs of SigmaBoolean propositions
+//!
+//! Every leaf of a valid proof tree satisfies the verification equation of its sigma protocol,
+//! `g^z = a * h^e` for Schnorr (ProveDlog) leaves and `g^z = a * u^e`, `h^z = b * v^e` for
+//! Diffie-Hellman tuple leaves, where `a` (and `b`) are the prover's commitments. The batch
+//! verifier raises the equations of all the leaves of all the proofs to random powers and checks
+//! their product with a single multi-scalar multiplication, instead of computing the commitments of
+//! every proof separately as [`verify_signature`] does. Since the powers are picked after the
+//! proofs are fixed, an invalid leaf passes the combined check with a negligible probability.
+//!
+//! The signature bytes do not carry the commitments, so the proofs parsed with
+//! [`super::sig_serializer::parse_sig_compute_challenges`] (without commitments) are verified one
+//! by one. If the batch is rejected, every proof is verified with [`verify_signature`] to identify
+//! the failing ones.
+
+use ergo_chain_types::ec_point::generator;
+use ergo_chain_types::ec_point::is_identity;
+use ergo_chain_types::ec_point::multi_exponentiate_all;
+use ergo_chain_types::EcPoint;
+use ergotree_ir::sigma_protocol::dlog_group::random_scalar_in_group_range;
+use ergotree_ir::sigma_protocol::sigma_boolean::SigmaBoolean;
+use k256::Scalar;
+use rand::thread_rng;
+use rand::RngCore;
+use thiserror::Error;
+
+use super::fiat_shamir::fiat_shamir_hash_fn;
+use super::fiat_shamir::fiat_shamir_tree_to_bytes;
+use super::sig_serializer::parse_sig_compute_challenges;
+use super::sig_serializer::serialize_sig;
+use super::unchecked_tree::UncheckedDhTuple;
+use super::unchecked_tree::UncheckedLeaf;
+use super::unchecked_tree::UncheckedSchnorr;
+use super::unchecked_tree::UncheckedTree;
+use super::verifier::verify_signature;
+use super::verifier::VerifierError;
+
+/// Proof that failed the batch verification
+#[derive(Debug)]
+pub struct InvalidProof {
+    /// Index of the proof in the batch
+    pub index: usize,
+    /// Error if the proof could not be parsed or checked, `None` if the proof does not prove the
+    /// proposition
+    pub error: Option<VerifierError>,
+}
+
+/// Errors on batch verification
+#[derive(Error, Debug)]
+#[error("Invalid proofs in the batch at indices {:?}", .invalid.iter().map(|p| p.index).collect::<Vec<_>>())]
+pub struct BatchVerifierError {
+    /// Failed proofs (in the order of the batch)
+    pub invalid: Vec<InvalidProof>,
+}
+
+/// Verifies the proofs of the propositions for the messages at once. Returns `Ok(())` if every
+/// proof is valid, otherwise the invalid proofs, the same ones [`verify_signature`] rejects for
+/// the serialized proofs.
+pub fn verify_batch<M: AsRef<[u8]>>(
+    batch: &[(SigmaBoolean, UncheckedTree, M)],
+) -> Result<(), BatchVerifierError> {
+    let mut rng = thread_rng();
+    let mut equations = Equations::default();
+    let mut batch_valid = true;
+    let mut sequential = Vec::new();
+    for (index, (sigma_tree, proof, message)) in batch.iter().enumerate() {
+        match sigma_tree {
+            SigmaBoolean::TrivialProp(b) => batch_valid &= *b,
+            sb => match with_commitments(sb, proof) {
+                Some(tree) => {
+                    batch_valid &= check_challenge(&tree, message.as_ref());
+                    batch_valid &= equations.add(tree, &mut rng);
+                }
+                None => sequential.push(index),
+            },
+        }
+    }
+    let indices: Vec<usize> = if batch_valid && equations.hold() {
+        sequential
+    } else {
+        (0..batch.len()).collect()
+    };
+    let invalid: Vec<InvalidProof> = indices
+        .into_iter()
+        .filter_map(|index| {
+            let (sigma_tree, proof, message) = &batch[index];
+            let signature: Vec<u8> = serialize_sig(proof.clone()).into();
+            match verify_signature(sigma_tree.clone(), message.as_ref(), &signature) {
+                Ok(true) => None,
+                Ok(false) => Some(InvalidProof { index, error: None }),
+                Err(e) => Some(InvalidProof {
+                    index,
+                    error: Some(e),
+                }),
+            }
+        })
+        .collect();
+    if invalid.is_empty() {
+        Ok(())
+    } else {
+        Err(BatchVerifierError { invalid })
+    }
+}
+
+/// Proof tree with the challenges computed from the serialized proof (Verifier Steps 1-3, as in
+/// [`verify_signature`]) and the commitments of the given proof. `None` if the proof cannot be
+/// parsed for the proposition or a leaf has no commitment.
+fn with_commitments(sigma_tree: &SigmaBoolean, proof: &UncheckedTree) -> Option<UncheckedTree> {
+    let signature: Vec<u8> = serialize_sig(proof.clone()).into();
+    let parsed = parse_sig_compute_challenges(sigma_tree, signature).ok()?;
+    let mut leaves = Vec::new();
+    collect_leaves(proof.clone(), &mut leaves);
+    let mut leaves = leaves.into_iter();
+    let tree = set_commitments(parsed, &mut leaves)?;
+    leaves.next().is_none().then_some(tree)
+}
+
+fn collect_leaves(tree: UncheckedTree, leaves: &mut Vec<UncheckedLeaf>) {
+    match tree {
+        UncheckedTree::UncheckedLeaf(leaf) => leaves.push(leaf),
+        UncheckedTree::UncheckedConjecture(conj) => conj
+            .children_ust()
+            .into_iter()
+            .for_each(|child| collect_leaves(child, leaves)),
+    }
+}
+
+fn set_commitments(
+    tree: UncheckedTree,
+    leaves: &mut impl Iterator<Item = UncheckedLeaf>,
+) -> Option<UncheckedTree> {
+    match tree {
+        UncheckedTree::UncheckedLeaf(leaf) => match (leaf, leaves.next()?) {
+            (UncheckedLeaf::UncheckedSchnorr(sn), UncheckedLeaf::UncheckedSchnorr(given)) => Some(
+                UncheckedSchnorr {
+                    commitment_opt: Some(given.commitment_opt?),
+                    ..sn
+                }
+                .into(),
+            ),
+            (UncheckedLeaf::UncheckedDhTuple(dh), UncheckedLeaf::UncheckedDhTuple(given)) => Some(
+                UncheckedDhTuple {
+                    commitment_opt: Some(given.commitment_opt?),
+                    ..dh
+                }
+                .into(),
+            ),
+            _ => None,
+        },
+        UncheckedTree::UncheckedConjecture(conj) => {
+            let children = conj
+                .clone()
+                .children_ust()
+                .try_mapped(|child| set_commitments(child, leaves).ok_or(()))
+                .ok()?;
+            Some(conj.with_children(children).into())
+        }
+    }
+}
+
+/// Verifier Steps 5-6 (see [`verify_signature`]) for the proof tree with the commitments
+fn check_challenge(tree: &UncheckedTree, message: &[u8]) -> bool {
+    match fiat_shamir_tree_to_bytes(&tree.clone().into()) {
+        Ok(mut s) => {
+            s.extend_from_slice(message);
+            tree.challenge() == fiat_shamir_hash_fn(s.as_slice()).into()
+        }
+        Err(_) => false,
+    }
+}
+
+/// Product of the randomly weighted verification equations of the leaves (as the bases and
+/// exponents), the exponent of the group generator is accumulated separately
+#[derive(Default)]
+struct Equations {
+    generator_exp: Scalar,
+    terms: Vec<(EcPoint, Scalar)>,
+}
+
+impl Equations {
+    /// Adds the equations of all the leaves of the tree, returns false if a leaf has no
+    /// commitment
+    fn add(&mut self, tree: UncheckedTree, rng: &mut impl RngCore) -> bool {
+        match tree {
+            UncheckedTree::UncheckedLeaf(UncheckedLeaf::UncheckedSchnorr(sn)) => {
+                let a = match sn.commitment_opt {
+                    Some(commitment) => *commitment.a,
+                    None => return false,
+                };
+                let e = Scalar::from(sn.challenge);
+                let z = sn.second_message.z.as_scalar_ref();
+                // (g^z * h^(-e) * a^(-1))^r = 1
+                let r = random_scalar_in_group_range(&mut *rng);
+                self.generator_exp += r * z;
+                self.terms.push((*sn.proposition.h, -(r * e)));
+                self.terms.push((a, -r));
+                true
+            }
+            UncheckedTree::UncheckedLeaf(UncheckedLeaf::UncheckedDhTuple(dh)) => {
+                let (a, b) = match dh.commitment_opt {
+                    Some(commitment) => (*commitment.a, *commitment.b),
+                    None => return false,
+                };
+                let e = Scalar::from(dh.challenge);
+                let z = dh.second_message.z.as_scalar_ref();
+                let proposition = dh.proposition;
+                // (g^z * u^(-e) * a^(-1))^r1 = 1, (h^z * v^(-e) * b^(-1))^r2 = 1
+                for (base, x, commitment) in [
+                    (*proposition.g, *proposition.u, a),
+                    (*proposition.h, *proposition.v, b),
+                ] {
+                    let r = random_scalar_in_group_range(&mut *rng);
+                    self.terms.push((base, r * z));
+                    self.terms.push((x, -(r * e)));
+                    self.terms.push((commitment, -r));
+                }
+                true
+            }
+            UncheckedTree::UncheckedConjecture(conj) => conj
+                .children_ust()
+                .into_iter()
+                .all(|child| self.add(child, rng)),
+        }
+    }
+
+    /// Checks the equations with a single multi-scalar multiplication
+    fn hold(mut self) -> bool {
+        self.terms.push((generator(), self.generator_exp));
+        is_identity(&multi_exponentiate_all(&self.terms))
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+    use crate::sigma_protocol::private_input::DhTupleProverInput;
+    use crate::sigma_protocol::private_input::DlogProverInput;
+    use crate::sigma_protocol::private_input::PrivateInput;
+    use crate::sigma_protocol::prover::hint::HintsBag;
+    use crate::sigma_protocol::prover::Prover;
+    use crate::sigma_protocol::prover::TestProver;
+    use crate::sigma_protocol::verifier::compute_commitments;
+    use ergotree_ir::sigma_protocol::sigma_boolean::cand::Cand;
+    use ergotree_ir::sigma_protocol::sigma_boolean::cor::Cor;
+
+    type BatchItem = (SigmaBoolean, UncheckedTree, Vec<u8>);
+
+    /// Proof tree with the commitments (computed by the verifier)
+    fn prove(
+        secrets: Vec<PrivateInput>,
+        sigma_tree: SigmaBoolean,
+        message: &[u8],
+    ) -> UncheckedTree {
+        let prover = TestProver { secrets };
+        let signature: Vec<u8> = prover
+            .generate_proof(sigma_tree.clone(), message, &HintsBag::empty())
+            .unwrap()
+            .into();
+        compute_commitments(parse_sig_compute_challenges(&sigma_tree, signature).unwrap())
+    }
+
+    fn dlog_item(message: Vec<u8>) -> BatchItem {
+        let secret = DlogProverInput::random();
+        let sigma_tree: SigmaBoolean = secret.public_image().into();
+        let proof = prove(vec![secret.into()], sigma_tree.clone(), &message);
+        (sigma_tree, proof, message)
+    }
+
+    fn dht_item(message: Vec<u8>) -> BatchItem {
+        let secret = DhTupleProverInput::random();
+        let sigma_tree: SigmaBoolean = secret.public_image().clone().into();
+        let proof = prove(vec![secret.into()], sigma_tree.clone(), &message);
+        (sigma_tree, proof, message)
+    }
+
+    /// `dlog && (dht || other dlog)`, the other dlog leaf is simulated
+    fn conjecture_item(message: Vec<u8>) -> BatchItem {
+        let dlog = DlogProverInput::random();
+        let dht = DhTupleProverInput::random();
+        let other = DlogProverInput::random();
+        let sigma_tree = Cand::normalized(
+            vec![
+                dlog.public_image().into(),
+                Cor::normalized(
+                    vec![
+                        dht.public_image().clone().into(),
+                        other.public_image().into(),
+                    ]
+                    .try_into()
+                    .unwrap(),
+                ),
+            ]
+            .try_into()
+            .unwrap(),
+        );
+        let proof = prove(vec![dlog.into(), dht.into()], sigma_tree.clone(), &message);
+        (sigma_tree, proof, message)
+    }
+
+    fn invalid_indices(batch: &[BatchItem]) -> Vec<usize> {
+        match verify_batch(batch) {
+            Ok(()) => vec![],
+            Err(e) => e.invalid.iter().map(|p| p.index).collect(),
+        }
+    }
+
+    #[test]
+    fn all_valid() {
+        let batch: Vec<BatchItem> = (0..6).map(|i| dlog_item(vec![i; 32])).collect();
+        assert!(verify_batch(&batch).is_ok());
+        assert!(verify_batch::<Vec<u8>>(&[]).is_ok());
+    }
+
+    #[test]
+    fn one_invalid() {
+        let mut batch: Vec<BatchItem> = (0..6).map(|i| dlog_item(vec![i; 32])).collect();
+        // proof of another message
+        batch[3].2 = vec![0xff; 32];
+        let err = verify_batch(&batch).unwrap_err();
+        assert_eq!(err.invalid.len(), 1);
+        assert_eq!(err.invalid[0].index, 3);
+        assert!(err.invalid[0].error.is_none());
+    }
+
+    #[test]
+    fn mixed_dlog_and_dht() {
+        let mut batch: Vec<BatchItem> = (0..9)
+            .map(|i| match i % 3 {
+                0 => dlog_item(vec![i; 32]),
+                1 => dht_item(vec![i; 32]),
+                _ => conjecture_item(vec![i; 32]),
+            })
+            .collect();
+        batch.push((SigmaBoolean::TrivialProp(true), batch[0].1.clone(), vec![]));
+        assert_eq!(invalid_indices(&batch), Vec::<usize>::new());
+        // proof of another proposition
+        batch[4].0 = dht_item(vec![]).0;
+        batch[8].2 = vec![0xff; 32];
+        assert_eq!(invalid_indices(&batch), vec![4, 8]);
+    }
+
+    #[test]
+    fn same_as_sequential_without_commitments() {
+        let (sigma_tree, proof, message) = dht_item(vec![1; 32]);
+        let signature: Vec<u8> = serialize_sig(proof).into();
+        // parsed proof carries no commitments
+        let parsed = parse_sig_compute_challenges(&sigma_tree, signature).unwrap();
+        let mut batch = vec![
+            dlog_item(vec![0; 32]),
+            (sigma_tree, parsed, message),
+            conjecture_item(vec![2; 32]),
+        ];
+        assert_eq!(invalid_indices(&batch), Vec::<usize>::new());
+        batch[1].2 = vec![0xff; 32];
+        assert_eq!(invalid_indices(&batch), vec![1]);
+    }
+
+    #[test]
+    fn wrong_commitment_falls_back_to_sequential() {
+        let mut batch: Vec<BatchItem> = (0..3).map(|i| dlog_item(vec![i; 32])).collect();
+        // the proof itself is valid (the commitment is not serialized), the batch check fails
+        if let UncheckedTree::UncheckedLeaf(UncheckedLeaf::UncheckedSchnorr(sn)) = &mut batch[1].1 {
+            *sn.commitment_opt.as_mut().unwrap().a = generator();
+        }
+        assert!(verify_batch(&batch).is_ok());
+    }
+}
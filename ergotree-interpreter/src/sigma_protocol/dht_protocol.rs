@@ -11,9 +11,9 @@ use ergotree_ir::serialization::SigmaSerializable;
 #[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct FirstDhTupleProverMessage {
     #[cfg_attr(feature = "json", serde(rename = "a"))]
-    a: Box<EcPoint>,
+    pub(crate) a: Box<EcPoint>,
     #[cfg_attr(feature = "json", serde(rename = "b"))]
-    b: Box<EcPoint>,
+    pub(crate) b: Box<EcPoint>,
 }
 
 impl FirstDhTupleProverMessage {
@@ -120,31 +120,19 @@ pub mod interactive_prover {
     /// and prover's response ("z")
     ///
     /// g^z = a*u^e, h^z = b*v^e  => a = g^z/u^e, b = h^z/v^e
-    #[allow(clippy::many_single_char_names)]
+    /// (each computed with a single multi-scalar multiplication)
     pub fn compute_commitment(
         proposition: &ProveDhTuple,
         challenge: &Challenge,
         second_message: &SecondDhTupleProverMessage,
     ) -> (EcPoint, EcPoint) {
-        let g = proposition.g.clone();
-        let h = proposition.h.clone();
-        let u = proposition.u.clone();
-        let v = proposition.v.clone();
+        use ergo_chain_types::ec_point::multi_exponentiate;
 
-        let z = second_message.z.clone();
+        let z = second_message.z.as_scalar_ref();
+        let minus_e = -Scalar::from(challenge.clone());
 
-        let e: Scalar = challenge.clone().into();
-
-        use ergo_chain_types::ec_point::{exponentiate, inverse};
-
-        let g_to_z = exponentiate(&g, z.as_scalar_ref());
-        let h_to_z = exponentiate(&h, z.as_scalar_ref());
-
-        let u_to_e = exponentiate(&u, &e);
-        let v_to_e = exponentiate(&v, &e);
-
-        let a = g_to_z.mul(&inverse(&u_to_e));
-        let b = h_to_z.mul(&inverse(&v_to_e));
+        let a = multi_exponentiate(&proposition.g, z, &proposition.u, &minus_e);
+        let b = multi_exponentiate(&proposition.h, z, &proposition.v, &minus_e);
         (a, b)
     }
 }
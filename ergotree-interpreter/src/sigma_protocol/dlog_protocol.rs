@@ -45,7 +45,7 @@ pub mod interactive_prover {
     use crate::sigma_protocol::wscalar::Wscalar;
     use crate::sigma_protocol::{private_input::DlogProverInput, Challenge};
     use ergo_chain_types::{
-        ec_point::{exponentiate, generator, inverse},
+        ec_point::{exponentiate, generator, multi_exponentiate},
         EcPoint,
     };
     use ergotree_ir::sigma_protocol::dlog_group;
@@ -106,18 +106,19 @@ pub mod interactive_prover {
     /// ("a" message of the sigma-protocol) based on the verifier's challenge ("e")
     /// and prover's response ("z")
     ///  
-    /// g^z = a*h^e => a = g^z/h^e
+    /// g^z = a*h^e => a = g^z/h^e (computed with a single multi-scalar multiplication)
     pub fn compute_commitment(
         proposition: &ProveDlog,
         challenge: &Challenge,
         second_message: &SecondDlogProverMessage,
     ) -> EcPoint {
-        let g = generator();
-        let h = *proposition.h.clone();
-        let e: Scalar = challenge.clone().into();
-        let g_z = exponentiate(&g, second_message.z.as_scalar_ref());
-        let h_e = exponentiate(&h, &e);
-        g_z * &inverse(&h_e)
+        let minus_e = -Scalar::from(challenge.clone());
+        multi_exponentiate(
+            &generator(),
+            second_message.z.as_scalar_ref(),
+            &proposition.h,
+            &minus_e,
+        )
     }
 }
 
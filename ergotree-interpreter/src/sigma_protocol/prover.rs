@@ -27,7 +27,7 @@ use gf2_192::gf2_192poly::Gf2_192Poly;
 use gf2_192::gf2_192poly::Gf2_192PolyError;
 use gf2_192::Gf2_192Error;
 use std::convert::TryInto;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub use context_extension::*;
 use ergotree_ir::ergo_tree::ErgoTree;
@@ -142,7 +142,7 @@ pub trait Prover {
         &self,
         tree: &ErgoTree,
         env: &Env,
-        ctx: Rc<Context>,
+        ctx: Arc<Context>,
         message: &[u8],
         hints_bag: &HintsBag,
     ) -> Result<ProverResult, ProverError> {
@@ -1222,7 +1222,7 @@ mod tests {
     use ergotree_ir::types::stype::SType;
     use sigma_test_util::force_any_val;
     use std::convert::TryFrom;
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     #[test]
     fn test_prove_true_prop() {
@@ -1237,7 +1237,7 @@ mod tests {
         let res = prover.prove(
             &bool_true_tree,
             &Env::empty(),
-            Rc::new(force_any_val::<Context>()),
+            Arc::new(force_any_val::<Context>()),
             message.as_slice(),
             &HintsBag::empty(),
         );
@@ -1258,7 +1258,7 @@ mod tests {
         let res = prover.prove(
             &bool_false_tree,
             &Env::empty(),
-            Rc::new(force_any_val::<Context>()),
+            Arc::new(force_any_val::<Context>()),
             message.as_slice(),
             &HintsBag::empty(),
         );
@@ -1278,7 +1278,7 @@ mod tests {
         let res = prover.prove(
             &tree,
             &Env::empty(),
-            Rc::new(force_any_val::<Context>()),
+            Arc::new(force_any_val::<Context>()),
             message.as_slice(),
             &HintsBag::empty(),
         );
@@ -1304,7 +1304,7 @@ mod tests {
         let res = prover.prove(
             &tree,
             &Env::empty(),
-            Rc::new(force_any_val::<Context>()),
+            Arc::new(force_any_val::<Context>()),
             message.as_slice(),
             &HintsBag::empty(),
         );
@@ -1336,7 +1336,7 @@ mod tests {
         let res = prover.prove(
             &tree,
             &Env::empty(),
-            Rc::new(force_any_val::<Context>()),
+            Arc::new(force_any_val::<Context>()),
             message.as_slice(),
             &HintsBag::empty(),
         );
@@ -1361,7 +1361,7 @@ mod tests {
         let res = prover.prove(
             &tree,
             &Env::empty(),
-            Rc::new(force_any_val::<Context>()),
+            Arc::new(force_any_val::<Context>()),
             message.as_slice(),
             &HintsBag::empty(),
         );
@@ -1393,7 +1393,7 @@ mod tests {
         let res = prover.prove(
             &tree,
             &Env::empty(),
-            Rc::new(force_any_val::<Context>()),
+            Arc::new(force_any_val::<Context>()),
             message.as_slice(),
             &HintsBag::empty(),
         );
@@ -1413,7 +1413,7 @@ mod tests {
         let res = prover.prove(
             &tree,
             &Env::empty(),
-            Rc::new(force_any_val::<Context>()),
+            Arc::new(force_any_val::<Context>()),
             message.as_slice(),
             &HintsBag::empty(),
         );
@@ -1461,7 +1461,7 @@ mod tests {
         };
 
         let message = vec![0u8; 100];
-        let ctx: Rc<Context> = force_any_val::<Context>().into();
+        let ctx: Arc<Context> = force_any_val::<Context>().into();
         let res = prover.prove(
             &tree,
             &Env::empty(),
@@ -1,6 +1,6 @@
 //! Verifier
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 use super::dht_protocol;
 use super::dht_protocol::FirstDhTupleProverMessage;
@@ -16,12 +16,18 @@ use super::{
     SigmaBoolean, UncheckedTree,
 };
 use crate::eval::context::Context;
+use crate::eval::costs::Cost;
+use crate::eval::costs::Costs;
 use crate::eval::env::Env;
 use crate::eval::EvalError;
 use crate::eval::{reduce_to_crypto, ReductionDiagnosticInfo};
 use dlog_protocol::FirstDlogProverMessage;
 use ergotree_ir::ergo_tree::ErgoTree;
 use ergotree_ir::ergo_tree::ErgoTreeError;
+use ergotree_ir::ergo_tree::ErgoTreeHeaderError;
+use ergotree_ir::ergo_tree::ErgoTreeVersion;
+use ergotree_ir::ergo_tree::ErgoTreeVersionError;
+use ergotree_ir::mir::expr::Expr;
 
 use derive_more::From;
 use thiserror::Error;
@@ -44,6 +50,7 @@ pub enum VerifierError {
 }
 
 /// Result of Box.ergoTree verification procedure (see `verify` method).
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct VerificationResult {
     /// result of SigmaProp condition verification via sigma protocol
     pub result: bool,
@@ -63,12 +70,14 @@ pub trait Verifier {
         &self,
         tree: &ErgoTree,
         env: &Env,
-        ctx: Rc<Context>,
+        ctx: Arc<Context>,
         proof: ProofBytes,
         message: &[u8],
     ) -> Result<VerificationResult, VerifierError> {
-        let expr = tree.proposition()?;
+        let expr = proposition_from_ergo_tree(tree, &ctx)?;
         let reduction_result = reduce_to_crypto(&expr, env, ctx)?;
+        let verify_cost = Costs::sigma_verify_cost(&reduction_result.sigma_prop);
+        let cost = reduction_result.cost + Cost::to_block_cost(verify_cost);
         let res: bool = match reduction_result.sigma_prop {
             SigmaBoolean::TrivialProp(b) => b,
             sb => {
@@ -85,12 +94,42 @@ pub trait Verifier {
         };
         Ok(VerificationResult {
             result: res,
-            cost: 0,
+            cost,
             diag: reduction_result.diag,
         })
     }
 }
 
+/// Returns the proposition of the given tree, or `true` if the tree cannot be parsed due to a
+/// soft-fork, i.e. the tree has a version above the supported one and a newer version is
+/// activated on the network, or the parsing error is allowed by the context's validation settings.
+pub fn proposition_from_ergo_tree(tree: &ErgoTree, ctx: &Context) -> Result<Expr, VerifierError> {
+    match tree {
+        ErgoTree::Unparsed {
+            error:
+                ErgoTreeError::HeaderError(ErgoTreeHeaderError::VersionError(
+                    ErgoTreeVersionError::InvalidVersion(version),
+                )),
+            ..
+        } => {
+            if ctx.activated_script_version() > ErgoTreeVersion::MAX_SCRIPT_VERSION {
+                Ok(Expr::Const(true.into()))
+            } else {
+                Err(EvalError::ScriptVersionError(format!(
+                    "ErgoTree version {} is not supported and not activated (activated version is {})",
+                    version,
+                    ctx.activated_script_version()
+                ))
+                .into())
+            }
+        }
+        ErgoTree::Unparsed { error, .. } if ctx.validation_settings.is_soft_fork(error) => {
+            Ok(Expr::Const(true.into()))
+        }
+        _ => Ok(tree.proposition()?),
+    }
+}
+
 /// Verify that the signature is presented to satisfy SigmaProp conditions.
 pub fn verify_signature(
     sigma_tree: SigmaBoolean,
@@ -219,14 +258,14 @@ mod tests {
             };
             let res = prover.prove(&tree,
                 &Env::empty(),
-                Rc::new(force_any_val::<Context>()),
+                Arc::new(force_any_val::<Context>()),
                 message.as_slice(),
                 &HintsBag::empty());
             let proof = res.unwrap().proof;
             let verifier = TestVerifier;
             prop_assert_eq!(verifier.verify(&tree,
                                             &Env::empty(),
-                                            Rc::new(force_any_val::<Context>()),
+                                            Arc::new(force_any_val::<Context>()),
                                             proof.clone(),
                                             message.as_slice())
                             .unwrap().result,
@@ -235,7 +274,7 @@ mod tests {
             // possible to append bytes
             prop_assert_eq!(verifier.verify(&tree,
                                             &Env::empty(),
-                                            Rc::new(force_any_val::<Context>()),
+                                            Arc::new(force_any_val::<Context>()),
                                             proof_append_some_byte(&proof),
                                             message.as_slice())
                             .unwrap().result,
@@ -244,7 +283,7 @@ mod tests {
             // wrong message
             prop_assert_eq!(verifier.verify(&tree,
                                             &Env::empty(),
-                                            Rc::new(force_any_val::<Context>()),
+                                            Arc::new(force_any_val::<Context>()),
                                             proof,
                                             vec![1u8; 100].as_slice())
                             .unwrap().result,
@@ -261,14 +300,14 @@ mod tests {
             };
             let res = prover.prove(&tree,
                 &Env::empty(),
-                Rc::new(force_any_val::<Context>()),
+                Arc::new(force_any_val::<Context>()),
                 message.as_slice(),
                 &HintsBag::empty());
             let proof = res.unwrap().proof;
             let verifier = TestVerifier;
             prop_assert_eq!(verifier.verify(&tree,
                                             &Env::empty(),
-                                            Rc::new(force_any_val::<Context>()),
+                                            Arc::new(force_any_val::<Context>()),
                                             proof.clone(),
                                             message.as_slice())
                             .unwrap().result,
@@ -277,7 +316,7 @@ mod tests {
             // possible to append bytes
             prop_assert_eq!(verifier.verify(&tree,
                                             &Env::empty(),
-                                            Rc::new(force_any_val::<Context>()),
+                                            Arc::new(force_any_val::<Context>()),
                                             proof_append_some_byte(&proof),
                                             message.as_slice())
                             .unwrap().result,
@@ -286,7 +325,7 @@ mod tests {
             // wrong message
             prop_assert_eq!(verifier.verify(&tree,
                                             &Env::empty(),
-                                            Rc::new(force_any_val::<Context>()),
+                                            Arc::new(force_any_val::<Context>()),
                                             proof,
                                             vec![1u8; 100].as_slice())
                             .unwrap().result,
@@ -308,14 +347,14 @@ mod tests {
             };
             let res = prover.prove(&tree,
                 &Env::empty(),
-                Rc::new(force_any_val::<Context>()),
+                Arc::new(force_any_val::<Context>()),
                 message.as_slice(),
                 &HintsBag::empty());
             let proof = res.unwrap().proof;
             let verifier = TestVerifier;
             let ver_res = verifier.verify(&tree,
                                           &Env::empty(),
-                                          Rc::new(force_any_val::<Context>()),
+                                          Arc::new(force_any_val::<Context>()),
                                           proof,
                                           message.as_slice());
             prop_assert_eq!(ver_res.unwrap().result, true);
@@ -339,14 +378,14 @@ mod tests {
             let prover = TestProver { secrets: vec![secret1, secret2, secret3] };
             let res = prover.prove(&tree,
                 &Env::empty(),
-                Rc::new(force_any_val::<Context>()),
+                Arc::new(force_any_val::<Context>()),
                 message.as_slice(),
                 &HintsBag::empty());
             let proof = res.unwrap().proof;
             let verifier = TestVerifier;
             let ver_res = verifier.verify(&tree,
                                           &Env::empty(),
-                                          Rc::new(force_any_val::<Context>()),
+                                          Arc::new(force_any_val::<Context>()),
                                           proof,
                                           message.as_slice());
             prop_assert_eq!(ver_res.unwrap().result, true);
@@ -370,14 +409,14 @@ mod tests {
                 };
                 let res = prover.prove(&tree,
                     &Env::empty(),
-                    Rc::new(force_any_val::<Context>()),
+                    Arc::new(force_any_val::<Context>()),
                     message.as_slice(),
                     &HintsBag::empty());
                 let proof = res.unwrap_or_else(|_| panic!("proof failed for secret: {:?}", secret)).proof;
                 let verifier = TestVerifier;
                 let ver_res = verifier.verify(&tree,
                                               &Env::empty(),
-                                              Rc::new(force_any_val::<Context>()),
+                                              Arc::new(force_any_val::<Context>()),
                                               proof,
                                               message.as_slice());
                 prop_assert_eq!(ver_res.unwrap().result, true, "verify failed on secret: {:?}", &secret);
@@ -407,14 +446,14 @@ mod tests {
                 };
                 let res = prover.prove(&tree,
                     &Env::empty(),
-                    Rc::new(force_any_val::<Context>()),
+                    Arc::new(force_any_val::<Context>()),
                     message.as_slice(),
                     &HintsBag::empty());
                 let proof = res.unwrap_or_else(|_| panic!("proof failed for secret: {:?}", secret)).proof;
                 let verifier = TestVerifier;
                 let ver_res = verifier.verify(&tree,
                                               &Env::empty(),
-                                              Rc::new(force_any_val::<Context>()),
+                                              Arc::new(force_any_val::<Context>()),
                                               proof,
                                               message.as_slice());
                 prop_assert_eq!(ver_res.unwrap().result, true, "verify failed on secret: {:?}", &secret);
@@ -451,17 +490,61 @@ mod tests {
 
             let res = prover.prove(&tree,
                 &Env::empty(),
-                Rc::new(force_any_val::<Context>()),
+                Arc::new(force_any_val::<Context>()),
                 message.as_slice(),
                 &HintsBag::empty());
             let proof = res.unwrap().proof;
             let verifier = TestVerifier;
             let ver_res = verifier.verify(&tree,
                                             &Env::empty(),
-                                            Rc::new(force_any_val::<Context>()),
+                                            Arc::new(force_any_val::<Context>()),
                                             proof,
                                             message.as_slice());
             prop_assert_eq!(ver_res.unwrap().result, true)
         }
     }
+
+    fn verify_without_proof(tree: &ErgoTree, ctx: Context) -> Result<bool, VerifierError> {
+        TestVerifier
+            .verify(tree, &Env::empty(), Arc::new(ctx), ProofBytes::Empty, &[])
+            .map(|res| res.result)
+    }
+
+    #[test]
+    fn test_soft_fork_unknown_tree_version() {
+        use crate::eval::tests::force_any_ctx_with_version;
+        use ergotree_ir::serialization::sigma_byte_reader::from_bytes;
+        use ergotree_ir::serialization::SigmaSerializable;
+        // v4 tree with size flag (parsed as a part of the box bytes)
+        let tree = ErgoTree::sigma_parse(&mut from_bytes(&[0x0c, 0x01, 0x7f])).unwrap();
+        let ctx = force_any_ctx_with_version(ErgoTreeVersion::MAX_SCRIPT_VERSION);
+        assert!(verify_without_proof(&tree, ctx).is_err());
+        let ctx = force_any_ctx_with_version(ErgoTreeVersion::from_block_version(5));
+        assert!(verify_without_proof(&tree, ctx).unwrap());
+    }
+
+    #[test]
+    fn test_soft_fork_validation_settings() {
+        use ergotree_ir::serialization::SigmaSerializable;
+        use ergotree_ir::validation::{
+            RuleStatus, ValidationRule, ValidationSettings, ValidationSettingsUpdate,
+        };
+        // v1 tree with unknown opcode 0x76 as a root
+        let tree = ErgoTree::sigma_parse_bytes(&[0x09, 0x01, 0x76]).unwrap();
+        let ctx = force_any_val::<Context>();
+        assert!(verify_without_proof(&tree, ctx).is_err());
+        let validation_settings =
+            ValidationSettings::default().updated(&ValidationSettingsUpdate {
+                rules_to_disable: vec![],
+                status_updates: vec![(
+                    ValidationRule::CheckValidOpCode.id(),
+                    RuleStatus::Changed(vec![0x76]),
+                )],
+            });
+        let ctx = Context {
+            validation_settings,
+            ..force_any_val::<Context>()
+        };
+        assert!(verify_without_proof(&tree, ctx).unwrap());
+    }
 }
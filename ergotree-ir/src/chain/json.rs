@@ -12,6 +12,7 @@ use serde::Deserializer;
 use serde::Serializer;
 
 pub(crate) mod box_value;
+pub mod contract_template;
 pub(crate) mod ergo_box;
 pub mod ergo_tree;
 pub(crate) mod sigma_protocol;
@@ -0,0 +1,317 @@
+//! Contract template JSON encoding (EIP-5)
+
+use std::convert::TryFrom;
+use std::io::Cursor;
+
+use ergo_chain_types::Base16DecodedBytes;
+use serde::ser::Error;
+use serde::{Deserialize, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::contract_template::ContractTemplate;
+use crate::contract_template::ContractTemplateError;
+use crate::contract_template::ContractTemplateParameter;
+use crate::ergo_tree::ErgoTreeVersion;
+use crate::ergo_tree::ErgoTreeVersionError;
+use crate::mir::constant::Constant;
+use crate::mir::constant::Literal;
+use crate::mir::expr::Expr;
+use crate::serialization::constant_store::ConstantStore;
+use crate::serialization::data::DataSerializer;
+use crate::serialization::sigma_byte_reader::SigmaByteRead;
+use crate::serialization::sigma_byte_reader::SigmaByteReader;
+use crate::serialization::sigma_byte_writer::SigmaByteWrite;
+use crate::serialization::sigma_byte_writer::SigmaByteWriter;
+use crate::serialization::SigmaParsingError;
+use crate::serialization::SigmaSerializable;
+use crate::serialization::SigmaSerializationError;
+use crate::types::stype::SType;
+
+/// Contract template in the EIP-5 JSON format, the types, values (without types) and the
+/// expression are serialized and Base16-encoded
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct ContractTemplateJson {
+    /// ErgoTree version of the instantiated trees
+    #[serde(rename = "treeVersion", default)]
+    pub tree_version: Option<u8>,
+    /// User-friendly name of the template
+    #[serde(rename = "name")]
+    pub name: String,
+    /// User-friendly description of the template
+    #[serde(rename = "description")]
+    pub description: String,
+    /// Serialized types of the constants
+    #[serde(rename = "constTypes")]
+    pub const_types: Vec<Base16DecodedBytes>,
+    /// Serialized default values of the constants (`null` for the constants without default)
+    #[serde(
+        rename = "constValues",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub const_values: Option<Vec<Option<Base16DecodedBytes>>>,
+    /// Named parameters
+    #[serde(rename = "parameters")]
+    pub parameters: Vec<ContractTemplateParameter>,
+    /// Serialized expression with constant placeholders
+    #[serde(rename = "expressionTree")]
+    pub expression_tree: Base16DecodedBytes,
+}
+
+/// Errors on contract template conversion from JSON
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum ContractTemplateFromJsonError {
+    /// Invalid tree version
+    #[error("Invalid tree version: {0}")]
+    TreeVersion(#[from] ErgoTreeVersionError),
+    /// Type, value or expression parsing error
+    #[error("Parsing error: {0}")]
+    SigmaParsingError(#[from] SigmaParsingError),
+    /// Invalid template
+    #[error("Invalid template: {0}")]
+    ContractTemplateError(#[from] ContractTemplateError),
+}
+
+impl TryFrom<ContractTemplateJson> for ContractTemplate {
+    type Error = ContractTemplateFromJsonError;
+
+    fn try_from(json: ContractTemplateJson) -> Result<Self, Self::Error> {
+        let tree_version = json
+            .tree_version
+            .map(ErgoTreeVersion::parse_version)
+            .transpose()?;
+        let const_types = json
+            .const_types
+            .iter()
+            .map(|bytes| SType::sigma_parse_bytes(&bytes.0))
+            .collect::<Result<Vec<_>, _>>()?;
+        match &json.const_values {
+            Some(values) if values.len() != const_types.len() => {
+                return Err(ContractTemplateError::ConstValuesLength {
+                    expected: const_types.len(),
+                    actual: values.len(),
+                }
+                .into())
+            }
+            _ => (),
+        }
+        let const_values = json
+            .const_values
+            .map(|values| {
+                values
+                    .iter()
+                    .zip(&const_types)
+                    .map(|(value, tpe)| {
+                        value
+                            .as_ref()
+                            .map(|bytes| parse_value(tpe.clone(), &bytes.0, tree_version))
+                            .transpose()
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+        // Only the types of the constants are needed to parse the placeholders
+        let type_store = ConstantStore::new(
+            const_types
+                .iter()
+                .map(|tpe| Constant {
+                    tpe: tpe.clone(),
+                    v: Literal::Unit,
+                })
+                .collect(),
+        );
+        let mut r = SigmaByteReader::new(Cursor::new(&json.expression_tree.0[..]), type_store);
+        if let Some(version) = tree_version {
+            r.set_tree_version(version);
+        }
+        let expression_tree = Expr::sigma_parse(&mut r)?;
+        Ok(ContractTemplate::new(
+            tree_version,
+            json.name,
+            json.description,
+            const_types,
+            const_values,
+            json.parameters,
+            expression_tree,
+        )?)
+    }
+}
+
+impl TryFrom<&ContractTemplate> for ContractTemplateJson {
+    type Error = SigmaSerializationError;
+
+    fn try_from(template: &ContractTemplate) -> Result<Self, Self::Error> {
+        let version = template
+            .tree_version()
+            .unwrap_or(ErgoTreeVersion::MAX_SCRIPT_VERSION);
+        let const_types = template
+            .const_types()
+            .iter()
+            .map(|tpe| tpe.sigma_serialize_bytes().map(Base16DecodedBytes))
+            .collect::<Result<Vec<_>, _>>()?;
+        let const_values = template
+            .const_values()
+            .map(|values| {
+                values
+                    .iter()
+                    .map(|value| {
+                        value
+                            .as_ref()
+                            .map(|c| serialize_value(&c.v, version).map(Base16DecodedBytes))
+                            .transpose()
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+        let mut expression_tree = Vec::new();
+        let mut w = SigmaByteWriter::new(&mut expression_tree, None);
+        w.set_tree_version(version);
+        template.expression_tree().sigma_serialize(&mut w)?;
+        Ok(ContractTemplateJson {
+            tree_version: template.tree_version().map(|v| v.value()),
+            name: template.name().to_string(),
+            description: template.description().to_string(),
+            const_types,
+            const_values,
+            parameters: template.parameters().to_vec(),
+            expression_tree: Base16DecodedBytes(expression_tree),
+        })
+    }
+}
+
+impl Serialize for ContractTemplate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ContractTemplateJson::try_from(self)
+            .map_err(|e| S::Error::custom(e.to_string()))?
+            .serialize(serializer)
+    }
+}
+
+fn serialize_value(
+    value: &Literal,
+    version: ErgoTreeVersion,
+) -> Result<Vec<u8>, SigmaSerializationError> {
+    let mut bytes = Vec::new();
+    let mut w = SigmaByteWriter::new(&mut bytes, None);
+    w.set_tree_version(version);
+    DataSerializer::sigma_serialize(value, &mut w)?;
+    Ok(bytes)
+}
+
+fn parse_value(
+    tpe: SType,
+    bytes: &[u8],
+    version: Option<ErgoTreeVersion>,
+) -> Result<Constant, SigmaParsingError> {
+    let mut r = SigmaByteReader::new(Cursor::new(bytes), ConstantStore::empty());
+    if let Some(version) = version {
+        r.set_tree_version(version);
+    }
+    let v = DataSerializer::sigma_parse(&tpe, &mut r)?;
+    Ok(Constant { tpe, v })
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mir::bin_op::BinOp;
+    use crate::mir::bin_op::RelationOp;
+    use crate::mir::bool_to_sigma::BoolToSigmaProp;
+    use crate::mir::constant::ConstantPlaceholder;
+    use crate::mir::global_vars::GlobalVars;
+    use expect_test::expect;
+
+    fn deadline_template() -> ContractTemplate {
+        let expr: Expr = BoolToSigmaProp {
+            input: Box::new(
+                BinOp {
+                    kind: RelationOp::Gt.into(),
+                    left: Box::new(GlobalVars::Height.into()),
+                    right: Box::new(
+                        ConstantPlaceholder {
+                            id: 0,
+                            tpe: SType::SInt,
+                        }
+                        .into(),
+                    ),
+                }
+                .into(),
+            ),
+        }
+        .into();
+        ContractTemplate::new(
+            Some(ErgoTreeVersion::V1),
+            "deadline".to_string(),
+            "Spendable after the deadline".to_string(),
+            vec![SType::SInt, SType::SLong],
+            Some(vec![None, Some(1000i64.into())]),
+            vec![ContractTemplateParameter {
+                name: "deadline".to_string(),
+                description: "Deadline height".to_string(),
+                constant_index: 0,
+            }],
+            expr,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let template = deadline_template();
+        let json = serde_json::to_string_pretty(&template).unwrap();
+        expect![[r#"
+            {
+              "treeVersion": 1,
+              "name": "deadline",
+              "description": "Spendable after the deadline",
+              "constTypes": [
+                "04",
+                "05"
+              ],
+              "constValues": [
+                null,
+                "d00f"
+              ],
+              "parameters": [
+                {
+                  "name": "deadline",
+                  "description": "Deadline height",
+                  "constantIndex": 0
+                }
+              ],
+              "expressionTree": "d191a37300"
+            }"#]]
+        .assert_eq(&json);
+        let parsed: ContractTemplate = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, template);
+    }
+
+    #[test]
+    fn parse_without_version_and_values() {
+        let json = r#"{
+            "name": "deadline",
+            "description": "",
+            "constTypes": ["04"],
+            "parameters": [{"name": "deadline", "description": "", "constantIndex": 0}],
+            "expressionTree": "d191a37300"
+        }"#;
+        let template: ContractTemplate = serde_json::from_str(json).unwrap();
+        assert_eq!(template.tree_version(), None);
+        assert_eq!(template.const_values(), None);
+        assert_eq!(template.const_types(), &[SType::SInt]);
+    }
+
+    #[test]
+    fn parse_invalid_template() {
+        let json = r#"{
+            "name": "deadline",
+            "description": "",
+            "constTypes": ["04"],
+            "constValues": [],
+            "parameters": [],
+            "expressionTree": "d191a37300"
+        }"#;
+        assert!(serde_json::from_str::<ContractTemplate>(json).is_err());
+    }
+}
@@ -0,0 +1,579 @@
+//! Contract templates (EIP-5)
+//!
+//! A template is an expression with constant placeholders, the types of the constants (and
+//! optionally their default values) and named parameters mapped to the constant positions.
+//! See <https://github.com/ergoplatform/eips/blob/master/eip-0005.md>
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use thiserror::Error;
+
+use crate::ergo_tree::ErgoTree;
+use crate::ergo_tree::ErgoTreeError;
+use crate::ergo_tree::ErgoTreeHeader;
+use crate::ergo_tree::ErgoTreeVersion;
+use crate::mir::constant::Constant;
+use crate::mir::expr::Expr;
+use crate::mir::traverse::for_each_descendant;
+use crate::types::stype::SType;
+
+/// Named parameter of a contract template
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContractTemplateParameter {
+    /// User-friendly name of the parameter
+    #[cfg_attr(feature = "json", serde(rename = "name"))]
+    pub name: String,
+    /// User-friendly description of the parameter
+    #[cfg_attr(feature = "json", serde(rename = "description"))]
+    pub description: String,
+    /// Index of the constant (in the template constants) this parameter sets
+    #[cfg_attr(feature = "json", serde(rename = "constantIndex"))]
+    pub constant_index: u32,
+}
+
+/// Contract template (EIP-5), an expression with constant placeholders which can be instantiated
+/// into an [`ErgoTree`] with the given parameter values
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Deserialize),
+    serde(try_from = "crate::chain::json::contract_template::ContractTemplateJson")
+)]
+pub struct ContractTemplate {
+    tree_version: Option<ErgoTreeVersion>,
+    name: String,
+    description: String,
+    const_types: Vec<SType>,
+    const_values: Option<Vec<Option<Constant>>>,
+    parameters: Vec<ContractTemplateParameter>,
+    expression_tree: Expr,
+}
+
+impl ContractTemplate {
+    /// Creates a template, checking that:
+    /// - the default values (if any) are given for each constant type and have that type;
+    /// - the parameters have unique names and refer to distinct constants;
+    /// - every constant without a default value is set by a parameter;
+    /// - the constant placeholders of the expression refer to the constants (with their types).
+    pub fn new(
+        tree_version: Option<ErgoTreeVersion>,
+        name: String,
+        description: String,
+        const_types: Vec<SType>,
+        const_values: Option<Vec<Option<Constant>>>,
+        parameters: Vec<ContractTemplateParameter>,
+        expression_tree: Expr,
+    ) -> Result<Self, ContractTemplateError> {
+        if let Some(values) = &const_values {
+            if values.len() != const_types.len() {
+                return Err(ContractTemplateError::ConstValuesLength {
+                    expected: const_types.len(),
+                    actual: values.len(),
+                });
+            }
+            for (index, (value, tpe)) in values.iter().zip(&const_types).enumerate() {
+                match value {
+                    Some(c) if c.tpe != *tpe => {
+                        return Err(ContractTemplateError::DefaultValueTypeMismatch {
+                            index,
+                            expected: tpe.clone(),
+                            actual: c.tpe.clone(),
+                        })
+                    }
+                    _ => (),
+                }
+            }
+        }
+        let mut names = HashSet::new();
+        let mut indices = HashSet::new();
+        for p in &parameters {
+            if p.constant_index as usize >= const_types.len() {
+                return Err(ContractTemplateError::ParameterIndexOutOfBounds {
+                    name: p.name.clone(),
+                    index: p.constant_index,
+                });
+            }
+            if !names.insert(p.name.as_str()) {
+                return Err(ContractTemplateError::DuplicateParameterName(
+                    p.name.clone(),
+                ));
+            }
+            if !indices.insert(p.constant_index) {
+                return Err(ContractTemplateError::DuplicateParameterIndex(
+                    p.constant_index,
+                ));
+            }
+        }
+        for index in 0..const_types.len() {
+            let has_default = const_values
+                .as_ref()
+                .map_or(false, |values| values[index].is_some());
+            if !has_default && !indices.contains(&(index as u32)) {
+                return Err(ContractTemplateError::MissingConstantValue(index));
+            }
+        }
+        let mut placeholder_error = None;
+        for_each_descendant(&expression_tree, &mut |e| {
+            if let Expr::ConstPlaceholder(ph) = e {
+                if placeholder_error.is_none() && const_types.get(ph.id as usize) != Some(&ph.tpe) {
+                    placeholder_error = Some(ContractTemplateError::InvalidPlaceholder {
+                        id: ph.id,
+                        tpe: ph.tpe.clone(),
+                    });
+                }
+            }
+        });
+        if let Some(e) = placeholder_error {
+            return Err(e);
+        }
+        Ok(ContractTemplate {
+            tree_version,
+            name,
+            description,
+            const_types,
+            const_values,
+            parameters,
+            expression_tree,
+        })
+    }
+
+    /// Creates a template from an expression in which the constant placeholders `0..n` are the
+    /// given `n` parameters (with their default values). The constants of the expression are
+    /// segregated after the parameters (and keep their values).
+    pub fn from_expr(
+        tree_version: Option<ErgoTreeVersion>,
+        name: String,
+        description: String,
+        parameters: Vec<(String, Constant)>,
+        expr: &Expr,
+    ) -> Result<Self, ContractTemplateError> {
+        let (param_names, param_values): (Vec<String>, Vec<Constant>) =
+            parameters.into_iter().unzip();
+        let (constants, expression_tree) = ErgoTree::segregate_constants(
+            tree_version.unwrap_or(ErgoTreeVersion::MAX_SCRIPT_VERSION),
+            param_values,
+            expr,
+        )?;
+        let parameters = param_names
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| ContractTemplateParameter {
+                name,
+                description: String::new(),
+                constant_index: index as u32,
+            })
+            .collect();
+        ContractTemplate::new(
+            tree_version,
+            name,
+            description,
+            constants.iter().map(|c| c.tpe.clone()).collect(),
+            Some(constants.into_iter().map(Some).collect()),
+            parameters,
+            expression_tree,
+        )
+    }
+
+    /// ErgoTree version of the instantiated trees (if fixed by the template)
+    pub fn tree_version(&self) -> Option<ErgoTreeVersion> {
+        self.tree_version
+    }
+
+    /// User-friendly name of the template
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// User-friendly description of the template
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Types of the constants (in the order of the segregated constants)
+    pub fn const_types(&self) -> &[SType] {
+        &self.const_types
+    }
+
+    /// Default values of the constants (`None` for the constants without a default value)
+    pub fn const_values(&self) -> Option<&[Option<Constant>]> {
+        self.const_values.as_deref()
+    }
+
+    /// Named parameters
+    pub fn parameters(&self) -> &[ContractTemplateParameter] {
+        &self.parameters
+    }
+
+    /// Expression with constant placeholders
+    pub fn expression_tree(&self) -> &Expr {
+        &self.expression_tree
+    }
+
+    /// Instantiates an [`ErgoTree`] (with constant segregation) with the given parameter values,
+    /// the parameters which are not given take their default values. The tree version of the
+    /// template takes precedence over the given `version`, which is required if the template
+    /// doesn't have one.
+    pub fn apply_template(
+        &self,
+        version: Option<ErgoTreeVersion>,
+        parameters: &HashMap<String, Constant>,
+    ) -> Result<ErgoTree, ContractTemplateError> {
+        let version = self
+            .tree_version
+            .or(version)
+            .ok_or(ContractTemplateError::MissingTreeVersion)?;
+        if let Some(name) = parameters
+            .keys()
+            .find(|name| !self.parameters.iter().any(|p| &&p.name == name))
+        {
+            return Err(ContractTemplateError::UnknownParameter(name.clone()));
+        }
+        let mut values = self
+            .const_values
+            .clone()
+            .unwrap_or_else(|| vec![None; self.const_types.len()]);
+        for p in &self.parameters {
+            let index = p.constant_index as usize;
+            match parameters.get(&p.name) {
+                Some(c) if c.tpe != self.const_types[index] => {
+                    return Err(ContractTemplateError::ParameterTypeMismatch {
+                        name: p.name.clone(),
+                        expected: self.const_types[index].clone(),
+                        actual: c.tpe.clone(),
+                    })
+                }
+                Some(c) => values[index] = Some(c.clone()),
+                None if values[index].is_none() => {
+                    return Err(ContractTemplateError::MissingParameter(p.name.clone()))
+                }
+                None => (),
+            }
+        }
+        let constants = values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| value.ok_or(ContractTemplateError::MissingConstantValue(index)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ErgoTree::with_segregated_constants(
+            ErgoTreeHeader::with_version(version, true),
+            constants,
+            &self.expression_tree,
+        )?)
+    }
+}
+
+/// Contract template errors
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum ContractTemplateError {
+    /// Number of the default values differs from the number of the constants
+    #[error("Expected {expected} constant values, got {actual}")]
+    ConstValuesLength {
+        /// Number of the constants
+        expected: usize,
+        /// Number of the default values
+        actual: usize,
+    },
+    /// Default value type differs from the constant type
+    #[error("Default value of the constant {index} has type {actual:?}, expected {expected:?}")]
+    DefaultValueTypeMismatch {
+        /// Constant index
+        index: usize,
+        /// Constant type
+        expected: SType,
+        /// Default value type
+        actual: SType,
+    },
+    /// Parameter refers to a non-existent constant
+    #[error("Parameter {name} refers to the constant {index} which is out of bounds")]
+    ParameterIndexOutOfBounds {
+        /// Parameter name
+        name: String,
+        /// Constant index
+        index: u32,
+    },
+    /// Several parameters have the same name
+    #[error("Duplicate parameter name: {0}")]
+    DuplicateParameterName(String),
+    /// Several parameters refer to the same constant
+    #[error("Several parameters refer to the constant {0}")]
+    DuplicateParameterIndex(u32),
+    /// Constant has neither a default value nor a parameter
+    #[error("Constant {0} has neither a default value nor a parameter")]
+    MissingConstantValue(usize),
+    /// Constant placeholder refers to a non-existent constant or has a different type
+    #[error("Constant placeholder {id} of type {tpe:?} doesn't match the template constants")]
+    InvalidPlaceholder {
+        /// Placeholder id (constant index)
+        id: u32,
+        /// Placeholder type
+        tpe: SType,
+    },
+    /// Parameter without a default value is not given
+    #[error("Value for the parameter {0} (without a default value) is not given")]
+    MissingParameter(String),
+    /// Parameter value type differs from the constant type
+    #[error("Parameter {name} has type {actual:?}, expected {expected:?}")]
+    ParameterTypeMismatch {
+        /// Parameter name
+        name: String,
+        /// Constant type
+        expected: SType,
+        /// Parameter value type
+        actual: SType,
+    },
+    /// Value is given for a parameter the template doesn't have
+    #[error("Unknown parameter: {0}")]
+    UnknownParameter(String),
+    /// Neither the template nor the caller specify the tree version
+    #[error("ErgoTree version is not specified by the template and is not given")]
+    MissingTreeVersion,
+    /// ErgoTree serialization error
+    #[error("ErgoTree error: {0}")]
+    ErgoTreeError(#[from] ErgoTreeError),
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mir::bin_op::BinOp;
+    use crate::mir::bin_op::RelationOp;
+    use crate::mir::bool_to_sigma::BoolToSigmaProp;
+    use crate::mir::constant::ConstantPlaceholder;
+    use crate::mir::global_vars::GlobalVars;
+    use crate::serialization::SigmaSerializable;
+
+    /// `sigmaProp(HEIGHT > rhs)`
+    fn height_gt(rhs: Expr) -> Expr {
+        BoolToSigmaProp {
+            input: Box::new(
+                BinOp {
+                    kind: RelationOp::Gt.into(),
+                    left: Box::new(GlobalVars::Height.into()),
+                    right: Box::new(rhs),
+                }
+                .into(),
+            ),
+        }
+        .into()
+    }
+
+    fn deadline_template(default: Option<i32>) -> ContractTemplate {
+        ContractTemplate::new(
+            None,
+            "deadline".to_string(),
+            "Spendable after the deadline".to_string(),
+            vec![SType::SInt],
+            Some(vec![default.map(Constant::from)]),
+            vec![ContractTemplateParameter {
+                name: "deadline".to_string(),
+                description: "Deadline height".to_string(),
+                constant_index: 0,
+            }],
+            height_gt(
+                ConstantPlaceholder {
+                    id: 0,
+                    tpe: SType::SInt,
+                }
+                .into(),
+            ),
+        )
+        .unwrap()
+    }
+
+    fn params(values: &[(&str, Constant)]) -> HashMap<String, Constant> {
+        values
+            .iter()
+            .map(|(name, c)| (name.to_string(), c.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn apply_template() {
+        let template = deadline_template(None);
+        let tree = template
+            .apply_template(
+                Some(ErgoTreeVersion::V0),
+                &params(&[("deadline", 100.into())]),
+            )
+            .unwrap();
+        assert_eq!(tree.get_constants().unwrap(), vec![Constant::from(100)]);
+        assert_eq!(
+            tree.proposition().unwrap(),
+            height_gt(Constant::from(100).into())
+        );
+        assert_eq!(
+            tree,
+            ErgoTree::new(
+                ErgoTreeHeader::v0(true),
+                &height_gt(Constant::from(100).into())
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn apply_template_default_value() {
+        let template = deadline_template(Some(50));
+        let tree = template
+            .apply_template(Some(ErgoTreeVersion::V0), &HashMap::new())
+            .unwrap();
+        assert_eq!(tree.get_constants().unwrap(), vec![Constant::from(50)]);
+    }
+
+    #[test]
+    fn apply_template_version() {
+        let template = deadline_template(Some(50));
+        let tree = template
+            .apply_template(Some(ErgoTreeVersion::V1), &HashMap::new())
+            .unwrap();
+        assert_eq!(tree.sigma_serialize_bytes().unwrap()[0], 0x19);
+        assert_eq!(
+            template.apply_template(None, &HashMap::new()),
+            Err(ContractTemplateError::MissingTreeVersion)
+        );
+        let versioned = ContractTemplate {
+            tree_version: Some(ErgoTreeVersion::V0),
+            ..template
+        };
+        let tree = versioned
+            .apply_template(Some(ErgoTreeVersion::V1), &HashMap::new())
+            .unwrap();
+        assert_eq!(tree.sigma_serialize_bytes().unwrap()[0], 0x10);
+    }
+
+    #[test]
+    fn apply_template_errors() {
+        let template = deadline_template(None);
+        let v0 = Some(ErgoTreeVersion::V0);
+        assert_eq!(
+            template.apply_template(v0, &HashMap::new()),
+            Err(ContractTemplateError::MissingParameter(
+                "deadline".to_string()
+            ))
+        );
+        assert_eq!(
+            template.apply_template(v0, &params(&[("deadline", 100i64.into())])),
+            Err(ContractTemplateError::ParameterTypeMismatch {
+                name: "deadline".to_string(),
+                expected: SType::SInt,
+                actual: SType::SLong,
+            })
+        );
+        assert_eq!(
+            template.apply_template(
+                v0,
+                &params(&[("deadline", 100.into()), ("dedline", 100.into())])
+            ),
+            Err(ContractTemplateError::UnknownParameter(
+                "dedline".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn new_checks_constants() {
+        let template = deadline_template(None);
+        let with = |const_values, parameters, expression_tree| {
+            ContractTemplate::new(
+                None,
+                String::new(),
+                String::new(),
+                vec![SType::SInt],
+                const_values,
+                parameters,
+                expression_tree,
+            )
+        };
+        assert_eq!(
+            with(None, vec![], template.expression_tree.clone()),
+            Err(ContractTemplateError::MissingConstantValue(0))
+        );
+        assert_eq!(
+            with(
+                Some(vec![Some(1i64.into())]),
+                vec![],
+                template.expression_tree.clone()
+            ),
+            Err(ContractTemplateError::DefaultValueTypeMismatch {
+                index: 0,
+                expected: SType::SInt,
+                actual: SType::SLong,
+            })
+        );
+        assert_eq!(
+            with(
+                None,
+                vec![
+                    template.parameters[0].clone(),
+                    template.parameters[0].clone()
+                ],
+                template.expression_tree.clone()
+            ),
+            Err(ContractTemplateError::DuplicateParameterName(
+                "deadline".to_string()
+            ))
+        );
+        assert_eq!(
+            with(
+                None,
+                template.parameters.clone(),
+                height_gt(
+                    ConstantPlaceholder {
+                        id: 1,
+                        tpe: SType::SInt,
+                    }
+                    .into()
+                )
+            ),
+            Err(ContractTemplateError::InvalidPlaceholder {
+                id: 1,
+                tpe: SType::SInt
+            })
+        );
+    }
+
+    #[test]
+    fn from_expr_segregates_constants_after_parameters() {
+        let expr: Expr = BoolToSigmaProp {
+            input: Box::new(
+                BinOp {
+                    kind: RelationOp::Gt.into(),
+                    left: Box::new(Constant::from(7).into()),
+                    right: Box::new(
+                        ConstantPlaceholder {
+                            id: 0,
+                            tpe: SType::SInt,
+                        }
+                        .into(),
+                    ),
+                }
+                .into(),
+            ),
+        }
+        .into();
+        let template = ContractTemplate::from_expr(
+            None,
+            "t".to_string(),
+            String::new(),
+            vec![("x".to_string(), 5.into())],
+            &expr,
+        )
+        .unwrap();
+        assert_eq!(template.const_types(), &[SType::SInt, SType::SInt]);
+        assert_eq!(
+            template.const_values(),
+            Some(&[Some(Constant::from(5)), Some(Constant::from(7))][..])
+        );
+        assert_eq!(template.parameters()[0].constant_index, 0);
+        let tree = template
+            .apply_template(Some(ErgoTreeVersion::V0), &params(&[("x", 3.into())]))
+            .unwrap();
+        assert_eq!(
+            tree.get_constants().unwrap(),
+            vec![Constant::from(3), Constant::from(7)]
+        );
+    }
+}
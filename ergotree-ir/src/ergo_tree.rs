@@ -139,13 +139,16 @@ impl ErgoTree {
         r: &mut R,
         header: ErgoTreeHeader,
     ) -> Result<ParsedErgoTree, ErgoTreeError> {
-        let constants = if header.is_constant_segregation() {
-            ErgoTree::sigma_parse_constants(r)?
-        } else {
-            vec![]
-        };
-        r.set_constant_store(ConstantStore::new(constants.clone()));
-        let root = Expr::sigma_parse(r)?;
+        let (constants, root) = ErgoTree::with_tree_version(r, *header.version(), |r| {
+            let constants = if header.is_constant_segregation() {
+                ErgoTree::sigma_parse_constants(r)?
+            } else {
+                vec![]
+            };
+            r.set_constant_store(ConstantStore::new(constants.clone()));
+            let root = Expr::sigma_parse(r)?;
+            Ok::<_, ErgoTreeError>((constants, root))
+        })?;
         Ok(ParsedErgoTree {
             header,
             constants,
@@ -153,6 +156,20 @@ impl ErgoTree {
         })
     }
 
+    /// Runs `f` with the reader switched to the given tree version, restoring the previous one
+    /// afterwards (trees can be nested, e.g. in box constants)
+    fn with_tree_version<R: SigmaByteRead, T, E>(
+        r: &mut R,
+        version: ErgoTreeVersion,
+        f: impl FnOnce(&mut R) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let prev_version = r.tree_version();
+        r.set_tree_version(version);
+        let res = f(r);
+        r.set_tree_version(prev_version);
+        res
+    }
+
     fn sigma_parse_constants<R: SigmaByteRead>(
         r: &mut R,
     ) -> Result<Vec<Constant>, SigmaParsingError> {
@@ -174,30 +191,55 @@ impl ErgoTree {
 
     /// Creates a tree using provided header and root expression
     pub fn new(header: ErgoTreeHeader, expr: &Expr) -> Result<Self, ErgoTreeError> {
-        Ok(if header.is_constant_segregation() {
-            let mut data = Vec::new();
-            let cs = ConstantStore::empty();
-            let mut w = SigmaByteWriter::new(&mut data, Some(cs));
-            expr.sigma_serialize(&mut w)?;
-            #[allow(clippy::unwrap_used)]
-            // We set constant store earlier
-            let constants = w.constant_store_mut_ref().unwrap().get_all();
-            let cursor = Cursor::new(&mut data[..]);
-            let new_cs = ConstantStore::new(constants.clone());
-            let mut sr = SigmaByteReader::new(cursor, new_cs);
-            let parsed_expr = Expr::sigma_parse(&mut sr)?;
-            ErgoTree::Parsed(ParsedErgoTree {
-                header,
-                constants,
-                root: parsed_expr,
-            })
+        if header.is_constant_segregation() {
+            ErgoTree::with_segregated_constants(header, Vec::new(), expr)
         } else {
-            ErgoTree::Parsed(ParsedErgoTree {
+            Ok(ErgoTree::Parsed(ParsedErgoTree {
                 header,
                 constants: Vec::new(),
                 root: expr.clone(),
-            })
-        })
+            }))
+        }
+    }
+
+    /// Creates a tree from the given header (with constant segregation flag set), constants and
+    /// root expression.
+    /// Constant placeholders in the expression refer to the given constants, constants of the
+    /// expression are segregated after them.
+    pub(crate) fn with_segregated_constants(
+        header: ErgoTreeHeader,
+        constants: Vec<Constant>,
+        expr: &Expr,
+    ) -> Result<Self, ErgoTreeError> {
+        let (constants, root) = ErgoTree::segregate_constants(*header.version(), constants, expr)?;
+        Ok(ErgoTree::Parsed(ParsedErgoTree {
+            header,
+            constants,
+            root,
+        }))
+    }
+
+    /// Replaces constants of the expression with placeholders (appending them to the given
+    /// constants), returns all constants and the new expression
+    pub(crate) fn segregate_constants(
+        version: ErgoTreeVersion,
+        constants: Vec<Constant>,
+        expr: &Expr,
+    ) -> Result<(Vec<Constant>, Expr), ErgoTreeError> {
+        let mut data = Vec::new();
+        let cs = ConstantStore::new(constants);
+        let mut w = SigmaByteWriter::new(&mut data, Some(cs));
+        w.set_tree_version(version);
+        expr.sigma_serialize(&mut w)?;
+        #[allow(clippy::unwrap_used)]
+        // We set constant store earlier
+        let constants = w.constant_store_mut_ref().unwrap().get_all();
+        let cursor = Cursor::new(&mut data[..]);
+        let new_cs = ConstantStore::new(constants.clone());
+        let mut sr = SigmaByteReader::new(cursor, new_cs);
+        sr.set_tree_version(version);
+        let parsed_expr = Expr::sigma_parse(&mut sr)?;
+        Ok((constants, parsed_expr))
     }
 
     /// Reasonable limit for the number of constants allowed in the ErgoTree
@@ -214,12 +256,14 @@ impl ErgoTree {
             let mut data = Vec::new();
             let cs = ConstantStore::empty();
             let mut w = SigmaByteWriter::new(&mut data, Some(cs));
+            w.set_tree_version(*tree.header.version());
             root.sigma_serialize(&mut w)?;
             let cursor = Cursor::new(&mut data[..]);
             let mut sr = SigmaByteReader::new_with_substitute_placeholders(
                 cursor,
                 ConstantStore::new(tree.constants),
             );
+            sr.set_tree_version(*tree.header.version());
             let parsed_expr = Expr::sigma_parse(&mut sr)?;
             Ok(parsed_expr)
         } else {
@@ -322,6 +366,7 @@ impl SigmaSerializable for ErgoTree {
                 let bytes = {
                     let mut data = Vec::new();
                     let mut inner_w = SigmaByteWriter::new(&mut data, None);
+                    inner_w.set_tree_version(*parsed_tree.header.version());
                     if parsed_tree.header.is_constant_segregation() {
                         inner_w.put_usize_as_u32_unwrapped(parsed_tree.constants.len())?;
                         parsed_tree
@@ -344,7 +389,26 @@ impl SigmaSerializable for ErgoTree {
     }
 
     fn sigma_parse<R: SigmaByteRead>(r: &mut R) -> Result<Self, SigmaParsingError> {
-        let header = ErgoTreeHeader::sigma_parse(r)?;
+        let header_byte = r.get_u8()?;
+        let header = match ErgoTreeHeader::new(header_byte) {
+            Ok(header) => header,
+            Err(error) if header_byte & ErgoTreeHeader::HAS_SIZE_FLAG != 0 => {
+                // A tree of the unknown (future) version, keep its bytes so that the verifier
+                // can decide whether it's a soft-fork (see `ValidationSettings`)
+                let tree_size_bytes = r.get_u32()?;
+                let mut buf = vec![0u8; tree_size_bytes as usize];
+                r.read_exact(buf.as_mut_slice())?;
+                let mut bytes = vec![header_byte];
+                #[allow(clippy::unwrap_used)]
+                bytes.put_u32(tree_size_bytes).unwrap();
+                bytes.extend_from_slice(&buf);
+                return Ok(ErgoTree::Unparsed {
+                    tree_bytes: bytes,
+                    error: error.into(),
+                });
+            }
+            Err(error) => return Err(error.into()),
+        };
         if header.has_size() {
             let tree_size_bytes = r.get_u32()?;
             let mut buf = vec![0u8; tree_size_bytes as usize];
@@ -366,13 +430,16 @@ impl SigmaSerializable for ErgoTree {
                 }
             }
         } else {
-            let constants = if header.is_constant_segregation() {
-                ErgoTree::sigma_parse_constants(r)?
-            } else {
-                vec![]
-            };
-            r.set_constant_store(ConstantStore::new(constants.clone()));
-            let root = Expr::sigma_parse(r)?;
+            let (constants, root) = ErgoTree::with_tree_version(r, *header.version(), |r| {
+                let constants = if header.is_constant_segregation() {
+                    ErgoTree::sigma_parse_constants(r)?
+                } else {
+                    vec![]
+                };
+                r.set_constant_store(ConstantStore::new(constants.clone()));
+                let root = Expr::sigma_parse(r)?;
+                Ok::<_, SigmaParsingError>((constants, root))
+            })?;
             Ok(ErgoTree::Parsed(ParsedErgoTree {
                 header,
                 constants,
@@ -766,4 +833,53 @@ mod tests {
         //dbg!(&tree);
         assert!(tree.parsed_tree().is_err(), "the tree is BoolToSigmaProp(SigmaOr(pk1, pk2)) is invalid (BoolToSigmaProp expects bool");
     }
+
+    #[test]
+    fn v3_tree_roundtrip() {
+        use crate::mir::bin_op::{BinOp, BinOpKind, RelationOp};
+        use crate::unsignedbigint256::UnsignedBigInt256;
+        let expr: Expr = BinOp {
+            kind: BinOpKind::Relation(RelationOp::Eq),
+            left: Box::new(Expr::Const(UnsignedBigInt256::max_value().into())),
+            right: Box::new(Expr::Const(UnsignedBigInt256::from(1u64).into())),
+        }
+        .into();
+        let tree = ErgoTree::new(ErgoTreeHeader::v3(true), &expr).unwrap();
+        let bytes = tree.sigma_serialize_bytes().unwrap();
+        assert_eq!(bytes[0], 0x1b);
+        let parsed = ErgoTree::sigma_parse_bytes(&bytes).unwrap();
+        assert_eq!(parsed, tree);
+        assert_eq!(
+            *parsed.parsed_tree().unwrap().header.version(),
+            ErgoTreeVersion::V3
+        );
+
+        // the same body is not valid in a v2 tree (UnsignedBigInt type is unknown)
+        let mut v2_bytes = bytes;
+        v2_bytes[0] = 0x1a;
+        assert!(ErgoTree::sigma_parse_bytes(&v2_bytes)
+            .unwrap()
+            .parsed_tree()
+            .is_err());
+    }
+
+    #[test]
+    fn unknown_tree_version() {
+        assert!(ErgoTreeHeader::new(0x0c).is_err());
+        assert!(ErgoTreeHeader::new(0x0b).is_ok());
+    }
+
+    #[test]
+    fn unknown_tree_version_with_size_is_unparsed() {
+        // header v4 with size flag, 2 bytes of the tree body, followed by a byte of the next item
+        let bytes = [0x0c, 0x02, 0xaa, 0xbb, 0x01];
+        let mut r = SigmaByteReader::new(Cursor::new(&bytes[..]), ConstantStore::empty());
+        let tree = ErgoTree::sigma_parse(&mut r).unwrap();
+        assert!(matches!(tree, ErgoTree::Unparsed { .. }));
+        assert_eq!(tree.sigma_serialize_bytes().unwrap(), bytes[..4].to_vec());
+        assert_eq!(r.get_u8().unwrap(), 0x01);
+        // without the size there is no way to skip the tree
+        let mut r = SigmaByteReader::new(Cursor::new(&[0x04u8, 0x01][..]), ConstantStore::empty());
+        assert!(ErgoTree::sigma_parse(&mut r).is_err());
+    }
 }
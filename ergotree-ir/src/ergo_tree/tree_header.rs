@@ -47,7 +47,7 @@ impl ErgoTreeHeader {
 
 impl ErgoTreeHeader {
     const CONSTANT_SEGREGATION_FLAG: u8 = 0b0001_0000;
-    const HAS_SIZE_FLAG: u8 = 0b0000_1000;
+    pub(crate) const HAS_SIZE_FLAG: u8 = 0b0000_1000;
 
     /// Parse from byte
     pub fn new(header_byte: u8) -> Result<Self, ErgoTreeHeaderError> {
@@ -91,6 +91,26 @@ impl ErgoTreeHeader {
         }
     }
 
+    /// Return a header with version set to 3 (Sigma 6.0, with size flag set) and constant
+    /// segregation flag set to the given value
+    pub fn v3(constant_segregation: bool) -> Self {
+        ErgoTreeHeader {
+            version: ErgoTreeVersion::V3,
+            is_constant_segregation: constant_segregation,
+            has_size: true,
+        }
+    }
+
+    /// Return a header with the given version (with size flag set for versions above 0) and
+    /// constant segregation flag set to the given value
+    pub fn with_version(version: ErgoTreeVersion, constant_segregation: bool) -> Self {
+        ErgoTreeHeader {
+            version,
+            is_constant_segregation: constant_segregation,
+            has_size: version > ErgoTreeVersion::V0,
+        }
+    }
+
     /// Returns true if constant segregation flag is set
     pub fn is_constant_segregation(&self) -> bool {
         self.is_constant_segregation
@@ -119,7 +139,7 @@ pub enum ErgoTreeHeaderError {
 }
 
 /// ErgoTree version 0..=7, should fit in 3 bits
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
 pub struct ErgoTreeVersion(u8);
 
 impl ErgoTreeVersion {
@@ -129,16 +149,42 @@ impl ErgoTreeVersion {
     pub const V0: Self = ErgoTreeVersion(0);
     /// Version 1 (size flag is mandatory)
     pub const V1: Self = ErgoTreeVersion(1);
+    /// Version 2 (JIT costing, Sigma 5.0)
+    pub const V2: Self = ErgoTreeVersion(2);
+    /// Version 3 (Sigma 6.0)
+    pub const V3: Self = ErgoTreeVersion(3);
+    /// The first version with Sigma 6.0 features (`UnsignedBigInt` type, new methods, `Option`
+    /// constants, etc.)
+    pub const V6_SOFT_FORK_VERSION: Self = Self::V3;
+    /// The maximum version supported by this implementation
+    pub const MAX_SCRIPT_VERSION: Self = Self::V3;
 
     /// Returns a value of the version bits from the given header byte.
     pub fn parse_version(header_byte: u8) -> Result<Self, ErgoTreeVersionError> {
         let version = header_byte & ErgoTreeVersion::VERSION_MASK;
-        if version <= 1 {
+        if version <= Self::MAX_SCRIPT_VERSION.0 {
             Ok(ErgoTreeVersion(version))
         } else {
             Err(ErgoTreeVersionError::InvalidVersion(version))
         }
     }
+
+    /// Script version activated on the blockchain for the given block version
+    /// (`block_version - 1`, the first block version is 1)
+    pub fn from_block_version(block_version: u8) -> Self {
+        ErgoTreeVersion(block_version.saturating_sub(1))
+    }
+
+    /// Version number
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for ErgoTreeVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "v{}", self.0)
+    }
 }
 
 /// Version parsing error
@@ -24,12 +24,16 @@ mod has_opcode;
 pub mod base16_str;
 pub mod bigint256;
 pub mod chain;
+pub mod contract_template;
 pub mod ergo_tree;
 pub mod mir;
+pub mod optimizer;
 pub mod pretty_printer;
 pub mod serialization;
 pub mod sigma_protocol;
 pub mod source_span;
 pub mod type_check;
 pub mod types;
+pub mod unsignedbigint256;
 pub mod util;
+pub mod validation;
@@ -30,6 +30,7 @@ pub mod coll_fold;
 pub mod coll_forall;
 /// Collection.map
 pub mod coll_map;
+pub mod coll_shift;
 /// Collection.size
 pub mod coll_size;
 /// Collection.slice
@@ -65,6 +66,8 @@ pub mod extract_id;
 pub mod extract_reg_as;
 /// Box.scriptBytes
 pub mod extract_script_bytes;
+/// Polymorphic function definition
+pub mod fun_def;
 /// User-defined function
 pub mod func_value;
 pub mod get_var;
@@ -76,6 +79,7 @@ pub mod logical_not;
 pub mod long_to_byte_array;
 /// Object method call
 pub mod method_call;
+pub mod mod_q;
 /// Multiply op for GroupElement
 pub mod multiply_group;
 /// Negation operation on numeric type.
@@ -86,6 +90,7 @@ pub mod option_get;
 pub mod option_get_or_else;
 /// Returns false if the option is None, true otherwise.
 pub mod option_is_defined;
+pub mod option_some;
 /// Logical OR op
 pub mod or;
 /// Object property call
@@ -96,7 +101,10 @@ pub mod sigma_and;
 pub mod sigma_or;
 /// Extract serialized bytes of a SigmaProp value
 pub mod sigma_prop_bytes;
+pub mod sigma_prop_is_proven;
 pub mod subst_const;
+/// Traversal of the expression tree
+pub mod traverse;
 /// Perform a lookup of key in a tree
 pub mod tree_lookup;
 /// Tuple of elements
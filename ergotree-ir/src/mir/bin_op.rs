@@ -147,6 +147,12 @@ pub enum BitOp {
     BitAnd,
     /// Bitwise Xor
     BitXor,
+    /// Arithmetic (sign-preserving) shift right
+    BitShiftRight,
+    /// Shift left
+    BitShiftLeft,
+    /// Logical (zero-filling) shift right
+    BitShiftRightZeroed,
 }
 
 impl From<BitOp> for OpCode {
@@ -155,6 +161,9 @@ impl From<BitOp> for OpCode {
             BitOp::BitOr => OpCode::BIT_OR,
             BitOp::BitAnd => OpCode::BIT_AND,
             BitOp::BitXor => OpCode::BIT_XOR,
+            BitOp::BitShiftRight => OpCode::BIT_SHIFT_RIGHT,
+            BitOp::BitShiftLeft => OpCode::BIT_SHIFT_LEFT,
+            BitOp::BitShiftRightZeroed => OpCode::BIT_SHIFT_RIGHT_ZEROED,
         }
     }
 }
@@ -165,6 +174,9 @@ impl Display for BitOp {
             BitOp::BitOr => write!(f, "|"),
             BitOp::BitAnd => write!(f, "&"),
             BitOp::BitXor => write!(f, "^"),
+            BitOp::BitShiftRight => write!(f, ">>"),
+            BitOp::BitShiftLeft => write!(f, "<<"),
+            BitOp::BitShiftRightZeroed => write!(f, ">>>"),
         }
     }
 }
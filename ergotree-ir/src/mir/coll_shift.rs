@@ -0,0 +1,171 @@
+//! Bit-wise shifts and rotations of collections
+
+use std::fmt::Display;
+
+use super::expr::Expr;
+use super::expr::InvalidArgumentError;
+use crate::has_opcode::HasOpCode;
+use crate::serialization::op_code::OpCode;
+use crate::serialization::sigma_byte_writer::SigmaByteWrite;
+use crate::serialization::SigmaSerializable;
+use crate::serialization::SigmaSerializeResult;
+use crate::types::stype::SType;
+
+#[cfg(feature = "arbitrary")]
+use proptest_derive::Arbitrary;
+
+/// Kind of the collection shift
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum CollShiftOp {
+    /// Arithmetic shift right (the vacated bits are filled with the most significant bit)
+    ShiftRight,
+    /// Shift left (the vacated bits are filled with zeros)
+    ShiftLeft,
+    /// Logical shift right (the vacated bits are filled with zeros)
+    ShiftRightZeroed,
+    /// Rotate left
+    RotateLeft,
+    /// Rotate right
+    RotateRight,
+}
+
+impl From<CollShiftOp> for OpCode {
+    fn from(op: CollShiftOp) -> Self {
+        match op {
+            CollShiftOp::ShiftRight => OpCode::COLL_SHIFT_RIGHT,
+            CollShiftOp::ShiftLeft => OpCode::COLL_SHIFT_LEFT,
+            CollShiftOp::ShiftRightZeroed => OpCode::COLL_SHIFT_RIGHT_ZEROED,
+            CollShiftOp::RotateLeft => OpCode::COLL_ROTATE_LEFT,
+            CollShiftOp::RotateRight => OpCode::COLL_ROTATE_RIGHT,
+        }
+    }
+}
+
+impl Display for CollShiftOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CollShiftOp::ShiftRight => write!(f, "shiftRight"),
+            CollShiftOp::ShiftLeft => write!(f, "shiftLeft"),
+            CollShiftOp::ShiftRightZeroed => write!(f, "shiftRightZeroed"),
+            CollShiftOp::RotateLeft => write!(f, "rotateLeft"),
+            CollShiftOp::RotateRight => write!(f, "rotateRight"),
+        }
+    }
+}
+
+/// Shift or rotate the bits of a collection.
+/// `Coll[Byte]` is treated as a big-endian bit string (the most significant bit of the first
+/// byte goes first), `Coll[Boolean]` is treated as a bit string with one element per bit.
+/// The size of the collection is preserved.
+///
+/// The opcodes are reserved in the reference implementation, which defines neither their
+/// serialization nor their semantics, so the bit order above is not backed by it. Trees with
+/// these opcodes are rejected by the parser (as by the node) and are only accepted if the opcode
+/// is voted in with the `CheckValidOpCode` soft-fork rule (see [`crate::validation`]).
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct CollShift {
+    /// Operation kind
+    pub kind: CollShiftOp,
+    /// Collection of `SByte` or `SBoolean`
+    pub input: Box<Expr>,
+    /// Number of bits to shift (of `SInt` type)
+    pub shift: Box<Expr>,
+}
+
+impl CollShift {
+    /// Create new object, returns an error if any of the requirements failed
+    pub fn new(kind: CollShiftOp, input: Expr, shift: Expr) -> Result<Self, InvalidArgumentError> {
+        let input_tpe = input.post_eval_tpe();
+        if !matches!(&input_tpe, SType::SColl(elem_tpe) if matches!(**elem_tpe, SType::SByte | SType::SBoolean))
+        {
+            return Err(InvalidArgumentError(format!(
+                "CollShift: expected input to be Coll[Byte] or Coll[Boolean], got {:?}",
+                input_tpe
+            )));
+        }
+        shift.check_post_eval_tpe(&SType::SInt)?;
+        Ok(CollShift {
+            kind,
+            input: input.into(),
+            shift: shift.into(),
+        })
+    }
+
+    /// Type
+    pub fn tpe(&self) -> SType {
+        self.input.post_eval_tpe()
+    }
+
+    pub(crate) fn sigma_serialize<W: SigmaByteWrite>(&self, w: &mut W) -> SigmaSerializeResult {
+        self.input.sigma_serialize(w)?;
+        self.shift.sigma_serialize(w)
+    }
+}
+
+impl HasOpCode for CollShift {
+    fn op_code(&self) -> OpCode {
+        self.kind.into()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod arbitrary {
+    use crate::mir::expr::arbitrary::ArbExprParams;
+
+    use super::*;
+    use proptest::prelude::*;
+
+    impl Arbitrary for CollShift {
+        type Strategy = BoxedStrategy<Self>;
+        type Parameters = ();
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            (
+                any::<CollShiftOp>(),
+                prop_oneof![
+                    any_with::<Expr>(ArbExprParams {
+                        tpe: SType::SColl(SType::SByte.into()),
+                        depth: 0,
+                    }),
+                    any_with::<Expr>(ArbExprParams {
+                        tpe: SType::SColl(SType::SBoolean.into()),
+                        depth: 0,
+                    }),
+                ],
+                any_with::<Expr>(ArbExprParams {
+                    tpe: SType::SInt,
+                    depth: 0,
+                }),
+            )
+                .prop_map(|(kind, input, shift)| Self::new(kind, input, shift).unwrap())
+                .boxed()
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::panic)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::serialization::SigmaParsingError;
+    use proptest::prelude::*;
+
+    proptest! {
+
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn parsing_rejected(v in any::<CollShift>()) {
+            let op_code = v.op_code();
+            let bytes = Expr::from(v).sigma_serialize_bytes().unwrap();
+            prop_assert_eq![
+                Expr::sigma_parse_bytes(&bytes),
+                Err(SigmaParsingError::InvalidOpCode(op_code.value()))
+            ];
+        }
+    }
+}
@@ -16,6 +16,7 @@ use crate::types::stuple::STuple;
 use crate::types::stuple::TupleItems;
 use crate::types::stype::LiftIntoSType;
 use crate::types::stype::SType;
+use crate::unsignedbigint256::UnsignedBigInt256;
 use ergo_chain_types::ADDigest;
 use ergo_chain_types::Base16DecodedBytes;
 use ergo_chain_types::Digest32;
@@ -66,6 +67,8 @@ pub enum Literal {
     Long(i64),
     /// Big integer
     BigInt(BigInt256),
+    /// Unsigned big integer
+    UnsignedBigInt(UnsignedBigInt256),
     /// Sigma property
     SigmaProp(Box<SigmaProp>),
     /// GroupElement
@@ -110,6 +113,7 @@ impl std::fmt::Debug for Literal {
             Literal::Int(v) => v.fmt(f),
             Literal::Long(v) => v.fmt(f),
             Literal::BigInt(v) => v.fmt(f),
+            Literal::UnsignedBigInt(v) => v.fmt(f),
             Literal::SigmaProp(v) => v.fmt(f),
             Literal::GroupElement(v) => v.fmt(f),
             Literal::AvlTree(v) => v.fmt(f),
@@ -167,6 +171,7 @@ impl std::fmt::Display for Literal {
             Literal::Int(v) => v.fmt(f),
             Literal::Long(v) => write!(f, "{}L", v),
             Literal::BigInt(v) => v.fmt(f),
+            Literal::UnsignedBigInt(v) => v.fmt(f),
             Literal::SigmaProp(v) => v.fmt(f),
             Literal::GroupElement(v) => v.fmt(f),
             Literal::AvlTree(v) => write!(f, "AvlTree({:?})", v),
@@ -217,6 +222,12 @@ impl From<BigInt256> for Literal {
     }
 }
 
+impl From<UnsignedBigInt256> for Literal {
+    fn from(v: UnsignedBigInt256) -> Literal {
+        Literal::UnsignedBigInt(v)
+    }
+}
+
 impl From<SigmaProp> for Literal {
     fn from(v: SigmaProp) -> Literal {
         Literal::SigmaProp(Box::new(v))
@@ -294,6 +305,7 @@ impl TryFrom<Value> for Constant {
             Value::Int(i) => Ok(Constant::from(i)),
             Value::Long(l) => Ok(Constant::from(l)),
             Value::BigInt(b) => Ok(Constant::from(b)),
+            Value::UnsignedBigInt(b) => Ok(Constant::from(b)),
             Value::Unit => Ok(Constant {
                 tpe: SType::SUnit,
                 v: Literal::Unit,
@@ -329,7 +341,7 @@ impl TryFrom<Value> for Constant {
                     let c = Constant::try_from(v)?;
                     Ok(Constant {
                         v: Literal::Opt(Box::new(Some(c.v))),
-                        tpe: c.tpe,
+                        tpe: SType::SOption(Box::new(c.tpe)),
                     })
                 }
                 None => Err("Can't convert from Value::Opt(None) to Constant".into()),
@@ -536,6 +548,15 @@ impl From<BigInt256> for Constant {
     }
 }
 
+impl From<UnsignedBigInt256> for Constant {
+    fn from(b: UnsignedBigInt256) -> Self {
+        Constant {
+            tpe: SType::SUnsignedBigInt,
+            v: Literal::UnsignedBigInt(b),
+        }
+    }
+}
+
 impl From<AvlTreeData> for Constant {
     fn from(a: AvlTreeData) -> Self {
         Constant {
@@ -788,6 +809,19 @@ impl TryExtractFrom<Literal> for Literal {
     }
 }
 
+impl TryExtractFrom<Literal> for UnsignedBigInt256 {
+    fn try_extract_from(v: Literal) -> Result<Self, TryExtractFromError> {
+        match v {
+            Literal::UnsignedBigInt(bi) => Ok(bi),
+            _ => Err(TryExtractFromError(format!(
+                "expected {:?}, found {:?}",
+                std::any::type_name::<Self>(),
+                v
+            ))),
+        }
+    }
+}
+
 impl TryExtractFrom<Literal> for BigInt256 {
     fn try_extract_from(v: Literal) -> Result<Self, TryExtractFromError> {
         match v {
@@ -957,6 +991,7 @@ pub(crate) mod arbitrary {
             SType::SInt => any::<i32>().prop_map_into().boxed(),
             SType::SLong => any::<i64>().prop_map_into().boxed(),
             SType::SBigInt => any::<i64>().prop_map(|v| BigInt256::from(v).into()).boxed(),
+            SType::SUnsignedBigInt => any::<UnsignedBigInt256>().prop_map_into().boxed(),
             SType::SGroupElement => any::<EcPoint>().prop_map_into().boxed(),
             SType::SSigmaProp => any::<SigmaProp>().prop_map_into().boxed(),
             SType::SBox => any::<ErgoBox>().prop_map_into().boxed(),
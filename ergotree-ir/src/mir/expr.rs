@@ -25,6 +25,7 @@ use super::coll_filter::Filter;
 use super::coll_fold::Fold;
 use super::coll_forall::ForAll;
 use super::coll_map::Map;
+use super::coll_shift::CollShift;
 use super::coll_size::SizeOf;
 use super::coll_slice::Slice;
 use super::collection::Collection;
@@ -44,23 +45,29 @@ use super::extract_creation_info::ExtractCreationInfo;
 use super::extract_id::ExtractId;
 use super::extract_reg_as::ExtractRegisterAs;
 use super::extract_script_bytes::ExtractScriptBytes;
+use super::fun_def::FunDef;
 use super::func_value::FuncValue;
 use super::global_vars::GlobalVars;
 use super::if_op::If;
 use super::logical_not::LogicalNot;
 use super::long_to_byte_array::LongToByteArray;
 use super::method_call::MethodCall;
+use super::mod_q::ModQ;
+use super::mod_q::ModQArithOp;
 use super::multiply_group::MultiplyGroup;
 use super::negation::Negation;
 use super::option_get::OptionGet;
 use super::option_get_or_else::OptionGetOrElse;
 use super::option_is_defined::OptionIsDefined;
+use super::option_some::NoneValue;
+use super::option_some::SomeValue;
 use super::or::Or;
 use super::property_call::PropertyCall;
 use super::select_field::SelectField;
 use super::sigma_and::SigmaAnd;
 use super::sigma_or::SigmaOr;
 use super::sigma_prop_bytes::SigmaPropBytes;
+use super::sigma_prop_is_proven::SigmaPropIsProven;
 use super::subst_const::SubstConstants;
 use super::tree_lookup::TreeLookup;
 use super::tuple::Tuple;
@@ -127,6 +134,8 @@ pub enum Expr {
     BlockValue(Spanned<BlockValue>),
     /// let-bound expression
     ValDef(Spanned<ValDef>),
+    /// let-bound polymorphic function
+    FunDef(FunDef),
     /// Reference to ValDef
     ValUse(ValUse),
     /// If, non-lazy - evaluate both branches
@@ -224,6 +233,18 @@ pub enum Expr {
     TreeLookup(Spanned<TreeLookup>),
     /// Create an AVL tree
     CreateAvlTree(CreateAvlTree),
+    /// BigInt value modulo the group order
+    ModQ(ModQ),
+    /// Addition/subtraction of BigInt values modulo the group order
+    ModQArithOp(ModQArithOp),
+    /// Bit-wise shift or rotation of a collection
+    CollShift(CollShift),
+    /// Wrap the value into `Some`
+    SomeValue(SomeValue),
+    /// Empty option
+    NoneValue(NoneValue),
+    /// Check if the SigmaProp value is proven
+    SigmaPropIsProven(SigmaPropIsProven),
 }
 
 impl Expr {
@@ -296,6 +317,13 @@ impl Expr {
             Expr::ExtractBytesWithNoRef(v) => v.tpe(),
             Expr::TreeLookup(v) => v.expr().tpe(),
             Expr::CreateAvlTree(v) => v.tpe(),
+            Expr::FunDef(v) => v.tpe(),
+            Expr::ModQ(v) => v.tpe(),
+            Expr::ModQArithOp(v) => v.tpe(),
+            Expr::CollShift(v) => v.tpe(),
+            Expr::SomeValue(v) => v.tpe(),
+            Expr::NoneValue(v) => v.tpe(),
+            Expr::SigmaPropIsProven(v) => v.tpe(),
         }
     }
 
@@ -0,0 +1,121 @@
+use crate::has_opcode::HasStaticOpCode;
+use crate::serialization::op_code::OpCode;
+use crate::serialization::sigma_byte_reader::SigmaByteRead;
+use crate::serialization::sigma_byte_writer::SigmaByteWrite;
+use crate::serialization::SigmaParsingError;
+use crate::serialization::SigmaSerializable;
+use crate::serialization::SigmaSerializeResult;
+use crate::types::stype::SType;
+use crate::types::stype_param::STypeVar;
+
+use super::expr::Expr;
+use super::val_def::ValId;
+
+/** IR node for let-bound polymorphic functions `def f[T](...) = rhs` which is FunDef.
+ * Behaves as a ValDef with the type arguments of the function.
+ * @param id unique identifier of the variable in the current scope.
+ * @param tpe_args type arguments of the function */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct FunDef {
+    /// Variable id
+    pub id: ValId,
+    /// Type arguments of the function
+    pub tpe_args: Vec<STypeVar>,
+    /// Expr, bound to the variable
+    pub rhs: Box<Expr>,
+}
+
+impl FunDef {
+    /// Type
+    pub fn tpe(&self) -> SType {
+        self.rhs.tpe()
+    }
+}
+
+impl HasStaticOpCode for FunDef {
+    const OP_CODE: OpCode = OpCode::FUN_DEF;
+}
+
+impl SigmaSerializable for FunDef {
+    fn sigma_serialize<W: SigmaByteWrite>(&self, w: &mut W) -> SigmaSerializeResult {
+        self.id.sigma_serialize(w)?;
+        w.put_u8(self.tpe_args.len() as u8)?;
+        for tpe_arg in &self.tpe_args {
+            SType::STypeVar(tpe_arg.clone()).sigma_serialize(w)?;
+        }
+        self.rhs.sigma_serialize(w)
+    }
+
+    fn sigma_parse<R: SigmaByteRead>(r: &mut R) -> Result<Self, SigmaParsingError> {
+        let id = ValId::sigma_parse(r)?;
+        let tpe_args_count = r.get_u8()?;
+        let mut tpe_args = Vec::with_capacity(tpe_args_count as usize);
+        for _ in 0..tpe_args_count {
+            match SType::sigma_parse(r)? {
+                SType::STypeVar(tpe_arg) => tpe_args.push(tpe_arg),
+                tpe => {
+                    return Err(SigmaParsingError::Misc(format!(
+                        "FunDef: expected type argument to be a type variable, got {:?}",
+                        tpe
+                    )))
+                }
+            }
+        }
+        let rhs = Expr::sigma_parse(r)?;
+        r.val_def_type_store().insert(id, rhs.tpe());
+        Ok(FunDef {
+            id,
+            tpe_args,
+            rhs: Box::new(rhs),
+        })
+    }
+}
+
+/// Arbitrary impl
+#[cfg(feature = "arbitrary")]
+mod arbitrary {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    impl Arbitrary for FunDef {
+        type Strategy = BoxedStrategy<Self>;
+        type Parameters = ();
+
+        fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+            (
+                any::<ValId>(),
+                vec(prop_oneof![Just(STypeVar::t()), Just(STypeVar::iv())], 0..3),
+                any::<Expr>(),
+            )
+                .prop_map(|(id, tpe_args, rhs)| Self {
+                    id,
+                    tpe_args,
+                    rhs: Box::new(rhs),
+                })
+                .boxed()
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::panic)]
+mod tests {
+    use crate::serialization::sigma_serialize_roundtrip;
+
+    use super::*;
+
+    use proptest::prelude::*;
+
+    proptest! {
+
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn ser_roundtrip(v in any::<FunDef>()) {
+            let e = Expr::FunDef(v);
+            prop_assert_eq![sigma_serialize_roundtrip(&e), e];
+        }
+    }
+}
@@ -80,6 +80,11 @@ impl FuncValue {
         &self.body
     }
 
+    /// Mutable function body (the rewrite must keep its type)
+    pub(crate) fn body_mut(&mut self) -> &mut Expr {
+        &mut self.body
+    }
+
     /// Type
     pub fn tpe(&self) -> SType {
         self.tpe.clone()
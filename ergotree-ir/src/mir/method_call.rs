@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+
 use crate::serialization::op_code::OpCode;
 use crate::types::smethod::SMethod;
 use crate::types::stype::SType;
+use crate::types::stype_param::STypeVar;
 
 use super::expr::Expr;
 use super::expr::InvalidArgumentError;
@@ -24,6 +27,9 @@ pub struct MethodCall {
     pub method: SMethod,
     /// Arguments passed to the method on invocation
     pub args: Vec<Expr>,
+    /// Types of the method type parameters which cannot be inferred from the arguments
+    /// (see [`SMethod::explicit_type_args`])
+    pub explicit_type_args: HashMap<STypeVar, SType>,
 }
 
 impl MethodCall {
@@ -53,6 +59,32 @@ impl MethodCall {
             obj: obj.into(),
             method,
             args,
+            explicit_type_args: HashMap::new(),
+        })
+    }
+
+    /// Set the types of the explicit type parameters of the method, returns an error if any of
+    /// them is missing
+    pub fn with_explicit_type_args(
+        self,
+        explicit_type_args: HashMap<STypeVar, SType>,
+    ) -> Result<Self, InvalidArgumentError> {
+        if let Some(missing) = self
+            .method
+            .explicit_type_args()
+            .iter()
+            .find(|tpe_var| !explicit_type_args.contains_key(tpe_var))
+        {
+            return Err(InvalidArgumentError(format!(
+                "MethodCall: missing explicit type argument {:?} for method {}",
+                missing,
+                self.method.name()
+            )));
+        }
+        Ok(Self {
+            method: self.method.with_concrete_types(&explicit_type_args),
+            explicit_type_args,
+            ..self
         })
     }
 
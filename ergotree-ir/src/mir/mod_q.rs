@@ -0,0 +1,204 @@
+//! Modular arithmetic with the order of the group (secp256k1 curve order `q`)
+
+use std::fmt::Display;
+
+use super::expr::Expr;
+use super::expr::InvalidArgumentError;
+use super::unary_op::OneArgOp;
+use super::unary_op::OneArgOpTryBuild;
+use crate::has_opcode::HasOpCode;
+use crate::has_opcode::HasStaticOpCode;
+use crate::serialization::op_code::OpCode;
+use crate::serialization::sigma_byte_reader::SigmaByteRead;
+use crate::serialization::sigma_byte_writer::SigmaByteWrite;
+use crate::serialization::SigmaParsingError;
+use crate::serialization::SigmaSerializable;
+use crate::serialization::SigmaSerializeResult;
+use crate::types::stype::SType;
+
+#[cfg(feature = "arbitrary")]
+use proptest_derive::Arbitrary;
+
+/// Returns the BigInt value modulo the group order `q`
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ModQ {
+    /// Input expr of BigInt type
+    pub input: Box<Expr>,
+}
+
+impl ModQ {
+    /// Type
+    pub fn tpe(&self) -> SType {
+        SType::SBigInt
+    }
+}
+
+impl HasStaticOpCode for ModQ {
+    const OP_CODE: OpCode = OpCode::MOD_Q;
+}
+
+impl OneArgOp for ModQ {
+    fn input(&self) -> &Expr {
+        &self.input
+    }
+}
+
+impl OneArgOpTryBuild for ModQ {
+    fn try_build(input: Expr) -> Result<Self, InvalidArgumentError> {
+        input.check_post_eval_tpe(&SType::SBigInt)?;
+        Ok(Self {
+            input: input.into(),
+        })
+    }
+}
+
+/// Kind of the arithmetic operation modulo the group order `q`
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum ModQArithOpKind {
+    /// Addition modulo `q`
+    Plus,
+    /// Subtraction modulo `q`
+    Minus,
+}
+
+impl From<ModQArithOpKind> for OpCode {
+    fn from(kind: ModQArithOpKind) -> Self {
+        match kind {
+            ModQArithOpKind::Plus => OpCode::PLUS_MOD_Q,
+            ModQArithOpKind::Minus => OpCode::MINUS_MOD_Q,
+        }
+    }
+}
+
+impl Display for ModQArithOpKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModQArithOpKind::Plus => write!(f, "plusModQ"),
+            ModQArithOpKind::Minus => write!(f, "minusModQ"),
+        }
+    }
+}
+
+/// Addition or subtraction of two BigInt values modulo the group order `q`
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ModQArithOp {
+    /// Operation kind
+    pub kind: ModQArithOpKind,
+    /// Left operand of BigInt type
+    pub left: Box<Expr>,
+    /// Right operand of BigInt type
+    pub right: Box<Expr>,
+}
+
+impl ModQArithOp {
+    /// Create new object, returns an error if any of the operands is not of BigInt type
+    pub fn new(
+        kind: ModQArithOpKind,
+        left: Expr,
+        right: Expr,
+    ) -> Result<Self, InvalidArgumentError> {
+        left.check_post_eval_tpe(&SType::SBigInt)?;
+        right.check_post_eval_tpe(&SType::SBigInt)?;
+        Ok(ModQArithOp {
+            kind,
+            left: left.into(),
+            right: right.into(),
+        })
+    }
+
+    /// Type
+    pub fn tpe(&self) -> SType {
+        SType::SBigInt
+    }
+
+    pub(crate) fn sigma_parse_with_kind<R: SigmaByteRead>(
+        kind: ModQArithOpKind,
+        r: &mut R,
+    ) -> Result<Self, SigmaParsingError> {
+        let left = Expr::sigma_parse(r)?;
+        let right = Expr::sigma_parse(r)?;
+        Ok(ModQArithOp::new(kind, left, right)?)
+    }
+
+    pub(crate) fn sigma_serialize<W: SigmaByteWrite>(&self, w: &mut W) -> SigmaSerializeResult {
+        self.left.sigma_serialize(w)?;
+        self.right.sigma_serialize(w)
+    }
+}
+
+impl HasOpCode for ModQArithOp {
+    fn op_code(&self) -> OpCode {
+        self.kind.into()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod arbitrary {
+    use crate::mir::expr::arbitrary::ArbExprParams;
+
+    use super::*;
+    use proptest::prelude::*;
+
+    impl Arbitrary for ModQ {
+        type Strategy = BoxedStrategy<Self>;
+        type Parameters = ();
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            any_with::<Expr>(ArbExprParams {
+                tpe: SType::SBigInt,
+                depth: 0,
+            })
+            .prop_map(|input| Self::try_build(input).unwrap())
+            .boxed()
+        }
+    }
+
+    impl Arbitrary for ModQArithOp {
+        type Strategy = BoxedStrategy<Self>;
+        type Parameters = ();
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            (
+                any::<ModQArithOpKind>(),
+                any_with::<Expr>(ArbExprParams {
+                    tpe: SType::SBigInt,
+                    depth: 0,
+                }),
+                any_with::<Expr>(ArbExprParams {
+                    tpe: SType::SBigInt,
+                    depth: 0,
+                }),
+            )
+                .prop_map(|(kind, left, right)| Self::new(kind, left, right).unwrap())
+                .boxed()
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::panic)]
+mod tests {
+    use super::*;
+    use crate::serialization::sigma_serialize_roundtrip;
+    use proptest::prelude::*;
+
+    proptest! {
+
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn ser_roundtrip_mod_q(v in any::<ModQ>()) {
+            let expr: Expr = v.into();
+            prop_assert_eq![sigma_serialize_roundtrip(&expr), expr];
+        }
+
+        #[test]
+        fn ser_roundtrip_mod_q_arith_op(v in any::<ModQArithOp>()) {
+            let expr: Expr = v.into();
+            prop_assert_eq![sigma_serialize_roundtrip(&expr), expr];
+        }
+    }
+}
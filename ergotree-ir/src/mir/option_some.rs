@@ -0,0 +1,134 @@
+//! Option value constructors (`Some(x)` and `None`)
+
+use crate::has_opcode::HasStaticOpCode;
+use crate::serialization::op_code::OpCode;
+use crate::serialization::sigma_byte_reader::SigmaByteRead;
+use crate::serialization::sigma_byte_writer::SigmaByteWrite;
+use crate::serialization::SigmaParsingError;
+use crate::serialization::SigmaSerializable;
+use crate::serialization::SigmaSerializeResult;
+use crate::types::stype::SType;
+
+use super::expr::Expr;
+use super::expr::InvalidArgumentError;
+use super::unary_op::OneArgOp;
+use super::unary_op::OneArgOpTryBuild;
+
+/// Wraps the value into `Some`
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SomeValue {
+    /// Value to wrap
+    pub input: Box<Expr>,
+}
+
+impl SomeValue {
+    /// Type
+    pub fn tpe(&self) -> SType {
+        SType::SOption(self.input.post_eval_tpe().into())
+    }
+}
+
+impl HasStaticOpCode for SomeValue {
+    const OP_CODE: OpCode = OpCode::SOME_VALUE;
+}
+
+impl OneArgOp for SomeValue {
+    fn input(&self) -> &Expr {
+        &self.input
+    }
+}
+
+impl OneArgOpTryBuild for SomeValue {
+    fn try_build(input: Expr) -> Result<Self, InvalidArgumentError> {
+        Ok(SomeValue {
+            input: input.into(),
+        })
+    }
+}
+
+/// Empty option (`None`) of the given element type
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct NoneValue {
+    /// Option element type
+    pub elem_tpe: SType,
+}
+
+impl NoneValue {
+    /// Type
+    pub fn tpe(&self) -> SType {
+        SType::SOption(self.elem_tpe.clone().into())
+    }
+}
+
+impl HasStaticOpCode for NoneValue {
+    const OP_CODE: OpCode = OpCode::NONE_VALUE;
+}
+
+impl SigmaSerializable for NoneValue {
+    fn sigma_serialize<W: SigmaByteWrite>(&self, w: &mut W) -> SigmaSerializeResult {
+        self.elem_tpe.sigma_serialize(w)
+    }
+
+    fn sigma_parse<R: SigmaByteRead>(r: &mut R) -> Result<Self, SigmaParsingError> {
+        let elem_tpe = SType::sigma_parse(r)?;
+        Ok(NoneValue { elem_tpe })
+    }
+}
+
+/// Arbitrary impl
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::unwrap_used)]
+mod arbitrary {
+    use crate::mir::constant::Constant;
+
+    use super::*;
+    use proptest::prelude::*;
+
+    impl Arbitrary for SomeValue {
+        type Strategy = BoxedStrategy<Self>;
+        type Parameters = ();
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            any::<Constant>()
+                .prop_map(|c| Self::try_build(c.into()).unwrap())
+                .boxed()
+        }
+    }
+
+    impl Arbitrary for NoneValue {
+        type Strategy = BoxedStrategy<Self>;
+        type Parameters = ();
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            any::<SType>()
+                .prop_map(|elem_tpe| Self { elem_tpe })
+                .boxed()
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+#[allow(clippy::panic)]
+mod tests {
+    use super::*;
+    use crate::serialization::sigma_serialize_roundtrip;
+    use proptest::prelude::*;
+
+    proptest! {
+
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn ser_roundtrip_some(v in any::<SomeValue>()) {
+            let expr: Expr = v.into();
+            prop_assert_eq![sigma_serialize_roundtrip(&expr), expr];
+        }
+
+        #[test]
+        fn ser_roundtrip_none(v in any::<NoneValue>()) {
+            let expr: Expr = v.into();
+            prop_assert_eq![sigma_serialize_roundtrip(&expr), expr];
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Traversal of the expression tree (children, descendants, bottom-up rewriting)
+
+use crate::mir::collection::Collection;
+use crate::mir::expr::Expr;
+
+macro_rules! for_each_child {
+    ($expr:expr, $f:ident, $iter:ident, $body:ident, $($mutability:tt)?) => {
+        match $expr {
+            Expr::Const(_)
+            | Expr::ConstPlaceholder(_)
+            | Expr::Context
+            | Expr::Global
+            | Expr::GlobalVars(_)
+            | Expr::ValUse(_)
+            | Expr::GetVar(_)
+            | Expr::DeserializeContext(_)
+            | Expr::NoneValue(_)
+            | Expr::Collection(Collection::BoolConstants(_)) => (),
+            Expr::Append(op) => {
+                $f(&$($mutability)? *op.expr.input);
+                $f(&$($mutability)? *op.expr.col_2);
+            }
+            Expr::SubstConstants(op) => {
+                $f(&$($mutability)? *op.expr.script_bytes);
+                $f(&$($mutability)? *op.expr.positions);
+                $f(&$($mutability)? *op.expr.new_values);
+            }
+            Expr::ByteArrayToLong(op) => $f(&$($mutability)? *op.expr.input),
+            Expr::ByteArrayToBigInt(op) => $f(&$($mutability)? *op.expr.input),
+            Expr::LongToByteArray(op) => $f(&$($mutability)? *op.input),
+            Expr::Collection(Collection::Exprs { items, .. }) => items.$iter().for_each(&mut *$f),
+            Expr::Tuple(op) => op.items.$iter().for_each(&mut *$f),
+            Expr::CalcBlake2b256(op) => $f(&$($mutability)? *op.input),
+            Expr::CalcSha256(op) => $f(&$($mutability)? *op.input),
+            Expr::FuncValue(op) => $f(op.$body()),
+            Expr::Apply(op) => {
+                $f(&$($mutability)? *op.func);
+                op.args.$iter().for_each(&mut *$f);
+            }
+            Expr::MethodCall(op) => {
+                $f(&$($mutability)? *op.expr.obj);
+                op.expr.args.$iter().for_each(&mut *$f);
+            }
+            Expr::PropertyCall(op) => $f(&$($mutability)? *op.expr.obj),
+            Expr::BlockValue(op) => {
+                op.expr.items.$iter().for_each(&mut *$f);
+                $f(&$($mutability)? *op.expr.result);
+            }
+            Expr::ValDef(op) => $f(&$($mutability)? *op.expr.rhs),
+            Expr::FunDef(op) => $f(&$($mutability)? *op.rhs),
+            Expr::If(op) => {
+                $f(&$($mutability)? *op.condition);
+                $f(&$($mutability)? *op.true_branch);
+                $f(&$($mutability)? *op.false_branch);
+            }
+            Expr::BinOp(op) => {
+                $f(&$($mutability)? *op.expr.left);
+                $f(&$($mutability)? *op.expr.right);
+            }
+            Expr::And(op) => $f(&$($mutability)? *op.expr.input),
+            Expr::Or(op) => $f(&$($mutability)? *op.expr.input),
+            Expr::Xor(op) => {
+                $f(&$($mutability)? *op.left);
+                $f(&$($mutability)? *op.right);
+            }
+            Expr::Atleast(op) => {
+                $f(&$($mutability)? *op.bound);
+                $f(&$($mutability)? *op.input);
+            }
+            Expr::LogicalNot(op) => $f(&$($mutability)? *op.expr.input),
+            Expr::Negation(op) => $f(&$($mutability)? *op.expr.input),
+            Expr::BitInversion(op) => $f(&$($mutability)? *op.input),
+            Expr::OptionGet(op) => $f(&$($mutability)? *op.expr.input),
+            Expr::OptionIsDefined(op) => $f(&$($mutability)? *op.expr.input),
+            Expr::OptionGetOrElse(op) => {
+                $f(&$($mutability)? *op.expr.input);
+                $f(&$($mutability)? *op.expr.default);
+            }
+            Expr::ExtractAmount(op) => $f(&$($mutability)? *op.input),
+            Expr::ExtractRegisterAs(op) => $f(&$($mutability)? *op.expr.input),
+            Expr::ExtractBytes(op) => $f(&$($mutability)? *op.input),
+            Expr::ExtractBytesWithNoRef(op) => $f(&$($mutability)? *op.input),
+            Expr::ExtractScriptBytes(op) => $f(&$($mutability)? *op.input),
+            Expr::ExtractCreationInfo(op) => $f(&$($mutability)? *op.input),
+            Expr::ExtractId(op) => $f(&$($mutability)? *op.input),
+            Expr::ByIndex(op) => {
+                $f(&$($mutability)? *op.expr.input);
+                $f(&$($mutability)? *op.expr.index);
+                if let Some(default) = &$($mutability)? op.expr.default {
+                    $f(&$($mutability)? **default);
+                }
+            }
+            Expr::SizeOf(op) => $f(&$($mutability)? *op.input),
+            Expr::Slice(op) => {
+                $f(&$($mutability)? *op.expr.input);
+                $f(&$($mutability)? *op.expr.from);
+                $f(&$($mutability)? *op.expr.until);
+            }
+            Expr::Fold(op) => {
+                $f(&$($mutability)? *op.expr.input);
+                $f(&$($mutability)? *op.expr.zero);
+                $f(&$($mutability)? *op.expr.fold_op);
+            }
+            Expr::Map(op) => {
+                $f(&$($mutability)? *op.expr.input);
+                $f(&$($mutability)? *op.expr.mapper);
+            }
+            Expr::Filter(op) => {
+                $f(&$($mutability)? *op.expr.input);
+                $f(&$($mutability)? *op.expr.condition);
+            }
+            Expr::Exists(op) => {
+                $f(&$($mutability)? *op.expr.input);
+                $f(&$($mutability)? *op.expr.condition);
+            }
+            Expr::ForAll(op) => {
+                $f(&$($mutability)? *op.expr.input);
+                $f(&$($mutability)? *op.expr.condition);
+            }
+            Expr::SelectField(op) => $f(&$($mutability)? *op.expr.input),
+            Expr::BoolToSigmaProp(op) => $f(&$($mutability)? *op.input),
+            Expr::Upcast(op) => $f(&$($mutability)? *op.input),
+            Expr::Downcast(op) => $f(&$($mutability)? *op.input),
+            Expr::CreateProveDlog(op) => $f(&$($mutability)? *op.input),
+            Expr::CreateProveDhTuple(op) => {
+                $f(&$($mutability)? *op.g);
+                $f(&$($mutability)? *op.h);
+                $f(&$($mutability)? *op.u);
+                $f(&$($mutability)? *op.v);
+            }
+            Expr::SigmaPropBytes(op) => $f(&$($mutability)? *op.input),
+            Expr::DecodePoint(op) => $f(&$($mutability)? *op.input),
+            Expr::SigmaAnd(op) => op.items.$iter().for_each(&mut *$f),
+            Expr::SigmaOr(op) => op.items.$iter().for_each(&mut *$f),
+            Expr::DeserializeRegister(op) => {
+                if let Some(default) = &$($mutability)? op.default {
+                    $f(&$($mutability)? **default);
+                }
+            }
+            Expr::MultiplyGroup(op) => {
+                $f(&$($mutability)? *op.left);
+                $f(&$($mutability)? *op.right);
+            }
+            Expr::Exponentiate(op) => {
+                $f(&$($mutability)? *op.left);
+                $f(&$($mutability)? *op.right);
+            }
+            Expr::XorOf(op) => $f(&$($mutability)? *op.input),
+            Expr::TreeLookup(op) => {
+                $f(&$($mutability)? *op.expr.tree);
+                $f(&$($mutability)? *op.expr.key);
+                $f(&$($mutability)? *op.expr.proof);
+            }
+            Expr::CreateAvlTree(op) => {
+                $f(&$($mutability)? *op.flags);
+                $f(&$($mutability)? *op.digest);
+                $f(&$($mutability)? *op.key_length);
+                if let Some(value_length) = &$($mutability)? op.value_length {
+                    $f(&$($mutability)? **value_length);
+                }
+            }
+            Expr::ModQ(op) => $f(&$($mutability)? *op.input),
+            Expr::ModQArithOp(op) => {
+                $f(&$($mutability)? *op.left);
+                $f(&$($mutability)? *op.right);
+            }
+            Expr::CollShift(op) => {
+                $f(&$($mutability)? *op.input);
+                $f(&$($mutability)? *op.shift);
+            }
+            Expr::SomeValue(op) => $f(&$($mutability)? *op.input),
+            Expr::SigmaPropIsProven(op) => $f(&$($mutability)? *op.input),
+        }
+    };
+}
+
+/// Calls `f` on each direct child of the expression
+pub fn for_each_child<'a>(expr: &'a Expr, f: &mut dyn FnMut(&'a Expr)) {
+    for_each_child!(expr, f, iter, body,)
+}
+
+/// Calls `f` on each direct child of the expression, the children may be rewritten (keeping
+/// their types)
+pub(crate) fn for_each_child_mut(expr: &mut Expr, f: &mut dyn FnMut(&mut Expr)) {
+    for_each_child!(expr, f, iter_mut, body_mut, mut)
+}
+
+/// Calls `f` on the expression and all its descendants (pre-order)
+pub fn for_each_descendant<'a>(expr: &'a Expr, f: &mut dyn FnMut(&'a Expr)) {
+    f(expr);
+    for_each_child(expr, &mut |child| for_each_descendant(child, f));
+}
+
+/// Rewrites the expression bottom-up, `f` is called on each node after its children
+pub(crate) fn rewrite_bottom_up(expr: &mut Expr, f: &mut dyn FnMut(&mut Expr)) {
+    for_each_child_mut(expr, &mut |child| rewrite_bottom_up(child, f));
+    f(expr);
+}
@@ -13,6 +13,7 @@ use crate::sigma_protocol::sigma_boolean::SigmaProp;
 use crate::types::stuple::TupleItems;
 use crate::types::stype::LiftIntoSType;
 use crate::types::stype::SType;
+use crate::unsignedbigint256::UnsignedBigInt256;
 use ergo_chain_types::{EcPoint, Header, PreHeader};
 
 use super::avl_tree_data::AvlTreeData;
@@ -108,6 +109,19 @@ where
         }
     }
 
+    /// Number of items in the collection
+    pub fn len(&self) -> usize {
+        match self {
+            CollKind::NativeColl(NativeColl::CollByte(coll_byte)) => coll_byte.len(),
+            CollKind::WrappedColl { items, .. } => items.len(),
+        }
+    }
+
+    /// Returns true if the collection has no items
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Return items, as vector of Values
     pub fn as_vec(&self) -> Vec<T> {
         match self {
@@ -150,6 +164,8 @@ pub enum Value {
     Unit,
     /// Big integer
     BigInt(BigInt256),
+    /// Unsigned big integer
+    UnsignedBigInt(UnsignedBigInt256),
     /// GroupElement
     GroupElement(Box<EcPoint>),
     /// Sigma property
@@ -222,6 +238,7 @@ impl From<Literal> for Value {
             Literal::Int(i) => Value::Int(i),
             Literal::Long(l) => Value::Long(l),
             Literal::BigInt(b) => Value::BigInt(b),
+            Literal::UnsignedBigInt(b) => Value::UnsignedBigInt(b),
             Literal::Unit => Value::Unit,
             Literal::SigmaProp(s) => Value::SigmaProp(s),
             Literal::GroupElement(e) => Value::GroupElement(e),
@@ -292,6 +309,7 @@ impl std::fmt::Display for Value {
             Value::Int(v) => v.fmt(f),
             Value::Long(v) => write!(f, "{}L", v),
             Value::BigInt(v) => v.fmt(f),
+            Value::UnsignedBigInt(v) => v.fmt(f),
             Value::SigmaProp(v) => v.fmt(f),
             Value::GroupElement(v) => v.fmt(f),
             Value::AvlTree(v) => write!(f, "AvlTree({:?})", v),
@@ -313,6 +331,7 @@ impl StoreWrapped for i16 {}
 impl StoreWrapped for i32 {}
 impl StoreWrapped for i64 {}
 impl StoreWrapped for BigInt256 {}
+impl StoreWrapped for UnsignedBigInt256 {}
 impl StoreWrapped for Header {}
 impl StoreWrapped for Arc<ErgoBox> {}
 impl StoreWrapped for EcPoint {}
@@ -560,6 +579,19 @@ impl TryExtractFrom<Value> for BigInt256 {
     }
 }
 
+impl TryExtractFrom<Value> for UnsignedBigInt256 {
+    fn try_extract_from(v: Value) -> Result<Self, TryExtractFromError> {
+        match v {
+            Value::UnsignedBigInt(bi) => Ok(bi),
+            _ => Err(TryExtractFromError(format!(
+                "expected {:?}, found {:?}",
+                std::any::type_name::<Self>(),
+                v
+            ))),
+        }
+    }
+}
+
 impl TryExtractFrom<Value> for AvlTreeData {
     fn try_extract_from(v: Value) -> Result<Self, TryExtractFromError> {
         match v {
@@ -0,0 +1,289 @@
+//! Optimization of the expression tree before it is serialized into [`crate::ergo_tree::ErgoTree`]
+//!
+//! The passes keep the result of the evaluation, and a script that fails keeps failing; the
+//! evaluation cost and the serialized size never grow:
+//! - constant folding (arithmetic operations that would overflow or divide by zero are kept);
+//! - inlining of the vals used once (only where they are evaluated exactly once);
+//! - elimination of the unused vals whose evaluation cannot fail;
+//! - extraction of the repeated subexpressions into vals.
+
+mod cse;
+mod fold;
+mod vals;
+
+use crate::mir::expr::Expr;
+use crate::mir::traverse::for_each_descendant;
+use crate::serialization::SigmaSerializable;
+
+use self::cse::eliminate_common_subexprs;
+use self::fold::fold_constants;
+use self::vals::has_unique_ids;
+use self::vals::inline_vals;
+
+/// Maximum number of the folding and inlining rounds
+const MAX_ROUNDS: usize = 16;
+
+/// Optimizes the expression. The vals are inlined and extracted only if each val and lambda
+/// argument id is defined once (which holds for the compiled trees).
+pub fn optimize(expr: Expr) -> Expr {
+    let mut optimized = expr.clone();
+    let rewrite_vals = has_unique_ids(&optimized);
+    for _ in 0..MAX_ROUNDS {
+        let folded = fold_constants(&mut optimized);
+        let inlined = rewrite_vals && inline_vals(&mut optimized);
+        if !folded && !inlined {
+            break;
+        }
+    }
+    if rewrite_vals {
+        let mut next_id = max_id(&optimized).map_or(0, |id| id + 1);
+        eliminate_common_subexprs(&mut optimized, &mut next_id);
+    }
+    // a folded constant can be serialized longer than the operation on the smaller ones
+    match (
+        expr.sigma_serialize_bytes(),
+        optimized.sigma_serialize_bytes(),
+    ) {
+        (Ok(original), Ok(new)) if new.len() > original.len() => expr,
+        _ => optimized,
+    }
+}
+
+/// Maximum val or lambda argument id
+fn max_id(expr: &Expr) -> Option<u32> {
+    let mut max: Option<u32> = None;
+    for_each_descendant(expr, &mut |e| {
+        let ids: Vec<u32> = match e {
+            Expr::ValDef(val_def) => vec![val_def.expr.id.0],
+            Expr::FunDef(fun_def) => vec![fun_def.id.0],
+            Expr::ValUse(val_use) => vec![val_use.val_id.0],
+            Expr::FuncValue(func) => func.args().iter().map(|arg| arg.idx.0).collect(),
+            _ => Vec::new(),
+        };
+        max = ids.into_iter().chain(max).max();
+    });
+    max
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::mir::bin_op::ArithOp;
+    use crate::mir::bin_op::BinOp;
+    use crate::mir::bin_op::LogicalOp;
+    use crate::mir::bin_op::RelationOp;
+    use crate::mir::block::BlockValue;
+    use crate::mir::coll_by_index::ByIndex;
+    use crate::mir::constant::Constant;
+    use crate::mir::extract_amount::ExtractAmount;
+    use crate::mir::extract_script_bytes::ExtractScriptBytes;
+    use crate::mir::global_vars::GlobalVars;
+    use crate::mir::if_op::If;
+    use crate::mir::val_def::ValDef;
+    use crate::mir::val_def::ValId;
+    use crate::mir::val_use::ValUse;
+    use crate::types::stype::SType;
+    use expect_test::expect;
+
+    fn bin_op(kind: impl Into<crate::mir::bin_op::BinOpKind>, left: Expr, right: Expr) -> Expr {
+        BinOp {
+            kind: kind.into(),
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+        .into()
+    }
+
+    fn height() -> Expr {
+        GlobalVars::Height.into()
+    }
+
+    fn val_def(id: u32, rhs: Expr) -> Expr {
+        ValDef {
+            id: ValId(id),
+            rhs: Box::new(rhs),
+        }
+        .into()
+    }
+
+    fn val_use(id: u32, tpe: SType) -> Expr {
+        ValUse {
+            val_id: ValId(id),
+            tpe,
+        }
+        .into()
+    }
+
+    fn block(items: Vec<Expr>, result: Expr) -> Expr {
+        BlockValue {
+            items,
+            result: Box::new(result),
+        }
+        .into()
+    }
+
+    fn output_value(idx: i32) -> Expr {
+        ExtractAmount {
+            input: Box::new(output(idx)),
+        }
+        .into()
+    }
+
+    fn output(idx: i32) -> Expr {
+        ByIndex::new(GlobalVars::Outputs.into(), idx.into(), None)
+            .unwrap()
+            .into()
+    }
+
+    fn if_op(condition: Expr, true_branch: Expr, false_branch: Expr) -> Expr {
+        If {
+            condition: Box::new(condition),
+            true_branch: Box::new(true_branch),
+            false_branch: Box::new(false_branch),
+        }
+        .into()
+    }
+
+    fn script_bytes(input: Expr) -> Expr {
+        ExtractScriptBytes {
+            input: Box::new(input),
+        }
+        .into()
+    }
+
+    fn check(expr: Expr, expected: expect_test::Expect) {
+        let optimized = optimize(expr.clone());
+        assert!(
+            optimized.sigma_serialize_bytes().unwrap().len()
+                <= expr.sigma_serialize_bytes().unwrap().len()
+        );
+        expected.assert_eq(&optimized.to_string_pretty());
+    }
+
+    #[test]
+    fn fold_arithmetic_and_comparison() {
+        let sum = bin_op(ArithOp::Plus, 1i32.into(), 2i32.into());
+        let product = bin_op(ArithOp::Multiply, sum, 3i32.into());
+        check(
+            bin_op(RelationOp::Lt, product.clone(), height()),
+            expect!["9 < HEIGHT"],
+        );
+        check(
+            bin_op(RelationOp::Lt, product, 10i32.into()),
+            expect!["true"],
+        );
+    }
+
+    #[test]
+    fn keep_failing_arithmetic() {
+        check(
+            bin_op(ArithOp::Plus, i32::MAX.into(), 1i32.into()),
+            expect!["2147483647 + 1"],
+        );
+        check(
+            bin_op(ArithOp::Divide, 1i64.into(), 0i64.into()),
+            expect!["1 / 0"],
+        );
+    }
+
+    #[test]
+    fn fold_logical_operations() {
+        let cond = bin_op(RelationOp::Gt, height(), 1i32.into());
+        check(
+            bin_op(LogicalOp::And, true.into(), cond.clone()),
+            expect!["HEIGHT > 1"],
+        );
+        check(
+            bin_op(LogicalOp::Or, true.into(), cond.clone()),
+            expect!["true"],
+        );
+        // the left operand is evaluated anyway
+        check(
+            bin_op(LogicalOp::And, cond.clone(), false.into()),
+            expect!["HEIGHT > 1 && false"],
+        );
+        check(
+            if_op(true.into(), cond, false.into()),
+            expect!["HEIGHT > 1"],
+        );
+    }
+
+    #[test]
+    fn inline_single_use_val() {
+        let rhs = bin_op(ArithOp::Plus, height(), 1i32.into());
+        check(
+            block(
+                vec![val_def(1, rhs)],
+                bin_op(RelationOp::Gt, val_use(1, SType::SInt), 2i32.into()),
+            ),
+            expect!["HEIGHT + 1 > 2"],
+        );
+    }
+
+    #[test]
+    fn keep_val_used_in_branch() {
+        // `HEIGHT + 1` can overflow, it must be evaluated even if the branch isn't taken
+        let rhs = bin_op(ArithOp::Plus, height(), 1i32.into());
+        let cond = bin_op(RelationOp::Gt, height(), 5i32.into());
+        let then_branch = bin_op(RelationOp::Gt, val_use(1, SType::SInt), 2i32.into());
+        check(
+            block(
+                vec![val_def(1, rhs)],
+                if_op(cond, then_branch, false.into()),
+            ),
+            expect![[r#"
+                {
+                  val v1 = HEIGHT + 1
+                  if (HEIGHT > 5) v1 > 2 else false
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn remove_unused_vals() {
+        let unused = bin_op(RelationOp::Gt, height(), 1i32.into());
+        // fails if there is no such output
+        let may_fail = output_value(5);
+        check(
+            block(
+                vec![val_def(1, unused), val_def(2, may_fail)],
+                bin_op(RelationOp::Gt, height(), 0i32.into()),
+            ),
+            expect![[r#"
+                {
+                  val v2 = OUTPUTS(5).value
+                  HEIGHT > 0
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn extract_common_subexpressions() {
+        let script: Expr = Constant::from(vec![7u8; 32]).into();
+        let self_script = script_bytes(GlobalVars::SelfBox.into());
+        let first = bin_op(RelationOp::Eq, self_script, script.clone());
+        let value_sum = bin_op(ArithOp::Plus, output_value(0), output_value(1));
+        let second = bin_op(
+            RelationOp::Gt,
+            value_sum.clone(),
+            bin_op(ArithOp::Multiply, value_sum, 2i64.into()),
+        );
+        let third = bin_op(RelationOp::Eq, script_bytes(output(0)), script);
+        // the sum is evaluated first and is extracted; the constant is not extracted (its val
+        // uses would cost as much as the constant) and neither is `OUTPUTS(0)` (the right operand
+        // of `&&` is evaluated only if the left one is true, so it is repeated only once)
+        let cond = bin_op(LogicalOp::And, second, bin_op(RelationOp::Eq, first, third));
+        check(
+            cond,
+            expect![[r#"
+                {
+                  val v0 = OUTPUTS(0).value + OUTPUTS(1).value
+                  v0 > v0 * 2 && SELF.propBytes == "0707070707070707070707070707070707070707070707070707070707070707" == OUTPUTS(0).propBytes == "0707070707070707070707070707070707070707070707070707070707070707"
+                }
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! Common subexpression elimination
+
+use std::collections::HashMap;
+
+use crate::mir::bin_op::BinOpKind;
+use crate::mir::block::BlockValue;
+use crate::mir::expr::Expr;
+use crate::mir::global_vars::GlobalVars;
+use crate::mir::traverse::for_each_child;
+use crate::mir::traverse::for_each_child_mut;
+use crate::mir::traverse::for_each_descendant;
+use crate::mir::val_def::ValDef;
+use crate::mir::val_def::ValId;
+use crate::mir::val_use::ValUse;
+use crate::serialization::SigmaSerializable;
+
+use super::vals::is_trivial;
+
+/// Serialized size of `ValDef` without the bound expression (op code and id)
+const VAL_DEF_OVERHEAD: usize = 2;
+/// Serialized size of `ValUse` (op code and id)
+const VAL_USE_SIZE: usize = 2;
+/// Serialized size of a new `BlockValue` without its statements and result
+const BLOCK_OVERHEAD: usize = 2;
+
+/// JIT cost of `ValDef` (adding the value to the environment)
+const VAL_DEF_COST: u64 = 5;
+/// JIT cost of `ValUse`
+const VAL_USE_COST: u64 = 5;
+/// JIT cost of a new `BlockValue` with one statement, or the max. cost increase of an existing
+/// block when a statement is added
+const NEW_BLOCK_COST: u64 = 2;
+const BLOCK_ITEM_COST: u64 = 1;
+/// Lower bound of the JIT cost of the nodes not listed in [`min_node_cost`]
+const MIN_NODE_COST: u64 = 3;
+
+/// Extracts the repeated subexpressions into `ValDef`s (in the root expression and each lambda
+/// body), returns `true` if anything changed. An expression is extracted only if it is
+/// evaluated every time its scope is evaluated, the evaluation cost saved by evaluating it once
+/// exceeds the cost of the val definition and uses, and the serialized tree gets smaller.
+pub(super) fn eliminate_common_subexprs(expr: &mut Expr, next_id: &mut u32) -> bool {
+    let mut changed = eliminate_in_lambdas(expr, next_id);
+    while eliminate_one(expr, next_id) {
+        changed = true;
+    }
+    changed
+}
+
+fn eliminate_in_lambdas(expr: &mut Expr, next_id: &mut u32) -> bool {
+    let mut changed = false;
+    for_each_child_mut(expr, &mut |child| {
+        changed |= match child {
+            Expr::FuncValue(func) => eliminate_common_subexprs(func.body_mut(), next_id),
+            _ => eliminate_in_lambdas(child, next_id),
+        }
+    });
+    changed
+}
+
+/// Repeated subexpression of the scope
+struct Candidate {
+    expr: Expr,
+    size: usize,
+    count: usize,
+    /// Whether any occurrence is evaluated every time the scope is evaluated
+    eager: bool,
+}
+
+/// Extracts the most profitable repeated subexpression of the scope
+fn eliminate_one(scope: &mut Expr, next_id: &mut u32) -> bool {
+    let mut candidates: HashMap<Vec<u8>, Candidate> = HashMap::new();
+    for_each_child(scope, &mut |child| {
+        collect(child, is_eager_child(scope, child), &mut candidates)
+    });
+    let defined_in_scope = defined_ids(scope);
+    let block_items = match &*scope {
+        Expr::BlockValue(block) => Some(&block.expr.items),
+        _ => None,
+    };
+    let best = candidates
+        .into_iter()
+        .filter(|(_, c)| c.count > 1 && c.eager)
+        .filter_map(|(bytes, c)| {
+            let position = insert_position(&c.expr, &defined_in_scope, block_items)?;
+            let (new_block, block_cost) = if block_items.is_some() {
+                (0, BLOCK_ITEM_COST)
+            } else {
+                (BLOCK_OVERHEAD, NEW_BLOCK_COST)
+            };
+            let saved_cost = (c.count as u64 - 1) * min_eval_cost(&c.expr);
+            let added_cost = VAL_DEF_COST + c.count as u64 * VAL_USE_COST + block_cost;
+            if saved_cost <= added_cost {
+                return None;
+            }
+            let saved = (c.count * c.size)
+                .checked_sub(c.size + VAL_DEF_OVERHEAD + c.count * VAL_USE_SIZE + new_block)
+                .filter(|saved| *saved > 0)?;
+            Some((saved, bytes, c.expr, position))
+        })
+        // the byte comparison makes the choice deterministic
+        .max_by(|(saved_a, bytes_a, _, _), (saved_b, bytes_b, _, _)| {
+            (saved_a, bytes_a.len(), bytes_a).cmp(&(saved_b, bytes_b.len(), bytes_b))
+        });
+    let (_, bytes, rhs, position) = match best {
+        Some(best) => best,
+        None => return false,
+    };
+    let id = ValId(*next_id);
+    *next_id += 1;
+    let val_use: Expr = ValUse {
+        val_id: id,
+        tpe: rhs.tpe(),
+    }
+    .into();
+    replace(scope, &bytes, &val_use);
+    let val_def: Expr = ValDef {
+        id,
+        rhs: Box::new(rhs),
+    }
+    .into();
+    match scope {
+        Expr::BlockValue(block) => block.expr.items.insert(position, val_def),
+        _ => {
+            let result = std::mem::replace(scope, val_use);
+            *scope = BlockValue {
+                items: vec![val_def],
+                result: Box::new(result),
+            }
+            .into();
+        }
+    }
+    true
+}
+
+fn collect(expr: &Expr, eager: bool, candidates: &mut HashMap<Vec<u8>, Candidate>) {
+    // lambda bodies are separate scopes
+    if matches!(expr, Expr::FuncValue(_)) {
+        return;
+    }
+    if is_extractable(expr) {
+        if let Ok(bytes) = expr.sigma_serialize_bytes() {
+            let candidate = candidates
+                .entry(bytes)
+                .or_insert_with_key(|bytes| Candidate {
+                    expr: expr.clone(),
+                    size: bytes.len(),
+                    count: 0,
+                    eager: false,
+                });
+            candidate.count += 1;
+            candidate.eager |= eager;
+        }
+    }
+    for_each_child(expr, &mut |child| {
+        collect(child, eager && is_eager_child(expr, child), candidates)
+    });
+}
+
+fn is_extractable(expr: &Expr) -> bool {
+    let mut has_definitions = false;
+    for_each_descendant(expr, &mut |e| {
+        has_definitions |= matches!(e, Expr::BlockValue(_) | Expr::ValDef(_) | Expr::FunDef(_))
+    });
+    // a constant costs as much as the val use, so extracting it never saves the cost
+    !is_trivial(expr) && !matches!(expr, Expr::FuncValue(_)) && !has_definitions
+}
+
+/// Lower bound of the JIT cost of the expression evaluation (the costs of the nodes that are
+/// always evaluated, see `Costs` in the interpreter), underestimating it only makes the
+/// extraction less likely
+fn min_eval_cost(expr: &Expr) -> u64 {
+    let mut cost = min_node_cost(expr);
+    for_each_child(expr, &mut |child| {
+        if is_eager_child(expr, child) {
+            cost += min_eval_cost(child);
+        }
+    });
+    cost
+}
+
+fn min_node_cost(expr: &Expr) -> u64 {
+    match expr {
+        Expr::ConstPlaceholder(_) | Expr::Context => 1,
+        Expr::Const(_) | Expr::ValUse(_) | Expr::Global => 5,
+        Expr::GlobalVars(GlobalVars::Height) => 26,
+        Expr::GlobalVars(GlobalVars::MinerPubKey) => 20,
+        Expr::GlobalVars(_) => 10,
+        Expr::ExtractAmount(_) => 8,
+        Expr::ExtractScriptBytes(_)
+        | Expr::ExtractBytes(_)
+        | Expr::ExtractBytesWithNoRef(_)
+        | Expr::ExtractId(_) => 12,
+        Expr::SizeOf(_) => 14,
+        Expr::ByIndex(_) => 30,
+        Expr::ExtractRegisterAs(_) => 50,
+        Expr::CalcBlake2b256(_) => 27,
+        Expr::CalcSha256(_) => 88,
+        Expr::DecodePoint(_) => 300,
+        Expr::Exponentiate(_) => 900,
+        Expr::MultiplyGroup(_) => 40,
+        _ => MIN_NODE_COST,
+    }
+}
+
+/// Whether the child is evaluated every time the expression is evaluated
+fn is_eager_child(expr: &Expr, child: &Expr) -> bool {
+    match expr {
+        Expr::FuncValue(_) | Expr::DeserializeRegister(_) => false,
+        Expr::If(op) => std::ptr::eq(&*op.condition, child),
+        Expr::BinOp(op) if matches!(op.expr.kind, BinOpKind::Logical(_)) => {
+            std::ptr::eq(&*op.expr.left, child)
+        }
+        Expr::ByIndex(op) => op
+            .expr
+            .default
+            .as_ref()
+            .map_or(true, |default| !std::ptr::eq(&**default, child)),
+        Expr::OptionGetOrElse(op) => std::ptr::eq(&*op.expr.input, child),
+        _ => true,
+    }
+}
+
+/// Ids of the vals and lambda arguments defined in the expression
+fn defined_ids(expr: &Expr) -> Vec<ValId> {
+    let mut ids = Vec::new();
+    for_each_descendant(expr, &mut |e| match e {
+        Expr::ValDef(val_def) => ids.push(val_def.expr.id),
+        Expr::FunDef(fun_def) => ids.push(fun_def.id),
+        Expr::FuncValue(func) => ids.extend(func.args().iter().map(|arg| arg.idx)),
+        _ => (),
+    });
+    ids
+}
+
+/// Index of the scope block statement before which the extracted val can be defined (after the
+/// vals it uses), `None` if it uses the vals defined deeper in the scope
+fn insert_position(
+    expr: &Expr,
+    defined_in_scope: &[ValId],
+    block_items: Option<&Vec<Expr>>,
+) -> Option<usize> {
+    let bound_inside = defined_ids(expr);
+    let mut position = 0;
+    let mut free_vars_in_scope = true;
+    for_each_descendant(expr, &mut |e| {
+        if let Expr::ValUse(val_use) = e {
+            let id = val_use.val_id;
+            if bound_inside.contains(&id) || !defined_in_scope.contains(&id) {
+                return;
+            }
+            let item_idx = block_items.and_then(|items| {
+                items.iter().position(|item| match item {
+                    Expr::ValDef(val_def) => val_def.expr.id == id,
+                    Expr::FunDef(fun_def) => fun_def.id == id,
+                    _ => false,
+                })
+            });
+            match item_idx {
+                Some(idx) => position = position.max(idx + 1),
+                None => free_vars_in_scope = false,
+            }
+        }
+    });
+    free_vars_in_scope.then_some(position)
+}
+
+/// Replaces the expressions serialized as `bytes` with `replacement`
+fn replace(expr: &mut Expr, bytes: &[u8], replacement: &Expr) {
+    if expr.sigma_serialize_bytes().ok().as_deref() == Some(bytes) {
+        *expr = replacement.clone();
+        return;
+    }
+    for_each_child_mut(expr, &mut |child| replace(child, bytes, replacement));
+}
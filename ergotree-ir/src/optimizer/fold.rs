@@ -0,0 +1,143 @@
+//! Constant folding
+
+use crate::mir::bin_op::ArithOp;
+use crate::mir::bin_op::BinOpKind;
+use crate::mir::bin_op::LogicalOp;
+use crate::mir::bin_op::RelationOp;
+use crate::mir::constant::Constant;
+use crate::mir::constant::Literal;
+use crate::mir::expr::Expr;
+use crate::mir::traverse::rewrite_bottom_up;
+
+/// Folds the operations on constants, returns `true` if anything changed.
+/// Operations that would fail at evaluation (overflow, division by zero) are kept as is, so the
+/// script still fails.
+pub(super) fn fold_constants(expr: &mut Expr) -> bool {
+    let mut changed = false;
+    rewrite_bottom_up(expr, &mut |e| {
+        if let Some(folded) = fold(e) {
+            *e = folded;
+            changed = true;
+        }
+    });
+    changed
+}
+
+fn fold(expr: &Expr) -> Option<Expr> {
+    match expr {
+        Expr::BinOp(op) => {
+            let (left, right) = (&*op.expr.left, &*op.expr.right);
+            match op.expr.kind {
+                BinOpKind::Arith(arith_op) => {
+                    fold_arith(arith_op, literal(left)?, literal(right)?).map(Expr::Const)
+                }
+                BinOpKind::Relation(rel_op) => {
+                    fold_relation(rel_op, literal(left)?, literal(right)?).map(Expr::from)
+                }
+                BinOpKind::Logical(logical_op) => fold_logical(logical_op, left, right),
+                BinOpKind::Bit(_) => None,
+            }
+        }
+        Expr::LogicalNot(op) => match &*op.expr.input {
+            Expr::LogicalNot(inner) => Some(*inner.expr.input.clone()),
+            input => boolean(input).map(|b| Expr::from(!b)),
+        },
+        Expr::Negation(op) => match literal(&op.expr.input)? {
+            Literal::Byte(v) => v.checked_neg().map(Expr::from),
+            Literal::Short(v) => v.checked_neg().map(Expr::from),
+            Literal::Int(v) => v.checked_neg().map(Expr::from),
+            Literal::Long(v) => v.checked_neg().map(Expr::from),
+            _ => None,
+        },
+        Expr::If(op) => {
+            let branch = if boolean(&op.condition)? {
+                &op.true_branch
+            } else {
+                &op.false_branch
+            };
+            Some(*branch.clone())
+        }
+        Expr::BlockValue(op) if op.expr.items.is_empty() => Some(*op.expr.result.clone()),
+        _ => None,
+    }
+}
+
+fn literal(expr: &Expr) -> Option<&Literal> {
+    match expr {
+        Expr::Const(c) => Some(&c.v),
+        _ => None,
+    }
+}
+
+fn boolean(expr: &Expr) -> Option<bool> {
+    match literal(expr)? {
+        Literal::Boolean(b) => Some(*b),
+        _ => None,
+    }
+}
+
+macro_rules! arith {
+    ($op:expr, $l:expr, $r:expr) => {
+        match $op {
+            ArithOp::Plus => $l.checked_add(*$r),
+            ArithOp::Minus => $l.checked_sub(*$r),
+            ArithOp::Multiply => $l.checked_mul(*$r),
+            ArithOp::Divide => $l.checked_div(*$r),
+            ArithOp::Modulo => $l.checked_rem(*$r),
+            ArithOp::Max => Some(*$l.max($r)),
+            ArithOp::Min => Some(*$l.min($r)),
+        }
+        .map(Constant::from)
+    };
+}
+
+fn fold_arith(op: ArithOp, left: &Literal, right: &Literal) -> Option<Constant> {
+    match (left, right) {
+        (Literal::Byte(l), Literal::Byte(r)) => arith!(op, l, r),
+        (Literal::Short(l), Literal::Short(r)) => arith!(op, l, r),
+        (Literal::Int(l), Literal::Int(r)) => arith!(op, l, r),
+        (Literal::Long(l), Literal::Long(r)) => arith!(op, l, r),
+        _ => None,
+    }
+}
+
+fn fold_relation(op: RelationOp, left: &Literal, right: &Literal) -> Option<bool> {
+    let ordering = match (left, right) {
+        (Literal::Byte(l), Literal::Byte(r)) => l.cmp(r),
+        (Literal::Short(l), Literal::Short(r)) => l.cmp(r),
+        (Literal::Int(l), Literal::Int(r)) => l.cmp(r),
+        (Literal::Long(l), Literal::Long(r)) => l.cmp(r),
+        (Literal::Boolean(l), Literal::Boolean(r)) => {
+            return match op {
+                RelationOp::Eq => Some(l == r),
+                RelationOp::NEq => Some(l != r),
+                _ => None,
+            }
+        }
+        _ => return None,
+    };
+    Some(match op {
+        RelationOp::Eq => ordering.is_eq(),
+        RelationOp::NEq => ordering.is_ne(),
+        RelationOp::Ge => ordering.is_ge(),
+        RelationOp::Gt => ordering.is_gt(),
+        RelationOp::Le => ordering.is_le(),
+        RelationOp::Lt => ordering.is_lt(),
+    })
+}
+
+/// `&&` and `||` evaluate the right operand only if needed, so a constant on the left decides
+/// the result; a constant on the right is dropped only when it doesn't change the result (the
+/// left operand is always evaluated)
+fn fold_logical(op: LogicalOp, left: &Expr, right: &Expr) -> Option<Expr> {
+    match (op, boolean(left), boolean(right)) {
+        (LogicalOp::And, Some(true), _) | (LogicalOp::Or, Some(false), _) => Some(right.clone()),
+        (LogicalOp::And, Some(false), _) => Some(false.into()),
+        (LogicalOp::Or, Some(true), _) => Some(true.into()),
+        (LogicalOp::And, None, Some(true)) | (LogicalOp::Or, None, Some(false)) => {
+            Some(left.clone())
+        }
+        (LogicalOp::Xor, Some(l), Some(r)) => Some((l ^ r).into()),
+        _ => None,
+    }
+}
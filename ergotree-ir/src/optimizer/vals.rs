@@ -0,0 +1,200 @@
+//! Inlining of the single-use `ValDef`s and elimination of the unused ones
+
+use std::collections::HashMap;
+
+use crate::mir::bin_op::BinOpKind;
+use crate::mir::expr::Expr;
+use crate::mir::traverse::for_each_child;
+use crate::mir::traverse::for_each_child_mut;
+use crate::mir::traverse::for_each_descendant;
+use crate::mir::traverse::rewrite_bottom_up;
+use crate::mir::val_def::ValId;
+
+/// Inlines the vals used once and removes the unused ones, returns `true` if anything changed.
+/// The bound expression is moved only to a place where it is evaluated exactly once (not into a
+/// lambda or a conditional branch) unless it is a constant or a variable, and it is removed only
+/// if its evaluation cannot fail.
+pub(super) fn inline_vals(expr: &mut Expr) -> bool {
+    let uses = use_counts(expr);
+    let mut changed = false;
+    rewrite_bottom_up(expr, &mut |e| {
+        let block = match e {
+            Expr::BlockValue(block) => &mut block.expr,
+            _ => return,
+        };
+        let mut idx = 0;
+        while idx < block.items.len() {
+            let (id, rhs) = match &block.items[idx] {
+                Expr::ValDef(val_def) => (val_def.expr.id, &*val_def.expr.rhs),
+                _ => {
+                    idx += 1;
+                    continue;
+                }
+            };
+            let removable = match uses.get(&id).copied().unwrap_or(0) {
+                0 => cannot_fail(rhs),
+                1 => {
+                    let rhs = rhs.clone();
+                    let (_, rest) = block.items.split_at_mut(idx + 1);
+                    let mut inlined = false;
+                    for stmt in rest.iter_mut().chain(std::iter::once(&mut *block.result)) {
+                        if inlined {
+                            break;
+                        }
+                        inlined = if is_trivial(&rhs) {
+                            substitute(stmt, id, &rhs)
+                        } else {
+                            substitute_eager(stmt, id, &rhs)
+                        };
+                    }
+                    inlined
+                }
+                _ => false,
+            };
+            if removable {
+                block.items.remove(idx);
+                changed = true;
+            } else {
+                idx += 1;
+            }
+        }
+        if block.items.is_empty() {
+            *e = *block.result.clone();
+        }
+    });
+    changed
+}
+
+/// Number of `ValUse`s of each val
+pub(super) fn use_counts(expr: &Expr) -> HashMap<ValId, usize> {
+    let mut uses = HashMap::new();
+    for_each_descendant(expr, &mut |e| {
+        if let Expr::ValUse(val_use) = e {
+            *uses.entry(val_use.val_id).or_insert(0) += 1;
+        }
+    });
+    uses
+}
+
+/// Whether each val and lambda argument id is defined once, so the expressions can be moved
+/// without being captured by another definition
+pub(super) fn has_unique_ids(expr: &Expr) -> bool {
+    let mut ids = Vec::new();
+    for_each_descendant(expr, &mut |e| match e {
+        Expr::ValDef(val_def) => ids.push(val_def.expr.id),
+        Expr::FunDef(fun_def) => ids.push(fun_def.id),
+        Expr::FuncValue(func) => ids.extend(func.args().iter().map(|arg| arg.idx)),
+        _ => (),
+    });
+    let count = ids.len();
+    ids.sort();
+    ids.dedup();
+    ids.len() == count
+}
+
+/// Variable or a value that is cheap to evaluate any number of times
+pub(super) fn is_trivial(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Const(_)
+            | Expr::ConstPlaceholder(_)
+            | Expr::ValUse(_)
+            | Expr::GlobalVars(_)
+            | Expr::Context
+            | Expr::Global
+    )
+}
+
+/// Whether the evaluation of the expression always succeeds (conservatively)
+pub(super) fn cannot_fail(expr: &Expr) -> bool {
+    let node_cannot_fail = match expr {
+        Expr::Const(_)
+        | Expr::ConstPlaceholder(_)
+        | Expr::ValUse(_)
+        | Expr::GlobalVars(_)
+        | Expr::Context
+        | Expr::Global
+        | Expr::FuncValue(_)
+        | Expr::Collection(_)
+        | Expr::Tuple(_)
+        | Expr::BlockValue(_)
+        | Expr::ValDef(_)
+        | Expr::If(_)
+        | Expr::LogicalNot(_)
+        | Expr::ExtractAmount(_)
+        | Expr::ExtractScriptBytes(_)
+        | Expr::ExtractBytes(_)
+        | Expr::ExtractBytesWithNoRef(_)
+        | Expr::ExtractId(_)
+        | Expr::ExtractCreationInfo(_)
+        | Expr::SizeOf(_)
+        | Expr::SelectField(_)
+        | Expr::OptionIsDefined(_)
+        | Expr::SomeValue(_)
+        | Expr::NoneValue(_)
+        | Expr::CalcBlake2b256(_)
+        | Expr::CalcSha256(_)
+        | Expr::BoolToSigmaProp(_)
+        | Expr::Upcast(_)
+        | Expr::CreateProveDlog(_)
+        | Expr::SigmaAnd(_)
+        | Expr::SigmaOr(_)
+        | Expr::SigmaPropBytes(_) => true,
+        Expr::BinOp(op) => matches!(op.expr.kind, BinOpKind::Relation(_) | BinOpKind::Logical(_)),
+        _ => false,
+    };
+    // functions are not called at their definition
+    let skip_children = matches!(expr, Expr::FuncValue(_));
+    let mut children_cannot_fail = true;
+    if !skip_children {
+        for_each_child(expr, &mut |child| {
+            children_cannot_fail = children_cannot_fail && cannot_fail(child)
+        });
+    }
+    node_cannot_fail && children_cannot_fail
+}
+
+/// Calls `f` on the children that are evaluated (once) every time the expression is evaluated
+pub(super) fn for_each_eager_child_mut(expr: &mut Expr, f: &mut dyn FnMut(&mut Expr)) {
+    match expr {
+        // function bodies are evaluated on calls
+        Expr::FuncValue(_) => (),
+        Expr::If(op) => f(&mut op.condition),
+        Expr::BinOp(op) if matches!(op.expr.kind, BinOpKind::Logical(_)) => f(&mut op.expr.left),
+        Expr::ByIndex(op) => {
+            f(&mut op.expr.input);
+            f(&mut op.expr.index);
+        }
+        Expr::OptionGetOrElse(op) => f(&mut op.expr.input),
+        Expr::DeserializeRegister(_) => (),
+        _ => for_each_child_mut(expr, f),
+    }
+}
+
+/// Replaces the `ValUse` of the val with the expression anywhere in `expr`, returns `true` if
+/// it was found
+fn substitute(expr: &mut Expr, id: ValId, rhs: &Expr) -> bool {
+    if matches!(expr, Expr::ValUse(val_use) if val_use.val_id == id) {
+        *expr = rhs.clone();
+        return true;
+    }
+    let mut found = false;
+    for_each_child_mut(expr, &mut |child| {
+        found = found || substitute(child, id, rhs)
+    });
+    found
+}
+
+/// Replaces the `ValUse` of the val with the expression if it's evaluated every time `expr` is
+/// evaluated, returns `true` if it was replaced
+fn substitute_eager(expr: &mut Expr, id: ValId, rhs: &Expr) -> bool {
+    if matches!(expr, Expr::ValUse(val_use) if val_use.val_id == id) {
+        *expr = rhs.clone();
+        return true;
+    }
+    let mut found = false;
+    for_each_eager_child_mut(expr, &mut |child| {
+        found = found || substitute_eager(child, id, rhs)
+    });
+    found
+}
@@ -202,6 +202,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn print_tuple_coll_and_functions() {
+        use crate::mir::bool_to_sigma::BoolToSigmaProp;
+        use crate::mir::collection::Collection;
+        use crate::mir::get_var::GetVar;
+        use crate::mir::global_vars::GlobalVars;
+        use crate::mir::select_field::SelectField;
+        use crate::mir::sigma_prop_bytes::SigmaPropBytes;
+        use crate::mir::subst_const::SubstConstants;
+        use crate::mir::tuple::Tuple;
+        let tuple: Expr = Tuple::new(vec![GlobalVars::Height.into(), Expr::Const(1i64.into())])
+            .unwrap()
+            .into();
+        check_pretty(
+            SelectField::new(tuple, 2.try_into().unwrap())
+                .unwrap()
+                .into(),
+            expect!["(HEIGHT, 1)._2"],
+        );
+        check_pretty(
+            Collection::new(
+                SType::SInt,
+                vec![Expr::Const(1i32.into()), GlobalVars::Height.into()],
+            )
+            .unwrap()
+            .into(),
+            expect!["Coll[Int](1, HEIGHT)"],
+        );
+        check_pretty(
+            Collection::from_bools(vec![true, false]).into(),
+            expect!["Coll[Boolean](true, false)"],
+        );
+        check_pretty(
+            BinOp {
+                kind: ArithOp::Min.into(),
+                left: Box::new(GlobalVars::Height.into()),
+                right: Box::new(Expr::Const(10i32.into())),
+            }
+            .into(),
+            expect!["min(HEIGHT, 10)"],
+        );
+        let prop: Expr = BoolToSigmaProp {
+            input: Box::new(Expr::Const(true.into())),
+        }
+        .into();
+        check_pretty(
+            SigmaPropBytes {
+                input: Box::new(prop),
+            }
+            .into(),
+            expect!["sigmaProp(true).propBytes"],
+        );
+        check_pretty(
+            SubstConstants::new(
+                Expr::Const(vec![0u8].into()),
+                Expr::Const(vec![0i32].into()),
+                Expr::Const(vec![1i32].into()),
+            )
+            .unwrap()
+            .into(),
+            expect![[r#"substConstants("00", [0], [1])"#]],
+        );
+        check_pretty(
+            GetVar {
+                var_id: 1,
+                var_tpe: SType::SInt,
+            }
+            .into(),
+            expect!["getVar[Int](1)"],
+        );
+    }
+
     #[test]
     fn eip23_refresh_contract() {
         let ergo_tree_bytes = base16::decode("1016043c040004000e202a472d4a614e645267556b58703273357638792f423f4528482b4d625065536801000502010105000400040004020402040204080400040a05c8010e20472b4b6250655368566d597133743677397a24432646294a404d635166546a570400040404020408d80ed60199a37300d602b2a4730100d603b5a4d901036395e6c672030605eded928cc77203017201938cb2db6308720373020001730393e4c672030504e4c6720205047304d604b17203d605b0720386027305860273067307d901053c413d0563d803d607e4c68c7205020605d6088c720501d6098c720802860272078602ed8c720901908c72080172079a8c7209027207d6068c720502d6078c720501d608db63087202d609b27208730800d60ab2a5730900d60bdb6308720ad60cb2720b730a00d60db27208730b00d60eb2a5730c00ea02ea02ea02ea02ea02ea02ea02ea02ea02ea02ea02ea02ea02ea02ea02ea02ea02cde4c6b27203e4e30004000407d18f8cc77202017201d1927204730dd18c720601d190997207e4c6b27203730e0006059d9c72077e730f057310d1938c7209017311d193b2720b7312007209d1938c720c018c720d01d1928c720c02998c720d027e9c7204731305d193b1720bb17208d193e4c6720a04059d8c7206027e720405d193e4c6720a05049ae4c6720205047314d193c2720ac27202d192c1720ac17202d1928cc7720a0199a37315d193db6308720edb6308a7d193c2720ec2a7d192c1720ec1a7").unwrap();
@@ -218,13 +290,13 @@ mod tests {
                       }
                     )
                   val v4 = v3.size
-                  val v5 = v3.fold((, 1, (, true, 0)))({
+                  val v5 = v3.fold((1, (true, 0)))({
                       (v5: ((Long, (Boolean, Long)), Box)) => 
                         {
                           val v7 = v5._2.getReg(6).get
                           val v8 = v5._1
                           val v9 = v8._2
-                          (, v7, (, v9._1 && v8._1 <= v7, v9._2 + v7))
+                          (v7, (v9._1 && v8._1 <= v7, v9._2 + v7))
                         }
 
                       }
@@ -255,7 +327,7 @@ mod tests {
                                               allOf(
                                                 allOf(
                                                   allOf(
-                                                    proveDlog(v3(getVar(0).get).getReg(4).get), 
+                                                    proveDlog(v3(getVar[Int](0).get).getReg(4).get), 
                                                     sigmaProp(v2.creationInfo._1 < v1), 
                                                   ), 
                                                   sigmaProp(v4 >= 4), 
@@ -344,7 +416,7 @@ mod tests {
             expect![[r#"
                 {
                   val v1 = SELF.getReg(4).get
-                  val v2 = OUTPUTS(getVar(0).get)
+                  val v2 = OUTPUTS(getVar[Int](0).get)
                   val v3 = v2.getReg(4)
                   anyOf(
                     proveDlog(v1), 
@@ -363,7 +435,7 @@ mod tests {
             ergo_tree.proposition().unwrap(),
             expect![[r#"
                 {
-                  val v1 = OUTPUTS(getVar(0).get)
+                  val v1 = OUTPUTS(getVar[Int](0).get)
                   val v2 = v1.tokens
                   val v3 = SELF.tokens
                   val v4 = SELF.getReg(4).get
@@ -419,8 +491,8 @@ mod tests {
                     val v26 = SELF.value
                     val v27 = v22.getReg(5).get
                     val v28 = v2.getReg(4).get / 100
-                    val v29 = v26 min v20 * v28 max 0
-                    val v30 = if (v17) v28 min if (v20 == 0) 9223372036854775807 else v29 / v20 * v24 else {
+                    val v29 = max(min(v26, v20 * v28), 0)
+                    val v30 = if (v17) min(v28, if (v20 == 0) 9223372036854775807 else v29 / v20) * v24 else {
                       val v30 = v26 - v29
                       if (v30 == 0) 1000000 else if (v18 == 0) 1000000 else v30 / v18 * v25
                     }
@@ -529,7 +601,7 @@ mod tests {
                   val v20 = upcast(v18)
                   val v21 = upcast(v10)
                   val v22 = upcast(v11) / v21
-                  sigmaProp(v1.propBytes == SELF.propBytes && v1.value >= SELF.value && v2(1) == v3(1) && v4._1 == v5._1 && v6._1 == v7._1 && v8._1 == v9._1 && if (v11 == 0) if (v14) v16 * v17 * BigInt256(Int256(997)) >= upcast(-v18) * v19 * BigInt256(Int256(1000)) + upcast(v13 * 997) else v19 * v20 * BigInt256(Int256(997)) >= upcast(-v13) * v16 * BigInt256(Int256(1000)) + upcast(v18 * 997) else if (v14 && v18 > 0) upcast(-v11) <= v17 * v21 / v19 min v20 * v21 / v16 else v17 >= v22 * v19 && v20 >= v22 * v16)
+                  sigmaProp(v1.propBytes == SELF.propBytes && v1.value >= SELF.value && v2(1) == v3(1) && v4._1 == v5._1 && v6._1 == v7._1 && v8._1 == v9._1 && if (v11 == 0) if (v14) v16 * v17 * BigInt256(Int256(997)) >= upcast(-v18) * v19 * BigInt256(Int256(1000)) + upcast(v13 * 997) else v19 * v20 * BigInt256(Int256(997)) >= upcast(-v13) * v16 * BigInt256(Int256(1000)) + upcast(v18 * 997) else if (v14 && v18 > 0) upcast(-v11) <= min(v17 * v21 / v19, v20 * v21 / v16) else v17 >= v22 * v19 && v20 >= v22 * v16)
                 }
             "#]],
         )
@@ -590,7 +662,7 @@ mod tests {
                   val v11 = v5._1
                   val v12 = v7.getReg(5).get
                   val v13 = v7.getReg(7).get
-                  sigmaProp(v1.propBytes == SELF.propBytes && v1.value >= SELF.value && v1.tokens(0) == (, v3, v2._2 - 1) && v1.getReg(4).get == v4 && v1.getReg(5).get == v5 && v1.getReg(6).get == v6 && v7.getReg(4).get == v4 && v7.getReg(8).get == v6 && v8(1)._1 == v3 && v8(0) == (, SELF.id, 1000000000000000000) && v9._1 * v10 == v9._2 * v11 * v12 && v13._1 * v10 == v13._2 * v11 * v12 + 1)
+                  sigmaProp(v1.propBytes == SELF.propBytes && v1.value >= SELF.value && v1.tokens(0) == (v3, v2._2 - 1) && v1.getReg(4).get == v4 && v1.getReg(5).get == v5 && v1.getReg(6).get == v6 && v7.getReg(4).get == v4 && v7.getReg(8).get == v6 && v8(1)._1 == v3 && v8(0) == (SELF.id, 1000000000000000000) && v9._1 * v10 == v9._2 * v11 * v12 && v13._1 * v10 == v13._2 * v11 * v12 + 1)
                 }
             "#]],
         )
@@ -619,7 +691,7 @@ mod tests {
                   val v10 = upcast(v9)
                   val v11 = v2(2)
                   val v12 = v3(0)
-                  sigmaProp(true && v1.value >= SELF.value && v2(0) == (, SELF.id, 1) && v2(1) == (, v3(1)._1, 1) && v1.getReg(4).get == SELF.getReg(4).get && v4 == SELF.getReg(6).get && v5 == SELF.getReg(7).get && (, v6._1, v2(4)._1) == SELF.getReg(8).get && v8 * v8 == v10 * v10 && if (v11._1 == v12._1) v11._2 else 0 >= v12._2 - v9 && v7 * upcast(v4._2) >= v7 * upcast(v4._1) && v7 * upcast(v5._2) < v7 * upcast(v5._1))
+                  sigmaProp(true && v1.value >= SELF.value && v2(0) == (SELF.id, 1) && v2(1) == (v3(1)._1, 1) && v1.getReg(4).get == SELF.getReg(4).get && v4 == SELF.getReg(6).get && v5 == SELF.getReg(7).get && (v6._1, v2(4)._1) == SELF.getReg(8).get && v8 * v8 == v10 * v10 && if (v11._1 == v12._1) v11._2 else 0 >= v12._2 - v9 && v7 * upcast(v4._2) >= v7 * upcast(v4._1) && v7 * upcast(v5._2) < v7 * upcast(v5._1))
                 }
             "#]],
         )
@@ -667,7 +739,7 @@ mod tests {
                   val v29 = upcast(v26)
                   val v30 = upcast(v13)
                   val v31 = upcast(v14) / v30
-                  sigmaProp(v1.propBytes == SELF.propBytes && v1.value >= SELF.value && v2(0) == v3(0) && v2(1) == v3(1) && v4._1 == v5._1 && v6._1 == v7._1 && v8._1 == v9._1 && v1.getReg(4).get == v10 && v1.getReg(5).get == v11 && v1.getReg(6).get == v12 && if (v14 == 0) if (v22) v24 * v25 * upcast(v10) >= upcast(-v26) * v27 * upcast(v28) + upcast(v21 * upcast(v10)) else v27 * v29 * upcast(v10) >= upcast(-v21) * v24 * upcast(v28) + upcast(v26 * upcast(v10)) && v19 else if (v22 && v26 > 0) upcast(-v14) <= v25 * v30 / v27 min v29 * v30 / v24 && v19 else v25 >= v31 * v27 && v29 >= v31 * v24)
+                  sigmaProp(v1.propBytes == SELF.propBytes && v1.value >= SELF.value && v2(0) == v3(0) && v2(1) == v3(1) && v4._1 == v5._1 && v6._1 == v7._1 && v8._1 == v9._1 && v1.getReg(4).get == v10 && v1.getReg(5).get == v11 && v1.getReg(6).get == v12 && if (v14 == 0) if (v22) v24 * v25 * upcast(v10) >= upcast(-v26) * v27 * upcast(v28) + upcast(v21 * upcast(v10)) else v27 * v29 * upcast(v10) >= upcast(-v21) * v24 * upcast(v28) + upcast(v26 * upcast(v10)) && v19 else if (v22 && v26 > 0) upcast(-v14) <= min(v25 * v30 / v27, v29 * v30 / v24) && v19 else v25 >= v31 * v27 && v29 >= v31 * v24)
                 }
             "#]],
         )
@@ -698,7 +770,7 @@ mod tests {
                     val v8 = v7(SELF)
                     val v9 = SELF.getReg(6).get
                     val v10 = SELF.getReg(8).get
-                    val v11 = Coll[Coll[Byte]]()
+                    val v11 = Coll[Byte]()
                     val v12 = OUTPUTS(1)
                     val v13 = SELF.getReg(5).get
                     val v14 = SELF.getReg(4).get
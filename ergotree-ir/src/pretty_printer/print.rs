@@ -3,7 +3,9 @@ use thiserror::Error;
 use crate::mir::and::And;
 use crate::mir::apply::Apply;
 use crate::mir::atleast::Atleast;
+use crate::mir::bin_op::ArithOp;
 use crate::mir::bin_op::BinOp;
+use crate::mir::bin_op::BinOpKind;
 use crate::mir::bit_inversion::BitInversion;
 use crate::mir::block::BlockValue;
 use crate::mir::bool_to_sigma::BoolToSigmaProp;
@@ -18,6 +20,7 @@ use crate::mir::coll_filter::Filter;
 use crate::mir::coll_fold::Fold;
 use crate::mir::coll_forall::ForAll;
 use crate::mir::coll_map::Map;
+use crate::mir::coll_shift::CollShift;
 use crate::mir::coll_size::SizeOf;
 use crate::mir::coll_slice::Slice;
 use crate::mir::collection::Collection;
@@ -38,6 +41,7 @@ use crate::mir::extract_creation_info::ExtractCreationInfo;
 use crate::mir::extract_id::ExtractId;
 use crate::mir::extract_reg_as::ExtractRegisterAs;
 use crate::mir::extract_script_bytes::ExtractScriptBytes;
+use crate::mir::fun_def::FunDef;
 use crate::mir::func_value::FuncValue;
 use crate::mir::get_var::GetVar;
 use crate::mir::global_vars::GlobalVars;
@@ -45,17 +49,22 @@ use crate::mir::if_op::If;
 use crate::mir::logical_not::LogicalNot;
 use crate::mir::long_to_byte_array::LongToByteArray;
 use crate::mir::method_call::MethodCall;
+use crate::mir::mod_q::ModQ;
+use crate::mir::mod_q::ModQArithOp;
 use crate::mir::multiply_group::MultiplyGroup;
 use crate::mir::negation::Negation;
 use crate::mir::option_get::OptionGet;
 use crate::mir::option_get_or_else::OptionGetOrElse;
 use crate::mir::option_is_defined::OptionIsDefined;
+use crate::mir::option_some::NoneValue;
+use crate::mir::option_some::SomeValue;
 use crate::mir::or::Or;
 use crate::mir::property_call::PropertyCall;
 use crate::mir::select_field::SelectField;
 use crate::mir::sigma_and::SigmaAnd;
 use crate::mir::sigma_or::SigmaOr;
 use crate::mir::sigma_prop_bytes::SigmaPropBytes;
+use crate::mir::sigma_prop_is_proven::SigmaPropIsProven;
 use crate::mir::subst_const::SubstConstants;
 use crate::mir::tree_lookup::TreeLookup;
 use crate::mir::tuple::Tuple;
@@ -171,6 +180,13 @@ impl Print for Expr {
             Expr::XorOf(v) => v.print(w),
             Expr::TreeLookup(v) => v.expr().print(w),
             Expr::CreateAvlTree(v) => v.print(w),
+            Expr::FunDef(v) => v.print(w),
+            Expr::ModQ(v) => v.print(w),
+            Expr::ModQArithOp(v) => v.print(w),
+            Expr::CollShift(v) => v.print(w),
+            Expr::SomeValue(v) => v.print(w),
+            Expr::NoneValue(v) => v.print(w),
+            Expr::SigmaPropIsProven(v) => v.print(w),
         }
     }
 }
@@ -258,9 +274,23 @@ impl Print for Append {
 impl Print for BinOp {
     fn print(&self, w: &mut dyn Printer) -> Result<Expr, PrintError> {
         let offset = w.current_pos();
-        let left = self.left.print(w)?;
-        write!(w, " {} ", self.kind)?;
-        let right = self.right.print(w)?;
+        let (left, right) = match self.kind {
+            // min and max are functions in ErgoScript
+            BinOpKind::Arith(ArithOp::Min) | BinOpKind::Arith(ArithOp::Max) => {
+                write!(w, "{}(", self.kind)?;
+                let left = self.left.print(w)?;
+                write!(w, ", ")?;
+                let right = self.right.print(w)?;
+                write!(w, ")")?;
+                (left, right)
+            }
+            _ => {
+                let left = self.left.print(w)?;
+                write!(w, " {} ", self.kind)?;
+                let right = self.right.print(w)?;
+                (left, right)
+            }
+        };
         let length = w.current_pos() - offset;
         // dbg!(offset, length);
         Ok(Spanned {
@@ -518,6 +548,7 @@ impl Print for MethodCall {
                 obj: Box::new(obj),
                 method: self.method.clone(),
                 args,
+                explicit_type_args: self.explicit_type_args.clone(),
             },
         }
         .into())
@@ -553,8 +584,12 @@ impl Print for SizeOf {
 impl Print for Tuple {
     fn print(&self, w: &mut dyn Printer) -> Result<Expr, PrintError> {
         write!(w, "(")?;
+        let mut first = true;
         let items = self.items.try_mapped_ref(|i| {
-            write!(w, ", ")?;
+            if !first {
+                write!(w, ", ")?;
+            }
+            first = false;
             i.print(w)
         })?;
         write!(w, ")")?;
@@ -644,7 +679,7 @@ impl Print for CreateProveDlog {
 
 impl Print for GetVar {
     fn print(&self, w: &mut dyn Printer) -> Result<Expr, PrintError> {
-        write!(w, "getVar({})", self.var_id)?;
+        write!(w, "getVar[{}]({})", self.var_tpe, self.var_id)?;
         Ok(self.clone().into())
     }
 }
@@ -755,11 +790,17 @@ impl Print for Apply {
 
 impl Print for Collection {
     fn print(&self, w: &mut dyn Printer) -> Result<Expr, PrintError> {
-        write!(w, "Coll[{}](", self.tpe())?;
+        match self.tpe() {
+            SType::SColl(elem_tpe) => write!(w, "Coll[{}](", elem_tpe)?,
+            tpe => write!(w, "{}(", tpe)?,
+        }
         match self {
             Collection::BoolConstants(bools) => {
-                for b in bools {
-                    write!(w, "{}, ", b)?;
+                for (i, b) in bools.iter().enumerate() {
+                    if i > 0 {
+                        write!(w, ", ")?;
+                    }
+                    write!(w, "{}", b)?;
                 }
                 write!(w, ")")?;
                 Ok(Collection::from_bools(bools.clone()).into())
@@ -767,8 +808,11 @@ impl Print for Collection {
             Collection::Exprs { elem_tpe, items } => {
                 let items = items
                     .iter()
-                    .map(|i| {
-                        write!(w, ", ")?;
+                    .enumerate()
+                    .map(|(idx, i)| {
+                        if idx > 0 {
+                            write!(w, ", ")?;
+                        }
                         i.print(w)
                     })
                     .collect::<Result<Vec<_>, _>>()?;
@@ -841,9 +885,11 @@ impl Print for CalcSha256 {
 
 impl Print for Xor {
     fn print(&self, w: &mut dyn Printer) -> Result<Expr, PrintError> {
+        write!(w, "xor(")?;
         let left = self.left.print(w)?;
-        write!(w, "^")?;
+        write!(w, ", ")?;
         let right = self.right.print(w)?;
+        write!(w, ")")?;
         Ok(Xor {
             left: left.into(),
             right: right.into(),
@@ -854,7 +900,7 @@ impl Print for Xor {
 
 impl Print for Atleast {
     fn print(&self, w: &mut dyn Printer) -> Result<Expr, PrintError> {
-        write!(w, ".atLeast(")?;
+        write!(w, "atLeast(")?;
         let bound = self.bound.print(w)?;
         write!(w, ", ")?;
         let input = self.input.print(w)?;
@@ -870,7 +916,7 @@ impl Print for Atleast {
 impl Print for SubstConstants {
     fn print(&self, w: &mut dyn Printer) -> Result<Expr, PrintError> {
         let offset = w.current_pos();
-        write!(w, ".substConstants(")?;
+        write!(w, "substConstants(")?;
         let script_bytes = self.script_bytes.print(w)?;
         write!(w, ", ")?;
         let positions = self.positions.print(w)?;
@@ -1004,9 +1050,8 @@ impl Print for CreateProveDhTuple {
 
 impl Print for SigmaPropBytes {
     fn print(&self, w: &mut dyn Printer) -> Result<Expr, PrintError> {
-        write!(w, "sigmaPropBytes(")?;
         let input = self.input.print(w)?;
-        write!(w, ")")?;
+        write!(w, ".propBytes")?;
         Ok(SigmaPropBytes {
             input: Box::new(input),
         }
@@ -1121,3 +1166,97 @@ impl Print for CreateAvlTree {
         .into())
     }
 }
+
+impl Print for FunDef {
+    fn print(&self, w: &mut dyn Printer) -> Result<Expr, PrintError> {
+        write!(w, "def v{}", self.id)?;
+        if !self.tpe_args.is_empty() {
+            let tpe_args = self
+                .tpe_args
+                .iter()
+                .map(|t| t.as_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(w, "[{}]", tpe_args)?;
+        }
+        write!(w, " = ")?;
+        let rhs = self.rhs.print(w)?;
+        Ok(FunDef {
+            id: self.id,
+            tpe_args: self.tpe_args.clone(),
+            rhs: Box::new(rhs),
+        }
+        .into())
+    }
+}
+
+impl Print for ModQ {
+    fn print(&self, w: &mut dyn Printer) -> Result<Expr, PrintError> {
+        let input = self.input.print(w)?;
+        write!(w, ".modQ")?;
+        Ok(ModQ {
+            input: Box::new(input),
+        }
+        .into())
+    }
+}
+
+impl Print for ModQArithOp {
+    fn print(&self, w: &mut dyn Printer) -> Result<Expr, PrintError> {
+        let left = self.left.print(w)?;
+        write!(w, ".{}(", self.kind)?;
+        let right = self.right.print(w)?;
+        write!(w, ")")?;
+        Ok(ModQArithOp {
+            kind: self.kind,
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+        .into())
+    }
+}
+
+impl Print for CollShift {
+    fn print(&self, w: &mut dyn Printer) -> Result<Expr, PrintError> {
+        let input = self.input.print(w)?;
+        write!(w, ".{}(", self.kind)?;
+        let shift = self.shift.print(w)?;
+        write!(w, ")")?;
+        Ok(CollShift {
+            kind: self.kind,
+            input: Box::new(input),
+            shift: Box::new(shift),
+        }
+        .into())
+    }
+}
+
+impl Print for SomeValue {
+    fn print(&self, w: &mut dyn Printer) -> Result<Expr, PrintError> {
+        write!(w, "Some(")?;
+        let input = self.input.print(w)?;
+        write!(w, ")")?;
+        Ok(SomeValue {
+            input: Box::new(input),
+        }
+        .into())
+    }
+}
+
+impl Print for NoneValue {
+    fn print(&self, w: &mut dyn Printer) -> Result<Expr, PrintError> {
+        write!(w, "None[{}]", self.elem_tpe)?;
+        Ok(self.clone().into())
+    }
+}
+
+impl Print for SigmaPropIsProven {
+    fn print(&self, w: &mut dyn Printer) -> Result<Expr, PrintError> {
+        let input = self.input.print(w)?;
+        write!(w, ".isProven")?;
+        Ok(SigmaPropIsProven {
+            input: Box::new(input),
+        }
+        .into())
+    }
+}
@@ -3,7 +3,6 @@
 mod bin_op;
 mod constant;
 mod constant_placeholder;
-pub(crate) mod data;
 mod expr;
 mod global_vars;
 mod method_call;
@@ -15,6 +14,7 @@ pub(crate) mod op_code;
 pub(crate) mod types;
 
 pub mod constant_store;
+pub mod data;
 pub mod sigma_byte_reader;
 pub mod sigma_byte_writer;
 
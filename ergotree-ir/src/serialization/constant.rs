@@ -37,9 +37,15 @@ impl SigmaSerializable for Constant {
 #[allow(clippy::panic, clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use crate::ergo_tree::ErgoTreeVersion;
     use crate::mir::constant::arbitrary::ArbConstantParams;
+    use crate::serialization::constant_store::ConstantStore;
+    use crate::serialization::sigma_byte_reader::SigmaByteReader;
+    use crate::serialization::sigma_byte_writer::SigmaByteWriter;
     use crate::serialization::sigma_serialize_roundtrip;
+    use crate::unsignedbigint256::UnsignedBigInt256;
     use proptest::prelude::*;
+    use std::io::Cursor;
 
     proptest! {
 
@@ -54,6 +60,43 @@ mod tests {
         }
     }
 
+    fn serialize_with_version(c: &Constant, version: ErgoTreeVersion) -> Result<Vec<u8>, String> {
+        let mut data = Vec::new();
+        let mut w = SigmaByteWriter::new(&mut data, None);
+        w.set_tree_version(version);
+        c.sigma_serialize(&mut w).map_err(|e| e.to_string())?;
+        Ok(data)
+    }
+
+    fn parse_with_version(bytes: &[u8], version: ErgoTreeVersion) -> Result<Constant, String> {
+        let mut r = SigmaByteReader::new(Cursor::new(bytes), ConstantStore::empty());
+        r.set_tree_version(version);
+        Constant::sigma_parse(&mut r).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn option_roundtrip_v3() {
+        for c in [
+            Constant::from(Some(42i64)),
+            Constant::from(None::<i64>),
+            Constant::from(Some(vec![1i32, 2])),
+        ] {
+            let bytes = serialize_with_version(&c, ErgoTreeVersion::V3).unwrap();
+            assert_eq!(parse_with_version(&bytes, ErgoTreeVersion::V3).unwrap(), c);
+            assert!(parse_with_version(&bytes, ErgoTreeVersion::V2).is_err());
+        }
+        assert!(serialize_with_version(&Some(1i32).into(), ErgoTreeVersion::V0).is_err());
+    }
+
+    #[test]
+    fn unsigned_bigint_requires_v3() {
+        let c: Constant = UnsignedBigInt256::max_value().into();
+        let bytes = serialize_with_version(&c, ErgoTreeVersion::V3).unwrap();
+        assert_eq!(parse_with_version(&bytes, ErgoTreeVersion::V3).unwrap(), c);
+        assert!(parse_with_version(&bytes, ErgoTreeVersion::V0).is_err());
+        assert!(serialize_with_version(&c, ErgoTreeVersion::V1).is_err());
+    }
+
     #[test]
     fn parse_register_coll_box_issue_695_incorrect_method_id_and_missing_type_spec() {
         // regression test for messed up method id (AvlTree.update <-> remove)
@@ -1,6 +1,9 @@
+//! Data (values without type info) serialization
+
 use sigma_util::AsVecU8;
 
 use crate::chain::ergo_box::ErgoBox;
+use crate::ergo_tree::ErgoTreeVersion;
 use crate::mir::avl_tree_data::AvlTreeData;
 use crate::mir::constant::Literal;
 use crate::mir::constant::TryExtractFromError;
@@ -15,6 +18,7 @@ use crate::serialization::{
 use crate::sigma_protocol::{sigma_boolean::SigmaBoolean, sigma_boolean::SigmaProp};
 use crate::types::stuple;
 use crate::types::stype::SType;
+use crate::unsignedbigint256::UnsignedBigInt256;
 use ergo_chain_types::EcPoint;
 
 use super::sigma_byte_writer::SigmaByteWrite;
@@ -25,6 +29,7 @@ use std::sync::Arc;
 pub struct DataSerializer {}
 
 impl DataSerializer {
+    /// Serialize the value (without its type)
     pub fn sigma_serialize<W: SigmaByteWrite>(c: &Literal, w: &mut W) -> SigmaSerializeResult {
         // for reference see http://github.com/ScorexFoundation/sigmastate-interpreter/blob/25251c1313b0131835f92099f02cef8a5d932b5e/sigmastate/src/main/scala/sigmastate/serialization/DataSerializer.scala#L26-L26
         Ok(match c {
@@ -39,6 +44,11 @@ impl DataSerializer {
                 w.put_u16(bytes.len() as u16)?;
                 w.write_all(&bytes)?
             }
+            Literal::UnsignedBigInt(v) => {
+                let bytes = v.to_bytes_be();
+                w.put_u16(bytes.len() as u16)?;
+                w.write_all(&bytes)?
+            }
             Literal::GroupElement(ecp) => ecp.sigma_serialize(w)?,
             Literal::SigmaProp(s) => s.value().sigma_serialize(w)?,
             Literal::AvlTree(a) => a.sigma_serialize(w)?,
@@ -72,16 +82,27 @@ impl DataSerializer {
             Literal::Tup(items) => items
                 .iter()
                 .try_for_each(|i| DataSerializer::sigma_serialize(i, w))?,
-            // unsupported, see
+            // supported since v6.0 (ErgoTree v3), see
             // https://github.com/ScorexFoundation/sigmastate-interpreter/issues/659
+            Literal::Opt(opt) if w.tree_version() >= ErgoTreeVersion::V6_SOFT_FORK_VERSION => {
+                match &**opt {
+                    Some(v) => {
+                        w.put_u8(1)?;
+                        DataSerializer::sigma_serialize(v, w)?
+                    }
+                    None => w.put_u8(0)?,
+                }
+            }
             Literal::Opt(_) => {
-                return Err(SigmaSerializationError::NotSupported(
-                    "Option serialization is not supported".to_string(),
-                ));
+                return Err(SigmaSerializationError::NotSupported(format!(
+                    "Option serialization is not supported in ErgoTree {}",
+                    w.tree_version()
+                )));
             }
         })
     }
 
+    /// Parse the value of the given type
     pub fn sigma_parse<R: SigmaByteRead>(
         tpe: &SType,
         r: &mut R,
@@ -109,6 +130,21 @@ impl DataSerializer {
                     Err(e) => return Err(SigmaParsingError::ValueOutOfBounds(e)),
                 }
             }
+            SUnsignedBigInt => {
+                let size = r.get_u16()? as usize;
+                if size > UnsignedBigInt256::MAX_SIZE_BYTES {
+                    return Err(SigmaParsingError::ValueOutOfBounds(format!(
+                        "serialized UnsignedBigInt size {0} bytes exceeds 32",
+                        size
+                    )));
+                }
+                let mut buf = vec![0u8; size];
+                r.read_exact(&mut buf)?;
+                match UnsignedBigInt256::from_bytes_be(&buf) {
+                    Ok(x) => Literal::UnsignedBigInt(x),
+                    Err(e) => return Err(SigmaParsingError::ValueOutOfBounds(e)),
+                }
+            }
             SUnit => Literal::Unit,
             SGroupElement => Literal::GroupElement(Box::new(EcPoint::sigma_parse(r)?)),
             SSigmaProp => {
@@ -155,6 +191,19 @@ impl DataSerializer {
             SAvlTree => Literal::AvlTree(Box::new(AvlTreeData::sigma_parse(r)?)),
             STypeVar(_) => return Err(SigmaParsingError::NotSupported("TypeVar data")),
             SAny => return Err(SigmaParsingError::NotSupported("SAny data")),
+            SOption(elem_type) if r.tree_version() >= ErgoTreeVersion::V6_SOFT_FORK_VERSION => {
+                let tag = r.get_u8()?;
+                match tag {
+                    0 => Literal::Opt(Box::new(None)),
+                    1 => Literal::Opt(Box::new(Some(DataSerializer::sigma_parse(elem_type, r)?))),
+                    _ => {
+                        return Err(SigmaParsingError::ValueOutOfBounds(format!(
+                            "invalid Option tag {0}",
+                            tag
+                        )))
+                    }
+                }
+            }
             SOption(_) => return Err(SigmaParsingError::NotSupported("SOption data")),
             SFunc(_) => return Err(SigmaParsingError::NotSupported("SFunc data")),
             SContext => return Err(SigmaParsingError::NotSupported("SContext data")),
@@ -47,6 +47,7 @@ use crate::mir::extract_creation_info::ExtractCreationInfo;
 use crate::mir::extract_id::ExtractId;
 use crate::mir::extract_reg_as::ExtractRegisterAs;
 use crate::mir::extract_script_bytes::ExtractScriptBytes;
+use crate::mir::fun_def::FunDef;
 use crate::mir::func_value::FuncValue;
 use crate::mir::get_var::GetVar;
 use crate::mir::global_vars::GlobalVars;
@@ -54,17 +55,23 @@ use crate::mir::if_op::If;
 use crate::mir::logical_not::LogicalNot;
 use crate::mir::long_to_byte_array::LongToByteArray;
 use crate::mir::method_call::MethodCall;
+use crate::mir::mod_q::ModQ;
+use crate::mir::mod_q::ModQArithOp;
+use crate::mir::mod_q::ModQArithOpKind;
 use crate::mir::multiply_group::MultiplyGroup;
 use crate::mir::negation::Negation;
 use crate::mir::option_get::OptionGet;
 use crate::mir::option_get_or_else::OptionGetOrElse;
 use crate::mir::option_is_defined::OptionIsDefined;
+use crate::mir::option_some::NoneValue;
+use crate::mir::option_some::SomeValue;
 use crate::mir::or::Or;
 use crate::mir::property_call::PropertyCall;
 use crate::mir::select_field::SelectField;
 use crate::mir::sigma_and::SigmaAnd;
 use crate::mir::sigma_or::SigmaOr;
 use crate::mir::sigma_prop_bytes::SigmaPropBytes;
+use crate::mir::sigma_prop_is_proven::SigmaPropIsProven;
 use crate::mir::subst_const::SubstConstants;
 use crate::mir::tree_lookup::TreeLookup;
 use crate::mir::tuple::Tuple;
@@ -149,10 +156,16 @@ impl Expr {
                 OpCode::BIT_OR => Ok(bin_op_sigma_parse(BitOp::BitOr.into(), r)?),
                 OpCode::BIT_AND => Ok(bin_op_sigma_parse(BitOp::BitAnd.into(), r)?),
                 OpCode::BIT_XOR => Ok(bin_op_sigma_parse(BitOp::BitXor.into(), r)?),
+                OpCode::BIT_SHIFT_RIGHT => Ok(bin_op_sigma_parse(BitOp::BitShiftRight.into(), r)?),
+                OpCode::BIT_SHIFT_LEFT => Ok(bin_op_sigma_parse(BitOp::BitShiftLeft.into(), r)?),
+                OpCode::BIT_SHIFT_RIGHT_ZEROED => {
+                    Ok(bin_op_sigma_parse(BitOp::BitShiftRightZeroed.into(), r)?)
+                }
                 OpCode::BLOCK_VALUE => Ok(Expr::BlockValue(BlockValue::sigma_parse(r)?.into())),
                 OpCode::FUNC_VALUE => Ok(Expr::FuncValue(FuncValue::sigma_parse(r)?)),
                 OpCode::APPLY => Ok(Expr::Apply(Apply::sigma_parse(r)?)),
                 OpCode::VAL_DEF => Ok(Expr::ValDef(ValDef::sigma_parse(r)?.into())),
+                FunDef::OP_CODE => Ok(Expr::FunDef(FunDef::sigma_parse(r)?)),
                 OpCode::VAL_USE => Ok(Expr::ValUse(ValUse::sigma_parse(r)?)),
                 ExtractAmount::OP_CODE => Ok(Expr::ExtractAmount(ExtractAmount::sigma_parse(r)?)),
                 OpCode::SELECT_FIELD => Ok(Expr::SelectField(SelectField::sigma_parse(r)?.into())),
@@ -194,11 +207,26 @@ impl Expr {
                 XorOf::OP_CODE => Ok(XorOf::sigma_parse(r)?.into()),
                 TreeLookup::OP_CODE => Ok(TreeLookup::sigma_parse(r)?.into()),
                 CreateAvlTree::OP_CODE => Ok(CreateAvlTree::sigma_parse(r)?.into()),
-                o => Err(SigmaParsingError::NotImplementedOpCode(format!(
-                    "{0}(shift {1})",
-                    o.value(),
-                    o.shift()
-                ))),
+                ModQ::OP_CODE => Ok(ModQ::sigma_parse(r)?.into()),
+                OpCode::PLUS_MOD_Q => {
+                    Ok(ModQArithOp::sigma_parse_with_kind(ModQArithOpKind::Plus, r)?.into())
+                }
+                OpCode::MINUS_MOD_Q => {
+                    Ok(ModQArithOp::sigma_parse_with_kind(ModQArithOpKind::Minus, r)?.into())
+                }
+                // reserved in the reference implementation, which has no serializer for them,
+                // so they are only accepted as a soft-fork (see `ValidationRule::CheckValidOpCode`)
+                OpCode::COLL_SHIFT_RIGHT
+                | OpCode::COLL_SHIFT_LEFT
+                | OpCode::COLL_SHIFT_RIGHT_ZEROED
+                | OpCode::COLL_ROTATE_LEFT
+                | OpCode::COLL_ROTATE_RIGHT => {
+                    Err(SigmaParsingError::InvalidOpCode(op_code.value()))
+                }
+                SomeValue::OP_CODE => Ok(SomeValue::sigma_parse(r)?.into()),
+                NoneValue::OP_CODE => Ok(NoneValue::sigma_parse(r)?.into()),
+                SigmaPropIsProven::OP_CODE => Ok(SigmaPropIsProven::sigma_parse(r)?.into()),
+                o => Err(SigmaParsingError::InvalidOpCode(o.value())),
             }
         };
         res
@@ -296,6 +324,19 @@ impl SigmaSerializable for Expr {
             Expr::ExtractBytesWithNoRef(op) => op.sigma_serialize_w_opcode(w),
             Expr::TreeLookup(op) => op.sigma_serialize_w_opcode(w),
             Expr::CreateAvlTree(op) => op.sigma_serialize_w_opcode(w),
+            Expr::FunDef(op) => op.sigma_serialize_w_opcode(w),
+            Expr::ModQ(op) => op.sigma_serialize_w_opcode(w),
+            Expr::ModQArithOp(op) => {
+                op.op_code().sigma_serialize(w)?;
+                op.sigma_serialize(w)
+            }
+            Expr::CollShift(op) => {
+                op.op_code().sigma_serialize(w)?;
+                op.sigma_serialize(w)
+            }
+            Expr::SomeValue(op) => op.sigma_serialize_w_opcode(w),
+            Expr::NoneValue(op) => op.sigma_serialize_w_opcode(w),
+            Expr::SigmaPropIsProven(op) => op.sigma_serialize_w_opcode(w),
         }
     }
 
@@ -328,6 +369,7 @@ impl<T: HasOpCode> HasOpCode for Spanned<T> {
 mod tests {
     use crate::chain::address::AddressEncoder;
     use crate::chain::address::NetworkPrefix;
+    use crate::ergo_tree::ErgoTree;
 
     use super::*;
     use crate::serialization::sigma_serialize_roundtrip;
@@ -506,4 +548,167 @@ mod tests {
         let script = addr.script().unwrap().proposition().unwrap();
         dbg!(&script);
     }
+
+    #[test]
+    fn parse_tree_with_v4_opcodes() {
+        // sigmaProp(
+        //   sigmaProp(true).isProven &&
+        //   Some(1).isDefined &&
+        //   plusModQ(42.toBigInt, 1.toBigInt) == 43.toBigInt &&
+        //   (1 << 1) == 2 &&
+        //   !None[Int].isDefined &&
+        //   { def v1[T] = 1; v1 } == 1
+        // )
+        let tree_bytes = base16::decode("00d1edcfd10101ede6de0402ed93e806012a06010106012bed93f7040204020404edefe6df0493d801d70101670154040272010402").unwrap();
+        let tree = ErgoTree::sigma_parse_bytes(&tree_bytes).unwrap();
+        let expr = tree.proposition().unwrap();
+        assert_eq!(sigma_serialize_roundtrip(&expr), expr);
+        assert_eq!(tree.sigma_serialize_bytes().unwrap(), tree_bytes);
+    }
+
+    #[test]
+    fn pre_v4_mainnet_trees_roundtrip() {
+        // none of the known mainnet trees use the v4 opcodes, see the synthetic trees below
+        let trees = [
+            // emission contract (block 540000)
+            "101004020e36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ea02d192a39a8cc7a7017300730110010204020404040004c0fd4f05808c82f5f6030580b8c9e5ae040580f882ad16040204c0944004c0f407040004000580f882ad16d19683030191a38cc7a7019683020193c2b2a57300007473017302830108cdeeac93a38cc7b2a573030001978302019683040193b1a5730493c2a7c2b2a573050093958fa3730673079973089c73097e9a730a9d99a3730b730c0599c1a7c1b2a5730d00938cc7b2a5730e0001a390c1a7730f",
+            // miner reward (block 540000)
+            "100204a00b08cd02b3a06d6eaa8671431ba1db4dd427a77f75a5c2acbd71bfb725d38adc2b55f669ea02d192a39a8cc7a70173007301",
+            // miner fee (tx 0e6acf3f18b95bdc5bb1b060baa1eafe53bd89fb08b0e86d6cc00fbdd9e43189)
+            "1005040004000e36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ea02d192a39a8cc7a701730073011001020402d19683030193a38cc7b2a57300000193c2b2a57301007473027303830108cdeeac93b1a57304",
+            // oracle datapoint (tx 0e6acf3f18b95bdc5bb1b060baa1eafe53bd89fb08b0e86d6cc00fbdd9e43189)
+            "100604000400050004000e20b662db51cf2dc39f110a021c2a31c74f0a1a18ffffbf73e8a051a7b8c0f09ebc0e2079974b2314c531e62776e6bc4babff35b37b178cebf0976fc0f416ff34ddbc4fd803d601b2a5730000d602e4c6a70407d603b2db6501fe730100ea02d1ededededed93e4c672010407720293e4c67201050ec5720391e4c672010605730293c27201c2a793db63087201db6308a7ed938cb2db6308720373030001730493cbc272037305cd7202",
+            // multi-sig (issue 597)
+            "100504000400040004000402d804d601b2a5730000d602e4c6a7041ad603e4c6a70510d604ad7202d901040ecdee7204ea02d19683020193c27201c2a7938cb2db63087201730100018cb2db6308a773020001eb02ea02d19683020193e4c67201041a720293e4c672010510720398b27203730300720498b272037304007204",
+        ];
+        for tree_hex in trees {
+            let tree_bytes = base16::decode(tree_hex).unwrap();
+            let tree = ErgoTree::sigma_parse_bytes(&tree_bytes).unwrap();
+            assert!(tree.proposition().is_ok(), "{}", tree_hex);
+            assert_eq!(tree.sigma_serialize_bytes().unwrap(), tree_bytes);
+        }
+    }
+
+    #[test]
+    fn synthetic_v4_opcodes_in_segregated_trees_roundtrip() {
+        use crate::bigint256::BigInt256;
+        use crate::ergo_tree::ErgoTreeHeader;
+        use crate::mir::bin_op::BinOp;
+        use crate::mir::bin_op::BinOpKind;
+        use crate::mir::fun_def::FunDef;
+        use crate::mir::global_vars::GlobalVars;
+        use crate::mir::mod_q::ModQArithOpKind;
+        use crate::mir::option_is_defined::OptionIsDefined;
+        use crate::mir::val_use::ValUse;
+        use crate::types::stype::SType;
+        use crate::types::stype_param::STypeVar;
+
+        let eq = |left: Expr, right: Expr| -> Expr {
+            BinOp {
+                kind: BinOpKind::Relation(RelationOp::Eq),
+                left: left.into(),
+                right: right.into(),
+            }
+            .into()
+        };
+        let big_int = |v: i64| -> Expr { Expr::Const(BigInt256::from(v).into()) };
+        let height = || -> Expr { GlobalVars::Height.into() };
+
+        let mut cases: Vec<Expr> = vec![
+            eq(
+                ModQ {
+                    input: big_int(42).into(),
+                }
+                .into(),
+                big_int(42),
+            ),
+            eq(
+                ModQArithOp::new(ModQArithOpKind::Plus, big_int(42), big_int(1))
+                    .unwrap()
+                    .into(),
+                big_int(43),
+            ),
+            eq(
+                ModQArithOp::new(ModQArithOpKind::Minus, big_int(42), big_int(1))
+                    .unwrap()
+                    .into(),
+                big_int(41),
+            ),
+            OptionIsDefined {
+                input: Box::new(
+                    SomeValue {
+                        input: height().into(),
+                    }
+                    .into(),
+                ),
+            }
+            .into(),
+            eq(
+                OptionIsDefined {
+                    input: Box::new(
+                        NoneValue {
+                            elem_tpe: SType::SInt,
+                        }
+                        .into(),
+                    ),
+                }
+                .into(),
+                false.into(),
+            ),
+            eq(
+                BlockValue {
+                    items: vec![FunDef {
+                        id: 1.into(),
+                        tpe_args: vec![STypeVar::t()],
+                        rhs: height().into(),
+                    }
+                    .into()],
+                    result: Box::new(
+                        ValUse {
+                            val_id: 1.into(),
+                            tpe: SType::SInt,
+                        }
+                        .into(),
+                    ),
+                }
+                .into(),
+                1.into(),
+            ),
+            SigmaPropIsProven {
+                input: Box::new(
+                    BoolToSigmaProp {
+                        input: Box::new(eq(height(), 1.into())),
+                    }
+                    .into(),
+                ),
+            }
+            .into(),
+        ];
+        for op in [
+            BitOp::BitShiftLeft,
+            BitOp::BitShiftRight,
+            BitOp::BitShiftRightZeroed,
+        ] {
+            cases.push(eq(
+                BinOp {
+                    kind: BinOpKind::Bit(op),
+                    left: height().into(),
+                    right: Box::new(1.into()),
+                }
+                .into(),
+                2.into(),
+            ));
+        }
+        // the node stores trees with the constants segregated into the constants table
+        for case in cases {
+            let prop: Expr = BoolToSigmaProp { input: case.into() }.into();
+            for header in [ErgoTreeHeader::v0(true), ErgoTreeHeader::v1(true)] {
+                let tree = ErgoTree::new(header, &prop).unwrap();
+                let tree_bytes = tree.sigma_serialize_bytes().unwrap();
+                let parsed = ErgoTree::sigma_parse_bytes(&tree_bytes).unwrap();
+                assert_eq!(parsed.proposition().unwrap(), prop);
+                assert_eq!(parsed.sigma_serialize_bytes().unwrap(), tree_bytes);
+            }
+        }
+    }
 }
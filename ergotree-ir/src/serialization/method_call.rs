@@ -2,12 +2,16 @@ use crate::mir::expr::Expr;
 use crate::mir::method_call::MethodCall;
 use crate::types::smethod::MethodId;
 use crate::types::smethod::SMethod;
+use crate::types::stype::SType;
+use crate::types::stype_param::STypeVar;
+use std::collections::HashMap;
 
 use super::sigma_byte_reader::SigmaByteRead;
 use super::sigma_byte_writer::SigmaByteWrite;
 use super::types::TypeCode;
 use super::SigmaParsingError;
 use super::SigmaSerializable;
+use super::SigmaSerializationError;
 use super::SigmaSerializeResult;
 
 impl SigmaSerializable for MethodCall {
@@ -16,6 +20,17 @@ impl SigmaSerializable for MethodCall {
         self.method.method_id().sigma_serialize(w)?;
         self.obj.sigma_serialize(w)?;
         self.args.sigma_serialize(w)?;
+        for tpe_var in self.method.explicit_type_args() {
+            match self.explicit_type_args.get(tpe_var) {
+                Some(tpe) => tpe.sigma_serialize(w)?,
+                None => {
+                    return Err(SigmaSerializationError::NotSupported(format!(
+                        "MethodCall: missing explicit type argument {:?}",
+                        tpe_var
+                    )))
+                }
+            }
+        }
         Ok(())
     }
 
@@ -25,8 +40,16 @@ impl SigmaSerializable for MethodCall {
         let obj = Expr::sigma_parse(r)?;
         let args = Vec::<Expr>::sigma_parse(r)?;
         let arg_types = args.iter().map(|arg| arg.tpe()).collect();
-        let method = SMethod::from_ids(type_id, method_id)?.specialize_for(obj.tpe(), arg_types)?;
-        Ok(MethodCall::new(obj, method, args)?)
+        let method = SMethod::from_ids_for_version(type_id, method_id, r.tree_version())?;
+        let explicit_type_args = method
+            .explicit_type_args()
+            .iter()
+            .map(|tpe_var| Ok((tpe_var.clone(), SType::sigma_parse(r)?)))
+            .collect::<Result<HashMap<STypeVar, SType>, SigmaParsingError>>()?;
+        let method = method
+            .with_concrete_types(&explicit_type_args)
+            .specialize_for(obj.tpe(), arg_types)?;
+        Ok(MethodCall::new(obj, method, args)?.with_explicit_type_args(explicit_type_args)?)
     }
 }
 
@@ -34,10 +57,18 @@ impl SigmaSerializable for MethodCall {
 #[cfg(feature = "arbitrary")]
 #[allow(clippy::unwrap_used)]
 mod tests {
+    use std::io::Cursor;
+
+    use crate::ergo_tree::ErgoTreeVersion;
     use crate::mir::expr::Expr;
     use crate::mir::method_call::MethodCall;
+    use crate::serialization::constant_store::ConstantStore;
+    use crate::serialization::sigma_byte_reader::SigmaByteRead;
+    use crate::serialization::sigma_byte_reader::SigmaByteReader;
     use crate::serialization::sigma_serialize_roundtrip;
+    use crate::serialization::SigmaSerializable;
     use crate::types::scoll;
+    use crate::types::sglobal;
     use crate::types::stype::SType;
     use crate::types::stype_param::STypeVar;
 
@@ -54,4 +85,45 @@ mod tests {
         .into();
         assert_eq![sigma_serialize_roundtrip(&mc), mc];
     }
+
+    fn from_big_endian_bytes_call() -> Expr {
+        MethodCall::new(
+            Expr::Global,
+            sglobal::FROM_BIG_ENDIAN_BYTES_METHOD.clone(),
+            vec![vec![1i8, 2i8].into()],
+        )
+        .unwrap()
+        .with_explicit_type_args([(STypeVar::t(), SType::SShort)].into_iter().collect())
+        .unwrap()
+        .into()
+    }
+
+    #[test]
+    fn ser_roundtrip_explicit_type_args() {
+        let mc = from_big_endian_bytes_call();
+        assert_eq!(mc.tpe(), SType::SShort);
+        assert_eq![sigma_serialize_roundtrip(&mc), mc];
+    }
+
+    #[test]
+    fn missing_explicit_type_args() {
+        assert!(MethodCall::new(
+            Expr::Global,
+            sglobal::FROM_BIG_ENDIAN_BYTES_METHOD.clone(),
+            vec![vec![1i8, 2i8].into()],
+        )
+        .unwrap()
+        .with_explicit_type_args(Default::default())
+        .is_err());
+    }
+
+    #[test]
+    fn v6_method_parse_requires_v3_tree() {
+        let bytes = from_big_endian_bytes_call()
+            .sigma_serialize_bytes()
+            .unwrap();
+        let mut r = SigmaByteReader::new(Cursor::new(&bytes[..]), ConstantStore::empty());
+        r.set_tree_version(ErgoTreeVersion::V2);
+        assert!(Expr::sigma_parse(&mut r).is_err());
+    }
 }
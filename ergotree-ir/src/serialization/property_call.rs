@@ -22,8 +22,8 @@ impl SigmaSerializable for PropertyCall {
         let type_id = TypeCode::sigma_parse(r)?;
         let method_id = MethodId::sigma_parse(r)?;
         let obj = Expr::sigma_parse(r)?;
-        let method =
-            SMethod::from_ids(type_id, method_id)?.specialize_for(obj.tpe(), Vec::new())?;
+        let method = SMethod::from_ids_for_version(type_id, method_id, r.tree_version())?
+            .specialize_for(obj.tpe(), Vec::new())?;
         Ok(PropertyCall::new(obj, method)?)
     }
 }
@@ -1,6 +1,7 @@
 use super::op_code::OpCode;
 use super::sigma_byte_writer::SigmaByteWrite;
 use super::SigmaSerializationError;
+use crate::ergo_tree::ErgoTreeVersion;
 use crate::serialization::SigmaSerializeResult;
 use crate::serialization::{
     sigma_byte_reader::SigmaByteRead, SigmaParsingError, SigmaSerializable,
@@ -25,6 +26,7 @@ pub enum TypeCode {
     SBIGINT = 6,
     SGROUP_ELEMENT = 7,
     SSIGMAPROP = 8,
+    SUNSIGNED_BIGINT = 9,
 
     COLL = (TypeCode::MAX_PRIM_TYPECODE + 1) * TypeCode::COLLECTION_CONSTR_ID, // 12 * 1
     COLL_BOOL = TypeCode::COLL as u8 + TypeCode::SBOOLEAN as u8,               // 13
@@ -35,6 +37,7 @@ pub enum TypeCode {
     COLL_BIGINT = TypeCode::COLL as u8 + TypeCode::SBIGINT as u8,              // 18
     COLL_GROUP_ELEMENT = TypeCode::COLL as u8 + TypeCode::SGROUP_ELEMENT as u8, // 19
     COLL_SIGMAPROP = TypeCode::COLL as u8 + TypeCode::SSIGMAPROP as u8,        // 20
+    COLL_UNSIGNED_BIGINT = TypeCode::COLL as u8 + TypeCode::SUNSIGNED_BIGINT as u8, // 21
 
     NESTED_COLL_BOOL = TypeCode::NESTED_COLL + TypeCode::SBOOLEAN as u8, // 25
     NESTED_COLL_BYTE = TypeCode::NESTED_COLL + TypeCode::SBYTE as u8,    // 26
@@ -44,6 +47,7 @@ pub enum TypeCode {
     NESTED_COLL_BIGINT = TypeCode::NESTED_COLL + TypeCode::SBIGINT as u8, // 30
     NESTED_COLL_GROUP_ELEMENT = TypeCode::NESTED_COLL + TypeCode::SGROUP_ELEMENT as u8, // 31
     NESTED_COLL_SIGMAPROP = TypeCode::NESTED_COLL + TypeCode::SSIGMAPROP as u8, // 32
+    NESTED_COLL_UNSIGNED_BIGINT = TypeCode::NESTED_COLL + TypeCode::SUNSIGNED_BIGINT as u8, // 33
 
     OPTION = (TypeCode::MAX_PRIM_TYPECODE + 1) * TypeCode::OPTION_CONSTR_ID, // 12 * 3 = 36
     OPTION_BOOL = TypeCode::OPTION as u8 + TypeCode::SBOOLEAN as u8,         // 37
@@ -54,6 +58,7 @@ pub enum TypeCode {
     OPTION_BIGINT = TypeCode::OPTION as u8 + TypeCode::SBIGINT as u8,        // 42
     OPTION_GROUP_ELEMENT = TypeCode::OPTION as u8 + TypeCode::SGROUP_ELEMENT as u8, // 43
     OPTION_SIGMAPROP = TypeCode::OPTION as u8 + TypeCode::SSIGMAPROP as u8,  // 44
+    OPTION_UNSIGNED_BIGINT = TypeCode::OPTION as u8 + TypeCode::SUNSIGNED_BIGINT as u8, // 45
 
     OPTION_COLL_BOOL = TypeCode::OPTION_COLLECTION + TypeCode::SBOOLEAN as u8, // 49
     OPTION_COLL_BYTE = TypeCode::OPTION_COLLECTION + TypeCode::SBYTE as u8,    // 50
@@ -63,6 +68,7 @@ pub enum TypeCode {
     OPTION_COLL_BIGINT = TypeCode::OPTION_COLLECTION + TypeCode::SBIGINT as u8, // 54
     OPTION_COLL_GROUP_ELEMENT = TypeCode::OPTION_COLLECTION + TypeCode::SGROUP_ELEMENT as u8, // 55
     OPTION_COLL_SIGMAPROP = TypeCode::OPTION_COLLECTION + TypeCode::SSIGMAPROP as u8, // 56
+    OPTION_COLL_UNSIGNED_BIGINT = TypeCode::OPTION_COLLECTION + TypeCode::SUNSIGNED_BIGINT as u8, // 57
 
     TUPLE_PAIR1 = (TypeCode::MAX_PRIM_TYPECODE + 1) * TypeCode::TUPLE_PAIR1_CONSTR_ID, // 12 * 5 = 60
     TUPLE_PAIR1_BOOL = TypeCode::TUPLE_PAIR1 as u8 + TypeCode::SBOOLEAN as u8,         // 61
@@ -73,6 +79,7 @@ pub enum TypeCode {
     TUPLE_PAIR1_BIGINT = TypeCode::TUPLE_PAIR1 as u8 + TypeCode::SBIGINT as u8,        // 66
     TUPLE_PAIR1_GROUP_ELEMENT = TypeCode::TUPLE_PAIR1 as u8 + TypeCode::SGROUP_ELEMENT as u8, // 67
     TUPLE_PAIR1_SIGMAPROP = TypeCode::TUPLE_PAIR1 as u8 + TypeCode::SSIGMAPROP as u8,  // 68
+    TUPLE_PAIR1_UNSIGNED_BIGINT = TypeCode::TUPLE_PAIR1 as u8 + TypeCode::SUNSIGNED_BIGINT as u8, // 69
 
     TUPLE_TRIPLE = Self::TUPLE_PAIR2, // 72
 
@@ -84,6 +91,7 @@ pub enum TypeCode {
     TUPLE_PAIR2_BIGINT = TypeCode::TUPLE_PAIR2 + TypeCode::SBIGINT as u8, // 78
     TUPLE_PAIR2_GROUP_ELEMENT = TypeCode::TUPLE_PAIR2 + TypeCode::SGROUP_ELEMENT as u8, // 79
     TUPLE_PAIR2_SIGMAPROP = TypeCode::TUPLE_PAIR2 + TypeCode::SSIGMAPROP as u8, // 80
+    TUPLE_PAIR2_UNSIGNED_BIGINT = TypeCode::TUPLE_PAIR2 + TypeCode::SUNSIGNED_BIGINT as u8, // 81
 
     TUPLE_QUADRUPLE = Self::TUPLE_PAIR_SYMMETRIC, // 84
 
@@ -96,6 +104,8 @@ pub enum TypeCode {
     TUPLE_PAIR_SYMMETRIC_GROUP_ELEMENT =
         TypeCode::TUPLE_PAIR_SYMMETRIC + TypeCode::SGROUP_ELEMENT as u8, // 91
     TUPLE_PAIR_SYMMETRIC_SIGMAPROP = TypeCode::TUPLE_PAIR_SYMMETRIC + TypeCode::SSIGMAPROP as u8, // 92
+    TUPLE_PAIR_SYMMETRIC_UNSIGNED_BIGINT =
+        TypeCode::TUPLE_PAIR_SYMMETRIC + TypeCode::SUNSIGNED_BIGINT as u8, // 93
 
     TUPLE = (TypeCode::MAX_PRIM_TYPECODE + 1) * 8, // 12 * 8 = 96
 
@@ -120,7 +130,7 @@ impl TypeCode {
 
     /// Type code of the last valid prim type so that (1 to LastPrimTypeCode) is a range of valid codes.
     #[allow(dead_code)]
-    const LAST_PRIM_TYPECODE: u8 = 8;
+    const LAST_PRIM_TYPECODE: u8 = 9;
 
     /// Upper limit of the interval of valid type codes for primitive types
     const MAX_PRIM_TYPECODE: u8 = 11;
@@ -156,10 +166,30 @@ impl TypeCode {
     pub(crate) const fn value(&self) -> u8 {
         *self as u8
     }
+
+    /// Minimal ErgoTree version where this type code can be used
+    pub(crate) fn min_version(&self) -> ErgoTreeVersion {
+        let v = self.value();
+        // UnsignedBigInt and all the types with UnsignedBigInt embedded in the type code
+        if v < TypeCode::TUPLE.value()
+            && v % (TypeCode::MAX_PRIM_TYPECODE + 1) == TypeCode::SUNSIGNED_BIGINT.value()
+        {
+            ErgoTreeVersion::V6_SOFT_FORK_VERSION
+        } else {
+            ErgoTreeVersion::V0
+        }
+    }
 }
 
 impl SigmaSerializable for TypeCode {
     fn sigma_serialize<W: SigmaByteWrite>(&self, w: &mut W) -> SigmaSerializeResult {
+        if self.min_version() > w.tree_version() {
+            return Err(SigmaSerializationError::NotSupported(format!(
+                "type code {} is not supported in ErgoTree {}",
+                self.value(),
+                w.tree_version()
+            )));
+        }
         w.put_u8(self.value())?;
         Ok(())
     }
@@ -178,6 +208,9 @@ impl SType {
         c: TypeCode,
     ) -> Result<Self, SigmaParsingError> {
         use SType::*;
+        if c.min_version() > r.tree_version() {
+            return Err(SigmaParsingError::InvalidTypeCode(c.value()));
+        }
         Ok(match c {
             TypeCode::SBOOLEAN => SBoolean,
             TypeCode::SBYTE => SByte,
@@ -187,6 +220,7 @@ impl SType {
             TypeCode::SBIGINT => SBigInt,
             TypeCode::SGROUP_ELEMENT => SGroupElement,
             TypeCode::SSIGMAPROP => SSigmaProp,
+            TypeCode::SUNSIGNED_BIGINT => SUnsignedBigInt,
 
             TypeCode::COLL => SColl(SType::sigma_parse(r)?.into()),
             TypeCode::COLL_BOOL => SColl(SBoolean.into()),
@@ -197,6 +231,7 @@ impl SType {
             TypeCode::COLL_BIGINT => SColl(SBigInt.into()),
             TypeCode::COLL_GROUP_ELEMENT => SColl(SGroupElement.into()),
             TypeCode::COLL_SIGMAPROP => SColl(SSigmaProp.into()),
+            TypeCode::COLL_UNSIGNED_BIGINT => SColl(SUnsignedBigInt.into()),
 
             TypeCode::NESTED_COLL_BOOL => SColl(SColl(SBoolean.into()).into()),
             TypeCode::NESTED_COLL_BYTE => SColl(SColl(SByte.into()).into()),
@@ -206,6 +241,7 @@ impl SType {
             TypeCode::NESTED_COLL_BIGINT => SColl(SColl(SBigInt.into()).into()),
             TypeCode::NESTED_COLL_GROUP_ELEMENT => SColl(SColl(SGroupElement.into()).into()),
             TypeCode::NESTED_COLL_SIGMAPROP => SColl(SColl(SSigmaProp.into()).into()),
+            TypeCode::NESTED_COLL_UNSIGNED_BIGINT => SColl(SColl(SUnsignedBigInt.into()).into()),
 
             TypeCode::OPTION => SOption(SType::sigma_parse(r)?.into()),
             TypeCode::OPTION_BOOL => SOption(SBoolean.into()),
@@ -216,6 +252,7 @@ impl SType {
             TypeCode::OPTION_BIGINT => SOption(SBigInt.into()),
             TypeCode::OPTION_GROUP_ELEMENT => SOption(SGroupElement.into()),
             TypeCode::OPTION_SIGMAPROP => SOption(SSigmaProp.into()),
+            TypeCode::OPTION_UNSIGNED_BIGINT => SOption(SUnsignedBigInt.into()),
 
             TypeCode::OPTION_COLL_BOOL => SOption(SColl(SBoolean.into()).into()),
             TypeCode::OPTION_COLL_BYTE => SOption(SColl(SByte.into()).into()),
@@ -225,6 +262,7 @@ impl SType {
             TypeCode::OPTION_COLL_BIGINT => SOption(SColl(SBigInt.into()).into()),
             TypeCode::OPTION_COLL_GROUP_ELEMENT => SOption(SColl(SGroupElement.into()).into()),
             TypeCode::OPTION_COLL_SIGMAPROP => SOption(SColl(SSigmaProp.into()).into()),
+            TypeCode::OPTION_COLL_UNSIGNED_BIGINT => SOption(SColl(SUnsignedBigInt.into()).into()),
 
             TypeCode::TUPLE_PAIR1 => STuple(stuple::STuple::pair(
                 SType::sigma_parse(r)?,
@@ -252,6 +290,10 @@ impl SType {
             TypeCode::TUPLE_PAIR1_SIGMAPROP => {
                 STuple(stuple::STuple::pair(SSigmaProp, SType::sigma_parse(r)?))
             }
+            TypeCode::TUPLE_PAIR1_UNSIGNED_BIGINT => STuple(stuple::STuple::pair(
+                SUnsignedBigInt,
+                SType::sigma_parse(r)?,
+            )),
 
             TypeCode::TUPLE_TRIPLE => STuple(stuple::STuple::triple(
                 SType::sigma_parse(r)?,
@@ -281,6 +323,10 @@ impl SType {
             TypeCode::TUPLE_PAIR2_SIGMAPROP => {
                 STuple(stuple::STuple::pair(SType::sigma_parse(r)?, SSigmaProp))
             }
+            TypeCode::TUPLE_PAIR2_UNSIGNED_BIGINT => STuple(stuple::STuple::pair(
+                SType::sigma_parse(r)?,
+                SUnsignedBigInt,
+            )),
 
             TypeCode::TUPLE_QUADRUPLE => STuple(stuple::STuple::quadruple(
                 SType::sigma_parse(r)?,
@@ -301,6 +347,9 @@ impl SType {
             TypeCode::TUPLE_PAIR_SYMMETRIC_SIGMAPROP => {
                 STuple(stuple::STuple::pair(SSigmaProp, SSigmaProp))
             }
+            TypeCode::TUPLE_PAIR_SYMMETRIC_UNSIGNED_BIGINT => {
+                STuple(stuple::STuple::pair(SUnsignedBigInt, SUnsignedBigInt))
+            }
 
             TypeCode::TUPLE => {
                 let len = r.get_u8()?;
@@ -355,6 +404,7 @@ impl SigmaSerializable for SType {
             SType::SBigInt => TypeCode::SBIGINT.sigma_serialize(w),
             SType::SGroupElement => TypeCode::SGROUP_ELEMENT.sigma_serialize(w),
             SType::SSigmaProp => TypeCode::SSIGMAPROP.sigma_serialize(w),
+            SType::SUnsignedBigInt => TypeCode::SUNSIGNED_BIGINT.sigma_serialize(w),
             SType::SBox => TypeCode::SBOX.sigma_serialize(w),
             SType::SAvlTree => TypeCode::SAVL_TREE.sigma_serialize(w),
             SType::SContext => TypeCode::SCONTEXT.sigma_serialize(w),
@@ -370,6 +420,7 @@ impl SigmaSerializable for SType {
                 SBigInt => TypeCode::OPTION_BIGINT.sigma_serialize(w),
                 SGroupElement => TypeCode::OPTION_GROUP_ELEMENT.sigma_serialize(w),
                 SSigmaProp => TypeCode::OPTION_SIGMAPROP.sigma_serialize(w),
+                SUnsignedBigInt => TypeCode::OPTION_UNSIGNED_BIGINT.sigma_serialize(w),
                 SColl(inner_elem_type) => match &**inner_elem_type {
                     SBoolean => TypeCode::OPTION_COLL_BOOL.sigma_serialize(w),
                     SByte => TypeCode::OPTION_COLL_BYTE.sigma_serialize(w),
@@ -379,6 +430,7 @@ impl SigmaSerializable for SType {
                     SBigInt => TypeCode::OPTION_COLL_BIGINT.sigma_serialize(w),
                     SGroupElement => TypeCode::OPTION_COLL_GROUP_ELEMENT.sigma_serialize(w),
                     SSigmaProp => TypeCode::OPTION_COLL_SIGMAPROP.sigma_serialize(w),
+                    SUnsignedBigInt => TypeCode::OPTION_COLL_UNSIGNED_BIGINT.sigma_serialize(w),
                     STypeVar(_) | SAny | SUnit | SBox | SAvlTree | SOption(_) | SColl(_)
                     | STuple(_) | SFunc(_) | SContext | SHeader | SPreHeader | SGlobal => {
                         // if not "embeddable" type fallback to generic Option type code following
@@ -405,6 +457,7 @@ impl SigmaSerializable for SType {
                 SBigInt => TypeCode::COLL_BIGINT.sigma_serialize(w),
                 SGroupElement => TypeCode::COLL_GROUP_ELEMENT.sigma_serialize(w),
                 SSigmaProp => TypeCode::COLL_SIGMAPROP.sigma_serialize(w),
+                SUnsignedBigInt => TypeCode::COLL_UNSIGNED_BIGINT.sigma_serialize(w),
                 SColl(inner_elem_type) => match &**inner_elem_type {
                     SBoolean => TypeCode::NESTED_COLL_BOOL.sigma_serialize(w),
                     SByte => TypeCode::NESTED_COLL_BYTE.sigma_serialize(w),
@@ -414,6 +467,7 @@ impl SigmaSerializable for SType {
                     SBigInt => TypeCode::NESTED_COLL_BIGINT.sigma_serialize(w),
                     SGroupElement => TypeCode::NESTED_COLL_GROUP_ELEMENT.sigma_serialize(w),
                     SSigmaProp => TypeCode::NESTED_COLL_SIGMAPROP.sigma_serialize(w),
+                    SUnsignedBigInt => TypeCode::NESTED_COLL_UNSIGNED_BIGINT.sigma_serialize(w),
                     STypeVar(_) | SAny | SUnit | SBox | SAvlTree | SOption(_) | SColl(_)
                     | STuple(_) | SFunc(_) | SContext | SHeader | SPreHeader | SGlobal => {
                         // if not "embeddable" type fallback to generic Coll type code following
@@ -443,6 +497,9 @@ impl SigmaSerializable for SType {
                     (SSigmaProp, SSigmaProp) => {
                         TypeCode::TUPLE_PAIR_SYMMETRIC_SIGMAPROP.sigma_serialize(w)
                     }
+                    (SUnsignedBigInt, SUnsignedBigInt) => {
+                        TypeCode::TUPLE_PAIR_SYMMETRIC_UNSIGNED_BIGINT.sigma_serialize(w)
+                    }
 
                     (SBoolean, t2) => {
                         TypeCode::TUPLE_PAIR1_BOOL.sigma_serialize(w)?;
@@ -476,6 +533,10 @@ impl SigmaSerializable for SType {
                         TypeCode::TUPLE_PAIR1_SIGMAPROP.sigma_serialize(w)?;
                         t2.sigma_serialize(w)
                     }
+                    (SUnsignedBigInt, t2) => {
+                        TypeCode::TUPLE_PAIR1_UNSIGNED_BIGINT.sigma_serialize(w)?;
+                        t2.sigma_serialize(w)
+                    }
 
                     (t1, SBoolean) => {
                         TypeCode::TUPLE_PAIR2_BOOL.sigma_serialize(w)?;
@@ -509,6 +570,10 @@ impl SigmaSerializable for SType {
                         TypeCode::TUPLE_PAIR2_SIGMAPROP.sigma_serialize(w)?;
                         t1.sigma_serialize(w)
                     }
+                    (t1, SUnsignedBigInt) => {
+                        TypeCode::TUPLE_PAIR2_UNSIGNED_BIGINT.sigma_serialize(w)?;
+                        t1.sigma_serialize(w)
+                    }
                     (
                         STypeVar(_) | SAny | SUnit | SBox | SAvlTree | SOption(_) | SColl(_)
                         | STuple(_) | SFunc(_) | SContext | SHeader | SPreHeader | SGlobal,
@@ -199,6 +199,13 @@ impl Expr {
             Expr::XorOf(_) => SourceSpan::empty(),
             Expr::TreeLookup(op) => op.source_span,
             Expr::CreateAvlTree(_) => SourceSpan::empty(),
+            Expr::FunDef(_) => SourceSpan::empty(),
+            Expr::ModQ(_) => SourceSpan::empty(),
+            Expr::ModQArithOp(_) => SourceSpan::empty(),
+            Expr::CollShift(_) => SourceSpan::empty(),
+            Expr::SomeValue(_) => SourceSpan::empty(),
+            Expr::NoneValue(_) => SourceSpan::empty(),
+            Expr::SigmaPropIsProven(_) => SourceSpan::empty(),
         }
     }
 }
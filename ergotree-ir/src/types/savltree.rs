@@ -1,3 +1,4 @@
+use crate::ergo_tree::ErgoTreeVersion;
 use crate::serialization::types::TypeCode;
 
 use super::sfunc::SFunc;
@@ -76,6 +77,8 @@ lazy_static! {
             t_range: SType::SColl(Box::new(SType::SByte)).into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// AvlTree.digest
     pub static ref DIGEST_METHOD: SMethod =
@@ -91,6 +94,8 @@ lazy_static! {
             t_range: SType::SByte.into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// AvlTree.enabledOperations
     pub static ref ENABLED_OPERATIONS_METHOD: SMethod =
@@ -106,6 +111,8 @@ lazy_static! {
             t_range: SType::SInt.into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// AvlTree.keyLength
     pub static ref KEY_LENGTH_METHOD: SMethod =
@@ -121,6 +128,8 @@ lazy_static! {
             t_range: SType::SOption(Box::new(SType::SInt)).into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// AvlTree.valueLengthOpt
     pub static ref VALUE_LENGTH_OPT_METHOD: SMethod =
@@ -136,6 +145,8 @@ lazy_static! {
             t_range: SType::SBoolean.into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// AvlTree.isInsertAllowed
     pub static ref IS_INSERT_ALLOWED_METHOD: SMethod =
@@ -151,6 +162,8 @@ lazy_static! {
             t_range: SType::SBoolean.into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// AvlTree.isUpdateAllowed
     pub static ref IS_UPDATE_ALLOWED_METHOD: SMethod =
@@ -166,6 +179,8 @@ lazy_static! {
             t_range: SType::SBoolean.into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// AvlTree.isRemoveAllowed
     pub static ref IS_REMOVE_ALLOWED_METHOD: SMethod =
@@ -181,6 +196,8 @@ lazy_static! {
             t_range: SType::SAvlTree.into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// AvlTree.updateOperations
     pub static ref UPDATE_OPERATIONS_METHOD: SMethod =
@@ -199,6 +216,8 @@ lazy_static! {
             t_range: SType::SOption(SType::SColl(SType::SByte.into()).into()).into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
 
     /// AvlTree.get
@@ -218,6 +237,8 @@ lazy_static! {
             t_range: SType::SColl(SType::SOption(SType::SColl(SType::SByte.into()).into()).into()).into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
 
     /// AvlTree.getMany
@@ -246,6 +267,8 @@ lazy_static! {
             t_range: SType::SOption(Box::new(SType::SAvlTree)).into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// AvlTree.insert
     pub static ref INSERT_METHOD: SMethod =
@@ -268,6 +291,8 @@ lazy_static! {
             t_range: SType::SOption(Box::new(SType::SAvlTree)).into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// AvlTree.remove
     pub static ref REMOVE_METHOD: SMethod =
@@ -287,6 +312,8 @@ lazy_static! {
             t_range: SType::SBoolean.into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// AvlTree.contains
     pub static ref CONTAINS_METHOD: SMethod =
@@ -314,6 +341,8 @@ lazy_static! {
             t_range: SType::SOption(Box::new(SType::SAvlTree)).into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// AvlTree.update
     pub static ref UPDATE_METHOD: SMethod =
@@ -329,6 +358,8 @@ lazy_static! {
             t_range: SType::SAvlTree.into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// AvlTree.updateDigest
     pub static ref UPDATE_DIGEST_METHOD: SMethod =
@@ -1,3 +1,4 @@
+use crate::ergo_tree::ErgoTreeVersion;
 use crate::serialization::types::TypeCode;
 
 use super::sfunc::SFunc;
@@ -16,20 +17,60 @@ pub const TYPE_CODE: TypeCode = TypeCode::SBOX;
 pub static TYPE_NAME: &str = "Box";
 /// Box.value property
 pub const VALUE_METHOD_ID: MethodId = MethodId(1);
+/// Box.propositionBytes property
+pub const PROPOSITION_BYTES_METHOD_ID: MethodId = MethodId(2);
+/// Box.bytes property
+pub const BYTES_METHOD_ID: MethodId = MethodId(3);
+/// Box.bytesWithoutRef property
+pub const BYTES_WITHOUT_REF_METHOD_ID: MethodId = MethodId(4);
+/// Box.id property
+pub const ID_METHOD_ID: MethodId = MethodId(5);
+/// Box.creationInfo property
+pub const CREATION_INFO_METHOD_ID: MethodId = MethodId(6);
 /// Box.Rx property
 pub const GET_REG_METHOD_ID: MethodId = MethodId(7);
 /// Box.tokens property
 pub const TOKENS_METHOD_ID: MethodId = MethodId(8);
+/// Box.R0 property
+pub const R0_METHOD_ID: MethodId = MethodId(9);
+/// Box.R1 property
+pub const R1_METHOD_ID: MethodId = MethodId(10);
+/// Box.R2 property
+pub const R2_METHOD_ID: MethodId = MethodId(11);
+/// Box.R3 property
+pub const R3_METHOD_ID: MethodId = MethodId(12);
+/// Box.R4 property
+pub const R4_METHOD_ID: MethodId = MethodId(13);
+/// Box.R5 property
+pub const R5_METHOD_ID: MethodId = MethodId(14);
+/// Box.R6 property
+pub const R6_METHOD_ID: MethodId = MethodId(15);
+/// Box.R7 property
+pub const R7_METHOD_ID: MethodId = MethodId(16);
+/// Box.R8 property
+pub const R8_METHOD_ID: MethodId = MethodId(17);
+/// Box.R9 property
+pub const R9_METHOD_ID: MethodId = MethodId(18);
+
+static REGISTER_METHOD_NAMES: [&str; 10] =
+    ["R0", "R1", "R2", "R3", "R4", "R5", "R6", "R7", "R8", "R9"];
 
 lazy_static! {
     /// Box method descriptors
-    pub(crate) static ref METHOD_DESC: Vec<&'static SMethodDesc> =
-        vec![
+    pub(crate) static ref METHOD_DESC: Vec<&'static SMethodDesc> = {
+        let mut methods: Vec<&'static SMethodDesc> = vec![
             &GET_REG_METHOD_DESC,
             &VALUE_METHOD_DESC,
-            &TOKENS_METHOD_DESC
-        ]
-    ;
+            &PROPOSITION_BYTES_METHOD_DESC,
+            &BYTES_METHOD_DESC,
+            &BYTES_WITHOUT_REF_METHOD_DESC,
+            &ID_METHOD_DESC,
+            &CREATION_INFO_METHOD_DESC,
+            &TOKENS_METHOD_DESC,
+        ];
+        methods.extend(REGISTER_METHOD_DESC.iter());
+        methods
+    };
 }
 
 lazy_static! {
@@ -41,11 +82,61 @@ lazy_static! {
             t_range: Box::new(SType::SLong),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// Box.value
     pub static ref VALUE_METHOD: SMethod = SMethod::new(STypeCompanion::Box, VALUE_METHOD_DESC.clone(),);
 }
 
+lazy_static! {
+    static ref PROPOSITION_BYTES_METHOD_DESC: SMethodDesc = property(
+        "propositionBytes",
+        SType::SColl(SType::SByte.into()),
+        PROPOSITION_BYTES_METHOD_ID
+    );
+    /// Box.propositionBytes
+    pub static ref PROPOSITION_BYTES_METHOD: SMethod =
+        SMethod::new(STypeCompanion::Box, PROPOSITION_BYTES_METHOD_DESC.clone(),);
+}
+
+lazy_static! {
+    static ref BYTES_METHOD_DESC: SMethodDesc =
+        property("bytes", SType::SColl(SType::SByte.into()), BYTES_METHOD_ID);
+    /// Box.bytes
+    pub static ref BYTES_METHOD: SMethod =
+        SMethod::new(STypeCompanion::Box, BYTES_METHOD_DESC.clone(),);
+}
+
+lazy_static! {
+    static ref BYTES_WITHOUT_REF_METHOD_DESC: SMethodDesc = property(
+        "bytesWithoutRef",
+        SType::SColl(SType::SByte.into()),
+        BYTES_WITHOUT_REF_METHOD_ID
+    );
+    /// Box.bytesWithoutRef
+    pub static ref BYTES_WITHOUT_REF_METHOD: SMethod =
+        SMethod::new(STypeCompanion::Box, BYTES_WITHOUT_REF_METHOD_DESC.clone(),);
+}
+
+lazy_static! {
+    static ref ID_METHOD_DESC: SMethodDesc =
+        property("id", SType::SColl(SType::SByte.into()), ID_METHOD_ID);
+    /// Box.id
+    pub static ref ID_METHOD: SMethod = SMethod::new(STypeCompanion::Box, ID_METHOD_DESC.clone(),);
+}
+
+lazy_static! {
+    static ref CREATION_INFO_METHOD_DESC: SMethodDesc = property(
+        "creationInfo",
+        STuple::pair(SType::SInt, SType::SColl(SType::SByte.into())).into(),
+        CREATION_INFO_METHOD_ID
+    );
+    /// Box.creationInfo
+    pub static ref CREATION_INFO_METHOD: SMethod =
+        SMethod::new(STypeCompanion::Box, CREATION_INFO_METHOD_DESC.clone(),);
+}
+
 lazy_static! {
     static ref GET_REG_METHOD_DESC: SMethodDesc = SMethodDesc {
         method_id: GET_REG_METHOD_ID,
@@ -55,6 +146,8 @@ lazy_static! {
             t_range: SType::SOption(Box::new(STypeVar::t().into())).into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// Box.getReg
     pub static ref GET_REG_METHOD: SMethod =
@@ -74,12 +167,41 @@ lazy_static! {
                     ).into())).into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// Box.tokens
     pub static ref TOKENS_METHOD: SMethod =
         SMethod::new( STypeCompanion::Box,TOKENS_METHOD_DESC.clone(),);
 }
 
+lazy_static! {
+    static ref REGISTER_METHOD_DESC: Vec<SMethodDesc> = REGISTER_METHOD_NAMES
+        .iter()
+        .zip(R0_METHOD_ID.0..)
+        .map(|(name, id)| SMethodDesc {
+            method_id: MethodId(id),
+            name,
+            tpe: SFunc {
+                t_dom: vec![SType::SBox],
+                t_range: SType::SOption(Box::new(STypeVar::t().into())).into(),
+                tpe_params: vec![],
+            },
+            explicit_type_args: vec![],
+            min_version: ErgoTreeVersion::V0,
+        })
+        .collect();
+    /// Box.R0 .. Box.R9 properties (indexed by the register number)
+    pub static ref REGISTER_METHODS: Vec<SMethod> = REGISTER_METHOD_DESC
+        .iter()
+        .map(|m| SMethod::new(STypeCompanion::Box, m.clone()))
+        .collect();
+}
+
+fn property(name: &'static str, res_tpe: SType, id: MethodId) -> SMethodDesc {
+    SMethodDesc::property(SType::SBox, name, res_tpe, id)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,5 +211,21 @@ mod tests {
         assert!(SMethod::from_ids(TYPE_CODE, VALUE_METHOD_ID).map(|e| e.name()) == Ok("value"));
         assert!(SMethod::from_ids(TYPE_CODE, GET_REG_METHOD_ID).map(|e| e.name()) == Ok("getReg"));
         assert!(SMethod::from_ids(TYPE_CODE, TOKENS_METHOD_ID).map(|e| e.name()) == Ok("tokens"));
+        assert!(
+            SMethod::from_ids(TYPE_CODE, PROPOSITION_BYTES_METHOD_ID).map(|e| e.name())
+                == Ok("propositionBytes")
+        );
+        assert!(SMethod::from_ids(TYPE_CODE, BYTES_METHOD_ID).map(|e| e.name()) == Ok("bytes"));
+        assert!(
+            SMethod::from_ids(TYPE_CODE, BYTES_WITHOUT_REF_METHOD_ID).map(|e| e.name())
+                == Ok("bytesWithoutRef")
+        );
+        assert!(SMethod::from_ids(TYPE_CODE, ID_METHOD_ID).map(|e| e.name()) == Ok("id"));
+        assert!(
+            SMethod::from_ids(TYPE_CODE, CREATION_INFO_METHOD_ID).map(|e| e.name())
+                == Ok("creationInfo")
+        );
+        assert!(SMethod::from_ids(TYPE_CODE, R0_METHOD_ID).map(|e| e.name()) == Ok("R0"));
+        assert!(SMethod::from_ids(TYPE_CODE, R9_METHOD_ID).map(|e| e.name()) == Ok("R9"));
     }
 }
@@ -1,3 +1,4 @@
+use crate::ergo_tree::ErgoTreeVersion;
 use crate::serialization::types::TypeCode;
 use crate::types::stuple::STuple;
 use crate::types::stype_companion::STypeCompanion;
@@ -28,6 +29,14 @@ pub const PATCH_METHOD_ID: MethodId = MethodId(19);
 pub const UPDATED_METHOD_ID: MethodId = MethodId(20);
 /// Coll.updateMany
 pub const UPDATE_MANY_METHOD_ID: MethodId = MethodId(21);
+/// Coll.reverse (v6.0)
+pub const REVERSE_METHOD_ID: MethodId = MethodId(30);
+/// Coll.startsWith (v6.0)
+pub const STARTS_WITH_METHOD_ID: MethodId = MethodId(31);
+/// Coll.endsWith (v6.0)
+pub const ENDS_WITH_METHOD_ID: MethodId = MethodId(32);
+/// Coll.get (v6.0)
+pub const GET_METHOD_ID: MethodId = MethodId(33);
 
 lazy_static! {
     /// Coll method descriptors
@@ -40,6 +49,10 @@ lazy_static! {
             &UPDATED_METHOD_DESC,
             &UPDATE_MANY_METHOD_DESC,
             &PATCH_METHOD_DESC,
+            &REVERSE_METHOD_DESC,
+            &STARTS_WITH_METHOD_DESC,
+            &ENDS_WITH_METHOD_DESC,
+            &GET_METHOD_DESC,
         ]
     ;
 }
@@ -56,6 +69,8 @@ lazy_static! {
             t_range: SType::SInt.into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// Coll.indexOf
     pub static ref INDEX_OF_METHOD: SMethod = SMethod::new(STypeCompanion::Coll, INDEX_OF_METHOD_DESC.clone());
@@ -75,6 +90,8 @@ lazy_static! {
                 ],
             SType::SColl(SType::STypeVar(STypeVar::ov()).into()),
         ),
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// Coll.flatMap
     pub static ref FLATMAP_METHOD: SMethod = SMethod::new(STypeCompanion::Coll, FLATMAP_METHOD_DESC.clone());
@@ -92,7 +109,9 @@ lazy_static! {
             SType::SColl(SType::STuple(STuple::pair(
                 STypeVar::t().into(), STypeVar::iv().into()
             )).into())
-        )
+        ),
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// Coll.zip
     pub static ref ZIP_METHOD: SMethod = SMethod::new(STypeCompanion::Coll, ZIP_METHOD_DESC.clone());
@@ -107,7 +126,9 @@ lazy_static! {
                 SType::SColl(SType::STypeVar(STypeVar::t()).into()),
             ],
             SType::SColl(SType::SInt.into())
-        )
+        ),
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// Coll.indices
     pub static ref INDICES_METHOD: SMethod = SMethod::new(STypeCompanion::Coll, INDICES_METHOD_DESC.clone());
@@ -125,7 +146,9 @@ lazy_static! {
                 SType::SInt,
             ],
             SType::SColl(SType::STypeVar(STypeVar::t()).into())
-        )
+        ),
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// Coll.patch
     pub static ref PATCH_METHOD: SMethod = SMethod::new(STypeCompanion::Coll, PATCH_METHOD_DESC.clone());
@@ -143,7 +166,9 @@ lazy_static! {
 
             ],
             SType::SColl(SType::STypeVar(STypeVar::t()).into())
-        )
+        ),
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// Coll.updated
     pub static ref UPDATED_METHOD: SMethod = SMethod::new(STypeCompanion::Coll, UPDATED_METHOD_DESC.clone());
@@ -161,12 +186,83 @@ lazy_static! {
 
             ],
             SType::SColl(SType::STypeVar(STypeVar::t()).into())
-        )
+        ),
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// Coll.updateMany
     pub static ref UPDATE_MANY_METHOD: SMethod = SMethod::new(STypeCompanion::Coll, UPDATE_MANY_METHOD_DESC.clone());
 }
 
+lazy_static! {
+    static ref REVERSE_METHOD_DESC: SMethodDesc = SMethodDesc {
+        method_id: REVERSE_METHOD_ID,
+        name: "reverse",
+        tpe: SFunc::new(
+            vec![SType::SColl(SType::STypeVar(STypeVar::t()).into())],
+            SType::SColl(SType::STypeVar(STypeVar::t()).into())
+        ),
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V6_SOFT_FORK_VERSION,
+    };
+    /// Coll.reverse
+    pub static ref REVERSE_METHOD: SMethod = SMethod::new(STypeCompanion::Coll, REVERSE_METHOD_DESC.clone());
+}
+
+lazy_static! {
+    static ref STARTS_WITH_METHOD_DESC: SMethodDesc = SMethodDesc {
+        method_id: STARTS_WITH_METHOD_ID,
+        name: "startsWith",
+        tpe: SFunc::new(
+            vec![
+                SType::SColl(SType::STypeVar(STypeVar::t()).into()),
+                SType::SColl(SType::STypeVar(STypeVar::t()).into()),
+            ],
+            SType::SBoolean
+        ),
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V6_SOFT_FORK_VERSION,
+    };
+    /// Coll.startsWith
+    pub static ref STARTS_WITH_METHOD: SMethod = SMethod::new(STypeCompanion::Coll, STARTS_WITH_METHOD_DESC.clone());
+}
+
+lazy_static! {
+    static ref ENDS_WITH_METHOD_DESC: SMethodDesc = SMethodDesc {
+        method_id: ENDS_WITH_METHOD_ID,
+        name: "endsWith",
+        tpe: SFunc::new(
+            vec![
+                SType::SColl(SType::STypeVar(STypeVar::t()).into()),
+                SType::SColl(SType::STypeVar(STypeVar::t()).into()),
+            ],
+            SType::SBoolean
+        ),
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V6_SOFT_FORK_VERSION,
+    };
+    /// Coll.endsWith
+    pub static ref ENDS_WITH_METHOD: SMethod = SMethod::new(STypeCompanion::Coll, ENDS_WITH_METHOD_DESC.clone());
+}
+
+lazy_static! {
+    static ref GET_METHOD_DESC: SMethodDesc = SMethodDesc {
+        method_id: GET_METHOD_ID,
+        name: "get",
+        tpe: SFunc::new(
+            vec![
+                SType::SColl(SType::STypeVar(STypeVar::t()).into()),
+                SType::SInt,
+            ],
+            SType::SOption(SType::STypeVar(STypeVar::t()).into())
+        ),
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V6_SOFT_FORK_VERSION,
+    };
+    /// Coll.get
+    pub static ref GET_METHOD: SMethod = SMethod::new(STypeCompanion::Coll, GET_METHOD_DESC.clone());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -185,5 +281,14 @@ mod tests {
             SMethod::from_ids(TYPE_CODE, UPDATE_MANY_METHOD_ID).map(|e| e.name())
                 == Ok("updateMany")
         );
+        assert!(SMethod::from_ids(TYPE_CODE, REVERSE_METHOD_ID).map(|e| e.name()) == Ok("reverse"));
+        assert!(
+            SMethod::from_ids(TYPE_CODE, STARTS_WITH_METHOD_ID).map(|e| e.name())
+                == Ok("startsWith")
+        );
+        assert!(
+            SMethod::from_ids(TYPE_CODE, ENDS_WITH_METHOD_ID).map(|e| e.name()) == Ok("endsWith")
+        );
+        assert!(SMethod::from_ids(TYPE_CODE, GET_METHOD_ID).map(|e| e.name()) == Ok("get"));
     }
 }
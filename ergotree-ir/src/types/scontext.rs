@@ -1,13 +1,16 @@
 #![allow(missing_docs)]
 
+use crate::ergo_tree::ErgoTreeVersion;
 use crate::serialization::types::TypeCode;
 use crate::types::stype_companion::STypeCompanion;
 
+use super::sfunc::SFunc;
 use super::smethod::MethodId;
 use super::smethod::SMethod;
 use super::smethod::SMethodDesc;
 use super::stype::SType;
 use super::stype::SType::{SAvlTree, SBox, SByte, SColl, SHeader, SInt, SPreHeader};
+use super::stype_param::STypeVar;
 use lazy_static::lazy_static;
 
 /// SContext type code
@@ -28,6 +31,7 @@ lazy_static! {
         &SELF_BOX_INDEX_PROPERTY_METHOD_DESC,
         &LAST_BLOCK_UTXO_ROOT_HASH_PROPERTY_METHOD_DESC,
         &MINER_PUBKEY_PROPERTY_METHOD_DESC,
+        &GET_VAR_METHOD_DESC,
     ];
 }
 
@@ -78,7 +82,7 @@ lazy_static! {
         property("INPUTS", SColl(SBox.into()), INPUTS_PROPERTY_METHOD_ID);
 }
 lazy_static! {
-    static ref INPUTS_PROPERTY: SMethod =
+    pub static ref INPUTS_PROPERTY: SMethod =
         SMethod::new(STypeCompanion::Context, INPUTS_PROPERTY_METHOD_DESC.clone());
 }
 
@@ -88,7 +92,7 @@ lazy_static! {
         property("OUTPUTS", SColl(SBox.into()), OUTPUTS_PROPERTY_METHOD_ID);
 }
 lazy_static! {
-    static ref OUTPUTS_PROPERTY: SMethod = SMethod::new(
+    pub static ref OUTPUTS_PROPERTY: SMethod = SMethod::new(
         STypeCompanion::Context,
         OUTPUTS_PROPERTY_METHOD_DESC.clone()
     );
@@ -100,7 +104,7 @@ lazy_static! {
         property("HEIGHT", SInt, HEIGHT_PROPERTY_METHOD_ID);
 }
 lazy_static! {
-    static ref HEIGHT_PROPERTY: SMethod =
+    pub static ref HEIGHT_PROPERTY: SMethod =
         SMethod::new(STypeCompanion::Context, HEIGHT_PROPERTY_METHOD_DESC.clone());
 }
 
@@ -110,7 +114,7 @@ lazy_static! {
         property("SELF", SBox, SELF_PROPERTY_METHOD_ID);
 }
 lazy_static! {
-    static ref SELF_PROPERTY: SMethod =
+    pub static ref SELF_PROPERTY: SMethod =
         SMethod::new(STypeCompanion::Context, SELF_PROPERTY_METHOD_DESC.clone());
 }
 
@@ -150,12 +154,32 @@ lazy_static! {
     );
 }
 lazy_static! {
-    static ref MINER_PUBKEY_PROPERTY: SMethod = SMethod::new(
+    pub static ref MINER_PUBKEY_PROPERTY: SMethod = SMethod::new(
         STypeCompanion::Context,
         MINER_PUBKEY_PROPERTY_METHOD_DESC.clone()
     );
 }
 
+pub const GET_VAR_METHOD_ID: MethodId = MethodId(11);
+lazy_static! {
+    static ref GET_VAR_METHOD_DESC: SMethodDesc = SMethodDesc {
+        method_id: GET_VAR_METHOD_ID,
+        name: "getVar",
+        tpe: SFunc {
+            t_dom: vec![SType::SContext, SByte],
+            t_range: SType::SOption(Box::new(STypeVar::t().into())).into(),
+            tpe_params: vec![],
+        },
+        explicit_type_args: vec![STypeVar::t()],
+        min_version: ErgoTreeVersion::V6_SOFT_FORK_VERSION,
+    };
+}
+lazy_static! {
+    /// Context.getVar[T](id: Byte): Option[T], the method form of [`crate::mir::get_var::GetVar`]
+    pub static ref GET_VAR_METHOD: SMethod =
+        SMethod::new(STypeCompanion::Context, GET_VAR_METHOD_DESC.clone());
+}
+
 fn property(name: &'static str, res_tpe: SType, id: MethodId) -> SMethodDesc {
     SMethodDesc::property(SType::SContext, name, res_tpe, id)
 }
@@ -170,5 +194,6 @@ mod tests {
             SMethod::from_ids(TYPE_CODE, DATA_INPUTS_PROPERTY_METHOD_ID).map(|e| e.name())
                 == Ok("dataInputs")
         );
+        assert!(SMethod::from_ids(TYPE_CODE, GET_VAR_METHOD_ID).map(|e| e.name()) == Ok("getVar"));
     }
 }
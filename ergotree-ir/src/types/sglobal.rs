@@ -1,9 +1,11 @@
+use crate::ergo_tree::ErgoTreeVersion;
 use crate::serialization::types::TypeCode;
 
 use super::sfunc::SFunc;
 use super::smethod::MethodId;
 use super::smethod::SMethodDesc;
 use super::stype::SType;
+use super::stype_param::STypeVar;
 use crate::types::smethod::SMethod;
 use crate::types::stype_companion::STypeCompanion;
 use lazy_static::lazy_static;
@@ -17,11 +19,20 @@ pub static TYPE_NAME: &str = "Global";
 pub const GROUP_GENERATOR_METHOD_ID: MethodId = MethodId(1);
 /// "xor" predefined function
 pub const XOR_METHOD_ID: MethodId = MethodId(2);
+/// Global.serialize (v6.0)
+pub const SERIALIZE_METHOD_ID: MethodId = MethodId(3);
+/// Global.fromBigEndianBytes (v6.0)
+pub const FROM_BIG_ENDIAN_BYTES_METHOD_ID: MethodId = MethodId(5);
 
 lazy_static! {
     /// Global method descriptors
     pub(crate) static ref METHOD_DESC: Vec<&'static SMethodDesc> =
-        vec![&GROUP_GENERATOR_METHOD_DESC, &XOR_METHOD_DESC,];
+        vec![
+            &GROUP_GENERATOR_METHOD_DESC,
+            &XOR_METHOD_DESC,
+            &SERIALIZE_METHOD_DESC,
+            &FROM_BIG_ENDIAN_BYTES_METHOD_DESC,
+        ];
 }
 
 lazy_static! {
@@ -33,6 +44,8 @@ lazy_static! {
             t_range: SType::SGroupElement.into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
      /// GLOBAL.GroupGenerator
     pub static ref GROUP_GENERATOR_METHOD: SMethod = SMethod::new(STypeCompanion::Global, GROUP_GENERATOR_METHOD_DESC.clone(),);
@@ -52,8 +65,42 @@ lazy_static! {
             t_range: SType::SColl(SType::SByte.into()).into(),
             tpe_params: vec![],
         },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
      /// GLOBAL.xor
     pub static ref XOR_METHOD: SMethod = SMethod::new(STypeCompanion::Global, XOR_METHOD_DESC.clone(),);
 
 }
+
+lazy_static! {
+    static ref SERIALIZE_METHOD_DESC: SMethodDesc = SMethodDesc {
+        method_id: SERIALIZE_METHOD_ID,
+        name: "serialize",
+        tpe: SFunc {
+            t_dom: vec![SType::SGlobal, STypeVar::t().into()],
+            t_range: SType::SColl(SType::SByte.into()).into(),
+            tpe_params: vec![],
+        },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V6_SOFT_FORK_VERSION,
+    };
+    /// GLOBAL.serialize
+    pub static ref SERIALIZE_METHOD: SMethod = SMethod::new(STypeCompanion::Global, SERIALIZE_METHOD_DESC.clone(),);
+}
+
+lazy_static! {
+    static ref FROM_BIG_ENDIAN_BYTES_METHOD_DESC: SMethodDesc = SMethodDesc {
+        method_id: FROM_BIG_ENDIAN_BYTES_METHOD_ID,
+        name: "fromBigEndianBytes",
+        tpe: SFunc {
+            t_dom: vec![SType::SGlobal, SType::SColl(SType::SByte.into())],
+            t_range: SType::STypeVar(STypeVar::t()).into(),
+            tpe_params: vec![],
+        },
+        explicit_type_args: vec![STypeVar::t()],
+        min_version: ErgoTreeVersion::V6_SOFT_FORK_VERSION,
+    };
+    /// GLOBAL.fromBigEndianBytes
+    pub static ref FROM_BIG_ENDIAN_BYTES_METHOD: SMethod = SMethod::new(STypeCompanion::Global, FROM_BIG_ENDIAN_BYTES_METHOD_DESC.clone(),);
+}
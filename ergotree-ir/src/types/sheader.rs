@@ -2,6 +2,7 @@
 
 use lazy_static::lazy_static;
 
+use crate::ergo_tree::ErgoTreeVersion;
 use crate::serialization::types::TypeCode;
 
 use super::smethod::{MethodId, SMethod, SMethodDesc};
@@ -42,6 +43,8 @@ pub const POW_NONCE_METHOD_ID: MethodId = MethodId(13);
 pub const POW_DISTANCE_METHOD_ID: MethodId = MethodId(14);
 /// `Header.votes`
 pub const VOTES_METHOD_ID: MethodId = MethodId(15);
+/// `Header.checkPow` (v6.0)
+pub const CHECK_POW_METHOD_ID: MethodId = MethodId(16);
 
 lazy_static! {
     /// Header method descriptors
@@ -62,6 +65,7 @@ lazy_static! {
             &POW_NONCE_PROPERTY_METHOD_DESC,
             &POW_DISTANCE_PROPERTY_METHOD_DESC,
             &VOTES_PROPERTY_METHOD_DESC,
+            &CHECK_POW_METHOD_DESC,
         ]
     ;
 }
@@ -98,6 +102,8 @@ lazy_static! {
         SMethod::new(Header, POW_DISTANCE_PROPERTY_METHOD_DESC.clone(),);
     pub static ref VOTES_PROPERTY: SMethod =
         SMethod::new(Header, VOTES_PROPERTY_METHOD_DESC.clone(),);
+    pub static ref CHECK_POW_METHOD: SMethod =
+        SMethod::new(Header, CHECK_POW_METHOD_DESC.clone(),);
 
     // Declaring private `SHeader` properties methods descriptors
 
@@ -152,6 +158,9 @@ lazy_static! {
     );
     static ref VOTES_PROPERTY_METHOD_DESC: SMethodDesc =
         property("votes", SColl(SByte.into()), VOTES_METHOD_ID);
+    static ref CHECK_POW_METHOD_DESC: SMethodDesc =
+        property("checkPow", SType::SBoolean, CHECK_POW_METHOD_ID)
+            .with_min_version(ErgoTreeVersion::V6_SOFT_FORK_VERSION);
 }
 
 fn property(name: &'static str, res_tpe: SType, id: MethodId) -> SMethodDesc {
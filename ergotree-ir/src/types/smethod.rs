@@ -1,3 +1,4 @@
+use crate::ergo_tree::ErgoTreeVersion;
 use crate::serialization::sigma_byte_reader::SigmaByteRead;
 use crate::serialization::sigma_byte_writer::SigmaByteWrite;
 use crate::serialization::types::TypeCode;
@@ -56,6 +57,20 @@ impl SMethod {
         }
     }
 
+    /// Get method from type and method ids, returns an error if the method is not available in
+    /// the given ErgoTree version
+    pub(crate) fn from_ids_for_version(
+        type_id: TypeCode,
+        method_id: MethodId,
+        tree_version: ErgoTreeVersion,
+    ) -> Result<Self, SigmaParsingError> {
+        let method = SMethod::from_ids(type_id, method_id.clone())?;
+        if method.min_version() > tree_version {
+            return Err(UnknownMethodId(method_id, type_id.value()));
+        }
+        Ok(method)
+    }
+
     /// Type
     pub fn tpe(&self) -> &SFunc {
         &self.method_raw.tpe
@@ -71,6 +86,16 @@ impl SMethod {
         self.method_raw.method_id.clone()
     }
 
+    /// Type parameters that have to be provided explicitly in the MethodCall
+    pub fn explicit_type_args(&self) -> &[STypeVar] {
+        &self.method_raw.explicit_type_args
+    }
+
+    /// Minimal ErgoTree version in which the method is available
+    pub fn min_version(&self) -> ErgoTreeVersion {
+        self.method_raw.min_version
+    }
+
     /// Return new SMethod with type variables substituted
     pub fn with_concrete_types(self, subst: &HashMap<STypeVar, SType>) -> Self {
         let new_tpe = self.method_raw.tpe.clone().with_subst(subst);
@@ -100,6 +125,11 @@ pub struct SMethodDesc {
     pub(crate) name: &'static str,
     pub(crate) method_id: MethodId,
     pub(crate) tpe: SFunc,
+    /// Type parameters which cannot be inferred from the arguments and are serialized
+    /// explicitly in the MethodCall (e.g. `T` in `Global.fromBigEndianBytes[T]`)
+    pub(crate) explicit_type_args: Vec<STypeVar>,
+    /// Minimal ErgoTree version in which the method is available
+    pub(crate) min_version: ErgoTreeVersion,
 }
 
 impl SMethodDesc {
@@ -118,6 +148,8 @@ impl SMethodDesc {
                 t_range: res_tpe.into(),
                 tpe_params: vec![],
             },
+            explicit_type_args: vec![],
+            min_version: ErgoTreeVersion::V0,
         }
     }
     pub(crate) fn as_method(&self, obj_type: STypeCompanion) -> SMethod {
@@ -130,4 +162,11 @@ impl SMethodDesc {
     pub(crate) fn with_tpe(self, tpe: SFunc) -> Self {
         Self { tpe, ..self }
     }
+
+    pub(crate) fn with_min_version(self, min_version: ErgoTreeVersion) -> Self {
+        Self {
+            min_version,
+            ..self
+        }
+    }
 }
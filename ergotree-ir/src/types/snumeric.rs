@@ -0,0 +1,213 @@
+use crate::ergo_tree::ErgoTreeVersion;
+use crate::serialization::types::TypeCode;
+
+use super::sfunc::SFunc;
+use super::smethod::MethodId;
+use super::smethod::SMethodDesc;
+use super::stype::SType;
+use lazy_static::lazy_static;
+
+/// SByte type code
+pub const BYTE_TYPE_CODE: TypeCode = TypeCode::SBYTE;
+/// SShort type code
+pub const SHORT_TYPE_CODE: TypeCode = TypeCode::SSHORT;
+/// SInt type code
+pub const INT_TYPE_CODE: TypeCode = TypeCode::SINT;
+/// SLong type code
+pub const LONG_TYPE_CODE: TypeCode = TypeCode::SLONG;
+/// SBigInt type code
+pub const BIGINT_TYPE_CODE: TypeCode = TypeCode::SBIGINT;
+/// SUnsignedBigInt type code
+pub const UNSIGNED_BIGINT_TYPE_CODE: TypeCode = TypeCode::SUNSIGNED_BIGINT;
+
+/// SByte type name
+pub static BYTE_TYPE_NAME: &str = "Byte";
+/// SShort type name
+pub static SHORT_TYPE_NAME: &str = "Short";
+/// SInt type name
+pub static INT_TYPE_NAME: &str = "Int";
+/// SLong type name
+pub static LONG_TYPE_NAME: &str = "Long";
+/// SBigInt type name
+pub static BIGINT_TYPE_NAME: &str = "BigInt";
+/// SUnsignedBigInt type name
+pub static UNSIGNED_BIGINT_TYPE_NAME: &str = "UnsignedBigInt";
+
+/// Numeric.toByte property
+pub const TO_BYTE_METHOD_ID: MethodId = MethodId(1);
+/// Numeric.toShort property
+pub const TO_SHORT_METHOD_ID: MethodId = MethodId(2);
+/// Numeric.toInt property
+pub const TO_INT_METHOD_ID: MethodId = MethodId(3);
+/// Numeric.toLong property
+pub const TO_LONG_METHOD_ID: MethodId = MethodId(4);
+/// Numeric.toBigInt property
+pub const TO_BIGINT_METHOD_ID: MethodId = MethodId(5);
+/// Numeric.toBytes property (big-endian two's complement representation)
+pub const TO_BYTES_METHOD_ID: MethodId = MethodId(6);
+/// Numeric.toBits property (bits of the big-endian representation, most significant first)
+pub const TO_BITS_METHOD_ID: MethodId = MethodId(7);
+/// Numeric.bitwiseInverse property (v6.0)
+pub const BITWISE_INVERSE_METHOD_ID: MethodId = MethodId(8);
+/// Numeric.bitwiseOr method (v6.0)
+pub const BITWISE_OR_METHOD_ID: MethodId = MethodId(9);
+/// Numeric.bitwiseAnd method (v6.0)
+pub const BITWISE_AND_METHOD_ID: MethodId = MethodId(10);
+/// Numeric.bitwiseXor method (v6.0)
+pub const BITWISE_XOR_METHOD_ID: MethodId = MethodId(11);
+/// Numeric.shiftLeft method (v6.0)
+pub const SHIFT_LEFT_METHOD_ID: MethodId = MethodId(12);
+/// Numeric.shiftRight method (v6.0)
+pub const SHIFT_RIGHT_METHOD_ID: MethodId = MethodId(13);
+
+lazy_static! {
+    static ref BYTE_METHODS: Vec<SMethodDesc> = method_descs(SType::SByte);
+    static ref SHORT_METHODS: Vec<SMethodDesc> = method_descs(SType::SShort);
+    static ref INT_METHODS: Vec<SMethodDesc> = method_descs(SType::SInt);
+    static ref LONG_METHODS: Vec<SMethodDesc> = method_descs(SType::SLong);
+    static ref BIGINT_METHODS: Vec<SMethodDesc> = method_descs(SType::SBigInt);
+    // UnsignedBigInt is a v6.0 type, so all its methods are v6.0 as well
+    static ref UNSIGNED_BIGINT_METHODS: Vec<SMethodDesc> = method_descs(SType::SUnsignedBigInt)
+        .into_iter()
+        .map(|m| m.with_min_version(ErgoTreeVersion::V6_SOFT_FORK_VERSION))
+        .collect();
+
+    /// Byte method descriptors
+    pub(crate) static ref BYTE_METHOD_DESC: Vec<&'static SMethodDesc> =
+        BYTE_METHODS.iter().collect();
+    /// Short method descriptors
+    pub(crate) static ref SHORT_METHOD_DESC: Vec<&'static SMethodDesc> =
+        SHORT_METHODS.iter().collect();
+    /// Int method descriptors
+    pub(crate) static ref INT_METHOD_DESC: Vec<&'static SMethodDesc> =
+        INT_METHODS.iter().collect();
+    /// Long method descriptors
+    pub(crate) static ref LONG_METHOD_DESC: Vec<&'static SMethodDesc> =
+        LONG_METHODS.iter().collect();
+    /// BigInt method descriptors
+    pub(crate) static ref BIGINT_METHOD_DESC: Vec<&'static SMethodDesc> =
+        BIGINT_METHODS.iter().collect();
+    /// UnsignedBigInt method descriptors
+    pub(crate) static ref UNSIGNED_BIGINT_METHOD_DESC: Vec<&'static SMethodDesc> =
+        UNSIGNED_BIGINT_METHODS.iter().collect();
+}
+
+/// Methods shared by all numeric types, `obj_tpe` is the type of the receiver
+fn method_descs(obj_tpe: SType) -> Vec<SMethodDesc> {
+    vec![
+        SMethodDesc::property(obj_tpe.clone(), "toByte", SType::SByte, TO_BYTE_METHOD_ID),
+        SMethodDesc::property(
+            obj_tpe.clone(),
+            "toShort",
+            SType::SShort,
+            TO_SHORT_METHOD_ID,
+        ),
+        SMethodDesc::property(obj_tpe.clone(), "toInt", SType::SInt, TO_INT_METHOD_ID),
+        SMethodDesc::property(obj_tpe.clone(), "toLong", SType::SLong, TO_LONG_METHOD_ID),
+        SMethodDesc::property(
+            obj_tpe.clone(),
+            "toBigInt",
+            SType::SBigInt,
+            TO_BIGINT_METHOD_ID,
+        ),
+        SMethodDesc::property(
+            obj_tpe.clone(),
+            "toBytes",
+            SType::SColl(SType::SByte.into()),
+            TO_BYTES_METHOD_ID,
+        )
+        .with_min_version(ErgoTreeVersion::V6_SOFT_FORK_VERSION),
+        SMethodDesc::property(
+            obj_tpe.clone(),
+            "toBits",
+            SType::SColl(SType::SBoolean.into()),
+            TO_BITS_METHOD_ID,
+        )
+        .with_min_version(ErgoTreeVersion::V6_SOFT_FORK_VERSION),
+        SMethodDesc::property(
+            obj_tpe.clone(),
+            "bitwiseInverse",
+            obj_tpe.clone(),
+            BITWISE_INVERSE_METHOD_ID,
+        )
+        .with_min_version(ErgoTreeVersion::V6_SOFT_FORK_VERSION),
+        v6_method(&obj_tpe, "bitwiseOr", obj_tpe.clone(), BITWISE_OR_METHOD_ID),
+        v6_method(
+            &obj_tpe,
+            "bitwiseAnd",
+            obj_tpe.clone(),
+            BITWISE_AND_METHOD_ID,
+        ),
+        v6_method(
+            &obj_tpe,
+            "bitwiseXor",
+            obj_tpe.clone(),
+            BITWISE_XOR_METHOD_ID,
+        ),
+        v6_method(&obj_tpe, "shiftLeft", SType::SInt, SHIFT_LEFT_METHOD_ID),
+        v6_method(&obj_tpe, "shiftRight", SType::SInt, SHIFT_RIGHT_METHOD_ID),
+    ]
+}
+
+/// Method with a single argument of `arg_tpe` returning the receiver type (since v6.0)
+fn v6_method(obj_tpe: &SType, name: &'static str, arg_tpe: SType, id: MethodId) -> SMethodDesc {
+    SMethodDesc {
+        method_id: id,
+        name,
+        tpe: SFunc {
+            t_dom: vec![obj_tpe.clone(), arg_tpe],
+            t_range: obj_tpe.clone().into(),
+            tpe_params: vec![],
+        },
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V6_SOFT_FORK_VERSION,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::types::smethod::SMethod;
+
+    #[test]
+    fn test_from_ids() {
+        assert!(
+            SMethod::from_ids(BYTE_TYPE_CODE, TO_INT_METHOD_ID).map(|e| e.name()) == Ok("toInt")
+        );
+        assert!(
+            SMethod::from_ids(LONG_TYPE_CODE, TO_BYTES_METHOD_ID).map(|e| e.name())
+                == Ok("toBytes")
+        );
+        assert!(
+            SMethod::from_ids(BIGINT_TYPE_CODE, TO_BITS_METHOD_ID).map(|e| e.name())
+                == Ok("toBits")
+        );
+    }
+
+    #[test]
+    fn test_v6_methods() {
+        let m = SMethod::from_ids(INT_TYPE_CODE, SHIFT_LEFT_METHOD_ID).unwrap();
+        assert_eq!(m.tpe().t_dom, vec![SType::SInt, SType::SInt]);
+        assert_eq!(m.min_version(), ErgoTreeVersion::V3);
+        assert_eq!(
+            SMethod::from_ids(LONG_TYPE_CODE, TO_BYTES_METHOD_ID)
+                .unwrap()
+                .min_version(),
+            ErgoTreeVersion::V3
+        );
+        assert_eq!(
+            SMethod::from_ids(UNSIGNED_BIGINT_TYPE_CODE, TO_BYTES_METHOD_ID)
+                .unwrap()
+                .min_version(),
+            ErgoTreeVersion::V3
+        );
+    }
+
+    #[test]
+    fn test_obj_type() {
+        let m = SMethod::from_ids(SHORT_TYPE_CODE, TO_LONG_METHOD_ID).unwrap();
+        assert_eq!(m.tpe().t_dom, vec![SType::SShort]);
+        assert_eq!(*m.tpe().t_range, SType::SLong);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::ergo_tree::ErgoTreeVersion;
 use crate::serialization::types::TypeCode;
 
 use super::sfunc::SFunc;
@@ -42,6 +43,8 @@ lazy_static! {
                 ],
             SType::SOption(SType::STypeVar(STypeVar::ov()).into()),
         ),
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// Option.map
     pub static ref MAP_METHOD: SMethod = SMethod::new(
@@ -63,6 +66,8 @@ lazy_static! {
                 ],
             SType::SOption(SType::STypeVar(STypeVar::iv()).into()),
         ),
+        explicit_type_args: vec![],
+        min_version: ErgoTreeVersion::V0,
     };
     /// Option.map
     pub static ref FILTER_METHOD: SMethod = SMethod::new(
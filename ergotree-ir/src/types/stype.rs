@@ -12,6 +12,7 @@ use crate::sigma_protocol::sigma_boolean::SigmaBoolean;
 use crate::sigma_protocol::sigma_boolean::SigmaProofOfKnowledgeTree;
 use crate::sigma_protocol::sigma_boolean::SigmaProp;
 use crate::sigma_protocol::sigma_boolean::{ProveDhTuple, ProveDlog};
+use crate::unsignedbigint256::UnsignedBigInt256;
 use ergo_chain_types::EcPoint;
 
 use super::sfunc::SFunc;
@@ -40,6 +41,8 @@ pub enum SType {
     SLong,
     /// 256-bit integer
     SBigInt,
+    /// 256-bit unsigned integer (ErgoTree v3 and later)
+    SUnsignedBigInt,
     /// Discrete logarithm prime-order group element [`EcPoint`]
     SGroupElement,
     /// Proposition which can be proven and verified by sigma protocol.
@@ -71,7 +74,12 @@ impl SType {
     pub fn is_numeric(&self) -> bool {
         matches!(
             self,
-            SType::SByte | SType::SShort | SType::SInt | SType::SLong | SType::SBigInt
+            SType::SByte
+                | SType::SShort
+                | SType::SInt
+                | SType::SLong
+                | SType::SBigInt
+                | SType::SUnsignedBigInt
         )
     }
 
@@ -84,6 +92,7 @@ impl SType {
                 | SType::SInt
                 | SType::SLong
                 | SType::SBigInt
+                | SType::SUnsignedBigInt
                 | SType::SAny
                 | SType::SGroupElement
                 | SType::SSigmaProp
@@ -97,7 +106,8 @@ impl SType {
         )
     }
 
-    pub(crate) fn with_subst(self, subst: &HashMap<STypeVar, SType>) -> Self {
+    /// Replaces type variables with the types from `subst`
+    pub fn with_subst(self, subst: &HashMap<STypeVar, SType>) -> Self {
         match self {
             SType::STypeVar(ref tpe_var) => subst.get(tpe_var).cloned().unwrap_or(self),
             SType::SOption(tpe) => SType::SOption(tpe.with_subst(subst).into()),
@@ -139,6 +149,7 @@ impl std::fmt::Display for SType {
             SType::SInt => write!(f, "Int"),
             SType::SLong => write!(f, "Long"),
             SType::SBigInt => write!(f, "BigInt"),
+            SType::SUnsignedBigInt => write!(f, "UnsignedBigInt"),
             SType::SGroupElement => write!(f, "GroupElement"),
             SType::SSigmaProp => write!(f, "SigmaProp"),
             SType::SBox => write!(f, "Box"),
@@ -245,6 +256,12 @@ impl LiftIntoSType for BigInt256 {
     }
 }
 
+impl LiftIntoSType for UnsignedBigInt256 {
+    fn stype() -> SType {
+        SType::SUnsignedBigInt
+    }
+}
+
 impl LiftIntoSType for ProveDhTuple {
     fn stype() -> SType {
         SType::SSigmaProp
@@ -287,6 +304,7 @@ pub(crate) mod tests {
             Just(SType::SInt),
             Just(SType::SLong),
             Just(SType::SBigInt),
+            Just(SType::SUnsignedBigInt),
             Just(SType::SGroupElement),
             Just(SType::SSigmaProp),
             Just(SType::SBox),
@@ -14,8 +14,10 @@ use super::sheader;
 use super::smethod::MethodId;
 use super::smethod::SMethod;
 use super::smethod::SMethodDesc;
+use super::snumeric;
 use super::soption;
 use super::spreheader;
+use super::stype::SType;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
@@ -40,6 +42,18 @@ pub enum STypeCompanion {
     Option,
     /// AVL tree
     AvlTree,
+    /// Byte
+    Byte,
+    /// Short
+    Short,
+    /// Int
+    Int,
+    /// Long
+    Long,
+    /// BigInt
+    BigInt,
+    /// UnsignedBigInt
+    UnsignedBigInt,
 }
 
 impl STypeCompanion {
@@ -54,6 +68,12 @@ impl STypeCompanion {
             STypeCompanion::PreHeader => &spreheader::METHOD_DESC,
             STypeCompanion::Option => &soption::METHOD_DESC,
             STypeCompanion::AvlTree => &savltree::METHOD_DESC,
+            STypeCompanion::Byte => &snumeric::BYTE_METHOD_DESC,
+            STypeCompanion::Short => &snumeric::SHORT_METHOD_DESC,
+            STypeCompanion::Int => &snumeric::INT_METHOD_DESC,
+            STypeCompanion::Long => &snumeric::LONG_METHOD_DESC,
+            STypeCompanion::BigInt => &snumeric::BIGINT_METHOD_DESC,
+            STypeCompanion::UnsignedBigInt => &snumeric::UNSIGNED_BIGINT_METHOD_DESC,
         }
     }
 
@@ -65,6 +85,36 @@ impl STypeCompanion {
             .map(|m| m.as_method(*self))
     }
 
+    /// Get method signature for this object by a method name
+    pub fn method_by_name(&self, name: &str) -> Option<SMethod> {
+        self.method_desc()
+            .iter()
+            .find(|m| m.name == name)
+            .map(|m| m.as_method(*self))
+    }
+
+    /// Get type companion of the given type (`None` if the type has no methods)
+    pub fn for_type(tpe: &SType) -> Option<Self> {
+        Some(match tpe {
+            SType::SContext => STypeCompanion::Context,
+            SType::SBox => STypeCompanion::Box,
+            SType::SColl(_) => STypeCompanion::Coll,
+            SType::SGroupElement => STypeCompanion::GroupElem,
+            SType::SGlobal => STypeCompanion::Global,
+            SType::SHeader => STypeCompanion::Header,
+            SType::SPreHeader => STypeCompanion::PreHeader,
+            SType::SOption(_) => STypeCompanion::Option,
+            SType::SAvlTree => STypeCompanion::AvlTree,
+            SType::SByte => STypeCompanion::Byte,
+            SType::SShort => STypeCompanion::Short,
+            SType::SInt => STypeCompanion::Int,
+            SType::SLong => STypeCompanion::Long,
+            SType::SBigInt => STypeCompanion::BigInt,
+            SType::SUnsignedBigInt => STypeCompanion::UnsignedBigInt,
+            _ => return None,
+        })
+    }
+
     /// Get list of method signatures for this object's type companion
     pub fn methods(&self) -> Vec<SMethod> {
         self.method_desc()
@@ -85,6 +135,12 @@ impl STypeCompanion {
             STypeCompanion::PreHeader => spreheader::TYPE_CODE,
             STypeCompanion::Option => soption::TYPE_CODE,
             STypeCompanion::AvlTree => savltree::TYPE_CODE,
+            STypeCompanion::Byte => snumeric::BYTE_TYPE_CODE,
+            STypeCompanion::Short => snumeric::SHORT_TYPE_CODE,
+            STypeCompanion::Int => snumeric::INT_TYPE_CODE,
+            STypeCompanion::Long => snumeric::LONG_TYPE_CODE,
+            STypeCompanion::BigInt => snumeric::BIGINT_TYPE_CODE,
+            STypeCompanion::UnsignedBigInt => snumeric::UNSIGNED_BIGINT_TYPE_CODE,
         }
     }
 
@@ -100,6 +156,12 @@ impl STypeCompanion {
             STypeCompanion::PreHeader => spreheader::TYPE_NAME,
             STypeCompanion::Option => soption::TYPE_NAME,
             STypeCompanion::AvlTree => savltree::TYPE_NAME,
+            STypeCompanion::Byte => snumeric::BYTE_TYPE_NAME,
+            STypeCompanion::Short => snumeric::SHORT_TYPE_NAME,
+            STypeCompanion::Int => snumeric::INT_TYPE_NAME,
+            STypeCompanion::Long => snumeric::LONG_TYPE_NAME,
+            STypeCompanion::BigInt => snumeric::BIGINT_TYPE_NAME,
+            STypeCompanion::UnsignedBigInt => snumeric::UNSIGNED_BIGINT_TYPE_NAME,
         }
     }
 }
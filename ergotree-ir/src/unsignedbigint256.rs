@@ -0,0 +1,304 @@
+//! 256-bit unsigned integer type (introduced in ErgoTree v3)
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Not, Rem, Sub};
+
+use num_bigint::BigInt;
+use num_bigint::BigUint;
+use num_bigint::Sign;
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedRem, CheckedSub, Num, One, Zero};
+
+use crate::bigint256::BigInt256;
+
+/// 256-bit unsigned integer type
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Hash)]
+pub struct UnsignedBigInt256(BigUint);
+
+impl UnsignedBigInt256 {
+    /// Max size of the big-endian byte representation
+    pub const MAX_SIZE_BYTES: usize = 32;
+
+    /// Smallest value (0)
+    pub fn min_value() -> Self {
+        Self(BigUint::zero())
+    }
+
+    /// Largest value (2^256 - 1)
+    pub fn max_value() -> Self {
+        Self((BigUint::one() << 256u32) - BigUint::one())
+    }
+
+    /// Big-endian unsigned byte representation (without leading zeros, at least one byte)
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        self.0.to_bytes_be()
+    }
+
+    /// Parse from the big-endian unsigned byte representation
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self, String> {
+        Self::try_from(BigUint::from_bytes_be(bytes))
+    }
+
+    /// Shift left, discarding bits shifted beyond 256 bits
+    pub fn shl_truncated(&self, bits: usize) -> Self {
+        Self((&self.0 << bits) & Self::max_value().0)
+    }
+
+    /// Shift right
+    pub fn shr_bits(&self, bits: usize) -> Self {
+        Self(&self.0 >> bits)
+    }
+}
+
+impl TryFrom<BigUint> for UnsignedBigInt256 {
+    type Error = String;
+
+    fn try_from(value: BigUint) -> Result<Self, Self::Error> {
+        if value.bits() > 256 {
+            Err(format!(
+                "UnsignedBigInt256: Value {} is larger than 2^256 - 1",
+                value
+            ))
+        } else {
+            Ok(Self(value))
+        }
+    }
+}
+
+impl TryFrom<BigInt> for UnsignedBigInt256 {
+    type Error = String;
+
+    fn try_from(value: BigInt) -> Result<Self, Self::Error> {
+        match value.to_biguint() {
+            Some(v) => Self::try_from(v),
+            None => Err(format!("UnsignedBigInt256: Value {} is negative", value)),
+        }
+    }
+}
+
+impl TryFrom<BigInt256> for UnsignedBigInt256 {
+    type Error = String;
+
+    fn try_from(value: BigInt256) -> Result<Self, Self::Error> {
+        Self::try_from(BigInt::from(value))
+    }
+}
+
+impl TryFrom<UnsignedBigInt256> for BigInt256 {
+    type Error = String;
+
+    fn try_from(value: UnsignedBigInt256) -> Result<Self, Self::Error> {
+        BigInt256::try_from(value.0)
+    }
+}
+
+impl From<u64> for UnsignedBigInt256 {
+    fn from(value: u64) -> Self {
+        Self(BigUint::from(value))
+    }
+}
+
+impl From<UnsignedBigInt256> for BigUint {
+    fn from(value: UnsignedBigInt256) -> Self {
+        value.0
+    }
+}
+
+impl From<UnsignedBigInt256> for BigInt {
+    fn from(value: UnsignedBigInt256) -> Self {
+        BigInt::from_biguint(Sign::Plus, value.0)
+    }
+}
+
+impl Zero for UnsignedBigInt256 {
+    fn zero() -> Self {
+        Self(BigUint::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+}
+
+impl One for UnsignedBigInt256 {
+    fn one() -> Self {
+        Self(BigUint::one())
+    }
+}
+
+impl Num for UnsignedBigInt256 {
+    type FromStrRadixErr = String;
+
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        match BigUint::from_str_radix(s, radix) {
+            Ok(n) => Self::try_from(n),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+impl CheckedAdd for UnsignedBigInt256 {
+    fn checked_add(&self, v: &Self) -> Option<Self> {
+        Self::try_from(&self.0 + &v.0).ok()
+    }
+}
+
+impl CheckedSub for UnsignedBigInt256 {
+    fn checked_sub(&self, v: &Self) -> Option<Self> {
+        self.0.checked_sub(&v.0).map(Self)
+    }
+}
+
+impl CheckedMul for UnsignedBigInt256 {
+    fn checked_mul(&self, v: &Self) -> Option<Self> {
+        Self::try_from(&self.0 * &v.0).ok()
+    }
+}
+
+impl CheckedDiv for UnsignedBigInt256 {
+    fn checked_div(&self, v: &Self) -> Option<Self> {
+        self.0.checked_div(&v.0).map(Self)
+    }
+}
+
+impl CheckedRem for UnsignedBigInt256 {
+    fn checked_rem(&self, v: &Self) -> Option<Self> {
+        if v.0.is_zero() {
+            None
+        } else {
+            Some(Self(&self.0 % &v.0))
+        }
+    }
+}
+
+// Required by num_traits::Checked* bounds
+impl Add for UnsignedBigInt256 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Sub for UnsignedBigInt256 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl Mul for UnsignedBigInt256 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(self.0 * rhs.0)
+    }
+}
+
+impl Div for UnsignedBigInt256 {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Self(self.0 / rhs.0)
+    }
+}
+
+impl Rem for UnsignedBigInt256 {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        Self(self.0 % rhs.0)
+    }
+}
+
+impl Not for UnsignedBigInt256 {
+    type Output = Self;
+
+    /// Inverts all 256 bits
+    fn not(self) -> Self::Output {
+        Self(Self::max_value().0 ^ self.0)
+    }
+}
+
+impl<'a> BitAnd<&'a UnsignedBigInt256> for &'a UnsignedBigInt256 {
+    type Output = UnsignedBigInt256;
+
+    fn bitand(self, rhs: &UnsignedBigInt256) -> Self::Output {
+        UnsignedBigInt256(&self.0 & &rhs.0)
+    }
+}
+
+impl<'a> BitOr<&'a UnsignedBigInt256> for &'a UnsignedBigInt256 {
+    type Output = UnsignedBigInt256;
+
+    fn bitor(self, rhs: &UnsignedBigInt256) -> Self::Output {
+        UnsignedBigInt256(&self.0 | &rhs.0)
+    }
+}
+
+impl<'a> BitXor<&'a UnsignedBigInt256> for &'a UnsignedBigInt256 {
+    type Output = UnsignedBigInt256;
+
+    fn bitxor(self, rhs: &UnsignedBigInt256) -> Self::Output {
+        UnsignedBigInt256(&self.0 ^ &rhs.0)
+    }
+}
+
+impl fmt::Display for UnsignedBigInt256 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary {
+    use super::*;
+    use proptest::prelude::*;
+
+    impl Arbitrary for UnsignedBigInt256 {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+            prop::collection::vec(any::<u8>(), 0..=UnsignedBigInt256::MAX_SIZE_BYTES)
+                .prop_map(|bytes| Self(BigUint::from_bytes_be(&bytes)))
+                .boxed()
+        }
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        let bytes = [0xff_u8; 32];
+        assert_eq!(
+            UnsignedBigInt256::from_bytes_be(&bytes).unwrap(),
+            UnsignedBigInt256::max_value()
+        );
+        assert!(UnsignedBigInt256::from_bytes_be(&[0x01_u8; 33]).is_err());
+        assert!(UnsignedBigInt256::try_from(BigInt::from(-1)).is_err());
+        assert!(UnsignedBigInt256::max_value()
+            .checked_add(&UnsignedBigInt256::from(1u64))
+            .is_none());
+        assert!(UnsignedBigInt256::min_value()
+            .checked_sub(&UnsignedBigInt256::from(1u64))
+            .is_none());
+    }
+
+    #[test]
+    fn bitwise() {
+        assert_eq!(
+            !UnsignedBigInt256::min_value(),
+            UnsignedBigInt256::max_value()
+        );
+        assert_eq!(
+            UnsignedBigInt256::max_value().shl_truncated(8),
+            &UnsignedBigInt256::max_value() ^ &UnsignedBigInt256::from(0xffu64)
+        );
+    }
+}
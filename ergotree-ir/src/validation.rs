@@ -0,0 +1,488 @@
+//! Soft-fork validation settings
+//!
+//! Some of the checks performed while parsing and evaluating ErgoTree are identified by a rule id.
+//! The status of each rule can be changed by miners voting (the updates are stored in the block
+//! extension section), which allows the network to introduce new opcodes, types and methods
+//! without a hard fork. Nodes that do not know about the new features treat the scripts that
+//! fail such checks as valid (soft-fork condition).
+//! See <https://github.com/ScorexFoundation/sigmastate-interpreter/blob/develop/docs/soft-fork-log.md>
+
+use std::collections::BTreeMap;
+
+use ergo_chain_types::ExtensionCandidate;
+use sigma_ser::vlq_encode::ReadSigmaVlqExt;
+use sigma_ser::vlq_encode::WriteSigmaVlqExt;
+use sigma_ser::ScorexParsingError;
+use sigma_ser::ScorexSerializable;
+use sigma_ser::ScorexSerializeResult;
+
+use crate::ergo_tree::ErgoTreeConstantError;
+use crate::ergo_tree::ErgoTreeError;
+use crate::ergo_tree::ErgoTreeRootParsingError;
+use crate::serialization::SigmaParsingError;
+
+/// Validation rules of the interpreter (ids as in the reference implementation)
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, PartialOrd, Ord)]
+#[repr(u16)]
+pub enum ValidationRule {
+    /// Deserialized script must have the expected type
+    CheckDeserializedScriptType = 1000,
+    /// Deserialized script must be of `SigmaProp` type
+    CheckDeserializedScriptIsSigmaProp = 1001,
+    /// Opcode must be known to the parser
+    CheckValidOpCode = 1002,
+    /// Index expression must be supported
+    CheckIsSupportedIndexExpression = 1003,
+    /// Cost function check (not used by JIT costing)
+    CheckCostFunc = 1004,
+    /// Calc function check (not used by JIT costing)
+    CheckCalcFunc = 1005,
+    /// Tuple type must have a valid number of items
+    CheckTupleType = 1006,
+    /// Primitive type code must be known
+    CheckPrimitiveTypeCode = 1007,
+    /// Type code must be known
+    CheckTypeCode = 1008,
+    /// Type must be serializable
+    CheckSerializableTypeCode = 1009,
+    /// Type must have a methods companion
+    CheckTypeWithMethods = 1010,
+    /// Method must be known for the given type
+    CheckAndGetMethod = 1011,
+    /// Size bit must be set in the header of ErgoTree with version > 0
+    CheckHeaderSizeBit = 1012,
+    /// Cost function operation check (not used by JIT costing)
+    CheckCostFuncOperation = 1013,
+    /// Reader position must be within the limit
+    CheckPositionLimit = 1014,
+    /// Loop level check in cost function (not used by JIT costing)
+    CheckLoopLevelInCostFunction = 1015,
+}
+
+impl ValidationRule {
+    /// Id of the first interpreter rule (the ids below are used by the node rules)
+    pub const FIRST_RULE_ID: u16 = 1000;
+
+    /// Rule id
+    pub fn id(&self) -> u16 {
+        *self as u16
+    }
+
+    /// Returns true if the rule failure on the given arguments is a soft-fork condition
+    /// under the given rule status.
+    fn is_soft_fork(&self, status: &RuleStatus, args: &[u8]) -> bool {
+        use ValidationRule::*;
+        match (self, status) {
+            (CheckValidOpCode, RuleStatus::Changed(data))
+            | (CheckPrimitiveTypeCode, RuleStatus::Changed(data))
+            | (CheckTypeCode, RuleStatus::Changed(data))
+            | (CheckTypeWithMethods, RuleStatus::Changed(data)) => {
+                args.first().map_or(false, |code| data.contains(code))
+            }
+            (CheckAndGetMethod, RuleStatus::Changed(data)) => {
+                data.chunks_exact(2).any(|pair| pair == args)
+            }
+            (CheckValidOpCode, _)
+            | (CheckPrimitiveTypeCode, _)
+            | (CheckTypeCode, _)
+            | (CheckTypeWithMethods, _)
+            | (CheckAndGetMethod, _) => false,
+            (_, RuleStatus::Replaced(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the rule (and its arguments) violated by the given parsing error, if any
+    fn violated_by(error: &SigmaParsingError) -> Option<(ValidationRule, Vec<u8>)> {
+        match error {
+            SigmaParsingError::InvalidOpCode(op_code) => {
+                Some((ValidationRule::CheckValidOpCode, vec![*op_code]))
+            }
+            SigmaParsingError::InvalidTypeCode(type_code) => {
+                Some((ValidationRule::CheckTypeCode, vec![*type_code]))
+            }
+            SigmaParsingError::UnknownMethodId(method_id, type_id) => Some((
+                ValidationRule::CheckAndGetMethod,
+                vec![*type_id, method_id.0],
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Status of the validation rule
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum RuleStatus {
+    /// Rule is active and checked
+    Enabled,
+    /// Rule is disabled and not checked
+    Disabled,
+    /// Rule is replaced by the rule with the given id
+    Replaced(u16),
+    /// Rule parameters are changed (e.g. new opcodes are added)
+    Changed(Vec<u8>),
+}
+
+impl RuleStatus {
+    const ENABLED_CODE: u8 = 1;
+    const DISABLED_CODE: u8 = 2;
+    const REPLACED_CODE: u8 = 3;
+    const CHANGED_CODE: u8 = 4;
+
+    /// Serialized status code
+    pub fn status_code(&self) -> u8 {
+        match self {
+            RuleStatus::Enabled => Self::ENABLED_CODE,
+            RuleStatus::Disabled => Self::DISABLED_CODE,
+            RuleStatus::Replaced(_) => Self::REPLACED_CODE,
+            RuleStatus::Changed(_) => Self::CHANGED_CODE,
+        }
+    }
+}
+
+impl ScorexSerializable for RuleStatus {
+    fn scorex_serialize<W: WriteSigmaVlqExt>(&self, w: &mut W) -> ScorexSerializeResult {
+        match self {
+            RuleStatus::Enabled | RuleStatus::Disabled => {
+                w.put_u16(0)?;
+                w.put_u8(self.status_code())?;
+            }
+            RuleStatus::Replaced(new_rule_id) => {
+                w.put_u16(2)?;
+                w.put_u8(self.status_code())?;
+                w.put_u16(*new_rule_id)?;
+            }
+            RuleStatus::Changed(data) => {
+                w.put_u16(data.len() as u16)?;
+                w.put_u8(self.status_code())?;
+                w.write_all(data)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn scorex_parse<R: ReadSigmaVlqExt>(r: &mut R) -> Result<Self, ScorexParsingError> {
+        let data_size = r.get_u16()?;
+        let status_code = r.get_u8()?;
+        Ok(match status_code {
+            Self::ENABLED_CODE => RuleStatus::Enabled,
+            Self::DISABLED_CODE => RuleStatus::Disabled,
+            Self::REPLACED_CODE => RuleStatus::Replaced(r.get_u16()?),
+            Self::CHANGED_CODE => {
+                let mut data = vec![0u8; data_size as usize];
+                r.read_exact(&mut data)?;
+                RuleStatus::Changed(data)
+            }
+            _ => {
+                // status introduced in a future version, skip its data
+                let mut data = vec![0u8; data_size as usize];
+                r.read_exact(&mut data)?;
+                RuleStatus::Enabled
+            }
+        })
+    }
+}
+
+/// Update of the validation settings voted by miners
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct ValidationSettingsUpdate {
+    /// Ids of the rules to disable
+    pub rules_to_disable: Vec<u16>,
+    /// New statuses of the rules
+    pub status_updates: Vec<(u16, RuleStatus)>,
+}
+
+impl ValidationSettingsUpdate {
+    /// Prefix of the extension keys where the serialized validation settings update is stored
+    pub const EXTENSION_KEY_PREFIX: u8 = 0x02;
+
+    /// Parse the update from the block extension fields. The serialized update is split into
+    /// fields with keys `[0x02, idx]`, which are concatenated in the index order.
+    /// Returns an empty update if there are no such fields.
+    pub fn from_extension_fields(
+        fields: &[([u8; 2], Vec<u8>)],
+    ) -> Result<Self, ScorexParsingError> {
+        let mut chunks: Vec<&([u8; 2], Vec<u8>)> = fields
+            .iter()
+            .filter(|(key, _)| key[0] == Self::EXTENSION_KEY_PREFIX)
+            .collect();
+        if chunks.is_empty() {
+            return Ok(Self::default());
+        }
+        chunks.sort_by_key(|(key, _)| key[1]);
+        let bytes: Vec<u8> = chunks
+            .into_iter()
+            .flat_map(|(_, value)| value.iter().copied())
+            .collect();
+        Self::scorex_parse_bytes(&bytes)
+    }
+
+    /// Parse the update from the block extension
+    pub fn from_extension(extension: &ExtensionCandidate) -> Result<Self, ScorexParsingError> {
+        Self::from_extension_fields(extension.fields())
+    }
+}
+
+impl ScorexSerializable for ValidationSettingsUpdate {
+    fn scorex_serialize<W: WriteSigmaVlqExt>(&self, w: &mut W) -> ScorexSerializeResult {
+        w.put_u32(self.rules_to_disable.len() as u32)?;
+        self.rules_to_disable
+            .iter()
+            .try_for_each(|rule_id| w.put_u16(*rule_id))?;
+        w.put_u32(self.status_updates.len() as u32)?;
+        self.status_updates
+            .iter()
+            .try_for_each(|(rule_id, status)| {
+                w.put_u16(rule_id.saturating_sub(ValidationRule::FIRST_RULE_ID))?;
+                status.scorex_serialize(w)
+            })
+    }
+
+    fn scorex_parse<R: ReadSigmaVlqExt>(r: &mut R) -> Result<Self, ScorexParsingError> {
+        let disabled_count = r.get_u32()?;
+        let mut rules_to_disable = Vec::new();
+        for _ in 0..disabled_count {
+            rules_to_disable.push(r.get_u16()?);
+        }
+        let updates_count = r.get_u32()?;
+        let mut status_updates = Vec::new();
+        for _ in 0..updates_count {
+            let rule_id = r
+                .get_u16()?
+                .checked_add(ValidationRule::FIRST_RULE_ID)
+                .ok_or_else(|| ScorexParsingError::ValueOutOfBounds("rule id".to_string()))?;
+            status_updates.push((rule_id, RuleStatus::scorex_parse(r)?));
+        }
+        Ok(ValidationSettingsUpdate {
+            rules_to_disable,
+            status_updates,
+        })
+    }
+}
+
+/// Current statuses of the validation rules. Rules without an explicit status are enabled.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct ValidationSettings {
+    statuses: BTreeMap<u16, RuleStatus>,
+}
+
+impl ValidationSettings {
+    /// Settings from the block extension of the last voting epoch start
+    /// (the extension holds the whole update accumulated since genesis)
+    pub fn from_extension(extension: &ExtensionCandidate) -> Result<Self, ScorexParsingError> {
+        Ok(Self::default().updated(&ValidationSettingsUpdate::from_extension(extension)?))
+    }
+
+    /// Status of the given rule
+    pub fn status(&self, rule: ValidationRule) -> RuleStatus {
+        self.status_by_id(rule.id())
+    }
+
+    /// Status of the rule with the given id
+    pub fn status_by_id(&self, rule_id: u16) -> RuleStatus {
+        self.statuses
+            .get(&rule_id)
+            .cloned()
+            .unwrap_or(RuleStatus::Enabled)
+    }
+
+    /// Returns new settings with the given update applied
+    pub fn updated(&self, update: &ValidationSettingsUpdate) -> Self {
+        let mut statuses = self.statuses.clone();
+        for rule_id in &update.rules_to_disable {
+            statuses.insert(*rule_id, RuleStatus::Disabled);
+        }
+        for (rule_id, status) in &update.status_updates {
+            statuses.insert(*rule_id, status.clone());
+        }
+        ValidationSettings { statuses }
+    }
+
+    /// Returns true if the given parsing error is a violation of a rule whose current status
+    /// makes it a soft-fork condition (i.e. the script should be accepted)
+    pub fn is_soft_fork_parsing_error(&self, error: &SigmaParsingError) -> bool {
+        ValidationRule::violated_by(error).map_or(false, |(rule, args)| {
+            rule.is_soft_fork(&self.status(rule), &args)
+        })
+    }
+
+    /// Returns true if the given ErgoTree error is a soft-fork condition
+    /// (see [`Self::is_soft_fork_parsing_error`])
+    pub fn is_soft_fork(&self, error: &ErgoTreeError) -> bool {
+        match error {
+            ErgoTreeError::SigmaParsingError(e)
+            | ErgoTreeError::RootParsingError(ErgoTreeRootParsingError::SigmaParsingError(e))
+            | ErgoTreeError::ConstantsError(ErgoTreeConstantError::ParsingError(e)) => {
+                self.is_soft_fork_parsing_error(e)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::panic)]
+mod tests {
+    use super::*;
+    use crate::ergo_tree::ErgoTree;
+    use crate::serialization::SigmaSerializable;
+    use crate::types::smethod::MethodId;
+
+    #[test]
+    fn update_roundtrip() {
+        let update = ValidationSettingsUpdate {
+            rules_to_disable: vec![215, 409],
+            status_updates: vec![
+                (
+                    ValidationRule::CheckValidOpCode.id(),
+                    RuleStatus::Changed(vec![0xa0, 0xa1]),
+                ),
+                (
+                    ValidationRule::CheckTupleType.id(),
+                    RuleStatus::Replaced(1016),
+                ),
+                (ValidationRule::CheckTypeCode.id(), RuleStatus::Disabled),
+            ],
+        };
+        let bytes = update.scorex_serialize_bytes().unwrap();
+        assert_eq!(
+            ValidationSettingsUpdate::scorex_parse_bytes(&bytes).unwrap(),
+            update
+        );
+    }
+
+    #[test]
+    fn unknown_status_is_skipped() {
+        // dataSize = 2, status code 5 (unknown), 2 bytes of data
+        let bytes = [2u8, 5, 0xff, 0xff];
+        assert_eq!(
+            RuleStatus::scorex_parse_bytes(&bytes).unwrap(),
+            RuleStatus::Enabled
+        );
+    }
+
+    #[test]
+    fn parse_from_extension_fields() {
+        let update = ValidationSettingsUpdate {
+            rules_to_disable: vec![],
+            status_updates: vec![(
+                ValidationRule::CheckAndGetMethod.id(),
+                RuleStatus::Changed(vec![106, 40]),
+            )],
+        };
+        let bytes = update.scorex_serialize_bytes().unwrap();
+        let (first, second) = bytes.split_at(bytes.len() / 2);
+        let fields = vec![
+            ([0x02, 1], second.to_vec()),
+            ([0x01, 0], vec![0, 0, 0, 1]),
+            ([0x02, 0], first.to_vec()),
+        ];
+        let extension = ExtensionCandidate::new(fields).unwrap();
+        let settings = ValidationSettings::from_extension(&extension).unwrap();
+        assert_eq!(
+            settings.status(ValidationRule::CheckAndGetMethod),
+            RuleStatus::Changed(vec![106, 40])
+        );
+        assert_eq!(
+            settings.status(ValidationRule::CheckValidOpCode),
+            RuleStatus::Enabled
+        );
+        assert_eq!(
+            ValidationSettings::from_extension(&ExtensionCandidate::default()).unwrap(),
+            ValidationSettings::default()
+        );
+    }
+
+    #[test]
+    fn soft_fork_conditions() {
+        let default = ValidationSettings::default();
+        let unknown_op = SigmaParsingError::InvalidOpCode(0x76);
+        let unknown_method = SigmaParsingError::UnknownMethodId(MethodId(40), 106);
+        assert!(!default.is_soft_fork_parsing_error(&unknown_op));
+        assert!(!default.is_soft_fork_parsing_error(&unknown_method));
+
+        let settings = default.updated(&ValidationSettingsUpdate {
+            rules_to_disable: vec![],
+            status_updates: vec![
+                (
+                    ValidationRule::CheckValidOpCode.id(),
+                    RuleStatus::Changed(vec![0x76]),
+                ),
+                (
+                    ValidationRule::CheckAndGetMethod.id(),
+                    RuleStatus::Changed(vec![106, 40]),
+                ),
+            ],
+        });
+        assert!(settings.is_soft_fork_parsing_error(&unknown_op));
+        assert!(!settings.is_soft_fork_parsing_error(&SigmaParsingError::InvalidOpCode(0x77)));
+        assert!(settings.is_soft_fork_parsing_error(&unknown_method));
+        assert!(!settings
+            .is_soft_fork_parsing_error(&SigmaParsingError::UnknownMethodId(MethodId(41), 106)));
+    }
+
+    #[test]
+    fn unknown_opcode_tree_is_soft_fork() {
+        // v1 tree (with size) with an unknown opcode 0x76 as a root
+        let bytes = [0x09, 0x01, 0x76];
+        let tree = ErgoTree::sigma_parse_bytes(&bytes).unwrap();
+        let error = match tree {
+            ErgoTree::Unparsed { error, .. } => error,
+            ErgoTree::Parsed(_) => panic!("expected unparsed tree"),
+        };
+        assert!(!ValidationSettings::default().is_soft_fork(&error));
+        let settings = ValidationSettings::default().updated(&ValidationSettingsUpdate {
+            rules_to_disable: vec![],
+            status_updates: vec![(
+                ValidationRule::CheckValidOpCode.id(),
+                RuleStatus::Changed(vec![0x76]),
+            )],
+        });
+        assert!(settings.is_soft_fork(&error));
+    }
+
+    #[test]
+    fn coll_shift_tree_is_soft_fork() {
+        use crate::ergo_tree::ErgoTreeHeader;
+        use crate::mir::bin_op::BinOp;
+        use crate::mir::bin_op::RelationOp;
+        use crate::mir::bool_to_sigma::BoolToSigmaProp;
+        use crate::mir::coll_shift::CollShift;
+        use crate::mir::coll_shift::CollShiftOp;
+        use crate::mir::expr::Expr;
+        use crate::serialization::op_code::OpCode;
+
+        let shift: Expr = CollShift::new(CollShiftOp::RotateLeft, vec![1i8].into(), 1.into())
+            .unwrap()
+            .into();
+        let prop: Expr = BoolToSigmaProp {
+            input: Box::new(
+                BinOp {
+                    kind: RelationOp::Eq.into(),
+                    left: shift.into(),
+                    right: Box::new(vec![2i8].into()),
+                }
+                .into(),
+            ),
+        }
+        .into();
+        let bytes = ErgoTree::new(ErgoTreeHeader::v1(false), &prop)
+            .unwrap()
+            .sigma_serialize_bytes()
+            .unwrap();
+        let error = match ErgoTree::sigma_parse_bytes(&bytes).unwrap() {
+            ErgoTree::Unparsed { error, .. } => error,
+            ErgoTree::Parsed(_) => panic!("expected unparsed tree"),
+        };
+        assert!(!ValidationSettings::default().is_soft_fork(&error));
+        let settings = ValidationSettings::default().updated(&ValidationSettingsUpdate {
+            rules_to_disable: vec![],
+            status_updates: vec![(
+                ValidationRule::CheckValidOpCode.id(),
+                RuleStatus::Changed(vec![OpCode::COLL_ROTATE_LEFT.value()]),
+            )],
+        });
+        assert!(settings.is_soft_fork(&error));
+    }
+}